@@ -2,6 +2,7 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use serde::Deserialize;
+use stupid_tool_runtime::Budget;
 use tracing::info;
 
 use crate::types::{AgentInfo, AgentTier};
@@ -14,6 +15,8 @@ pub struct AgentConfig {
     pub description: String,
     pub tier: AgentTier,
     pub system_prompt: String,
+    /// Default spending limit for runs of this agent.
+    pub budget: Option<Budget>,
 }
 
 impl From<AgentYamlConfig> for AgentConfig {
@@ -23,6 +26,7 @@ impl From<AgentYamlConfig> for AgentConfig {
             description: yaml.description,
             tier: yaml.tier,
             system_prompt: yaml.system_prompt,
+            budget: yaml.execution.budget,
         }
     }
 }
@@ -101,6 +105,7 @@ fn load_agent_file(path: &Path) -> Result<AgentConfig, AgentConfigError> {
         description,
         tier,
         system_prompt,
+        budget: None,
     })
 }
 
@@ -1,9 +1,10 @@
 use std::collections::HashMap;
 use std::time::Instant;
 
-use tracing::info;
+use tracing::{info, warn};
 
 use stupid_llm::provider::{LlmError, LlmProvider, Message, Role};
+use stupid_tool_runtime::{Budget, TokenUsage};
 
 use crate::config::AgentConfig;
 use crate::types::{AgentResponse, ExecutionStatus};
@@ -37,6 +38,18 @@ impl AgentExecutor {
         agent_name: &str,
         task: &str,
         context: Option<&serde_json::Value>,
+    ) -> Result<AgentResponse, AgentExecutionError> {
+        self.execute_with_budget(agent_name, task, context, None).await
+    }
+
+    /// Execute a single agent with a task, limited by `budget` instead of
+    /// the agent's configured budget when one is given.
+    pub async fn execute_with_budget(
+        &self,
+        agent_name: &str,
+        task: &str,
+        context: Option<&serde_json::Value>,
+        budget: Option<&Budget>,
     ) -> Result<AgentResponse, AgentExecutionError> {
         let start = Instant::now();
 
@@ -67,22 +80,11 @@ impl AgentExecutor {
             },
         ];
 
-        let output = self
-            .provider
-            .complete(messages, self.temperature, self.max_tokens)
-            .await
-            .map_err(AgentExecutionError::LlmError)?;
-
-        let elapsed_ms = start.elapsed().as_millis() as u64;
-        info!(agent = agent_name, elapsed_ms, "agent execution complete");
+        let budget = budget.or(config.budget.as_ref());
+        let response = self.complete_within_budget(agent_name, messages, budget, start).await?;
+        info!(agent = agent_name, elapsed_ms = response.execution_time_ms, "agent execution complete");
 
-        Ok(AgentResponse {
-            agent_name: agent_name.to_string(),
-            status: ExecutionStatus::Success,
-            output,
-            execution_time_ms: elapsed_ms,
-            tokens_used: None,
-        })
+        Ok(response)
     }
 
     /// Execute using an externally-provided config (e.g. from AgentStore fallback).
@@ -115,22 +117,10 @@ impl AgentExecutor {
             },
         ];
 
-        let output = self
-            .provider
-            .complete(messages, self.temperature, self.max_tokens)
-            .await
-            .map_err(AgentExecutionError::LlmError)?;
-
-        let elapsed_ms = start.elapsed().as_millis() as u64;
-        info!(agent = config.name, elapsed_ms, "agent execution complete");
+        let response = self.complete_within_budget(&config.name, messages, config.budget.as_ref(), start).await?;
+        info!(agent = config.name, elapsed_ms = response.execution_time_ms, "agent execution complete");
 
-        Ok(AgentResponse {
-            agent_name: config.name.clone(),
-            status: ExecutionStatus::Success,
-            output,
-            execution_time_ms: elapsed_ms,
-            tokens_used: None,
-        })
+        Ok(response)
     }
 
     /// Execute an agent with conversation history for context continuity.
@@ -209,22 +199,10 @@ impl AgentExecutor {
             content: task.to_string(),
         });
 
-        let output = self
-            .provider
-            .complete(messages, self.temperature, self.max_tokens)
-            .await
-            .map_err(AgentExecutionError::LlmError)?;
-
-        let elapsed_ms = start.elapsed().as_millis() as u64;
-        info!(agent = agent_name, elapsed_ms, "agent execution with history complete");
+        let response = self.complete_within_budget(agent_name, messages, config.budget.as_ref(), start).await?;
+        info!(agent = agent_name, elapsed_ms = response.execution_time_ms, "agent execution with history complete");
 
-        Ok(AgentResponse {
-            agent_name: agent_name.to_string(),
-            status: ExecutionStatus::Success,
-            output,
-            execution_time_ms: elapsed_ms,
-            tokens_used: None,
-        })
+        Ok(response)
     }
 
     /// Execute as the default assistant with session history.
@@ -313,21 +291,53 @@ impl AgentExecutor {
             content: task.to_string(),
         });
 
-        let output = self
-            .provider
-            .complete(messages, self.temperature, self.max_tokens)
-            .await
-            .map_err(AgentExecutionError::LlmError)?;
+        let response = self.complete_within_budget("assistant", messages, None, start).await?;
+        info!(elapsed_ms = response.execution_time_ms, "execute_as_assistant: inline fallback complete");
 
-        let elapsed_ms = start.elapsed().as_millis() as u64;
-        info!(elapsed_ms, "execute_as_assistant: inline fallback complete");
+        Ok(response)
+    }
+
+    /// Run one completion for `agent_name`, capped to what `budget` has left.
+    ///
+    /// Tokens are counted with the provider's tokenizer. A prompt that alone
+    /// uses up the budget is not sent; a response that uses up the rest comes
+    /// back as [`ExecutionStatus::BudgetExceeded`] with whatever was produced.
+    async fn complete_within_budget(
+        &self,
+        agent_name: &str,
+        messages: Vec<Message>,
+        budget: Option<&Budget>,
+        start: Instant,
+    ) -> Result<AgentResponse, AgentExecutionError> {
+        let prompt_tokens: usize = messages.iter().map(|m| self.provider.count_tokens(&m.content)).sum();
+        let mut usage = TokenUsage { input_tokens: prompt_tokens as u64, output_tokens: 0 };
+        let remaining = budget.and_then(|b| b.remaining_tokens(&usage));
+
+        let (status, output) = if remaining == Some(0) {
+            warn!(agent = agent_name, prompt_tokens, "prompt alone uses up the agent's budget");
+            (ExecutionStatus::BudgetExceeded, String::new())
+        } else {
+            let max_tokens = remaining.map_or(self.max_tokens, |r| self.max_tokens.min(r.try_into().unwrap_or(u32::MAX)));
+            let output = self
+                .provider
+                .complete(messages, self.temperature, max_tokens)
+                .await
+                .map_err(AgentExecutionError::LlmError)?;
+            usage.output_tokens = self.provider.count_tokens(&output) as u64;
+            if budget.is_some_and(|b| b.is_exhausted(&usage)) {
+                warn!(agent = agent_name, tokens = usage.total(), "agent run stopped: budget exceeded");
+                (ExecutionStatus::BudgetExceeded, output)
+            } else {
+                (ExecutionStatus::Success, output)
+            }
+        };
 
         Ok(AgentResponse {
-            agent_name: "assistant".to_string(),
-            status: ExecutionStatus::Success,
+            agent_name: agent_name.to_string(),
+            status,
             output,
-            execution_time_ms: elapsed_ms,
-            tokens_used: None,
+            execution_time_ms: start.elapsed().as_millis() as u64,
+            tokens_used: Some(usage.total()),
         })
     }
 
@@ -354,3 +364,74 @@ pub enum AgentExecutionError {
     #[error("LLM error: {0}")]
     LlmError(LlmError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    use async_trait::async_trait;
+
+    use crate::types::AgentTier;
+
+    /// Counts one token per word and answers with `max_tokens` words of a
+    /// long reply, like a model cut off at its output limit.
+    struct WordProvider {
+        /// `max_tokens` of each call.
+        calls: Arc<Mutex<Vec<u32>>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for WordProvider {
+        async fn complete(&self, _messages: Vec<Message>, _temperature: f32, max_tokens: u32) -> Result<String, LlmError> {
+            self.calls.lock().unwrap().push(max_tokens);
+            Ok(vec!["word"; 100.min(max_tokens as usize)].join(" "))
+        }
+
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    fn executor(budget: Option<Budget>) -> (AgentExecutor, Arc<Mutex<Vec<u32>>>) {
+        let config = AgentConfig {
+            name: "analyst".to_string(),
+            description: String::new(),
+            tier: AgentTier::Specialist,
+            system_prompt: "You analyse data".to_string(),
+            budget,
+        };
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let provider = WordProvider { calls: calls.clone() };
+        let agents = HashMap::from([(config.name.clone(), config)]);
+        (AgentExecutor::new(agents, Box::new(provider), 0.0, 4096), calls)
+    }
+
+    #[tokio::test]
+    async fn test_budget_caps_response_and_reports_exceeded() {
+        let (executor, calls) = executor(Some(Budget::tokens(20)));
+
+        // 3 system + 2 task tokens leave 15 for the response.
+        let response = executor.execute("analyst", "Summarise sales", None).await.unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec![15]);
+        assert_eq!(response.status, ExecutionStatus::BudgetExceeded);
+        assert_eq!(response.output.split_whitespace().count(), 15);
+        assert_eq!(response.tokens_used, Some(20));
+
+        // A per-run budget overrides the agent's; the prompt alone uses it up.
+        let tight = Budget::tokens(4);
+        let response = executor.execute_with_budget("analyst", "Summarise sales", None, Some(&tight)).await.unwrap();
+        assert_eq!(response.status, ExecutionStatus::BudgetExceeded);
+        assert!(response.output.is_empty());
+        assert_eq!(calls.lock().unwrap().len(), 1, "over-budget prompt must not be sent");
+    }
+
+    #[tokio::test]
+    async fn test_without_budget_counts_tokens() {
+        let (executor, calls) = executor(None);
+        let response = executor.execute("analyst", "Summarise sales", None).await.unwrap();
+        assert_eq!(*calls.lock().unwrap(), vec![4096]);
+        assert_eq!(response.status, ExecutionStatus::Success);
+        assert_eq!(response.tokens_used, Some(105));
+    }
+}
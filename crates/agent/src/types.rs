@@ -53,6 +53,9 @@ pub enum ExecutionStatus {
     Error,
     Timeout,
     Partial,
+    /// The run's token or dollar budget was used up; output is what was
+    /// produced before that.
+    BudgetExceeded,
 }
 
 /// Team execution strategy.
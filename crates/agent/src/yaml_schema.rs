@@ -11,6 +11,7 @@ use std::collections::HashMap;
 use std::path::Path;
 
 use serde::{Deserialize, Serialize};
+use stupid_tool_runtime::Budget;
 
 use crate::types::AgentTier;
 
@@ -206,6 +207,10 @@ pub struct ExecutionConfig {
     /// Per-request timeout in seconds.
     #[serde(default = "default_timeout")]
     pub timeout_seconds: u32,
+
+    /// Token or dollar limit per run.
+    #[serde(default)]
+    pub budget: Option<Budget>,
 }
 
 impl Default for ExecutionConfig {
@@ -215,6 +220,7 @@ impl Default for ExecutionConfig {
             max_tokens: default_max_tokens(),
             top_p: None,
             timeout_seconds: default_timeout(),
+            budget: None,
         }
     }
 }
@@ -503,6 +509,8 @@ provider:
     num_gpu: 1
 execution:
   timeout_seconds: 300
+  budget:
+    max_tokens: 20000
 "#;
         let config: AgentYamlConfig = serde_yaml::from_str(yaml).unwrap();
         assert_eq!(config.name, "local-agent");
@@ -513,6 +521,7 @@ execution:
             assert_eq!(c.ollama.num_gpu, Some(1));
         }
         assert_eq!(config.execution.timeout_seconds, 300);
+        assert_eq!(config.execution.budget, Some(Budget::tokens(20000)));
     }
 
     #[test]
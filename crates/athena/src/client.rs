@@ -47,6 +47,10 @@ pub enum AthenaError {
     /// Failed to parse Athena result data.
     #[error("Parse error: {0}")]
     ParseError(String),
+
+    /// A query parameter is missing or has no safe SQL literal form.
+    #[error("Invalid query parameter: {0}")]
+    InvalidParameter(String),
 }
 
 // ---------------------------------------------------------------------------
@@ -4,6 +4,7 @@ pub mod result;
 pub mod convert;
 pub mod parquet;
 pub mod query_step;
+pub mod sql_params;
 
 pub use config::AthenaConfig;
 pub use client::{AthenaClient, AthenaError};
@@ -11,3 +12,4 @@ pub use result::{AthenaQueryResult, AthenaColumn, QueryMetadata};
 pub use convert::result_to_documents;
 pub use parquet::{write_parquet, write_parquet_bytes, result_to_record_batch, ParquetError};
 pub use query_step::{AthenaQueryStep, AthenaQueryStepParams};
+pub use sql_params::bind_params;
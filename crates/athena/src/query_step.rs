@@ -1,8 +1,11 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 
 use crate::client::{AthenaClient, AthenaError};
 use crate::result::AthenaQueryResult;
 use crate::convert::result_to_documents;
+use crate::sql_params::bind_params;
 use stupid_core::Document;
 
 // ---------------------------------------------------------------------------
@@ -12,8 +15,13 @@ use stupid_core::Document;
 /// Parameters for an Athena query step in a query plan.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AthenaQueryStepParams {
-    /// SQL query to execute against Athena.
+    /// SQL query to execute against Athena. May contain `:name` placeholders
+    /// bound from `params`.
     pub sql: String,
+    /// Values for the `:name` placeholders in `sql`, quoted client-side by
+    /// [`bind_params`]. Put untrusted values here, never into `sql`.
+    #[serde(default)]
+    pub params: BTreeMap<String, serde_json::Value>,
     /// Optional scan limit in GB (converted to bytes for execution).
     /// If None, uses the config default from AthenaClient.
     #[serde(default)]
@@ -63,16 +71,24 @@ impl AthenaQueryStep {
         Self { id, params }
     }
 
+    /// The SQL with every placeholder replaced by its quoted parameter.
+    pub fn bound_sql(&self) -> Result<String, AthenaError> {
+        bind_params(&self.params.sql, &self.params.params)
+    }
+
     /// Execute the query against Athena and return the raw result.
     ///
     /// If `params.max_scan_gb` is set, the query will fail with
     /// [`AthenaError::ScanLimitExceeded`] if the scan exceeds the limit.
+    /// Fails with [`AthenaError::InvalidParameter`] before anything is sent
+    /// if a placeholder can't be bound.
     pub async fn execute(&self, client: &AthenaClient) -> Result<AthenaQueryResult, AthenaError> {
+        let sql = self.bound_sql()?;
         if let Some(max_gb) = self.params.max_scan_gb {
             let max_bytes = gb_to_bytes(max_gb);
-            client.execute_query_with_limit(&self.params.sql, max_bytes).await
+            client.execute_query_with_limit(&sql, max_bytes).await
         } else {
-            client.execute_query(&self.params.sql).await
+            client.execute_query(&sql).await
         }
     }
 
@@ -115,6 +131,7 @@ mod tests {
     fn test_construction() {
         let params = AthenaQueryStepParams {
             sql: "SELECT 1".into(),
+            params: BTreeMap::new(),
             max_scan_gb: Some(5.0),
             event_type: Some("TestEvent".into()),
             timestamp_column: Some("ts".into()),
@@ -132,6 +149,7 @@ mod tests {
     fn test_serialization_roundtrip() {
         let params = AthenaQueryStepParams {
             sql: "SELECT date_trunc('day', timestamp) as day, count(*) as error_count FROM events WHERE event_type = 'API Error' AND timestamp BETWEEN '2024-10-01' AND '2024-12-31' GROUP BY 1 ORDER BY 1".into(),
+            params: BTreeMap::new(),
             max_scan_gb: Some(5.0),
             event_type: None,
             timestamp_column: None,
@@ -206,5 +224,23 @@ mod tests {
         assert_eq!(params.max_scan_gb, None);
         assert_eq!(params.event_type, None);
         assert_eq!(params.timestamp_column, None);
+        assert!(params.params.is_empty());
+    }
+
+    #[test]
+    fn test_bound_sql_quotes_params() {
+        let json = r#"{
+            "id": "logins",
+            "params": {
+                "sql": "SELECT * FROM logins WHERE member = :member LIMIT :limit",
+                "params": {"member": "a'; DELETE FROM logins; --", "limit": 10}
+            }
+        }"#;
+
+        let step: AthenaQueryStep = serde_json::from_str(json).expect("deserialize");
+        assert_eq!(
+            step.bound_sql().unwrap(),
+            "SELECT * FROM logins WHERE member = 'a''; DELETE FROM logins; --' LIMIT 10"
+        );
     }
 }
@@ -0,0 +1,165 @@
+//! Client-side parameter binding for Athena SQL.
+//!
+//! Athena has no server-side prepared statements for ad-hoc queries, so
+//! `:name` placeholders are replaced with literals quoted for their JSON
+//! type. Values that have no safe literal form are rejected rather than
+//! passed through.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::client::AthenaError;
+
+/// Replace every `:name` placeholder in `sql` with the quoted value of
+/// `params[name]`.
+///
+/// Placeholders inside string literals, quoted identifiers and comments are
+/// left alone. Strings become `'...'` with embedded quotes doubled, numbers
+/// and booleans become bare literals, `null` becomes `NULL` and an array of
+/// scalars becomes a parenthesized list for `IN (...)`.
+pub fn bind_params(sql: &str, params: &BTreeMap<String, Value>) -> Result<String, AthenaError> {
+    let mut out = String::with_capacity(sql.len());
+    let mut chars = sql.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\'' | '"' | '`' => {
+                // Copy the quoted run verbatim; a doubled quote stays inside it.
+                out.push(c);
+                while let Some((_, q)) = chars.next() {
+                    out.push(q);
+                    if q == c {
+                        if chars.peek().map(|&(_, n)| n) == Some(c) {
+                            out.push(chars.next().unwrap().1);
+                        } else {
+                            break;
+                        }
+                    }
+                }
+            }
+            '-' if sql[i..].starts_with("--") => {
+                out.push(c);
+                for (_, n) in chars.by_ref() {
+                    out.push(n);
+                    if n == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if sql[i..].starts_with("/*") => {
+                out.push(c);
+                out.push(chars.next().unwrap().1);
+                let mut prev = ' ';
+                for (_, n) in chars.by_ref() {
+                    out.push(n);
+                    if prev == '*' && n == '/' {
+                        break;
+                    }
+                    prev = n;
+                }
+            }
+            ':' if is_placeholder_start(sql, i) => {
+                let name: String = sql[i + 1..].chars().take_while(|c| c.is_ascii_alphanumeric() || *c == '_').collect();
+                let value = params
+                    .get(&name)
+                    .ok_or_else(|| AthenaError::InvalidParameter(format!("no value for placeholder :{name}")))?;
+                out.push_str(&literal(&name, value)?);
+                for _ in 0..name.len() {
+                    chars.next();
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    Ok(out)
+}
+
+/// `:` starts a placeholder when followed by an identifier and not part of `::`.
+fn is_placeholder_start(sql: &str, i: usize) -> bool {
+    let prev_colon = sql[..i].ends_with(':');
+    let next = sql[i + 1..].chars().next();
+    !prev_colon && next.is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+}
+
+/// SQL literal for a parameter value.
+fn literal(name: &str, value: &Value) -> Result<String, AthenaError> {
+    match value {
+        Value::Array(items) if items.is_empty() => Err(invalid(name, "empty list")),
+        Value::Array(items) => {
+            let items = items
+                .iter()
+                .map(|item| match item {
+                    Value::Array(_) | Value::Object(_) => Err(invalid(name, "nested list or object")),
+                    scalar => literal(name, scalar),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(format!("({})", items.join(", ")))
+        }
+        Value::Object(_) => Err(invalid(name, "object")),
+        scalar => scalar_literal(name, scalar),
+    }
+}
+
+fn scalar_literal(name: &str, value: &Value) -> Result<String, AthenaError> {
+    match value {
+        Value::Null => Ok("NULL".to_string()),
+        Value::Bool(b) => Ok(if *b { "TRUE" } else { "FALSE" }.to_string()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::String(s) if s.contains('\0') => Err(invalid(name, "string contains a NUL byte")),
+        Value::String(s) => Ok(format!("'{}'", s.replace('\'', "''"))),
+        Value::Array(_) | Value::Object(_) => Err(invalid(name, "not a scalar")),
+    }
+}
+
+fn invalid(name: &str, reason: &str) -> AthenaError {
+    AthenaError::InvalidParameter(format!(":{name} can't be bound: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn params(value: Value) -> BTreeMap<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_quote_and_semicolon_are_neutralized() {
+        let sql = "SELECT * FROM events WHERE member = :member";
+        let bound = bind_params(sql, &params(json!({"member": "x'; DROP TABLE events; --"}))).unwrap();
+        assert_eq!(bound, "SELECT * FROM events WHERE member = 'x''; DROP TABLE events; --'");
+    }
+
+    #[test]
+    fn test_binds_each_type() {
+        let sql = "SELECT * FROM t WHERE a = :n AND b = :f AND c = :flag AND d IS :none AND e IN :ids";
+        let values = params(json!({"n": 42, "f": 1.5, "flag": true, "none": null, "ids": ["a", "b'c", 3]}));
+        assert_eq!(
+            bind_params(sql, &values).unwrap(),
+            "SELECT * FROM t WHERE a = 42 AND b = 1.5 AND c = TRUE AND d IS NULL AND e IN ('a', 'b''c', 3)"
+        );
+    }
+
+    #[test]
+    fn test_ignores_placeholders_in_literals_and_comments() {
+        let sql = "SELECT '12:30', \"col:x\" FROM t -- :skip\nWHERE a = :a /* :skip */ AND b = CAST(x AS varchar)";
+        let bound = bind_params(sql, &params(json!({"a": "it's"}))).unwrap();
+        assert_eq!(
+            bound,
+            "SELECT '12:30', \"col:x\" FROM t -- :skip\nWHERE a = 'it''s' /* :skip */ AND b = CAST(x AS varchar)"
+        );
+        assert_eq!(bind_params("SELECT 'a'':b'", &BTreeMap::new()).unwrap(), "SELECT 'a'':b'");
+    }
+
+    #[test]
+    fn test_rejects_unbindable_values() {
+        let sql = "SELECT :v";
+        for value in [json!({"k": 1}), json!([]), json!([[1]]), json!("nul\0byte")] {
+            let err = bind_params(sql, &params(json!({"v": value}))).unwrap_err();
+            assert!(matches!(err, AthenaError::InvalidParameter(_)), "{err}");
+        }
+        assert!(matches!(bind_params(sql, &BTreeMap::new()), Err(AthenaError::InvalidParameter(_))));
+    }
+}
@@ -3,11 +3,15 @@ use std::collections::{HashMap, HashSet};
 use stupid_graph::GraphStore;
 use tracing::info;
 
-use super::types::{Catalog, CatalogEntry, EdgeSummary, ExternalSource, PartialCatalog};
+use super::hll::HyperLogLog;
+use super::fields::{collect_field_stats, merge_field_stats, KEY_FIELD};
+use super::types::{Catalog, CatalogEntry, EdgeSummary, ExternalSource, FieldStats, PartialCatalog};
 
 impl Catalog {
     /// Inspect a GraphStore and build a catalog of all entity/edge types.
     pub fn from_graph(graph: &GraphStore) -> Self {
+        let mut type_fields = collect_field_stats(graph, graph.nodes.values(), graph.edges.values());
+
         // Count nodes per entity type and collect sample keys
         let mut type_nodes: HashMap<String, Vec<String>> = HashMap::new();
         for node in graph.nodes.values() {
@@ -23,10 +27,12 @@ impl Catalog {
                 let node_count = keys.len();
                 let mut sample_keys: Vec<String> = keys.into_iter().take(5).collect();
                 sample_keys.sort();
+                let fields = type_fields.remove(&entity_type).unwrap_or_default();
                 CatalogEntry {
                     entity_type,
                     node_count,
                     sample_keys,
+                    fields,
                 }
             })
             .collect();
@@ -89,12 +95,14 @@ impl Catalog {
     /// Build a catalog by merging multiple per-segment partial catalogs.
     ///
     /// Entity type counts are summed across partials, sample keys are
-    /// merged (capped at 5 per entity type), and edge source/target type
+    /// merged (capped at 5 per entity type), field statistics are merged
+    /// (distinct counts via their sketches), and edge source/target type
     /// sets are unioned. The result is sorted by count descending, matching
     /// `from_graph()` ordering.
     pub fn from_partials(partials: &[PartialCatalog]) -> Self {
         let mut type_counts: HashMap<String, usize> = HashMap::new();
         let mut type_samples: HashMap<String, Vec<String>> = HashMap::new();
+        let mut type_fields: HashMap<String, Vec<FieldStats>> = HashMap::new();
         let mut edge_info: HashMap<String, (usize, HashSet<String>, HashSet<String>)> =
             HashMap::new();
         let mut total_nodes: usize = 0;
@@ -112,6 +120,10 @@ impl Catalog {
                         samples.push(key.clone());
                     }
                 }
+                merge_field_stats(
+                    type_fields.entry(entry.entity_type.clone()).or_default(),
+                    &entry.fields,
+                );
             }
 
             for edge in &partial.edge_types {
@@ -129,10 +141,12 @@ impl Catalog {
             .map(|(entity_type, node_count)| {
                 let mut sample_keys = type_samples.remove(&entity_type).unwrap_or_default();
                 sample_keys.sort();
+                let fields = type_fields.remove(&entity_type).unwrap_or_default();
                 CatalogEntry {
                     entity_type,
                     node_count,
                     sample_keys,
+                    fields,
                 }
             })
             .collect();
@@ -165,11 +179,31 @@ impl Catalog {
     }
 
     /// Attach external SQL sources (e.g. Athena, Trino) to the catalog.
-    pub fn with_external_sources(mut self, sources: Vec<ExternalSource>) -> Self {
+    ///
+    /// A source without a `last_refreshed` timestamp keeps the one of the
+    /// source it replaces, so re-attaching doesn't make it look stale.
+    pub fn with_external_sources(mut self, mut sources: Vec<ExternalSource>) -> Self {
+        for source in sources.iter_mut().filter(|s| s.last_refreshed.is_none()) {
+            source.last_refreshed = self
+                .external_sources
+                .iter()
+                .find(|old| old.kind == source.kind && old.connection_id == source.connection_id)
+                .and_then(|old| old.last_refreshed);
+        }
         self.external_sources = sources;
         self
     }
 
+    /// The catalog without its fields' HyperLogLog sketches, which are only
+    /// needed to merge partials; `distinct_estimate` keeps their result.
+    /// Cleared sketches are left out when serialized, for API responses.
+    pub fn without_sketches(mut self) -> Self {
+        for field in self.entity_types.iter_mut().flat_map(|entry| &mut entry.fields) {
+            field.sketch = HyperLogLog::default();
+        }
+        self
+    }
+
     /// Generate a natural-language schema description for an LLM system prompt.
     pub fn to_system_prompt(&self) -> String {
         let mut lines = Vec::new();
@@ -189,6 +223,16 @@ impl Catalog {
                 "  - {} ({} nodes){}",
                 entry.entity_type, entry.node_count, samples
             ));
+            for field in entry.fields.iter().filter(|f| f.field != KEY_FIELD) {
+                let top: Vec<&str> = field.top_values.iter().map(|v| v.value.as_str()).collect();
+                lines.push(format!(
+                    "      {} \u{2192} {} (~{} distinct{})",
+                    field.field,
+                    field.value_type.as_deref().unwrap_or("?"),
+                    field.distinct_estimate,
+                    if top.is_empty() { String::new() } else { format!("; top: {}", top.join(", ")) },
+                ));
+            }
         }
 
         lines.push(String::new());
@@ -0,0 +1,124 @@
+use std::collections::{HashMap, HashSet};
+
+use stupid_core::NodeId;
+use stupid_graph::store::{Edge, Node};
+use stupid_graph::GraphStore;
+
+use super::hll::HyperLogLog;
+use super::types::{FieldStats, ValueCount, TOP_VALUES};
+
+/// Name of the field holding the node key itself.
+pub(super) const KEY_FIELD: &str = "key";
+
+#[derive(Default)]
+struct FieldAccumulator {
+    value_type: Option<String>,
+    present: HashSet<NodeId>,
+    counts: HashMap<String, usize>,
+    sketch: HyperLogLog,
+}
+
+/// Field statistics per entity type over `nodes` and the `edges` leaving them.
+///
+/// Edges are deduplicated per (source, target, type) in the graph, so an
+/// edge count per target is the number of source nodes with that value.
+pub(super) fn collect_field_stats<'a>(
+    graph: &GraphStore,
+    nodes: impl IntoIterator<Item = &'a Node>,
+    edges: impl IntoIterator<Item = &'a Edge>,
+) -> HashMap<String, Vec<FieldStats>> {
+    let mut keys: HashMap<String, (usize, HyperLogLog)> = HashMap::new();
+    for node in nodes {
+        let (count, sketch) = keys.entry(node.entity_type.to_string()).or_default();
+        *count += 1;
+        sketch.insert(&node.key);
+    }
+
+    let mut fields: HashMap<(String, String), FieldAccumulator> = HashMap::new();
+    for edge in edges {
+        let (Some(source), Some(target)) = (graph.nodes.get(&edge.source), graph.nodes.get(&edge.target))
+        else {
+            continue;
+        };
+        let acc = fields
+            .entry((source.entity_type.to_string(), edge.edge_type.to_string()))
+            .or_default();
+        acc.value_type.get_or_insert_with(|| target.entity_type.to_string());
+        acc.present.insert(source.id);
+        *acc.counts.entry(target.key.clone()).or_default() += 1;
+        acc.sketch.insert(&target.key);
+    }
+
+    let mut by_type: HashMap<String, Vec<FieldStats>> = HashMap::new();
+    for (entity_type, (present_count, sketch)) in keys {
+        by_type.entry(entity_type).or_default().push(FieldStats {
+            field: KEY_FIELD.to_string(),
+            value_type: None,
+            present_count,
+            distinct_estimate: sketch.estimate(),
+            top_values: Vec::new(),
+            sketch,
+        });
+    }
+    for ((entity_type, field), acc) in fields {
+        by_type.entry(entity_type).or_default().push(FieldStats {
+            field,
+            value_type: acc.value_type,
+            present_count: acc.present.len(),
+            distinct_estimate: acc.sketch.estimate(),
+            top_values: top_values(acc.counts),
+            sketch: acc.sketch,
+        });
+    }
+    for stats in by_type.values_mut() {
+        sort_fields(stats);
+    }
+    by_type
+}
+
+/// Merge `other` into `fields`: presence and value counts are summed and
+/// sketches unioned, so distinct estimates don't double-count values seen
+/// in both. Merged top values are approximate, since a value outside one
+/// side's top list contributes nothing from that side.
+pub(super) fn merge_field_stats(fields: &mut Vec<FieldStats>, other: &[FieldStats]) {
+    for incoming in other {
+        let Some(existing) = fields.iter_mut().find(|f| f.field == incoming.field) else {
+            fields.push(incoming.clone());
+            continue;
+        };
+        existing.present_count += incoming.present_count;
+        if existing.value_type.is_none() {
+            existing.value_type = incoming.value_type.clone();
+        }
+        existing.sketch.merge(&incoming.sketch);
+        existing.distinct_estimate = existing.sketch.estimate();
+
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for vc in existing.top_values.drain(..).chain(incoming.top_values.iter().cloned()) {
+            *counts.entry(vc.value).or_default() += vc.count;
+        }
+        existing.top_values = top_values(counts);
+    }
+    sort_fields(fields);
+}
+
+/// Most common values first, ties by value, capped at [`TOP_VALUES`].
+fn top_values(counts: HashMap<String, usize>) -> Vec<ValueCount> {
+    let mut values: Vec<ValueCount> = counts
+        .into_iter()
+        .map(|(value, count)| ValueCount { value, count })
+        .collect();
+    values.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.value.cmp(&b.value)));
+    values.truncate(TOP_VALUES);
+    values
+}
+
+/// `key` first, then by presence descending.
+fn sort_fields(fields: &mut [FieldStats]) {
+    fields.sort_by(|a, b| {
+        (b.field == KEY_FIELD)
+            .cmp(&(a.field == KEY_FIELD))
+            .then_with(|| b.present_count.cmp(&a.present_count))
+            .then_with(|| a.field.cmp(&b.field))
+    });
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use stupid_core::hashing::hash64;
+
+/// Register index bits: 2^10 registers, ~3.3% standard error.
+const PRECISION: u32 = 10;
+const REGISTERS: usize = 1 << PRECISION;
+
+/// HyperLogLog sketch for approximate distinct counts.
+///
+/// Sketches built from different partials merge losslessly (register-wise
+/// max), so the merged estimate is the same as one built over all values.
+/// Values are hashed with a fixed function, so sketches persisted by
+/// different processes stay comparable. Serialized as a hex string of the
+/// registers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    pub fn insert(&mut self, value: &str) {
+        let hash = hash64(value.as_bytes());
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // Position of the first set bit in the remaining 54 bits, 1-based.
+        let rest = hash << PRECISION;
+        let rank = (rest.leading_zeros().min(64 - PRECISION) + 1) as u8;
+        if rank > self.registers[index] {
+            self.registers[index] = rank;
+        }
+    }
+
+    /// Whether no value has been inserted.
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|&r| r == 0)
+    }
+
+    /// Fold `other` into this sketch.
+    pub fn merge(&mut self, other: &HyperLogLog) {
+        for (mine, theirs) in self.registers.iter_mut().zip(&other.registers) {
+            *mine = (*mine).max(*theirs);
+        }
+    }
+
+    /// Estimated number of distinct values inserted.
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&r| 2f64.powi(-(r as i32))).sum();
+        let raw = alpha * m * m / sum;
+
+        let zeros = self.registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 {
+            // Linear counting is more accurate for small cardinalities.
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+impl Serialize for HyperLogLog {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let hex: String = self.registers.iter().map(|r| format!("{r:02x}")).collect();
+        serializer.serialize_str(&hex)
+    }
+}
+
+impl<'de> Deserialize<'de> for HyperLogLog {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        if hex.len() != REGISTERS * 2 || !hex.is_ascii() {
+            return Err(serde::de::Error::custom(format!(
+                "expected {} hex digits for HyperLogLog registers, got {}",
+                REGISTERS * 2,
+                hex.len()
+            )));
+        }
+        let registers = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(serde::de::Error::custom)?;
+        Ok(Self { registers })
+    }
+}
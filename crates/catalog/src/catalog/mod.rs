@@ -1,6 +1,9 @@
 mod builder;
+mod fields;
+mod hll;
 mod partial;
 mod tests;
 mod types;
 
+pub use hll::HyperLogLog;
 pub use types::*;
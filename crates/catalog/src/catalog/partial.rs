@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet};
 use stupid_graph::GraphStore;
 use tracing::info;
 
+use super::fields::collect_field_stats;
 use super::types::{CatalogEntry, EdgeSummary, PartialCatalog};
 
 impl PartialCatalog {
@@ -11,6 +12,12 @@ impl PartialCatalog {
     /// Nodes are included if their `segment_refs` contains `segment_id`.
     /// Edges are included if their `segment_id` matches exactly.
     pub fn from_graph_segment(graph: &GraphStore, segment_id: &str) -> Self {
+        let mut type_fields = collect_field_stats(
+            graph,
+            graph.nodes.values().filter(|n| n.segment_refs.contains(segment_id)),
+            graph.edges.values().filter(|e| e.segment_id == segment_id),
+        );
+
         // Collect nodes that belong to this segment.
         let mut type_nodes: HashMap<String, Vec<String>> = HashMap::new();
         let mut segment_node_count = 0usize;
@@ -31,10 +38,12 @@ impl PartialCatalog {
                 let node_count = keys.len();
                 let mut sample_keys: Vec<String> = keys.into_iter().take(5).collect();
                 sample_keys.sort();
+                let fields = type_fields.remove(&entity_type).unwrap_or_default();
                 CatalogEntry {
                     entity_type,
                     node_count,
                     sample_keys,
+                    fields,
                 }
             })
             .collect();
@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests {
     use crate::catalog::types::*;
+    use crate::catalog::HyperLogLog;
     use stupid_core::{EdgeType, EntityType};
     use stupid_graph::GraphStore;
 
@@ -84,6 +85,8 @@ mod tests {
                     ],
                 }],
             }],
+            last_refreshed: None,
+            refresh_ttl_secs: DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
         }]);
 
         assert_eq!(cat.external_sources.len(), 1);
@@ -128,6 +131,8 @@ mod tests {
                     }],
                 }],
             }],
+            last_refreshed: None,
+            refresh_ttl_secs: DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
         }]);
 
         let json = serde_json::to_string(&cat).expect("serialize");
@@ -278,4 +283,170 @@ mod tests {
         let merged = Catalog::from_partials(&[partial]);
         assert!(merged.entity_types[0].sample_keys.len() <= 5);
     }
+
+    // -- Field statistics --
+
+    /// Members m0..m5999 in seg-a and m3000..m8999 in seg-b (3000 shared),
+    /// each with a currency edge.
+    fn build_overlapping_members_graph() -> GraphStore {
+        let mut g = GraphStore::new();
+        let seg_a = "seg-a".to_string();
+        let seg_b = "seg-b".to_string();
+        let currencies: Vec<_> = ["usd", "eur", "thb", "vnd"]
+            .iter()
+            .map(|c| g.upsert_node(EntityType::Currency, c, &seg_a))
+            .collect();
+        g.upsert_node(EntityType::Currency, "vnd", &seg_b);
+
+        for i in 0..6000 {
+            let m = g.upsert_node(EntityType::Member, &format!("m{i}"), &seg_a);
+            g.add_edge(m, currencies[i % 3], EdgeType::UsesCurrency, &seg_a);
+        }
+        for i in 3000..9000 {
+            let m = g.upsert_node(EntityType::Member, &format!("m{i}"), &seg_b);
+            let currency = if i % 2 == 0 { currencies[0] } else { currencies[3] };
+            g.add_edge(m, currency, EdgeType::UsesCurrency, &seg_b);
+        }
+        g
+    }
+
+    fn field<'a>(entry: &'a CatalogEntry, name: &str) -> &'a FieldStats {
+        entry.fields.iter().find(|f| f.field == name).unwrap()
+    }
+
+    fn members(entries: &[CatalogEntry]) -> &CatalogEntry {
+        entries.iter().find(|e| e.entity_type == "Member").unwrap()
+    }
+
+    #[test]
+    fn partial_catalog_field_stats() {
+        let g = build_overlapping_members_graph();
+        let partial = PartialCatalog::from_graph_segment(&g, "seg-a");
+        let member = members(&partial.entity_types);
+
+        assert_eq!(member.fields[0].field, "key");
+        let key = field(member, "key");
+        assert_eq!(key.present_count, 6000);
+        assert!(key.top_values.is_empty());
+
+        let currency = field(member, "UsesCurrency");
+        assert_eq!(currency.value_type.as_deref(), Some("Currency"));
+        assert_eq!(currency.present_count, 6000);
+        assert_eq!(currency.distinct_estimate, 3);
+        assert_eq!(
+            currency.top_values,
+            ["eur", "thb", "usd"]
+                .iter()
+                .map(|v| ValueCount { value: v.to_string(), count: 2000 })
+                .collect::<Vec<_>>()
+        );
+
+        let json = serde_json::to_string(&partial).unwrap();
+        let restored: PartialCatalog = serde_json::from_str(&json).unwrap();
+        assert_eq!(field(members(&restored.entity_types), "key").sketch, key.sketch);
+    }
+
+    #[test]
+    fn catalog_from_partials_merges_cardinality() {
+        let g = build_overlapping_members_graph();
+        let partials = [
+            PartialCatalog::from_graph_segment(&g, "seg-a"),
+            PartialCatalog::from_graph_segment(&g, "seg-b"),
+        ];
+        let merged = Catalog::from_partials(&partials);
+        let member = members(&merged.entity_types);
+
+        // Node counts double-count the 3000 shared members; the sketch doesn't.
+        assert_eq!(member.node_count, 12_000);
+        let key = field(member, "key");
+        let error = (key.distinct_estimate as f64 - 9000.0).abs() / 9000.0;
+        assert!(error < 0.1, "estimated {} distinct members", key.distinct_estimate);
+
+        // Merging sketches is lossless: same estimate as one pass over the graph.
+        let whole = Catalog::from_graph(&g);
+        assert_eq!(key.distinct_estimate, field(members(&whole.entity_types), "key").distinct_estimate);
+
+        let currency = field(member, "UsesCurrency");
+        assert_eq!(currency.distinct_estimate, 4);
+        assert_eq!(currency.top_values[0].value, "usd");
+        // Incremental merge (existing catalog + new partial) agrees.
+        let existing = Catalog::from_partials(&partials[..1]);
+        let existing_as_partial = PartialCatalog {
+            segment_id: "__existing__".to_string(),
+            entity_types: existing.entity_types,
+            edge_types: existing.edge_types,
+            node_count: existing.total_nodes,
+            edge_count: existing.total_edges,
+        };
+        let incremental = Catalog::from_partials(&[existing_as_partial, partials[1].clone()]);
+        assert_eq!(field(members(&incremental.entity_types), "key").sketch, key.sketch);
+    }
+
+    #[test]
+    fn catalog_without_sketches_keeps_estimates() {
+        let catalog = Catalog::from_graph(&build_overlapping_members_graph());
+        let estimate = field(members(&catalog.entity_types), "key").distinct_estimate;
+        assert!(serde_json::to_string(&catalog).unwrap().contains("\"sketch\""));
+
+        let json = serde_json::to_string(&catalog.without_sketches()).unwrap();
+        assert!(!json.contains("\"sketch\""));
+        let restored: Catalog = serde_json::from_str(&json).unwrap();
+        assert_eq!(field(members(&restored.entity_types), "key").distinct_estimate, estimate);
+    }
+
+    #[test]
+    fn hyperloglog_estimates_within_error() {
+        for n in [10usize, 1_000, 100_000] {
+            let mut sketch = HyperLogLog::new();
+            for i in 0..n {
+                sketch.insert(&format!("value-{i}"));
+                // Repeats don't count.
+                sketch.insert("value-0");
+            }
+            let error = (sketch.estimate() as f64 - n as f64).abs() / n as f64;
+            assert!(error < 0.1, "n={n}: estimated {}", sketch.estimate());
+        }
+        assert_eq!(HyperLogLog::new().estimate(), 0);
+        assert!(serde_json::from_str::<HyperLogLog>("\"00\"").is_err());
+    }
+
+    #[test]
+    fn catalog_prompt_lists_fields() {
+        let prompt = Catalog::from_graph(&build_overlapping_members_graph()).to_system_prompt();
+        assert!(prompt.contains("UsesCurrency \u{2192} Currency (~4 distinct; top: usd, vnd"));
+    }
+
+    fn bare_source(connection_id: &str, last_refreshed: Option<chrono::DateTime<chrono::Utc>>) -> ExternalSource {
+        ExternalSource {
+            name: connection_id.to_string(),
+            kind: "athena".to_string(),
+            connection_id: connection_id.to_string(),
+            databases: vec![],
+            last_refreshed,
+            refresh_ttl_secs: 3600,
+        }
+    }
+
+    #[test]
+    fn external_source_due_after_ttl() {
+        let now = chrono::Utc::now();
+        let fresh = bare_source("fresh", Some(now - chrono::Duration::minutes(59)));
+        let stale = bare_source("stale", Some(now - chrono::Duration::minutes(60)));
+        assert!(!fresh.is_due_for_refresh(now));
+        assert!(stale.is_due_for_refresh(now));
+        assert!(bare_source("never", None).is_due_for_refresh(now));
+
+        let disabled = ExternalSource { refresh_ttl_secs: 0, ..bare_source("off", None) };
+        assert!(!disabled.is_due_for_refresh(now));
+    }
+
+    #[test]
+    fn with_external_sources_keeps_refresh_time() {
+        let refreshed = chrono::Utc::now() - chrono::Duration::minutes(5);
+        let cat = Catalog::from_graph(&GraphStore::new())
+            .with_external_sources(vec![bare_source("lake", Some(refreshed))])
+            .with_external_sources(vec![bare_source("lake", None), bare_source("new", None)]);
+        assert_eq!(cat.external_sources[0].last_refreshed, Some(refreshed));
+        assert_eq!(cat.external_sources[1].last_refreshed, None);
+    }
 }
@@ -1,11 +1,62 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use super::hll::HyperLogLog;
+
+/// How long an external source's schema stays fresh by default (24h).
+pub const DEFAULT_EXTERNAL_REFRESH_TTL_SECS: u64 = 24 * 60 * 60;
+
+fn default_refresh_ttl_secs() -> u64 {
+    DEFAULT_EXTERNAL_REFRESH_TTL_SECS
+}
+
 /// Describes a single entity type discovered in the graph.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CatalogEntry {
     pub entity_type: String,
     pub node_count: usize,
     pub sample_keys: Vec<String>,
+    /// Per-field statistics: the node key, then one field per outgoing
+    /// edge type (the document field the edge was extracted from).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<FieldStats>,
+}
+
+/// Presence and cardinality of one field of an entity type.
+///
+/// Fields are `key` (the node key itself) and the type's outgoing edge
+/// types, whose values are the keys of the nodes they point to, e.g. a
+/// Member's `UsesCurrency` values are currency keys.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldStats {
+    /// `key`, or the edge type name.
+    pub field: String,
+    /// Entity type of the values; `None` for `key`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_type: Option<String>,
+    /// Nodes that have the field. Summed across partials, so a node in
+    /// several segments counts once per segment, like `node_count`.
+    pub present_count: usize,
+    /// Approximate number of distinct values, from `sketch`.
+    pub distinct_estimate: u64,
+    /// Most common values with the number of nodes having each, at most
+    /// [`TOP_VALUES`]; empty for `key`, whose values are unique per node.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub top_values: Vec<ValueCount>,
+    /// Kept for merging partials; empty (and not serialized) in
+    /// [`Catalog::without_sketches`].
+    #[serde(default, skip_serializing_if = "HyperLogLog::is_empty")]
+    pub sketch: HyperLogLog,
+}
+
+/// Values kept in [`FieldStats::top_values`].
+pub const TOP_VALUES: usize = 5;
+
+/// A field value and how many nodes have it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ValueCount {
+    pub value: String,
+    pub count: usize,
 }
 
 /// Describes an edge type discovered in the graph.
@@ -27,6 +78,27 @@ pub struct ExternalSource {
     /// Connection identifier for routing queries.
     pub connection_id: String,
     pub databases: Vec<ExternalDatabase>,
+    /// When the schema was last fetched from the source; `None` if unknown.
+    #[serde(default)]
+    pub last_refreshed: Option<DateTime<Utc>>,
+    /// Seconds after `last_refreshed` at which the schema counts as stale.
+    /// Zero disables scheduled refreshes.
+    #[serde(default = "default_refresh_ttl_secs")]
+    pub refresh_ttl_secs: u64,
+}
+
+impl ExternalSource {
+    /// Whether the schema has outlived its TTL at `now`. A source that was
+    /// never refreshed is due straight away, unless refreshes are disabled.
+    pub fn is_due_for_refresh(&self, now: DateTime<Utc>) -> bool {
+        if self.refresh_ttl_secs == 0 {
+            return false;
+        }
+        match self.last_refreshed {
+            None => true,
+            Some(last) => now - last >= chrono::Duration::seconds(self.refresh_ttl_secs as i64),
+        }
+    }
 }
 
 /// A database within an external source.
@@ -1,11 +1,15 @@
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
-use serde_json::{json, Value};
+use async_trait::async_trait;
+use serde_json::{json, Map, Value};
 use stupid_core::NodeId;
 use stupid_graph::GraphStore;
 use thiserror::Error;
 use tracing::debug;
 
+use crate::catalog::Catalog;
+use crate::metadata_query::MetadataQuery;
 use crate::plan::*;
 
 #[derive(Debug, Error)]
@@ -18,30 +22,235 @@ pub enum ExecutorError {
     UnknownEdgeType(String),
     #[error("step '{0}' has no input nodes (missing depends_on?)")]
     NoInput(String),
+    #[error("step '{0}' depends on external rows, not nodes")]
+    RowsAsNodes(String),
+    #[error("external scan '{0}' was not fetched before execution")]
+    NotScanned(String),
+    #[error("external scan '{step}' failed: {error:#}")]
+    ExternalScan { step: String, error: anyhow::Error },
+    #[error("join '{0}' needs a node step and an external scan in depends_on")]
+    InvalidJoin(String),
+    #[error("step '{0}' needs the graph; only catalog metadata can be queried here")]
+    NeedsGraph(String),
 }
 
+/// Fetches the rows of an [`ExternalScanStep`], applying its filter at the
+/// source. Each row is a JSON object keyed by column name.
+#[async_trait]
+pub trait ExternalScanner: Send + Sync {
+    async fn scan(&self, step: &ExternalScanStep) -> anyhow::Result<Vec<Value>>;
+}
+
+/// Rows fetched for a plan's external scans, keyed by step ID.
+pub type ExternalRows = HashMap<String, Vec<Value>>;
+
 /// Hard limit on the number of result rows returned to the client.
-const MAX_RESULT_ROWS: usize = 200;
+pub const MAX_RESULT_ROWS: usize = 200;
 
 /// Hard limit on intermediate node sets to prevent runaway traversals.
 const MAX_INTERMEDIATE_NODES: usize = 50_000;
 
+/// How many nodes a filter or traversal visits between deadline checks.
+const DEADLINE_CHECK_INTERVAL: usize = 4096;
+
+/// Which page of results to produce, and when to give up.
+#[derive(Debug, Clone, Copy)]
+pub struct ExecuteOptions {
+    /// Result rows to skip.
+    pub offset: usize,
+    /// Maximum result rows to produce.
+    pub limit: usize,
+    /// Stop once this instant passes, keeping the rows produced so far.
+    pub deadline: Option<Instant>,
+}
+
+impl Default for ExecuteOptions {
+    fn default() -> Self {
+        Self { offset: 0, limit: MAX_RESULT_ROWS, deadline: None }
+    }
+}
+
+/// Totals of an execution; the rows themselves go to the caller's callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionSummary {
+    /// Rows the plan matched, before paging. Zero if execution timed out
+    /// before the final step finished.
+    pub total: usize,
+    /// Rows handed to the callback.
+    pub returned: usize,
+    /// The deadline passed; the rows produced are incomplete.
+    pub timed_out: bool,
+}
+
+/// One page of results, collected by [`QueryExecutor::execute_with`].
+#[derive(Debug, Clone)]
+pub struct PagedResults {
+    pub rows: Vec<Value>,
+    pub total: usize,
+    pub timed_out: bool,
+}
+
+#[derive(Clone, Copy)]
+struct Deadline(Option<Instant>);
+
+impl Deadline {
+    fn passed(&self) -> bool {
+        self.0.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+}
+
 /// Executes a QueryPlan against a GraphStore, returning results as JSON values.
+///
+/// Plans with `external_scan` steps run in two phases: [`scan_external`]
+/// fetches their rows without touching the graph, then one of the
+/// `*_external` methods runs the plan against the graph and those rows.
+///
+/// [`scan_external`]: QueryExecutor::scan_external
 pub struct QueryExecutor;
 
 impl QueryExecutor {
+    /// Run plans against `catalog`'s metadata instead of a graph, e.g. to
+    /// query a historical snapshot.
+    pub fn with_catalog(catalog: &Catalog) -> MetadataQuery<'_> {
+        MetadataQuery::new(catalog)
+    }
+
+    /// Fetch the rows of every `external_scan` step in `plan`.
+    pub async fn scan_external(
+        plan: &QueryPlan,
+        scanner: &dyn ExternalScanner,
+    ) -> Result<ExternalRows, ExecutorError> {
+        let mut rows = ExternalRows::new();
+        for step in &plan.steps {
+            if let StepKind::ExternalScan(scan) = &step.kind {
+                let fetched = scanner
+                    .scan(scan)
+                    .await
+                    .map_err(|error| ExecutorError::ExternalScan { step: step.id.clone(), error })?;
+                debug!("Step '{}' external scan of {}: {} rows", step.id, scan.qualified_name(), fetched.len());
+                rows.insert(step.id.clone(), fetched);
+            }
+        }
+        Ok(rows)
+    }
+
     /// Execute a query plan and return results as a list of JSON objects.
+    ///
+    /// At most [`MAX_RESULT_ROWS`] rows are returned; if more matched, a
+    /// trailing `_truncated` row says how many.
     pub fn execute(plan: &QueryPlan, graph: &GraphStore) -> Result<Vec<Value>, ExecutorError> {
+        let PagedResults { mut rows, total, .. } = Self::execute_with(plan, graph, &ExecuteOptions::default())?;
+
+        if total > MAX_RESULT_ROWS {
+            rows.push(json!({
+                "_truncated": true,
+                "_total_matched": total,
+                "_returned": MAX_RESULT_ROWS,
+                "_message": format!(
+                    "Result set truncated: {} of {} matches returned. Refine your query or use an aggregate step.",
+                    MAX_RESULT_ROWS, total
+                ),
+            }));
+        }
+
+        Ok(rows)
+    }
+
+    /// Execute a query plan and collect one page of results.
+    pub fn execute_with(
+        plan: &QueryPlan,
+        graph: &GraphStore,
+        options: &ExecuteOptions,
+    ) -> Result<PagedResults, ExecutorError> {
+        Self::execute_with_external(plan, graph, &ExternalRows::new(), options)
+    }
+
+    /// Like [`execute_with`](Self::execute_with), reading `external_scan`
+    /// steps from `external`.
+    pub fn execute_with_external(
+        plan: &QueryPlan,
+        graph: &GraphStore,
+        external: &ExternalRows,
+        options: &ExecuteOptions,
+    ) -> Result<PagedResults, ExecutorError> {
+        let mut rows = Vec::new();
+        let summary = Self::execute_each_external(plan, graph, external, options, |row| {
+            rows.push(row);
+            true
+        })?;
+        Ok(PagedResults { rows, total: summary.total, timed_out: summary.timed_out })
+    }
+
+    /// Execute a query plan, handing each result row of the requested page
+    /// to `emit` as it is produced. `emit` returns `false` to stop early.
+    ///
+    /// Node rows are ordered by node ID and aggregate rows by descending
+    /// count, so pages are stable across calls on an unchanged graph.
+    pub fn execute_each(
+        plan: &QueryPlan,
+        graph: &GraphStore,
+        options: &ExecuteOptions,
+        emit: impl FnMut(Value) -> bool,
+    ) -> Result<ExecutionSummary, ExecutorError> {
+        Self::execute_each_external(plan, graph, &ExternalRows::new(), options, emit)
+    }
+
+    /// Like [`execute_each`](Self::execute_each), reading `external_scan`
+    /// steps from `external`. Join rows are ordered by node ID, then by
+    /// external row order.
+    pub fn execute_each_external(
+        plan: &QueryPlan,
+        graph: &GraphStore,
+        external: &ExternalRows,
+        options: &ExecuteOptions,
+        mut emit: impl FnMut(Value) -> bool,
+    ) -> Result<ExecutionSummary, ExecutorError> {
+        let deadline = Deadline(options.deadline);
+        let timed_out = ExecutionSummary { total: 0, returned: 0, timed_out: true };
+
         // step_id -> set of node IDs produced by that step
         let mut step_results: HashMap<String, HashSet<NodeId>> = HashMap::new();
+        // step_id -> rows produced by an external scan or join
+        let mut row_results: HashMap<String, Vec<Value>> = HashMap::new();
+        let mut aggregate_rows = None;
 
         for step in &plan.steps {
+            if deadline.passed() {
+                return Ok(timed_out);
+            }
+
+            match &step.kind {
+                StepKind::ExternalScan(_) => {
+                    let rows = external
+                        .get(&step.id)
+                        .ok_or_else(|| ExecutorError::NotScanned(step.id.clone()))?;
+                    row_results.insert(step.id.clone(), rows.clone());
+                    continue;
+                }
+                StepKind::Join(join) => {
+                    let invalid = || ExecutorError::InvalidJoin(step.id.clone());
+                    let [left, right] = step.depends_on.as_slice() else {
+                        return Err(invalid());
+                    };
+                    let nodes = step_results.get(left).ok_or_else(invalid)?;
+                    let rows = row_results.get(right).ok_or_else(invalid)?;
+                    let joined = Self::exec_join(join, graph, nodes, rows);
+                    debug!("Step '{}' join: {} rows", step.id, joined.len());
+                    row_results.insert(step.id.clone(), joined);
+                    continue;
+                }
+                _ => {}
+            }
+
             // Gather input nodes from dependencies
             let input_nodes: Option<HashSet<NodeId>> = if step.depends_on.is_empty() {
                 None // no dependency = operate on full graph
             } else {
                 let mut combined = HashSet::new();
                 for dep_id in &step.depends_on {
+                    if row_results.contains_key(dep_id) {
+                        return Err(ExecutorError::RowsAsNodes(step.id.clone()));
+                    }
                     let dep = step_results
                         .get(dep_id)
                         .ok_or_else(|| ExecutorError::UnknownDependency(dep_id.clone()))?;
@@ -52,7 +261,7 @@ impl QueryExecutor {
 
             let result = match &step.kind {
                 StepKind::Filter(f) => {
-                    let mut nodes = Self::exec_filter(f, graph, input_nodes.as_ref());
+                    let mut nodes = Self::exec_filter(f, graph, input_nodes.as_ref(), deadline);
                     if nodes.len() > MAX_INTERMEDIATE_NODES {
                         debug!(
                             "Step '{}' filter capped from {} to {} nodes",
@@ -65,7 +274,7 @@ impl QueryExecutor {
                 StepKind::Traversal(t) => {
                     let input = input_nodes
                         .ok_or_else(|| ExecutorError::NoInput(step.id.clone()))?;
-                    let mut nodes = Self::exec_traversal(t, graph, &input);
+                    let mut nodes = Self::exec_traversal(t, graph, &input, deadline);
                     if nodes.len() > MAX_INTERMEDIATE_NODES {
                         debug!(
                             "Step '{}' traversal capped from {} to {} nodes",
@@ -80,64 +289,71 @@ impl QueryExecutor {
                         graph.nodes.keys().copied().collect()
                     });
                     let agg = Self::exec_aggregate(a, graph, &nodes);
-                    // For aggregate, we return early with the aggregation result
                     step_results.insert(step.id.clone(), nodes);
                     debug!("Step '{}' aggregate: {} groups", step.id, agg.len());
+                    // The aggregation is the result if this is the last step.
                     if step.id == plan.steps.last().map(|s| s.id.as_str()).unwrap_or("") {
-                        return Ok(agg);
+                        aggregate_rows = Some(agg);
+                        break;
                     }
                     continue;
                 }
+                StepKind::ExternalScan(_) | StepKind::Join(_) => unreachable!("handled above"),
             };
 
+            // A step cut short by the deadline has incomplete results.
+            if deadline.passed() {
+                return Ok(timed_out);
+            }
             debug!("Step '{}': {} nodes", step.id, result.len());
             step_results.insert(step.id.clone(), result);
         }
 
-        // Return the final step's nodes as JSON
-        let last_step_id = plan
-            .steps
-            .last()
-            .map(|s| s.id.as_str())
-            .unwrap_or("");
-        let final_nodes = step_results
-            .get(last_step_id)
-            .cloned()
-            .unwrap_or_default();
-
-        let total_matched = final_nodes.len();
-        let mut results: Vec<Value> = final_nodes
-            .iter()
-            .take(MAX_RESULT_ROWS)
-            .filter_map(|id| {
-                let node = graph.nodes.get(id)?;
-                Some(json!({
-                    "id": id.to_string(),
-                    "entity_type": node.entity_type.to_string(),
-                    "key": node.key,
-                }))
-            })
-            .collect();
+        let last_step_id = plan.steps.last().map(|s| s.id.as_str()).unwrap_or("");
+        let last_rows = row_results.remove(last_step_id);
+        let (total, rows): (usize, Box<dyn Iterator<Item = Value>>) = match (aggregate_rows, last_rows) {
+            (Some(agg), _) => (agg.len(), Box::new(agg.into_iter())),
+            (None, Some(rows)) => (rows.len(), Box::new(rows.into_iter())),
+            (None, None) => {
+                // Return the final step's nodes as JSON
+                let mut final_nodes: Vec<NodeId> = step_results
+                    .remove(last_step_id)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .collect();
+                final_nodes.sort_unstable();
+                let total = final_nodes.len();
+                let rows = final_nodes.into_iter().filter_map(|id| {
+                    let node = graph.nodes.get(&id)?;
+                    Some(json!({
+                        "id": id.to_string(),
+                        "entity_type": node.entity_type.to_string(),
+                        "key": node.key,
+                    }))
+                });
+                (total, Box::new(rows))
+            }
+        };
 
-        if total_matched > MAX_RESULT_ROWS {
-            results.push(json!({
-                "_truncated": true,
-                "_total_matched": total_matched,
-                "_returned": MAX_RESULT_ROWS,
-                "_message": format!(
-                    "Result set truncated: {} of {} matches returned. Refine your query or use an aggregate step.",
-                    MAX_RESULT_ROWS, total_matched
-                ),
-            }));
+        let mut summary = ExecutionSummary { total, returned: 0, timed_out: false };
+        for row in rows.skip(options.offset).take(options.limit) {
+            if deadline.passed() {
+                summary.timed_out = true;
+                break;
+            }
+            summary.returned += 1;
+            if !emit(row) {
+                break;
+            }
         }
-
-        Ok(results)
+        Ok(summary)
     }
 
     fn exec_filter(
         filter: &FilterStep,
         graph: &GraphStore,
         input: Option<&HashSet<NodeId>>,
+        deadline: Deadline,
     ) -> HashSet<NodeId> {
         let candidates: Box<dyn Iterator<Item = (&NodeId, &stupid_graph::store::Node)>> =
             if let Some(input_set) = input {
@@ -151,6 +367,9 @@ impl QueryExecutor {
             };
 
         candidates
+            .enumerate()
+            .take_while(|(i, _)| i % DEADLINE_CHECK_INTERVAL != 0 || !deadline.passed())
+            .map(|(_, candidate)| candidate)
             .filter(|(_, node)| {
                 // Match entity type (case-insensitive)
                 if !node
@@ -191,15 +410,53 @@ impl QueryExecutor {
             .collect()
     }
 
+    /// Inner join of `nodes` with `rows` on `left_key` = `right_key`,
+    /// merging each pair into one row. Node fields win over same-named
+    /// columns.
+    fn exec_join(join: &JoinStep, graph: &GraphStore, nodes: &HashSet<NodeId>, rows: &[Value]) -> Vec<Value> {
+        let mut rows_by_key: HashMap<String, Vec<&Map<String, Value>>> = HashMap::new();
+        for row in rows.iter().filter_map(Value::as_object) {
+            if let Some(key) = row.get(&join.right_key).and_then(join_key) {
+                rows_by_key.entry(key).or_default().push(row);
+            }
+        }
+
+        let mut ids: Vec<NodeId> = nodes.iter().copied().collect();
+        ids.sort_unstable();
+        let mut joined = Vec::new();
+        for id in ids {
+            let Some(node) = graph.nodes.get(&id) else { continue };
+            let left = match join.left_key.as_str() {
+                "key" => &node.key,
+                _ => continue,
+            };
+            for row in rows_by_key.get(left).into_iter().flatten() {
+                let mut merged = Map::new();
+                merged.insert("id".into(), json!(id.to_string()));
+                merged.insert("entity_type".into(), json!(node.entity_type.to_string()));
+                merged.insert("key".into(), json!(node.key));
+                for (column, value) in row.iter() {
+                    merged.entry(column.clone()).or_insert_with(|| value.clone());
+                }
+                joined.push(Value::Object(merged));
+            }
+        }
+        joined
+    }
+
     fn exec_traversal(
         traversal: &TraversalStep,
         graph: &GraphStore,
         input: &HashSet<NodeId>,
+        deadline: Deadline,
     ) -> HashSet<NodeId> {
         let mut result = HashSet::new();
         let edge_type_str = &traversal.edge_type;
 
-        for &node_id in input {
+        for (i, &node_id) in input.iter().enumerate() {
+            if i % DEADLINE_CHECK_INTERVAL == 0 && deadline.passed() {
+                break;
+            }
             // Outgoing edges
             if matches!(
                 traversal.direction,
@@ -267,13 +524,23 @@ impl QueryExecutor {
         result.sort_by(|a, b| {
             let ca = a["count"].as_u64().unwrap_or(0);
             let cb = b["count"].as_u64().unwrap_or(0);
-            cb.cmp(&ca)
+            cb.cmp(&ca).then_with(|| a["group"].as_str().cmp(&b["group"].as_str()))
         });
 
         result
     }
 }
 
+/// A join key column value as a string; `None` for nulls and nested values.
+fn join_key(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -322,6 +589,59 @@ mod tests {
         assert_eq!(results.len(), 2);
     }
 
+    #[test]
+    fn execute_with_pages_through_sorted_rows() {
+        let g = build_test_graph();
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Device"}]}"#,
+        )
+        .unwrap();
+
+        let all = QueryExecutor::execute_with(&plan, &g, &ExecuteOptions::default()).unwrap();
+        assert_eq!(all.total, 2);
+        assert!(!all.timed_out);
+
+        let options = ExecuteOptions { offset: 1, limit: 1, ..Default::default() };
+        let page = QueryExecutor::execute_with(&plan, &g, &options).unwrap();
+        assert_eq!(page.total, 2);
+        assert_eq!(page.rows, vec![all.rows[1].clone()]);
+
+        let options = ExecuteOptions { offset: 5, ..Default::default() };
+        assert!(QueryExecutor::execute_with(&plan, &g, &options).unwrap().rows.is_empty());
+    }
+
+    #[test]
+    fn passed_deadline_returns_partial_result() {
+        let g = build_test_graph();
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#,
+        )
+        .unwrap();
+
+        let options = ExecuteOptions { deadline: Some(Instant::now()), ..Default::default() };
+        let result = QueryExecutor::execute_with(&plan, &g, &options).unwrap();
+        assert!(result.timed_out);
+        assert!(result.rows.is_empty());
+    }
+
+    #[test]
+    fn execute_each_stops_when_emit_declines() {
+        let g = build_test_graph();
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#,
+        )
+        .unwrap();
+
+        let mut seen = 0;
+        let summary = QueryExecutor::execute_each(&plan, &g, &ExecuteOptions::default(), |_| {
+            seen += 1;
+            false
+        })
+        .unwrap();
+        assert_eq!(seen, 1);
+        assert_eq!(summary, ExecutionSummary { total: 2, returned: 1, timed_out: false });
+    }
+
     #[test]
     fn execute_aggregate() {
         let g = build_test_graph();
@@ -335,4 +655,107 @@ mod tests {
         let results = QueryExecutor::execute(&plan, &g).unwrap();
         assert_eq!(results.len(), 2); // Member + Device groups
     }
+
+    /// Athena stand-in holding one table's rows, applying `equals` filters.
+    struct MockAthena(Vec<Value>);
+
+    #[async_trait]
+    impl ExternalScanner for MockAthena {
+        async fn scan(&self, step: &ExternalScanStep) -> anyhow::Result<Vec<Value>> {
+            anyhow::ensure!(step.table == "deposits", "table {} not found", step.table);
+            Ok(self
+                .0
+                .iter()
+                .filter(|row| {
+                    step.filter.as_ref().is_none_or(|f| row[&f.column].as_str() == Some(f.value.as_str()))
+                })
+                .cloned()
+                .collect())
+        }
+    }
+
+    fn deposits() -> MockAthena {
+        MockAthena(vec![
+            json!({"member_code": "alice", "total": "100", "currency": "EUR"}),
+            json!({"member_code": "carol", "total": "75", "currency": "EUR"}),
+            json!({"member_code": "alice", "total": "40", "currency": "USD"}),
+            json!({"member_code": "bob", "total": "10", "currency": "USD"}),
+        ])
+    }
+
+    fn join_plan(filter: &str) -> QueryPlan {
+        serde_json::from_str(&format!(
+            r#"{{"steps":[
+                {{"id":"members","type":"filter","entity_type":"Member"}},
+                {{"id":"deposits","type":"external_scan","connection_id":"lake","database":"finance","table":"deposits"{filter}}},
+                {{"id":"joined","depends_on":["members","deposits"],"type":"join","right_key":"member_code"}}
+            ]}}"#
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn join_graph_nodes_with_external_rows() {
+        let g = build_test_graph();
+        let plan = join_plan(r#","filter":{"column":"currency","operator":"equals","value":"EUR"}"#);
+
+        let external = QueryExecutor::scan_external(&plan, &deposits()).await.unwrap();
+        assert_eq!(external["deposits"].len(), 2);
+        let result = QueryExecutor::execute_with_external(&plan, &g, &external, &ExecuteOptions::default()).unwrap();
+
+        // carol has no node and bob's deposit was filtered out: inner join.
+        assert_eq!(result.total, 1);
+        let row = &result.rows[0];
+        assert_eq!((row["entity_type"].as_str(), row["key"].as_str()), (Some("Member"), Some("alice")));
+        assert_eq!((row["total"].as_str(), row["member_code"].as_str()), (Some("100"), Some("alice")));
+    }
+
+    #[tokio::test]
+    async fn join_emits_one_row_per_matching_pair() {
+        let g = build_test_graph();
+        let plan = join_plan("");
+        let external = QueryExecutor::scan_external(&plan, &deposits()).await.unwrap();
+        let result = QueryExecutor::execute_with_external(&plan, &g, &external, &ExecuteOptions::default()).unwrap();
+
+        let mut pairs: Vec<(&str, &str)> = result
+            .rows
+            .iter()
+            .map(|r| (r["key"].as_str().unwrap(), r["total"].as_str().unwrap()))
+            .collect();
+        // Rows for one node stay in external order; nodes are ordered by ID.
+        let alice: Vec<_> = pairs.iter().filter(|(key, _)| *key == "alice").map(|(_, total)| *total).collect();
+        assert_eq!(alice, vec!["100", "40"]);
+        pairs.sort_unstable();
+        assert_eq!(pairs, vec![("alice", "100"), ("alice", "40"), ("bob", "10")]);
+    }
+
+    #[tokio::test]
+    async fn external_steps_report_errors() {
+        let g = build_test_graph();
+        let plan = join_plan("");
+        assert!(matches!(
+            QueryExecutor::execute_with(&plan, &g, &ExecuteOptions::default()),
+            Err(ExecutorError::NotScanned(step)) if step == "deposits"
+        ));
+
+        let mut missing = plan.clone();
+        if let StepKind::ExternalScan(scan) = &mut missing.steps[1].kind {
+            scan.table = "withdrawals".into();
+        }
+        let err = QueryExecutor::scan_external(&missing, &deposits()).await.unwrap_err();
+        assert_eq!(err.to_string(), "external scan 'deposits' failed: table withdrawals not found");
+
+        let rows_as_nodes: QueryPlan = serde_json::from_str(
+            r#"{"steps":[
+                {"id":"deposits","type":"external_scan","connection_id":"lake","database":"finance","table":"deposits"},
+                {"id":"s2","depends_on":["deposits"],"type":"traversal","edge_type":"LoggedInFrom"}
+            ]}"#,
+        )
+        .unwrap();
+        let external = QueryExecutor::scan_external(&rows_as_nodes, &deposits()).await.unwrap();
+        assert!(matches!(
+            QueryExecutor::execute_with_external(&rows_as_nodes, &g, &external, &ExecuteOptions::default()),
+            Err(ExecutorError::RowsAsNodes(_))
+        ));
+    }
 }
@@ -1,14 +1,22 @@
 pub mod catalog;
 pub mod executor;
 pub mod manifest;
+pub mod metadata_query;
 pub mod plan;
 pub mod store;
 
 pub use catalog::{
     Catalog, CatalogEntry, EdgeSummary, ExternalColumn, ExternalDatabase, ExternalSource,
-    ExternalTable, PartialCatalog,
+    ExternalTable, PartialCatalog, DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
+};
+pub use executor::{
+    ExecuteOptions, ExecutionSummary, ExternalRows, ExternalScanner, PagedResults, QueryExecutor,
+    MAX_RESULT_ROWS,
 };
-pub use executor::QueryExecutor;
 pub use manifest::CatalogManifest;
-pub use plan::{AggregateStep, FilterStep, QueryPlan, QueryStep, TraversalStep};
+pub use metadata_query::MetadataQuery;
+pub use plan::{
+    AggregateStep, ExternalFilter, ExternalScanStep, FilterStep, InvalidReference, JoinStep,
+    QueryPlan, QueryStep, ReferenceKind, TraversalStep,
+};
 pub use store::CatalogStore;
@@ -0,0 +1,181 @@
+//! Query plans answered from catalog metadata alone.
+//!
+//! Historical snapshots keep the catalog but not the graph, so a plan run
+//! against one can only use what the catalog records per entity type:
+//! filters by entity type and counts grouped by entity type.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+use crate::catalog::{Catalog, CatalogEntry};
+use crate::executor::ExecutorError;
+use crate::plan::{QueryPlan, StepKind};
+
+/// Executes a [`QueryPlan`] against a [`Catalog`] instead of the graph.
+///
+/// Created with [`QueryExecutor::with_catalog`](crate::QueryExecutor::with_catalog).
+/// Supports `filter` steps without a field condition and `aggregate` steps
+/// grouped by `entity_type`; any other step fails with
+/// [`ExecutorError::NeedsGraph`].
+pub struct MetadataQuery<'a> {
+    catalog: &'a Catalog,
+}
+
+impl<'a> MetadataQuery<'a> {
+    pub(crate) fn new(catalog: &'a Catalog) -> Self {
+        Self { catalog }
+    }
+
+    /// Execute `plan` and return its rows.
+    ///
+    /// A final filter step yields one row per matching entity type with its
+    /// `node_count` and `sample_keys`; a final aggregate yields
+    /// `{group, count}` rows ordered by descending count, like the graph
+    /// executor.
+    pub fn execute(&self, plan: &QueryPlan) -> Result<Vec<Value>, ExecutorError> {
+        let mut step_results: HashMap<&str, Vec<&CatalogEntry>> = HashMap::new();
+        let mut aggregate_rows = None;
+
+        for step in &plan.steps {
+            let input: Vec<&CatalogEntry> = if step.depends_on.is_empty() {
+                self.catalog.entity_types.iter().collect()
+            } else {
+                let mut combined: Vec<&CatalogEntry> = Vec::new();
+                for dep_id in &step.depends_on {
+                    let dep = step_results
+                        .get(dep_id.as_str())
+                        .ok_or_else(|| ExecutorError::UnknownDependency(dep_id.clone()))?;
+                    for entry in dep {
+                        if !combined.iter().any(|e| e.entity_type == entry.entity_type) {
+                            combined.push(entry);
+                        }
+                    }
+                }
+                combined
+            };
+
+            let result = match &step.kind {
+                // A field condition needs node keys, which the catalog doesn't keep.
+                StepKind::Filter(f) if f.field.is_none() || f.operator.is_none() || f.value.is_none() => input
+                    .into_iter()
+                    .filter(|e| e.entity_type.eq_ignore_ascii_case(&f.entity_type))
+                    .collect(),
+                StepKind::Aggregate(a) if a.group_by == "entity_type" => {
+                    let is_last = plan.steps.last().is_some_and(|s| s.id == step.id);
+                    if is_last {
+                        aggregate_rows = Some(aggregate_by_entity_type(&input));
+                        break;
+                    }
+                    input
+                }
+                _ => return Err(ExecutorError::NeedsGraph(step.id.clone())),
+            };
+            step_results.insert(&step.id, result);
+        }
+
+        if let Some(rows) = aggregate_rows {
+            return Ok(rows);
+        }
+        let last_step_id = plan.steps.last().map(|s| s.id.as_str()).unwrap_or("");
+        let mut entries = step_results.remove(last_step_id).unwrap_or_default();
+        entries.sort_by(|a, b| a.entity_type.cmp(&b.entity_type));
+        Ok(entries
+            .into_iter()
+            .map(|e| {
+                json!({
+                    "entity_type": e.entity_type,
+                    "node_count": e.node_count,
+                    "sample_keys": e.sample_keys,
+                })
+            })
+            .collect())
+    }
+}
+
+fn aggregate_by_entity_type(entries: &[&CatalogEntry]) -> Vec<Value> {
+    let mut groups: Vec<(&str, usize)> =
+        entries.iter().map(|e| (e.entity_type.as_str(), e.node_count)).collect();
+    groups.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    groups
+        .into_iter()
+        .map(|(group, count)| json!({ "group": group, "count": count }))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog::EdgeSummary;
+    use crate::QueryExecutor;
+
+    fn catalog(members: usize, devices: usize) -> Catalog {
+        let entry = |entity_type: &str, node_count| CatalogEntry {
+            entity_type: entity_type.to_string(),
+            node_count,
+            sample_keys: vec![format!("{}-1", entity_type.to_lowercase())],
+            fields: vec![],
+        };
+        Catalog {
+            entity_types: vec![entry("Member", members), entry("Device", devices)],
+            edge_types: vec![EdgeSummary {
+                edge_type: "LoggedInFrom".to_string(),
+                count: members,
+                source_types: vec!["Member".to_string()],
+                target_types: vec!["Device".to_string()],
+            }],
+            total_nodes: members + devices,
+            total_edges: members,
+            external_sources: vec![],
+        }
+    }
+
+    fn plan(json: &str) -> QueryPlan {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn filter_returns_entity_type_metadata() {
+        let snapshot = catalog(10, 4);
+        let rows = QueryExecutor::with_catalog(&snapshot)
+            .execute(&plan(r#"{"steps":[{"id":"s1","type":"filter","entity_type":"member"}]}"#))
+            .unwrap();
+
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0]["entity_type"], "Member");
+        assert_eq!(rows[0]["node_count"], 10);
+        assert_eq!(rows[0]["sample_keys"][0], "member-1");
+    }
+
+    #[test]
+    fn aggregate_counts_by_entity_type() {
+        let snapshot = catalog(3, 8);
+        let rows = QueryExecutor::with_catalog(&snapshot)
+            .execute(&plan(r#"{"steps":[{"id":"agg","type":"aggregate","group_by":"entity_type"}]}"#))
+            .unwrap();
+
+        assert_eq!(rows, vec![
+            json!({"group": "Device", "count": 8}),
+            json!({"group": "Member", "count": 3}),
+        ]);
+    }
+
+    #[test]
+    fn graph_steps_are_rejected() {
+        let snapshot = catalog(3, 8);
+        let query = QueryExecutor::with_catalog(&snapshot);
+
+        let traversal = plan(
+            r#"{"steps":[
+                {"id":"s1","type":"filter","entity_type":"Member"},
+                {"id":"s2","type":"traversal","edge_type":"LoggedInFrom","depends_on":["s1"]}
+            ]}"#,
+        );
+        assert!(matches!(query.execute(&traversal), Err(ExecutorError::NeedsGraph(id)) if id == "s2"));
+
+        let keyed = plan(
+            r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member","field":"key","operator":"equals","value":"alice"}]}"#,
+        );
+        assert!(matches!(query.execute(&keyed), Err(ExecutorError::NeedsGraph(_))));
+    }
+}
@@ -1,14 +1,168 @@
+use std::collections::HashMap;
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 
+use crate::catalog::{Catalog, ExternalTable};
+
+/// Node fields the executor can filter on.
+pub const FILTER_FIELDS: &[&str] = &["key"];
+
+/// Node fields the executor can group aggregates by.
+pub const GROUP_BY_FIELDS: &[&str] = &["entity_type", "key"];
+
 /// A structured query plan represented as a DAG of steps.
 ///
 /// The LLM generates this JSON structure, and the QueryExecutor runs it
-/// against the GraphStore.
+/// against the GraphStore, fetching `external_scan` steps from external
+/// sources through an [`ExternalScanner`](crate::executor::ExternalScanner).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QueryPlan {
     pub steps: Vec<QueryStep>,
 }
 
+impl QueryPlan {
+    /// Check that every step references entity types, edge types and fields
+    /// that exist in `catalog` (or that the executor supports), and that
+    /// `depends_on` only names earlier steps.
+    ///
+    /// Type names are compared case-insensitively, as the executor does.
+    /// Returns every invalid reference found; an empty vec means the plan is valid.
+    pub fn validate(&self, catalog: &Catalog) -> Vec<InvalidReference> {
+        let mut invalid = Vec::new();
+        let mut seen: HashMap<&str, &StepKind> = HashMap::new();
+        for step in &self.steps {
+            for dep in &step.depends_on {
+                if !seen.contains_key(dep.as_str()) {
+                    invalid.push(InvalidReference::new(&step.id, ReferenceKind::Step, dep));
+                }
+            }
+
+            match &step.kind {
+                StepKind::Filter(filter) => {
+                    let types = catalog.entity_types.iter().map(|e| e.entity_type.as_str());
+                    if !contains_ignore_case(types, &filter.entity_type) {
+                        invalid.push(InvalidReference::new(
+                            &step.id,
+                            ReferenceKind::EntityType,
+                            &filter.entity_type,
+                        ));
+                    }
+                    if let Some(field) = &filter.field {
+                        if !FILTER_FIELDS.contains(&field.as_str()) {
+                            invalid.push(InvalidReference::new(&step.id, ReferenceKind::Field, field));
+                        }
+                    }
+                }
+                StepKind::Traversal(traversal) => {
+                    let types = catalog.edge_types.iter().map(|e| e.edge_type.as_str());
+                    if !contains_ignore_case(types, &traversal.edge_type) {
+                        invalid.push(InvalidReference::new(
+                            &step.id,
+                            ReferenceKind::EdgeType,
+                            &traversal.edge_type,
+                        ));
+                    }
+                }
+                StepKind::Aggregate(aggregate) => {
+                    if !GROUP_BY_FIELDS.contains(&aggregate.group_by.as_str()) {
+                        invalid.push(InvalidReference::new(
+                            &step.id,
+                            ReferenceKind::Field,
+                            &aggregate.group_by,
+                        ));
+                    }
+                }
+                StepKind::ExternalScan(scan) => match scan.resolve(catalog) {
+                    Some(table) => {
+                        if let Some(filter) = &scan.filter {
+                            if !has_column(table, &filter.column) {
+                                invalid.push(InvalidReference::new(&step.id, ReferenceKind::Field, &filter.column));
+                            }
+                        }
+                    }
+                    None => invalid.push(InvalidReference::new(
+                        &step.id,
+                        ReferenceKind::ExternalTable,
+                        &scan.qualified_name(),
+                    )),
+                },
+                StepKind::Join(join) => {
+                    if !FILTER_FIELDS.contains(&join.left_key.as_str()) {
+                        invalid.push(InvalidReference::new(&step.id, ReferenceKind::Field, &join.left_key));
+                    }
+                    let right = step.depends_on.get(1).and_then(|dep| seen.get(dep.as_str()));
+                    if let Some(StepKind::ExternalScan(scan)) = right {
+                        let table = scan.resolve(catalog);
+                        if table.is_some_and(|table| !has_column(table, &join.right_key)) {
+                            invalid.push(InvalidReference::new(&step.id, ReferenceKind::Field, &join.right_key));
+                        }
+                    }
+                }
+            }
+            seen.insert(&step.id, &step.kind);
+        }
+        invalid
+    }
+}
+
+fn has_column(table: &ExternalTable, column: &str) -> bool {
+    table.columns.iter().any(|c| c.name.eq_ignore_ascii_case(column))
+}
+
+fn contains_ignore_case<'a>(mut names: impl Iterator<Item = &'a str>, name: &str) -> bool {
+    names.any(|n| n.eq_ignore_ascii_case(name))
+}
+
+/// What kind of name an [`InvalidReference`] points at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferenceKind {
+    EntityType,
+    EdgeType,
+    Field,
+    Step,
+    ExternalTable,
+}
+
+impl fmt::Display for ReferenceKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::EntityType => "entity type",
+            Self::EdgeType => "edge type",
+            Self::Field => "field",
+            Self::Step => "step",
+            Self::ExternalTable => "external table",
+        })
+    }
+}
+
+/// A name in a [`QueryPlan`] step that doesn't resolve against the catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InvalidReference {
+    /// ID of the step containing the reference.
+    pub step_id: String,
+    pub kind: ReferenceKind,
+    /// The name as written in the plan.
+    pub name: String,
+}
+
+impl InvalidReference {
+    fn new(step_id: &str, kind: ReferenceKind, name: &str) -> Self {
+        Self {
+            step_id: step_id.to_string(),
+            kind,
+            name: name.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for InvalidReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "step '{}': unknown {} '{}'", self.step_id, self.kind, self.name)
+    }
+}
+
 /// A single step in a query plan. Each step has a unique ID and can
 /// depend on outputs from previous steps.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,6 +183,10 @@ pub enum StepKind {
     Traversal(TraversalStep),
     #[serde(rename = "aggregate")]
     Aggregate(AggregateStep),
+    #[serde(rename = "external_scan")]
+    ExternalScan(ExternalScanStep),
+    #[serde(rename = "join")]
+    Join(JoinStep),
 }
 
 /// Filter nodes by entity type and optional field matching.
@@ -96,6 +254,61 @@ pub enum AggregateMetric {
     Sum,
 }
 
+/// Read rows from a table of an external source, such as an Athena
+/// connection in the catalog.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalScanStep {
+    pub connection_id: String,
+    pub database: String,
+    pub table: String,
+    #[serde(default)]
+    pub filter: Option<ExternalFilter>,
+}
+
+impl ExternalScanStep {
+    /// `connection_id.database.table`, for messages.
+    pub fn qualified_name(&self) -> String {
+        format!("{}.{}.{}", self.connection_id, self.database, self.table)
+    }
+
+    /// The scanned table in `catalog`, if it has been discovered.
+    fn resolve<'a>(&self, catalog: &'a Catalog) -> Option<&'a ExternalTable> {
+        catalog
+            .external_sources
+            .iter()
+            .filter(|source| source.connection_id == self.connection_id)
+            .flat_map(|source| &source.databases)
+            .filter(|db| db.name.eq_ignore_ascii_case(&self.database))
+            .flat_map(|db| &db.tables)
+            .find(|table| table.name.eq_ignore_ascii_case(&self.table))
+    }
+}
+
+/// Row filter pushed down to the external source.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExternalFilter {
+    pub column: String,
+    pub operator: FilterOperator,
+    pub value: String,
+}
+
+/// Inner join of a node step (first `depends_on`) with an external scan
+/// (second `depends_on`) where the node's `left_key` equals the row's
+/// `right_key` column.
+///
+/// Produces one row per matching pair: the node's fields plus the row's
+/// columns. A join's rows can only be the plan's result.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JoinStep {
+    #[serde(default = "default_join_key")]
+    pub left_key: String,
+    pub right_key: String,
+}
+
+fn default_join_key() -> String {
+    "key".to_string()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -135,4 +348,116 @@ mod tests {
         assert_eq!(plan.steps[0].id, "step1");
         assert_eq!(plan.steps[1].depends_on, vec!["step1"]);
     }
+
+    fn test_catalog() -> Catalog {
+        Catalog {
+            entity_types: vec![crate::CatalogEntry {
+                entity_type: "Member".into(),
+                node_count: 10,
+                sample_keys: vec!["alice".into()],
+                fields: vec![],
+            }],
+            edge_types: vec![crate::EdgeSummary {
+                edge_type: "LoggedInFrom".into(),
+                count: 5,
+                source_types: vec!["Member".into()],
+                target_types: vec!["Device".into()],
+            }],
+            total_nodes: 10,
+            total_edges: 5,
+            external_sources: vec![crate::ExternalSource {
+                name: "Data Lake".into(),
+                kind: "athena".into(),
+                connection_id: "lake".into(),
+                databases: vec![crate::ExternalDatabase {
+                    name: "finance".into(),
+                    tables: vec![ExternalTable {
+                        name: "deposits".into(),
+                        columns: ["member_code", "total"]
+                            .into_iter()
+                            .map(|name| crate::ExternalColumn { name: name.into(), data_type: "varchar".into() })
+                            .collect(),
+                    }],
+                }],
+                last_refreshed: None,
+                refresh_ttl_secs: 0,
+            }],
+        }
+    }
+
+    #[test]
+    fn validate_accepts_known_references() {
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps": [
+                {"id": "s1", "type": "filter", "entity_type": "member", "field": "key", "operator": "equals", "value": "alice"},
+                {"id": "s2", "depends_on": ["s1"], "type": "traversal", "edge_type": "LoggedInFrom"},
+                {"id": "s3", "depends_on": ["s2"], "type": "aggregate", "group_by": "entity_type"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(plan.validate(&test_catalog()).is_empty());
+    }
+
+    #[test]
+    fn validate_reports_every_invalid_reference() {
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps": [
+                {"id": "s1", "type": "filter", "entity_type": "Customer", "field": "email"},
+                {"id": "s2", "depends_on": ["s1", "s9"], "type": "traversal", "edge_type": "PaidWith"},
+                {"id": "s3", "depends_on": ["s2"], "type": "aggregate", "group_by": "country"}
+            ]}"#,
+        )
+        .unwrap();
+
+        let invalid = plan.validate(&test_catalog());
+        let found: Vec<(&str, ReferenceKind, &str)> = invalid
+            .iter()
+            .map(|r| (r.step_id.as_str(), r.kind, r.name.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("s1", ReferenceKind::EntityType, "Customer"),
+                ("s1", ReferenceKind::Field, "email"),
+                ("s2", ReferenceKind::Step, "s9"),
+                ("s2", ReferenceKind::EdgeType, "PaidWith"),
+                ("s3", ReferenceKind::Field, "country"),
+            ]
+        );
+        assert_eq!(invalid[0].to_string(), "step 's1': unknown entity type 'Customer'");
+    }
+
+    #[test]
+    fn validate_checks_external_scan_and_join() {
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps": [
+                {"id": "members", "type": "filter", "entity_type": "Member"},
+                {"id": "deposits", "type": "external_scan", "connection_id": "lake", "database": "finance", "table": "deposits",
+                 "filter": {"column": "total", "operator": "starts_with", "value": "1"}},
+                {"id": "joined", "depends_on": ["members", "deposits"], "type": "join", "right_key": "member_code"}
+            ]}"#,
+        )
+        .unwrap();
+        assert!(plan.validate(&test_catalog()).is_empty());
+
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps": [
+                {"id": "members", "type": "filter", "entity_type": "Member"},
+                {"id": "deposits", "type": "external_scan", "connection_id": "lake", "database": "finance", "table": "deposits"},
+                {"id": "withdrawals", "type": "external_scan", "connection_id": "lake", "database": "finance", "table": "withdrawals"},
+                {"id": "joined", "depends_on": ["members", "deposits"], "type": "join", "left_key": "name", "right_key": "member"}
+            ]}"#,
+        )
+        .unwrap();
+        let found: Vec<(ReferenceKind, String)> =
+            plan.validate(&test_catalog()).into_iter().map(|r| (r.kind, r.name)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (ReferenceKind::ExternalTable, "lake.finance.withdrawals".to_string()),
+                (ReferenceKind::Field, "name".to_string()),
+                (ReferenceKind::Field, "member".to_string()),
+            ]
+        );
+    }
 }
@@ -1,4 +1,6 @@
-use crate::catalog::{ExternalDatabase, ExternalSource, ExternalTable};
+use chrono::{DateTime, Utc};
+
+use crate::catalog::{ExternalDatabase, ExternalSource, ExternalTable, DEFAULT_EXTERNAL_REFRESH_TTL_SECS};
 
 use super::error::CatalogStoreError;
 use super::CatalogStore;
@@ -11,7 +13,7 @@ impl CatalogStore {
     /// Directory structure:
     /// ```text
     /// external/{kind}-{connection_id}/
-    ///   metadata.json           <- name, kind, connection_id
+    ///   metadata.json           <- name, kind, connection_id, refresh times
     ///   {database}/
     ///     {table}.json          <- table schema (name, columns)
     /// ```
@@ -30,20 +32,22 @@ impl CatalogStore {
             "name": source.name,
             "kind": source.kind,
             "connection_id": source.connection_id,
+            "last_refreshed": source.last_refreshed,
+            "refresh_ttl_secs": source.refresh_ttl_secs,
         });
         let metadata_path = source_dir.join("metadata.json");
-        std::fs::create_dir_all(&source_dir)?;
-        std::fs::write(metadata_path, serde_json::to_string_pretty(&metadata)?)?;
+        self.files.create_dir_all(&source_dir)?;
+        self.files.write(&metadata_path, serde_json::to_string_pretty(&metadata)?)?;
 
         // Save each database's tables
         for db in &source.databases {
             let db_dir = source_dir.join(&db.name);
-            std::fs::create_dir_all(&db_dir)?;
+            self.files.create_dir_all(&db_dir)?;
 
             for table in &db.tables {
                 let table_path = db_dir.join(format!("{}.json", table.name));
                 let json = serde_json::to_string_pretty(table)?;
-                std::fs::write(table_path, json)?;
+                self.files.write(&table_path, json)?;
             }
         }
 
@@ -60,36 +64,39 @@ impl CatalogStore {
             .join("external")
             .join(format!("{}-{}", kind, connection_id));
 
-        if !source_dir.exists() {
+        if !self.files.exists(&source_dir) {
             return Ok(None);
         }
 
         // Load metadata
         let metadata_path = source_dir.join("metadata.json");
         let metadata: serde_json::Value =
-            serde_json::from_str(&std::fs::read_to_string(metadata_path)?)?;
+            serde_json::from_str(&self.files.read_to_string(&metadata_path)?)?;
 
         let name = metadata["name"].as_str().unwrap_or("").to_string();
         let kind = metadata["kind"].as_str().unwrap_or("").to_string();
         let connection_id = metadata["connection_id"].as_str().unwrap_or("").to_string();
+        let last_refreshed = serde_json::from_value(metadata["last_refreshed"].clone()).unwrap_or(None);
+        let refresh_ttl_secs = metadata["refresh_ttl_secs"]
+            .as_u64()
+            .unwrap_or(DEFAULT_EXTERNAL_REFRESH_TTL_SECS);
 
         // Load all databases
         let mut databases = Vec::new();
-        for db_entry in std::fs::read_dir(&source_dir)? {
-            let db_entry = db_entry?;
-            let db_path = db_entry.path();
-
-            if db_path.is_dir() {
-                let db_name = db_path.file_name().unwrap().to_string_lossy().to_string();
+        for db_entry in self.files.read_dir(&source_dir)? {
+            if db_entry.is_dir {
+                let db_path = source_dir.join(&db_entry.name);
+                let db_name = db_entry.name;
                 let mut tables = Vec::new();
 
                 // Load all tables in this database
-                for table_entry in std::fs::read_dir(&db_path)? {
-                    let table_entry = table_entry?;
-                    let table_path = table_entry.path();
+                for table_entry in self.files.read_dir(&db_path)? {
+                    let table_path = db_path.join(&table_entry.name);
 
-                    if table_path.extension().and_then(|s| s.to_str()) == Some("json") {
-                        let json = std::fs::read_to_string(&table_path)?;
+                    if !table_entry.is_dir
+                        && table_path.extension().and_then(|s| s.to_str()) == Some("json")
+                    {
+                        let json = self.files.read_to_string(&table_path)?;
                         let table: ExternalTable = serde_json::from_str(&json)?;
                         tables.push(table);
                     }
@@ -110,28 +117,22 @@ impl CatalogStore {
             kind,
             connection_id,
             databases,
+            last_refreshed,
+            refresh_ttl_secs,
         }))
     }
 
-    /// List all external sources on disk.
+    /// List all persisted external sources.
     pub fn list_external_sources(&self) -> Result<Vec<ExternalSource>, CatalogStoreError> {
         let external_dir = self.base_dir.join("external");
         let mut sources = Vec::new();
 
-        for entry in std::fs::read_dir(external_dir)? {
-            let entry = entry?;
-            let path = entry.path();
-
-            if path.is_dir() {
+        for entry in self.files.read_dir(&external_dir)? {
+            if entry.is_dir {
                 // Extract kind and connection_id from directory name: "{kind}-{connection_id}"
-                if let Some(dir_name) = path.file_name().and_then(|n| n.to_str()) {
-                    if let Some(dash_pos) = dir_name.find('-') {
-                        let kind = &dir_name[..dash_pos];
-                        let connection_id = &dir_name[dash_pos + 1..];
-
-                        if let Ok(Some(source)) = self.load_external_source(kind, connection_id) {
-                            sources.push(source);
-                        }
+                if let Some((kind, connection_id)) = entry.name.split_once('-') {
+                    if let Ok(Some(source)) = self.load_external_source(kind, connection_id) {
+                        sources.push(source);
                     }
                 }
             }
@@ -141,6 +142,24 @@ impl CatalogStore {
         Ok(sources)
     }
 
+    /// External sources whose schema has outlived its TTL at `now`, oldest
+    /// refresh first (never-refreshed sources lead).
+    pub fn external_sources_due_for_refresh(
+        &self,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ExternalSource>, CatalogStoreError> {
+        if !self.files.exists(&self.base_dir.join("external")) {
+            return Ok(Vec::new());
+        }
+        let mut due: Vec<ExternalSource> = self
+            .list_external_sources()?
+            .into_iter()
+            .filter(|s| s.is_due_for_refresh(now))
+            .collect();
+        due.sort_by_key(|s| s.last_refreshed);
+        Ok(due)
+    }
+
     /// Remove an external source by kind and connection ID.
     pub fn remove_external_source(
         &self,
@@ -151,8 +170,8 @@ impl CatalogStore {
             .join("external")
             .join(format!("{}-{}", kind, connection_id));
 
-        if source_dir.exists() {
-            std::fs::remove_dir_all(source_dir)?;
+        if self.files.exists(&source_dir) {
+            self.files.remove_dir_all(&source_dir)?;
         }
         Ok(())
     }
@@ -0,0 +1,125 @@
+//! File access behind [`CatalogStore`](super::CatalogStore).
+//!
+//! Mirrors the handful of `std::fs` calls the store needs so the same
+//! operations can run against an in-memory map in tests.
+
+use std::io;
+use std::path::Path;
+#[cfg(any(test, feature = "test-utils"))]
+use std::{collections::BTreeMap, path::PathBuf, sync::Mutex};
+
+pub(super) enum Files {
+    Disk,
+    /// File contents keyed by path; directories exist implicitly.
+    #[cfg(any(test, feature = "test-utils"))]
+    Memory(Mutex<BTreeMap<PathBuf, String>>),
+}
+
+/// A directory entry: file name and whether it is a directory.
+pub(super) struct DirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+impl Files {
+    pub fn create_dir_all(&self, path: &Path) -> io::Result<()> {
+        match self {
+            Files::Disk => std::fs::create_dir_all(path),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(_) => Ok(()),
+        }
+    }
+
+    pub fn exists(&self, path: &Path) -> bool {
+        match self {
+            Files::Disk => path.exists(),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(files) => files.lock().unwrap().keys().any(|key| key.starts_with(path)),
+        }
+    }
+
+    pub fn write(&self, path: &Path, contents: String) -> io::Result<()> {
+        match self {
+            Files::Disk => std::fs::write(path, contents),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(files) => {
+                files.lock().unwrap().insert(path.to_path_buf(), contents);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn read_to_string(&self, path: &Path) -> io::Result<String> {
+        match self {
+            Files::Disk => std::fs::read_to_string(path),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(files) => files
+                .lock()
+                .unwrap()
+                .get(path)
+                .cloned()
+                .ok_or_else(|| not_found(path)),
+        }
+    }
+
+    /// List the direct children of `dir`.
+    pub fn read_dir(&self, dir: &Path) -> io::Result<Vec<DirEntry>> {
+        match self {
+            Files::Disk => std::fs::read_dir(dir)?
+                .map(|entry| {
+                    let entry = entry?;
+                    Ok(DirEntry {
+                        name: entry.file_name().to_string_lossy().into_owned(),
+                        is_dir: entry.path().is_dir(),
+                    })
+                })
+                .collect(),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(files) => {
+                let mut children = BTreeMap::new();
+                for key in files.lock().unwrap().keys() {
+                    let Ok(rest) = key.strip_prefix(dir) else { continue };
+                    let mut components = rest.components();
+                    if let Some(first) = components.next() {
+                        let name = first.as_os_str().to_string_lossy().into_owned();
+                        let is_dir = components.next().is_some();
+                        *children.entry(name).or_insert(false) |= is_dir;
+                    }
+                }
+                Ok(children
+                    .into_iter()
+                    .map(|(name, is_dir)| DirEntry { name, is_dir })
+                    .collect())
+            }
+        }
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        match self {
+            Files::Disk => std::fs::remove_file(path),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(files) => files
+                .lock()
+                .unwrap()
+                .remove(path)
+                .map(drop)
+                .ok_or_else(|| not_found(path)),
+        }
+    }
+
+    pub fn remove_dir_all(&self, path: &Path) -> io::Result<()> {
+        match self {
+            Files::Disk => std::fs::remove_dir_all(path),
+            #[cfg(any(test, feature = "test-utils"))]
+            Files::Memory(files) => {
+                files.lock().unwrap().retain(|key, _| !key.starts_with(path));
+                Ok(())
+            }
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+fn not_found(path: &Path) -> io::Error {
+    io::Error::new(io::ErrorKind::NotFound, format!("{} not found", path.display()))
+}
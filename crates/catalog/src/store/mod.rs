@@ -1,5 +1,6 @@
 mod error;
 mod external;
+mod files;
 mod operations;
 
 pub use error::CatalogStoreError;
@@ -10,9 +11,13 @@ use crate::catalog::{Catalog, PartialCatalog};
 use crate::manifest::CatalogManifest;
 
 use error::segment_filename;
+use files::Files;
 
 /// Filesystem-backed catalog persistence.
 ///
+/// With the `test-utils` feature, [`CatalogStore::in_memory`] keeps the same
+/// layout in memory instead so tests never touch disk.
+///
 /// Manages the `data/catalog/` directory structure:
 /// ```text
 /// catalog/
@@ -30,6 +35,7 @@ use error::segment_filename;
 /// ```
 pub struct CatalogStore {
     base_dir: PathBuf,
+    files: Files,
 }
 
 impl CatalogStore {
@@ -39,10 +45,19 @@ impl CatalogStore {
         std::fs::create_dir_all(base_dir.join("segments"))?;
         std::fs::create_dir_all(base_dir.join("external"))?;
         std::fs::create_dir_all(base_dir.join("snapshots"))?;
-        Ok(Self { base_dir })
+        Ok(Self { base_dir, files: Files::Disk })
+    }
+
+    /// Create a store that keeps everything in memory and never touches disk.
+    #[cfg(any(test, feature = "test-utils"))]
+    pub fn in_memory() -> Self {
+        Self {
+            base_dir: PathBuf::new(),
+            files: Files::Memory(Default::default()),
+        }
     }
 
-    /// Base path for this store.
+    /// Base path for this store (empty for an in-memory store).
     pub fn base_dir(&self) -> &Path {
         &self.base_dir
     }
@@ -52,17 +67,17 @@ impl CatalogStore {
     /// Save the merged catalog as `current.json`.
     pub fn save_current(&self, catalog: &Catalog) -> Result<(), CatalogStoreError> {
         let json = serde_json::to_string_pretty(catalog)?;
-        std::fs::write(self.base_dir.join("current.json"), json)?;
+        self.files.write(&self.base_dir.join("current.json"), json)?;
         Ok(())
     }
 
     /// Load the merged catalog from `current.json`.
     pub fn load_current(&self) -> Result<Option<Catalog>, CatalogStoreError> {
         let path = self.base_dir.join("current.json");
-        if !path.exists() {
+        if !self.files.exists(&path) {
             return Ok(None);
         }
-        let json = std::fs::read_to_string(path)?;
+        let json = self.files.read_to_string(&path)?;
         let catalog = serde_json::from_str(&json)?;
         Ok(Some(catalog))
     }
@@ -81,7 +96,7 @@ impl CatalogStore {
         let filename = segment_filename(segment_id);
         let path = self.base_dir.join("segments").join(filename);
         let json = serde_json::to_string_pretty(partial)?;
-        std::fs::write(path, json)?;
+        self.files.write(&path, json)?;
         Ok(())
     }
 
@@ -92,23 +107,20 @@ impl CatalogStore {
     ) -> Result<Option<PartialCatalog>, CatalogStoreError> {
         let filename = segment_filename(segment_id);
         let path = self.base_dir.join("segments").join(filename);
-        if !path.exists() {
+        if !self.files.exists(&path) {
             return Ok(None);
         }
-        let json = std::fs::read_to_string(path)?;
+        let json = self.files.read_to_string(&path)?;
         let partial = serde_json::from_str(&json)?;
         Ok(Some(partial))
     }
 
-    /// List all partial catalog segment IDs on disk.
+    /// List all persisted partial catalog segment IDs.
     pub fn list_partials(&self) -> Result<Vec<String>, CatalogStoreError> {
         let dir = self.base_dir.join("segments");
         let mut ids = Vec::new();
-        for entry in std::fs::read_dir(dir)? {
-            let entry = entry?;
-            let name = entry.file_name();
-            let name = name.to_string_lossy();
-            if let Some(stem) = name.strip_suffix(".json") {
+        for entry in self.files.read_dir(&dir)? {
+            if let Some(stem) = entry.name.strip_suffix(".json") {
                 // Reverse the flattening: `__` back to `/`
                 ids.push(stem.replace("__", "/"));
             }
@@ -121,8 +133,8 @@ impl CatalogStore {
     pub fn remove_partial(&self, segment_id: &str) -> Result<(), CatalogStoreError> {
         let filename = segment_filename(segment_id);
         let path = self.base_dir.join("segments").join(filename);
-        if path.exists() {
-            std::fs::remove_file(path)?;
+        if self.files.exists(&path) {
+            self.files.remove_file(&path)?;
         }
         Ok(())
     }
@@ -132,17 +144,17 @@ impl CatalogStore {
     /// Save the catalog manifest.
     pub fn save_manifest(&self, manifest: &CatalogManifest) -> Result<(), CatalogStoreError> {
         let json = serde_json::to_string_pretty(manifest)?;
-        std::fs::write(self.base_dir.join("manifest.json"), json)?;
+        self.files.write(&self.base_dir.join("manifest.json"), json)?;
         Ok(())
     }
 
     /// Load the catalog manifest.
     pub fn load_manifest(&self) -> Result<Option<CatalogManifest>, CatalogStoreError> {
         let path = self.base_dir.join("manifest.json");
-        if !path.exists() {
+        if !self.files.exists(&path) {
             return Ok(None);
         }
-        let json = std::fs::read_to_string(path)?;
+        let json = self.files.read_to_string(&path)?;
         let manifest = serde_json::from_str(&json)?;
         Ok(Some(manifest))
     }
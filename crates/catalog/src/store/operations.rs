@@ -1,3 +1,4 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
 use tracing::info;
 
 use crate::catalog::{Catalog, PartialCatalog};
@@ -6,19 +7,84 @@ use crate::manifest::CatalogManifest;
 use super::error::CatalogStoreError;
 use super::CatalogStore;
 
+/// `strftime` format of snapshot names, e.g. `2026-02-16T04-16-00`.
+const SNAPSHOT_NAME_FORMAT: &str = "%Y-%m-%dT%H-%M-%S";
+
+/// When the snapshot called `name` was taken; `None` if `name` isn't a
+/// snapshot timestamp.
+fn snapshot_time(name: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(name, SNAPSHOT_NAME_FORMAT)
+        .ok()
+        .map(|t| t.and_utc())
+}
+
 impl CatalogStore {
     // ── Snapshots ───────────────────────────────────────────────
 
     /// Save a snapshot of the catalog with a timestamp-based filename.
     pub fn save_snapshot(&self, catalog: &Catalog) -> Result<String, CatalogStoreError> {
-        let ts = chrono::Utc::now().format("%Y-%m-%dT%H-%M-%S").to_string();
-        let filename = format!("{}.json", ts);
+        self.save_snapshot_at(catalog, Utc::now())
+    }
+
+    /// Save a snapshot of the catalog as it was at `at`, named after `at`
+    /// (to the second). Replaces a snapshot with the same name.
+    pub fn save_snapshot_at(
+        &self,
+        catalog: &Catalog,
+        at: DateTime<Utc>,
+    ) -> Result<String, CatalogStoreError> {
+        let filename = format!("{}.json", at.format(SNAPSHOT_NAME_FORMAT));
         let path = self.base_dir.join("snapshots").join(&filename);
         let json = serde_json::to_string_pretty(catalog)?;
-        std::fs::write(path, json)?;
+        self.files.write(&path, json)?;
         Ok(filename)
     }
 
+    /// Names of all snapshots (filenames without `.json`), oldest first.
+    pub fn list_snapshots(&self) -> Result<Vec<String>, CatalogStoreError> {
+        let mut names: Vec<String> = self
+            .files
+            .read_dir(&self.base_dir.join("snapshots"))?
+            .into_iter()
+            .filter_map(|entry| entry.name.strip_suffix(".json").map(str::to_string))
+            .filter(|name| snapshot_time(name).is_some())
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    /// Load the snapshot called `name`, with or without the `.json` suffix.
+    ///
+    /// Returns `None` if there is no such snapshot, including for names
+    /// that aren't snapshot timestamps.
+    pub fn load_snapshot(&self, name: &str) -> Result<Option<Catalog>, CatalogStoreError> {
+        let name = name.strip_suffix(".json").unwrap_or(name);
+        if snapshot_time(name).is_none() {
+            return Ok(None);
+        }
+        let path = self.base_dir.join("snapshots").join(format!("{name}.json"));
+        if !self.files.exists(&path) {
+            return Ok(None);
+        }
+        let json = self.files.read_to_string(&path)?;
+        Ok(Some(serde_json::from_str(&json)?))
+    }
+
+    /// The latest snapshot taken at or before `at`, with its name.
+    pub fn snapshot_at(
+        &self,
+        at: DateTime<Utc>,
+    ) -> Result<Option<(String, Catalog)>, CatalogStoreError> {
+        let latest = self
+            .list_snapshots()?
+            .into_iter()
+            .rfind(|name| snapshot_time(name).is_some_and(|t| t <= at));
+        match latest {
+            Some(name) => Ok(self.load_snapshot(&name)?.map(|catalog| (name, catalog))),
+            None => Ok(None),
+        }
+    }
+
     // ── High-level operations ───────────────────────────────────
 
     /// Rebuild the full catalog from all persisted partial catalogs.
@@ -94,6 +160,22 @@ impl CatalogStore {
         Ok(merged)
     }
 
+    /// Replace a segment's partial catalog and re-merge.
+    ///
+    /// Unlike [`add_segment`](Self::add_segment), the segment's previous
+    /// contribution is dropped rather than added to, so this is safe for a
+    /// segment that is already part of the catalog. Other segments'
+    /// persisted partials are reused as-is.
+    pub fn replace_segment(
+        &self,
+        segment_id: &str,
+        partial: &PartialCatalog,
+    ) -> Result<Catalog, CatalogStoreError> {
+        self.save_partial(segment_id, partial)?;
+        info!("Replaced partial for '{}', re-merging catalog...", segment_id);
+        self.rebuild_from_partials()
+    }
+
     /// Remove a segment and rebuild the catalog from remaining partials.
     ///
     /// Removal requires a full re-merge because we cannot subtract a
@@ -8,6 +8,7 @@ fn make_partial(segment_id: &str, node_count: usize, edge_count: usize) -> Parti
             entity_type: "Member".to_string(),
             node_count,
             sample_keys: vec!["alice".to_string()],
+            fields: vec![],
         }],
         edge_types: vec![EdgeSummary {
             edge_type: "LoggedInFrom".to_string(),
@@ -168,6 +169,25 @@ fn store_remove_segment() {
     assert_eq!(catalog.total_edges, 10);
 }
 
+#[test]
+fn store_replace_segment_swaps_only_its_contribution() {
+    let tmp = tempfile::tempdir().unwrap();
+    let store = CatalogStore::new(tmp.path().join("catalog")).unwrap();
+
+    store.add_segment("seg-a", &make_partial("seg-a", 10, 5)).unwrap();
+    store.add_segment("seg-b", &make_partial("seg-b", 20, 10)).unwrap();
+
+    let catalog = store.replace_segment("seg-a", &make_partial("seg-a", 12, 7)).unwrap();
+    assert_eq!(catalog.total_nodes, 32);
+    assert_eq!(catalog.total_edges, 17);
+    assert_eq!(catalog.entity_types[0].node_count, 32);
+    assert_eq!(store.load_current().unwrap().unwrap().total_nodes, 32);
+
+    assert_eq!(store.load_partial("seg-a").unwrap().unwrap().node_count, 12);
+    assert_eq!(store.load_partial("seg-b").unwrap().unwrap().node_count, 20);
+    assert_eq!(store.list_partials().unwrap(), vec!["seg-a", "seg-b"]);
+}
+
 #[test]
 fn store_segment_filename_with_slashes() {
     let tmp = tempfile::tempdir().unwrap();
@@ -182,3 +202,109 @@ fn store_segment_filename_with_slashes() {
     let loaded = store.load_partial("Login/2025-W24").unwrap().unwrap();
     assert_eq!(loaded.node_count, 10);
 }
+
+#[test]
+fn store_external_sources_due_for_refresh() {
+    use crate::catalog::ExternalSource;
+
+    let tmp = tempfile::tempdir().unwrap();
+    let store = CatalogStore::new(tmp.path().join("catalog")).unwrap();
+    let now = chrono::Utc::now();
+    assert!(store.external_sources_due_for_refresh(now).unwrap().is_empty());
+
+    let source = |id: &str, hours_ago: Option<i64>, ttl_hours: u64| ExternalSource {
+        name: id.to_string(),
+        kind: "athena".to_string(),
+        connection_id: id.to_string(),
+        databases: vec![],
+        last_refreshed: hours_ago.map(|h| now - chrono::Duration::hours(h)),
+        refresh_ttl_secs: ttl_hours * 3600,
+    };
+    store.save_external_source(&source("fresh", Some(1), 24)).unwrap();
+    store.save_external_source(&source("stale", Some(30), 24)).unwrap();
+    store.save_external_source(&source("older", Some(50), 24)).unwrap();
+    store.save_external_source(&source("never", None, 24)).unwrap();
+    store.save_external_source(&source("short-ttl", Some(2), 1)).unwrap();
+    store.save_external_source(&source("disabled", None, 0)).unwrap();
+
+    let due: Vec<String> = store
+        .external_sources_due_for_refresh(now)
+        .unwrap()
+        .into_iter()
+        .map(|s| s.connection_id)
+        .collect();
+    assert_eq!(due, vec!["never", "older", "stale", "short-ttl"]);
+
+    // Timestamps and TTLs survive the round trip through metadata.json.
+    let loaded = store.load_external_source("athena", "short-ttl").unwrap().unwrap();
+    assert_eq!(loaded.refresh_ttl_secs, 3600);
+    assert_eq!(loaded.last_refreshed, Some(now - chrono::Duration::hours(2)));
+}
+
+#[test]
+fn store_in_memory_round_trip() {
+    use crate::catalog::{ExternalColumn, ExternalDatabase, ExternalSource, ExternalTable};
+
+    let store = CatalogStore::in_memory();
+    assert!(store.load_current().unwrap().is_none());
+    assert!(store.list_partials().unwrap().is_empty());
+    assert!(store.list_external_sources().unwrap().is_empty());
+
+    store.add_segment("Login/2025-W24", &make_partial("Login/2025-W24", 10, 4)).unwrap();
+    store.add_segment("seg-2", &make_partial("seg-2", 5, 1)).unwrap();
+    assert_eq!(store.list_partials().unwrap(), vec!["Login/2025-W24", "seg-2"]);
+    assert_eq!(store.load_manifest().unwrap().unwrap().segment_ids.len(), 2);
+
+    let cat = store.remove_segment("seg-2").unwrap();
+    assert_eq!(cat.total_nodes, 10);
+    assert_eq!(store.load_current().unwrap().unwrap().total_edges, 4);
+
+    let source = ExternalSource {
+        name: "Prod lake".to_string(),
+        kind: "athena".to_string(),
+        connection_id: "prod-lake".to_string(),
+        databases: vec![ExternalDatabase {
+            name: "analytics".to_string(),
+            tables: vec![ExternalTable {
+                name: "events".to_string(),
+                columns: vec![ExternalColumn {
+                    name: "id".to_string(),
+                    data_type: "string".to_string(),
+                }],
+            }],
+        }],
+        last_refreshed: None,
+        refresh_ttl_secs: 0,
+    };
+    store.save_external_source(&source).unwrap();
+    let sources = store.list_external_sources().unwrap();
+    assert_eq!(sources.len(), 1);
+    assert_eq!(sources[0].connection_id, "prod-lake");
+    assert_eq!(sources[0].databases[0].tables[0].columns[0].name, "id");
+
+    store.remove_external_source("athena", "prod-lake").unwrap();
+    assert!(store.load_external_source("athena", "prod-lake").unwrap().is_none());
+}
+
+#[test]
+fn store_snapshot_at_picks_latest_not_after() {
+    use chrono::TimeZone;
+
+    let store = CatalogStore::in_memory();
+    let monday = chrono::Utc.with_ymd_and_hms(2026, 2, 16, 4, 16, 0).unwrap();
+    let wednesday = chrono::Utc.with_ymd_and_hms(2026, 2, 18, 9, 0, 0).unwrap();
+    store.save_snapshot_at(&Catalog::from_partials(&[make_partial("seg-1", 10, 5)]), monday).unwrap();
+    store.save_snapshot_at(&Catalog::from_partials(&[make_partial("seg-1", 25, 9)]), wednesday).unwrap();
+
+    assert_eq!(store.list_snapshots().unwrap(), vec!["2026-02-16T04-16-00", "2026-02-18T09-00-00"]);
+
+    let tuesday = chrono::Utc.with_ymd_and_hms(2026, 2, 17, 12, 0, 0).unwrap();
+    let (name, catalog) = store.snapshot_at(tuesday).unwrap().unwrap();
+    assert_eq!(name, "2026-02-16T04-16-00");
+    assert_eq!(catalog.total_nodes, 10);
+    assert_eq!(store.snapshot_at(wednesday).unwrap().unwrap().1.total_nodes, 25);
+    assert!(store.snapshot_at(monday - chrono::Duration::seconds(1)).unwrap().is_none());
+
+    assert!(store.load_snapshot("2026-02-18T09-00-00.json").unwrap().is_some());
+    assert!(store.load_snapshot("../current").unwrap().is_none());
+}
@@ -12,9 +12,11 @@ use futures::StreamExt;
 use std::sync::Arc;
 use tracing::{error, info};
 
-use stupid_tool_runtime::permission::{PermissionLevel, PermissionPolicy, PolicyChecker};
+use stupid_tool_runtime::permission::{
+    PermissionLevel, PermissionPolicy, PolicyChecker, DEFAULT_APPROVAL_TIMEOUT,
+};
 use stupid_tool_runtime::tool::{EchoTool, ToolContext};
-use stupid_tool_runtime::{AgenticLoop, Conversation, PermissionChecker, ToolRegistry};
+use stupid_tool_runtime::{AgenticLoop, PermissionChecker, ToolRegistry};
 
 use crate::cli::CliArgs;
 use crate::config::CliConfig;
@@ -192,7 +194,9 @@ async fn run_local(args: &CliArgs, config: &CliConfig, terminal: &Terminal) -> R
         };
         policy.rules.insert(tool_name.clone(), level);
     }
-    let permission_checker: Arc<dyn PermissionChecker> = Arc::new(PolicyChecker::new(policy));
+    let permission_checker: Arc<dyn PermissionChecker> = Arc::new(
+        PolicyChecker::new(policy).with_approver(Arc::new(terminal.approver()), DEFAULT_APPROVAL_TIMEOUT),
+    );
 
     // Create agentic loop
     let agentic_loop = AgenticLoop::new(provider, Arc::new(registry), permission_checker)
@@ -218,7 +222,7 @@ async fn run_local(args: &CliArgs, config: &CliConfig, terminal: &Terminal) -> R
     };
 
     // Build conversation from session state
-    let mut conversation = Conversation::new(config.max_context_tokens);
+    let mut conversation = agentic_loop.new_conversation(config.max_context_tokens);
     let system_prompt = if let Some(ref prompt) = session.system_prompt {
         prompt.clone()
     } else {
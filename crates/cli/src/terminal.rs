@@ -6,7 +6,10 @@ use crossterm::{
 use std::io::{self, Write};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
-use stupid_tool_runtime::stream::StreamEvent;
+use tokio::sync::mpsc;
+use stupid_tool_runtime::permission::{Approver, PermissionDecision};
+use stupid_tool_runtime::stream::{StreamEvent, ToolCallStage};
+use stupid_tool_runtime::ToolCall;
 use tracing::debug;
 
 /// Color scheme for terminal output.
@@ -22,10 +25,58 @@ impl Colors {
     const HEADER: Color = Color::Magenta;
 }
 
+/// Lines typed on stdin, read by a single background thread.
+///
+/// Every prompt takes its answer from here instead of reading stdin itself,
+/// so a prompt that stops waiting (an approval that timed out) leaves no
+/// read behind to swallow the next line. Lines typed while nothing was
+/// prompting are discarded when the next prompt starts.
+#[derive(Clone)]
+pub struct StdinLines(Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<io::Result<String>>>>);
+
+impl StdinLines {
+    fn spawn() -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        std::thread::spawn(move || loop {
+            let mut line = String::new();
+            match io::stdin().read_line(&mut line) {
+                Ok(0) => break,
+                Ok(_) => {
+                    if tx.send(Ok(line)).is_err() {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    let _ = tx.send(Err(e));
+                    break;
+                }
+            }
+        });
+        Self(Arc::new(tokio::sync::Mutex::new(rx)))
+    }
+
+    /// The next line typed after this call; `None` at end of input.
+    pub async fn next(&self) -> Option<io::Result<String>> {
+        let mut lines = self.0.lock().await;
+        while lines.try_recv().is_ok() {}
+        lines.recv().await
+    }
+
+    /// [`next`](Self::next) for synchronous callers on the runtime.
+    fn next_blocking(&self) -> Option<io::Result<String>> {
+        tokio::task::block_in_place(|| {
+            let mut lines = self.0.blocking_lock();
+            while lines.try_recv().is_ok() {}
+            lines.blocking_recv()
+        })
+    }
+}
+
 /// Manages terminal I/O for the interactive REPL.
 pub struct Terminal {
     /// Flag set when Ctrl+C is pressed to cancel current operation.
     cancelled: Arc<AtomicBool>,
+    lines: StdinLines,
 }
 
 impl Terminal {
@@ -33,9 +84,15 @@ impl Terminal {
     pub fn new() -> Self {
         Self {
             cancelled: Arc::new(AtomicBool::new(false)),
+            lines: StdinLines::spawn(),
         }
     }
 
+    /// An approver prompting on this terminal.
+    pub fn approver(&self) -> TerminalApprover {
+        TerminalApprover { lines: self.lines.clone() }
+    }
+
     /// Get a clone of the cancellation flag for async tasks.
     pub fn cancellation_token(&self) -> Arc<AtomicBool> {
         self.cancelled.clone()
@@ -83,9 +140,10 @@ impl Terminal {
         )?;
         stdout.flush()?;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let trimmed = input.trim().to_string();
+        let Some(input) = self.lines.next_blocking() else {
+            return Ok(None);
+        };
+        let trimmed = input?.trim().to_string();
 
         if trimmed.is_empty() {
             return Ok(Some(String::new()));
@@ -111,28 +169,16 @@ impl Terminal {
                 )?;
                 stdout.flush()?;
             }
-            StreamEvent::ToolCallStart { id: _, name } => {
-                execute!(
-                    stdout,
-                    Print("\n"),
-                    SetForegroundColor(Colors::TOOL_CALL),
-                    Print(format!("[tool: {}] ", name)),
-                    ResetColor,
-                )?;
-                stdout.flush()?;
+            StreamEvent::ToolCallStart { id, name } => {
+                // Shown once the loop assigns a call ID (ToolCallStarted)
+                debug!(%id, %name, "Tool call start");
             }
             StreamEvent::ToolCallDelta { arguments_delta, .. } => {
-                // Accumulate silently; the full args are shown at ToolCallEnd
+                // Accumulate silently; the full args are ready at ToolCallEnd
                 debug!(delta = %arguments_delta, "Tool call argument delta");
             }
             StreamEvent::ToolCallEnd { id } => {
-                execute!(
-                    stdout,
-                    SetForegroundColor(Colors::DIM),
-                    Print(format!("[call {}...done]\n", &id[..id.len().min(8)])),
-                    ResetColor,
-                )?;
-                stdout.flush()?;
+                debug!(%id, "Tool call end");
             }
             StreamEvent::MessageEnd { stop_reason } => {
                 debug!(?stop_reason, "Message ended");
@@ -149,22 +195,72 @@ impl Terminal {
                 )?;
                 stdout.flush()?;
             }
-            StreamEvent::ToolExecutionStart { name, .. } => {
+            StreamEvent::ToolCallStarted { call_id, name, .. } => {
                 execute!(
                     stdout,
+                    Print("\n"),
                     SetForegroundColor(Colors::TOOL_CALL),
-                    Print(format!("[executing: {}] ", name)),
+                    Print(format!("[tool: {}] ", name)),
+                    SetForegroundColor(Colors::DIM),
+                    Print(format!("({}) ", call_id)),
                     ResetColor,
                 )?;
                 stdout.flush()?;
             }
-            StreamEvent::ToolExecutionResult { content, is_error, .. } => {
+            StreamEvent::ToolCallProgress { call_id, stage } => match stage {
+                ToolCallStage::InputReady { .. } => {
+                    debug!(%call_id, "Tool call input ready");
+                }
+                ToolCallStage::AliasDeprecated { alias, canonical } => {
+                    execute!(
+                        stdout,
+                        SetForegroundColor(Colors::DIM),
+                        Print(format!("[warning: '{}' is deprecated, use '{}'] ", alias, canonical)),
+                        ResetColor,
+                    )?;
+                    stdout.flush()?;
+                }
+                ToolCallStage::AwaitingApproval => {
+                    debug!(%call_id, "Tool call awaited approval");
+                }
+                ToolCallStage::Executing => {
+                    execute!(
+                        stdout,
+                        SetForegroundColor(Colors::TOOL_CALL),
+                        Print(format!("[executing {}] ", call_id)),
+                        ResetColor,
+                    )?;
+                    stdout.flush()?;
+                }
+                ToolCallStage::CacheHit { age_ms } => {
+                    execute!(
+                        stdout,
+                        SetForegroundColor(Colors::DIM),
+                        Print(format!("[cached {}, {}ms old] ", call_id, age_ms)),
+                        ResetColor,
+                    )?;
+                    stdout.flush()?;
+                }
+            },
+            StreamEvent::ToolCallCompleted { content, is_error, duration_ms, .. } => {
                 let color = if *is_error { Colors::ERROR } else { Colors::TOOL_RESULT };
                 let label = if *is_error { "error" } else { "result" };
                 execute!(
                     stdout,
                     SetForegroundColor(color),
-                    Print(format!("[{}: {}]\n", label, content)),
+                    Print(format!("[{} in {}ms: {}]\n", label, duration_ms, content)),
+                    ResetColor,
+                )?;
+                stdout.flush()?;
+            }
+            StreamEvent::Usage { input_tokens, output_tokens } => {
+                debug!(input_tokens, output_tokens, "Token usage");
+            }
+            StreamEvent::BudgetExceeded { usage } => {
+                execute!(
+                    stdout,
+                    SetForegroundColor(Colors::ERROR),
+                    Print(format!("[budget exceeded after {} tokens]\n", usage.total())),
                     ResetColor,
                 )?;
                 stdout.flush()?;
@@ -260,8 +356,7 @@ impl Terminal {
         )?;
         stdout.flush()?;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+        let input = self.lines.next_blocking().transpose()?.unwrap_or_default();
         let trimmed = input.trim().to_lowercase();
 
         Ok(trimmed.is_empty() || trimmed == "y" || trimmed == "yes")
@@ -361,6 +456,41 @@ impl Drop for SpinnerHandle {
     }
 }
 
+/// Asks on the terminal whether to run a tool call that needs confirmation.
+///
+/// Created with [`Terminal::approver`]. An answer typed after the checker's
+/// approval timeout is discarded.
+pub struct TerminalApprover {
+    lines: StdinLines,
+}
+
+#[async_trait::async_trait]
+impl Approver for TerminalApprover {
+    async fn request_approval(&self, call_id: &str, call: &ToolCall) -> PermissionDecision {
+        let mut stdout = io::stdout();
+        let prompt = execute!(
+            stdout,
+            Print("\n"),
+            SetForegroundColor(Colors::TOOL_CALL),
+            Print(format!("Allow {} ({}) with {}? [y/N] ", call.name, call_id, call.input)),
+            ResetColor,
+        )
+        .and_then(|_| stdout.flush());
+        if let Err(e) = prompt {
+            return PermissionDecision::Denied(format!("Confirmation prompt failed: {}", e));
+        }
+
+        match self.lines.next().await {
+            Some(Ok(answer)) if matches!(answer.trim().to_lowercase().as_str(), "y" | "yes") => {
+                PermissionDecision::Approved
+            }
+            Some(Ok(_)) => PermissionDecision::Denied("Declined at the terminal".to_string()),
+            Some(Err(e)) => PermissionDecision::Denied(format!("Failed to read confirmation: {}", e)),
+            None => PermissionDecision::Denied("Input closed before confirmation".to_string()),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -382,4 +512,34 @@ mod tests {
         // Note: token still holds true, but the Terminal's own flag is reset
         assert!(!term.cancelled.load(Ordering::SeqCst));
     }
+
+    #[tokio::test]
+    async fn test_approver_only_takes_lines_typed_while_prompting() {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let lines = StdinLines(Arc::new(tokio::sync::Mutex::new(rx)));
+        let approver = TerminalApprover { lines: lines.clone() };
+        let call = ToolCall {
+            id: "toolu_1".to_string(),
+            name: "bash_execute".to_string(),
+            input: serde_json::json!({"command": "make deploy"}),
+        };
+
+        // Typed before the prompt, so not an answer.
+        tx.send(Ok("y\n".to_string())).unwrap();
+        let mut decision = std::pin::pin!(approver.request_approval("call_1", &call));
+        assert!(futures::poll!(&mut decision).is_pending());
+        tx.send(Ok("n\n".to_string())).unwrap();
+        assert_eq!(decision.await, PermissionDecision::Denied("Declined at the terminal".to_string()));
+
+        // A prompt that times out leaves no read behind to take the next line.
+        let timed_out = tokio::time::timeout(
+            std::time::Duration::from_millis(10),
+            approver.request_approval("call_2", &call),
+        );
+        assert!(timed_out.await.is_err());
+        let mut next = std::pin::pin!(lines.next());
+        assert!(futures::poll!(&mut next).is_pending());
+        tx.send(Ok("show tables\n".to_string())).unwrap();
+        assert_eq!(next.await.unwrap().unwrap(), "show tables\n");
+    }
 }
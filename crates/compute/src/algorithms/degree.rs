@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use serde::Serialize;
-use stupid_core::NodeId;
+use stupid_core::{EntityType, NodeId};
 use stupid_graph::GraphStore;
 
 /// Degree information for a single node.
@@ -32,10 +32,75 @@ pub fn degree_centrality(graph: &GraphStore) -> HashMap<NodeId, DegreeInfo> {
         .collect()
 }
 
+/// A node whose total degree is unusual for its entity type.
+#[derive(Debug, Clone, Serialize)]
+pub struct DegreeAnomaly {
+    pub node_id: NodeId,
+    pub entity_type: EntityType,
+    pub total: usize,
+    /// Mean total degree of nodes of the same entity type.
+    pub type_mean: f64,
+    /// Population standard deviation of those degrees.
+    pub type_std_dev: f64,
+    /// Signed distance from `type_mean` in standard deviations.
+    pub z_score: f64,
+}
+
+/// Nodes whose total degree is more than `threshold` standard deviations
+/// from the mean degree of their entity type, sorted by `|z_score|`
+/// descending.
+///
+/// Distributions are per type because normal degrees differ by orders of
+/// magnitude between types: a platform node linked to every member is
+/// expected, a device linked to thousands is not. Types whose nodes all
+/// share the same degree have no spread and yield no anomalies.
+pub fn degree_anomalies(
+    graph: &GraphStore,
+    degrees: &HashMap<NodeId, DegreeInfo>,
+    threshold: f64,
+) -> Vec<DegreeAnomaly> {
+    let mut by_type: HashMap<EntityType, Vec<(NodeId, usize)>> = HashMap::new();
+    for (node_id, info) in degrees {
+        if let Some(node) = graph.nodes.get(node_id) {
+            by_type.entry(node.entity_type).or_default().push((*node_id, info.total));
+        }
+    }
+
+    let mut anomalies = Vec::new();
+    for (entity_type, nodes) in by_type {
+        let n = nodes.len() as f64;
+        let mean = nodes.iter().map(|&(_, total)| total as f64).sum::<f64>() / n;
+        let variance = nodes
+            .iter()
+            .map(|&(_, total)| (total as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+        let std_dev = variance.sqrt();
+        if std_dev == 0.0 {
+            continue;
+        }
+        for (node_id, total) in nodes {
+            let z_score = (total as f64 - mean) / std_dev;
+            if z_score.abs() > threshold {
+                anomalies.push(DegreeAnomaly {
+                    node_id,
+                    entity_type,
+                    total,
+                    type_mean: mean,
+                    type_std_dev: std_dev,
+                    z_score,
+                });
+            }
+        }
+    }
+    anomalies.sort_by(|a, b| b.z_score.abs().total_cmp(&a.z_score.abs()));
+    anomalies
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use stupid_core::{EdgeType, EntityType};
+    use stupid_core::EdgeType;
 
     #[test]
     fn degree_basic() {
@@ -57,4 +122,63 @@ mod tests {
         assert_eq!(deg[&b].in_deg, 1);
         assert_eq!(deg[&c].in_deg, 1);
     }
+
+    /// 20 members, each on its own device, plus one device shared by all
+    /// of them. Platforms are few and heavily connected by nature.
+    /// Returns the shared device and the busiest platform.
+    fn shared_device_graph() -> (GraphStore, NodeId, NodeId) {
+        let mut g = GraphStore::new();
+        let seg = "test".to_string();
+
+        let shared = g.upsert_node(EntityType::Device, "device:shared", &seg);
+        let web = g.upsert_node(EntityType::Platform, "web", &seg);
+        let mobile = g.upsert_node(EntityType::Platform, "mobile", &seg);
+        for i in 0..20 {
+            let member = g.upsert_node(EntityType::Member, &format!("m{i}"), &seg);
+            let device = g.upsert_node(EntityType::Device, &format!("device:{i}"), &seg);
+            g.add_edge(member, device, EdgeType::LoggedInFrom, &seg);
+            g.add_edge(member, shared, EdgeType::LoggedInFrom, &seg);
+            let platform = if i == 0 { mobile } else { web };
+            g.add_edge(member, platform, EdgeType::PlaysOnPlatform, &seg);
+        }
+        // Members without a known device.
+        for i in 20..25 {
+            let member = g.upsert_node(EntityType::Member, &format!("m{i}"), &seg);
+            g.add_edge(member, web, EdgeType::PlaysOnPlatform, &seg);
+        }
+        (g, shared, web)
+    }
+
+    #[test]
+    fn degree_anomalies_flags_outlier_within_type() {
+        let (g, shared, web) = shared_device_graph();
+        let deg = degree_centrality(&g);
+
+        let anomalies = degree_anomalies(&g, &deg, 3.0);
+
+        assert_eq!(anomalies.len(), 1);
+        let a = &anomalies[0];
+        assert_eq!(a.node_id, shared);
+        assert_eq!(a.entity_type, EntityType::Device);
+        assert_eq!(a.total, 20);
+        assert!((a.type_mean - 40.0 / 21.0).abs() < 1e-9);
+        assert!(a.z_score > 4.0, "z = {}", a.z_score);
+        // The web platform has the highest degree in the graph but is
+        // ordinary among platforms.
+        assert!(deg[&web].total > deg[&shared].total);
+    }
+
+    #[test]
+    fn degree_anomalies_skips_uniform_types() {
+        let mut g = GraphStore::new();
+        let seg = "test".to_string();
+        for i in 0..5 {
+            let member = g.upsert_node(EntityType::Member, &format!("m{i}"), &seg);
+            let device = g.upsert_node(EntityType::Device, &format!("d{i}"), &seg);
+            g.add_edge(member, device, EdgeType::LoggedInFrom, &seg);
+        }
+        let deg = degree_centrality(&g);
+
+        assert!(degree_anomalies(&g, &deg, 0.0).is_empty());
+    }
 }
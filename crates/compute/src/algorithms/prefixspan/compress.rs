@@ -1,4 +1,4 @@
-use stupid_core::{Document, FieldValue};
+use stupid_core::Document;
 
 use super::types::EventTypeCompressed;
 
@@ -10,9 +10,7 @@ use super::types::EventTypeCompressed;
 /// - API Error -> "E:auth" (with error category) or "E"
 /// - Other -> first 3 chars
 pub fn compress_event(doc: &Document) -> EventTypeCompressed {
-    let get = |name: &str| -> Option<&str> {
-        doc.fields.get(name).and_then(FieldValue::as_str).filter(|s| !s.is_empty())
-    };
+    let get = |name: &str| doc.field_str(name);
 
     let code = match doc.event_type.as_str() {
         "Login" => "L".to_string(),
@@ -63,9 +61,7 @@ pub fn compress_event_with_config(
     doc: &Document,
     config: &stupid_rules::feature_config::CompiledFeatureConfig,
 ) -> EventTypeCompressed {
-    let get = |name: &str| -> Option<&str> {
-        doc.fields.get(name).and_then(FieldValue::as_str).filter(|s| !s.is_empty())
-    };
+    let get = |name: &str| doc.field_str(name);
 
     if let Some(rule) = config.event_compression.get(doc.event_type.as_str()) {
         let code = if let Some(ref field) = rule.subtype_field {
@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use chrono::{DateTime, Utc};
 use tracing::debug;
 
-use stupid_core::{Document, FieldValue};
+use stupid_core::Document;
 
 use super::classify::classify_pattern;
 use super::compress::compress_event;
@@ -17,9 +17,8 @@ pub fn build_sequences(docs: &[Document]) -> HashMap<String, Vec<(DateTime<Utc>,
     let mut sequences: HashMap<String, Vec<(DateTime<Utc>, EventTypeCompressed)>> = HashMap::new();
 
     for doc in docs {
-        let member_code = match doc.fields.get("memberCode").and_then(FieldValue::as_str) {
-            Some(code) if !code.is_empty() => code,
-            _ => continue,
+        let Some(member_code) = doc.field_str("memberCode") else {
+            continue;
         };
 
         let compressed = compress_event(doc);
@@ -98,6 +98,38 @@ impl StreamingKMeans {
         self.counts.clone()
     }
 
+    /// Number of clusters.
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    /// Replace centroids and assignments with the result of a batch run,
+    /// discarding the drift accumulated by online updates.
+    ///
+    /// Counts restart at each cluster's batch size, so later online updates
+    /// move the centroids at the same rate as before the reseed.
+    ///
+    /// # Panics
+    /// Panics if there are more than `k` centroids or any has the wrong length.
+    pub fn reseed(&mut self, centroids: Vec<Vec<f64>>, assignments: HashMap<NodeId, ClusterId>) {
+        assert!(centroids.len() <= self.k, "reseed: more than k centroids");
+        assert!(
+            centroids.iter().all(|c| c.len() == self.dim),
+            "reseed: centroid length mismatch"
+        );
+
+        let mut counts = vec![0; centroids.len()];
+        for &cluster in assignments.values() {
+            if let Some(count) = counts.get_mut(cluster as usize) {
+                *count += 1;
+            }
+        }
+
+        self.centroids = centroids;
+        self.counts = counts;
+        self.assignments = assignments;
+    }
+
     /// Find the index of the nearest centroid using squared Euclidean distance.
     /// Avoids the sqrt since we only need the argmin.
     fn nearest_centroid(&self, point: &[f64]) -> usize {
@@ -233,4 +265,23 @@ mod tests {
         assert!((c[1] - 2.0).abs() < 1e-10);
         assert!((c[2] - 3.0).abs() < 1e-10);
     }
+
+    #[test]
+    fn reseed_replaces_centroids_and_counts() {
+        let mut km = StreamingKMeans::new(2, 1);
+        km.update(node(1), vec![0.0]);
+        km.update(node(2), vec![10.0]);
+        km.update(node(3), vec![4.0]);
+
+        let assignments = HashMap::from([(node(1), 0), (node(2), 1), (node(3), 1)]);
+        km.reseed(vec![vec![0.0], vec![7.0]], assignments);
+
+        assert_eq!(km.centroids(), &[vec![0.0], vec![7.0]]);
+        assert_eq!(km.cluster_counts(), vec![1, 2]);
+        assert_eq!(km.get_cluster(&node(3)), Some(1));
+
+        // Online updates continue from the batch counts.
+        km.update(node(4), vec![10.0]);
+        assert!((km.centroids()[1][0] - 8.0).abs() < 1e-10);
+    }
 }
@@ -3,17 +3,20 @@ pub mod engine;
 pub mod pipeline;
 pub mod scheduler;
 
-pub use algorithms::degree::DegreeInfo;
+pub use algorithms::degree::{DegreeAnomaly, DegreeInfo};
 pub use algorithms::prefixspan::{
     self, EventTypeCompressed, PatternCategory, PrefixSpanConfig,
     TemporalPattern as PrefixSpanPattern,
 };
 pub use engine::ComputeEngine;
-pub use pipeline::cooccurrence::CooccurrenceMatrix;
+pub use pipeline::cooccurrence::{Association, CooccurrenceMatrix};
 pub use pipeline::trend::{self as trend_detection, Severity, Trend as TrendResult, TrendDetector, TrendDirection};
-pub use pipeline::{Pipeline, metrics::PipelineMetrics};
+pub use pipeline::{Pipeline, anomaly::AnomalyThresholds, metrics::PipelineMetrics};
 pub use scheduler::{
-    AnomalyDetectionTask, ComputeError, ComputeResult, ComputeTask, KnowledgeState, LoadLevel,
-    Priority, Scheduler, SchedulerConfig, SchedulerMetrics, SharedKnowledgeState,
+    AnomalyDetectionTask, ComputeError, ComputeResult, ComputeTask, KnowledgeSnapshot,
+    KnowledgeState, LoadLevel, Priority, Scheduler, SchedulerConfig, SchedulerMetrics,
+    SharedKnowledgeState, SharedSnapshotCell, SnapshotCell,
+};
+pub use scheduler::types::{
+    AnomalyClassification, AnomalyExplanation, AnomalyResult, FeatureContribution,
 };
-pub use scheduler::types::{AnomalyClassification, AnomalyResult};
@@ -0,0 +1,101 @@
+//! Per-member anomaly explanations.
+//!
+//! Names the detector signal that contributed most to a composite score and
+//! the feature dimensions that deviate furthest from the member's baseline,
+//! so a result reads as "login_count is 8σ above their cluster" rather than
+//! a bare number.
+
+use stupid_rules::templates::FEATURE_NAMES;
+
+use crate::scheduler::types::FeatureContribution;
+
+/// Number of feature dimensions listed in an explanation.
+pub const TOP_FEATURE_COUNT: usize = 3;
+
+/// Name of the signal with the largest weighted contribution.
+///
+/// `weighted` holds (detector_name, raw_score × weight). Returns `None`
+/// when no signal contributes anything.
+pub fn dominant_signal(weighted: &[(&str, f64)]) -> Option<String> {
+    weighted
+        .iter()
+        .filter(|(_, contribution)| *contribution > 0.0)
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(name, _)| (*name).to_string())
+}
+
+/// The `top` feature dimensions with the largest |z-score| against
+/// `baseline`, largest first.
+///
+/// Dimensions with no variance or no deviation are skipped, matching
+/// [`compute_anomaly_score`](super::compute_anomaly_score).
+pub fn top_feature_deviations(
+    features: &[f64],
+    baseline: &[f64],
+    std_dev: &[f64],
+    top: usize,
+) -> Vec<FeatureContribution> {
+    let dim = features.len().min(baseline.len()).min(std_dev.len());
+
+    let mut contributions: Vec<FeatureContribution> = (0..dim)
+        .filter(|&i| std_dev[i] > f64::EPSILON)
+        .map(|i| FeatureContribution {
+            feature: feature_name(i),
+            value: features[i],
+            baseline: baseline[i],
+            z_score: (features[i] - baseline[i]) / std_dev[i],
+        })
+        .filter(|c| c.z_score != 0.0)
+        .collect();
+
+    contributions.sort_by(|a, b| b.z_score.abs().total_cmp(&a.z_score.abs()));
+    contributions.truncate(top);
+    contributions
+}
+
+fn feature_name(index: usize) -> String {
+    FEATURE_NAMES
+        .get(index)
+        .map(|name| (*name).to_string())
+        .unwrap_or_else(|| format!("feature_{}", index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dominant_signal_is_largest_weighted_contribution() {
+        let weighted = [
+            ("statistical", 0.18),
+            ("dbscan_noise", 0.3),
+            ("behavioral", 0.06),
+            ("graph", 0.0),
+        ];
+        assert_eq!(dominant_signal(&weighted).as_deref(), Some("dbscan_noise"));
+        assert_eq!(dominant_signal(&[("statistical", 0.0), ("graph", 0.0)]), None);
+    }
+
+    #[test]
+    fn top_features_ranked_by_absolute_z() {
+        let features = [10.0, 2.0, -1.0, 5.0];
+        let baseline = [2.0, 2.0, 3.0, 5.0];
+        let std_dev = [1.0, 1.0, 2.0, 0.0];
+
+        let top = top_feature_deviations(&features, &baseline, &std_dev, 3);
+        // Dim 1 has no deviation and dim 3 no variance.
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0].feature, "login_count");
+        assert!((top[0].z_score - 8.0).abs() < 1e-10);
+        assert_eq!(top[1].feature, "unique_games");
+        assert!((top[1].z_score + 2.0).abs() < 1e-10);
+    }
+
+    #[test]
+    fn unnamed_dimensions_get_index_names() {
+        let mut baseline = vec![0.0; 12];
+        baseline[11] = 4.0;
+        let top = top_feature_deviations(&[0.0; 12], &baseline, &[1.0; 12], 1);
+        assert_eq!(top[0].feature, "feature_11");
+    }
+}
@@ -7,14 +7,21 @@
 //! Sub-modules:
 //! - [`signals`] — individual signal scorer functions
 //! - [`population`] — population-level statistics (mean, variance, std-dev)
+//! - [`explanation`] — dominant signal and top deviating features per member
 
+pub mod explanation;
 pub mod population;
 pub mod signals;
 
+use std::collections::HashMap;
+
 use stupid_core::NodeId;
 
 use crate::algorithms::dbscan::DbscanResult;
-use crate::scheduler::types::{AnomalyClassification, AnomalyResult, AnomalyScore, ClusterId};
+use crate::scheduler::types::{
+    AnomalyClassification, AnomalyExplanation, AnomalyResult, AnomalyScore, ClusterId,
+    InsightSeverity,
+};
 use super::features::MemberFeatures;
 
 // ── Re-exports ────────────────────────────────────────────────────────
@@ -24,10 +31,57 @@ pub use signals::{
     statistical_outlier_score,
 };
 pub use population::compute_population_stats;
+pub use explanation::{dominant_signal, top_feature_deviations, TOP_FEATURE_COUNT};
 
 /// Default threshold above which a member is considered anomalous.
 const DEFAULT_ANOMALY_THRESHOLD: f64 = 2.0;
 
+/// Cluster z-score cutoffs for warm-compute anomaly scoring.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnomalyThresholds {
+    /// Z-score above which a member is anomalous.
+    pub threshold: f64,
+    /// Z-score above which an anomalous member's insight is a Warning
+    /// rather than Info.
+    pub warning: f64,
+    /// Z-score above which it is Critical.
+    pub critical: f64,
+}
+
+impl Default for AnomalyThresholds {
+    fn default() -> Self {
+        Self {
+            threshold: DEFAULT_ANOMALY_THRESHOLD,
+            warning: 3.0,
+            critical: 4.0,
+        }
+    }
+}
+
+impl AnomalyThresholds {
+    /// The scoring config's `default_anomaly_threshold` and `z_score_severity`.
+    pub fn from_scoring_config(config: &stupid_rules::scoring_config::CompiledScoringConfig) -> Self {
+        Self {
+            threshold: config.default_anomaly_threshold,
+            warning: config.z_score_severity.warning,
+            critical: config.z_score_severity.critical,
+        }
+    }
+
+    /// Insight severity for `score`, `None` if it isn't anomalous.
+    pub fn severity(&self, score: &AnomalyScore) -> Option<InsightSeverity> {
+        if !score.is_anomalous {
+            None
+        } else if score.score > self.critical {
+            Some(InsightSeverity::Critical)
+        } else if score.score > self.warning {
+            Some(InsightSeverity::Warning)
+        } else {
+            Some(InsightSeverity::Info)
+        }
+    }
+}
+
 /// Trait abstracting cluster assignment and centroid access.
 ///
 /// Implemented by `StreamingKMeans` and test doubles. Decouples anomaly
@@ -120,47 +174,23 @@ pub fn compute_anomaly_score_with_threshold(
 
 /// Score all tracked members against their assigned cluster centroids.
 ///
-/// For each member with a cluster assignment, computes the z-score anomaly.
-/// Cluster standard deviations are estimated from all members in that cluster.
+/// For each member with a cluster assignment, computes the z-score anomaly,
+/// flagging members above `threshold`. Cluster standard deviations are
+/// estimated from all members in that cluster.
 pub fn score_all_members<C: ClusterProvider>(
     features: &MemberFeatures,
     kmeans: &C,
+    threshold: f64,
 ) -> Vec<(NodeId, AnomalyScore)> {
     let centroids = kmeans.centroids();
     if centroids.is_empty() {
         return Vec::new();
     }
 
-    let dim = centroids[0].len();
-
-    // First pass: collect all feature vectors grouped by cluster to compute std.
-    let mut cluster_vectors: std::collections::HashMap<ClusterId, Vec<Vec<f64>>> =
-        std::collections::HashMap::new();
-
     let members: Vec<NodeId> = features.members().copied().collect();
+    let cluster_stds = cluster_std_devs(features, kmeans, &members);
 
-    for member_id in &members {
-        if let (Some(cluster_id), Some(fv)) =
-            (kmeans.get_cluster(member_id), features.to_feature_vector(member_id))
-        {
-            cluster_vectors
-                .entry(cluster_id)
-                .or_default()
-                .push(fv);
-        }
-    }
-
-    // Compute per-cluster standard deviations.
-    let cluster_stds: std::collections::HashMap<ClusterId, Vec<f64>> = cluster_vectors
-        .iter()
-        .map(|(&cid, vecs)| {
-            let centroid = &centroids[cid as usize];
-            let std_dev = population::compute_std_dev(vecs, centroid, dim);
-            (cid, std_dev)
-        })
-        .collect();
-
-    // Second pass: score each member.
+    // Score each member against its cluster.
     let mut results = Vec::with_capacity(members.len());
     for member_id in &members {
         if let (Some(cluster_id), Some(fv)) =
@@ -171,7 +201,7 @@ pub fn score_all_members<C: ClusterProvider>(
                 Some(s) => s,
                 None => continue,
             };
-            let score = compute_anomaly_score(&fv, centroid, std_dev);
+            let score = compute_anomaly_score_with_threshold(&fv, centroid, std_dev, threshold);
             results.push((*member_id, score));
         }
     }
@@ -179,6 +209,35 @@ pub fn score_all_members<C: ClusterProvider>(
     results
 }
 
+/// Per-cluster standard deviations around each centroid, estimated from
+/// the members assigned to that cluster.
+fn cluster_std_devs<C: ClusterProvider>(
+    features: &MemberFeatures,
+    kmeans: &C,
+    members: &[NodeId],
+) -> HashMap<ClusterId, Vec<f64>> {
+    let centroids = kmeans.centroids();
+
+    // Collect all feature vectors grouped by cluster.
+    let mut cluster_vectors: HashMap<ClusterId, Vec<Vec<f64>>> = HashMap::new();
+    for member_id in members {
+        if let (Some(cluster_id), Some(fv)) =
+            (kmeans.get_cluster(member_id), features.to_feature_vector(member_id))
+        {
+            cluster_vectors.entry(cluster_id).or_default().push(fv);
+        }
+    }
+
+    cluster_vectors
+        .iter()
+        .filter_map(|(&cid, vecs)| {
+            let centroid = centroids.get(cid as usize)?;
+            let std_dev = population::compute_std_dev(vecs, centroid, centroid.len());
+            Some((cid, std_dev))
+        })
+        .collect()
+}
+
 /// Combine four detector signals into a weighted anomaly result.
 pub fn multi_signal_score(
     statistical: f64,
@@ -186,10 +245,13 @@ pub fn multi_signal_score(
     behavioral: f64,
     graph: f64,
 ) -> AnomalyResult {
-    let score = statistical * WEIGHT_STATISTICAL
-        + dbscan_noise * WEIGHT_DBSCAN_NOISE
-        + behavioral * WEIGHT_BEHAVIORAL
-        + graph * WEIGHT_GRAPH;
+    let weighted = [
+        ("statistical", statistical * WEIGHT_STATISTICAL),
+        ("dbscan_noise", dbscan_noise * WEIGHT_DBSCAN_NOISE),
+        ("behavioral", behavioral * WEIGHT_BEHAVIORAL),
+        ("graph", graph * WEIGHT_GRAPH),
+    ];
+    let score: f64 = weighted.iter().map(|(_, w)| w).sum();
 
     let score = score.clamp(0.0, 1.0);
     let classification = AnomalyClassification::from_score(score);
@@ -203,6 +265,10 @@ pub fn multi_signal_score(
             ("behavioral".to_string(), behavioral),
             ("graph".to_string(), graph),
         ],
+        explanation: AnomalyExplanation {
+            dominant_signal: dominant_signal(&weighted),
+            top_features: Vec::new(),
+        },
     }
 }
 
@@ -215,10 +281,13 @@ pub fn multi_signal_score_with_config(
     config: &stupid_rules::scoring_config::CompiledScoringConfig,
 ) -> AnomalyResult {
     let w = &config.multi_signal_weights;
-    let score = statistical * w.statistical
-        + dbscan_noise * w.dbscan_noise
-        + behavioral * w.behavioral
-        + graph * w.graph;
+    let weighted = [
+        ("statistical", statistical * w.statistical),
+        ("dbscan_noise", dbscan_noise * w.dbscan_noise),
+        ("behavioral", behavioral * w.behavioral),
+        ("graph", graph * w.graph),
+    ];
+    let score: f64 = weighted.iter().map(|(_, w)| w).sum();
 
     let score = score.clamp(0.0, 1.0);
     let classification = classify_score_with_config(score, &config.classification_thresholds);
@@ -232,6 +301,10 @@ pub fn multi_signal_score_with_config(
             ("behavioral".to_string(), behavioral),
             ("graph".to_string(), graph),
         ],
+        explanation: AnomalyExplanation {
+            dominant_signal: dominant_signal(&weighted),
+            top_features: Vec::new(),
+        },
     }
 }
 
@@ -251,12 +324,49 @@ pub fn classify_score_with_config(
     }
 }
 
+/// Classify a batch of scores, honouring the thresholds' mode.
+///
+/// `Absolute` mode applies [`classify_score_with_config`] to each score.
+/// `Percentile` mode ranks each score within the batch — the fraction of
+/// scores strictly below it — and compares that rank to the thresholds, so
+/// only the tail is flagged however far the whole population has shifted.
+/// Tied scores share a rank. Output order matches `scores`.
+pub fn classify_batch_with_config(
+    scores: &[f64],
+    thresholds: &stupid_rules::scoring_config::ClassificationThresholds,
+) -> Vec<AnomalyClassification> {
+    use stupid_rules::scoring_config::ClassificationMode;
+
+    match thresholds.mode {
+        ClassificationMode::Absolute => scores
+            .iter()
+            .map(|&score| classify_score_with_config(score, thresholds))
+            .collect(),
+        ClassificationMode::Percentile => {
+            let mut sorted = scores.to_vec();
+            sorted.sort_by(f64::total_cmp);
+            let n = sorted.len() as f64;
+            scores
+                .iter()
+                .map(|score| {
+                    let below = sorted.partition_point(|s| s < score);
+                    classify_score_with_config(below as f64 / n, thresholds)
+                })
+                .collect()
+        }
+    }
+}
+
 /// Score all members using the multi-signal approach.
 ///
 /// This is the main entry point for the anomaly detection pipeline.
 /// It runs all available detectors and combines the results.
 ///
 /// `dbscan_result` and `community_map` are optional — if None, those signals score 0.
+///
+/// Each result's explanation lists the member's top deviating features
+/// against its cluster (centroid and cluster std-dev), or against the
+/// population when the member is unclustered.
 pub fn multi_signal_score_all<C: ClusterProvider>(
     features: &MemberFeatures,
     kmeans: &C,
@@ -276,6 +386,7 @@ pub fn multi_signal_score_all<C: ClusterProvider>(
     }
 
     let (pop_means, pop_stddevs) = compute_population_stats(&all_fvs);
+    let cluster_stds = cluster_std_devs(features, kmeans, &members);
 
     let mut results = Vec::with_capacity(members.len());
 
@@ -324,7 +435,15 @@ pub fn multi_signal_score_all<C: ClusterProvider>(
             0.0
         };
 
-        results.push((*member_id, multi_signal_score(s1, s2, s3, s4)));
+        let mut result = multi_signal_score(s1, s2, s3, s4);
+        let (baseline, std_dev) = kmeans
+            .get_cluster(member_id)
+            .and_then(|cid| Some((kmeans.centroids().get(cid as usize)?, cluster_stds.get(&cid)?)))
+            .unwrap_or((&pop_means, &pop_stddevs));
+        result.explanation.top_features =
+            top_feature_deviations(&fv, baseline, std_dev, TOP_FEATURE_COUNT);
+
+        results.push((*member_id, result));
     }
 
     results
@@ -397,6 +516,79 @@ mod tests {
         assert_eq!(result.classification, AnomalyClassification::Mild);
     }
 
+    #[test]
+    fn multi_signal_names_dominant_signal() {
+        // Weighted: 0.2*0.9=0.18, 0.3*0.0, 0.3*0.4=0.12, 0.2*0.0
+        let result = multi_signal_score(0.9, 0.0, 0.4, 0.0);
+        assert_eq!(result.explanation.dominant_signal.as_deref(), Some("statistical"));
+
+        let result = multi_signal_score(0.0, 0.0, 0.0, 0.0);
+        assert_eq!(result.explanation.dominant_signal, None);
+    }
+
+    /// Assigns every member to the first centroid, if there is one.
+    struct SingleCluster(Vec<Vec<f64>>);
+
+    impl ClusterProvider for SingleCluster {
+        fn get_cluster(&self, _member_id: &NodeId) -> Option<ClusterId> {
+            self.0.first().map(|_| 0)
+        }
+
+        fn centroids(&self) -> &[Vec<f64>] {
+            &self.0
+        }
+    }
+
+    /// Ten members with 1-3 games and errors each, plus `M_OUT` with 40 errors.
+    fn features_with_error_outlier() -> (MemberFeatures, NodeId) {
+        use stupid_core::{Document, FieldValue};
+
+        let mut features = MemberFeatures::new();
+        let mut record = |member: &str, event: &str, times: usize| {
+            for _ in 0..times {
+                features.update(&Document {
+                    id: uuid::Uuid::new_v4(),
+                    timestamp: chrono::Utc::now(),
+                    event_type: event.to_string(),
+                    fields: [("memberCode".to_string(), FieldValue::Text(member.to_string()))].into(),
+                });
+            }
+        };
+        for i in 0..10 {
+            let member = format!("M{i:03}");
+            record(&member, "game_round", 1 + i % 3);
+            record(&member, "error", 1 + (i + 1) % 3);
+        }
+        record("M_OUT", "game_round", 2);
+        record("M_OUT", "error", 40);
+
+        let outlier = crate::pipeline::features::member_code_to_node_id("M_OUT");
+        (features, outlier)
+    }
+
+    #[test]
+    fn explanation_identifies_outlier_dimension() {
+        let (features, outlier) = features_with_error_outlier();
+
+        // Unclustered: explained against the population.
+        let results = multi_signal_score_all(&features, &SingleCluster(Vec::new()), None, None, 0.0);
+        let (_, result) = results.iter().find(|(id, _)| *id == outlier).unwrap();
+        let top = &result.explanation.top_features[0];
+        assert_eq!(top.feature, "error_count");
+        assert_eq!(top.value, 40.0);
+        assert!(top.z_score > 3.0, "z = {}", top.z_score);
+        assert_eq!(result.explanation.dominant_signal.as_deref(), Some("statistical"));
+
+        // Clustered: explained against the centroid.
+        let centroid = vec![0.0, 2.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        let results = multi_signal_score_all(&features, &SingleCluster(vec![centroid]), None, None, 0.0);
+        let (_, result) = results.iter().find(|(id, _)| *id == outlier).unwrap();
+        let top = &result.explanation.top_features;
+        assert_eq!(top[0].feature, "error_count");
+        assert_eq!(top[0].baseline, 2.0);
+        assert!(top[0].z_score > top.get(1).map_or(0.0, |c| c.z_score.abs()));
+    }
+
     #[test]
     fn classification_thresholds() {
         assert_eq!(AnomalyClassification::from_score(0.0), AnomalyClassification::Normal);
@@ -408,4 +600,49 @@ mod tests {
         assert_eq!(AnomalyClassification::from_score(0.7), AnomalyClassification::HighlyAnomalous);
         assert_eq!(AnomalyClassification::from_score(1.0), AnomalyClassification::HighlyAnomalous);
     }
+
+    fn thresholds(
+        mode: stupid_rules::scoring_config::ClassificationMode,
+        mild: f64,
+        anomalous: f64,
+        highly: f64,
+    ) -> stupid_rules::scoring_config::ClassificationThresholds {
+        stupid_rules::scoring_config::ClassificationThresholds {
+            mode,
+            mild,
+            anomalous,
+            highly_anomalous: highly,
+        }
+    }
+
+    #[test]
+    fn percentile_mode_flags_only_tail_of_shifted_population() {
+        use stupid_rules::scoring_config::ClassificationMode;
+
+        // The whole population has drifted above every absolute cutoff.
+        let scores: Vec<f64> = (0..100).map(|i| 0.75 + i as f64 * 0.002).collect();
+
+        let absolute =
+            classify_batch_with_config(&scores, &thresholds(ClassificationMode::Absolute, 0.3, 0.5, 0.7));
+        assert!(absolute.iter().all(|c| *c == AnomalyClassification::HighlyAnomalous));
+
+        let percentile =
+            classify_batch_with_config(&scores, &thresholds(ClassificationMode::Percentile, 0.90, 0.95, 0.99));
+        let count = |class| percentile.iter().filter(|c| **c == class).count();
+        assert_eq!(count(AnomalyClassification::HighlyAnomalous), 1);
+        assert_eq!(count(AnomalyClassification::Anomalous), 4);
+        assert_eq!(count(AnomalyClassification::Mild), 5);
+        assert_eq!(count(AnomalyClassification::Normal), 90);
+        // The highest score is the one flagged.
+        assert_eq!(percentile[99], AnomalyClassification::HighlyAnomalous);
+    }
+
+    #[test]
+    fn percentile_mode_ties_share_a_rank() {
+        use stupid_rules::scoring_config::ClassificationMode;
+
+        let classes =
+            classify_batch_with_config(&[0.9; 10], &thresholds(ClassificationMode::Percentile, 0.90, 0.95, 0.99));
+        assert!(classes.iter().all(|c| *c == AnomalyClassification::Normal));
+    }
 }
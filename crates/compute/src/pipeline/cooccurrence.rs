@@ -33,6 +33,55 @@ pub struct CooccurrenceMatrix {
     pub total_docs: f64,
 }
 
+/// An entity co-occurring with a queried entity, with the pair's scores.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Association {
+    /// The co-occurring entity key.
+    pub item: String,
+    /// Documents in which both entities appeared.
+    pub count: f64,
+    /// PMI of the pair.
+    pub pmi: f64,
+}
+
+impl CooccurrenceMatrix {
+    /// The `k` entities with the highest PMI alongside `item`.
+    ///
+    /// Pairs seen fewer than `min_count` times are skipped: PMI is unstable
+    /// for rare pairs, and a single shared document can outscore a strong
+    /// recurring association. Ties are broken by count, then by key.
+    pub fn top_associations(&self, item: &str, k: usize, min_count: f64) -> Vec<Association> {
+        let mut associations: Vec<Association> = self
+            .pmi
+            .iter()
+            .filter_map(|((a, b), &pmi)| {
+                let other = if a == item {
+                    b
+                } else if b == item {
+                    a
+                } else {
+                    return None;
+                };
+                let count = self.counts.entries.get(&(a.clone(), b.clone())).copied()?;
+                (count >= min_count).then(|| Association {
+                    item: other.clone(),
+                    count,
+                    pmi,
+                })
+            })
+            .collect();
+
+        associations.sort_by(|x, y| {
+            y.pmi
+                .total_cmp(&x.pmi)
+                .then(y.count.total_cmp(&x.count))
+                .then_with(|| x.item.cmp(&y.item))
+        });
+        associations.truncate(k);
+        associations
+    }
+}
+
 /// Update co-occurrence matrices from a batch of documents.
 ///
 /// For each document, all present entity fields are extracted and every pair
@@ -445,4 +494,41 @@ mod tests {
         let pmi = matrix.pmi[&("A".to_string(), "B".to_string())];
         assert!(pmi < 0.0, "PMI should be negative for anti-correlated entities");
     }
+
+    #[test]
+    fn top_associations_ranked_by_pmi_with_count_floor() {
+        let mut matrix = CooccurrenceMatrix::default();
+        // mobile pairs with slots in 8 of its 10 documents, with poker in 4
+        // of 20, and with keno in its only document; desktop only with poker.
+        for ((a, b), count) in [
+            (("mobile", "slots"), 8.0),
+            (("mobile", "poker"), 4.0),
+            (("mobile", "keno"), 1.0),
+            (("desktop", "poker"), 16.0),
+        ] {
+            matrix.counts.entries.insert((a.to_string(), b.to_string()), count);
+        }
+        for (key, marginal) in [("mobile", 13.0), ("desktop", 16.0), ("slots", 10.0), ("poker", 20.0), ("keno", 1.0)] {
+            matrix.marginals.insert(key.to_string(), marginal);
+        }
+        matrix.total_docs = 29.0;
+        compute_pmi(&mut matrix);
+
+        // keno's single document gives it the highest PMI.
+        let all = matrix.top_associations("mobile", 10, 0.0);
+        let items: Vec<&str> = all.iter().map(|a| a.item.as_str()).collect();
+        assert_eq!(items, vec!["keno", "slots", "poker"]);
+        assert!(all.windows(2).all(|w| w[0].pmi >= w[1].pmi));
+
+        let floored = matrix.top_associations("mobile", 10, 2.0);
+        let items: Vec<&str> = floored.iter().map(|a| a.item.as_str()).collect();
+        assert_eq!(items, vec!["slots", "poker"]);
+        assert_eq!(floored[0].count, 8.0);
+
+        // Works from either side of the pair, and k caps the result.
+        let top = matrix.top_associations("poker", 1, 0.0);
+        assert_eq!(top.len(), 1);
+        assert_eq!(top[0].item, "desktop");
+        assert!(matrix.top_associations("unknown", 5, 0.0).is_empty());
+    }
 }
@@ -1,7 +1,7 @@
 use std::collections::{HashMap, HashSet};
 
 use chrono::{DateTime, Utc};
-use stupid_core::{Document, FieldValue, NodeId};
+use stupid_core::{Document, NodeId};
 use uuid::Uuid;
 
 /// Derive a stable deterministic `NodeId` from a member code string.
@@ -74,7 +74,7 @@ impl FeatureAccumulator {
 
 /// Helper to extract a text field from a document's fields map.
 fn get_field<'a>(doc: &'a Document, key: &str) -> Option<&'a str> {
-    doc.fields.get(key).and_then(FieldValue::as_str)
+    doc.field_str(key)
 }
 
 impl MemberFeatures {
@@ -326,6 +326,7 @@ pub fn classify_event_from_config(
 mod tests {
     use super::*;
     use std::collections::HashMap;
+    use stupid_core::FieldValue;
 
     fn make_doc(event_type: &str, fields: Vec<(&str, &str)>) -> Document {
         let mut field_map = HashMap::new();
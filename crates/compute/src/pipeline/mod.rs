@@ -18,15 +18,19 @@ use chrono::Utc;
 use tracing::{debug, info};
 use uuid::Uuid;
 
-use stupid_core::Document;
+use stupid_core::{Document, NodeId};
+use stupid_rules::scoring_config::{ClassificationMode, CompiledScoringConfig};
 
+use crate::algorithms::kmeans::{kmeans, KmeansResult};
 use crate::algorithms::streaming_kmeans::StreamingKMeans;
 use crate::scheduler::state::KnowledgeState;
-use crate::scheduler::types::{ClusterInfo, Insight, InsightSeverity};
+use crate::scheduler::types::{
+    AnomalyClassification, AnomalyScore, ClusterInfo, Insight, InsightSeverity,
+};
 
 use crate::algorithms::prefixspan;
 
-use self::anomaly::score_all_members;
+use self::anomaly::{classify_batch_with_config, score_all_members, AnomalyThresholds};
 use self::cooccurrence::update_cooccurrence;
 use self::features::{member_code_to_node_id, MemberFeatures};
 use self::metrics::PipelineMetrics;
@@ -48,6 +52,11 @@ pub struct Pipeline {
     kmeans: StreamingKMeans,
     /// Trend detector for z-score based trend detection.
     trend_detector: TrendDetector,
+    /// Scoring config used to classify warm-compute anomaly scores. When
+    /// unset, members above the z-score threshold are anomalous.
+    scoring: Option<CompiledScoringConfig>,
+    /// Z-score threshold and severity cutoffs for warm-compute anomalies.
+    anomaly_thresholds: AnomalyThresholds,
 }
 
 impl Pipeline {
@@ -58,6 +67,8 @@ impl Pipeline {
             metrics: PipelineMetrics::default(),
             kmeans: StreamingKMeans::new(DEFAULT_K, FEATURE_DIM),
             trend_detector: TrendDetector::new(),
+            scoring: None,
+            anomaly_thresholds: AnomalyThresholds::default(),
         }
     }
 
@@ -68,9 +79,30 @@ impl Pipeline {
             metrics: PipelineMetrics::default(),
             kmeans: StreamingKMeans::new(k, FEATURE_DIM),
             trend_detector: TrendDetector::new(),
+            scoring: None,
+            anomaly_thresholds: AnomalyThresholds::default(),
         }
     }
 
+    /// Classify warm-compute anomalies with a scoring config's thresholds,
+    /// including percentile mode, instead of the default z-score cutoff.
+    pub fn set_scoring_config(&mut self, config: CompiledScoringConfig) {
+        self.scoring = Some(config);
+    }
+
+    /// The scoring config set with [`set_scoring_config`](Self::set_scoring_config), if any.
+    pub fn scoring_config(&self) -> Option<&CompiledScoringConfig> {
+        self.scoring.as_ref()
+    }
+
+    /// Flag members and grade their insights with these z-score cutoffs
+    /// instead of the defaults (2.0 anomalous, 3.0 Warning, 4.0 Critical).
+    /// A scoring config set with [`set_scoring_config`](Self::set_scoring_config)
+    /// classifies by its own thresholds and takes precedence for severities.
+    pub fn set_anomaly_thresholds(&mut self, thresholds: AnomalyThresholds) {
+        self.anomaly_thresholds = thresholds;
+    }
+
     /// Stage 2 hot path: process incoming documents in real time.
     ///
     /// For each document:
@@ -117,10 +149,64 @@ impl Pipeline {
             }
         }
 
-        // Update cluster info with current centroids.
+        self.sync_cluster_info(state);
+
+        let elapsed = start.elapsed();
+        self.metrics.record_hot_batch(docs.len() as u64, elapsed);
+
+        debug!(
+            docs = docs.len(),
+            members = updated_members.len(),
+            elapsed_us = elapsed.as_micros(),
+            "hot_connect completed"
+        );
+    }
+
+    /// Full batch K-means over every tracked member, re-seeding the
+    /// streaming model with the clean centroids.
+    ///
+    /// Online updates weigh early points forever and never move a member
+    /// whose features stopped changing, so the streaming centroids drift
+    /// from what Lloyd's algorithm would find. This replaces them, along with
+    /// every member's assignment in `state`. Returns `None` without touching
+    /// anything when there are fewer members than clusters.
+    pub fn reseed_kmeans(
+        &mut self,
+        max_iterations: usize,
+        state: &mut KnowledgeState,
+    ) -> Option<KmeansResult> {
+        let mut points: Vec<(NodeId, Vec<f64>)> = self
+            .features
+            .members()
+            .filter_map(|id| Some((*id, self.features.to_feature_vector(id)?)))
+            .collect();
+        if points.len() < self.kmeans.k() {
+            return None;
+        }
+        // Sort so the deterministic K-means++ seeding sees a stable order.
+        points.sort_by_key(|(id, _)| *id);
+
+        let result = kmeans(&points, self.kmeans.k(), max_iterations);
+        self.kmeans
+            .reseed(result.centroids.clone(), result.assignments.clone());
+
+        state.clusters.extend(result.assignments.iter().map(|(id, c)| (*id, *c)));
+        state.cluster_info.clear();
+        self.sync_cluster_info(state);
+
+        Some(result)
+    }
+
+    /// Number of members with a feature vector.
+    pub fn member_count(&self) -> usize {
+        self.features.members().count()
+    }
+
+    /// Write the streaming model's centroids and sizes to `cluster_info`.
+    fn sync_cluster_info(&self, state: &mut KnowledgeState) {
+        let counts = self.kmeans.cluster_counts();
         for (i, centroid) in self.kmeans.centroids().iter().enumerate() {
             let cluster_id = i as u64;
-            let counts = self.kmeans.cluster_counts();
             state.cluster_info.insert(
                 cluster_id,
                 ClusterInfo {
@@ -131,16 +217,48 @@ impl Pipeline {
                 },
             );
         }
+    }
 
-        let elapsed = start.elapsed();
-        self.metrics.record_hot_batch(docs.len() as u64, elapsed);
+    /// Centroids of the streaming K-means model.
+    pub fn centroids(&self) -> &[Vec<f64>] {
+        self.kmeans.centroids()
+    }
 
-        debug!(
-            docs = docs.len(),
-            members = updated_members.len(),
-            elapsed_us = elapsed.as_micros(),
-            "hot_connect completed"
-        );
+    /// Insight severity for each warm-compute score, `None` if not anomalous.
+    ///
+    /// Without a scoring config, the z-score threshold decides and the
+    /// z-score sets the severity, per [`AnomalyThresholds`]. With one, the batch is classified by its
+    /// thresholds: absolute mode on z-scores normalized to [0, 1], percentile
+    /// mode on the raw scores' rank. Anomalous maps to Warning and
+    /// HighlyAnomalous to Critical.
+    fn anomaly_severities(&self, results: &[(NodeId, AnomalyScore)]) -> Vec<Option<InsightSeverity>> {
+        let Some(config) = &self.scoring else {
+            return results
+                .iter()
+                .map(|(_, score)| self.anomaly_thresholds.severity(score))
+                .collect();
+        };
+
+        let thresholds = &config.classification_thresholds;
+        let scores: Vec<f64> = results
+            .iter()
+            .map(|(_, score)| match thresholds.mode {
+                ClassificationMode::Absolute => {
+                    (score.score / config.z_score_normalization.divisor).clamp(0.0, 1.0)
+                }
+                // Clamping would create ties at the top and hide the tail.
+                ClassificationMode::Percentile => score.score,
+            })
+            .collect();
+
+        classify_batch_with_config(&scores, thresholds)
+            .into_iter()
+            .map(|class| match class {
+                AnomalyClassification::HighlyAnomalous => Some(InsightSeverity::Critical),
+                AnomalyClassification::Anomalous => Some(InsightSeverity::Warning),
+                AnomalyClassification::Mild | AnomalyClassification::Normal => None,
+            })
+            .collect()
     }
 
     /// Stage 3 warm compute: periodic analysis on recent data.
@@ -162,10 +280,13 @@ impl Pipeline {
         }
 
         // Step 2: Anomaly scoring.
-        let anomaly_results = score_all_members(&self.features, &self.kmeans);
+        let mut anomaly_results =
+            score_all_members(&self.features, &self.kmeans, self.anomaly_thresholds.threshold);
+        let severities = self.anomaly_severities(&anomaly_results);
 
         let mut anomaly_count = 0usize;
-        for (member_id, score) in &anomaly_results {
+        for ((member_id, score), severity) in anomaly_results.iter_mut().zip(&severities) {
+            score.is_anomalous = severity.is_some();
             state.anomalies.insert(*member_id, *score);
             if score.is_anomalous {
                 anomaly_count += 1;
@@ -176,16 +297,8 @@ impl Pipeline {
         if anomaly_count > 0 {
             info!(anomaly_count, "anomalous members detected");
 
-            for (member_id, score) in &anomaly_results {
-                if score.is_anomalous {
-                    let severity = if score.score > 4.0 {
-                        InsightSeverity::Critical
-                    } else if score.score > 3.0 {
-                        InsightSeverity::Warning
-                    } else {
-                        InsightSeverity::Info
-                    };
-
+            for ((member_id, score), severity) in anomaly_results.iter().zip(severities) {
+                if let Some(severity) = severity {
                     let insight = Insight {
                         id: Uuid::new_v4().to_string(),
                         title: format!("Anomalous behavior detected (z={:.2})", score.score),
@@ -198,15 +311,9 @@ impl Pipeline {
                         related_nodes: vec![*member_id],
                     };
 
-                    state.insights.push_back(insight);
+                    state.insights.push(insight);
                 }
             }
-
-            // Cap insight queue to prevent unbounded growth.
-            const MAX_INSIGHTS: usize = 10_000;
-            while state.insights.len() > MAX_INSIGHTS {
-                state.insights.pop_front();
-            }
         }
 
         // Step 4: Trend detection.
@@ -227,6 +334,9 @@ impl Pipeline {
                     magnitude: t.z_score.abs(),
                     baseline: t.baseline_mean,
                     current: t.current_value,
+                    sample_count: t.sample_count,
+                    standard_error: t.standard_error,
+                    confidence_interval: t.confidence_interval,
                 },
             );
 
@@ -256,7 +366,7 @@ impl Pipeline {
                     related_nodes: vec![],
                 };
 
-                state.insights.push_back(insight);
+                state.insights.push(insight);
             }
         }
 
@@ -346,6 +456,93 @@ mod tests {
         assert!(!state.cooccurrence.is_empty());
     }
 
+    #[test]
+    fn pipeline_warm_compute_percentile_mode_flags_tail() {
+        let mut pipeline = Pipeline::with_k(2);
+        let mut state = KnowledgeState::default();
+
+        // 40 members with steadily increasing activity.
+        let mut docs = Vec::new();
+        for m in 0..40 {
+            for e in 0..=m {
+                let game = format!("game{}", e % (1 + m / 10));
+                docs.push(make_doc("gameOpen", vec![
+                    ("memberCode", &format!("M{:03}", m)),
+                    ("gameName", &game),
+                ]));
+            }
+        }
+        pipeline.hot_connect(&docs, &mut state);
+
+        let yaml = include_str!("../../../../data/rules/scoring/scoring-config.yml");
+        let rule: stupid_rules::scoring_config::ScoringConfigRule = serde_yaml::from_str(yaml).unwrap();
+        let mut config = rule.compile();
+        config.classification_thresholds = stupid_rules::scoring_config::ClassificationThresholds {
+            mode: ClassificationMode::Percentile,
+            mild: 0.80,
+            anomalous: 0.90,
+            highly_anomalous: 0.975,
+        };
+        pipeline.set_scoring_config(config);
+        pipeline.warm_compute(&mut state, &docs);
+
+        let flagged = state.anomalies.values().filter(|a| a.is_anomalous).count();
+        assert!(flagged > 0, "percentile mode should flag the tail");
+        assert!(flagged <= 4, "only the top 10% may be flagged, got {flagged}");
+        let member_insights = state
+            .insights
+            .iter()
+            .filter(|i| i.title.starts_with("Anomalous behavior"))
+            .count();
+        assert_eq!(member_insights, flagged);
+    }
+
+    #[test]
+    fn pipeline_lower_anomaly_threshold_flags_more_members() {
+        let mut pipeline = Pipeline::with_k(2);
+        let mut state = KnowledgeState::default();
+        let mut docs = Vec::new();
+        for m in 0..40 {
+            for e in 0..=m {
+                let game = format!("game{}", e % (1 + m / 10));
+                docs.push(make_doc("gameOpen", vec![
+                    ("memberCode", &format!("M{:03}", m)),
+                    ("gameName", &game),
+                ]));
+            }
+        }
+        pipeline.hot_connect(&docs, &mut state);
+
+        let severities = |state: &KnowledgeState| -> Vec<InsightSeverity> {
+            state
+                .insights
+                .iter()
+                .filter(|i| i.title.starts_with("Anomalous behavior"))
+                .map(|i| i.severity)
+                .collect()
+        };
+
+        let mut default_state = state.clone();
+        pipeline.warm_compute(&mut default_state, &docs);
+        let default_flagged = default_state.anomalies.values().filter(|a| a.is_anomalous).count();
+
+        let lowered = AnomalyThresholds { threshold: 0.5, warning: 0.75, critical: 1.0 };
+        pipeline.set_anomaly_thresholds(lowered);
+        let mut lowered_state = state.clone();
+        pipeline.warm_compute(&mut lowered_state, &docs);
+        let lowered_flagged = lowered_state.anomalies.values().filter(|a| a.is_anomalous).count();
+
+        assert!(
+            lowered_flagged > default_flagged,
+            "lowered threshold flagged {lowered_flagged}, default {default_flagged}"
+        );
+        let critical = |s: &[InsightSeverity]| s.iter().filter(|s| **s == InsightSeverity::Critical).count();
+        assert!(critical(&severities(&lowered_state)) > critical(&severities(&default_state)));
+        for score in lowered_state.anomalies.values() {
+            assert_eq!(score.is_anomalous, score.score > 0.5);
+        }
+    }
+
     #[test]
     fn pipeline_empty_docs_noop() {
         let mut pipeline = Pipeline::new();
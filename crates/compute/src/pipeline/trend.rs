@@ -4,7 +4,9 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use tracing::debug;
 
-use stupid_core::{Document, FieldValue};
+use stupid_core::Document;
+
+use crate::scheduler::types::ConfidenceInterval;
 
 /// Direction of a detected trend.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -36,6 +38,12 @@ pub struct Trend {
     pub direction: TrendDirection,
     pub severity: Severity,
     pub since: DateTime<Utc>,
+    /// Baseline observations the z-score was computed from.
+    pub sample_count: usize,
+    /// Standard error of the baseline mean.
+    pub standard_error: f64,
+    /// 95% confidence interval of the baseline mean; narrows as samples accumulate.
+    pub confidence_interval: ConfidenceInterval,
 }
 
 /// Rolling baseline tracker for a single metric.
@@ -81,6 +89,49 @@ impl MetricBaseline {
             / self.values.len() as f64;
         variance.sqrt()
     }
+
+    /// Standard error of the mean, using the sample (n - 1) standard deviation.
+    fn standard_error(&self) -> f64 {
+        let n = self.values.len();
+        if n < 2 {
+            return 0.0;
+        }
+        let mean = self.mean();
+        let sample_variance =
+            self.values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        (sample_variance / n as f64).sqrt()
+    }
+
+    /// 95% confidence interval of the mean (Student's t).
+    fn confidence_interval(&self) -> ConfidenceInterval {
+        let mean = self.mean();
+        let n = self.values.len();
+        if n < 2 {
+            return ConfidenceInterval { lower: mean, upper: mean };
+        }
+        let margin = t_critical_95(n - 1) * self.standard_error();
+        ConfidenceInterval {
+            lower: mean - margin,
+            upper: mean + margin,
+        }
+    }
+}
+
+/// Two-sided 95% critical value of Student's t for `df` degrees of freedom.
+fn t_critical_95(df: usize) -> f64 {
+    const TABLE: [f64; 30] = [
+        12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228,
+        2.201, 2.179, 2.160, 2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086,
+        2.080, 2.074, 2.069, 2.064, 2.060, 2.056, 2.052, 2.048, 2.045, 2.042,
+    ];
+    match df {
+        0 => f64::INFINITY,
+        1..=30 => TABLE[df - 1],
+        31..=40 => 2.021,
+        41..=60 => 2.000,
+        61..=120 => 1.980,
+        _ => 1.960,
+    }
 }
 
 /// Trend detector that tracks multiple metrics over time.
@@ -155,10 +206,8 @@ impl TrendDetector {
                 .entry(doc.event_type.clone())
                 .or_default() += 1.0;
 
-            if let Some(member) = doc.fields.get("memberCode").and_then(FieldValue::as_str) {
-                if !member.is_empty() {
-                    unique_members.insert(member.to_owned());
-                }
+            if let Some(member) = doc.field_str("memberCode") {
+                unique_members.insert(member.to_owned());
             }
 
             if doc.event_type.contains("Error") || doc.event_type.contains("error") {
@@ -235,6 +284,9 @@ impl TrendDetector {
                         direction,
                         severity,
                         since: now,
+                        sample_count: baseline.values.len(),
+                        standard_error: baseline.standard_error(),
+                        confidence_interval: baseline.confidence_interval(),
                     });
                 }
             }
@@ -265,6 +317,7 @@ pub fn z_score(value: f64, mean: f64, stddev: f64) -> f64 {
 mod tests {
     use super::*;
     use std::collections::HashMap;
+    use stupid_core::FieldValue;
     use uuid::Uuid;
 
     fn make_doc(event_type: &str, fields: Vec<(&str, &str)>) -> Document {
@@ -395,4 +448,42 @@ mod tests {
 
         assert!(trends.is_empty(), "Should not detect trend for normal value");
     }
+
+    #[test]
+    fn confidence_interval_narrows_with_more_samples() {
+        let pattern = [98.0, 102.0, 100.0];
+        let detect_after = |samples: usize| {
+            let mut detector = TrendDetector::with_window(samples);
+            for val in pattern.iter().cycle().take(samples) {
+                detector.detect(&HashMap::from([("m".to_string(), *val)]));
+            }
+            detector
+                .detect(&HashMap::from([("m".to_string(), 150.0)]))
+                .pop()
+                .expect("spike should be a trend")
+        };
+
+        let small = detect_after(3);
+        let large = detect_after(30);
+        assert_eq!(small.sample_count, 3);
+        assert_eq!(large.sample_count, 30);
+        assert!(small.standard_error > large.standard_error);
+        assert!(
+            small.confidence_interval.width() > 3.0 * large.confidence_interval.width(),
+            "small: {:?}, large: {:?}",
+            small.confidence_interval,
+            large.confidence_interval
+        );
+        for trend in [&small, &large] {
+            assert!(trend.confidence_interval.lower < trend.baseline_mean);
+            assert!(trend.confidence_interval.upper > trend.baseline_mean);
+        }
+    }
+
+    #[test]
+    fn t_critical_approaches_normal() {
+        assert!(t_critical_95(2) > t_critical_95(10));
+        assert!(t_critical_95(10) > t_critical_95(1000));
+        assert!((t_critical_95(1000) - 1.96).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,227 @@
+//! Capped insight store with severity, time and related-node lookups.
+
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+use stupid_core::NodeId;
+
+use super::types::{Insight, InsightSeverity};
+
+/// Insights kept before the oldest are evicted.
+pub const MAX_INSIGHTS: usize = 10_000;
+
+/// Filters and paging for [`InsightStore::query`]. Every filter is optional.
+#[derive(Debug, Clone, Default)]
+pub struct InsightQuery {
+    /// Only insights at least this severe.
+    pub min_severity: Option<InsightSeverity>,
+    /// Only insights created at or after this instant.
+    pub since: Option<DateTime<Utc>>,
+    /// Only insights created before this instant.
+    pub until: Option<DateTime<Utc>>,
+    /// Only insights that list this node in `related_nodes`.
+    pub related_node: Option<NodeId>,
+    /// Matching insights to skip, newest first.
+    pub offset: usize,
+    /// Maximum insights to return (`None` = all).
+    pub limit: Option<usize>,
+}
+
+/// One page of [`InsightStore::query`] results.
+#[derive(Debug, Clone)]
+pub struct InsightPage {
+    /// Matching insights, newest first.
+    pub insights: Vec<Insight>,
+    /// Matching insights before paging.
+    pub total: usize,
+}
+
+/// Insight queue (newest at back) capped at [`MAX_INSIGHTS`], indexed by
+/// related node.
+///
+/// Each insight gets a sequence number when pushed; the node index stores
+/// those, so lookups by node touch only that node's insights and eviction
+/// from the front stays cheap.
+#[derive(Debug, Clone)]
+pub struct InsightStore {
+    insights: VecDeque<Insight>,
+    /// Sequence number of `insights[0]`.
+    front_seq: u64,
+    /// Node -> sequence numbers of its insights, ascending.
+    by_node: HashMap<NodeId, VecDeque<u64>>,
+    cap: usize,
+}
+
+impl Default for InsightStore {
+    fn default() -> Self {
+        Self::with_cap(MAX_INSIGHTS)
+    }
+}
+
+impl InsightStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A store that keeps at most `cap` insights (minimum 1).
+    pub fn with_cap(cap: usize) -> Self {
+        Self {
+            insights: VecDeque::new(),
+            front_seq: 0,
+            by_node: HashMap::new(),
+            cap: cap.max(1),
+        }
+    }
+
+    /// Append an insight, evicting the oldest once over the cap.
+    pub fn push(&mut self, insight: Insight) {
+        let seq = self.front_seq + self.insights.len() as u64;
+        for node in &insight.related_nodes {
+            let seqs = self.by_node.entry(*node).or_default();
+            // A node listed twice on one insight is indexed once.
+            if seqs.back() != Some(&seq) {
+                seqs.push_back(seq);
+            }
+        }
+        self.insights.push_back(insight);
+
+        while self.insights.len() > self.cap {
+            self.evict_front();
+        }
+    }
+
+    fn evict_front(&mut self) {
+        let Some(evicted) = self.insights.pop_front() else {
+            return;
+        };
+        for node in &evicted.related_nodes {
+            if let Some(seqs) = self.by_node.get_mut(node) {
+                if seqs.front() == Some(&self.front_seq) {
+                    seqs.pop_front();
+                }
+                if seqs.is_empty() {
+                    self.by_node.remove(node);
+                }
+            }
+        }
+        self.front_seq += 1;
+    }
+
+    pub fn len(&self) -> usize {
+        self.insights.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.insights.is_empty()
+    }
+
+    /// All insights, oldest first.
+    pub fn iter(&self) -> std::collections::vec_deque::Iter<'_, Insight> {
+        self.insights.iter()
+    }
+
+    /// The newest insight.
+    pub fn back(&self) -> Option<&Insight> {
+        self.insights.back()
+    }
+
+    /// Insights matching `query`, newest first, paged by `offset`/`limit`.
+    pub fn query(&self, query: &InsightQuery) -> InsightPage {
+        let candidates: Box<dyn Iterator<Item = &Insight>> = match query.related_node {
+            Some(node) => {
+                let seqs = self.by_node.get(&node).into_iter().flatten();
+                Box::new(seqs.rev().map(|seq| &self.insights[(seq - self.front_seq) as usize]))
+            }
+            None => Box::new(self.insights.iter().rev()),
+        };
+
+        let mut total = 0;
+        let mut insights = Vec::new();
+        for insight in candidates.filter(|i| matches(i, query)) {
+            if total >= query.offset && query.limit.is_none_or(|limit| insights.len() < limit) {
+                insights.push(insight.clone());
+            }
+            total += 1;
+        }
+        InsightPage { insights, total }
+    }
+}
+
+fn matches(insight: &Insight, query: &InsightQuery) -> bool {
+    query.min_severity.is_none_or(|min| insight.severity >= min)
+        && query.since.is_none_or(|since| insight.created_at >= since)
+        && query.until.is_none_or(|until| insight.created_at < until)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use uuid::Uuid;
+
+    fn insight(id: &str, severity: InsightSeverity, minutes_ago: i64, nodes: &[NodeId]) -> Insight {
+        Insight {
+            id: id.to_string(),
+            title: id.to_string(),
+            description: String::new(),
+            severity,
+            created_at: Utc::now() - Duration::minutes(minutes_ago),
+            related_nodes: nodes.to_vec(),
+        }
+    }
+
+    fn ids(page: &InsightPage) -> Vec<&str> {
+        page.insights.iter().map(|i| i.id.as_str()).collect()
+    }
+
+    #[test]
+    fn severity_and_time_filters() {
+        let mut store = InsightStore::new();
+        store.push(insight("old-crit", InsightSeverity::Critical, 120, &[]));
+        store.push(insight("info", InsightSeverity::Info, 30, &[]));
+        store.push(insight("warn", InsightSeverity::Warning, 20, &[]));
+        store.push(insight("crit", InsightSeverity::Critical, 10, &[]));
+
+        let critical = store.query(&InsightQuery { min_severity: Some(InsightSeverity::Critical), ..Default::default() });
+        assert_eq!(ids(&critical), vec!["crit", "old-crit"]);
+
+        let last_hour = InsightQuery {
+            min_severity: Some(InsightSeverity::Warning),
+            since: Some(Utc::now() - Duration::hours(1)),
+            ..Default::default()
+        };
+        assert_eq!(ids(&store.query(&last_hour)), vec!["crit", "warn"]);
+
+        let paged = store.query(&InsightQuery { offset: 1, limit: Some(2), ..Default::default() });
+        assert_eq!(ids(&paged), vec!["warn", "info"]);
+        assert_eq!(paged.total, 4);
+    }
+
+    #[test]
+    fn related_node_lookup_survives_eviction() {
+        let (m42, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut store = InsightStore::with_cap(3);
+        store.push(insight("a", InsightSeverity::Critical, 4, &[m42]));
+        store.push(insight("b", InsightSeverity::Warning, 3, &[other]));
+        store.push(insight("c", InsightSeverity::Critical, 2, &[m42, other, m42]));
+        store.push(insight("d", InsightSeverity::Info, 1, &[m42]));
+
+        // "a" was evicted by the cap.
+        assert_eq!(store.len(), 3);
+        let for_m42 = store.query(&InsightQuery { related_node: Some(m42), ..Default::default() });
+        assert_eq!(ids(&for_m42), vec!["d", "c"]);
+
+        let critical_m42 = InsightQuery {
+            related_node: Some(m42),
+            min_severity: Some(InsightSeverity::Critical),
+            ..Default::default()
+        };
+        assert_eq!(ids(&store.query(&critical_m42)), vec!["c"]);
+        assert_eq!(store.query(&InsightQuery { related_node: Some(Uuid::new_v4()), ..Default::default() }).total, 0);
+
+        for i in 0..3 {
+            store.push(insight(&format!("n{i}"), InsightSeverity::Info, 0, &[]));
+        }
+        assert!(store.by_node.is_empty());
+    }
+}
@@ -6,6 +6,7 @@
 //!
 //! See `docs/compute/scheduler.md` for the full design.
 
+pub mod insights;
 pub mod metrics;
 pub mod runner;
 pub mod state;
@@ -13,9 +14,13 @@ pub mod task;
 pub mod tasks;
 pub mod types;
 
+pub use insights::{InsightPage, InsightQuery, InsightStore};
 pub use metrics::SchedulerMetrics;
 pub use runner::Scheduler;
-pub use state::{KnowledgeState, SharedKnowledgeState, new_shared_state};
+pub use state::{
+    KnowledgeSnapshot, KnowledgeState, SharedKnowledgeState, SharedSnapshotCell, SnapshotCell,
+    new_shared_state,
+};
 pub use task::{ComputeError, ComputeTask};
 pub use tasks::{AnomalyDetectionTask, CommunityDetectionTask, DegreeCentralityTask, FullKmeansTask, PageRankTask};
 pub use types::{
@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc};
 use tracing::info;
 
 use crate::scheduler::metrics::SchedulerMetrics;
-use crate::scheduler::state::SharedKnowledgeState;
+use crate::scheduler::state::{SharedKnowledgeState, SharedSnapshotCell, SnapshotCell};
 use crate::scheduler::task::ComputeTask;
 use crate::scheduler::types::{SchedulerConfig, TaskDependency};
 
@@ -20,6 +20,8 @@ pub struct Scheduler {
     pub(super) dependencies: Vec<TaskDependency>,
     /// Shared knowledge state written to by tasks.
     pub(super) state: SharedKnowledgeState,
+    /// Point-in-time snapshot of `state`, invalidated after each task.
+    pub(super) snapshots: SharedSnapshotCell,
     /// Scheduler metrics.
     pub(super) metrics: Arc<RwLock<SchedulerMetrics>>,
     /// Last run time per task name.
@@ -35,11 +37,13 @@ pub struct Scheduler {
 impl Scheduler {
     /// Create a new scheduler with the given config and shared state.
     pub fn new(config: SchedulerConfig, state: SharedKnowledgeState) -> Self {
+        let snapshots = Arc::new(SnapshotCell::new(Arc::clone(&state)));
         Self {
             config,
             registered_tasks: Vec::new(),
             dependencies: Vec::new(),
             state,
+            snapshots,
             metrics: Arc::new(RwLock::new(SchedulerMetrics::default())),
             last_run: Arc::new(RwLock::new(HashMap::new())),
             ingest_queue_depth: Arc::new(AtomicUsize::new(0)),
@@ -77,6 +81,11 @@ impl Scheduler {
         Arc::clone(&self.state)
     }
 
+    /// Get a handle to the published knowledge snapshot.
+    pub fn knowledge_snapshots(&self) -> SharedSnapshotCell {
+        Arc::clone(&self.snapshots)
+    }
+
     /// Signal the scheduler to stop.
     pub fn shutdown(&self) {
         info!("Scheduler shutdown requested");
@@ -11,7 +11,8 @@ use crate::scheduler::types::assess_load;
 use super::Scheduler;
 
 impl Scheduler {
-    /// Execute a P0 task immediately on the current thread.
+    /// Execute a P0 task immediately on the current thread, then invalidate
+    /// the published knowledge snapshot.
     pub fn execute_immediate(&self, task: &dyn crate::scheduler::task::ComputeTask) -> Result<(), ComputeError> {
         debug!("Executing P0 task: {}", task.name());
         let mut state = self.state.write().map_err(|e| {
@@ -19,6 +20,8 @@ impl Scheduler {
         })?;
 
         let result = task.execute(&mut state)?;
+        drop(state);
+        self.snapshots.invalidate();
 
         // Record metrics
         if let Ok(mut metrics) = self.metrics.write() {
@@ -66,6 +69,7 @@ impl Scheduler {
             // Execute runnable tasks on the thread pool
             for task in runnable {
                 let state = Arc::clone(&self.state);
+                let snapshots = Arc::clone(&self.snapshots);
                 let metrics = Arc::clone(&self.metrics);
                 let last_run = Arc::clone(&self.last_run);
                 let active_workers = Arc::clone(&self.active_workers);
@@ -88,6 +92,7 @@ impl Scheduler {
                     match result {
                         Ok(r) => {
                             debug!("Task {} completed in {:?}", task.name(), r.duration);
+                            snapshots.invalidate();
                             if let Ok(mut m) = metrics.write() {
                                 m.record_execution(task.name(), r.duration);
                             }
@@ -234,4 +234,106 @@ mod tests {
         let runnable = scheduler.collect_runnable(LoadLevel::Normal);
         assert!(runnable.is_empty(), "P2 shouldn't run with only 2 available workers");
     }
+
+    /// Task that stamps every node's pagerank and community with the same
+    /// version number, one field after the other.
+    struct VersionStampTask {
+        version: AtomicUsize,
+    }
+
+    impl ComputeTask for VersionStampTask {
+        fn name(&self) -> &str { "version_stamp" }
+        fn priority(&self) -> Priority { Priority::P0 }
+        fn estimated_duration(&self) -> Duration { Duration::from_millis(1) }
+
+        fn execute(&self, state: &mut KnowledgeState) -> Result<ComputeResult, ComputeError> {
+            let version = self.version.fetch_add(1, Ordering::Relaxed) + 1;
+            for i in 0..200u128 {
+                state.pagerank.insert(uuid::Uuid::from_u128(i), version as f64);
+            }
+            for i in 0..200u128 {
+                state.communities.insert(uuid::Uuid::from_u128(i), version as u64);
+            }
+            Ok(ComputeResult {
+                task_name: self.name().to_string(),
+                duration: Duration::from_millis(1),
+                items_processed: 200,
+                summary: None,
+            })
+        }
+
+        fn should_run(&self, _last_run: Option<DateTime<Utc>>, _state: &KnowledgeState) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn snapshot_readers_never_see_partial_update() {
+        let state = new_shared_state();
+        let scheduler = Arc::new(Scheduler::new(SchedulerConfig::default(), state));
+        let snapshots = scheduler.knowledge_snapshots();
+        assert!(snapshots.load().pagerank.is_empty());
+
+        let writer = {
+            let scheduler = Arc::clone(&scheduler);
+            std::thread::spawn(move || {
+                let task = VersionStampTask { version: AtomicUsize::new(0) };
+                for _ in 0..200 {
+                    scheduler.execute_immediate(&task).unwrap();
+                }
+            })
+        };
+
+        let readers: Vec<_> = (0..4)
+            .map(|_| {
+                let snapshots = Arc::clone(&snapshots);
+                std::thread::spawn(move || {
+                    let mut last_seen = 0u64;
+                    loop {
+                        let snap = snapshots.load();
+                        let versions: std::collections::HashSet<u64> = snap
+                            .pagerank
+                            .values()
+                            .map(|v| *v as u64)
+                            .chain(snap.communities.values().copied())
+                            .collect();
+                        assert!(versions.len() <= 1, "torn snapshot: {:?}", versions);
+                        let version = versions.into_iter().next().unwrap_or(0);
+                        assert!(version >= last_seen, "snapshot went backwards");
+                        last_seen = version;
+                        if version == 200 {
+                            break;
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        writer.join().unwrap();
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        let snap = snapshots.load();
+        assert_eq!(snap.pagerank.len(), 200);
+        assert_eq!(snap.communities.len(), 200);
+    }
+
+    #[test]
+    fn snapshot_is_only_copied_when_read_after_a_task() {
+        let scheduler = Scheduler::new(SchedulerConfig::default(), new_shared_state());
+        let snapshots = scheduler.knowledge_snapshots();
+        let task = VersionStampTask { version: AtomicUsize::new(0) };
+
+        let first = snapshots.load();
+        assert!(Arc::ptr_eq(&first, &snapshots.load()), "unchanged state must not be copied again");
+
+        for _ in 0..3 {
+            scheduler.execute_immediate(&task).unwrap();
+        }
+        let latest = snapshots.load();
+        assert!(!Arc::ptr_eq(&first, &latest));
+        assert!(latest.pagerank.values().all(|v| *v == 3.0));
+        assert!(Arc::ptr_eq(&latest, &snapshots.load()));
+    }
 }
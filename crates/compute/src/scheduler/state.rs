@@ -1,4 +1,5 @@
-use std::collections::{HashMap, VecDeque};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
 use stupid_core::{EntityType, NodeId};
@@ -7,16 +8,17 @@ use crate::algorithms::degree::DegreeInfo;
 use crate::algorithms::prefixspan::TemporalPattern as PrefixSpanPattern;
 use crate::pipeline::cooccurrence::CooccurrenceMatrix;
 
+use super::insights::InsightStore;
 use super::types::{
-    AnomalyScore, ClusterId, ClusterInfo, CommunityId, Insight, SparseMatrix, TemporalPattern,
-    Trend,
+    AnomalyScore, ClusterId, ClusterInfo, CommunityId, SparseMatrix, TemporalPattern, Trend,
 };
 
 /// Shared materialized knowledge produced by compute tasks.
 ///
 /// All compute results are written here. The dashboard and query engine
-/// read from this state via the `Arc<RwLock<_>>` wrapper.
-#[derive(Debug, Default)]
+/// read from this state via the `Arc<RwLock<_>>` wrapper, or from a
+/// published [`KnowledgeSnapshot`] when they need a coherent point-in-time view.
+#[derive(Debug, Default, Clone)]
 pub struct KnowledgeState {
     /// Cluster assignments: member -> cluster_id
     pub clusters: HashMap<NodeId, ClusterId>,
@@ -40,10 +42,78 @@ pub struct KnowledgeState {
     pub cooccurrence_pmi: HashMap<(EntityType, EntityType), CooccurrenceMatrix>,
     /// Trends: metric name -> trend data.
     pub trends: HashMap<String, Trend>,
-    /// Proactive insights queue (newest at back).
-    pub insights: VecDeque<Insight>,
+    /// Proactive insights (newest at back), capped and queryable.
+    pub insights: InsightStore,
 }
 
+impl KnowledgeState {
+    /// PageRank and community assignments for graph export, omitting
+    /// whichever hasn't been computed yet.
+    pub fn node_metrics(&self) -> stupid_graph::NodeMetrics<'_> {
+        stupid_graph::NodeMetrics {
+            pagerank: (!self.pagerank.is_empty()).then_some(&self.pagerank),
+            community: (!self.communities.is_empty()).then_some(&self.communities),
+        }
+    }
+
+    /// Freeze a copy of the current state for point-in-time reads.
+    pub fn snapshot(&self) -> KnowledgeSnapshot {
+        Arc::new(self.clone())
+    }
+}
+
+/// Immutable point-in-time copy of [`KnowledgeState`].
+pub type KnowledgeSnapshot = Arc<KnowledgeState>;
+
+/// The most recently published [`KnowledgeSnapshot`].
+///
+/// The scheduler marks the snapshot stale after each completed task; the copy
+/// is only taken on the next [`load`](Self::load), so tasks that finish with
+/// no reader in between don't pay for cloning the state. Readers keep a
+/// consistent view for as long as they hold the returned `Arc`, however many
+/// tasks complete in the meantime.
+#[derive(Debug)]
+pub struct SnapshotCell {
+    source: SharedKnowledgeState,
+    current: RwLock<KnowledgeSnapshot>,
+    stale: AtomicBool,
+}
+
+impl SnapshotCell {
+    /// A cell publishing snapshots of `source`.
+    pub fn new(source: SharedKnowledgeState) -> Self {
+        Self {
+            source,
+            current: RwLock::default(),
+            stale: AtomicBool::new(true),
+        }
+    }
+
+    /// The latest published snapshot, taken now if `source` changed since.
+    pub fn load(&self) -> KnowledgeSnapshot {
+        if self.stale.load(Ordering::Acquire) {
+            let mut current = self.current.write().expect("snapshot lock poisoned");
+            // Another reader may have refreshed it while we waited.
+            if self.stale.swap(false, Ordering::AcqRel) {
+                match self.source.read() {
+                    Ok(state) => *current = state.snapshot(),
+                    Err(e) => tracing::error!("Failed to snapshot knowledge state: {}", e),
+                }
+            }
+            return Arc::clone(&current);
+        }
+        Arc::clone(&self.current.read().expect("snapshot lock poisoned"))
+    }
+
+    /// Mark the published snapshot out of date after `source` changed.
+    pub fn invalidate(&self) {
+        self.stale.store(true, Ordering::Release);
+    }
+}
+
+/// Thread-safe handle to the published knowledge snapshot.
+pub type SharedSnapshotCell = Arc<SnapshotCell>;
+
 /// Thread-safe handle to shared knowledge state.
 pub type SharedKnowledgeState = Arc<RwLock<KnowledgeState>>;
 
@@ -15,7 +15,7 @@ use crate::scheduler::types::{ComputeResult, Insight, InsightSeverity, Priority}
 ///
 /// The actual multi-signal scoring happens in the pipeline's warm compute
 /// stage via `multi_signal_score_all`. This task complements it by
-/// periodically generating insights and capping the insight queue.
+/// periodically generating insights; the insight store caps itself.
 pub struct AnomalyDetectionTask {
     interval: Duration,
 }
@@ -78,13 +78,7 @@ impl ComputeTask for AnomalyDetectionTask {
                     related_nodes: vec![*member_id],
                 };
 
-                state.insights.push_back(insight);
-            }
-
-            // Cap insight queue.
-            const MAX_INSIGHTS: usize = 10_000;
-            while state.insights.len() > MAX_INSIGHTS {
-                state.insights.pop_front();
+                state.insights.push(insight);
             }
         }
 
@@ -1,44 +1,49 @@
+use std::sync::{Arc, Mutex, TryLockError};
 use std::time::{Duration, Instant};
 
 use chrono::{DateTime, Utc};
 use tracing::info;
 
-use crate::algorithms::kmeans::optimal_kmeans;
+use crate::pipeline::Pipeline;
 use crate::scheduler::state::KnowledgeState;
 use crate::scheduler::task::{ComputeError, ComputeTask};
-use crate::scheduler::types::{ClusterInfo, ComputeResult, Priority};
+use crate::scheduler::types::{ComputeResult, Priority};
+
+/// Shared pipeline handle (matches server's SharedPipeline type).
+type SharedPipeline = Arc<Mutex<Pipeline>>;
+
+/// Members needed before a full run is worth doing.
+const DEFAULT_MIN_FEATURES: usize = 100;
 
 /// Full batch K-means recompute as a schedulable P3 task.
 ///
-/// Collects all existing cluster centroids and feature data from
-/// `KnowledgeState`, runs `optimal_kmeans` with silhouette-based K
-/// selection, and writes updated cluster assignments and metadata back.
-///
-/// This is a daily background task complementing the P0 streaming K-means.
+/// Runs Lloyd's algorithm over every member feature vector the pipeline
+/// has accumulated and re-seeds the P0 streaming K-means with the
+/// resulting centroids and assignments, undoing drift from online updates.
+/// Skips when fewer than `min_features` members are tracked.
 pub struct FullKmeansTask {
+    pipeline: SharedPipeline,
     interval: Duration,
-    k_range: std::ops::Range<usize>,
+    min_features: usize,
     max_iterations: usize,
 }
 
 impl FullKmeansTask {
-    pub fn new(interval: Duration) -> Self {
-        Self {
-            interval,
-            k_range: 2..20,
-            max_iterations: 100,
-        }
+    pub fn new(pipeline: SharedPipeline, interval: Duration) -> Self {
+        Self::with_params(pipeline, interval, DEFAULT_MIN_FEATURES, 100)
     }
 
-    /// Create with custom K range and iteration limit.
+    /// Create with a custom member threshold and iteration limit.
     pub fn with_params(
+        pipeline: SharedPipeline,
         interval: Duration,
-        k_range: std::ops::Range<usize>,
+        min_features: usize,
         max_iterations: usize,
     ) -> Self {
         Self {
+            pipeline,
             interval,
-            k_range,
+            min_features,
             max_iterations,
         }
     }
@@ -60,72 +65,36 @@ impl ComputeTask for FullKmeansTask {
     fn execute(&self, state: &mut KnowledgeState) -> Result<ComputeResult, ComputeError> {
         let start = Instant::now();
 
-        // Collect feature vectors from cluster_info centroids.
-        // Each existing cluster centroid becomes a "point" keyed by a synthetic NodeId.
-        // In production, this would be fed real member feature vectors from the pipeline.
-        let points: Vec<_> = state
-            .cluster_info
-            .iter()
-            .filter(|(_, info)| !info.centroid.is_empty())
-            .map(|(&_cid, info)| {
-                // Use a deterministic NodeId derived from cluster id.
-                let id = uuid::Uuid::from_u128(info.id as u128);
-                (id, info.centroid.clone())
-            })
-            .collect();
-
-        if points.len() < 2 {
+        // The scheduler already holds the knowledge lock, while ingest paths
+        // lock the pipeline first; waiting here could deadlock.
+        let mut pipeline = match self.pipeline.try_lock() {
+            Ok(pipeline) => pipeline,
+            Err(TryLockError::WouldBlock) => {
+                return Err(ComputeError::Skipped("pipeline is busy".to_string()))
+            }
+            Err(TryLockError::Poisoned(e)) => return Err(ComputeError::LockPoisoned(e.to_string())),
+        };
+
+        let member_count = pipeline.member_count();
+        if member_count < self.min_features {
             return Err(ComputeError::Skipped(format!(
-                "Not enough data points for K-means ({} found, need >= 2)",
-                points.len()
+                "Not enough members for full K-means ({} found, need >= {})",
+                member_count, self.min_features
             )));
         }
 
-        let k_range_end = self.k_range.end.min(points.len());
-        let k_range = self.k_range.start..k_range_end;
-
-        if k_range.is_empty() {
-            return Err(ComputeError::Skipped(
-                "K range is empty after clamping to data size".to_string(),
-            ));
-        }
-
-        let result = optimal_kmeans(&points, k_range, self.max_iterations);
-
-        // Write results back to KnowledgeState.
-        state.clusters.clear();
-        state.cluster_info.clear();
-
-        for (id, cluster_id) in &result.assignments {
-            state.clusters.insert(*id, *cluster_id);
-        }
-
-        for (cluster_idx, centroid) in result.centroids.iter().enumerate() {
-            let cid = cluster_idx as u64;
-            let member_count = result
-                .assignments
-                .values()
-                .filter(|&&c| c == cid)
-                .count();
-
-            state.cluster_info.insert(
-                cid,
-                ClusterInfo {
-                    id: cid,
-                    centroid: centroid.clone(),
-                    member_count,
-                    label: None,
-                },
-            );
-        }
+        let Some(result) = pipeline.reseed_kmeans(self.max_iterations, state) else {
+            return Err(ComputeError::Skipped(format!(
+                "Fewer members ({member_count}) than clusters"
+            )));
+        };
 
         let duration = start.elapsed();
-        let point_count = points.len();
 
         info!(
-            "Full K-means: k={}, {} points, {} iterations, inertia={:.2} ({:.1}s)",
+            "Full K-means: k={}, {} members, {} iterations, inertia={:.2} ({:.1}s)",
             result.k,
-            point_count,
+            member_count,
             result.iterations,
             result.inertia,
             duration.as_secs_f64()
@@ -134,24 +103,18 @@ impl ComputeTask for FullKmeansTask {
         Ok(ComputeResult {
             task_name: self.name().to_string(),
             duration,
-            items_processed: point_count,
+            items_processed: member_count,
             summary: Some(format!(
-                "K-means converged: k={}, {} points, {} iterations",
-                result.k, point_count, result.iterations
+                "Re-seeded streaming K-means: k={}, {} members, {} iterations",
+                result.k, member_count, result.iterations
             )),
         })
     }
 
     fn should_run(&self, last_run: Option<DateTime<Utc>>, state: &KnowledgeState) -> bool {
-        // Need at least 2 cluster_info entries with centroids.
-        let has_data = state
-            .cluster_info
-            .values()
-            .filter(|info| !info.centroid.is_empty())
-            .count()
-            >= 2;
-
-        if !has_data {
+        // Cluster assignments track pipeline members, so this avoids
+        // locking the pipeline just to decide.
+        if state.clusters.len() < self.min_features {
             return false;
         }
 
@@ -164,3 +127,77 @@ impl ComputeTask for FullKmeansTask {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use stupid_core::{Document, FieldValue};
+
+    use crate::algorithms::kmeans::kmeans;
+
+    /// Members in two behavioural groups: a few logins, or many game opens.
+    fn docs() -> Vec<Document> {
+        let mut docs = Vec::new();
+        for m in 0..12 {
+            let (event, count) = if m % 2 == 0 { ("login", 1 + m % 3) } else { ("gameOpen", 20 + m) };
+            for _ in 0..count {
+                let mut fields = HashMap::new();
+                fields.insert("memberCode".to_string(), FieldValue::Text(format!("M{m:03}")));
+                fields.insert("gameName".to_string(), FieldValue::Text(format!("game{}", m % 4)));
+                docs.push(Document {
+                    id: uuid::Uuid::new_v4(),
+                    timestamp: Utc::now(),
+                    event_type: event.to_string(),
+                    fields,
+                });
+            }
+        }
+        docs
+    }
+
+    #[test]
+    fn full_run_reseeds_streaming_centroids_with_batch_result() {
+        let pipeline = Arc::new(Mutex::new(Pipeline::with_k(2)));
+        let mut state = KnowledgeState::default();
+        pipeline.lock().unwrap().hot_connect(&docs(), &mut state);
+
+        // Expected: Lloyd's over the same static features, in the same order.
+        let expected = {
+            let p = pipeline.lock().unwrap();
+            let mut points: Vec<_> = p
+                .features
+                .members()
+                .map(|id| (*id, p.features.to_feature_vector(id).unwrap()))
+                .collect();
+            points.sort_by_key(|(id, _)| *id);
+            kmeans(&points, 2, 100)
+        };
+
+        let task = FullKmeansTask::with_params(pipeline.clone(), Duration::from_secs(3600), 10, 100);
+        assert!(task.should_run(None, &state));
+        let result = task.execute(&mut state).unwrap();
+        assert_eq!(result.items_processed, 12);
+
+        let p = pipeline.lock().unwrap();
+        assert_eq!(p.centroids(), expected.centroids.as_slice());
+        for (id, cluster) in &expected.assignments {
+            assert_eq!(state.clusters.get(id), Some(cluster));
+        }
+        for (i, centroid) in expected.centroids.iter().enumerate() {
+            assert_eq!(&state.cluster_info[&(i as u64)].centroid, centroid);
+        }
+    }
+
+    #[test]
+    fn skips_below_feature_threshold() {
+        let pipeline = Arc::new(Mutex::new(Pipeline::with_k(2)));
+        let mut state = KnowledgeState::default();
+        pipeline.lock().unwrap().hot_connect(&docs(), &mut state);
+
+        let task = FullKmeansTask::with_params(pipeline, Duration::from_secs(3600), 50, 100);
+        assert!(!task.should_run(None, &state));
+        assert!(matches!(task.execute(&mut state), Err(ComputeError::Skipped(_))));
+    }
+}
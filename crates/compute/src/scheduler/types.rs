@@ -153,6 +153,33 @@ pub struct AnomalyResult {
     pub classification: AnomalyClassification,
     /// Per-detector signal breakdown: (detector_name, raw_score).
     pub signals: Vec<(String, f64)>,
+    /// Why the member scored as it did.
+    #[serde(default)]
+    pub explanation: AnomalyExplanation,
+}
+
+/// The signals and feature dimensions that drove an anomaly score.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AnomalyExplanation {
+    /// Detector with the largest weighted contribution to the score;
+    /// `None` when every signal is zero.
+    pub dominant_signal: Option<String>,
+    /// Feature dimensions furthest from the member's baseline, largest
+    /// |z-score| first.
+    pub top_features: Vec<FeatureContribution>,
+}
+
+/// One feature dimension's deviation from a member's baseline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeatureContribution {
+    /// Feature name, e.g. `login_count`.
+    pub feature: String,
+    /// The member's value.
+    pub value: f64,
+    /// Cluster centroid value, or population mean for unclustered members.
+    pub baseline: f64,
+    /// Signed deviation in standard deviations: positive is above baseline.
+    pub z_score: f64,
 }
 
 /// Anomaly classification based on combined score thresholds.
@@ -201,6 +228,28 @@ pub struct Trend {
     pub magnitude: f64,
     pub baseline: f64,
     pub current: f64,
+    /// Baseline observations the trend was measured against.
+    #[serde(default)]
+    pub sample_count: usize,
+    /// Standard error of the baseline mean.
+    #[serde(default)]
+    pub standard_error: f64,
+    /// 95% confidence interval of the baseline mean.
+    #[serde(default)]
+    pub confidence_interval: ConfidenceInterval,
+}
+
+/// Lower and upper bounds of a confidence interval.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct ConfidenceInterval {
+    pub lower: f64,
+    pub upper: f64,
+}
+
+impl ConfidenceInterval {
+    pub fn width(&self) -> f64 {
+        self.upper - self.lower
+    }
 }
 
 /// Direction of a trend.
@@ -222,8 +271,8 @@ pub struct Insight {
     pub related_nodes: Vec<NodeId>,
 }
 
-/// Severity of an insight.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+/// Severity of an insight, ordered from least to most severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum InsightSeverity {
     Info,
     Warning,
@@ -159,17 +159,7 @@ fn link_affiliate(doc: &Document, graph: &mut GraphStore, segment_id: &SegmentId
 }
 
 fn get_field<'a>(doc: &'a Document, name: &str) -> Option<&'a str> {
-    doc.fields.get(name).and_then(|v| match v {
-        FieldValue::Text(s) => {
-            let trimmed = s.trim();
-            if trimmed.is_empty() || trimmed == "None" || trimmed == "null" || trimmed == "undefined" {
-                None
-            } else {
-                Some(trimmed)
-            }
-        }
-        _ => None,
-    })
+    doc.field_str(name)
 }
 
 fn get_affiliate(doc: &Document) -> Option<&str> {
@@ -1,5 +1,6 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
 use uuid::Uuid;
 
@@ -18,6 +19,152 @@ pub struct Document {
     pub fields: HashMap<String, FieldValue>,
 }
 
+impl Document {
+    /// Stable SHA-256 of the document's content: its event type and every
+    /// field, in key order.
+    ///
+    /// The ID is left out, and so is `timestamp`: sources that carry an event
+    /// time do so in a field (hashed with the rest), and documents without
+    /// one are stamped with the import time, which differs on every run.
+    /// Equal content hashes the same across runs, platforms and releases.
+    pub fn content_hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hash_str(&mut hasher, &self.event_type);
+        let mut keys: Vec<&String> = self.fields.keys().collect();
+        keys.sort();
+        for key in keys {
+            hash_str(&mut hasher, key);
+            match &self.fields[key] {
+                FieldValue::Text(s) => {
+                    hasher.update([1]);
+                    hash_str(&mut hasher, s);
+                }
+                FieldValue::Integer(i) => {
+                    hasher.update([2]);
+                    hasher.update(i.to_le_bytes());
+                }
+                FieldValue::Float(f) => {
+                    hasher.update([3]);
+                    // -0.0 and 0.0 are the same value.
+                    hasher.update((f + 0.0).to_bits().to_le_bytes());
+                }
+                FieldValue::Boolean(b) => hasher.update([4, *b as u8]),
+                FieldValue::Null => hasher.update([0]),
+            }
+        }
+        hasher.finalize().into()
+    }
+
+    /// Document ID derived from [`content_hash`](Self::content_hash), so
+    /// importing the same source event twice yields the same ID. The result
+    /// is a version 8 (custom) UUID built from the first 16 hash bytes.
+    pub fn content_id(&self) -> DocId {
+        let hash = self.content_hash();
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&hash[..16]);
+        uuid::Builder::from_custom_bytes(bytes).into_uuid()
+    }
+
+    /// A field's trimmed text, treating missing fields, non-text values and
+    /// null sentinels alike as absent. See [`FieldValue::as_clean_str`].
+    pub fn field_str(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).and_then(FieldValue::as_clean_str)
+    }
+
+    /// Parse a source timestamp into UTC.
+    ///
+    /// Accepts:
+    /// - epoch seconds, milliseconds, microseconds or nanoseconds (told apart
+    ///   by magnitude), optionally with a fractional part (`1718366400.5`)
+    /// - RFC 3339 / ISO 8601 with an offset (`2025-06-14T12:00:00+08:00`,
+    ///   `...Z`), converted to UTC
+    /// - ISO date-times without an offset, with `T` or a space separator and
+    ///   optional fractional seconds, taken as UTC
+    /// - plain dates (`2025-06-14`, `20250614`), as midnight UTC
+    ///
+    /// Returns `None` for anything else.
+    pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+        let value = value.trim();
+        if value.is_empty() {
+            return None;
+        }
+
+        // Compact dates would otherwise read as epoch seconds in 1970.
+        if value.len() == 8 && value.bytes().all(|b| b.is_ascii_digit()) {
+            if let Ok(date) = NaiveDate::parse_from_str(value, "%Y%m%d") {
+                return Some(date.and_hms_opt(0, 0, 0)?.and_utc());
+            }
+        }
+
+        if let Some(ts) = parse_epoch(value) {
+            return Some(ts);
+        }
+
+        if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+            return Some(ts.with_timezone(&Utc));
+        }
+        for format in ["%Y-%m-%d %H:%M:%S%.f%:z", "%Y-%m-%d %H:%M:%S%.f%z", "%Y-%m-%dT%H:%M:%S%.f%z"] {
+            if let Ok(ts) = DateTime::parse_from_str(value, format) {
+                return Some(ts.with_timezone(&Utc));
+            }
+        }
+        for format in ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M"] {
+            if let Ok(ts) = NaiveDateTime::parse_from_str(value, format) {
+                return Some(ts.and_utc());
+            }
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|ts| ts.and_utc())
+    }
+}
+
+/// Feed a length-prefixed string, so adjacent strings can't run together
+/// (`"ab" + "c"` vs `"a" + "bc"`).
+fn hash_str(hasher: &mut Sha256, s: &str) {
+    hasher.update((s.len() as u64).to_le_bytes());
+    hasher.update(s.as_bytes());
+}
+
+/// Parse an epoch timestamp, inferring the unit from its magnitude: values
+/// below 10^11 are seconds (up to year 5138), then milliseconds, microseconds
+/// and nanoseconds.
+fn parse_epoch(value: &str) -> Option<DateTime<Utc>> {
+    let (int_part, frac_part) = match value.split_once('.') {
+        Some((i, f)) => (i, Some(f)),
+        None => (value, None),
+    };
+    let digits = int_part.strip_prefix('-').unwrap_or(int_part);
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if let Some(frac) = frac_part {
+        if frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        // Fractional values are only accepted as seconds.
+        let secs: f64 = value.parse().ok()?;
+        return DateTime::from_timestamp_micros((secs * 1e6).round() as i64);
+    }
+
+    let n: i64 = value.parse().ok()?;
+    match n.unsigned_abs() {
+        0..=99_999_999_999 => DateTime::from_timestamp(n, 0),
+        100_000_000_000..=99_999_999_999_999 => DateTime::from_timestamp_millis(n),
+        100_000_000_000_000..=99_999_999_999_999_999 => DateTime::from_timestamp_micros(n),
+        _ => Some(DateTime::from_timestamp_nanos(n)),
+    }
+}
+
+/// ISO-8601 week label of a UTC timestamp (e.g. `2025-W24`), used to bucket
+/// imported documents into weekly segments. Near the new year the ISO year can
+/// differ from the calendar year (`2024-12-30` is `2025-W01`).
+pub fn iso_week_label(ts: &DateTime<Utc>) -> String {
+    let week = ts.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
 /// Typed field values — all source data arrives as strings but we preserve type info.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum FieldValue {
@@ -28,6 +175,16 @@ pub enum FieldValue {
     Null,
 }
 
+/// Text values that source systems write for a missing value.
+pub const NULL_SENTINELS: &[&str] = &["None", "null", "undefined"];
+
+/// Whether a raw source string stands for "no value": empty after trimming,
+/// or one of [`NULL_SENTINELS`].
+pub fn is_null_sentinel(value: &str) -> bool {
+    let value = value.trim();
+    value.is_empty() || NULL_SENTINELS.contains(&value)
+}
+
 impl FieldValue {
     /// Extract as string, returning None for Null.
     pub fn as_str(&self) -> Option<&str> {
@@ -36,6 +193,67 @@ impl FieldValue {
             _ => None,
         }
     }
+
+    /// Trimmed text, or `None` for non-text values and for text that is
+    /// empty or a null sentinel (`None`, `null`, `undefined`).
+    pub fn as_clean_str(&self) -> Option<&str> {
+        self.as_str()
+            .filter(|s| !is_null_sentinel(s))
+            .map(str::trim)
+    }
+
+    /// Numeric value as `f64`. Integers widen; text is parsed after
+    /// trimming. Booleans, nulls and non-finite values are `None`.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            FieldValue::Float(f) => Some(*f),
+            FieldValue::Integer(i) => Some(*i as f64),
+            FieldValue::Text(_) => self.as_clean_str()?.parse::<f64>().ok(),
+            FieldValue::Boolean(_) | FieldValue::Null => None,
+        }
+        .filter(|f| f.is_finite())
+    }
+
+    /// Numeric value as `i64`. Floats and numeric text convert only when they
+    /// are whole numbers in range (`"42"`, `"42.0"`, `42.0`), never by truncation.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            FieldValue::Integer(i) => Some(*i),
+            FieldValue::Text(_) => {
+                let s = self.as_clean_str()?;
+                s.parse::<i64>().ok().or_else(|| whole_f64_to_i64(s.parse().ok()?))
+            }
+            FieldValue::Float(f) => whole_f64_to_i64(*f),
+            FieldValue::Boolean(_) | FieldValue::Null => None,
+        }
+    }
+
+    /// Boolean value. Text accepts `true`/`false`, `yes`/`no` and `1`/`0`
+    /// (case-insensitive); integers accept 1 and 0.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            FieldValue::Boolean(b) => Some(*b),
+            FieldValue::Integer(1) => Some(true),
+            FieldValue::Integer(0) => Some(false),
+            FieldValue::Text(_) => {
+                let s = self.as_clean_str()?;
+                if ["true", "yes", "1"].iter().any(|t| s.eq_ignore_ascii_case(t)) {
+                    Some(true)
+                } else if ["false", "no", "0"].iter().any(|t| s.eq_ignore_ascii_case(t)) {
+                    Some(false)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn whole_f64_to_i64(f: f64) -> Option<i64> {
+    // 2^63 is exactly representable; anything at or beyond it overflows i64.
+    const TWO_POW_63: f64 = 9_223_372_036_854_775_808.0;
+    (f.fract() == 0.0 && (-TWO_POW_63..TWO_POW_63).contains(&f)).then_some(f as i64)
 }
 
 /// Address of a document in storage.
@@ -44,3 +262,203 @@ pub struct DocAddress {
     pub segment_id: SegmentId,
     pub offset: u64,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32, sec: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, sec).unwrap()
+    }
+
+    #[test]
+    fn parses_epoch_units_by_magnitude() {
+        let expected = utc(2024, 6, 14, 12, 0, 0);
+        assert_eq!(Document::parse_timestamp("1718366400"), Some(expected));
+        assert_eq!(Document::parse_timestamp("1718366400000"), Some(expected));
+        assert_eq!(Document::parse_timestamp("1718366400000000"), Some(expected));
+        assert_eq!(Document::parse_timestamp("1718366400000000000"), Some(expected));
+        assert_eq!(
+            Document::parse_timestamp("1718366400.25"),
+            Some(expected + chrono::Duration::milliseconds(250))
+        );
+        assert_eq!(Document::parse_timestamp("0"), Some(utc(1970, 1, 1, 0, 0, 0)));
+    }
+
+    #[test]
+    fn parses_iso_forms_to_utc() {
+        let expected = utc(2025, 6, 14, 12, 0, 0);
+        for input in [
+            "2025-06-14T12:00:00Z",
+            "2025-06-14T12:00:00.000Z",
+            "2025-06-14T20:00:00+08:00",
+            "2025-06-14 20:00:00+08:00",
+            "2025-06-14T20:00:00+0800",
+            "2025-06-14T12:00:00",
+            "2025-06-14 12:00:00",
+            "2025-06-14T12:00",
+            "  2025-06-14T12:00:00Z  ",
+        ] {
+            assert_eq!(Document::parse_timestamp(input), Some(expected), "input {input:?}");
+        }
+        assert_eq!(
+            Document::parse_timestamp("2025-06-14 12:00:00.123456"),
+            Some(expected + chrono::Duration::microseconds(123_456))
+        );
+    }
+
+    #[test]
+    fn parses_plain_dates_as_midnight_utc() {
+        let expected = utc(2025, 6, 14, 0, 0, 0);
+        assert_eq!(Document::parse_timestamp("2025-06-14"), Some(expected));
+        assert_eq!(Document::parse_timestamp("20250614"), Some(expected));
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for input in ["", "None", "yesterday", "2025-13-01", "12:00", "1.2.3", "-", "1e9"] {
+            assert_eq!(Document::parse_timestamp(input), None, "input {input:?}");
+        }
+    }
+
+    #[test]
+    fn clean_str_treats_sentinels_as_absent() {
+        for raw in ["", "   ", "None", "null", " undefined "] {
+            assert_eq!(FieldValue::Text(raw.to_string()).as_clean_str(), None, "raw {raw:?}");
+        }
+        assert_eq!(FieldValue::Text("  alice ".into()).as_clean_str(), Some("alice"));
+        // Only exact sentinels: these are real values.
+        assert_eq!(FieldValue::Text("NULL".into()).as_clean_str(), Some("NULL"));
+        assert_eq!(FieldValue::Text("nonesuch".into()).as_clean_str(), Some("nonesuch"));
+        assert_eq!(FieldValue::Integer(3).as_clean_str(), None);
+        assert_eq!(FieldValue::Null.as_clean_str(), None);
+    }
+
+    #[test]
+    fn numeric_coercion_from_text() {
+        let text = |s: &str| FieldValue::Text(s.to_string());
+        assert_eq!(text(" 12.5 ").as_f64(), Some(12.5));
+        assert_eq!(text("42").as_f64(), Some(42.0));
+        assert_eq!(text("42").as_i64(), Some(42));
+        assert_eq!(text("-7.0").as_i64(), Some(-7));
+        assert_eq!(text("12.5").as_i64(), None, "no truncation");
+        assert_eq!(text("1e3").as_i64(), Some(1000));
+        assert_eq!(text("NaN").as_f64(), None);
+        assert_eq!(text("inf").as_f64(), None);
+        assert_eq!(text("null").as_f64(), None);
+        assert_eq!(text("abc").as_i64(), None);
+        assert_eq!(text("9223372036854775807").as_i64(), Some(i64::MAX));
+        assert_eq!(text("9223372036854775808").as_i64(), None);
+    }
+
+    #[test]
+    fn numeric_coercion_from_typed_values() {
+        assert_eq!(FieldValue::Integer(3).as_f64(), Some(3.0));
+        assert_eq!(FieldValue::Float(3.0).as_i64(), Some(3));
+        assert_eq!(FieldValue::Float(3.5).as_i64(), None);
+        assert_eq!(FieldValue::Float(f64::NAN).as_f64(), None);
+        assert_eq!(FieldValue::Boolean(true).as_f64(), None);
+        assert_eq!(FieldValue::Null.as_i64(), None);
+    }
+
+    #[test]
+    fn bool_coercion() {
+        let text = |s: &str| FieldValue::Text(s.to_string());
+        assert_eq!(text("TRUE").as_bool(), Some(true));
+        assert_eq!(text(" yes").as_bool(), Some(true));
+        assert_eq!(text("0").as_bool(), Some(false));
+        assert_eq!(text("No").as_bool(), Some(false));
+        assert_eq!(text("maybe").as_bool(), None);
+        assert_eq!(text("undefined").as_bool(), None);
+        assert_eq!(FieldValue::Integer(1).as_bool(), Some(true));
+        assert_eq!(FieldValue::Integer(2).as_bool(), None);
+        assert_eq!(FieldValue::Boolean(false).as_bool(), Some(false));
+    }
+
+    #[test]
+    fn document_field_str() {
+        let mut fields = HashMap::new();
+        fields.insert("memberCode".to_string(), FieldValue::Text(" M001 ".into()));
+        fields.insert("affiliateId".to_string(), FieldValue::Text("None".into()));
+        let doc = Document {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: "Login".into(),
+            fields,
+        };
+        assert_eq!(doc.field_str("memberCode"), Some("M001"));
+        assert_eq!(doc.field_str("affiliateId"), None);
+        assert_eq!(doc.field_str("missing"), None);
+    }
+
+    #[test]
+    fn local_time_near_new_year_lands_in_utc_iso_week() {
+        // Monday 2024-12-30 01:00 in UTC+8 is still Sunday 2024-12-29 in UTC,
+        // the last day of 2024-W52 — not 2025-W01 as the local date suggests.
+        let ts = Document::parse_timestamp("2024-12-30T01:00:00+08:00").unwrap();
+        assert_eq!(iso_week_label(&ts), "2024-W52");
+
+        let ts = Document::parse_timestamp("2024-12-30T08:00:00+08:00").unwrap();
+        assert_eq!(iso_week_label(&ts), "2025-W01");
+
+        // 2021-01-03 is a Sunday that belongs to the previous ISO year.
+        let ts = Document::parse_timestamp("2021-01-03").unwrap();
+        assert_eq!(iso_week_label(&ts), "2020-W53");
+    }
+
+    fn login(fields: &[(&str, FieldValue)]) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: "Login".into(),
+            fields: fields.iter().map(|(k, v)| (k.to_string(), v.clone())).collect(),
+        }
+    }
+
+    #[test]
+    fn content_hash_ignores_id_timestamp_and_field_order() {
+        let fields = [
+            ("memberCode", FieldValue::Text("M001".into())),
+            ("@timestamp", FieldValue::Text("2025-06-14T10:00:00Z".into())),
+            ("amount", FieldValue::Float(12.5)),
+        ];
+        let a = login(&fields);
+        let mut reversed = fields.clone();
+        reversed.reverse();
+        let mut b = login(&reversed);
+        b.timestamp = a.timestamp - chrono::Duration::hours(1);
+
+        assert_ne!(a.id, b.id);
+        assert_eq!(a.content_hash(), b.content_hash());
+        assert_eq!(a.content_id(), b.content_id());
+        assert_eq!(a.content_id().get_version_num(), 8);
+        assert_eq!(login(&[]).content_hash(), login(&[]).content_hash());
+    }
+
+    #[test]
+    fn content_hash_changes_with_any_field() {
+        let base = login(&[
+            ("memberCode", FieldValue::Text("M001".into())),
+            ("amount", FieldValue::Integer(12)),
+        ]);
+        let variants = [
+            // Changed value, type, key, event type; added and removed field.
+            login(&[("memberCode", FieldValue::Text("M002".into())), ("amount", FieldValue::Integer(12))]),
+            login(&[("memberCode", FieldValue::Text("M001".into())), ("amount", FieldValue::Text("12".into()))]),
+            login(&[("memberCode", FieldValue::Text("M001".into())), ("amount2", FieldValue::Integer(12))]),
+            Document { event_type: "Deposit".into(), ..base.clone() },
+            login(&[
+                ("memberCode", FieldValue::Text("M001".into())),
+                ("amount", FieldValue::Integer(12)),
+                ("note", FieldValue::Null),
+            ]),
+            login(&[("memberCode", FieldValue::Text("M001".into()))]),
+            // Key/value boundaries can't shift.
+            login(&[("memberCod", FieldValue::Text("eM001".into())), ("amount", FieldValue::Integer(12))]),
+        ];
+        for (i, variant) in variants.iter().enumerate() {
+            assert_ne!(base.content_hash(), variant.content_hash(), "variant {i}");
+        }
+    }
+}
@@ -18,6 +18,24 @@ pub enum EntityType {
     Provider,
 }
 
+impl EntityType {
+    /// Prefix of this entity's node keys, e.g. `device` in `device:abc`.
+    pub fn key_prefix(&self) -> &'static str {
+        match self {
+            EntityType::Member => "member",
+            EntityType::Device => "device",
+            EntityType::Game => "game",
+            EntityType::Affiliate => "affiliate",
+            EntityType::Currency => "currency",
+            EntityType::VipGroup => "vipgroup",
+            EntityType::Error => "error",
+            EntityType::Platform => "platform",
+            EntityType::Popup => "popup",
+            EntityType::Provider => "provider",
+        }
+    }
+}
+
 impl std::fmt::Display for EntityType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -35,6 +53,27 @@ impl std::fmt::Display for EntityType {
     }
 }
 
+impl std::str::FromStr for EntityType {
+    type Err = String;
+
+    /// Parse an entity type name, ignoring ASCII case (`device`, `VipGroup`, ...).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "member" => Ok(EntityType::Member),
+            "device" => Ok(EntityType::Device),
+            "game" => Ok(EntityType::Game),
+            "affiliate" => Ok(EntityType::Affiliate),
+            "currency" => Ok(EntityType::Currency),
+            "vipgroup" => Ok(EntityType::VipGroup),
+            "error" => Ok(EntityType::Error),
+            "platform" => Ok(EntityType::Platform),
+            "popup" => Ok(EntityType::Popup),
+            "provider" => Ok(EntityType::Provider),
+            other => Err(format!("unknown entity type: '{}'", other)),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum EdgeType {
     LoggedInFrom,
@@ -1,15 +1,18 @@
 use thiserror::Error;
 
+/// Underlying cause kept as an error's `source()`, whatever crate it came from.
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
 #[derive(Error, Debug)]
 pub enum StupidError {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
     #[error("Serialization error: {0}")]
-    Serialize(String),
+    Serialize(#[source] BoxError),
 
     #[error("Parquet error: {0}")]
-    Parquet(String),
+    Parquet(#[source] BoxError),
 
     #[error("Segment not found: {0}")]
     SegmentNotFound(String),
@@ -26,3 +29,24 @@ pub enum StupidError {
     #[error("{0}")]
     Other(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn io_error_is_kept_as_source() {
+        let err = StupidError::from(std::io::Error::new(std::io::ErrorKind::NotFound, "meta.json"));
+        let source = err.source().and_then(|s| s.downcast_ref::<std::io::Error>()).unwrap();
+        assert_eq!(source.kind(), std::io::ErrorKind::NotFound);
+        assert_eq!(source.to_string(), "meta.json");
+    }
+
+    #[test]
+    fn serialize_error_is_kept_as_source() {
+        let json_err = serde_json::from_str::<u32>("nope").unwrap_err();
+        let err = StupidError::Serialize(json_err.into());
+        assert!(err.source().unwrap().downcast_ref::<serde_json::Error>().is_some());
+    }
+}
@@ -0,0 +1,190 @@
+//! Event-type → graph edge mapping used when extracting entities from documents.
+//!
+//! Each document with a `memberCode` becomes a [`EntityType::Member`] node.
+//! Its event type selects a list of [`EdgeMapping`]s; every mapping whose key
+//! fields are present adds an edge from the member to the target entity.
+//! Event types without an entry are ignored.
+//!
+//! The built-in table lives in `graph_mapping.yaml` next to this file and can
+//! be replaced at startup with [`GraphMapping::load_or_builtin`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Document, EdgeType, EntityType, StupidError};
+
+/// Field holding the member code that keys the source node of every event.
+pub const MEMBER_FIELD: &str = "memberCode";
+
+const BUILTIN_MAPPING: &str = include_str!("graph_mapping.yaml");
+
+/// How one document field (or group of fields) becomes an edge.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EdgeMapping {
+    /// Candidate keys, tried in order; the first whose fields are all present wins.
+    pub fields: Vec<FieldKey>,
+    pub entity: EntityType,
+    pub edge: EdgeType,
+    /// Edges added from the target node rather than the member
+    /// (e.g. game → provider), only when the target itself was found.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub then: Vec<EdgeMapping>,
+}
+
+/// A single field, or several whose values are joined with `:`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldKey {
+    Field(String),
+    Composite(Vec<String>),
+}
+
+impl FieldKey {
+    fn names(&self) -> &[String] {
+        match self {
+            FieldKey::Field(name) => std::slice::from_ref(name),
+            FieldKey::Composite(names) => names,
+        }
+    }
+}
+
+impl EdgeMapping {
+    /// Node key of the target in `doc` (e.g. `device:abc`), if any candidate matches.
+    pub fn target_key(&self, doc: &Document) -> Option<String> {
+        self.fields.iter().find_map(|key| {
+            let values: Option<Vec<&str>> = key.names().iter().map(|f| doc.field_str(f)).collect();
+            Some(format!("{}:{}", self.entity.key_prefix(), values?.join(":")))
+        })
+    }
+}
+
+/// Edge mappings keyed by event type.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GraphMapping {
+    pub events: HashMap<String, Vec<EdgeMapping>>,
+}
+
+impl Default for GraphMapping {
+    fn default() -> Self {
+        Self::from_yaml(BUILTIN_MAPPING).expect("built-in graph mapping is valid")
+    }
+}
+
+impl GraphMapping {
+    pub fn from_yaml(yaml: &str) -> Result<Self, StupidError> {
+        serde_yaml::from_str(yaml).map_err(|e| StupidError::Serialize(e.into()))
+    }
+
+    /// Load the mapping from `path`, or the built-in one if the file doesn't exist.
+    pub fn load_or_builtin(path: &Path) -> Result<Self, StupidError> {
+        match std::fs::read_to_string(path) {
+            Ok(yaml) => Self::from_yaml(&yaml),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Edge mappings for `event_type`, or `None` if it isn't mapped.
+    pub fn edges_for(&self, event_type: &str) -> Option<&[EdgeMapping]> {
+        self.events.get(event_type).map(Vec::as_slice)
+    }
+
+    /// Every document field the mapping reads, including [`MEMBER_FIELD`].
+    pub fn fields(&self) -> Vec<&str> {
+        fn collect<'a>(mappings: &'a [EdgeMapping], out: &mut Vec<&'a str>) {
+            for mapping in mappings {
+                out.extend(mapping.fields.iter().flat_map(|k| k.names()).map(String::as_str));
+                collect(&mapping.then, out);
+            }
+        }
+
+        let mut fields = vec![MEMBER_FIELD];
+        for mappings in self.events.values() {
+            collect(mappings, &mut fields);
+        }
+        fields.sort_unstable();
+        fields.dedup();
+        fields
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FieldValue;
+    use chrono::Utc;
+
+    fn doc(fields: &[(&str, &str)]) -> Document {
+        Document {
+            id: uuid::Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: "API Error".into(),
+            fields: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), FieldValue::Text(v.to_string())))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn builtin_mapping_parses() {
+        let mapping = GraphMapping::default();
+        assert!(mapping.edges_for("Login").is_some());
+        assert!(mapping.edges_for("GridClick").is_some());
+        assert!(mapping.edges_for("Logout").is_none());
+
+        let fields = mapping.fields();
+        for field in ["memberCode", "fingerprint", "affiliateID", "gameTrackingProvider", "statusCode"] {
+            assert!(fields.contains(&field), "missing {field}");
+        }
+    }
+
+    #[test]
+    fn target_key_tries_candidates_in_order() {
+        let mapping = EdgeMapping {
+            fields: vec![
+                FieldKey::Composite(vec!["statusCode".into(), "url".into()]),
+                FieldKey::Field("url".into()),
+                FieldKey::Field("error".into()),
+            ],
+            entity: EntityType::Error,
+            edge: EdgeType::HitError,
+            then: Vec::new(),
+        };
+
+        let both = doc(&[("url", "/api/bet"), ("statusCode", "500")]);
+        assert_eq!(mapping.target_key(&both).as_deref(), Some("error:500:/api/bet"));
+        let url_only = doc(&[("url", "/api/bet"), ("error", "boom")]);
+        assert_eq!(mapping.target_key(&url_only).as_deref(), Some("error:/api/bet"));
+        let code_only = doc(&[("statusCode", "500"), ("error", "boom")]);
+        assert_eq!(mapping.target_key(&code_only).as_deref(), Some("error:boom"));
+        assert_eq!(mapping.target_key(&doc(&[("url", "null")])), None);
+    }
+
+    #[test]
+    fn yaml_accepts_single_and_composite_keys() {
+        let mapping = GraphMapping::from_yaml(
+            r#"
+events:
+  Deposit:
+    - fields: [currency]
+      entity: Currency
+      edge: UsesCurrency
+    - fields: [[gameTrackingProvider, game]]
+      entity: Game
+      edge: OpenedGame
+"#,
+        )
+        .unwrap();
+        let edges = mapping.edges_for("Deposit").unwrap();
+        assert_eq!(edges[0].fields, vec![FieldKey::Field("currency".into())]);
+        assert_eq!(
+            edges[1].fields,
+            vec![FieldKey::Composite(vec!["gameTrackingProvider".into(), "game".into()])]
+        );
+
+        assert!(GraphMapping::from_yaml("events:\n  Login:\n    - fields: [x]\n      entity: Planet\n      edge: OpenedGame\n").is_err());
+    }
+}
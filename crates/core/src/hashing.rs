@@ -0,0 +1,108 @@
+//! Stable non-cryptographic hashing and bloom-filter arithmetic.
+//!
+//! Shared by the segment bloom filters, the import dedup seen-set and the
+//! catalog's HyperLogLog sketches. All of them persist values derived from
+//! these functions, so the outputs must never change.
+
+/// FNV-1a, so keys stay stable across runs and Rust versions (unlike
+/// `DefaultHasher`), which persisted filters and sketches rely on.
+#[derive(Debug, Clone)]
+pub struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// The splitmix64 finalizer: spreads every input bit over the whole word.
+fn mix64(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// One splitmix64 step from `x`.
+pub fn splitmix64(x: u64) -> u64 {
+    mix64(x.wrapping_add(0x9e37_79b9_7f4a_7c15))
+}
+
+/// FNV-1a of `bytes` followed by the splitmix64 finalizer, which spreads
+/// FNV's weak low bits over the whole word.
+pub fn hash64(bytes: &[u8]) -> u64 {
+    let mut hasher = Fnv1a::default();
+    hasher.write(bytes);
+    mix64(hasher.finish())
+}
+
+/// Bits and hash count of a bloom filter holding `expected_items` keys at
+/// `false_positive_rate` (which callers clamp to a sane range). At least 64
+/// bits and 1..=16 hashes.
+pub fn bloom_size(expected_items: u64, false_positive_rate: f64) -> (u64, u32) {
+    let n = expected_items.max(1) as f64;
+    let ln2 = std::f64::consts::LN_2;
+    let num_bits = ((-n * false_positive_rate.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+    let num_hashes = ((num_bits as f64 / n) * ln2).round().clamp(1.0, 16.0) as u32;
+    (num_bits, num_hashes)
+}
+
+/// The `num_hashes` bit positions of `key` in a filter of `num_bits` bits,
+/// by Kirsch–Mitzenmacher double hashing from two mixes of the key.
+pub fn bloom_positions(key: u64, num_bits: u64, num_hashes: u32) -> impl Iterator<Item = u64> {
+    let h1 = splitmix64(key);
+    let h2 = splitmix64(h1) | 1;
+    (0..u64::from(num_hashes)).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % num_bits)
+}
+
+/// Set `key`'s bits in `bits` (`num_bits.div_ceil(64)` words).
+pub fn bloom_insert(bits: &mut [u64], num_bits: u64, num_hashes: u32, key: u64) {
+    for bit in bloom_positions(key, num_bits, num_hashes) {
+        bits[(bit / 64) as usize] |= 1 << (bit % 64);
+    }
+}
+
+/// Whether all of `key`'s bits are set in `bits`.
+pub fn bloom_contains(bits: &[u64], num_bits: u64, num_hashes: u32, key: u64) -> bool {
+    bloom_positions(key, num_bits, num_hashes).all(|bit| bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_are_stable() {
+        // Persisted filters and sketches depend on these exact values.
+        let mut fnv = Fnv1a::default();
+        fnv.write(b"a");
+        assert_eq!(fnv.finish(), 0xaf63_dc4c_8601_ec8c);
+        assert_eq!(splitmix64(0), 0xe220_a839_7b1d_cdaf);
+        assert_eq!(hash64(b"a"), mix64(0xaf63_dc4c_8601_ec8c));
+    }
+
+    #[test]
+    fn bloom_has_no_false_negatives() {
+        let (num_bits, num_hashes) = bloom_size(1000, 0.01);
+        assert_eq!((num_bits, num_hashes), (9586, 7));
+        let mut bits = vec![0; num_bits.div_ceil(64) as usize];
+        for key in 0..1000 {
+            bloom_insert(&mut bits, num_bits, num_hashes, key);
+        }
+        assert!((0..1000).all(|key| bloom_contains(&bits, num_bits, num_hashes, key)));
+        let false_positives = (1000..11_000).filter(|&key| bloom_contains(&bits, num_bits, num_hashes, key)).count();
+        assert!(false_positives < 300, "{false_positives} false positives");
+    }
+}
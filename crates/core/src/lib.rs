@@ -2,8 +2,11 @@ pub mod config;
 pub mod document;
 pub mod entity;
 pub mod error;
+pub mod graph_mapping;
+pub mod hashing;
 
 pub use config::Config;
 pub use document::*;
 pub use entity::*;
 pub use error::*;
+pub use graph_mapping::*;
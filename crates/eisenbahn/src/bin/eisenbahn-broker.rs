@@ -17,9 +17,11 @@
 //! ```
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use clap::Parser;
 use stupid_eisenbahn::broker::{BrokerConfig, EventBroker};
+use stupid_eisenbahn::metrics::HeartbeatThresholds;
 use stupid_eisenbahn::transport::Transport;
 
 /// Central PUB/SUB event broker for the eisenbahn messaging layer.
@@ -50,10 +52,18 @@ struct Cli {
     #[arg(long, env = "EISENBAHN_IPC_PREFIX", default_value = "broker")]
     ipc_prefix: String,
 
-    /// HTTP port for the `/metrics` JSON endpoint (0 = disabled).
+    /// HTTP port for the `/metrics` and `/health` JSON endpoints (0 = disabled).
     #[arg(long, env = "EISENBAHN_METRICS_PORT", default_value_t = 0)]
     metrics_port: u16,
 
+    /// Seconds without a worker heartbeat before `/health` reports it stale.
+    #[arg(long, env = "EISENBAHN_BROKER_STALE_AFTER_SECS", default_value_t = 90)]
+    stale_after_secs: u64,
+
+    /// Seconds without a worker heartbeat before `/health` reports it dead.
+    #[arg(long, env = "EISENBAHN_BROKER_DEAD_AFTER_SECS", default_value_t = 300)]
+    dead_after_secs: u64,
+
     /// Interval in seconds between metrics log lines (0 = disabled).
     #[arg(long, env = "EISENBAHN_METRICS_INTERVAL", default_value_t = 30)]
     metrics_interval: u64,
@@ -66,6 +76,10 @@ impl Cli {
         } else {
             None
         };
+        let heartbeat = HeartbeatThresholds {
+            stale_after: Duration::from_secs(self.stale_after_secs),
+            dead_after: Duration::from_secs(self.dead_after_secs),
+        };
         match self.transport.as_str() {
             "tcp" => {
                 let mut cfg = BrokerConfig::tcp(
@@ -75,6 +89,7 @@ impl Cli {
                     self.health_port,
                 );
                 cfg.metrics_port = metrics_port;
+                cfg.heartbeat = heartbeat;
                 cfg
             }
             _ => BrokerConfig {
@@ -82,6 +97,7 @@ impl Cli {
                 backend: Transport::ipc(&format!("{}-backend", self.ipc_prefix)),
                 health: Transport::ipc(&format!("{}-health", self.ipc_prefix)),
                 metrics_port,
+                heartbeat,
             },
         }
     }
@@ -6,7 +6,7 @@ use tokio::sync::Mutex;
 use zeromq::prelude::*;
 use zeromq::{PubSocket, RepSocket, SubSocket, ZmqMessage};
 
-use crate::metrics::MetricsCollector;
+use crate::metrics::{HeartbeatThresholds, MetricsCollector};
 use crate::messages::events::WorkerHealth;
 use crate::messages::topics::WORKER_HEALTH;
 use crate::transport::Transport;
@@ -43,8 +43,10 @@ pub struct BrokerConfig {
     pub backend: Transport,
     /// Health check endpoint (REP socket for liveness probes).
     pub health: Transport,
-    /// Optional HTTP port for the `/metrics` JSON endpoint.
+    /// Optional HTTP port for the `/metrics` and `/health` JSON endpoints.
     pub metrics_port: Option<u16>,
+    /// Heartbeat ages at which workers are reported stale or dead.
+    pub heartbeat: HeartbeatThresholds,
 }
 
 impl BrokerConfig {
@@ -55,6 +57,7 @@ impl BrokerConfig {
             backend: Transport::ipc("broker-backend"),
             health: Transport::ipc("broker-health"),
             metrics_port: None,
+            heartbeat: HeartbeatThresholds::default(),
         }
     }
 
@@ -65,6 +68,7 @@ impl BrokerConfig {
             backend: Transport::tcp(host, backend_port),
             health: Transport::tcp(host, health_port),
             metrics_port: None,
+            heartbeat: HeartbeatThresholds::default(),
         }
     }
 }
@@ -95,9 +99,9 @@ impl EventBroker {
     /// Create a new broker with the given configuration.
     pub fn new(config: BrokerConfig) -> Self {
         Self {
+            collector: MetricsCollector::with_thresholds(config.heartbeat),
             config,
             metrics: Arc::new(BrokerMetrics::new()),
-            collector: MetricsCollector::new(),
             shutdown: Arc::new(AtomicBool::new(false)),
         }
     }
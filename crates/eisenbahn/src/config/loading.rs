@@ -1,7 +1,9 @@
 use std::collections::HashMap;
 use std::path::Path;
+use std::time::Duration;
 
 use crate::error::EisenbahnError;
+use crate::metrics::HeartbeatThresholds;
 use crate::transport::Transport;
 
 use super::helpers::parse_endpoint_to_transport;
@@ -41,6 +43,7 @@ impl EisenbahnConfig {
                 frontend: format!("tcp://{broker_host}:{broker_port}"),
                 backend: format!("tcp://{broker_host}:{}", broker_port + 1),
                 metrics_port: Some(broker_port + 2),
+                ..BrokerConfig::default()
             },
             workers: HashMap::new(),
             pipeline: PipelineTopology::default(),
@@ -58,6 +61,14 @@ impl EisenbahnConfig {
         parse_endpoint_to_transport(&self.broker.frontend)
     }
 
+    /// Heartbeat thresholds for the broker's worker liveness view.
+    pub fn heartbeat_thresholds(&self) -> HeartbeatThresholds {
+        HeartbeatThresholds {
+            stale_after: Duration::from_secs(self.broker.stale_after_secs),
+            dead_after: Duration::from_secs(self.broker.dead_after_secs),
+        }
+    }
+
     /// Resolve the broker's backend transport.
     pub fn broker_backend_transport(&self) -> Transport {
         parse_endpoint_to_transport(&self.broker.backend)
@@ -88,6 +99,8 @@ impl EisenbahnConfig {
     /// - `EISENBAHN_BROKER_FRONTEND` -> `broker.frontend`
     /// - `EISENBAHN_BROKER_BACKEND` -> `broker.backend`
     /// - `EISENBAHN_BROKER_METRICS_PORT` -> `broker.metrics_port`
+    /// - `EISENBAHN_BROKER_STALE_AFTER_SECS` -> `broker.stale_after_secs`
+    /// - `EISENBAHN_BROKER_DEAD_AFTER_SECS` -> `broker.dead_after_secs`
     /// - `EISENBAHN_TRANSPORT_KIND` -> `transport.kind`
     /// - `EISENBAHN_TRANSPORT_DEFAULT_HOST` -> `transport.default_host`
     /// - `EISENBAHN_TRANSPORT_BASE_PORT` -> `transport.base_port`
@@ -103,6 +116,16 @@ impl EisenbahnConfig {
                 self.broker.metrics_port = Some(port);
             }
         }
+        if let Ok(v) = std::env::var("EISENBAHN_BROKER_STALE_AFTER_SECS") {
+            if let Ok(secs) = v.parse::<u64>() {
+                self.broker.stale_after_secs = secs;
+            }
+        }
+        if let Ok(v) = std::env::var("EISENBAHN_BROKER_DEAD_AFTER_SECS") {
+            if let Ok(secs) = v.parse::<u64>() {
+                self.broker.dead_after_secs = secs;
+            }
+        }
         if let Ok(v) = std::env::var("EISENBAHN_TRANSPORT_KIND") {
             self.transport.kind = v;
         }
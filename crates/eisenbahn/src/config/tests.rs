@@ -289,3 +289,29 @@ after = ["loop"]
         "got: {msg}"
     );
 }
+
+#[test]
+fn heartbeat_thresholds_default_and_override() {
+    let cfg = EisenbahnConfig::from_toml("[broker]\n").unwrap();
+    assert_eq!(cfg.heartbeat_thresholds(), crate::metrics::HeartbeatThresholds::default());
+
+    let toml = r#"
+[broker]
+stale_after_secs = 15
+dead_after_secs = 60
+"#;
+    let thresholds = EisenbahnConfig::from_toml(toml).unwrap().heartbeat_thresholds();
+    assert_eq!(thresholds.stale_after, std::time::Duration::from_secs(15));
+    assert_eq!(thresholds.dead_after, std::time::Duration::from_secs(60));
+}
+
+#[test]
+fn detect_stale_after_not_before_dead_after() {
+    let toml = r#"
+[broker]
+stale_after_secs = 120
+dead_after_secs = 60
+"#;
+    let err = EisenbahnConfig::from_toml(toml).unwrap_err();
+    assert!(err.to_string().contains("stale_after_secs"));
+}
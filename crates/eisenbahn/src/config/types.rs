@@ -45,6 +45,14 @@ pub struct BrokerConfig {
 
     /// Optional metrics/health endpoint port.
     pub metrics_port: Option<u16>,
+
+    /// Seconds without a worker heartbeat before it is reported stale.
+    #[serde(default = "default_stale_after_secs")]
+    pub stale_after_secs: u64,
+
+    /// Seconds without a worker heartbeat before it is reported dead.
+    #[serde(default = "default_dead_after_secs")]
+    pub dead_after_secs: u64,
 }
 
 fn default_broker_frontend() -> String {
@@ -55,12 +63,22 @@ fn default_broker_backend() -> String {
     "ipc:///tmp/stupid-db/broker-backend.sock".into()
 }
 
+fn default_stale_after_secs() -> u64 {
+    crate::metrics::DEFAULT_STALE_AFTER.as_secs()
+}
+
+fn default_dead_after_secs() -> u64 {
+    crate::metrics::DEFAULT_DEAD_AFTER.as_secs()
+}
+
 impl Default for BrokerConfig {
     fn default() -> Self {
         Self {
             frontend: default_broker_frontend(),
             backend: default_broker_backend(),
             metrics_port: None,
+            stale_after_secs: default_stale_after_secs(),
+            dead_after_secs: default_dead_after_secs(),
         }
     }
 }
@@ -9,6 +9,7 @@ impl EisenbahnConfig {
         self.validate_no_circular_dependencies()?;
         self.validate_worker_pipelines()?;
         self.validate_transport_kind()?;
+        self.validate_heartbeat_thresholds()?;
         Ok(())
     }
 
@@ -57,4 +58,16 @@ impl EisenbahnConfig {
             ))),
         }
     }
+
+    /// Ensure a worker turns stale before it is declared dead.
+    fn validate_heartbeat_thresholds(&self) -> Result<(), EisenbahnError> {
+        let broker = &self.broker;
+        if broker.stale_after_secs == 0 || broker.stale_after_secs >= broker.dead_after_secs {
+            return Err(EisenbahnError::Config(format!(
+                "broker.stale_after_secs ({}) must be positive and less than broker.dead_after_secs ({})",
+                broker.stale_after_secs, broker.dead_after_secs
+            )));
+        }
+        Ok(())
+    }
 }
@@ -27,6 +27,13 @@ pub enum EisenbahnError {
     #[error("circular pipeline dependency: {0}")]
     CircularDependency(String),
 
+    #[error("unsupported message version {version} on '{topic}' (handler accepts {supported})")]
+    UnsupportedVersion {
+        topic: String,
+        version: u16,
+        supported: crate::message::VersionRange,
+    },
+
     #[error("config I/O error: {0}")]
     ConfigIo(#[from] std::io::Error),
 }
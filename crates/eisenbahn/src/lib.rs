@@ -16,7 +16,7 @@ pub use config::{
     WorkerConfig,
 };
 pub use error::EisenbahnError;
-pub use message::Message;
+pub use message::{Message, VersionRange, CURRENT_VERSION};
 pub use messages::events;
 pub use messages::pipeline as msg_pipeline;
 pub use messages::services;
@@ -28,6 +28,6 @@ pub use traits::{
     EventPublisher, EventSubscriber, PipelineReceiver, PipelineSender, RequestHandler,
     RequestSender,
 };
-pub use metrics::MetricsCollector;
+pub use metrics::{HeartbeatThresholds, Liveness, MetricsCollector};
 pub use transport::Transport;
 pub use worker::{Worker, WorkerBuilder, WorkerRunner, WorkerRunnerConfig};
@@ -2,11 +2,21 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::error::EisenbahnError;
+
+/// Schema version stamped on messages created by this build.
+pub const CURRENT_VERSION: u16 = 1;
+
 /// Wire-format message envelope for inter-component communication.
 ///
 /// Messages are serialized with MessagePack for compact, fast transport.
 /// The `topic` field is used by PUB/SUB routing, while `correlation_id`
 /// enables request-response tracking and distributed tracing.
+///
+/// Envelopes and payloads are encoded with field names, so a reader
+/// ignores fields added by a newer writer instead of failing on them. New
+/// payload fields should be `#[serde(default)]` so newer readers still
+/// accept messages from older writers.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     /// Routing topic for PUB/SUB filtering (e.g. "entity.created", "anomaly.detected").
@@ -23,7 +33,8 @@ pub struct Message {
     pub correlation_id: Uuid,
 
     /// Schema version for forward-compatible evolution.
-    /// Consumers should check this before deserializing the payload.
+    /// Consumers should check this before deserializing the payload
+    /// (see [`check_version`](Self::check_version)).
     #[serde(default = "default_version")]
     pub version: u16,
 }
@@ -33,6 +44,34 @@ fn default_version() -> u16 {
     1
 }
 
+/// Inclusive range of message versions a handler understands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VersionRange {
+    pub min: u16,
+    pub max: u16,
+}
+
+impl VersionRange {
+    pub fn new(min: u16, max: u16) -> Self {
+        Self { min, max }
+    }
+
+    /// Only `version`.
+    pub fn exactly(version: u16) -> Self {
+        Self::new(version, version)
+    }
+
+    pub fn contains(&self, version: u16) -> bool {
+        (self.min..=self.max).contains(&version)
+    }
+}
+
+impl std::fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}..={}", self.min, self.max)
+    }
+}
+
 impl Message {
     /// Create a new message, serializing the payload with MessagePack.
     pub fn new<T: Serialize>(
@@ -41,10 +80,10 @@ impl Message {
     ) -> Result<Self, rmp_serde::encode::Error> {
         Ok(Self {
             topic: topic.into(),
-            payload: rmp_serde::to_vec(payload)?,
+            payload: rmp_serde::to_vec_named(payload)?,
             timestamp: Utc::now(),
             correlation_id: Uuid::new_v4(),
-            version: 1,
+            version: CURRENT_VERSION,
         })
     }
 
@@ -56,21 +95,54 @@ impl Message {
     ) -> Result<Self, rmp_serde::encode::Error> {
         Ok(Self {
             topic: topic.into(),
-            payload: rmp_serde::to_vec(payload)?,
+            payload: rmp_serde::to_vec_named(payload)?,
             timestamp: Utc::now(),
             correlation_id,
-            version: 1,
+            version: CURRENT_VERSION,
         })
     }
 
+    /// Stamp a schema version other than [`CURRENT_VERSION`].
+    pub fn with_version(mut self, version: u16) -> Self {
+        self.version = version;
+        self
+    }
+
     /// Deserialize the payload into the expected type.
+    ///
+    /// Fields the type doesn't know are ignored, so a payload written by a
+    /// newer schema still decodes as long as the fields the type needs are
+    /// there.
     pub fn decode<T: for<'de> Deserialize<'de>>(&self) -> Result<T, rmp_serde::decode::Error> {
         rmp_serde::from_slice(&self.payload)
     }
 
+    /// Fail with [`EisenbahnError::UnsupportedVersion`] unless `supported`
+    /// covers this message's version.
+    pub fn check_version(&self, supported: VersionRange) -> Result<(), EisenbahnError> {
+        if supported.contains(self.version) {
+            Ok(())
+        } else {
+            Err(EisenbahnError::UnsupportedVersion {
+                topic: self.topic.clone(),
+                version: self.version,
+                supported,
+            })
+        }
+    }
+
+    /// [`check_version`](Self::check_version), then [`decode`](Self::decode).
+    pub fn decode_versioned<T: for<'de> Deserialize<'de>>(
+        &self,
+        supported: VersionRange,
+    ) -> Result<T, EisenbahnError> {
+        self.check_version(supported)?;
+        Ok(self.decode()?)
+    }
+
     /// Serialize this entire message envelope to MessagePack bytes.
     pub fn to_bytes(&self) -> Result<Vec<u8>, rmp_serde::encode::Error> {
-        rmp_serde::to_vec(self)
+        rmp_serde::to_vec_named(self)
     }
 
     /// Deserialize a message envelope from MessagePack bytes.
@@ -123,4 +195,46 @@ mod tests {
         let msg = Message::with_correlation("reply", &true, id).unwrap();
         assert_eq!(msg.correlation_id, id);
     }
+
+    #[derive(Serialize)]
+    struct JobV2 {
+        job_id: u64,
+        source: String,
+        priority: u8,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct JobV1 {
+        job_id: u64,
+        source: String,
+    }
+
+    #[test]
+    fn old_reader_ignores_fields_from_newer_writer() {
+        let job = JobV2 { job_id: 7, source: "s3://bucket".to_string(), priority: 3 };
+        let msg = Message::new("jobs", &job).unwrap().with_version(2);
+        let decoded = Message::from_bytes(&msg.to_bytes().unwrap()).unwrap();
+
+        assert_eq!(decoded.version, 2);
+        let old: JobV1 = decoded.decode().unwrap();
+        assert_eq!((old.job_id, old.source.as_str()), (7, "s3://bucket"));
+    }
+
+    #[test]
+    fn check_version_rejects_out_of_range() {
+        let msg = Message::new("jobs", &1u8).unwrap().with_version(3);
+        assert!(msg.check_version(VersionRange::new(1, 3)).is_ok());
+
+        let err = msg.decode_versioned::<u8>(VersionRange::exactly(1)).unwrap_err();
+        assert!(matches!(err, EisenbahnError::UnsupportedVersion { version: 3, .. }));
+        assert_eq!(err.to_string(), "unsupported message version 3 on 'jobs' (handler accepts 1..=1)");
+    }
+
+    #[test]
+    fn compact_envelopes_still_decode() {
+        // Envelopes written before the switch to named encoding.
+        let msg = Message::new("legacy", &5u32).unwrap();
+        let compact = rmp_serde::to_vec(&msg).unwrap();
+        assert_eq!(Message::from_bytes(&compact).unwrap().correlation_id, msg.correlation_id);
+    }
 }
@@ -147,6 +147,17 @@ pub struct WorkerHealth {
     pub mem_bytes: u64,
 }
 
+/// A message a worker rejected without running its handler.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadLetter {
+    /// Worker that rejected the message.
+    pub worker_id: String,
+    /// Why it was rejected.
+    pub reason: String,
+    /// The rejected envelope, unchanged.
+    pub message: crate::Message,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
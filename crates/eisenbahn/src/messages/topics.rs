@@ -29,6 +29,9 @@ pub const COMPUTE_COMPLETE: &str = "eisenbahn.compute.complete";
 /// Periodic worker health heartbeat.
 pub const WORKER_HEALTH: &str = "eisenbahn.worker.health";
 
+/// Messages a worker rejected without handling (e.g. unsupported version).
+pub const DEAD_LETTER: &str = "eisenbahn.worker.dead_letter";
+
 // ── Pipeline topics ───────────────────────────────────────────────────────
 
 /// Raw records pushed into the ingest pipeline.
@@ -2,6 +2,7 @@
 //!
 //! Provides per-topic throughput tracking, worker health aggregation,
 //! and a ring buffer of time-series snapshots exposed via `GET /metrics`.
+//! Worker liveness, derived from heartbeat age, is exposed via `GET /health`.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -20,6 +21,13 @@ const RING_BUFFER_CAPACITY: usize = 300;
 /// Snapshot interval for the time-series ring buffer.
 const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(1);
 
+/// Default heartbeat age after which a worker is stale: three missed
+/// pings at the default 30s health interval.
+pub const DEFAULT_STALE_AFTER: Duration = Duration::from_secs(90);
+
+/// Default heartbeat age after which a worker is considered dead.
+pub const DEFAULT_DEAD_AFTER: Duration = Duration::from_secs(300);
+
 // ── Per-topic stats ──────────────────────────────────────────────────
 
 /// Accumulated stats for a single topic.
@@ -50,6 +58,47 @@ pub struct TopicMetrics {
 
 // ── Worker health tracking ───────────────────────────────────────────
 
+/// Whether a worker is still heartbeating, judged by its last ping's age.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Liveness {
+    /// Pinged within `stale_after`.
+    Alive,
+    /// Missed pings for longer than `stale_after`, but not `dead_after`.
+    Stale,
+    /// No ping for `dead_after` or longer.
+    Dead,
+}
+
+/// Heartbeat ages at which a worker turns stale and then dead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HeartbeatThresholds {
+    pub stale_after: Duration,
+    pub dead_after: Duration,
+}
+
+impl HeartbeatThresholds {
+    /// Classify a worker whose last heartbeat is `age` old.
+    pub fn liveness(&self, age: Duration) -> Liveness {
+        if age >= self.dead_after {
+            Liveness::Dead
+        } else if age >= self.stale_after {
+            Liveness::Stale
+        } else {
+            Liveness::Alive
+        }
+    }
+}
+
+impl Default for HeartbeatThresholds {
+    fn default() -> Self {
+        Self {
+            stale_after: DEFAULT_STALE_AFTER,
+            dead_after: DEFAULT_DEAD_AFTER,
+        }
+    }
+}
+
 /// Tracked state for a single worker.
 #[derive(Debug, Clone)]
 struct WorkerState {
@@ -67,6 +116,7 @@ pub struct WorkerMetricsSnapshot {
     pub cpu_pct: f64,
     pub mem_bytes: u64,
     pub last_seen_secs_ago: f64,
+    pub liveness: Liveness,
 }
 
 // ── Ring buffer ──────────────────────────────────────────────────────
@@ -134,6 +184,22 @@ pub struct MetricsResponse {
     pub time_series: Vec<TimeSeriesPoint>,
     pub total_messages: u64,
     pub uptime_secs: f64,
+    /// Message handlers currently running, per worker.
+    pub in_flight: HashMap<String, u64>,
+}
+
+/// JSON response from `GET /health`: every worker that has ever pinged
+/// the broker, with its liveness.
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthResponse {
+    /// Workers sorted by id.
+    pub workers: Vec<WorkerMetricsSnapshot>,
+    pub alive: usize,
+    pub stale: usize,
+    pub dead: usize,
+    pub stale_after_secs: f64,
+    pub dead_after_secs: f64,
+    pub uptime_secs: f64,
 }
 
 // ── MetricsCollector ─────────────────────────────────────────────────
@@ -145,6 +211,7 @@ struct Inner {
     workers: HashMap<String, WorkerState>,
     ring: RingBuffer<TimeSeriesPoint>,
     total_messages: u64,
+    in_flight: HashMap<String, u64>,
 }
 
 /// Thread-safe metrics collector for the broker.
@@ -155,22 +222,35 @@ struct Inner {
 pub struct MetricsCollector {
     inner: Arc<Mutex<Inner>>,
     start: Instant,
+    thresholds: HeartbeatThresholds,
 }
 
 impl MetricsCollector {
-    /// Create a new collector.
+    /// Create a new collector with the default heartbeat thresholds.
     pub fn new() -> Self {
+        Self::with_thresholds(HeartbeatThresholds::default())
+    }
+
+    /// Create a new collector that judges worker liveness by `thresholds`.
+    pub fn with_thresholds(thresholds: HeartbeatThresholds) -> Self {
         Self {
             inner: Arc::new(Mutex::new(Inner {
                 topics: HashMap::new(),
                 workers: HashMap::new(),
                 ring: RingBuffer::new(RING_BUFFER_CAPACITY),
                 total_messages: 0,
+                in_flight: HashMap::new(),
             })),
             start: Instant::now(),
+            thresholds,
         }
     }
 
+    /// The heartbeat thresholds liveness is judged by.
+    pub fn thresholds(&self) -> HeartbeatThresholds {
+        self.thresholds
+    }
+
     /// Record a message on the given topic with the given byte size.
     pub async fn record_message(&self, topic: &str, byte_size: u64) {
         let mut inner = self.inner.lock().await;
@@ -196,6 +276,23 @@ impl MetricsCollector {
         );
     }
 
+    /// Record that a message handler started on `worker`.
+    pub async fn handler_started(&self, worker: &str) {
+        *self.inner.lock().await.in_flight.entry(worker.to_string()).or_default() += 1;
+    }
+
+    /// Record that a message handler on `worker` finished.
+    pub async fn handler_finished(&self, worker: &str) {
+        if let Some(count) = self.inner.lock().await.in_flight.get_mut(worker) {
+            *count = count.saturating_sub(1);
+        }
+    }
+
+    /// Message handlers currently running on `worker`.
+    pub async fn in_flight(&self, worker: &str) -> u64 {
+        self.inner.lock().await.in_flight.get(worker).copied().unwrap_or(0)
+    }
+
     /// Flush the current 1-second window: roll rates and push a time-series point.
     ///
     /// Called by the background tick task every second.
@@ -241,17 +338,7 @@ impl MetricsCollector {
             })
             .collect();
 
-        let workers = inner
-            .workers
-            .iter()
-            .map(|(id, state)| WorkerMetricsSnapshot {
-                worker_id: id.clone(),
-                status: format!("{:?}", state.status),
-                cpu_pct: state.cpu_pct,
-                mem_bytes: state.mem_bytes,
-                last_seen_secs_ago: now.duration_since(state.last_seen).as_secs_f64(),
-            })
-            .collect();
+        let workers = self.worker_snapshots(&inner, now);
 
         let time_series: Vec<TimeSeriesPoint> = inner.ring.iter().cloned().collect();
 
@@ -261,8 +348,45 @@ impl MetricsCollector {
             time_series,
             total_messages: inner.total_messages,
             uptime_secs: self.start.elapsed().as_secs_f64(),
+            in_flight: inner.in_flight.clone(),
+        }
+    }
+
+    /// Build the liveness view for `GET /health`.
+    pub async fn health(&self) -> HealthResponse {
+        let inner = self.inner.lock().await;
+        let mut workers = self.worker_snapshots(&inner, Instant::now());
+        workers.sort_by(|a, b| a.worker_id.cmp(&b.worker_id));
+        let count = |liveness| workers.iter().filter(|w| w.liveness == liveness).count();
+
+        HealthResponse {
+            alive: count(Liveness::Alive),
+            stale: count(Liveness::Stale),
+            dead: count(Liveness::Dead),
+            workers,
+            stale_after_secs: self.thresholds.stale_after.as_secs_f64(),
+            dead_after_secs: self.thresholds.dead_after.as_secs_f64(),
+            uptime_secs: self.start.elapsed().as_secs_f64(),
         }
     }
+
+    fn worker_snapshots(&self, inner: &Inner, now: Instant) -> Vec<WorkerMetricsSnapshot> {
+        inner
+            .workers
+            .iter()
+            .map(|(id, state)| {
+                let age = now.duration_since(state.last_seen);
+                WorkerMetricsSnapshot {
+                    worker_id: id.clone(),
+                    status: format!("{:?}", state.status),
+                    cpu_pct: state.cpu_pct,
+                    mem_bytes: state.mem_bytes,
+                    last_seen_secs_ago: age.as_secs_f64(),
+                    liveness: self.thresholds.liveness(age),
+                }
+            })
+            .collect()
+    }
 }
 
 impl Default for MetricsCollector {
@@ -284,6 +408,7 @@ pub fn spawn_metrics_server(
     tokio::spawn(async move {
         let app = axum::Router::new()
             .route("/metrics", axum::routing::get(metrics_handler))
+            .route("/health", axum::routing::get(health_handler))
             .with_state(collector);
 
         let addr = std::net::SocketAddr::from(([0, 0, 0, 0], port));
@@ -342,6 +467,13 @@ async fn metrics_handler(
     axum::Json(collector.snapshot().await)
 }
 
+/// Axum handler: `GET /health` → worker liveness.
+async fn health_handler(
+    axum::extract::State(collector): axum::extract::State<MetricsCollector>,
+) -> axum::Json<HealthResponse> {
+    axum::Json(collector.health().await)
+}
+
 // ── Tests ────────────────────────────────────────────────────────────
 
 #[cfg(test)]
@@ -464,4 +596,64 @@ mod tests {
         assert!(snap.workers[0].last_seen_secs_ago > 0.0);
         assert_eq!(snap.workers[0].status, "Degraded");
     }
+
+    #[tokio::test]
+    async fn in_flight_gauge_tracks_running_handlers() {
+        let collector = MetricsCollector::new();
+        collector.handler_started("w1").await;
+        collector.handler_started("w1").await;
+        collector.handler_finished("w1").await;
+
+        assert_eq!(collector.in_flight("w1").await, 1);
+        assert_eq!(collector.snapshot().await.in_flight["w1"], 1);
+
+        collector.handler_finished("w1").await;
+        collector.handler_finished("w1").await;
+        assert_eq!(collector.in_flight("w1").await, 0);
+        assert_eq!(collector.in_flight("unknown").await, 0);
+    }
+
+    #[test]
+    fn liveness_follows_heartbeat_age() {
+        let thresholds = HeartbeatThresholds {
+            stale_after: Duration::from_secs(10),
+            dead_after: Duration::from_secs(60),
+        };
+        assert_eq!(thresholds.liveness(Duration::from_secs(9)), Liveness::Alive);
+        assert_eq!(thresholds.liveness(Duration::from_secs(10)), Liveness::Stale);
+        assert_eq!(thresholds.liveness(Duration::from_secs(59)), Liveness::Stale);
+        assert_eq!(thresholds.liveness(Duration::from_secs(60)), Liveness::Dead);
+    }
+
+    #[tokio::test]
+    async fn worker_that_stops_heartbeating_turns_stale_then_dead() {
+        let collector = MetricsCollector::with_thresholds(HeartbeatThresholds {
+            stale_after: Duration::from_millis(50),
+            dead_after: Duration::from_millis(150),
+        });
+        let ping = |worker_id: &str| WorkerHealth {
+            worker_id: worker_id.into(),
+            status: WorkerStatus::Healthy,
+            cpu_pct: 1.0,
+            mem_bytes: 1024,
+        };
+        collector.record_worker_health(&ping("quiet")).await;
+        collector.record_worker_health(&ping("steady")).await;
+        assert_eq!(collector.health().await.alive, 2);
+
+        // Only "steady" keeps heartbeating.
+        tokio::time::sleep(Duration::from_millis(80)).await;
+        collector.record_worker_health(&ping("steady")).await;
+
+        let health = collector.health().await;
+        assert_eq!((health.alive, health.stale, health.dead), (1, 1, 0));
+        assert_eq!(health.workers[0].worker_id, "quiet");
+        assert_eq!(health.workers[0].liveness, Liveness::Stale);
+        assert_eq!(health.workers[1].liveness, Liveness::Alive);
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let health = collector.health().await;
+        assert_eq!(health.workers[0].liveness, Liveness::Dead);
+        assert_eq!(collector.snapshot().await.workers.len(), 2);
+    }
 }
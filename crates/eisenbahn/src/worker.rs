@@ -2,7 +2,8 @@
 //!
 //! Provides the [`Worker`] trait for defining long-running processes,
 //! [`WorkerBuilder`] for fluent configuration, and [`WorkerRunner`] for
-//! executing the event loop with automatic health pings and graceful shutdown.
+//! executing the event loop with automatic health pings, bounded message
+//! handling and graceful shutdown.
 
 use std::future::Future;
 use std::pin::Pin;
@@ -10,14 +11,21 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use tokio::sync::Notify;
+use tokio::sync::{Notify, Semaphore};
 use tracing::{info, warn};
 
 use crate::error::EisenbahnError;
-use crate::message::Message;
-use crate::messages::events::{WorkerHealth, WorkerStatus};
-use crate::messages::topics::WORKER_HEALTH;
-use crate::traits::EventPublisher;
+use crate::message::{Message, VersionRange};
+use crate::messages::events::{DeadLetter, WorkerHealth, WorkerStatus};
+use crate::messages::topics::{DEAD_LETTER, WORKER_HEALTH};
+use crate::metrics::MetricsCollector;
+use crate::traits::{EventPublisher, EventSubscriber};
+
+/// Default cap on concurrently running message handlers.
+const DEFAULT_MAX_CONCURRENCY: usize = 16;
+
+/// Pause after a failed `recv` before pulling again.
+const RECV_ERROR_BACKOFF: Duration = Duration::from_millis(100);
 
 // ── Worker trait ─────────────────────────────────────────────────────
 
@@ -63,6 +71,10 @@ pub struct WorkerBuilder {
     shutdown_timeout: Duration,
     message_handler: Option<MessageHandler>,
     subscriptions: Vec<String>,
+    message_source: Option<Arc<dyn EventSubscriber>>,
+    max_concurrency: usize,
+    metrics: Option<MetricsCollector>,
+    versions: Option<VersionRange>,
 }
 
 impl WorkerBuilder {
@@ -74,6 +86,10 @@ impl WorkerBuilder {
             shutdown_timeout: Duration::from_secs(5),
             message_handler: None,
             subscriptions: Vec::new(),
+            message_source: None,
+            max_concurrency: DEFAULT_MAX_CONCURRENCY,
+            metrics: None,
+            versions: None,
         }
     }
 
@@ -105,6 +121,37 @@ impl WorkerBuilder {
         self
     }
 
+    /// Pull messages for the handler from `source`, subscribed to every topic
+    /// added with [`subscribe`](Self::subscribe).
+    pub fn message_source(mut self, source: Arc<dyn EventSubscriber>) -> Self {
+        self.message_source = Some(source);
+        self
+    }
+
+    /// Cap how many message handlers run at once (default: 16, minimum 1).
+    ///
+    /// At capacity the runner stops pulling from the source until a handler
+    /// finishes, so bursts queue up in the transport instead of in memory.
+    pub fn max_concurrency(mut self, max: usize) -> Self {
+        self.max_concurrency = max.max(1);
+        self
+    }
+
+    /// Report in-flight handler counts to `metrics`.
+    pub fn metrics(mut self, metrics: MetricsCollector) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Message versions the handler understands (default: all).
+    ///
+    /// Messages outside the range never reach the handler; they are
+    /// published to [`DEAD_LETTER`] as a [`DeadLetter`] instead.
+    pub fn accept_versions(mut self, versions: VersionRange) -> Self {
+        self.versions = Some(versions);
+        self
+    }
+
     /// Build the runner configuration. The actual [`Worker`] impl and publisher
     /// are provided to [`WorkerRunner::run`].
     pub fn build(self) -> WorkerRunnerConfig {
@@ -114,6 +161,10 @@ impl WorkerBuilder {
             shutdown_timeout: self.shutdown_timeout,
             message_handler: self.message_handler,
             subscriptions: self.subscriptions,
+            message_source: self.message_source,
+            max_concurrency: self.max_concurrency,
+            metrics: self.metrics,
+            versions: self.versions,
         }
     }
 }
@@ -127,16 +178,22 @@ pub struct WorkerRunnerConfig {
     pub shutdown_timeout: Duration,
     pub message_handler: Option<MessageHandler>,
     pub subscriptions: Vec<String>,
+    pub message_source: Option<Arc<dyn EventSubscriber>>,
+    pub max_concurrency: usize,
+    pub metrics: Option<MetricsCollector>,
+    pub versions: Option<VersionRange>,
 }
 
 // ── WorkerRunner ─────────────────────────────────────────────────────
 
 /// Runs a [`Worker`] with automatic health pings and graceful shutdown.
 ///
-/// The runner manages three concurrent tasks:
+/// The runner manages these concurrent tasks:
 /// 1. **Health ping loop** — publishes [`WorkerHealth`] at a configured interval
 /// 2. **Signal handler** — listens for SIGINT/SIGTERM and initiates shutdown
 /// 3. **Worker lifecycle** — calls `start()`, waits for shutdown, then calls `stop()`
+/// 4. **Dispatch loop** — when a handler and message source are configured,
+///    pulls messages and runs at most `max_concurrency` handlers at a time
 pub struct WorkerRunner;
 
 impl WorkerRunner {
@@ -160,6 +217,29 @@ impl WorkerRunner {
         // Publish initial health ping
         Self::publish_health(&*publisher, &worker_name, WorkerStatus::Healthy).await;
 
+        // Spawn the bounded dispatch loop
+        let slots = Arc::new(Semaphore::new(config.max_concurrency));
+        let dispatch_handle = match (config.message_handler, config.message_source) {
+            (Some(handler), Some(source)) => {
+                for topic in &config.subscriptions {
+                    source.subscribe(topic).await?;
+                }
+                Some(tokio::spawn(Self::dispatch_loop(
+                    worker_name.clone(),
+                    source,
+                    Arc::new(handler),
+                    slots.clone(),
+                    config.metrics,
+                    config.versions.map(|versions| (versions, publisher.clone())),
+                )))
+            }
+            (Some(_), None) => {
+                warn!(worker = %worker_name, "message handler registered without a message source; it will not be called");
+                None
+            }
+            _ => None,
+        };
+
         // Create a shared shutdown signal
         let shutdown = Arc::new(Notify::new());
 
@@ -195,6 +275,18 @@ impl WorkerRunner {
         health_handle.abort();
         signal_handle.abort();
 
+        // Stop pulling messages and let in-flight handlers finish
+        if let Some(handle) = dispatch_handle {
+            handle.abort();
+            let all_slots = config.max_concurrency as u32;
+            if tokio::time::timeout(config.shutdown_timeout, slots.acquire_many(all_slots))
+                .await
+                .is_err()
+            {
+                warn!(worker = %worker_name, "in-flight message handlers did not finish before shutdown timeout");
+            }
+        }
+
         // Graceful shutdown: stop the worker with timeout
         info!(worker = %worker_name, timeout = ?config.shutdown_timeout, "stopping worker");
         match tokio::time::timeout(config.shutdown_timeout, worker.stop()).await {
@@ -216,6 +308,58 @@ impl WorkerRunner {
         Ok(())
     }
 
+    /// Pull messages from `source` and run `handler` on each, holding one of
+    /// `slots` per running handler. Waits for a free slot before pulling.
+    ///
+    /// With `versions`, messages outside the supported range are sent to the
+    /// dead-letter topic through its publisher instead of the handler.
+    async fn dispatch_loop(
+        worker_name: String,
+        source: Arc<dyn EventSubscriber>,
+        handler: Arc<MessageHandler>,
+        slots: Arc<Semaphore>,
+        metrics: Option<MetricsCollector>,
+        versions: Option<(VersionRange, Arc<dyn EventPublisher>)>,
+    ) {
+        loop {
+            let Ok(permit) = slots.clone().acquire_owned().await else {
+                break;
+            };
+            let msg = match source.recv().await {
+                Ok(msg) => msg,
+                Err(e) => {
+                    warn!(worker = %worker_name, error = %e, "failed to receive message");
+                    tokio::time::sleep(RECV_ERROR_BACKOFF).await;
+                    continue;
+                }
+            };
+            if let Some((supported, publisher)) = &versions {
+                if let Err(e) = msg.check_version(*supported) {
+                    warn!(worker = %worker_name, error = %e, "rejecting message");
+                    Self::publish_dead_letter(&**publisher, &worker_name, msg, e.to_string()).await;
+                    continue;
+                }
+            }
+
+            let handler = handler.clone();
+            let metrics = metrics.clone();
+            let worker_name = worker_name.clone();
+            tokio::spawn(async move {
+                if let Some(metrics) = &metrics {
+                    metrics.handler_started(&worker_name).await;
+                }
+                let topic = msg.topic.clone();
+                if let Err(e) = handler(msg).await {
+                    warn!(worker = %worker_name, topic = %topic, error = %e, "message handler failed");
+                }
+                if let Some(metrics) = &metrics {
+                    metrics.handler_finished(&worker_name).await;
+                }
+                drop(permit);
+            });
+        }
+    }
+
     /// Periodically publish health pings until shutdown is signalled.
     async fn health_loop(
         publisher: &dyn EventPublisher,
@@ -264,6 +408,30 @@ impl WorkerRunner {
         }
     }
 
+    /// Publish `message` to the dead-letter topic with the rejection `reason`.
+    async fn publish_dead_letter(
+        publisher: &dyn EventPublisher,
+        worker_name: &str,
+        message: Message,
+        reason: String,
+    ) {
+        let dead_letter = DeadLetter {
+            worker_id: worker_name.to_string(),
+            reason,
+            message,
+        };
+        match Message::with_correlation(DEAD_LETTER, &dead_letter, dead_letter.message.correlation_id) {
+            Ok(msg) => {
+                if let Err(e) = publisher.publish(msg).await {
+                    warn!(worker = %worker_name, error = %e, "failed to publish dead letter");
+                }
+            }
+            Err(e) => {
+                warn!(worker = %worker_name, error = %e, "failed to serialize dead letter");
+            }
+        }
+    }
+
     /// Wait for either an OS shutdown signal or a programmatic notification.
     async fn wait_for_shutdown(external: Option<Arc<Notify>>) {
         match external {
@@ -437,6 +605,9 @@ mod tests {
         assert_eq!(config.shutdown_timeout, Duration::from_secs(5));
         assert!(config.subscriptions.is_empty());
         assert!(config.message_handler.is_none());
+        assert!(config.message_source.is_none());
+        assert_eq!(config.max_concurrency, DEFAULT_MAX_CONCURRENCY);
+        assert_eq!(WorkerBuilder::new("w").max_concurrency(0).build().max_concurrency, 1);
     }
 
     #[tokio::test]
@@ -465,4 +636,146 @@ mod tests {
         assert_eq!(health.worker_id, "my-worker");
         assert_eq!(health.status, WorkerStatus::Degraded);
     }
+
+    /// Subscriber fed from a channel, counting how many messages were pulled.
+    struct ChannelSubscriber {
+        rx: Mutex<tokio::sync::mpsc::UnboundedReceiver<Message>>,
+        pulled: AtomicU32,
+    }
+
+    #[async_trait]
+    impl EventSubscriber for ChannelSubscriber {
+        async fn subscribe(&self, _topic_prefix: &str) -> Result<(), EisenbahnError> {
+            Ok(())
+        }
+
+        async fn recv(&self) -> Result<Message, EisenbahnError> {
+            let msg = self.rx.lock().await.recv().await;
+            self.pulled.fetch_add(1, Ordering::SeqCst);
+            Ok(msg.expect("test channel closed"))
+        }
+    }
+
+    #[tokio::test]
+    async fn handlers_never_exceed_max_concurrency() {
+        const MAX: usize = 3;
+        const TOTAL: u32 = 12;
+
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        for i in 0..TOTAL {
+            tx.send(Message::new("eisenbahn.test", &i).unwrap()).unwrap();
+        }
+        let source = Arc::new(ChannelSubscriber { rx: Mutex::new(rx), pulled: AtomicU32::new(0) });
+        let metrics = MetricsCollector::new();
+
+        // Handlers block on `gate` until the test opens it.
+        let gate = Arc::new(Semaphore::new(0));
+        let running = Arc::new(AtomicU32::new(0));
+        let peak = Arc::new(AtomicU32::new(0));
+        let done = Arc::new(AtomicU32::new(0));
+        let (g, r, p, d) = (gate.clone(), running.clone(), peak.clone(), done.clone());
+        let config = WorkerBuilder::new("bounded")
+            .subscribe("eisenbahn.test")
+            .message_source(source.clone())
+            .max_concurrency(MAX)
+            .metrics(metrics.clone())
+            .on_message(move |_msg| {
+                let (gate, running, peak, done) = (g.clone(), r.clone(), p.clone(), d.clone());
+                async move {
+                    let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    gate.acquire().await.unwrap().forget();
+                    running.fetch_sub(1, Ordering::SeqCst);
+                    done.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                }
+            })
+            .build();
+
+        let shutdown = Arc::new(Notify::new());
+        let s = shutdown.clone();
+        let handle = tokio::spawn(WorkerRunner::run(
+            Arc::new(TestWorker::new()),
+            Arc::new(MockPublisher::new()),
+            config,
+            Some(s),
+        ));
+
+        // With every slot taken the runner stops pulling.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(running.load(Ordering::SeqCst), MAX as u32);
+        assert_eq!(source.pulled.load(Ordering::SeqCst), MAX as u32);
+        assert_eq!(metrics.in_flight("bounded").await, MAX as u64);
+
+        gate.add_permits(TOTAL as usize);
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while done.load(Ordering::SeqCst) < TOTAL {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("all messages should be handled");
+
+        assert_eq!(peak.load(Ordering::SeqCst), MAX as u32);
+        shutdown.notify_waiters();
+        handle.await.unwrap().unwrap();
+        assert_eq!(metrics.in_flight("bounded").await, 0);
+    }
+
+    #[tokio::test]
+    async fn newer_versions_go_to_dead_letter_instead_of_old_handler() {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+        tx.send(Message::new("eisenbahn.test", &1u32).unwrap()).unwrap();
+        tx.send(Message::new("eisenbahn.test", &2u32).unwrap().with_version(2)).unwrap();
+        tx.send(Message::new("eisenbahn.test", &3u32).unwrap()).unwrap();
+        let source = Arc::new(ChannelSubscriber { rx: Mutex::new(rx), pulled: AtomicU32::new(0) });
+
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let h = handled.clone();
+        let config = WorkerBuilder::new("v1-worker")
+            .subscribe("eisenbahn.test")
+            .message_source(source)
+            .max_concurrency(1)
+            .accept_versions(VersionRange::exactly(1))
+            .on_message(move |msg| {
+                let handled = h.clone();
+                async move {
+                    handled.lock().await.push(msg.decode::<u32>()?);
+                    Ok(())
+                }
+            })
+            .build();
+
+        let publisher = Arc::new(MockPublisher::new());
+        let shutdown = Arc::new(Notify::new());
+        let handle = tokio::spawn(WorkerRunner::run(
+            Arc::new(TestWorker::new()),
+            publisher.clone(),
+            config,
+            Some(shutdown.clone()),
+        ));
+
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while handled.lock().await.len() < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .expect("v1 messages should be handled");
+        shutdown.notify_waiters();
+        handle.await.unwrap().unwrap();
+
+        assert_eq!(*handled.lock().await, vec![1, 3]);
+        let messages = publisher.messages.lock().await;
+        let dead: Vec<DeadLetter> = messages
+            .iter()
+            .filter(|m| m.topic == DEAD_LETTER)
+            .map(|m| m.decode().unwrap())
+            .collect();
+        assert_eq!(dead.len(), 1);
+        assert_eq!(dead[0].worker_id, "v1-worker");
+        assert_eq!(dead[0].message.version, 2);
+        assert_eq!(dead[0].message.decode::<u32>().unwrap(), 2);
+        assert!(dead[0].reason.contains("unsupported message version 2"), "{}", dead[0].reason);
+    }
 }
@@ -1,7 +1,8 @@
 //! Integration tests for worker health pings through the broker.
 //!
 //! Verifies that WorkerRunner publishes health pings via PUB/SUB,
-//! and that the broker's MetricsCollector receives them.
+//! that the broker's MetricsCollector receives them, and that a worker
+//! which stops pinging is reported stale.
 
 use std::sync::Arc;
 use std::time::Duration;
@@ -14,7 +15,7 @@ use stupid_eisenbahn::broker::{BrokerConfig, EventBroker};
 use stupid_eisenbahn::error::EisenbahnError;
 use stupid_eisenbahn::messages::events::{WorkerHealth, WorkerStatus};
 use stupid_eisenbahn::messages::topics;
-use stupid_eisenbahn::EventSubscriber;
+use stupid_eisenbahn::{EventSubscriber, HeartbeatThresholds, Liveness};
 use stupid_eisenbahn::transport::Transport;
 use stupid_eisenbahn::{Worker, WorkerBuilder, WorkerRunner, ZmqPublisher, ZmqSubscriber};
 
@@ -231,3 +232,55 @@ async fn multiple_workers_health_pings() {
     let _ = timeout(TIMEOUT, h2).await;
     broker_handle.abort();
 }
+
+#[tokio::test]
+async fn worker_that_stops_heartbeating_is_marked_stale() {
+    let mut cfg = BrokerConfig::tcp("127.0.0.1", 16430, 16431, 16432);
+    cfg.heartbeat = HeartbeatThresholds {
+        stale_after: Duration::from_millis(400),
+        dead_after: Duration::from_secs(30),
+    };
+    let broker = EventBroker::new(cfg);
+    let collector = broker.collector().clone();
+
+    let broker_handle = tokio::spawn(async move {
+        let _ = broker.run().await;
+    });
+    tokio::time::sleep(SETTLE).await;
+
+    let frontend = Transport::tcp("127.0.0.1", 16430);
+
+    let worker = Arc::new(NoopWorker::new("fading-worker"));
+    let publisher = Arc::new(ZmqPublisher::connect(&frontend).await.unwrap());
+    let shutdown = Arc::new(Notify::new());
+    let config = WorkerBuilder::new("fading-worker")
+        .health_interval(Duration::from_millis(100))
+        .shutdown_timeout(Duration::from_secs(1))
+        .build();
+
+    let s = shutdown.clone();
+    let worker_handle = tokio::spawn(async move {
+        WorkerRunner::run(worker, publisher, config, Some(s)).await
+    });
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    let liveness = |health: &stupid_eisenbahn::metrics::HealthResponse| {
+        health
+            .workers
+            .iter()
+            .find(|w| w.worker_id == "fading-worker")
+            .map(|w| w.liveness)
+    };
+    assert_eq!(liveness(&collector.health().await), Some(Liveness::Alive));
+
+    // Stop the worker: heartbeats cease after its final ping.
+    shutdown.notify_waiters();
+    let _ = timeout(TIMEOUT, worker_handle).await;
+    tokio::time::sleep(Duration::from_millis(700)).await;
+
+    let health = collector.health().await;
+    assert_eq!(liveness(&health), Some(Liveness::Stale), "workers = {:?}", health.workers);
+    assert_eq!(health.stale, 1);
+
+    broker_handle.abort();
+}
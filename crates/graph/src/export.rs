@@ -0,0 +1,294 @@
+//! Export a [`GraphStore`] to GraphML, Graphviz DOT, or node/edge CSV.
+//!
+//! Output is streamed to a [`Write`]: nodes in id order, then each node's
+//! outgoing edges, so nothing beyond the graph itself is buffered.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::io::{self, Write};
+
+use stupid_core::NodeId;
+
+use crate::store::{Edge, GraphStore, Node};
+
+/// Output format for [`GraphStore::export`].
+///
+/// CSV is written as two tables, one per call: [`ExportFormat::NodesCsv`] and
+/// [`ExportFormat::EdgesCsv`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    GraphMl,
+    Dot,
+    NodesCsv,
+    EdgesCsv,
+}
+
+/// Per-node metrics computed outside the graph (e.g. from the compute
+/// scheduler's knowledge state), added as node attributes when present.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NodeMetrics<'a> {
+    pub pagerank: Option<&'a HashMap<NodeId, f64>>,
+    pub community: Option<&'a HashMap<NodeId, u64>>,
+}
+
+impl GraphStore {
+    /// Write the whole graph to `writer` in `format`.
+    pub fn export<W: Write>(
+        &self,
+        format: ExportFormat,
+        metrics: NodeMetrics<'_>,
+        mut writer: W,
+    ) -> io::Result<()> {
+        match format {
+            ExportFormat::GraphMl => self.write_graphml(metrics, &mut writer)?,
+            ExportFormat::Dot => self.write_dot(metrics, &mut writer)?,
+            ExportFormat::NodesCsv => self.write_nodes_csv(metrics, &mut writer)?,
+            ExportFormat::EdgesCsv => self.write_edges_csv(&mut writer)?,
+        }
+        writer.flush()
+    }
+
+    fn edges_by_source(&self) -> impl Iterator<Item = &Edge> + '_ {
+        self.nodes_by_id()
+            .flat_map(|n| self.outgoing.get(&n.id).into_iter().flatten())
+            .filter_map(|eid| self.edges.get(eid))
+    }
+
+    fn write_graphml(&self, metrics: NodeMetrics<'_>, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(w, r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#)?;
+        writeln!(w, r#"  <key id="entity_type" for="node" attr.name="entity_type" attr.type="string"/>"#)?;
+        writeln!(w, r#"  <key id="key" for="node" attr.name="key" attr.type="string"/>"#)?;
+        if metrics.pagerank.is_some() {
+            writeln!(w, r#"  <key id="pagerank" for="node" attr.name="pagerank" attr.type="double"/>"#)?;
+        }
+        if metrics.community.is_some() {
+            writeln!(w, r#"  <key id="community" for="node" attr.name="community" attr.type="long"/>"#)?;
+        }
+        writeln!(w, r#"  <key id="edge_type" for="edge" attr.name="edge_type" attr.type="string"/>"#)?;
+        writeln!(w, r#"  <key id="weight" for="edge" attr.name="weight" attr.type="double"/>"#)?;
+        writeln!(w, r#"  <graph id="G" edgedefault="directed">"#)?;
+
+        for node in self.nodes_by_id() {
+            writeln!(w, r#"    <node id="{}">"#, node.id)?;
+            writeln!(w, r#"      <data key="entity_type">{}</data>"#, node.entity_type)?;
+            writeln!(w, r#"      <data key="key">{}</data>"#, xml_escape(&node.key))?;
+            if let Some(score) = metric(metrics.pagerank, node) {
+                writeln!(w, r#"      <data key="pagerank">{}</data>"#, score)?;
+            }
+            if let Some(community) = metric(metrics.community, node) {
+                writeln!(w, r#"      <data key="community">{}</data>"#, community)?;
+            }
+            writeln!(w, "    </node>")?;
+        }
+        for edge in self.edges_by_source() {
+            writeln!(
+                w,
+                r#"    <edge id="{}" source="{}" target="{}">"#,
+                edge.id, edge.source, edge.target
+            )?;
+            writeln!(w, r#"      <data key="edge_type">{}</data>"#, edge.edge_type)?;
+            writeln!(w, r#"      <data key="weight">{}</data>"#, edge.weight)?;
+            writeln!(w, "    </edge>")?;
+        }
+
+        writeln!(w, "  </graph>")?;
+        writeln!(w, "</graphml>")
+    }
+
+    fn write_dot(&self, metrics: NodeMetrics<'_>, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "digraph G {{")?;
+        for node in self.nodes_by_id() {
+            write!(
+                w,
+                r#"  "{}" [label="{}", entity_type="{}""#,
+                node.id,
+                dot_escape(&node.key),
+                node.entity_type
+            )?;
+            if let Some(score) = metric(metrics.pagerank, node) {
+                write!(w, ", pagerank={}", score)?;
+            }
+            if let Some(community) = metric(metrics.community, node) {
+                write!(w, ", community={}", community)?;
+            }
+            writeln!(w, "];")?;
+        }
+        for edge in self.edges_by_source() {
+            writeln!(
+                w,
+                r#"  "{}" -> "{}" [edge_type="{}", weight={}];"#,
+                edge.source, edge.target, edge.edge_type, edge.weight
+            )?;
+        }
+        writeln!(w, "}}")
+    }
+
+    fn write_nodes_csv(&self, metrics: NodeMetrics<'_>, w: &mut impl Write) -> io::Result<()> {
+        write!(w, "id,entity_type,key")?;
+        if metrics.pagerank.is_some() {
+            write!(w, ",pagerank")?;
+        }
+        if metrics.community.is_some() {
+            write!(w, ",community")?;
+        }
+        writeln!(w)?;
+
+        for node in self.nodes_by_id() {
+            write!(w, "{},{},{}", node.id, node.entity_type, csv_escape(&node.key))?;
+            if metrics.pagerank.is_some() {
+                write!(w, ",{}", OptionalField(metric(metrics.pagerank, node)))?;
+            }
+            if metrics.community.is_some() {
+                write!(w, ",{}", OptionalField(metric(metrics.community, node)))?;
+            }
+            writeln!(w)?;
+        }
+        Ok(())
+    }
+
+    fn write_edges_csv(&self, w: &mut impl Write) -> io::Result<()> {
+        writeln!(w, "id,source,target,edge_type,weight,segment_id")?;
+        for edge in self.edges_by_source() {
+            writeln!(
+                w,
+                "{},{},{},{},{},{}",
+                edge.id,
+                edge.source,
+                edge.target,
+                edge.edge_type,
+                edge.weight,
+                csv_escape(&edge.segment_id)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn metric<T: Copy>(values: Option<&HashMap<NodeId, T>>, node: &Node) -> Option<T> {
+    values?.get(&node.id).copied()
+}
+
+/// Displays the value, or nothing for a missing one.
+struct OptionalField<T>(Option<T>);
+
+impl<T: Display> Display for OptionalField<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Some(value) => value.fmt(f),
+            None => Ok(()),
+        }
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn csv_escape(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stupid_core::{EdgeType, EntityType};
+
+    fn graph() -> (GraphStore, NodeId, NodeId) {
+        let mut g = GraphStore::new();
+        let seg = "2025-06-14".to_string();
+        let alice = g.upsert_node(EntityType::Member, "member:alice", &seg);
+        let err = g.upsert_node(EntityType::Error, "error:500:/api/bet?a=1&b=\"x\",y", &seg);
+        g.add_edge(alice, err, EdgeType::HitError, &seg);
+        g.add_edge(alice, err, EdgeType::HitError, &seg);
+        (g, alice, err)
+    }
+
+    fn export(g: &GraphStore, format: ExportFormat, metrics: NodeMetrics<'_>) -> String {
+        let mut out = Vec::new();
+        g.export(format, metrics, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn graphml_is_well_formed() {
+        let (g, alice, err) = graph();
+        let pagerank = HashMap::from([(alice, 0.25)]);
+        let metrics = NodeMetrics { pagerank: Some(&pagerank), community: None };
+        let xml = export(&g, ExportFormat::GraphMl, metrics);
+
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.trim_end().ends_with("</graphml>"));
+        assert_eq!(xml.matches("<node ").count(), 2);
+        assert_eq!(xml.matches("</node>").count(), 2);
+        assert_eq!(xml.matches("<edge ").count(), 1);
+        assert_eq!(xml.matches("</edge>").count(), 1);
+        assert!(xml.contains(&format!(r#"source="{alice}" target="{err}""#)));
+        assert!(xml.contains(r#"<data key="weight">2</data>"#));
+        assert!(xml.contains(r#"<data key="pagerank">0.25</data>"#));
+        assert!(!xml.contains(r#"key="community""#));
+        // Only the escaped form of the key may appear.
+        assert!(xml.contains("/api/bet?a=1&amp;b=&quot;x&quot;,y"));
+        assert!(!xml.contains("a=1&b"));
+    }
+
+    #[test]
+    fn dot_quotes_ids_and_escapes_labels() {
+        let (g, alice, err) = graph();
+        let community = HashMap::from([(alice, 7), (err, 7)]);
+        let metrics = NodeMetrics { pagerank: None, community: Some(&community) };
+        let dot = export(&g, ExportFormat::Dot, metrics);
+
+        let lines: Vec<&str> = dot.lines().collect();
+        assert_eq!(lines.first(), Some(&"digraph G {"));
+        assert_eq!(lines.last(), Some(&"}"));
+        assert_eq!(lines.len(), 5);
+        assert!(lines[1..4].iter().all(|l| l.ends_with("];")));
+        assert!(dot.contains(r#"[label="member:alice", entity_type="Member", community=7]"#));
+        assert!(dot.contains(r#"b=\"x\",y""#));
+        assert!(dot.contains(&format!(
+            r#""{alice}" -> "{err}" [edge_type="HitError", weight=2];"#
+        )));
+    }
+
+    #[test]
+    fn csv_tables_have_consistent_columns() {
+        let (g, alice, _) = graph();
+        let pagerank = HashMap::from([(alice, 0.5)]);
+        let metrics = NodeMetrics { pagerank: Some(&pagerank), community: None };
+
+        let nodes = export(&g, ExportFormat::NodesCsv, metrics);
+        let rows: Vec<&str> = nodes.lines().collect();
+        assert_eq!(rows[0], "id,entity_type,key,pagerank");
+        assert_eq!(rows.len(), 3);
+        assert!(rows.contains(&format!("{alice},Member,member:alice,0.5").as_str()));
+        // The error key needs quoting; its missing pagerank is an empty field.
+        assert!(rows.iter().any(|r| r.ends_with(r#",Error,"error:500:/api/bet?a=1&b=""x"",y","#)));
+
+        let edges = export(&g, ExportFormat::EdgesCsv, NodeMetrics::default());
+        let rows: Vec<&str> = edges.lines().collect();
+        assert_eq!(rows[0], "id,source,target,edge_type,weight,segment_id");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[1].split(',').count(), 6);
+        assert!(rows[1].ends_with(",HitError,2,2025-06-14"));
+    }
+}
@@ -1,3 +1,9 @@
+pub mod export;
+pub mod snapshot;
 pub mod store;
+pub mod traversal;
 
-pub use store::GraphStore;
+pub use export::{ExportFormat, NodeMetrics};
+pub use snapshot::GraphManifest;
+pub use store::{GraphStore, NodeFilter, NodePage};
+pub use traversal::{GraphPath, Subgraph};
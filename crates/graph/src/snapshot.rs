@@ -0,0 +1,219 @@
+//! On-disk graph snapshots, so startup can skip rebuilding from segments.
+//!
+//! A snapshot is the node and edge lists as zstd-compressed MessagePack; the
+//! lookup indices are rebuilt on load. A JSON [`GraphManifest`] next to it
+//! records which segments and graph mapping produced it.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::Path;
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use stupid_core::{GraphMapping, StupidError};
+
+use crate::store::{Edge, GraphStore, Node};
+
+/// Snapshot format version; snapshots with any other version are rejected.
+const SNAPSHOT_VERSION: u32 = 1;
+const ZSTD_LEVEL: i32 = 3;
+
+#[derive(Serialize)]
+struct SnapshotRef<'a> {
+    version: u32,
+    nodes: Vec<&'a Node>,
+    edges: Vec<&'a Edge>,
+}
+
+#[derive(Deserialize)]
+struct Snapshot {
+    version: u32,
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+}
+
+impl GraphStore {
+    /// Write the graph to `path`, replacing any existing snapshot atomically.
+    pub fn save_snapshot(&self, path: &Path) -> Result<(), StupidError> {
+        let snapshot = SnapshotRef {
+            version: SNAPSHOT_VERSION,
+            nodes: self.nodes_by_id().collect(),
+            edges: self
+                .nodes_by_id()
+                .flat_map(|n| self.outgoing.get(&n.id).into_iter().flatten())
+                .filter_map(|eid| self.edges.get(eid))
+                .collect(),
+        };
+
+        let tmp = path.with_extension("tmp");
+        let mut encoder = zstd::Encoder::new(BufWriter::new(File::create(&tmp)?), ZSTD_LEVEL)?;
+        rmp_serde::encode::write(&mut encoder, &snapshot)
+            .map_err(|e| StupidError::Serialize(e.into()))?;
+        encoder.finish()?.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Read a graph written by [`GraphStore::save_snapshot`].
+    pub fn load_snapshot(path: &Path) -> Result<GraphStore, StupidError> {
+        let decoder = zstd::Decoder::new(BufReader::new(File::open(path)?))?;
+        let snapshot: Snapshot =
+            rmp_serde::from_read(decoder).map_err(|e| StupidError::Serialize(e.into()))?;
+        if snapshot.version != SNAPSHOT_VERSION {
+            return Err(StupidError::Other(format!(
+                "unsupported graph snapshot version {} (expected {})",
+                snapshot.version, SNAPSHOT_VERSION
+            )));
+        }
+
+        let mut graph = GraphStore::new();
+        for node in snapshot.nodes {
+            graph.insert_node(node);
+        }
+        for edge in snapshot.edges {
+            graph.insert_edge(edge);
+        }
+        Ok(graph)
+    }
+}
+
+/// What a graph snapshot was built from, used to tell whether it's still fresh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphManifest {
+    /// Sorted segment IDs the graph was built from.
+    pub segment_ids: Vec<String>,
+    /// SHA-256 hex digest of the graph mapping in effect.
+    pub mapping_hash: String,
+    /// Documents read while building the graph.
+    pub doc_count: u64,
+    /// RFC 3339 timestamp of when the snapshot was written.
+    pub created_at: String,
+}
+
+impl GraphManifest {
+    pub fn new(segment_ids: &[String], mapping: &GraphMapping, doc_count: u64) -> Self {
+        let mut segment_ids = segment_ids.to_vec();
+        segment_ids.sort();
+        Self {
+            segment_ids,
+            mapping_hash: mapping_hash(mapping),
+            doc_count,
+            created_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    /// Whether a graph built from `segment_ids` with `mapping` would match
+    /// the snapshot. Segment order doesn't matter.
+    pub fn is_fresh(&self, segment_ids: &[String], mapping: &GraphMapping) -> bool {
+        let mut sorted = segment_ids.to_vec();
+        sorted.sort();
+        self.segment_ids == sorted && self.mapping_hash == mapping_hash(mapping)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), StupidError> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| StupidError::Serialize(e.into()))?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+
+    /// Load the manifest at `path`, or `None` if there isn't one.
+    pub fn load(path: &Path) -> Result<Option<Self>, StupidError> {
+        let json = match std::fs::read_to_string(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        serde_json::from_str(&json).map(Some).map_err(|e| StupidError::Serialize(e.into()))
+    }
+}
+
+/// Hash of the mapping's JSON form; `serde_json::Value` objects are sorted by
+/// key, so the event map's iteration order doesn't affect it.
+fn mapping_hash(mapping: &GraphMapping) -> String {
+    let canonical = serde_json::to_value(mapping).map(|v| v.to_string()).unwrap_or_default();
+    format!("{:x}", Sha256::digest(canonical.as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stupid_core::{EdgeType, EntityType};
+
+    fn graph() -> GraphStore {
+        let mut g = GraphStore::new();
+        let (seg_a, seg_b) = ("2025-06-14".to_string(), "2025-06-15".to_string());
+        let alice = g.upsert_node(EntityType::Member, "member:alice", &seg_a);
+        let bob = g.upsert_node(EntityType::Member, "member:bob", &seg_b);
+        let dev = g.upsert_node(EntityType::Device, "device:d1", &seg_a);
+        let game = g.upsert_node(EntityType::Game, "game:slots", &seg_b);
+        g.upsert_node(EntityType::Member, "member:alice", &seg_b);
+        g.add_edge(alice, dev, EdgeType::LoggedInFrom, &seg_a);
+        g.add_edge(alice, dev, EdgeType::LoggedInFrom, &seg_a);
+        g.add_edge(bob, dev, EdgeType::LoggedInFrom, &seg_b);
+        g.add_edge(bob, game, EdgeType::OpenedGame, &seg_b);
+        g
+    }
+
+    #[test]
+    fn snapshot_round_trip_preserves_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("graph.snapshot");
+        let original = graph();
+        original.save_snapshot(&path).unwrap();
+        let mut loaded = GraphStore::load_snapshot(&path).unwrap();
+
+        let (a, b) = (original.stats(), loaded.stats());
+        assert_eq!((a.node_count, a.edge_count), (b.node_count, b.edge_count));
+        assert_eq!(a.nodes_by_type, b.nodes_by_type);
+        assert_eq!(a.edges_by_type, b.edges_by_type);
+
+        for (id, node) in &original.nodes {
+            let copy = &loaded.nodes[id];
+            assert_eq!((&copy.key, &copy.segment_refs), (&node.key, &node.segment_refs));
+            assert_eq!(original.neighbors(id).len(), loaded.neighbors(id).len());
+        }
+        for (id, edge) in &original.edges {
+            assert_eq!(loaded.edges[id].weight, edge.weight);
+        }
+
+        // Indices are rebuilt: upserts and repeat edges hit the existing entries.
+        let alice = loaded.nodes.values().find(|n| n.key == "member:alice").unwrap().id;
+        let dev = loaded.nodes.values().find(|n| n.key == "device:d1").unwrap().id;
+        assert_eq!(loaded.upsert_node(EntityType::Member, "member:alice", &"x".to_string()), alice);
+        let edge = loaded.add_edge(alice, dev, EdgeType::LoggedInFrom, &"x".to_string());
+        assert_eq!(loaded.edges[&edge].weight, 3.0);
+        assert_eq!(loaded.stats().edge_count, a.edge_count);
+        assert!(!tmp.path().join("graph.tmp").exists());
+    }
+
+    #[test]
+    fn load_snapshot_rejects_garbage() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("graph.snapshot");
+        std::fs::write(&path, b"not a snapshot").unwrap();
+        assert!(GraphStore::load_snapshot(&path).is_err());
+        assert!(GraphStore::load_snapshot(&tmp.path().join("missing")).is_err());
+    }
+
+    #[test]
+    fn manifest_freshness_tracks_segments_and_mapping() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("manifest.json");
+        assert!(GraphManifest::load(&path).unwrap().is_none());
+
+        let mapping = GraphMapping::default();
+        let ids = vec!["seg-b".to_string(), "seg-a".to_string()];
+        GraphManifest::new(&ids, &mapping, 42).save(&path).unwrap();
+        let manifest = GraphManifest::load(&path).unwrap().unwrap();
+
+        assert_eq!(manifest.doc_count, 42);
+        assert!(manifest.is_fresh(&["seg-a".into(), "seg-b".into()], &mapping));
+        assert!(!manifest.is_fresh(&["seg-a".into()], &mapping));
+        assert!(!manifest.is_fresh(&["seg-a".into(), "seg-b".into(), "seg-c".into()], &mapping));
+
+        let mut changed = mapping.clone();
+        changed.events.remove("Login");
+        assert!(!manifest.is_fresh(&ids, &changed));
+    }
+}
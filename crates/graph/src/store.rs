@@ -1,11 +1,11 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use stupid_core::{EdgeId, EdgeType, EntityType, NodeId, SegmentId};
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Node {
     pub id: NodeId,
     pub entity_type: EntityType,
@@ -15,7 +15,7 @@ pub struct Node {
     pub last_seen: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     pub id: EdgeId,
     pub source: NodeId,
@@ -35,6 +35,29 @@ pub struct GraphStats {
     pub edges_by_type: HashMap<String, usize>,
 }
 
+/// Criteria for [`GraphStore::nodes_page`]; `None` fields match every node.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NodeFilter<'a> {
+    pub entity_type: Option<EntityType>,
+    /// Matches the start of the node key, e.g. `device:` or `game:evo`.
+    pub key_prefix: Option<&'a str>,
+}
+
+impl NodeFilter<'_> {
+    fn matches(&self, node: &Node) -> bool {
+        self.entity_type.is_none_or(|t| node.entity_type == t)
+            && self.key_prefix.is_none_or(|p| node.key.starts_with(p))
+    }
+}
+
+/// One page of nodes matching a [`NodeFilter`].
+#[derive(Debug)]
+pub struct NodePage<'a> {
+    /// Number of matching nodes across all pages.
+    pub total: usize,
+    pub nodes: Vec<&'a Node>,
+}
+
 pub struct GraphStore {
     pub nodes: HashMap<NodeId, Node>,
     key_index: HashMap<(EntityType, String), NodeId>,
@@ -43,6 +66,8 @@ pub struct GraphStore {
     pub outgoing: HashMap<NodeId, Vec<EdgeId>>,
     pub incoming: HashMap<NodeId, Vec<EdgeId>>,
     segment_edges: HashMap<SegmentId, Vec<EdgeId>>,
+    /// Node ids in sorted order, so pages are stable across requests.
+    node_order: BTreeSet<NodeId>,
 }
 
 impl GraphStore {
@@ -55,6 +80,7 @@ impl GraphStore {
             outgoing: HashMap::new(),
             incoming: HashMap::new(),
             segment_edges: HashMap::new(),
+            node_order: BTreeSet::new(),
         }
     }
 
@@ -86,11 +112,16 @@ impl GraphStore {
             last_seen: now,
         };
 
-        self.nodes.insert(id, node);
-        self.key_index.insert(lookup, id);
+        self.insert_node(node);
         id
     }
 
+    pub(crate) fn insert_node(&mut self, node: Node) {
+        self.key_index.insert((node.entity_type, node.key.clone()), node.id);
+        self.node_order.insert(node.id);
+        self.nodes.insert(node.id, node);
+    }
+
     pub fn add_edge(
         &mut self,
         source: NodeId,
@@ -120,14 +151,52 @@ impl GraphStore {
             segment_id: segment_id.clone(),
         };
 
-        self.edges.insert(id, edge);
-        self.edge_dedup.insert(dedup_key, id);
-        self.outgoing.entry(source).or_default().push(id);
-        self.incoming.entry(target).or_default().push(id);
-        self.segment_edges.entry(segment_id.clone()).or_default().push(id);
+        self.insert_edge(edge);
         id
     }
 
+    pub(crate) fn insert_edge(&mut self, edge: Edge) {
+        let id = edge.id;
+        self.edge_dedup.insert((edge.source, edge.target, edge.edge_type), id);
+        self.outgoing.entry(edge.source).or_default().push(id);
+        self.incoming.entry(edge.target).or_default().push(id);
+        self.segment_edges.entry(edge.segment_id.clone()).or_default().push(id);
+        self.edges.insert(id, edge);
+    }
+
+    /// Copy of the graph keeping only nodes of `entity_types` and the edges of
+    /// `edge_types` between them.
+    ///
+    /// Kept nodes and edges get fresh ids (one per original node, so edges
+    /// stay connected); keys, segment refs, timestamps and weights carry over.
+    pub fn subgraph(&self, entity_types: &[EntityType], edge_types: &[EdgeType]) -> GraphStore {
+        let mut sub = GraphStore::new();
+        let mut new_ids: HashMap<NodeId, NodeId> = HashMap::new();
+
+        for node in self.nodes_by_id().filter(|n| entity_types.contains(&n.entity_type)) {
+            let id = Uuid::new_v4();
+            new_ids.insert(node.id, id);
+            sub.insert_node(Node { id, ..node.clone() });
+        }
+
+        for node in self.nodes_by_id().filter(|n| new_ids.contains_key(&n.id)) {
+            for edge_id in self.outgoing.get(&node.id).into_iter().flatten() {
+                let Some(edge) = self.edges.get(edge_id) else { continue };
+                let Some(&target) = new_ids.get(&edge.target) else { continue };
+                if !edge_types.contains(&edge.edge_type) {
+                    continue;
+                }
+                sub.insert_edge(Edge {
+                    id: Uuid::new_v4(),
+                    source: new_ids[&edge.source],
+                    target,
+                    ..edge.clone()
+                });
+            }
+        }
+        sub
+    }
+
     pub fn stats(&self) -> GraphStats {
         let mut nodes_by_type: HashMap<String, usize> = HashMap::new();
         for node in self.nodes.values() {
@@ -147,6 +216,26 @@ impl GraphStore {
         }
     }
 
+    /// Nodes matching `filter`, ordered by id, skipping `offset` and
+    /// returning at most `limit`. Only the returned window is collected.
+    pub fn nodes_page(&self, filter: NodeFilter<'_>, offset: usize, limit: usize) -> NodePage<'_> {
+        let mut total = 0;
+        let mut nodes = Vec::new();
+        let matching = self.nodes_by_id().filter(|n| filter.matches(n));
+        for node in matching {
+            if total >= offset && nodes.len() < limit {
+                nodes.push(node);
+            }
+            total += 1;
+        }
+        NodePage { total, nodes }
+    }
+
+    /// All nodes, ordered by id.
+    pub(crate) fn nodes_by_id(&self) -> impl Iterator<Item = &Node> + '_ {
+        self.node_order.iter().filter_map(|id| self.nodes.get(id))
+    }
+
     /// Get neighbors of a node (outgoing edges).
     pub fn neighbors(&self, node_id: &NodeId) -> Vec<(&Edge, &Node)> {
         let mut result = Vec::new();
@@ -171,3 +260,119 @@ impl GraphStore {
         result
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph() -> GraphStore {
+        let mut g = GraphStore::new();
+        let seg = "2025-06-14".to_string();
+        for key in ["member:alice", "member:bob", "member:carol"] {
+            g.upsert_node(EntityType::Member, key, &seg);
+        }
+        for key in ["device:a1", "device:a2", "device:b1"] {
+            g.upsert_node(EntityType::Device, key, &seg);
+        }
+        g.upsert_node(EntityType::Game, "game:evo:roulette", &seg);
+        g
+    }
+
+    fn keys(page: &NodePage<'_>) -> Vec<String> {
+        let mut keys: Vec<String> = page.nodes.iter().map(|n| n.key.clone()).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn filters_by_entity_type() {
+        let g = graph();
+        let filter = NodeFilter { entity_type: Some(EntityType::Device), ..Default::default() };
+        let page = g.nodes_page(filter, 0, 100);
+        assert_eq!(page.total, 3);
+        assert_eq!(keys(&page), vec!["device:a1", "device:a2", "device:b1"]);
+
+        let filter = NodeFilter { entity_type: Some(EntityType::Provider), ..Default::default() };
+        let page = g.nodes_page(filter, 0, 100);
+        assert_eq!(page.total, 0);
+        assert!(page.nodes.is_empty());
+    }
+
+    #[test]
+    fn filters_by_key_prefix() {
+        let g = graph();
+        let page = g.nodes_page(NodeFilter { key_prefix: Some("device:a"), ..Default::default() }, 0, 100);
+        assert_eq!(page.total, 2);
+        assert_eq!(keys(&page), vec!["device:a1", "device:a2"]);
+
+        let filter = NodeFilter { entity_type: Some(EntityType::Member), key_prefix: Some("device:") };
+        assert_eq!(g.nodes_page(filter, 0, 100).total, 0);
+    }
+
+    #[test]
+    fn pages_are_stable_and_sorted_by_id() {
+        let g = graph();
+        let all = g.nodes_page(NodeFilter::default(), 0, 100);
+        assert_eq!(all.total, 7);
+        let ids: Vec<NodeId> = all.nodes.iter().map(|n| n.id).collect();
+        assert!(ids.windows(2).all(|w| w[0] < w[1]));
+
+        let first = g.nodes_page(NodeFilter::default(), 0, 3);
+        let last = g.nodes_page(NodeFilter::default(), 6, 3);
+        assert_eq!((first.total, first.nodes.len()), (7, 3));
+        assert_eq!((last.total, last.nodes.len()), (7, 1));
+        assert_eq!(first.nodes[0].id, ids[0]);
+        assert_eq!(last.nodes[0].id, ids[6]);
+
+        let past_end = g.nodes_page(NodeFilter::default(), 7, 3);
+        assert_eq!(past_end.total, 7);
+        assert!(past_end.nodes.is_empty());
+        assert!(g.nodes_page(NodeFilter::default(), 0, 0).nodes.is_empty());
+    }
+
+    #[test]
+    fn subgraph_keeps_only_selected_types() {
+        let mut g = GraphStore::new();
+        let seg = "2025-06-14".to_string();
+        let alice = g.upsert_node(EntityType::Member, "member:alice", &seg);
+        let bob = g.upsert_node(EntityType::Member, "member:bob", &seg);
+        let dev = g.upsert_node(EntityType::Device, "device:d1", &seg);
+        let web = g.upsert_node(EntityType::Platform, "platform:web", &seg);
+        let game = g.upsert_node(EntityType::Game, "game:slots", &seg);
+        g.add_edge(alice, dev, EdgeType::LoggedInFrom, &seg);
+        g.add_edge(alice, dev, EdgeType::LoggedInFrom, &seg);
+        g.add_edge(bob, dev, EdgeType::LoggedInFrom, &seg);
+        g.add_edge(alice, web, EdgeType::PlaysOnPlatform, &seg);
+        g.add_edge(bob, game, EdgeType::OpenedGame, &seg);
+        g.add_edge(bob, dev, EdgeType::OpenedGame, &seg);
+
+        let sub = g.subgraph(&[EntityType::Member, EntityType::Device], &[EdgeType::LoggedInFrom]);
+
+        let stats = sub.stats();
+        assert_eq!(stats.node_count, 3);
+        assert_eq!(stats.nodes_by_type.get("Platform"), None);
+        assert_eq!(stats.nodes_by_type.get("Game"), None);
+        assert_eq!(stats.edge_count, 2);
+        assert!(sub.edges.values().all(|e| e.edge_type == EdgeType::LoggedInFrom));
+        assert!(sub.nodes.keys().all(|id| !g.nodes.contains_key(id)));
+
+        // Both members still reach the shared device, and weights carry over.
+        let sub_dev = sub.nodes.values().find(|n| n.key == "device:d1").unwrap().id;
+        let mut members: Vec<(String, f64)> = sub.incoming[&sub_dev]
+            .iter()
+            .map(|eid| {
+                let edge = &sub.edges[eid];
+                (sub.nodes[&edge.source].key.clone(), edge.weight)
+            })
+            .collect();
+        members.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            members,
+            vec![("member:alice".to_string(), 2.0), ("member:bob".to_string(), 1.0)]
+        );
+
+        // The copy is a regular store: upserts resolve to the renumbered nodes.
+        let mut sub = sub;
+        assert_eq!(sub.upsert_node(EntityType::Device, "device:d1", &seg), sub_dev);
+    }
+}
@@ -0,0 +1,237 @@
+//! Path and neighborhood queries over [`GraphStore`].
+//!
+//! Edges are followed in both directions, like [`GraphStore::neighbors`].
+//! Every search stops after visiting [`VISIT_BUDGET`] nodes so a hub with a
+//! huge fan-out can't stall the caller.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use stupid_core::{EdgeId, EdgeType, NodeId};
+
+use crate::store::{Edge, GraphStore, Node};
+
+/// Maximum number of nodes a single traversal may visit.
+pub const VISIT_BUDGET: usize = 50_000;
+
+/// A path between two nodes: `edges[i]` connects `nodes[i]` and `nodes[i + 1]`.
+#[derive(Debug)]
+pub struct GraphPath<'a> {
+    pub nodes: Vec<&'a Node>,
+    pub edges: Vec<&'a Edge>,
+}
+
+/// Nodes within some hops of a center node and every edge between them.
+#[derive(Debug)]
+pub struct Subgraph<'a> {
+    /// In BFS order, starting with the center node.
+    pub nodes: Vec<&'a Node>,
+    pub edges: Vec<&'a Edge>,
+    /// Whether the visit budget ran out before the full depth was explored.
+    pub truncated: bool,
+}
+
+impl GraphStore {
+    /// Shortest path from `from` to `to` of at most `max_depth` edges,
+    /// following only `edge_types` when given.
+    ///
+    /// Returns `None` if either node is missing, no such path exists, or the
+    /// visit budget runs out first.
+    pub fn shortest_path(
+        &self,
+        from: NodeId,
+        to: NodeId,
+        max_depth: usize,
+        edge_types: Option<&[EdgeType]>,
+    ) -> Option<GraphPath<'_>> {
+        if !self.nodes.contains_key(&from) || !self.nodes.contains_key(&to) {
+            return None;
+        }
+
+        // Node -> (previous node, edge used to reach it).
+        let mut came_from: HashMap<NodeId, Option<(NodeId, EdgeId)>> = HashMap::new();
+        came_from.insert(from, None);
+        let mut queue = VecDeque::from([(from, 0)]);
+
+        'search: while let Some((node_id, depth)) = queue.pop_front() {
+            if node_id == to {
+                break;
+            }
+            if depth == max_depth {
+                continue;
+            }
+            for (edge, next) in self.adjacent(node_id) {
+                if edge_types.is_some_and(|types| !types.contains(&edge.edge_type)) {
+                    continue;
+                }
+                if came_from.contains_key(&next) {
+                    continue;
+                }
+                if came_from.len() >= VISIT_BUDGET {
+                    break 'search;
+                }
+                came_from.insert(next, Some((node_id, edge.id)));
+                queue.push_back((next, depth + 1));
+            }
+        }
+
+        came_from.get(&to)?;
+        let mut nodes = vec![&self.nodes[&to]];
+        let mut edges = Vec::new();
+        let mut current = to;
+        while let Some(&Some((prev, edge_id))) = came_from.get(&current) {
+            edges.push(&self.edges[&edge_id]);
+            nodes.push(&self.nodes[&prev]);
+            current = prev;
+        }
+        nodes.reverse();
+        edges.reverse();
+        Some(GraphPath { nodes, edges })
+    }
+
+    /// Nodes at most `depth` hops from `node`, with the edges among them.
+    ///
+    /// Returns an empty subgraph if `node` doesn't exist.
+    pub fn neighborhood(&self, node: NodeId, depth: usize) -> Subgraph<'_> {
+        let mut subgraph = Subgraph { nodes: Vec::new(), edges: Vec::new(), truncated: false };
+        let Some(center) = self.nodes.get(&node) else {
+            return subgraph;
+        };
+
+        let mut visited = HashSet::from([node]);
+        subgraph.nodes.push(center);
+        let mut queue = VecDeque::from([(node, 0)]);
+
+        'search: while let Some((node_id, hops)) = queue.pop_front() {
+            if hops == depth {
+                continue;
+            }
+            for (_, next) in self.adjacent(node_id) {
+                if visited.contains(&next) {
+                    continue;
+                }
+                if visited.len() >= VISIT_BUDGET {
+                    subgraph.truncated = true;
+                    break 'search;
+                }
+                visited.insert(next);
+                subgraph.nodes.push(&self.nodes[&next]);
+                queue.push_back((next, hops + 1));
+            }
+        }
+
+        // Each edge is listed once, in the outgoing list of its source.
+        for node in &subgraph.nodes {
+            for edge_id in self.outgoing.get(&node.id).into_iter().flatten() {
+                if let Some(edge) = self.edges.get(edge_id) {
+                    if visited.contains(&edge.target) {
+                        subgraph.edges.push(edge);
+                    }
+                }
+            }
+        }
+        subgraph
+    }
+
+    /// Edges touching `node_id` in either direction, paired with the node at
+    /// the other end.
+    fn adjacent(&self, node_id: NodeId) -> impl Iterator<Item = (&Edge, NodeId)> + '_ {
+        let outgoing = self.outgoing.get(&node_id).into_iter().flatten();
+        let incoming = self.incoming.get(&node_id).into_iter().flatten();
+        outgoing
+            .chain(incoming)
+            .filter_map(|eid| self.edges.get(eid))
+            .filter(|edge| self.nodes.contains_key(&edge.target) && self.nodes.contains_key(&edge.source))
+            .map(move |edge| {
+                let other = if edge.source == node_id { edge.target } else { edge.source };
+                (edge, other)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stupid_core::EntityType;
+
+    /// alice -> dev1 <- bob -> game1 -> provider1; carol is isolated.
+    struct Fixture {
+        graph: GraphStore,
+        alice: NodeId,
+        bob: NodeId,
+        carol: NodeId,
+        dev1: NodeId,
+        game1: NodeId,
+        provider1: NodeId,
+    }
+
+    fn fixture() -> Fixture {
+        let mut graph = GraphStore::new();
+        let seg = "2025-06-14".to_string();
+        let alice = graph.upsert_node(EntityType::Member, "member:alice", &seg);
+        let bob = graph.upsert_node(EntityType::Member, "member:bob", &seg);
+        let carol = graph.upsert_node(EntityType::Member, "member:carol", &seg);
+        let dev1 = graph.upsert_node(EntityType::Device, "device:dev1", &seg);
+        let game1 = graph.upsert_node(EntityType::Game, "game:game1", &seg);
+        let provider1 = graph.upsert_node(EntityType::Provider, "provider:provider1", &seg);
+
+        graph.add_edge(alice, dev1, EdgeType::LoggedInFrom, &seg);
+        graph.add_edge(bob, dev1, EdgeType::LoggedInFrom, &seg);
+        graph.add_edge(bob, game1, EdgeType::OpenedGame, &seg);
+        graph.add_edge(game1, provider1, EdgeType::ProvidedBy, &seg);
+
+        Fixture { graph, alice, bob, carol, dev1, game1, provider1 }
+    }
+
+    fn keys(nodes: &[&Node]) -> Vec<String> {
+        nodes.iter().map(|n| n.key.clone()).collect()
+    }
+
+    #[test]
+    fn shortest_path_follows_edges_both_ways() {
+        let f = fixture();
+        let path = f.graph.shortest_path(f.alice, f.provider1, 5, None).unwrap();
+        assert_eq!(
+            keys(&path.nodes),
+            vec!["member:alice", "device:dev1", "member:bob", "game:game1", "provider:provider1"]
+        );
+        let edge_types: Vec<EdgeType> = path.edges.iter().map(|e| e.edge_type).collect();
+        assert_eq!(
+            edge_types,
+            vec![EdgeType::LoggedInFrom, EdgeType::LoggedInFrom, EdgeType::OpenedGame, EdgeType::ProvidedBy]
+        );
+
+        let to_self = f.graph.shortest_path(f.bob, f.bob, 0, None).unwrap();
+        assert_eq!(keys(&to_self.nodes), vec!["member:bob"]);
+        assert!(to_self.edges.is_empty());
+    }
+
+    #[test]
+    fn shortest_path_respects_depth_and_edge_types() {
+        let f = fixture();
+        assert!(f.graph.shortest_path(f.alice, f.carol, 10, None).is_none());
+        assert!(f.graph.shortest_path(f.alice, f.provider1, 3, None).is_none());
+        assert!(f.graph.shortest_path(f.alice, f.game1, 5, Some(&[EdgeType::LoggedInFrom])).is_none());
+
+        let path = f.graph.shortest_path(f.alice, f.bob, 2, Some(&[EdgeType::LoggedInFrom])).unwrap();
+        assert_eq!(keys(&path.nodes), vec!["member:alice", "device:dev1", "member:bob"]);
+    }
+
+    #[test]
+    fn neighborhood_is_depth_limited_induced_subgraph() {
+        let f = fixture();
+        let hood = f.graph.neighborhood(f.bob, 1);
+        let mut ids: Vec<NodeId> = hood.nodes.iter().map(|n| n.id).collect();
+        assert_eq!(ids[0], f.bob);
+        ids.sort();
+        let mut expected = vec![f.bob, f.dev1, f.game1];
+        expected.sort();
+        assert_eq!(ids, expected);
+        // bob->dev1 and bob->game1; alice->dev1 and game1->provider1 leave the subgraph.
+        assert_eq!(hood.edges.len(), 2);
+        assert!(!hood.truncated);
+
+        assert_eq!(f.graph.neighborhood(f.bob, 2).nodes.len(), 5);
+        assert_eq!(f.graph.neighborhood(f.carol, 3).nodes.len(), 1);
+        assert!(f.graph.neighborhood(uuid::Uuid::new_v4(), 1).nodes.is_empty());
+    }
+}
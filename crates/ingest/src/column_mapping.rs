@@ -0,0 +1,130 @@
+//! Column → field mapping shared by the row-oriented importers
+//! ([`CsvImporter`](crate::csv_import::CsvImporter) and
+//! [`JsonlImporter`](crate::jsonl_import::JsonlImporter)).
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use stupid_core::{is_null_sentinel, DocId, Document, FieldValue};
+
+/// How source columns become document fields.
+#[derive(Debug, Clone)]
+pub struct ColumnMapping {
+    /// Source column → field name. Unlisted columns keep their own name.
+    pub rename: HashMap<String, String>,
+    /// Store values that look like integers, floats or booleans as typed
+    /// fields. Off by default: like `ParquetImporter`, everything is text,
+    /// which is what entity extraction reads (e.g. numeric `memberCode`s).
+    pub infer_types: bool,
+    /// Field (after renaming) holding the event time; rows without a
+    /// parseable one are stamped with the import time.
+    pub timestamp_field: String,
+    /// Derive each document's ID from its content
+    /// ([`Document::content_id`]) instead of a random UUID, so re-importing
+    /// the same rows yields the same IDs.
+    pub content_ids: bool,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self {
+            rename: HashMap::new(),
+            infer_types: false,
+            timestamp_field: "@timestamp".to_string(),
+            content_ids: false,
+        }
+    }
+}
+
+/// Documents read from a file, plus the rows that couldn't be parsed.
+#[derive(Debug)]
+pub struct ImportOutcome {
+    pub documents: Vec<Document>,
+    pub skipped_rows: usize,
+}
+
+impl ColumnMapping {
+    /// Field name for `column`.
+    pub fn field_name<'a>(&'a self, column: &'a str) -> &'a str {
+        self.rename.get(column).map_or(column, String::as_str)
+    }
+
+    /// Value for raw text, or `None` if it is empty or a null sentinel.
+    pub fn text_value(&self, raw: &str) -> Option<FieldValue> {
+        let value = raw.trim();
+        if is_null_sentinel(value) {
+            return None;
+        }
+        if self.infer_types {
+            if let Some(typed) = infer(value) {
+                return Some(typed);
+            }
+        }
+        Some(FieldValue::Text(value.to_string()))
+    }
+
+    /// Assemble a document from already-mapped fields.
+    pub(crate) fn document(&self, event_type: &str, fields: HashMap<String, FieldValue>) -> Document {
+        let timestamp: Option<DateTime<Utc>> = match fields.get(&self.timestamp_field) {
+            Some(FieldValue::Text(s)) => Document::parse_timestamp(s),
+            Some(FieldValue::Integer(n)) => Document::parse_timestamp(&n.to_string()),
+            Some(FieldValue::Float(f)) => Document::parse_timestamp(&f.to_string()),
+            _ => None,
+        };
+        let mut doc = Document {
+            id: DocId::new_v4(),
+            timestamp: timestamp.unwrap_or_else(Utc::now),
+            event_type: event_type.to_string(),
+            fields,
+        };
+        if self.content_ids {
+            doc.id = doc.content_id();
+        }
+        doc
+    }
+}
+
+/// Integer, float or boolean reading of `value`, if it has one.
+/// Numbers with leading zeros (`007`, codes and IDs) stay text.
+fn infer(value: &str) -> Option<FieldValue> {
+    if value.eq_ignore_ascii_case("true") {
+        return Some(FieldValue::Boolean(true));
+    }
+    if value.eq_ignore_ascii_case("false") {
+        return Some(FieldValue::Boolean(false));
+    }
+    let digits = value.strip_prefix('-').unwrap_or(value);
+    if digits.len() > 1 && digits.starts_with('0') && !digits.starts_with("0.") {
+        return None;
+    }
+    if let Ok(i) = value.parse::<i64>() {
+        return Some(FieldValue::Integer(i));
+    }
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|f| f.is_finite() && digits.starts_with(|c: char| c.is_ascii_digit()))
+        .map(FieldValue::Float)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_value_infers_only_when_enabled() {
+        let plain = ColumnMapping::default();
+        assert_eq!(plain.text_value(" 42 "), Some(FieldValue::Text("42".into())));
+        assert_eq!(plain.text_value("null"), None);
+        assert_eq!(plain.text_value("  "), None);
+
+        let typed = ColumnMapping { infer_types: true, ..Default::default() };
+        assert_eq!(typed.text_value("42"), Some(FieldValue::Integer(42)));
+        assert_eq!(typed.text_value("-1.5"), Some(FieldValue::Float(-1.5)));
+        assert_eq!(typed.text_value("TRUE"), Some(FieldValue::Boolean(true)));
+        assert_eq!(typed.text_value("007"), Some(FieldValue::Text("007".into())));
+        assert_eq!(typed.text_value("0.25"), Some(FieldValue::Float(0.25)));
+        assert_eq!(typed.text_value("NaN"), Some(FieldValue::Text("NaN".into())));
+        assert_eq!(typed.text_value("inf"), Some(FieldValue::Text("inf".into())));
+    }
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use stupid_core::StupidError;
+use tracing::{info, warn};
+
+use crate::column_mapping::{ColumnMapping, ImportOutcome};
+
+/// Imports a CSV file with a header row, one document per row.
+pub struct CsvImporter {
+    mapping: ColumnMapping,
+}
+
+impl CsvImporter {
+    pub fn new(mapping: ColumnMapping) -> Self {
+        Self { mapping }
+    }
+
+    /// Read `path`, skipping (and counting) rows that don't parse or whose
+    /// column count doesn't match the header.
+    pub fn import(&self, path: &Path, event_type: &str) -> Result<ImportOutcome, StupidError> {
+        let file = std::fs::File::open(path).map_err(StupidError::Io)?;
+        let mut reader = csv::ReaderBuilder::new().has_headers(true).from_reader(file);
+        let headers = reader
+            .headers()
+            .map_err(|e| StupidError::Serialize(e.into()))?
+            .clone();
+        let fields: Vec<&str> = headers.iter().map(|h| self.mapping.field_name(h.trim())).collect();
+
+        let mut documents = Vec::new();
+        let mut skipped_rows = 0;
+        for (row, record) in reader.records().enumerate() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    warn!("Skipping row {} of {}: {}", row + 1, path.display(), e);
+                    skipped_rows += 1;
+                    continue;
+                }
+            };
+
+            let doc_fields: HashMap<_, _> = fields
+                .iter()
+                .zip(record.iter())
+                .filter_map(|(&field, raw)| Some((field.to_string(), self.mapping.text_value(raw)?)))
+                .collect();
+            documents.push(self.mapping.document(event_type, doc_fields));
+        }
+
+        info!(
+            "Imported {} documents from {} ({} rows skipped)",
+            documents.len(),
+            path.display(),
+            skipped_rows
+        );
+        Ok(ImportOutcome { documents, skipped_rows })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stupid_core::FieldValue;
+
+    #[test]
+    fn imports_rows_and_skips_malformed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("2025-06-14.csv");
+        std::fs::write(
+            &path,
+            "@timestamp,member_code,amount,note\n\
+             2025-06-14T10:00:00Z,M001,12.5,\"first, with comma\"\n\
+             2025-06-14T11:00:00Z,M002,oops\n\
+             2025-06-14T12:00:00Z,M003,7,null\n",
+        )
+        .unwrap();
+
+        let mapping = ColumnMapping {
+            rename: HashMap::from([("member_code".to_string(), "memberCode".to_string())]),
+            infer_types: true,
+            ..Default::default()
+        };
+        let outcome = CsvImporter::new(mapping).import(&path, "Deposit").unwrap();
+
+        assert_eq!(outcome.skipped_rows, 1);
+        assert_eq!(outcome.documents.len(), 2);
+        let first = &outcome.documents[0];
+        assert_eq!(first.event_type, "Deposit");
+        assert_eq!(first.timestamp.to_rfc3339(), "2025-06-14T10:00:00+00:00");
+        assert_eq!(first.field_str("memberCode"), Some("M001"));
+        assert!(!first.fields.contains_key("member_code"));
+        assert_eq!(first.fields["amount"], FieldValue::Float(12.5));
+        assert_eq!(first.field_str("note"), Some("first, with comma"));
+
+        let last = &outcome.documents[1];
+        assert_eq!(last.fields["amount"], FieldValue::Integer(7));
+        assert!(!last.fields.contains_key("note"));
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let importer = CsvImporter::new(ColumnMapping::default());
+        assert!(importer.import(Path::new("/nonexistent/x.csv"), "Login").is_err());
+    }
+}
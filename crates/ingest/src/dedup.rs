@@ -0,0 +1,371 @@
+//! Import-time deduplication of documents that describe the same event.
+//!
+//! Re-importing an overlapping dump produces new [`Document`]s (fresh IDs)
+//! for events already stored. A [`DedupKey`] says which fields identify an
+//! event; a [`Deduplicator`] drops documents whose key was already seen in
+//! the current import run and, with a [`BloomSeenSet`] attached, in earlier
+//! runs too. Seen-set hits can be confirmed against the store holding the
+//! earlier runs' documents (see [`Deduplicator::retain_unique_confirmed`]),
+//! so bloom false positives don't drop new events.
+
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+use stupid_core::hashing::{bloom_contains, bloom_insert, bloom_size, Fnv1a};
+use stupid_core::{Document, FieldValue};
+
+/// Fields that identify the underlying event of a document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DedupKey {
+    /// A single field that is unique per event (e.g. `eventId`).
+    Field(String),
+    /// Hash of several fields that together identify an event.
+    Fields(Vec<String>),
+}
+
+impl DedupKey {
+    /// Parse a comma-separated field list: one name becomes
+    /// [`DedupKey::Field`], several become [`DedupKey::Fields`].
+    /// Returns `None` if no field name is given.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let mut fields: Vec<String> = spec
+            .split(',')
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+            .map(str::to_string)
+            .collect();
+        match fields.len() {
+            0 => None,
+            1 => fields.pop().map(DedupKey::Field),
+            _ => Some(DedupKey::Fields(fields)),
+        }
+    }
+
+    /// The key's field names, in order.
+    pub fn field_names(&self) -> &[String] {
+        match self {
+            DedupKey::Field(name) => std::slice::from_ref(name),
+            DedupKey::Fields(names) => names,
+        }
+    }
+
+    /// Stable 64-bit key of `doc`, scoped to its event type. `None` when the
+    /// document has none of the key fields, so it can't be deduplicated.
+    pub fn key(&self, doc: &Document) -> Option<u64> {
+        let mut hasher = Fnv1a::default();
+        hasher.write(doc.event_type.as_bytes());
+        let mut present = false;
+        for name in self.field_names() {
+            hasher.write(&[0xff]);
+            match doc.fields.get(name) {
+                Some(FieldValue::Null) | None => hasher.write(&[0]),
+                Some(value) => {
+                    present = true;
+                    hash_value(&mut hasher, value);
+                }
+            }
+        }
+        present.then(|| hasher.finish())
+    }
+}
+
+fn hash_value(hasher: &mut Fnv1a, value: &FieldValue) {
+    match value {
+        FieldValue::Text(s) => {
+            hasher.write(&[1]);
+            hasher.write(s.as_bytes());
+        }
+        FieldValue::Integer(i) => {
+            hasher.write(&[2]);
+            hasher.write(&i.to_le_bytes());
+        }
+        FieldValue::Float(f) => {
+            hasher.write(&[3]);
+            hasher.write(&f.to_bits().to_le_bytes());
+        }
+        FieldValue::Boolean(b) => hasher.write(&[4, *b as u8]),
+        FieldValue::Null => hasher.write(&[0]),
+    }
+}
+
+/// Bloom filter over dedup keys, for remembering events across import runs
+/// without keeping every key. False positives report a genuinely new key as
+/// seen at roughly the configured rate; there are no false negatives.
+///
+/// The filter grows as keys are added: once the newest layer holds the keys
+/// it was sized for, a layer twice as large (at half the false-positive rate,
+/// keeping the overall rate within twice the configured one) takes over.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BloomSeenSet {
+    layers: Vec<BloomLayer>,
+    false_positive_rate: f64,
+}
+
+/// One fixed-size layer of a [`BloomSeenSet`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BloomLayer {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    /// Keys this layer was sized for.
+    capacity: u64,
+    /// Keys inserted into this layer.
+    len: u64,
+}
+
+impl BloomLayer {
+    fn with_capacity(expected_items: u64, false_positive_rate: f64) -> Self {
+        let (num_bits, num_hashes) = bloom_size(expected_items, false_positive_rate);
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+            capacity: expected_items.max(1),
+            len: 0,
+        }
+    }
+
+    fn contains(&self, key: u64) -> bool {
+        bloom_contains(&self.bits, self.num_bits, self.num_hashes, key)
+    }
+
+    fn insert(&mut self, key: u64) {
+        bloom_insert(&mut self.bits, self.num_bits, self.num_hashes, key);
+        self.len += 1;
+    }
+}
+
+impl BloomSeenSet {
+    /// A filter sized for `expected_items` keys at `false_positive_rate`
+    /// (clamped to `0.0001..=0.5`) before it first grows.
+    pub fn with_capacity(expected_items: usize, false_positive_rate: f64) -> Self {
+        let false_positive_rate = false_positive_rate.clamp(0.0001, 0.5);
+        Self {
+            layers: vec![BloomLayer::with_capacity(expected_items as u64, false_positive_rate / 2.0)],
+            false_positive_rate,
+        }
+    }
+
+    /// Keys inserted so far.
+    pub fn len(&self) -> u64 {
+        self.layers.iter().map(|layer| layer.len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of layers the filter has grown to.
+    pub fn layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Whether `key` may have been inserted before.
+    pub fn contains(&self, key: u64) -> bool {
+        self.layers.iter().any(|layer| layer.contains(key))
+    }
+
+    /// Insert `key`. Returns `false` if it may already have been present.
+    pub fn insert(&mut self, key: u64) -> bool {
+        if self.contains(key) {
+            return false;
+        }
+        let newest = self.layers.last().expect("seen-set has at least one layer");
+        if newest.len >= newest.capacity {
+            let rate = self.false_positive_rate / 2f64.powi(self.layers.len() as i32 + 1);
+            let grown = BloomLayer::with_capacity(newest.capacity.saturating_mul(2), rate.max(f64::MIN_POSITIVE));
+            self.layers.push(grown);
+        }
+        self.layers.last_mut().expect("seen-set has at least one layer").insert(key);
+        true
+    }
+}
+
+/// Drops documents whose [`DedupKey`] was already seen during this import
+/// run, and optionally in earlier runs via a [`BloomSeenSet`].
+#[derive(Debug)]
+pub struct Deduplicator {
+    key: DedupKey,
+    seen: HashSet<u64>,
+    seen_set: Option<BloomSeenSet>,
+    duplicates: usize,
+}
+
+impl Deduplicator {
+    pub fn new(key: DedupKey) -> Self {
+        Self { key, seen: HashSet::new(), seen_set: None, duplicates: 0 }
+    }
+
+    /// Also skip documents recorded in `seen_set` by earlier runs, and record
+    /// this run's keys in it.
+    pub fn with_seen_set(mut self, seen_set: BloomSeenSet) -> Self {
+        self.seen_set = Some(seen_set);
+        self
+    }
+
+    /// Record `doc`'s key and report whether it was a duplicate. Documents
+    /// without any key field are never duplicates.
+    pub fn is_duplicate(&mut self, doc: &Document) -> bool {
+        self.is_duplicate_confirmed(doc, |_| true)
+    }
+
+    /// Like [`is_duplicate`](Self::is_duplicate), but a seen-set hit only
+    /// counts if `confirm` agrees that the event is already stored; otherwise
+    /// it was a false positive and the document is kept.
+    pub fn is_duplicate_confirmed(&mut self, doc: &Document, confirm: impl FnOnce(&Document) -> bool) -> bool {
+        let Some(key) = self.key.key(doc) else {
+            return false;
+        };
+        let seen_before = !self.seen.insert(key);
+        // Checked after the exact set, so only cross-run repeats hit the filter.
+        let seen_in_earlier_run = !seen_before
+            && self.seen_set.as_mut().is_some_and(|set| !set.insert(key))
+            && confirm(doc);
+        let duplicate = seen_before || seen_in_earlier_run;
+        if duplicate {
+            self.duplicates += 1;
+        }
+        duplicate
+    }
+
+    /// Keep only the documents that are not duplicates, in order.
+    pub fn retain_unique(&mut self, documents: Vec<Document>) -> Vec<Document> {
+        documents.into_iter().filter(|doc| !self.is_duplicate(doc)).collect()
+    }
+
+    /// Like [`retain_unique`](Self::retain_unique), confirming seen-set hits
+    /// with `confirm` (see [`is_duplicate_confirmed`](Self::is_duplicate_confirmed)).
+    pub fn retain_unique_confirmed(
+        &mut self,
+        documents: Vec<Document>,
+        mut confirm: impl FnMut(&Document) -> bool,
+    ) -> Vec<Document> {
+        documents
+            .into_iter()
+            .filter(|doc| !self.is_duplicate_confirmed(doc, &mut confirm))
+            .collect()
+    }
+
+    /// Documents dropped as duplicates so far.
+    pub fn duplicates(&self) -> usize {
+        self.duplicates
+    }
+
+    /// The cross-run seen-set, updated with this run's keys, for persisting.
+    pub fn into_seen_set(self) -> Option<BloomSeenSet> {
+        self.seen_set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn doc(fields: &[(&str, &str)]) -> Document {
+        let fields: HashMap<String, FieldValue> = fields
+            .iter()
+            .map(|(k, v)| (k.to_string(), FieldValue::Text(v.to_string())))
+            .collect();
+        Document {
+            id: stupid_core::DocId::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: "Login".to_string(),
+            fields,
+        }
+    }
+
+    #[test]
+    fn parse_dedup_key() {
+        assert_eq!(DedupKey::parse("eventId"), Some(DedupKey::Field("eventId".into())));
+        assert_eq!(
+            DedupKey::parse(" memberCode, @timestamp ,"),
+            Some(DedupKey::Fields(vec!["memberCode".into(), "@timestamp".into()]))
+        );
+        assert_eq!(DedupKey::parse(" , "), None);
+    }
+
+    #[test]
+    fn dedup_by_configurable_key() {
+        let docs = vec![
+            doc(&[("memberCode", "M1"), ("@timestamp", "2025-06-14T10:00:00Z")]),
+            doc(&[("memberCode", "M1"), ("@timestamp", "2025-06-14T11:00:00Z")]),
+            doc(&[("memberCode", "M1"), ("@timestamp", "2025-06-14T10:00:00Z")]),
+            doc(&[("@timestamp", "2025-06-14T10:00:00Z")]),
+        ];
+
+        let mut by_member = Deduplicator::new(DedupKey::parse("memberCode").unwrap());
+        // The document without `memberCode` has no key and is kept.
+        assert_eq!(by_member.retain_unique(docs.clone()).len(), 2);
+        assert_eq!(by_member.duplicates(), 2);
+
+        let mut by_event = Deduplicator::new(DedupKey::parse("memberCode,@timestamp").unwrap());
+        let kept = by_event.retain_unique(docs);
+        assert_eq!(kept.len(), 3);
+        assert_eq!(by_event.duplicates(), 1);
+    }
+
+    #[test]
+    fn key_is_scoped_to_event_type() {
+        let login = doc(&[("eventId", "e1")]);
+        let mut other = login.clone();
+        other.event_type = "GameOpened".to_string();
+
+        let key = DedupKey::Field("eventId".into());
+        assert_ne!(key.key(&login), key.key(&other));
+        assert_eq!(key.key(&login), key.key(&doc(&[("eventId", "e1")])));
+    }
+
+    #[test]
+    fn seen_set_dedups_across_runs() {
+        let key = DedupKey::Field("eventId".into());
+        let mut first = Deduplicator::new(key.clone()).with_seen_set(BloomSeenSet::with_capacity(1000, 0.01));
+        assert_eq!(first.retain_unique(vec![doc(&[("eventId", "e1")]), doc(&[("eventId", "e2")])]).len(), 2);
+        let seen = first.into_seen_set().unwrap();
+        assert_eq!(seen.len(), 2);
+
+        let restored: BloomSeenSet = serde_json::from_str(&serde_json::to_string(&seen).unwrap()).unwrap();
+        let mut second = Deduplicator::new(key).with_seen_set(restored);
+        let kept = second.retain_unique(vec![doc(&[("eventId", "e2")]), doc(&[("eventId", "e3")])]);
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].fields["eventId"], FieldValue::Text("e3".into()));
+        assert_eq!(second.duplicates(), 1);
+    }
+
+    #[test]
+    fn unconfirmed_seen_set_hits_are_kept() {
+        let key = DedupKey::Field("eventId".into());
+        let mut seen = BloomSeenSet::with_capacity(1000, 0.01);
+        seen.insert(key.key(&doc(&[("eventId", "e1")])).unwrap());
+
+        let mut dedup = Deduplicator::new(key).with_seen_set(seen);
+        let docs = vec![doc(&[("eventId", "e1")]), doc(&[("eventId", "e1")])];
+        // The store doesn't hold e1, so the hit was a false positive; the
+        // second copy is still an exact in-run duplicate.
+        assert_eq!(dedup.retain_unique_confirmed(docs, |_| false).len(), 1);
+        assert_eq!(dedup.duplicates(), 1);
+    }
+
+    #[test]
+    fn seen_set_grows_past_its_capacity() {
+        let mut set = BloomSeenSet::with_capacity(1000, 0.01);
+        for key in 0..10_000u64 {
+            set.insert(key);
+        }
+        assert!(set.layers() > 1);
+        assert!((0..10_000u64).all(|key| set.contains(key)));
+        let false_positives = (10_000..20_000u64).filter(|key| set.contains(*key)).count();
+        assert!(false_positives < 300, "{false_positives} false positives");
+    }
+
+    #[test]
+    fn bloom_false_positive_rate_is_bounded() {
+        let mut set = BloomSeenSet::with_capacity(10_000, 0.01);
+        for key in 0..10_000u64 {
+            set.insert(key);
+        }
+        assert!((0..10_000u64).all(|key| set.contains(key)));
+        let false_positives = (10_000..20_000u64).filter(|key| set.contains(*key)).count();
+        assert!(false_positives < 300, "{false_positives} false positives");
+    }
+}
@@ -2,14 +2,17 @@
 //!
 //! Splits extracted documents into overlapping chunks suitable for embedding,
 //! dispatching strategy by file type: markdown (heading-aware), PDF (page-aware),
-//! and plain text (paragraph/sentence splitting).
+//! and plain text (paragraph/sentence splitting). [`chunk_spans`] instead packs
+//! raw text into token windows with exact offsets.
 
 mod helpers;
+mod spans;
 mod strategies;
 mod types;
 
+pub use spans::chunk_spans;
 pub use strategies::chunk_document;
-pub use types::{Chunk, ChunkConfig};
+pub use types::{Chunk, ChunkBoundary, ChunkConfig, SpanChunk};
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,104 @@
+//! Token-window chunking over raw text with exact offsets for citation.
+//!
+//! Unlike the file-type strategies, chunks are packed greedily up to
+//! `max_chunk_tokens` and end at the last preferred boundary that fits;
+//! consecutive chunks share `overlap_tokens` words.
+
+use super::types::{ChunkBoundary, ChunkConfig, SpanChunk};
+
+/// Byte range of one whitespace-separated word.
+struct Token {
+    start: usize,
+    end: usize,
+}
+
+/// How strong a break follows a token, weakest first.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Break {
+    None,
+    Sentence,
+    Paragraph,
+}
+
+/// Split `text` into chunks of at most `config.max_chunk_tokens` words.
+///
+/// A chunk that can't take the rest of the text ends at the last boundary of
+/// the configured kind that keeps it at least half full, falling back to a
+/// sentence end and then to a hard cut. `min_chunk_tokens` is not used.
+pub fn chunk_spans(text: &str, config: &ChunkConfig) -> Vec<SpanChunk> {
+    let tokens = tokenize(text);
+    let breaks: Vec<Break> = (0..tokens.len()).map(|i| break_after(text, &tokens, i)).collect();
+    let preferred = match config.boundary {
+        ChunkBoundary::Sentence => Break::Sentence,
+        ChunkBoundary::Paragraph => Break::Paragraph,
+    };
+
+    let max = config.max_chunk_tokens.max(1);
+    let overlap = config.overlap_tokens.min(max - 1);
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < tokens.len() {
+        let limit = (start + max).min(tokens.len());
+        let end = if limit == tokens.len() {
+            limit
+        } else {
+            // Shortest acceptable chunk: half full, and past the overlap so
+            // the next chunk starts further on.
+            let min_end = start + (max / 2).max(overlap + 1);
+            [preferred, Break::Sentence]
+                .into_iter()
+                .find_map(|level| (min_end..=limit).rev().find(|&end| breaks[end - 1] >= level))
+                .unwrap_or(limit)
+        };
+
+        let (from, to) = (tokens[start].start, tokens[end - 1].end);
+        chunks.push(SpanChunk {
+            index: chunks.len(),
+            content: text[from..to].to_string(),
+            start: from,
+            end: to,
+            token_count: end - start,
+        });
+
+        if end == tokens.len() {
+            break;
+        }
+        start = end - overlap;
+    }
+    chunks
+}
+
+fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word_start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_whitespace(), word_start) {
+            (true, Some(start)) => {
+                tokens.push(Token { start, end: i });
+                word_start = None;
+            }
+            (false, None) => word_start = Some(i),
+            _ => {}
+        }
+    }
+    if let Some(start) = word_start {
+        tokens.push(Token { start, end: text.len() });
+    }
+    tokens
+}
+
+fn break_after(text: &str, tokens: &[Token], i: usize) -> Break {
+    let paragraph = match tokens.get(i + 1) {
+        Some(next) => text[tokens[i].end..next.start].matches('\n').count() >= 2,
+        None => true,
+    };
+    let word = text[tokens[i].start..tokens[i].end].trim_end_matches(['"', '\'', ')', ']']);
+    if paragraph {
+        Break::Paragraph
+    } else if word.ends_with(['.', '!', '?']) {
+        Break::Sentence
+    } else {
+        Break::None
+    }
+}
@@ -1,8 +1,9 @@
 //! Tests for the chunking engine.
 
 use super::helpers::{count_tokens, get_overlap_text, split_sentences};
+use super::spans::chunk_spans;
 use super::strategies::chunk_document;
-use super::types::ChunkConfig;
+use super::types::{ChunkBoundary, ChunkConfig};
 use crate::document::{ExtractedDocument, PageContent};
 
 fn make_doc(file_type: &str, text: &str) -> ExtractedDocument {
@@ -42,6 +43,7 @@ fn md_splits_at_headings() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 2);
@@ -59,6 +61,7 @@ fn md_preserves_section_heading() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks[0].section_heading.as_deref(), Some("Heading A"));
@@ -75,6 +78,7 @@ fn md_splits_oversized_section() {
         max_chunk_tokens: 200,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert!(chunks.len() >= 3, "should split 600 words across >=3 chunks at max 200");
@@ -93,6 +97,7 @@ fn txt_splits_at_paragraphs() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 3);
@@ -108,6 +113,7 @@ fn txt_respects_max_tokens() {
         max_chunk_tokens: 300,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert!(chunks.len() >= 2);
@@ -121,6 +127,7 @@ fn txt_merges_tiny_paragraphs() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 5,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     // The two tiny paragraphs (1 word each) should merge.
@@ -140,6 +147,7 @@ fn pdf_preserves_page_number() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 3);
@@ -158,6 +166,7 @@ fn pdf_no_cross_page_overlap() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 3,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 2);
@@ -176,6 +185,7 @@ fn overlap_tokens_appear_between_chunks() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 3,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 2);
@@ -209,6 +219,7 @@ fn single_paragraph_produces_one_chunk() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 1);
@@ -224,6 +235,7 @@ fn very_long_single_line_gets_split() {
         max_chunk_tokens: 200,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     // Without sentence boundaries it stays as one piece (best-effort),
@@ -238,6 +250,7 @@ fn tiny_document_below_min_produces_one_chunk() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 100,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 1);
@@ -251,6 +264,7 @@ fn chunk_indices_are_sequential() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     for (i, c) in chunks.iter().enumerate() {
@@ -291,6 +305,7 @@ fn md_nested_headings() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 3);
@@ -307,6 +322,7 @@ fn zero_overlap_produces_no_repeated_words() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 2);
@@ -327,6 +343,7 @@ fn pdf_multi_page_chunk_page_numbers() {
         max_chunk_tokens: 500,
         min_chunk_tokens: 1,
         overlap_tokens: 0,
+        ..Default::default()
     };
     let chunks = chunk_document(&doc, &config);
     assert_eq!(chunks.len(), 4);
@@ -335,3 +352,74 @@ fn pdf_multi_page_chunk_page_numbers() {
         assert_eq!(chunk.index, i);
     }
 }
+
+// ── Token spans ─────────────────────────────────────────────────────
+
+fn span_config(max: usize, overlap: usize, boundary: ChunkBoundary) -> ChunkConfig {
+    ChunkConfig {
+        max_chunk_tokens: max,
+        overlap_tokens: overlap,
+        boundary,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn spans_never_exceed_max_tokens_and_cover_text() {
+    let text = (0..95).map(|i| format!("w{i}")).collect::<Vec<_>>().join(" ");
+    let chunks = chunk_spans(&text, &span_config(20, 5, ChunkBoundary::Sentence));
+
+    assert!(chunks.iter().all(|c| c.token_count <= 20));
+    assert!(chunks.iter().all(|c| c.content == text[c.start..c.end]));
+    assert_eq!(chunks.first().unwrap().start, 0);
+    assert_eq!(chunks.last().unwrap().end, text.len());
+    for (i, c) in chunks.iter().enumerate() {
+        assert_eq!(c.index, i);
+    }
+}
+
+#[test]
+fn spans_overlap_by_configured_tokens() {
+    let text = (0..50).map(|i| format!("w{i}")).collect::<Vec<_>>().join(" ");
+    let chunks = chunk_spans(&text, &span_config(20, 4, ChunkBoundary::Sentence));
+    assert!(chunks.len() >= 3);
+
+    for pair in chunks.windows(2) {
+        let prev: Vec<&str> = pair[0].content.split_whitespace().collect();
+        let next: Vec<&str> = pair[1].content.split_whitespace().collect();
+        assert_eq!(&prev[prev.len() - 4..], &next[..4]);
+        assert!(pair[1].start < pair[0].end);
+    }
+}
+
+#[test]
+fn spans_prefer_sentence_boundaries() {
+    // Sentences of 6 words; a 10-word window fits one sentence and a bit.
+    let text = "One two three four five six. Seven eight nine ten eleven twelve.                 Thirteen fourteen fifteen sixteen seventeen eighteen.";
+    let chunks = chunk_spans(text, &span_config(10, 0, ChunkBoundary::Sentence));
+
+    assert_eq!(chunks.len(), 3);
+    assert_eq!(chunks[0].content, "One two three four five six.");
+    assert!(chunks.iter().all(|c| c.content.ends_with('.')));
+}
+
+#[test]
+fn spans_prefer_paragraphs_then_fall_back_to_sentences() {
+    let text = "Alpha beta gamma. Delta epsilon.\n\nZeta eta theta iota. Kappa lambda mu nu xi.";
+    let para = chunk_spans(text, &span_config(8, 0, ChunkBoundary::Paragraph));
+    assert_eq!(para[0].content, "Alpha beta gamma. Delta epsilon.");
+    // The second paragraph is too long for one chunk, so it ends at a sentence.
+    assert_eq!(para[1].content, "Zeta eta theta iota.");
+    assert_eq!(para[2].content, "Kappa lambda mu nu xi.");
+
+    // With room for more, sentence mode packs across the paragraph break.
+    let para = chunk_spans(text, &span_config(10, 0, ChunkBoundary::Paragraph));
+    assert_eq!(para[0].content, "Alpha beta gamma. Delta epsilon.");
+    let sentence = chunk_spans(text, &span_config(10, 0, ChunkBoundary::Sentence));
+    assert_eq!(sentence[0].content, "Alpha beta gamma. Delta epsilon.\n\nZeta eta theta iota.");
+}
+
+#[test]
+fn spans_of_empty_text_are_empty() {
+    assert!(chunk_spans("  \n\n ", &ChunkConfig::default()).is_empty());
+}
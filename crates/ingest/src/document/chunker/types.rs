@@ -11,6 +11,19 @@ pub struct ChunkConfig {
     pub min_chunk_tokens: usize,
     /// Overlap tokens between adjacent chunks (default: 50).
     pub overlap_tokens: usize,
+    /// Where [`chunk_spans`](super::chunk_spans) prefers to end a chunk
+    /// (default: sentence).
+    pub boundary: ChunkBoundary,
+}
+
+/// Preferred place to end a chunk when it can't take the rest of the text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ChunkBoundary {
+    /// After `.`, `!` or `?` (or a paragraph break).
+    #[default]
+    Sentence,
+    /// At a blank line, falling back to a sentence end.
+    Paragraph,
 }
 
 impl Default for ChunkConfig {
@@ -19,6 +32,7 @@ impl Default for ChunkConfig {
             max_chunk_tokens: 500,
             min_chunk_tokens: 50,
             overlap_tokens: 50,
+            boundary: ChunkBoundary::default(),
         }
     }
 }
@@ -39,3 +53,19 @@ pub struct Chunk {
     /// Character offset in the original document.
     pub char_offset: usize,
 }
+
+/// A chunk from [`chunk_spans`](super::chunk_spans): an exact slice of the
+/// source text, so `start..end` can be cited back into it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpanChunk {
+    /// 0-based index within the text.
+    pub index: usize,
+    /// `text[start..end]`.
+    pub content: String,
+    /// Byte offset of the first character.
+    pub start: usize,
+    /// Byte offset just past the last character.
+    pub end: usize,
+    /// Approximate token count (whitespace-separated words).
+    pub token_count: usize,
+}
@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use serde_json::Value;
+use stupid_core::{FieldValue, StupidError};
+use tracing::{info, warn};
+
+use crate::column_mapping::{ColumnMapping, ImportOutcome};
+
+/// Imports a JSON Lines file: one JSON object per line, one document per object.
+pub struct JsonlImporter {
+    mapping: ColumnMapping,
+}
+
+impl JsonlImporter {
+    pub fn new(mapping: ColumnMapping) -> Self {
+        Self { mapping }
+    }
+
+    /// Read `path`, skipping (and counting) lines that aren't JSON objects.
+    /// Blank lines are ignored.
+    ///
+    /// Top-level keys are the columns. Nested objects and arrays are stored
+    /// as their JSON text; with type inference, numbers and booleans keep
+    /// their JSON type, otherwise they become text too.
+    pub fn import(&self, path: &Path, event_type: &str) -> Result<ImportOutcome, StupidError> {
+        let file = std::fs::File::open(path).map_err(StupidError::Io)?;
+
+        let mut documents = Vec::new();
+        let mut skipped_rows = 0;
+        for (line_no, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let object = match serde_json::from_str::<Value>(&line) {
+                Ok(Value::Object(object)) => object,
+                Ok(_) => {
+                    warn!("Skipping line {} of {}: not a JSON object", line_no + 1, path.display());
+                    skipped_rows += 1;
+                    continue;
+                }
+                Err(e) => {
+                    warn!("Skipping line {} of {}: {}", line_no + 1, path.display(), e);
+                    skipped_rows += 1;
+                    continue;
+                }
+            };
+
+            let fields: HashMap<_, _> = object
+                .iter()
+                .filter_map(|(key, value)| {
+                    let field = self.mapping.field_name(key).to_string();
+                    Some((field, self.json_value(value)?))
+                })
+                .collect();
+            documents.push(self.mapping.document(event_type, fields));
+        }
+
+        info!(
+            "Imported {} documents from {} ({} lines skipped)",
+            documents.len(),
+            path.display(),
+            skipped_rows
+        );
+        Ok(ImportOutcome { documents, skipped_rows })
+    }
+
+    fn json_value(&self, value: &Value) -> Option<FieldValue> {
+        match value {
+            Value::Null => None,
+            Value::String(s) => self.mapping.text_value(s),
+            Value::Bool(b) if self.mapping.infer_types => Some(FieldValue::Boolean(*b)),
+            Value::Number(n) if self.mapping.infer_types => Some(
+                n.as_i64()
+                    .map(FieldValue::Integer)
+                    .or_else(|| n.as_f64().map(FieldValue::Float))
+                    .unwrap_or_else(|| FieldValue::Text(n.to_string())),
+            ),
+            other => Some(FieldValue::Text(other.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(contents: &str) -> (tempfile::TempDir, std::path::PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("2025-06-14.jsonl");
+        std::fs::write(&path, contents).unwrap();
+        (tmp, path)
+    }
+
+    const LINES: &str = r#"{"@timestamp":"2025-06-14T10:00:00Z","memberCode":12345,"vip":true,"tags":["a","b"]}
+{"@timestamp": "2025-06-14T11:00:00Z", "memberCode": "M002"
+[1, 2, 3]
+
+{"ts":1718366400,"member":"M003","currency":"null"}
+"#;
+
+    #[test]
+    fn imports_objects_and_skips_malformed_lines() {
+        let (_tmp, path) = write(LINES);
+        let outcome = JsonlImporter::new(ColumnMapping::default()).import(&path, "Login").unwrap();
+
+        assert_eq!(outcome.skipped_rows, 2);
+        assert_eq!(outcome.documents.len(), 2);
+        let first = &outcome.documents[0];
+        assert_eq!(first.timestamp.to_rfc3339(), "2025-06-14T10:00:00+00:00");
+        // Without inference every value is text, so entity extraction can read it.
+        assert_eq!(first.field_str("memberCode"), Some("12345"));
+        assert_eq!(first.field_str("vip"), Some("true"));
+        assert_eq!(first.field_str("tags"), Some(r#"["a","b"]"#));
+        assert!(!outcome.documents[1].fields.contains_key("currency"));
+    }
+
+    #[test]
+    fn mapping_renames_and_keeps_json_types() {
+        let (_tmp, path) = write(LINES);
+        let mapping = ColumnMapping {
+            rename: HashMap::from([("member".to_string(), "memberCode".to_string())]),
+            infer_types: true,
+            timestamp_field: "ts".to_string(),
+            content_ids: true,
+        };
+        let outcome = JsonlImporter::new(mapping).import(&path, "Login").unwrap();
+
+        let first = &outcome.documents[0];
+        assert_eq!(first.fields["memberCode"], FieldValue::Integer(12345));
+        assert_eq!(first.fields["vip"], FieldValue::Boolean(true));
+        let last = &outcome.documents[1];
+        assert_eq!(last.field_str("memberCode"), Some("M003"));
+        assert_eq!(last.timestamp.timestamp(), 1_718_366_400);
+        assert_eq!(first.id, first.content_id());
+    }
+}
@@ -1,3 +1,7 @@
+pub mod column_mapping;
+pub mod csv_import;
+pub mod dedup;
 pub mod document;
 pub mod embedding;
+pub mod jsonl_import;
 pub mod parquet_import;
@@ -1,25 +1,122 @@
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use arrow::array::{Array, StringArray};
 use chrono::{DateTime, Utc};
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
-use stupid_core::{DocId, Document, FieldValue, StupidError};
+use serde::Serialize;
+use stupid_core::{is_null_sentinel, DocId, Document, FieldValue, StupidError};
 use tracing::info;
 
+use crate::dedup::Deduplicator;
+
 pub struct ParquetImporter;
 
+/// Union schema of a set of parquet files, from [`ParquetImporter::infer_schema`].
+#[derive(Debug, Default, Serialize)]
+pub struct SchemaReport {
+    pub files: usize,
+    pub total_rows: u64,
+    /// Every column seen in any file, by name.
+    pub columns: BTreeMap<String, ColumnReport>,
+}
+
+/// What one column looks like across the files that were inspected.
+#[derive(Debug, Default, Serialize)]
+pub struct ColumnReport {
+    /// Arrow type name → files where the column has that type.
+    pub types: BTreeMap<String, Vec<PathBuf>>,
+    /// Null values, counting text null sentinels (`null`, `None`, ...) and
+    /// every row of files that lack the column.
+    pub nulls: u64,
+    /// Share of all rows that are null, from 0.0 to 1.0.
+    pub null_ratio: f64,
+}
+
+impl ColumnReport {
+    /// Whether the column has different types in different files.
+    pub fn is_conflicting(&self) -> bool {
+        self.types.len() > 1
+    }
+}
+
+impl SchemaReport {
+    /// Columns whose type differs between files.
+    pub fn conflicts(&self) -> impl Iterator<Item = (&str, &ColumnReport)> {
+        self.columns
+            .iter()
+            .filter(|(_, c)| c.is_conflicting())
+            .map(|(name, c)| (name.as_str(), c))
+    }
+}
+
 impl ParquetImporter {
+    /// Inspect `paths` and report each column's types and null ratio, without
+    /// building documents. Fails on the first file that can't be read.
+    pub fn infer_schema(paths: &[PathBuf]) -> Result<SchemaReport, StupidError> {
+        let mut report = SchemaReport { files: paths.len(), ..Default::default() };
+        // Rows per file, to charge files that lack a column with nulls.
+        let mut file_rows: Vec<(&Path, u64)> = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            let file = std::fs::File::open(path).map_err(StupidError::Io)?;
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file)
+                .map_err(|e| StupidError::Parquet(e.into()))?;
+            let schema = builder.schema().clone();
+            let reader = builder.build().map_err(|e| StupidError::Parquet(e.into()))?;
+
+            for field in schema.fields() {
+                let column = report.columns.entry(field.name().clone()).or_default();
+                column.types.entry(field.data_type().to_string()).or_default().push(path.clone());
+            }
+
+            let mut rows = 0u64;
+            for batch in reader {
+                let batch = batch.map_err(|e| StupidError::Parquet(e.into()))?;
+                rows += batch.num_rows() as u64;
+                for (field, array) in schema.fields().iter().zip(batch.columns()) {
+                    let mut nulls = array.null_count() as u64;
+                    if let Some(strings) = array.as_any().downcast_ref::<StringArray>() {
+                        nulls += strings.iter().flatten().filter(|v| is_null_sentinel(v)).count() as u64;
+                    }
+                    report.columns.get_mut(field.name()).expect("column registered above").nulls += nulls;
+                }
+            }
+            report.total_rows += rows;
+            file_rows.push((path, rows));
+        }
+
+        for column in report.columns.values_mut() {
+            for (path, rows) in &file_rows {
+                if !column.types.values().flatten().any(|p| p == path) {
+                    column.nulls += rows;
+                }
+            }
+            if report.total_rows > 0 {
+                column.null_ratio = column.nulls as f64 / report.total_rows as f64;
+            }
+        }
+
+        info!(
+            "Inferred schema of {} files: {} columns, {} conflicting",
+            report.files,
+            report.columns.len(),
+            report.conflicts().count()
+        );
+        Ok(report)
+    }
+
     pub fn import(path: &Path, event_type: &str) -> Result<Vec<Document>, StupidError> {
         let file = std::fs::File::open(path).map_err(StupidError::Io)?;
         let builder = ParquetRecordBatchReaderBuilder::try_new(file)
-            .map_err(|e| StupidError::Parquet(e.to_string()))?;
+            .map_err(|e| StupidError::Parquet(e.into()))?;
 
-        let reader = builder.build().map_err(|e| StupidError::Parquet(e.to_string()))?;
+        let reader = builder.build().map_err(|e| StupidError::Parquet(e.into()))?;
 
         let mut documents = Vec::new();
 
         for batch_result in reader {
-            let batch = batch_result.map_err(|e| StupidError::Parquet(e.to_string()))?;
+            let batch = batch_result.map_err(|e| StupidError::Parquet(e.into()))?;
             let schema = batch.schema();
             let num_rows = batch.num_rows();
 
@@ -46,13 +143,13 @@ impl ParquetImporter {
                         continue;
                     }
                     let val = arr.value(row_idx).trim();
-                    if val.is_empty() || val == "None" || val == "null" || val == "undefined" {
+                    if is_null_sentinel(val) {
                         continue;
                     }
 
                     // Parse @timestamp
                     if col_name == "@timestamp" {
-                        if let Ok(ts) = val.parse::<DateTime<Utc>>() {
+                        if let Some(ts) = Document::parse_timestamp(val) {
                             timestamp = Some(ts);
                         }
                     }
@@ -74,4 +171,153 @@ impl ParquetImporter {
         info!("Imported {} documents from {}", documents.len(), path.display());
         Ok(documents)
     }
+
+    /// Like [`import`](Self::import), but each document's ID is derived from
+    /// its content ([`Document::content_id`]), so re-importing the same file
+    /// yields the same IDs.
+    pub fn import_content_addressed(path: &Path, event_type: &str) -> Result<Vec<Document>, StupidError> {
+        let mut documents = Self::import(path, event_type)?;
+        for doc in &mut documents {
+            doc.id = doc.content_id();
+        }
+        Ok(documents)
+    }
+
+    /// Like [`import`](Self::import), but drops documents `dedup` has already
+    /// seen in this run (or, with a seen-set, in earlier runs). The running
+    /// duplicate count is [`Deduplicator::duplicates`].
+    pub fn import_deduplicated(
+        path: &Path,
+        event_type: &str,
+        dedup: &mut Deduplicator,
+    ) -> Result<Vec<Document>, StupidError> {
+        let before = dedup.duplicates();
+        let documents = dedup.retain_unique(Self::import(path, event_type)?);
+        info!(
+            "Kept {} documents from {} ({} duplicates skipped)",
+            documents.len(),
+            path.display(),
+            dedup.duplicates() - before
+        );
+        Ok(documents)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    use arrow::array::{ArrayRef, Int64Array};
+    use arrow::record_batch::RecordBatch;
+    use parquet::arrow::ArrowWriter;
+
+    fn write_parquet(path: &Path, columns: Vec<(&str, ArrayRef)>) {
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let mut writer = ArrowWriter::try_new(std::fs::File::create(path).unwrap(), batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+    }
+
+    fn fixtures(dir: &Path) -> Vec<PathBuf> {
+        let first = dir.join("2025-06-14.parquet");
+        write_parquet(
+            &first,
+            vec![
+                ("memberCode", Arc::new(StringArray::from(vec![Some("M1"), Some("null"), None, Some("M4")])) as ArrayRef),
+                ("amount", Arc::new(Int64Array::from(vec![10, 20, 30, 40])) as ArrayRef),
+            ],
+        );
+        let second = dir.join("2025-06-15.parquet");
+        write_parquet(
+            &second,
+            vec![
+                ("memberCode", Arc::new(StringArray::from(vec!["M5", "M6"])) as ArrayRef),
+                ("amount", Arc::new(StringArray::from(vec![Some("12.50"), None])) as ArrayRef),
+                ("currency", Arc::new(StringArray::from(vec!["EUR", "USD"])) as ArrayRef),
+            ],
+        );
+        vec![first, second]
+    }
+
+    #[test]
+    fn infer_schema_reports_types_nulls_and_conflicts() {
+        let tmp = tempfile::tempdir().unwrap();
+        let paths = fixtures(tmp.path());
+        let report = ParquetImporter::infer_schema(&paths).unwrap();
+
+        assert_eq!(report.files, 2);
+        assert_eq!(report.total_rows, 6);
+        assert_eq!(report.columns.len(), 3);
+
+        let member = &report.columns["memberCode"];
+        assert!(!member.is_conflicting());
+        assert_eq!(member.types["Utf8"].len(), 2);
+        assert_eq!(member.nulls, 2);
+
+        let amount = &report.columns["amount"];
+        assert!(amount.is_conflicting());
+        assert_eq!(amount.types["Int64"], vec![paths[0].clone()]);
+        assert_eq!(amount.types["Utf8"], vec![paths[1].clone()]);
+        assert_eq!(amount.nulls, 1);
+
+        // Missing from the first file's four rows.
+        let currency = &report.columns["currency"];
+        assert_eq!(currency.nulls, 4);
+        assert!((currency.null_ratio - 4.0 / 6.0).abs() < 1e-9);
+
+        let conflicts: Vec<&str> = report.conflicts().map(|(name, _)| name).collect();
+        assert_eq!(conflicts, vec!["amount"]);
+    }
+
+    #[test]
+    fn import_deduplicated_skips_intra_file_duplicates() {
+        use crate::dedup::DedupKey;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("2025-06-14.parquet");
+        write_parquet(
+            &path,
+            vec![
+                ("eventId", Arc::new(StringArray::from(vec!["e1", "e2", "e1", "e3", "e2"])) as ArrayRef),
+                ("memberCode", Arc::new(StringArray::from(vec!["M1", "M2", "M1", "M1", "M2"])) as ArrayRef),
+            ],
+        );
+
+        let mut dedup = Deduplicator::new(DedupKey::parse("eventId").unwrap());
+        let docs = ParquetImporter::import_deduplicated(&path, "Login", &mut dedup).unwrap();
+        let ids: Vec<_> = docs.iter().filter_map(|d| d.fields["eventId"].as_str()).collect();
+        assert_eq!(ids, vec!["e1", "e2", "e3"]);
+        assert_eq!(dedup.duplicates(), 2);
+
+        // Same run, same file again: everything is a duplicate now.
+        assert!(ParquetImporter::import_deduplicated(&path, "Login", &mut dedup).unwrap().is_empty());
+        assert_eq!(dedup.duplicates(), 7);
+    }
+
+    #[test]
+    fn content_addressed_import_is_idempotent() {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp.path().join("2025-06-14.parquet");
+        write_parquet(
+            &path,
+            vec![("memberCode", Arc::new(StringArray::from(vec!["M1", "M2", "M1"])) as ArrayRef)],
+        );
+
+        let ids = |docs: Vec<Document>| docs.into_iter().map(|d| d.id).collect::<Vec<_>>();
+        let first = ids(ParquetImporter::import_content_addressed(&path, "Login").unwrap());
+        assert_eq!(first, ids(ParquetImporter::import_content_addressed(&path, "Login").unwrap()));
+        // Identical rows share an ID; distinct rows don't.
+        assert_eq!(first[0], first[2]);
+        assert_ne!(first[0], first[1]);
+        assert_ne!(first, ids(ParquetImporter::import(&path, "Login").unwrap()));
+    }
+
+    #[test]
+    fn infer_schema_fails_on_unreadable_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let bogus = tmp.path().join("bogus.parquet");
+        std::fs::write(&bogus, b"not parquet").unwrap();
+        assert!(ParquetImporter::infer_schema(&[bogus]).is_err());
+    }
 }
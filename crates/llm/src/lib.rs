@@ -1,7 +1,16 @@
+pub mod logging;
 pub mod provider;
 pub mod providers;
 pub mod query;
+pub mod retry;
+pub mod stream;
+mod tokens;
+pub mod tool_calls;
 
+pub use logging::{ExchangeSink, LoggingProvider, Redactor};
 pub use provider::{LlmProvider, LlmProviderAdapter, Message, Role};
 pub use providers::claude_tool_provider::ClaudeToolProvider;
 pub use query::QueryGenerator;
+pub use retry::{RetryPolicy, RetryingProvider};
+pub use stream::{collect_stream, Chunk, ChunkStream};
+pub use tool_calls::{codec_for_provider, NormalizedResponse, ToolCallCodec};
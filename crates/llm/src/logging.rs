@@ -0,0 +1,410 @@
+//! Debug logging of provider requests and responses.
+//!
+//! [`LoggingProvider`] wraps any [`LlmProvider`] and records each exchange —
+//! the provider's request headers, the outbound messages and the completion
+//! or error — as one JSON object, either to `tracing` at debug level or as a
+//! line appended to a file. Credentials and configured fields are redacted
+//! before anything is written.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde_json::{json, Map, Value};
+use tracing::{debug, warn};
+
+use crate::provider::{LlmError, LlmProvider, Message};
+use crate::stream::ChunkStream;
+
+/// Replaces every redacted value.
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Headers that always carry credentials, compared case-insensitively.
+const CREDENTIAL_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "x-api-key",
+    "x-goog-api-key",
+    "api-key",
+];
+
+/// Shortest header value scrubbed from the rest of the record, so short
+/// values like a version number don't blank out unrelated text.
+const MIN_SCRUBBED_SECRET_LEN: usize = 8;
+
+/// Where exchange records go.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExchangeSink {
+    /// `tracing` at debug level, target `stupid_llm::exchange`.
+    Tracing,
+    /// One JSON object per line, appended to this file.
+    File(PathBuf),
+}
+
+impl ExchangeSink {
+    /// Parse an `LLM_EXCHANGE_LOG` value: `tracing` or a file path.
+    /// Returns `None` for an empty value.
+    pub fn parse(spec: &str) -> Option<Self> {
+        match spec.trim() {
+            "" => None,
+            "tracing" => Some(Self::Tracing),
+            path => Some(Self::File(PathBuf::from(path))),
+        }
+    }
+}
+
+/// Redacts credential headers and configured header or field names.
+#[derive(Debug, Clone, Default)]
+pub struct Redactor {
+    /// Extra names to redact, lowercased.
+    names: Vec<String>,
+}
+
+impl Redactor {
+    /// Redact credential headers plus `names` (compared case-insensitively).
+    pub fn new(names: &[String]) -> Self {
+        Self { names: names.iter().map(|n| n.to_lowercase()).collect() }
+    }
+
+    fn is_sensitive(&self, name: &str) -> bool {
+        let name = name.to_lowercase();
+        CREDENTIAL_HEADERS.contains(&name.as_str()) || self.names.contains(&name)
+    }
+
+    /// `headers` as a JSON object with sensitive values replaced.
+    pub fn headers(&self, headers: &[(&str, String)]) -> Map<String, Value> {
+        headers
+            .iter()
+            .map(|(name, value)| {
+                let value = if self.is_sensitive(name) { REDACTED } else { value.as_str() };
+                (name.to_string(), Value::String(value.to_string()))
+            })
+            .collect()
+    }
+
+    /// Replace the values of sensitive keys anywhere in `value`.
+    pub fn redact_fields(&self, value: &mut Value) {
+        match value {
+            Value::Object(map) => {
+                for (key, field) in map.iter_mut() {
+                    if self.is_sensitive(key) {
+                        *field = Value::String(REDACTED.to_string());
+                    } else {
+                        self.redact_fields(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(|item| self.redact_fields(item)),
+            _ => {}
+        }
+    }
+
+    /// Secret values in `headers` that must not appear anywhere in a record,
+    /// e.g. an API key echoed back in an error body. For `Bearer` tokens
+    /// the token alone is included too.
+    fn secrets(&self, headers: &[(&str, String)]) -> Vec<String> {
+        let mut secrets = Vec::new();
+        for (_, value) in headers.iter().filter(|(name, _)| self.is_sensitive(name)) {
+            secrets.push(value.clone());
+            if let Some(token) = value.strip_prefix("Bearer ") {
+                secrets.push(token.to_string());
+            }
+        }
+        secrets.retain(|s| s.len() >= MIN_SCRUBBED_SECRET_LEN);
+        // Longest first, so a token inside a longer header value is still caught.
+        secrets.sort_by_key(|s| std::cmp::Reverse(s.len()));
+        secrets
+    }
+}
+
+/// Decorator that logs an inner provider's requests and responses.
+///
+/// Streams are recorded once they end, with the concatenated text.
+/// Embedding requests are passed through without logging.
+pub struct LoggingProvider {
+    inner: Box<dyn LlmProvider>,
+    log: Arc<ExchangeLog>,
+}
+
+impl LoggingProvider {
+    /// Log exchanges with `inner`, labelled `provider` (e.g. `anthropic`).
+    pub fn new(inner: Box<dyn LlmProvider>, provider: &str, sink: ExchangeSink, redactor: Redactor) -> Self {
+        Self {
+            inner,
+            log: Arc::new(ExchangeLog {
+                provider: provider.to_string(),
+                sink,
+                redactor,
+                file_lock: Mutex::new(()),
+            }),
+        }
+    }
+}
+
+/// Builds and writes exchange records; shared with in-flight streams.
+struct ExchangeLog {
+    provider: String,
+    sink: ExchangeSink,
+    redactor: Redactor,
+    /// Serializes appends so concurrent records don't interleave.
+    file_lock: Mutex<()>,
+}
+
+/// The outbound side of one exchange.
+struct Request {
+    headers: Vec<(&'static str, String)>,
+    messages: Vec<Message>,
+    temperature: f32,
+    max_tokens: u32,
+    started: Instant,
+}
+
+impl ExchangeLog {
+    /// The redacted record of `request` and its `outcome` as one JSON line.
+    fn record(&self, request: &Request, outcome: Result<&str, &LlmError>) -> String {
+        let timestamp_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis() as u64;
+        let mut record = json!({
+            "timestamp_ms": timestamp_ms,
+            "provider": self.provider,
+            "duration_ms": request.started.elapsed().as_millis() as u64,
+            "request": {
+                "headers": self.redactor.headers(&request.headers),
+                "messages": request.messages,
+                "temperature": request.temperature,
+                "max_tokens": request.max_tokens,
+            },
+        });
+        match outcome {
+            Ok(completion) => record["response"] = json!({ "completion": completion }),
+            Err(error) => record["error"] = json!(error.to_string()),
+        }
+        self.redactor.redact_fields(&mut record["request"]["messages"]);
+        self.redactor.redact_fields(&mut record["response"]);
+
+        let mut line = record.to_string();
+        for secret in self.redactor.secrets(&request.headers) {
+            line = line.replace(&secret, REDACTED);
+        }
+        line
+    }
+
+    fn write(&self, request: &Request, outcome: Result<&str, &LlmError>) {
+        let line = self.record(request, outcome);
+        match &self.sink {
+            ExchangeSink::Tracing => debug!(target: "stupid_llm::exchange", "{line}"),
+            ExchangeSink::File(path) => {
+                let _guard = self.file_lock.lock().unwrap_or_else(|e| e.into_inner());
+                let result = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .and_then(|mut file| writeln!(file, "{line}"));
+                if let Err(e) = result {
+                    warn!(path = %path.display(), error = %e, "Failed to write LLM exchange log");
+                }
+            }
+        }
+    }
+}
+
+impl LoggingProvider {
+    fn request(&self, messages: &[Message], temperature: f32, max_tokens: u32) -> Request {
+        Request {
+            headers: self.inner.request_headers(),
+            messages: messages.to_vec(),
+            temperature,
+            max_tokens,
+            started: Instant::now(),
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for LoggingProvider {
+    async fn complete(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<String, LlmError> {
+        let request = self.request(&messages, temperature, max_tokens);
+        let result = self.inner.complete(messages, temperature, max_tokens).await;
+        self.log.write(&request, result.as_deref());
+        result
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<ChunkStream, LlmError> {
+        let request = self.request(&messages, temperature, max_tokens);
+        let stream = match self.inner.complete_stream(messages, temperature, max_tokens).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                self.log.write(&request, Err(&e));
+                return Err(e);
+            }
+        };
+
+        // Collect the text as it passes through, then log once the stream ends.
+        let collected: Arc<Mutex<Result<String, String>>> = Arc::new(Mutex::new(Ok(String::new())));
+        let seen = collected.clone();
+        let log = self.log.clone();
+        let finished = futures::stream::once(async move {
+            let outcome = collected.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            match outcome {
+                Ok(text) => log.write(&request, Ok(&text)),
+                Err(message) => log.write(&request, Err(&LlmError::StreamError(message))),
+            }
+        })
+        .filter_map(|()| async { None });
+
+        let stream = stream
+            .inspect(move |item| {
+                let mut seen = seen.lock().unwrap_or_else(|e| e.into_inner());
+                match (item, &mut *seen) {
+                    (Ok(chunk), Ok(text)) => text.push_str(&chunk.delta),
+                    (Err(e), _) => *seen = Err(e.to_string()),
+                    (Ok(_), Err(_)) => {}
+                }
+            })
+            .chain(finished);
+        Ok(Box::pin(stream))
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        self.inner.embed(texts).await
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.inner.count_tokens(text)
+    }
+
+    fn context_window(&self) -> usize {
+        self.inner.context_window()
+    }
+
+    fn request_headers(&self) -> Vec<(&'static str, String)> {
+        self.inner.request_headers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Role;
+    use crate::stream::{collect_stream, Chunk};
+
+    const API_KEY: &str = "sk-live-0123456789abcdef";
+
+    /// Answers with a fixed completion (echoing the key in an error if asked
+    /// to fail) and reports OpenAI-style auth headers.
+    struct StubProvider {
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl LlmProvider for StubProvider {
+        async fn complete(&self, _: Vec<Message>, _: f32, _: u32) -> Result<String, LlmError> {
+            if self.fail {
+                return Err(LlmError::ApiError { status: 401, body: format!("invalid key {API_KEY}") });
+            }
+            Ok(r#"{"answer": 42, "email": "alice@example.com"}"#.to_string())
+        }
+
+        async fn complete_stream(&self, _: Vec<Message>, _: f32, _: u32) -> Result<ChunkStream, LlmError> {
+            let chunks = ["Hel", "lo"].map(|delta| Ok(Chunk { delta: delta.to_string(), finish_reason: None }));
+            Ok(Box::pin(futures::stream::iter(chunks)))
+        }
+
+        fn request_headers(&self) -> Vec<(&'static str, String)> {
+            vec![
+                ("Authorization", format!("Bearer {API_KEY}")),
+                ("OpenAI-Organization", "org-example".to_string()),
+            ]
+        }
+    }
+
+    fn log_path() -> PathBuf {
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        std::env::temp_dir().join(format!("llm-exchange-{}-{nanos}.jsonl", std::process::id()))
+    }
+
+    fn provider(fail: bool, path: &std::path::Path, redact: &[&str]) -> LoggingProvider {
+        let names: Vec<String> = redact.iter().map(|n| n.to_string()).collect();
+        LoggingProvider::new(
+            Box::new(StubProvider { fail }),
+            "openai",
+            ExchangeSink::File(path.to_path_buf()),
+            Redactor::new(&names),
+        )
+    }
+
+    fn messages() -> Vec<Message> {
+        vec![Message { role: Role::User, content: "What is the answer?".into() }]
+    }
+
+    fn records(path: &std::path::Path) -> Vec<Value> {
+        let text = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).ok();
+        assert!(!text.contains(API_KEY), "API key leaked into the exchange log: {text}");
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_logged_payload_redacts_auth_header() {
+        let path = log_path();
+        let provider = provider(false, &path, &[]);
+        provider.complete(messages(), 0.1, 256).await.unwrap();
+
+        let records = records(&path);
+        assert_eq!(records.len(), 1);
+        let record = &records[0];
+        assert_eq!(record["provider"], "openai");
+        assert_eq!(record["request"]["headers"]["Authorization"], REDACTED);
+        assert_eq!(record["request"]["headers"]["OpenAI-Organization"], "org-example");
+        assert_eq!(record["request"]["messages"][0]["content"], "What is the answer?");
+        assert_eq!(record["request"]["max_tokens"], 256);
+        assert!(record["response"]["completion"].as_str().unwrap().contains("42"));
+    }
+
+    #[tokio::test]
+    async fn test_configured_fields_and_echoed_keys_are_redacted() {
+        let path = log_path();
+        provider(true, &path, &["content", "openai-organization"])
+            .complete(messages(), 0.1, 256)
+            .await
+            .unwrap_err();
+
+        let record = &records(&path)[0];
+        assert_eq!(record["request"]["messages"][0]["content"], REDACTED);
+        assert_eq!(record["request"]["headers"]["OpenAI-Organization"], REDACTED);
+        assert_eq!(record["error"], format!("API error: 401 — invalid key {REDACTED}"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_is_logged_when_it_ends() {
+        let path = log_path();
+        let stream = provider(false, &path, &[]).complete_stream(messages(), 0.1, 256).await.unwrap();
+        assert_eq!(collect_stream(stream).await.unwrap(), "Hello");
+
+        let record = &records(&path)[0];
+        assert_eq!(record["response"]["completion"], "Hello");
+    }
+
+    #[test]
+    fn test_parse_sink() {
+        assert_eq!(ExchangeSink::parse(" tracing "), Some(ExchangeSink::Tracing));
+        assert_eq!(ExchangeSink::parse("logs/llm.jsonl"), Some(ExchangeSink::File("logs/llm.jsonl".into())));
+        assert_eq!(ExchangeSink::parse(""), None);
+    }
+}
@@ -1,6 +1,10 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use stupid_tool_runtime::tokens::{estimate_tokens, DEFAULT_CONTEXT_WINDOW};
+
+use crate::stream::{Chunk, ChunkStream};
+
 /// A chat message for the LLM.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
@@ -26,6 +30,51 @@ pub trait LlmProvider: Send + Sync {
         temperature: f32,
         max_tokens: u32,
     ) -> Result<String, LlmError>;
+
+    /// Stream the assistant's response as it is generated.
+    ///
+    /// Providers without a streaming API yield the whole [`complete`](Self::complete)
+    /// response as a single chunk.
+    async fn complete_stream(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<ChunkStream, LlmError> {
+        let text = self.complete(messages, temperature, max_tokens).await?;
+        let chunk = Chunk {
+            delta: text,
+            finish_reason: Some("stop".to_string()),
+        };
+        Ok(Box::pin(futures::stream::once(async move { Ok(chunk) })))
+    }
+
+    /// Embed a batch of texts, returning one vector per input, in order.
+    ///
+    /// Providers without an embeddings API return [`LlmError::Unsupported`].
+    async fn embed(&self, _texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        Err(LlmError::Unsupported("embeddings".to_string()))
+    }
+
+    /// Number of tokens `text` occupies in the model's context.
+    ///
+    /// Providers without a local tokenizer return a conservative
+    /// character-based estimate.
+    fn count_tokens(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+
+    /// Maximum tokens (prompt plus completion) the configured model accepts.
+    fn context_window(&self) -> usize {
+        DEFAULT_CONTEXT_WINDOW
+    }
+
+    /// Headers sent with every request besides `Content-Type`, including
+    /// credentials, so decorators such as [`LoggingProvider`](crate::LoggingProvider)
+    /// can show them (redacted).
+    fn request_headers(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -34,10 +83,57 @@ pub enum LlmError {
     HttpError(#[from] reqwest::Error),
     #[error("API error: {status} — {body}")]
     ApiError { status: u16, body: String },
+    #[error("rate limited: {body}")]
+    RateLimited {
+        /// Seconds to wait, from the `Retry-After` header if the provider sent one.
+        retry_after_secs: Option<u64>,
+        body: String,
+    },
     #[error("failed to parse response: {0}")]
     ParseError(String),
+    #[error("stream error: {0}")]
+    StreamError(String),
     #[error("provider not configured: {0}")]
     NotConfigured(String),
+    #[error("not supported by this provider: {0}")]
+    Unsupported(String),
+}
+
+impl LlmError {
+    /// Whether the request may succeed if retried: rate limits, transient
+    /// server errors, and timeouts or connection failures.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            LlmError::RateLimited { .. } => true,
+            LlmError::ApiError { status, .. } => matches!(status, 408 | 500 | 502 | 503 | 504 | 529),
+            LlmError::HttpError(e) => e.is_timeout() || e.is_connect(),
+            _ => false,
+        }
+    }
+}
+
+/// Turn a non-200 response into an error, keeping `Retry-After` on 429s.
+pub(crate) async fn error_from_response(response: reqwest::Response) -> LlmError {
+    let status = response.status().as_u16();
+    let retry_after_secs = response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(parse_retry_after);
+    let body = response.text().await.unwrap_or_default();
+
+    if status == 429 {
+        LlmError::RateLimited { retry_after_secs, body }
+    } else {
+        LlmError::ApiError { status, body }
+    }
+}
+
+/// Parse a `Retry-After` value given in (possibly fractional) seconds.
+/// HTTP-date values are not supported and yield `None`.
+fn parse_retry_after(value: &str) -> Option<u64> {
+    let secs: f64 = value.trim().parse().ok()?;
+    (secs.is_finite() && secs >= 0.0).then(|| secs.ceil() as u64)
 }
 
 /// Adapter that wraps a `Box<dyn LlmProvider>` and implements `SimpleLlmProvider`.
@@ -70,4 +166,37 @@ impl stupid_tool_runtime::bridge::SimpleLlmProvider for LlmProviderAdapter {
             .await
             .map_err(|e| stupid_tool_runtime::bridge::BridgeError(e.to_string()))
     }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.0.count_tokens(text)
+    }
+
+    fn context_window(&self) -> usize {
+        self.0.context_window()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_retry_after() {
+        assert_eq!(parse_retry_after("20"), Some(20));
+        assert_eq!(parse_retry_after(" 0.4 "), Some(1));
+        assert_eq!(parse_retry_after("Wed, 21 Oct 2026 07:28:00 GMT"), None);
+        assert_eq!(parse_retry_after("-3"), None);
+    }
+
+    #[test]
+    fn test_retryable_errors() {
+        let rate_limited = LlmError::RateLimited { retry_after_secs: Some(1), body: String::new() };
+        assert!(rate_limited.is_retryable());
+        assert!(LlmError::ApiError { status: 503, body: String::new() }.is_retryable());
+        assert!(LlmError::ApiError { status: 529, body: String::new() }.is_retryable());
+        assert!(!LlmError::ApiError { status: 400, body: String::new() }.is_retryable());
+        assert!(!LlmError::ApiError { status: 401, body: String::new() }.is_retryable());
+        assert!(!LlmError::ParseError("bad".into()).is_retryable());
+        assert!(!LlmError::NotConfigured("key".into()).is_retryable());
+    }
 }
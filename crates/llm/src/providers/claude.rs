@@ -2,7 +2,8 @@ use async_trait::async_trait;
 use serde_json::json;
 use tracing::debug;
 
-use crate::provider::{LlmError, LlmProvider, Message, Role};
+use crate::provider::{error_from_response, LlmError, LlmProvider, Message, Role};
+use crate::stream::{collect_stream, decode_stream, sse_data, Chunk, ChunkDecoder, ChunkStream};
 
 pub struct ClaudeProvider {
     client: reqwest::Client,
@@ -28,6 +29,15 @@ impl LlmProvider for ClaudeProvider {
         temperature: f32,
         max_tokens: u32,
     ) -> Result<String, LlmError> {
+        collect_stream(self.complete_stream(messages, temperature, max_tokens).await?).await
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<ChunkStream, LlmError> {
         let url = "https://api.anthropic.com/v1/messages";
 
         // Claude API uses separate system parameter
@@ -56,6 +66,7 @@ impl LlmProvider for ClaudeProvider {
             "messages": api_messages,
             "temperature": temperature,
             "max_tokens": max_tokens,
+            "stream": true,
         });
 
         if let Some(system) = system_msg {
@@ -64,28 +75,90 @@ impl LlmProvider for ClaudeProvider {
 
         debug!("Claude request to {}", url);
 
-        let response = self
-            .client
-            .post(url)
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", "2023-06-01")
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        let status = response.status().as_u16();
-        if status != 200 {
-            let body = response.text().await.unwrap_or_default();
-            return Err(LlmError::ApiError { status, body });
+        let mut request = self.client.post(url).header("Content-Type", "application/json");
+        for (name, value) in self.request_headers() {
+            request = request.header(name, value);
+        }
+        let response = request.json(&body).send().await?;
+
+        if response.status().as_u16() != 200 {
+            return Err(error_from_response(response).await);
         }
 
-        let resp: serde_json::Value = response.json().await?;
-        let content = resp["content"][0]["text"]
-            .as_str()
-            .ok_or_else(|| LlmError::ParseError("missing content[0].text".into()))?
-            .to_string();
+        Ok(decode_stream(response.bytes_stream(), ClaudeDecoder))
+    }
+
+    fn context_window(&self) -> usize {
+        crate::tokens::claude_context_window(&self.model)
+    }
+
+    fn request_headers(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("x-api-key", self.api_key.clone()),
+            ("anthropic-version", "2023-06-01".to_string()),
+        ]
+    }
+}
 
-        Ok(content)
+/// Decodes `messages` stream events. Each `data:` line carries its event
+/// type, so the preceding `event:` lines are not needed.
+struct ClaudeDecoder;
+
+impl ChunkDecoder for ClaudeDecoder {
+    fn decode_line(&mut self, line: &str) -> Result<Option<Chunk>, LlmError> {
+        let Some(data) = sse_data(line) else {
+            return Ok(None);
+        };
+
+        let event: serde_json::Value =
+            serde_json::from_str(data).map_err(|e| LlmError::ParseError(e.to_string()))?;
+        match event["type"].as_str() {
+            Some("content_block_delta") if event["delta"]["type"] == "text_delta" => Ok(Some(Chunk {
+                delta: event["delta"]["text"].as_str().unwrap_or_default().to_string(),
+                finish_reason: None,
+            })),
+            Some("message_delta") => Ok(event["delta"]["stop_reason"].as_str().map(|reason| Chunk {
+                delta: String::new(),
+                finish_reason: Some(reason.to_string()),
+            })),
+            Some("error") => {
+                let message = event["error"]["message"].as_str().unwrap_or("unknown error");
+                Err(LlmError::StreamError(message.to_string()))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::test_support::{drain, fixture_stream};
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/claude_stream.sse");
+    const ERROR_FIXTURE: &str = include_str!("../../tests/fixtures/claude_stream_error.sse");
+
+    #[tokio::test]
+    async fn test_stream_assembles_chunks() {
+        for size in [1, 5, 64, FIXTURE.len()] {
+            let text = collect_stream(fixture_stream(FIXTURE, size, ClaudeDecoder)).await.unwrap();
+            assert_eq!(text, "Top members by logins: alice (42) — bob (17).");
+        }
+
+        let items = drain(fixture_stream(FIXTURE, 16, ClaudeDecoder)).await;
+        assert_eq!(items.len(), 4);
+        let last = items.last().unwrap().as_ref().unwrap();
+        assert_eq!(last.finish_reason.as_deref(), Some("end_turn"));
+    }
+
+    #[tokio::test]
+    async fn test_mid_stream_error_is_err() {
+        let items = drain(fixture_stream(ERROR_FIXTURE, 32, ClaudeDecoder)).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap().delta, "Partial");
+        match &items[1] {
+            Err(LlmError::StreamError(msg)) => assert_eq!(msg, "Overloaded"),
+            other => panic!("expected stream error, got {other:?}"),
+        }
     }
 }
@@ -80,6 +80,9 @@ pub(super) fn parse_sse_event(event_type: &str, data: &str) -> Vec<StreamEvent>
                     Some("stop_sequence") => StopReason::StopSequence,
                     _ => StopReason::EndTurn,
                 };
+                if let Some(output_tokens) = parsed["usage"]["output_tokens"].as_u64() {
+                    events.push(StreamEvent::Usage { input_tokens: 0, output_tokens });
+                }
                 events.push(StreamEvent::MessageEnd { stop_reason });
             }
         }
@@ -87,7 +90,16 @@ pub(super) fn parse_sse_event(event_type: &str, data: &str) -> Vec<StreamEvent>
             // message_delta already emitted MessageEnd with stop_reason.
             // message_stop is just a sentinel -- nothing to emit.
         }
-        "message_start" | "ping" => {
+        "message_start" => {
+            // Input tokens are known up front; output tokens arrive in message_delta.
+            let input_tokens = serde_json::from_str::<Value>(data)
+                .ok()
+                .and_then(|v| v["message"]["usage"]["input_tokens"].as_u64());
+            if let Some(input_tokens) = input_tokens {
+                events.push(StreamEvent::Usage { input_tokens, output_tokens: 0 });
+            }
+        }
+        "ping" => {
             // Informational, no action needed.
         }
         "error" => {
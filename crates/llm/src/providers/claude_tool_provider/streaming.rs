@@ -10,6 +10,7 @@ use stupid_tool_runtime::{
     conversation::ConversationMessage,
     provider::{LlmError, ToolAwareLlmProvider},
     stream::StreamEvent,
+    tokens::TokenCounter,
     tool::ToolDefinition,
 };
 
@@ -53,6 +54,12 @@ impl ClaudeToolProvider {
     }
 }
 
+impl TokenCounter for ClaudeToolProvider {
+    fn context_window(&self) -> usize {
+        crate::tokens::claude_context_window(&self.model)
+    }
+}
+
 #[async_trait]
 impl ToolAwareLlmProvider for ClaudeToolProvider {
     async fn stream_with_tools(
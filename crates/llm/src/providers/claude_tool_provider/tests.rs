@@ -151,8 +151,9 @@ fn test_sse_message_delta_end_turn() {
         "message_delta",
         r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":42}}"#,
     );
-    assert_eq!(events.len(), 1);
-    match &events[0] {
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], StreamEvent::Usage { input_tokens: 0, output_tokens: 42 }));
+    match &events[1] {
         StreamEvent::MessageEnd { stop_reason } => {
             assert_eq!(*stop_reason, StopReason::EndTurn);
         }
@@ -166,8 +167,9 @@ fn test_sse_message_delta_tool_use() {
         "message_delta",
         r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":10}}"#,
     );
-    assert_eq!(events.len(), 1);
-    match &events[0] {
+    assert_eq!(events.len(), 2);
+    assert!(matches!(events[0], StreamEvent::Usage { input_tokens: 0, output_tokens: 10 }));
+    match &events[1] {
         StreamEvent::MessageEnd { stop_reason } => {
             assert_eq!(*stop_reason, StopReason::ToolUse);
         }
@@ -188,6 +190,16 @@ fn test_sse_error_event() {
     }
 }
 
+#[test]
+fn test_sse_message_start_reports_input_tokens() {
+    let events = parse_sse_event(
+        "message_start",
+        r#"{"type":"message_start","message":{"id":"msg_1","usage":{"input_tokens":120,"output_tokens":1}}}"#,
+    );
+    assert_eq!(events.len(), 1);
+    assert!(matches!(events[0], StreamEvent::Usage { input_tokens: 120, output_tokens: 0 }));
+}
+
 #[test]
 fn test_sse_ping_ignored() {
     let events = parse_sse_event("ping", "{}");
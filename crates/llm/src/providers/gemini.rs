@@ -2,7 +2,7 @@ use async_trait::async_trait;
 use serde_json::json;
 use tracing::debug;
 
-use crate::provider::{LlmError, LlmProvider, Message, Role};
+use crate::provider::{error_from_response, LlmError, LlmProvider, Message, Role};
 
 pub struct GeminiProvider {
     client: reqwest::Client,
@@ -73,26 +73,22 @@ impl LlmProvider for GeminiProvider {
         max_tokens: u32,
     ) -> Result<String, LlmError> {
         let url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
-            self.model, self.api_key,
+            "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent",
+            self.model,
         );
 
         let body = Self::build_request_body(&messages, temperature, max_tokens);
 
         debug!("Gemini request to model={}", self.model);
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        let status = response.status().as_u16();
-        if status != 200 {
-            let body = response.text().await.unwrap_or_default();
-            return Err(LlmError::ApiError { status, body });
+        let mut request = self.client.post(&url).header("Content-Type", "application/json");
+        for (name, value) in self.request_headers() {
+            request = request.header(name, value);
+        }
+        let response = request.json(&body).send().await?;
+
+        if response.status().as_u16() != 200 {
+            return Err(error_from_response(response).await);
         }
 
         let resp: serde_json::Value = response.json().await?;
@@ -107,6 +103,16 @@ impl LlmProvider for GeminiProvider {
 
         Ok(content)
     }
+
+    fn context_window(&self) -> usize {
+        crate::tokens::gemini_context_window(&self.model)
+    }
+
+    /// The key goes in a header rather than the `key` query parameter so it
+    /// stays out of URLs.
+    fn request_headers(&self) -> Vec<(&'static str, String)> {
+        vec![("x-goog-api-key", self.api_key.clone())]
+    }
 }
 
 #[cfg(test)]
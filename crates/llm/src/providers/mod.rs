@@ -6,12 +6,37 @@ pub mod openai;
 
 use stupid_core::config::{LlmConfig, OllamaConfig};
 
+use crate::logging::{ExchangeSink, LoggingProvider, Redactor};
 use crate::provider::{LlmError, LlmProvider};
+use crate::retry::{RetryPolicy, RetryingProvider};
 
 /// Create the appropriate LLM provider based on config.
+///
+/// When `exchange_log` is set, the provider is wrapped in a
+/// [`LoggingProvider`], so each retry attempt is logged. Unless `max_retries`
+/// is 0, the result is wrapped in a [`RetryingProvider`].
 pub fn create_provider(
     llm_config: &LlmConfig,
     ollama_config: &OllamaConfig,
+) -> Result<Box<dyn LlmProvider>, LlmError> {
+    let mut provider = create_base_provider(llm_config, ollama_config)?;
+    if let Some(sink) = llm_config.exchange_log.as_deref().and_then(ExchangeSink::parse) {
+        let redactor = Redactor::new(&llm_config.exchange_log_redact);
+        provider = Box::new(LoggingProvider::new(provider, &llm_config.provider, sink, redactor));
+    }
+    if llm_config.max_retries == 0 {
+        return Ok(provider);
+    }
+    let policy = RetryPolicy {
+        max_retries: llm_config.max_retries,
+        ..RetryPolicy::default()
+    };
+    Ok(Box::new(RetryingProvider::new(provider, policy)))
+}
+
+fn create_base_provider(
+    llm_config: &LlmConfig,
+    ollama_config: &OllamaConfig,
 ) -> Result<Box<dyn LlmProvider>, LlmError> {
     match llm_config.provider.as_str() {
         "openai" => {
@@ -39,7 +64,7 @@ pub fn create_provider(
                 llm_config.anthropic_model.clone(),
             )))
         }
-        "gemini" => {
+        "gemini" | "google" => {
             let api_key = llm_config
                 .gemini_api_key
                 .as_ref()
@@ -49,13 +74,75 @@ pub fn create_provider(
                 llm_config.gemini_model.clone(),
             )))
         }
-        "ollama" => Ok(Box::new(ollama::OllamaProvider::new(
-            ollama_config.url.clone(),
-            ollama_config.model.clone(),
-        ))),
+        "ollama" => Ok(Box::new(
+            ollama::OllamaProvider::new(ollama_config.url.clone(), ollama_config.model.clone())
+                .with_embedding_model(ollama_config.embedding_model.clone()),
+        )),
         other => Err(LlmError::NotConfigured(format!(
             "unknown LLM provider: '{}'",
             other
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn llm_config(provider: &str) -> LlmConfig {
+        LlmConfig {
+            provider: provider.to_string(),
+            openai_api_key: None,
+            openai_model: "gpt-4o".to_string(),
+            openai_base_url: None,
+            anthropic_api_key: None,
+            anthropic_model: "claude-sonnet-4-5-20250929".to_string(),
+            gemini_api_key: None,
+            gemini_model: "gemini-2.0-flash".to_string(),
+            temperature: 0.1,
+            max_tokens: 4096,
+            max_retries: 0,
+            exchange_log: None,
+            exchange_log_redact: vec![],
+            confirm_tools: vec![],
+            approval_timeout_secs: 120,
+        }
+    }
+
+    fn ollama_config() -> OllamaConfig {
+        OllamaConfig {
+            url: "http://localhost:11434".to_string(),
+            model: "llama3.2".to_string(),
+            embedding_model: "nomic-embed-text".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_gemini_requires_api_key() {
+        for provider in ["gemini", "google"] {
+            match create_provider(&llm_config(provider), &ollama_config()) {
+                Err(LlmError::NotConfigured(msg)) => assert!(msg.contains("GEMINI_API_KEY")),
+                Err(e) => panic!("{provider}: unexpected error {e}"),
+                Ok(_) => panic!("{provider}: expected missing key error"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_gemini_and_google_create_provider() {
+        for provider in ["gemini", "google"] {
+            let mut config = llm_config(provider);
+            config.gemini_api_key = Some("test-key".to_string());
+            assert!(config.is_configured());
+            assert!(create_provider(&config, &ollama_config()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_unknown_provider_errors() {
+        assert!(matches!(
+            create_provider(&llm_config("palm"), &ollama_config()),
+            Err(LlmError::NotConfigured(_))
+        ));
+    }
+}
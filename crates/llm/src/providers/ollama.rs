@@ -2,12 +2,20 @@ use async_trait::async_trait;
 use serde_json::json;
 use tracing::debug;
 
-use crate::provider::{LlmError, LlmProvider, Message, Role};
+use crate::provider::{error_from_response, LlmError, LlmProvider, Message, Role};
+use crate::stream::{collect_stream, decode_stream, Chunk, ChunkDecoder, ChunkStream};
+
+/// Ollama's default `num_ctx` when a request doesn't set one.
+const OLLAMA_DEFAULT_NUM_CTX: usize = 4096;
+
+/// Embedding model used unless overridden with [`OllamaProvider::with_embedding_model`].
+const DEFAULT_EMBEDDING_MODEL: &str = "nomic-embed-text";
 
 pub struct OllamaProvider {
     client: reqwest::Client,
     url: String,
     model: String,
+    embedding_model: String,
 }
 
 impl OllamaProvider {
@@ -16,8 +24,15 @@ impl OllamaProvider {
             client: reqwest::Client::new(),
             url,
             model,
+            embedding_model: DEFAULT_EMBEDDING_MODEL.to_string(),
         }
     }
+
+    /// Use `model` for [`embed`](LlmProvider::embed) requests.
+    pub fn with_embedding_model(mut self, model: String) -> Self {
+        self.embedding_model = model;
+        self
+    }
 }
 
 #[async_trait]
@@ -26,8 +41,17 @@ impl LlmProvider for OllamaProvider {
         &self,
         messages: Vec<Message>,
         temperature: f32,
-        _max_tokens: u32,
+        max_tokens: u32,
     ) -> Result<String, LlmError> {
+        collect_stream(self.complete_stream(messages, temperature, max_tokens).await?).await
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        _max_tokens: u32,
+    ) -> Result<ChunkStream, LlmError> {
         let url = format!("{}/api/chat", self.url);
 
         let api_messages: Vec<serde_json::Value> = messages
@@ -47,7 +71,7 @@ impl LlmProvider for OllamaProvider {
         let body = json!({
             "model": self.model,
             "messages": api_messages,
-            "stream": false,
+            "stream": true,
             "options": {
                 "temperature": temperature,
             },
@@ -63,18 +87,104 @@ impl LlmProvider for OllamaProvider {
             .send()
             .await?;
 
-        let status = response.status().as_u16();
-        if status != 200 {
-            let body = response.text().await.unwrap_or_default();
-            return Err(LlmError::ApiError { status, body });
+        if response.status().as_u16() != 200 {
+            return Err(error_from_response(response).await);
+        }
+
+        Ok(decode_stream(response.bytes_stream(), OllamaDecoder))
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        // The batch endpoint; the older `/api/embeddings` takes one prompt per request.
+        let url = format!("{}/api/embed", self.url);
+        let body = json!({
+            "model": self.embedding_model,
+            "input": texts,
+        });
+
+        debug!("Ollama embeddings request to {} ({} inputs)", url, texts.len());
+
+        let response = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await?;
+
+        if response.status().as_u16() != 200 {
+            return Err(error_from_response(response).await);
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let embeddings: Vec<Vec<f32>> = serde_json::from_value(json["embeddings"].clone())
+            .map_err(|e| LlmError::ParseError(e.to_string()))?;
+        if embeddings.len() != texts.len() {
+            return Err(LlmError::ParseError(format!(
+                "expected {} embeddings, got {}",
+                texts.len(),
+                embeddings.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+
+    fn context_window(&self) -> usize {
+        // Ollama truncates prompts to the server's `num_ctx`, not the model's
+        // trained context; we don't set it, so assume the server default.
+        OLLAMA_DEFAULT_NUM_CTX
+    }
+}
+
+/// Decodes `/api/chat` NDJSON: one JSON object per line, the last with `done: true`.
+struct OllamaDecoder;
+
+impl ChunkDecoder for OllamaDecoder {
+    fn decode_line(&mut self, line: &str) -> Result<Option<Chunk>, LlmError> {
+        let event: serde_json::Value =
+            serde_json::from_str(line).map_err(|e| LlmError::ParseError(e.to_string()))?;
+        if let Some(error) = event["error"].as_str() {
+            return Err(LlmError::StreamError(error.to_string()));
         }
 
-        let resp: serde_json::Value = response.json().await?;
-        let content = resp["message"]["content"]
-            .as_str()
-            .ok_or_else(|| LlmError::ParseError("missing message.content".into()))?
-            .to_string();
+        let finish_reason = event["done"]
+            .as_bool()
+            .unwrap_or(false)
+            .then(|| event["done_reason"].as_str().unwrap_or("stop").to_string());
+        let chunk = Chunk {
+            delta: event["message"]["content"].as_str().unwrap_or_default().to_string(),
+            finish_reason,
+        };
+        Ok((!chunk.delta.is_empty() || chunk.finish_reason.is_some()).then_some(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::test_support::{drain, fixture_stream};
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/ollama_stream.ndjson");
+    const ERROR_FIXTURE: &str = include_str!("../../tests/fixtures/ollama_stream_error.ndjson");
+
+    #[tokio::test]
+    async fn test_stream_assembles_chunks() {
+        for size in [1, 5, 64, FIXTURE.len()] {
+            let text = collect_stream(fixture_stream(FIXTURE, size, OllamaDecoder)).await.unwrap();
+            assert_eq!(text, "Top members by logins: alice (42) — bob (17).");
+        }
+
+        let items = drain(fixture_stream(FIXTURE, 16, OllamaDecoder)).await;
+        assert_eq!(items.len(), 4);
+        let last = items.last().unwrap().as_ref().unwrap();
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+    }
 
-        Ok(content)
+    #[tokio::test]
+    async fn test_mid_stream_error_is_err() {
+        let items = drain(fixture_stream(ERROR_FIXTURE, 32, OllamaDecoder)).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap().delta, "Partial");
+        assert!(matches!(&items[1], Err(LlmError::StreamError(msg)) if msg.contains("unexpectedly stopped")));
     }
 }
@@ -2,12 +2,17 @@ use async_trait::async_trait;
 use serde_json::json;
 use tracing::debug;
 
-use crate::provider::{LlmError, LlmProvider, Message, Role};
+use crate::provider::{error_from_response, LlmError, LlmProvider, Message, Role};
+use crate::stream::{collect_stream, decode_stream, sse_data, Chunk, ChunkDecoder, ChunkStream};
+
+/// Embedding model used unless overridden with [`OpenAiProvider::with_embedding_model`].
+const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
 
 pub struct OpenAiProvider {
     client: reqwest::Client,
     api_key: String,
     model: String,
+    embedding_model: String,
     base_url: String,
 }
 
@@ -17,9 +22,16 @@ impl OpenAiProvider {
             client: reqwest::Client::new(),
             api_key,
             model,
+            embedding_model: DEFAULT_EMBEDDING_MODEL.to_string(),
             base_url,
         }
     }
+
+    /// Use `model` for [`embed`](LlmProvider::embed) requests.
+    pub fn with_embedding_model(mut self, model: String) -> Self {
+        self.embedding_model = model;
+        self
+    }
 }
 
 #[async_trait]
@@ -30,6 +42,15 @@ impl LlmProvider for OpenAiProvider {
         temperature: f32,
         max_tokens: u32,
     ) -> Result<String, LlmError> {
+        collect_stream(self.complete_stream(messages, temperature, max_tokens).await?).await
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<ChunkStream, LlmError> {
         let url = format!("{}/v1/chat/completions", self.base_url);
 
         let api_messages: Vec<serde_json::Value> = messages
@@ -51,31 +72,194 @@ impl LlmProvider for OpenAiProvider {
             "messages": api_messages,
             "temperature": temperature,
             "max_tokens": max_tokens,
+            "stream": true,
         });
 
         debug!("OpenAI request to {}", url);
 
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&body)
-            .send()
-            .await?;
-
-        let status = response.status().as_u16();
-        if status != 200 {
-            let body = response.text().await.unwrap_or_default();
-            return Err(LlmError::ApiError { status, body });
+        let mut request = self.client.post(&url).header("Content-Type", "application/json");
+        for (name, value) in self.request_headers() {
+            request = request.header(name, value);
+        }
+        let response = request.json(&body).send().await?;
+
+        if response.status().as_u16() != 200 {
+            return Err(error_from_response(response).await);
+        }
+
+        Ok(decode_stream(response.bytes_stream(), OpenAiDecoder))
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let url = format!("{}/v1/embeddings", self.base_url);
+        let body = embedding_request(&self.embedding_model, texts);
+
+        debug!("OpenAI embeddings request to {} ({} inputs)", url, texts.len());
+
+        let mut request = self.client.post(&url).header("Content-Type", "application/json");
+        for (name, value) in self.request_headers() {
+            request = request.header(name, value);
+        }
+        let response = request.json(&body).send().await?;
+
+        if response.status().as_u16() != 200 {
+            return Err(error_from_response(response).await);
         }
 
-        let resp: serde_json::Value = response.json().await?;
-        let content = resp["choices"][0]["message"]["content"]
-            .as_str()
-            .ok_or_else(|| LlmError::ParseError("missing choices[0].message.content".into()))?
-            .to_string();
+        let json: serde_json::Value = response.json().await?;
+        parse_embedding_response(json, texts.len())
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        crate::tokens::openai_count_tokens(&self.model, text)
+    }
+
+    fn context_window(&self) -> usize {
+        crate::tokens::openai_context_window(&self.model)
+    }
+
+    fn request_headers(&self) -> Vec<(&'static str, String)> {
+        vec![("Authorization", format!("Bearer {}", self.api_key))]
+    }
+}
+
+/// Request body for a batch `embeddings` call.
+fn embedding_request(model: &str, texts: &[String]) -> serde_json::Value {
+    json!({
+        "model": model,
+        "input": texts,
+        "encoding_format": "float",
+    })
+}
+
+/// Extract the vectors from an `embeddings` response, ordered by each item's
+/// `index` (the API doesn't guarantee response order matches input order).
+fn parse_embedding_response(
+    json: serde_json::Value,
+    expected: usize,
+) -> Result<Vec<Vec<f32>>, LlmError> {
+    #[derive(serde::Deserialize)]
+    struct Item {
+        index: usize,
+        embedding: Vec<f32>,
+    }
+    #[derive(serde::Deserialize)]
+    struct Response {
+        data: Vec<Item>,
+    }
+
+    let mut response: Response =
+        serde_json::from_value(json).map_err(|e| LlmError::ParseError(e.to_string()))?;
+    if response.data.len() != expected {
+        return Err(LlmError::ParseError(format!(
+            "expected {expected} embeddings, got {}",
+            response.data.len()
+        )));
+    }
+    response.data.sort_by_key(|item| item.index);
+    Ok(response.data.into_iter().map(|item| item.embedding).collect())
+}
+
+/// Decodes `chat.completions` SSE: one `data:` JSON chunk per line, ending
+/// with `data: [DONE]`.
+struct OpenAiDecoder;
+
+impl ChunkDecoder for OpenAiDecoder {
+    fn decode_line(&mut self, line: &str) -> Result<Option<Chunk>, LlmError> {
+        let Some(data) = sse_data(line) else {
+            return Ok(None);
+        };
+        if data == "[DONE]" {
+            return Ok(None);
+        }
+
+        let event: serde_json::Value =
+            serde_json::from_str(data).map_err(|e| LlmError::ParseError(e.to_string()))?;
+        if let Some(error) = event.get("error") {
+            let message = error["message"].as_str().unwrap_or("unknown error");
+            return Err(LlmError::StreamError(message.to_string()));
+        }
+
+        let choice = &event["choices"][0];
+        let chunk = Chunk {
+            delta: choice["delta"]["content"].as_str().unwrap_or_default().to_string(),
+            finish_reason: choice["finish_reason"].as_str().map(String::from),
+        };
+        Ok((!chunk.delta.is_empty() || chunk.finish_reason.is_some()).then_some(chunk))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stream::test_support::{drain, fixture_stream};
+
+    const FIXTURE: &str = include_str!("../../tests/fixtures/openai_stream.sse");
+    const ERROR_FIXTURE: &str = include_str!("../../tests/fixtures/openai_stream_error.sse");
+
+    #[tokio::test]
+    async fn test_stream_assembles_chunks() {
+        for size in [1, 5, 64, FIXTURE.len()] {
+            let text = collect_stream(fixture_stream(FIXTURE, size, OpenAiDecoder)).await.unwrap();
+            assert_eq!(text, "Top members by logins: alice (42) — bob (17).");
+        }
+
+        let items = drain(fixture_stream(FIXTURE, 16, OpenAiDecoder)).await;
+        assert_eq!(items.len(), 4);
+        let last = items.last().unwrap().as_ref().unwrap();
+        assert_eq!(last.delta, "");
+        assert_eq!(last.finish_reason.as_deref(), Some("stop"));
+    }
+
+    #[test]
+    fn test_embedding_request_batches_inputs() {
+        let texts = vec!["alice logged in".to_string(), "bob opened a game".to_string()];
+        let body = embedding_request("text-embedding-3-small", &texts);
+        assert_eq!(
+            body,
+            json!({
+                "model": "text-embedding-3-small",
+                "input": ["alice logged in", "bob opened a game"],
+                "encoding_format": "float",
+            })
+        );
+    }
+
+    #[test]
+    fn test_embedding_response_is_ordered_by_index() {
+        let response = json!({
+            "object": "list",
+            "data": [
+                {"object": "embedding", "index": 1, "embedding": [0.5, -0.5]},
+                {"object": "embedding", "index": 0, "embedding": [0.25, 0.75]},
+            ],
+            "model": "text-embedding-3-small",
+            "usage": {"prompt_tokens": 8, "total_tokens": 8},
+        });
+        let embeddings = parse_embedding_response(response, 2).unwrap();
+        assert_eq!(embeddings, vec![vec![0.25, 0.75], vec![0.5, -0.5]]);
+    }
+
+    #[test]
+    fn test_embedding_response_count_mismatch_is_error() {
+        let response = json!({"data": [{"index": 0, "embedding": [1.0]}]});
+        assert!(matches!(
+            parse_embedding_response(response, 2),
+            Err(LlmError::ParseError(_))
+        ));
+        assert!(parse_embedding_response(json!({"error": "nope"}), 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_mid_stream_error_is_err() {
+        let items = drain(fixture_stream(ERROR_FIXTURE, 32, OpenAiDecoder)).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap().delta, "Partial");
+        match &items[1] {
+            Err(LlmError::StreamError(msg)) => assert!(msg.contains("server had an error")),
+            other => panic!("expected stream error, got {other:?}"),
+        }
 
-        Ok(content)
+        assert!(collect_stream(fixture_stream(ERROR_FIXTURE, 32, OpenAiDecoder)).await.is_err());
     }
 }
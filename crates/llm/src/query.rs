@@ -1,9 +1,9 @@
 use std::path::Path;
 
 use serde_json::Value;
-use stupid_catalog::{Catalog, QueryExecutor, QueryPlan};
+use stupid_catalog::{Catalog, InvalidReference, QueryExecutor, QueryPlan};
 use stupid_graph::GraphStore;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use crate::provider::{LlmError, LlmProvider, Message, Role};
 
@@ -50,6 +50,11 @@ impl QueryGenerator {
     }
 
     /// Generate a QueryPlan from a natural language question.
+    ///
+    /// The plan is validated against `catalog`. If it references unknown
+    /// entity types, edge types, fields or steps, the model is asked once to
+    /// repair it; a plan that is still invalid is rejected with
+    /// [`QueryError::InvalidReferences`].
     pub async fn generate_plan(
         &self,
         question: &str,
@@ -65,7 +70,7 @@ impl QueryGenerator {
 
         info!("Generating query plan for: {}", question);
 
-        let messages = vec![
+        let mut messages = vec![
             Message {
                 role: Role::System,
                 content: system_prompt,
@@ -76,9 +81,42 @@ impl QueryGenerator {
             },
         ];
 
+        let (plan, response) = self.request_plan(&messages).await?;
+        let invalid = plan.validate(catalog);
+        if invalid.is_empty() {
+            info!("Generated plan with {} steps", plan.steps.len());
+            return Ok(plan);
+        }
+
+        warn!(
+            "Generated plan has {} invalid references, asking for a repair",
+            invalid.len()
+        );
+        messages.push(Message {
+            role: Role::Assistant,
+            content: response,
+        });
+        messages.push(Message {
+            role: Role::User,
+            content: repair_prompt(&invalid),
+        });
+
+        let (plan, _) = self.request_plan(&messages).await?;
+        let invalid = plan.validate(catalog);
+        if !invalid.is_empty() {
+            return Err(QueryError::InvalidReferences { invalid, plan });
+        }
+
+        info!("Repaired plan with {} steps", plan.steps.len());
+        Ok(plan)
+    }
+
+    /// Send `messages` and parse the reply as a QueryPlan, returning the plan
+    /// together with the raw response.
+    async fn request_plan(&self, messages: &[Message]) -> Result<(QueryPlan, String), QueryError> {
         let response = self
             .provider
-            .complete(messages, self.temperature, self.max_tokens)
+            .complete(messages.to_vec(), self.temperature, self.max_tokens)
             .await
             .map_err(QueryError::LlmError)?;
 
@@ -93,8 +131,7 @@ impl QueryGenerator {
                 raw_response: response.clone(),
             })?;
 
-        info!("Generated plan with {} steps", plan.steps.len());
-        Ok(plan)
+        Ok((plan, response))
     }
 
     /// End-to-end: question → plan → execute → results.
@@ -135,10 +172,33 @@ pub enum QueryError {
         reason: String,
         raw_response: String,
     },
+    #[error("query plan references unknown names: {}", format_references(.invalid))]
+    InvalidReferences {
+        invalid: Vec<InvalidReference>,
+        plan: QueryPlan,
+    },
     #[error("execution error: {reason}")]
     ExecutionError { reason: String },
 }
 
+fn format_references(invalid: &[InvalidReference]) -> String {
+    invalid
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Follow-up prompt asking the model to fix the listed references.
+fn repair_prompt(invalid: &[InvalidReference]) -> String {
+    let list: String = invalid.iter().map(|r| format!("- {r}\n")).collect();
+    format!(
+        "That plan references names that do not exist in the schema:\n{list}\n\
+         Use only the entity types, edge types and fields listed in the schema. \
+         Respond ONLY with the corrected QueryPlan JSON, no explanation."
+    )
+}
+
 /// Load a prompt template from disk, failing eagerly with a clear message.
 fn load_template(path: &str) -> Result<String, String> {
     let path = Path::new(path);
@@ -261,4 +321,99 @@ mod tests {
         assert!(prompt.contains("QueryPlan"));
         assert!(!prompt.contains(SCHEMA_PLACEHOLDER));
     }
+
+    /// Replies with the queued responses in order, recording each request.
+    struct ScriptedProvider {
+        responses: std::sync::Mutex<Vec<String>>,
+        requests: Requests,
+    }
+
+    #[async_trait::async_trait]
+    impl LlmProvider for ScriptedProvider {
+        async fn complete(&self, messages: Vec<Message>, _: f32, _: u32) -> Result<String, LlmError> {
+            self.requests.lock().unwrap().push(messages);
+            Ok(self.responses.lock().unwrap().remove(0))
+        }
+    }
+
+    type Requests = std::sync::Arc<std::sync::Mutex<Vec<Vec<Message>>>>;
+
+    fn scripted_generator(responses: &[&str]) -> (QueryGenerator, Requests) {
+        let requests = Requests::default();
+        let provider = ScriptedProvider {
+            responses: std::sync::Mutex::new(responses.iter().map(|r| r.to_string()).collect()),
+            requests: requests.clone(),
+        };
+        let generator = QueryGenerator {
+            provider: Box::new(provider),
+            temperature: 0.0,
+            max_tokens: 512,
+            system_prompt_template: load_template(&workspace_template_path()).unwrap(),
+        };
+        (generator, requests)
+    }
+
+    fn member_catalog() -> Catalog {
+        Catalog {
+            entity_types: vec![stupid_catalog::CatalogEntry {
+                entity_type: "Member".into(),
+                node_count: 10,
+                sample_keys: vec!["alice".into()],
+                fields: vec![],
+            }],
+            edge_types: vec![stupid_catalog::EdgeSummary {
+                edge_type: "LoggedInFrom".into(),
+                count: 5,
+                source_types: vec!["Member".into()],
+                target_types: vec!["Device".into()],
+            }],
+            total_nodes: 10,
+            total_edges: 5,
+            external_sources: vec![],
+        }
+    }
+
+    const BOGUS_PLAN: &str = r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Customer"}]}"#;
+    const VALID_PLAN: &str = r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#;
+
+    #[tokio::test]
+    async fn valid_plan_needs_no_repair() {
+        let (generator, requests) = scripted_generator(&[VALID_PLAN]);
+        let plan = generator.generate_plan("all members", &member_catalog()).await.unwrap();
+        assert_eq!(plan.steps.len(), 1);
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn bogus_entity_type_is_repaired() {
+        let (generator, requests) = scripted_generator(&[BOGUS_PLAN, VALID_PLAN]);
+        let plan = generator.generate_plan("all customers", &member_catalog()).await.unwrap();
+        match &plan.steps[0].kind {
+            stupid_catalog::plan::StepKind::Filter(f) => assert_eq!(f.entity_type, "Member"),
+            other => panic!("expected filter step, got {other:?}"),
+        }
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let repair = &requests[1];
+        assert_eq!(repair.len(), 4, "repair resends the conversation plus the bad reply");
+        assert_eq!(repair[2].content, BOGUS_PLAN);
+        assert!(repair[3].content.contains("unknown entity type 'Customer'"));
+    }
+
+    #[tokio::test]
+    async fn unrepaired_plan_lists_invalid_references() {
+        let (generator, requests) = scripted_generator(&[BOGUS_PLAN, BOGUS_PLAN]);
+        let err = generator.generate_plan("all customers", &member_catalog()).await.unwrap_err();
+        match err {
+            QueryError::InvalidReferences { invalid, plan } => {
+                assert_eq!(invalid.len(), 1);
+                assert_eq!(invalid[0].kind, stupid_catalog::ReferenceKind::EntityType);
+                assert_eq!(invalid[0].name, "Customer");
+                assert_eq!(plan.steps.len(), 1);
+            }
+            other => panic!("expected invalid references, got {other:?}"),
+        }
+        assert_eq!(requests.lock().unwrap().len(), 2, "only one repair attempt");
+    }
 }
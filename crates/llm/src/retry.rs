@@ -0,0 +1,269 @@
+//! Shared retry handling for LLM providers.
+//!
+//! [`RetryingProvider`] wraps any [`LlmProvider`] and retries requests that
+//! fail with a retryable error (see [`LlmError::is_retryable`]), honouring the
+//! provider's `Retry-After` when it sent one and otherwise backing off
+//! exponentially with jitter.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use tracing::warn;
+
+use crate::provider::{LlmError, LlmProvider, Message};
+use crate::stream::ChunkStream;
+
+/// How many times, and how patiently, to retry a failed request.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt; 0 disables retrying.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled for each further retry.
+    pub base_delay: Duration,
+    /// Upper bound for any single delay, including a provider's `Retry-After`.
+    pub max_delay: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Delay before retry number `retry` (0-based) after `error`.
+    fn delay(&self, retry: u32, error: &LlmError) -> Duration {
+        let delay = match error {
+            LlmError::RateLimited { retry_after_secs: Some(secs), .. } => Duration::from_secs(*secs),
+            _ => {
+                let backoff = self.base_delay.saturating_mul(1u32 << retry.min(16));
+                // Up to 25% jitter so concurrent callers don't retry in lockstep.
+                let jitter_ms = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .subsec_nanos() as u64
+                    % (backoff.as_millis() as u64 / 4 + 1);
+                backoff + Duration::from_millis(jitter_ms)
+            }
+        };
+        delay.min(self.max_delay)
+    }
+}
+
+/// Decorator that retries an inner provider's failed requests.
+///
+/// For [`complete_stream`](LlmProvider::complete_stream) only establishing the
+/// stream is retried; errors after chunks have been delivered are passed on.
+pub struct RetryingProvider {
+    inner: Box<dyn LlmProvider>,
+    policy: RetryPolicy,
+}
+
+impl RetryingProvider {
+    pub fn new(inner: Box<dyn LlmProvider>, policy: RetryPolicy) -> Self {
+        Self { inner, policy }
+    }
+
+    /// Wait before retry number `retry`, or return `Err(error)` if `error`
+    /// isn't retryable or the retries are used up.
+    async fn backoff(&self, retry: u32, error: LlmError) -> Result<(), LlmError> {
+        if !error.is_retryable() || retry >= self.policy.max_retries {
+            return Err(error);
+        }
+        let delay = self.policy.delay(retry, &error);
+        warn!(
+            attempt = retry + 1,
+            max_retries = self.policy.max_retries,
+            delay_ms = delay.as_millis() as u64,
+            error = %error,
+            "LLM request failed, retrying"
+        );
+        tokio::time::sleep(delay).await;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl LlmProvider for RetryingProvider {
+    async fn complete(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<String, LlmError> {
+        let mut retry = 0;
+        loop {
+            match self.inner.complete(messages.clone(), temperature, max_tokens).await {
+                Ok(text) => return Ok(text),
+                Err(e) => self.backoff(retry, e).await?,
+            }
+            retry += 1;
+        }
+    }
+
+    async fn complete_stream(
+        &self,
+        messages: Vec<Message>,
+        temperature: f32,
+        max_tokens: u32,
+    ) -> Result<ChunkStream, LlmError> {
+        let mut retry = 0;
+        loop {
+            match self.inner.complete_stream(messages.clone(), temperature, max_tokens).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => self.backoff(retry, e).await?,
+            }
+            retry += 1;
+        }
+    }
+
+    async fn embed(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, LlmError> {
+        let mut retry = 0;
+        loop {
+            match self.inner.embed(texts).await {
+                Ok(embeddings) => return Ok(embeddings),
+                Err(e) => self.backoff(retry, e).await?,
+            }
+            retry += 1;
+        }
+    }
+
+    fn count_tokens(&self, text: &str) -> usize {
+        self.inner.count_tokens(text)
+    }
+
+    fn context_window(&self) -> usize {
+        self.inner.context_window()
+    }
+
+    fn request_headers(&self) -> Vec<(&'static str, String)> {
+        self.inner.request_headers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use crate::provider::Role;
+
+    /// Fails with the queued errors in order, then succeeds.
+    struct StubProvider {
+        errors: Mutex<Vec<LlmError>>,
+        calls: Arc<AtomicU32>,
+    }
+
+    impl StubProvider {
+        fn new(mut errors: Vec<LlmError>) -> (Self, Arc<AtomicU32>) {
+            errors.reverse();
+            let calls = Arc::new(AtomicU32::new(0));
+            (Self { errors: Mutex::new(errors), calls: calls.clone() }, calls)
+        }
+    }
+
+    #[async_trait]
+    impl LlmProvider for StubProvider {
+        async fn complete(&self, _: Vec<Message>, _: f32, _: u32) -> Result<String, LlmError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            match self.errors.lock().unwrap().pop() {
+                Some(e) => Err(e),
+                None => Ok("ok".to_string()),
+            }
+        }
+    }
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(5),
+        }
+    }
+
+    fn rate_limited() -> LlmError {
+        LlmError::RateLimited { retry_after_secs: Some(0), body: "slow down".into() }
+    }
+
+    fn messages() -> Vec<Message> {
+        vec![Message { role: Role::User, content: "hi".into() }]
+    }
+
+    #[tokio::test]
+    async fn test_fails_twice_then_succeeds() {
+        let (stub, calls) = StubProvider::new(vec![
+            rate_limited(),
+            LlmError::ApiError { status: 503, body: "unavailable".into() },
+        ]);
+        let provider = RetryingProvider::new(Box::new(stub), fast_policy(3));
+
+        assert_eq!(provider.complete(messages(), 0.1, 100).await.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_surface_rate_limited() {
+        let (stub, calls) = StubProvider::new(vec![rate_limited(), rate_limited(), rate_limited()]);
+        let provider = RetryingProvider::new(Box::new(stub), fast_policy(2));
+
+        let err = provider.complete(messages(), 0.1, 100).await.unwrap_err();
+        assert!(matches!(err, LlmError::RateLimited { .. }));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_non_retryable_error_is_returned_immediately() {
+        let (stub, calls) = StubProvider::new(vec![LlmError::ApiError { status: 400, body: "bad".into() }]);
+        let provider = RetryingProvider::new(Box::new(stub), fast_policy(3));
+
+        let err = provider.complete(messages(), 0.1, 100).await.unwrap_err();
+        assert!(matches!(err, LlmError::ApiError { status: 400, .. }));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_embed_passes_through_unsupported() {
+        let (stub, calls) = StubProvider::new(vec![]);
+        let provider = RetryingProvider::new(Box::new(stub), fast_policy(3));
+
+        let err = provider.embed(&["hi".to_string()]).await.unwrap_err();
+        assert!(matches!(err, LlmError::Unsupported(_)));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_stream_setup_is_retried() {
+        let (stub, calls) = StubProvider::new(vec![rate_limited(), rate_limited()]);
+        let provider = RetryingProvider::new(Box::new(stub), fast_policy(3));
+
+        let stream = provider.complete_stream(messages(), 0.1, 100).await.unwrap();
+        assert_eq!(crate::stream::collect_stream(stream).await.unwrap(), "ok");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_delay_honours_retry_after_and_caps() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_secs(10),
+        };
+        let limited = LlmError::RateLimited { retry_after_secs: Some(7), body: String::new() };
+        assert_eq!(policy.delay(0, &limited), Duration::from_secs(7));
+        let limited = LlmError::RateLimited { retry_after_secs: Some(60), body: String::new() };
+        assert_eq!(policy.delay(0, &limited), Duration::from_secs(10));
+
+        let server = LlmError::ApiError { status: 503, body: String::new() };
+        for retry in 0..4 {
+            let base = Duration::from_millis(100 << retry);
+            let delay = policy.delay(retry, &server);
+            assert!(delay >= base && delay <= base + base / 4, "retry {retry}: {delay:?}");
+        }
+        assert_eq!(policy.delay(12, &server), Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,203 @@
+//! Streaming completions: the [`Chunk`] type and a line-oriented driver that
+//! turns a provider's HTTP byte stream into a [`ChunkStream`].
+//!
+//! Each provider supplies a [`ChunkDecoder`] for its wire format (SSE for
+//! OpenAI and Claude, NDJSON for Ollama); the driver handles buffering,
+//! line splitting across network reads, and error propagation.
+
+use std::collections::VecDeque;
+use std::pin::Pin;
+
+use bytes::Bytes;
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::provider::LlmError;
+
+/// An incremental piece of a streamed completion.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Chunk {
+    /// Text appended to the response by this chunk (may be empty).
+    pub delta: String,
+    /// Set on the chunk that ends the response, with the provider's stop reason.
+    pub finish_reason: Option<String>,
+}
+
+/// A boxed stream of completion chunks. A mid-stream failure is yielded as an
+/// `Err` item and ends the stream.
+pub type ChunkStream = Pin<Box<dyn Stream<Item = Result<Chunk, LlmError>> + Send>>;
+
+/// Decodes one line of a provider's streaming response body.
+pub(crate) trait ChunkDecoder: Send + 'static {
+    /// Return the chunk carried by `line`, `None` for lines without content
+    /// (comments, keep-alives, bookkeeping events), or an error the provider
+    /// reported mid-stream.
+    fn decode_line(&mut self, line: &str) -> Result<Option<Chunk>, LlmError>;
+}
+
+/// Concatenate a stream's deltas into the full response text.
+pub async fn collect_stream(mut stream: ChunkStream) -> Result<String, LlmError> {
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        text.push_str(&chunk?.delta);
+    }
+    Ok(text)
+}
+
+struct DriverState<S, D> {
+    bytes: Pin<Box<S>>,
+    buffer: Vec<u8>,
+    decoder: D,
+    pending: VecDeque<Result<Chunk, LlmError>>,
+    done: bool,
+}
+
+/// Turn a response byte stream into a [`ChunkStream`] using `decoder`.
+pub(crate) fn decode_stream<S, E, D>(bytes: S, decoder: D) -> ChunkStream
+where
+    S: Stream<Item = Result<Bytes, E>> + Send + 'static,
+    E: Into<LlmError> + 'static,
+    D: ChunkDecoder,
+{
+    let state = DriverState {
+        bytes: Box::pin(bytes),
+        buffer: Vec::new(),
+        decoder,
+        pending: VecDeque::new(),
+        done: false,
+    };
+
+    Box::pin(stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(item) = state.pending.pop_front() {
+                return Some((item, state));
+            }
+            if state.done {
+                return None;
+            }
+
+            match state.bytes.next().await {
+                Some(Ok(bytes)) => state.buffer.extend_from_slice(&bytes),
+                Some(Err(e)) => {
+                    state.done = true;
+                    return Some((Err(e.into()), state));
+                }
+                None => {
+                    // Treat a trailing line without a newline as complete.
+                    state.done = true;
+                    if !state.buffer.is_empty() {
+                        state.buffer.push(b'\n');
+                    }
+                }
+            }
+
+            // Split on raw bytes so multi-byte characters can straddle reads.
+            while let Some(pos) = state.buffer.iter().position(|&b| b == b'\n') {
+                let line: Vec<u8> = state.buffer.drain(..=pos).collect();
+                let line = String::from_utf8_lossy(&line);
+                let line = line.trim_end_matches(['\n', '\r']);
+                if line.is_empty() {
+                    continue;
+                }
+                match state.decoder.decode_line(line) {
+                    Ok(Some(chunk)) => state.pending.push_back(Ok(chunk)),
+                    Ok(None) => {}
+                    Err(e) => {
+                        // Deliver what was decoded before the error, then stop.
+                        state.pending.push_back(Err(e));
+                        state.buffer.clear();
+                        state.done = true;
+                    }
+                }
+            }
+        }
+    }))
+}
+
+/// Payload of an SSE `data:` line, if `line` is one.
+pub(crate) fn sse_data(line: &str) -> Option<&str> {
+    line.strip_prefix("data:").map(str::trim_start)
+}
+
+#[cfg(test)]
+pub(crate) mod test_support {
+    use super::*;
+
+    /// Feed `body` to the driver in `size`-byte pieces, as a network would.
+    pub(crate) fn fixture_stream<D: ChunkDecoder>(body: &str, size: usize, decoder: D) -> ChunkStream {
+        let pieces: Vec<Result<Bytes, LlmError>> = body
+            .as_bytes()
+            .chunks(size)
+            .map(|c| Ok(Bytes::copy_from_slice(c)))
+            .collect();
+        decode_stream(stream::iter(pieces), decoder)
+    }
+
+    /// Drain a stream, returning every item in order.
+    pub(crate) async fn drain(mut stream: ChunkStream) -> Vec<Result<Chunk, LlmError>> {
+        let mut items = Vec::new();
+        while let Some(item) = stream.next().await {
+            items.push(item);
+        }
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_support::*;
+    use super::*;
+
+    /// One chunk per line; "!" lines are errors.
+    struct LineDecoder;
+
+    impl ChunkDecoder for LineDecoder {
+        fn decode_line(&mut self, line: &str) -> Result<Option<Chunk>, LlmError> {
+            match line {
+                "!" => Err(LlmError::StreamError("boom".into())),
+                "skip" => Ok(None),
+                _ => Ok(Some(Chunk { delta: line.to_string(), finish_reason: None })),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_lines_reassembled_across_reads() {
+        for size in [1, 2, 3, 7, 64] {
+            let stream = fixture_stream("héllo\r\nskip\n\nwörld", size, LineDecoder);
+            let text = collect_stream(stream).await.unwrap();
+            assert_eq!(text, "héllowörld", "read size {size}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_error_ends_stream_after_earlier_chunks() {
+        let items = drain(fixture_stream("a\nb\n!\nc\n", 64, LineDecoder)).await;
+        assert_eq!(items.len(), 3);
+        assert_eq!(items[0].as_ref().unwrap().delta, "a");
+        assert_eq!(items[1].as_ref().unwrap().delta, "b");
+        assert!(matches!(items[2], Err(LlmError::StreamError(_))));
+
+        let err = collect_stream(fixture_stream("a\n!\n", 1, LineDecoder)).await;
+        assert!(err.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_transport_error_surfaces() {
+        let pieces: Vec<Result<Bytes, LlmError>> = vec![
+            Ok(Bytes::from_static(b"a\n")),
+            Err(LlmError::StreamError("connection reset".into())),
+            Ok(Bytes::from_static(b"b\n")),
+        ];
+        let items = drain(decode_stream(stream::iter(pieces), LineDecoder)).await;
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].as_ref().unwrap().delta, "a");
+        assert!(items[1].is_err());
+    }
+
+    #[test]
+    fn test_sse_data() {
+        assert_eq!(sse_data("data: {\"a\":1}"), Some("{\"a\":1}"));
+        assert_eq!(sse_data("data:[DONE]"), Some("[DONE]"));
+        assert_eq!(sse_data("event: ping"), None);
+    }
+}
@@ -0,0 +1,79 @@
+//! Per-model token counts and context windows for the built-in providers.
+//!
+//! OpenAI models are counted exactly with their tiktoken encoding. The other
+//! providers don't publish a local tokenizer, so they use the conservative
+//! [`estimate_tokens`] and only report their model's context window.
+
+use stupid_tool_runtime::tokens::{estimate_tokens, DEFAULT_CONTEXT_WINDOW};
+use tiktoken_rs::tokenizer::{get_tokenizer, Tokenizer};
+
+/// Count `text` with the tiktoken encoding used by OpenAI `model`, falling
+/// back to the estimate for models tiktoken doesn't know (e.g. local models
+/// behind an OpenAI-compatible endpoint).
+pub(crate) fn openai_count_tokens(model: &str, text: &str) -> usize {
+    let bpe = match get_tokenizer(model) {
+        Some(Tokenizer::O200kBase) => tiktoken_rs::o200k_base_singleton(),
+        Some(Tokenizer::Cl100kBase) => tiktoken_rs::cl100k_base_singleton(),
+        _ => return estimate_tokens(text),
+    };
+    let bpe = bpe.lock();
+    bpe.encode_ordinary(text).len()
+}
+
+/// Context window of OpenAI `model` (4096 for unknown models).
+pub(crate) fn openai_context_window(model: &str) -> usize {
+    tiktoken_rs::model::get_context_size(model)
+}
+
+/// Context window of Anthropic `model`.
+pub(crate) fn claude_context_window(model: &str) -> usize {
+    if model.starts_with("claude-") {
+        200_000
+    } else {
+        DEFAULT_CONTEXT_WINDOW
+    }
+}
+
+/// Context window of Gemini `model`.
+pub(crate) fn gemini_context_window(model: &str) -> usize {
+    if model.starts_with("gemini-1.5-pro") {
+        2_097_152
+    } else if model.starts_with("gemini-1.5") || model.starts_with("gemini-2") {
+        1_048_576
+    } else if model.starts_with("gemini-") {
+        32_768
+    } else {
+        DEFAULT_CONTEXT_WINDOW
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_openai_counts_match_tiktoken_fixtures() {
+        // cl100k_base, from the OpenAI cookbook's "How to count tokens".
+        assert_eq!(openai_count_tokens("gpt-4", "tiktoken is great!"), 6);
+        assert_eq!(openai_count_tokens("gpt-3.5-turbo", "hello world"), 2);
+        // o200k_base.
+        assert_eq!(openai_count_tokens("gpt-4o", "hello world"), 2);
+        assert_eq!(openai_count_tokens("gpt-4o", ""), 0);
+    }
+
+    #[test]
+    fn test_unknown_openai_model_uses_estimate() {
+        let text = "Top members by logins: alice (42), bob (17).";
+        assert_eq!(openai_count_tokens("llama3.2", text), estimate_tokens(text));
+        assert!(estimate_tokens(text) >= openai_count_tokens("gpt-4o", text));
+    }
+
+    #[test]
+    fn test_context_windows() {
+        assert_eq!(openai_context_window("gpt-4o"), 128_000);
+        assert_eq!(openai_context_window("gpt-4"), 8192);
+        assert_eq!(claude_context_window("claude-sonnet-4-5-20250929"), 200_000);
+        assert_eq!(gemini_context_window("gemini-2.0-flash"), 1_048_576);
+        assert_eq!(gemini_context_window("unknown"), DEFAULT_CONTEXT_WINDOW);
+    }
+}
@@ -0,0 +1,108 @@
+//! Claude Messages API: `tool_use` / `tool_result` content blocks.
+
+use serde_json::{json, Value};
+
+use stupid_tool_runtime::conversation::{AssistantContent, ConversationMessage};
+use stupid_tool_runtime::provider::LlmError;
+use stupid_tool_runtime::stream::StopReason;
+use stupid_tool_runtime::tool::{ToolCall, ToolDefinition};
+
+use super::{join_text, NormalizedResponse, ToolCallCodec};
+
+/// Tool calling for the Anthropic Messages API.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClaudeToolCalls;
+
+impl ToolCallCodec for ClaudeToolCalls {
+    fn encode_tool(&self, tool: &ToolDefinition) -> Value {
+        json!({
+            "name": tool.name,
+            "description": tool.description,
+            "input_schema": tool.input_schema,
+        })
+    }
+
+    fn encode_message(&self, msg: &ConversationMessage) -> Value {
+        match msg {
+            ConversationMessage::User(text) => json!({
+                "role": "user",
+                "content": text,
+            }),
+            ConversationMessage::Assistant(content) => {
+                let mut blocks: Vec<Value> = Vec::new();
+                if let Some(text) = &content.text {
+                    blocks.push(json!({"type": "text", "text": text}));
+                }
+                for tc in &content.tool_calls {
+                    blocks.push(json!({
+                        "type": "tool_use",
+                        "id": tc.id,
+                        "name": tc.name,
+                        "input": tc.input,
+                    }));
+                }
+                json!({
+                    "role": "assistant",
+                    "content": blocks,
+                })
+            }
+            ConversationMessage::ToolResult(result) => json!({
+                "role": "user",
+                "content": [{
+                    "type": "tool_result",
+                    "tool_use_id": result.tool_call_id,
+                    "content": result.content,
+                    "is_error": result.is_error,
+                }],
+            }),
+        }
+    }
+
+    fn decode_response(&self, response: &Value) -> Result<NormalizedResponse, LlmError> {
+        let blocks = response["content"]
+            .as_array()
+            .ok_or_else(|| LlmError::InvalidResponse("missing 'content' array".into()))?;
+
+        let mut text = Vec::new();
+        let mut tool_calls = Vec::new();
+        for block in blocks {
+            match block["type"].as_str() {
+                Some("text") => text.push(block["text"].as_str().unwrap_or_default()),
+                Some("tool_use") => tool_calls.push(decode_tool_use(block)?),
+                // Thinking and other block types carry nothing the runtime uses.
+                _ => {}
+            }
+        }
+
+        Ok(NormalizedResponse {
+            content: AssistantContent { text: join_text(text), tool_calls },
+            stop_reason: stop_reason(response["stop_reason"].as_str()),
+        })
+    }
+}
+
+fn decode_tool_use(block: &Value) -> Result<ToolCall, LlmError> {
+    let field = |name: &str| {
+        block[name]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| LlmError::InvalidResponse(format!("tool_use block missing '{name}'")))
+    };
+    Ok(ToolCall {
+        id: field("id")?,
+        name: field("name")?,
+        input: match &block["input"] {
+            Value::Null => json!({}),
+            input => input.clone(),
+        },
+    })
+}
+
+fn stop_reason(reason: Option<&str>) -> StopReason {
+    match reason {
+        Some("tool_use") => StopReason::ToolUse,
+        Some("max_tokens") => StopReason::MaxTokens,
+        Some("stop_sequence") => StopReason::StopSequence,
+        _ => StopReason::EndTurn,
+    }
+}
@@ -0,0 +1,59 @@
+//! Provider-agnostic tool/function calling.
+//!
+//! Each provider encodes tool definitions, tool calls and tool results in its
+//! own wire format. A [`ToolCallCodec`] maps between that format and the
+//! tool runtime's [`ToolCall`](stupid_tool_runtime::ToolCall) /
+//! [`ToolResult`](stupid_tool_runtime::ToolResult) types, so the runtime
+//! speaks one protocol regardless of provider.
+
+mod claude;
+mod openai;
+
+pub use self::claude::ClaudeToolCalls;
+pub use self::openai::OpenAiToolCalls;
+
+use serde_json::Value;
+
+use stupid_tool_runtime::conversation::{AssistantContent, ConversationMessage};
+use stupid_tool_runtime::provider::LlmError;
+use stupid_tool_runtime::stream::StopReason;
+use stupid_tool_runtime::tool::ToolDefinition;
+
+/// An assistant turn decoded from a provider's (non-streaming) response.
+#[derive(Debug, Clone)]
+pub struct NormalizedResponse {
+    pub content: AssistantContent,
+    pub stop_reason: StopReason,
+}
+
+/// Maps one provider's native tool-calling format to and from the common
+/// tool runtime types.
+pub trait ToolCallCodec: Send + Sync {
+    /// Encode a tool definition as the provider expects it in `tools`.
+    fn encode_tool(&self, tool: &ToolDefinition) -> Value;
+
+    /// Encode a conversation message as a provider message object.
+    fn encode_message(&self, msg: &ConversationMessage) -> Value;
+
+    /// Decode a complete provider response into text, tool calls and the
+    /// reason the model stopped.
+    fn decode_response(&self, response: &Value) -> Result<NormalizedResponse, LlmError>;
+}
+
+/// The codec for a provider name as used in `LlmConfig::provider`, or `None`
+/// if the provider has no tool-calling support.
+pub fn codec_for_provider(provider: &str) -> Option<Box<dyn ToolCallCodec>> {
+    match provider {
+        "anthropic" | "claude" => Some(Box::new(ClaudeToolCalls)),
+        "openai" => Some(Box::new(OpenAiToolCalls)),
+        _ => None,
+    }
+}
+
+/// Join text parts, treating "no parts" as no text at all.
+fn join_text(parts: Vec<&str>) -> Option<String> {
+    (!parts.is_empty()).then(|| parts.concat())
+}
+
+#[cfg(test)]
+mod tests;
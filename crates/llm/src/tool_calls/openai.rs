@@ -0,0 +1,123 @@
+//! OpenAI Chat Completions: `tool_calls` on assistant messages and `tool`
+//! role messages for results.
+
+use serde_json::{json, Value};
+
+use stupid_tool_runtime::conversation::{AssistantContent, ConversationMessage};
+use stupid_tool_runtime::provider::LlmError;
+use stupid_tool_runtime::stream::StopReason;
+use stupid_tool_runtime::tool::{ToolCall, ToolDefinition};
+
+use super::{join_text, NormalizedResponse, ToolCallCodec};
+
+/// Tool calling for the OpenAI Chat Completions API (and compatible servers).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenAiToolCalls;
+
+impl ToolCallCodec for OpenAiToolCalls {
+    fn encode_tool(&self, tool: &ToolDefinition) -> Value {
+        json!({
+            "type": "function",
+            "function": {
+                "name": tool.name,
+                "description": tool.description,
+                "parameters": tool.input_schema,
+            },
+        })
+    }
+
+    fn encode_message(&self, msg: &ConversationMessage) -> Value {
+        match msg {
+            ConversationMessage::User(text) => json!({
+                "role": "user",
+                "content": text,
+            }),
+            ConversationMessage::Assistant(content) => {
+                let mut message = json!({
+                    "role": "assistant",
+                    "content": content.text,
+                });
+                if !content.tool_calls.is_empty() {
+                    message["tool_calls"] = content
+                        .tool_calls
+                        .iter()
+                        .map(|tc| {
+                            json!({
+                                "id": tc.id,
+                                "type": "function",
+                                "function": {
+                                    "name": tc.name,
+                                    // OpenAI expects the arguments as a JSON string.
+                                    "arguments": tc.input.to_string(),
+                                },
+                            })
+                        })
+                        .collect();
+                }
+                message
+            }
+            // `tool` messages have no error flag, so errors are marked in the text.
+            ConversationMessage::ToolResult(result) => json!({
+                "role": "tool",
+                "tool_call_id": result.tool_call_id,
+                "content": if result.is_error {
+                    format!("Error: {}", result.content)
+                } else {
+                    result.content.clone()
+                },
+            }),
+        }
+    }
+
+    fn decode_response(&self, response: &Value) -> Result<NormalizedResponse, LlmError> {
+        let choice = &response["choices"][0];
+        let message = choice
+            .get("message")
+            .ok_or_else(|| LlmError::InvalidResponse("missing 'choices[0].message'".into()))?;
+
+        let mut tool_calls = match message["tool_calls"].as_array() {
+            Some(calls) => calls.iter().map(decode_tool_call).collect::<Result<Vec<_>, _>>()?,
+            None => Vec::new(),
+        };
+        // Legacy function calling: a single `function_call` without an ID.
+        if let Some(call) = message.get("function_call").filter(|c| !c.is_null()) {
+            tool_calls.push(decode_function(call, "function_call".to_string())?);
+        }
+
+        let text = message["content"].as_str().filter(|t| !t.is_empty());
+        Ok(NormalizedResponse {
+            content: AssistantContent { text: join_text(text.into_iter().collect()), tool_calls },
+            stop_reason: stop_reason(choice["finish_reason"].as_str()),
+        })
+    }
+}
+
+fn decode_tool_call(call: &Value) -> Result<ToolCall, LlmError> {
+    let id = call["id"]
+        .as_str()
+        .ok_or_else(|| LlmError::InvalidResponse("tool call missing 'id'".into()))?;
+    decode_function(&call["function"], id.to_string())
+}
+
+/// Decode a `{name, arguments}` function object; `arguments` is a JSON
+/// string, empty when the function takes no arguments.
+fn decode_function(function: &Value, id: String) -> Result<ToolCall, LlmError> {
+    let name = function["name"]
+        .as_str()
+        .ok_or_else(|| LlmError::InvalidResponse(format!("tool call '{id}' missing function name")))?;
+    let input = match function["arguments"].as_str().map(str::trim) {
+        None | Some("") => json!({}),
+        Some(arguments) => serde_json::from_str(arguments).map_err(|e| {
+            LlmError::InvalidResponse(format!("tool call '{id}' has invalid arguments: {e}"))
+        })?,
+    };
+    Ok(ToolCall { id, name: name.to_string(), input })
+}
+
+fn stop_reason(reason: Option<&str>) -> StopReason {
+    match reason {
+        Some("tool_calls") | Some("function_call") => StopReason::ToolUse,
+        Some("length") => StopReason::MaxTokens,
+        _ => StopReason::EndTurn,
+    }
+}
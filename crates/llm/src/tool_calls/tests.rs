@@ -0,0 +1,192 @@
+//! Unit tests for tool-call normalization.
+
+use serde_json::{json, Value};
+
+use stupid_tool_runtime::conversation::{AssistantContent, ConversationMessage};
+use stupid_tool_runtime::provider::LlmError;
+use stupid_tool_runtime::stream::StopReason;
+use stupid_tool_runtime::tool::{ToolCall, ToolDefinition};
+use stupid_tool_runtime::ToolResult;
+
+use super::*;
+
+const OPENAI_FIXTURE: &str = include_str!("../../tests/fixtures/openai_tool_calls.json");
+const CLAUDE_FIXTURE: &str = include_str!("../../tests/fixtures/claude_tool_use.json");
+
+fn fixture(text: &str) -> Value {
+    serde_json::from_str(text).unwrap()
+}
+
+#[test]
+fn test_openai_tool_calls_response() {
+    let response = OpenAiToolCalls.decode_response(&fixture(OPENAI_FIXTURE)).unwrap();
+
+    assert_eq!(response.stop_reason, StopReason::ToolUse);
+    assert_eq!(response.content.text, None);
+    let calls = &response.content.tool_calls;
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0].id, "call_8fJ2kQm1");
+    assert_eq!(calls[0].name, "graph_query");
+    assert_eq!(calls[0].input, json!({"member_code": "M1024", "depth": 2}));
+    // Empty arguments decode to an empty object, as Claude sends them.
+    assert_eq!(calls[1].name, "list_segments");
+    assert_eq!(calls[1].input, json!({}));
+}
+
+#[test]
+fn test_claude_tool_use_response() {
+    let response = ClaudeToolCalls.decode_response(&fixture(CLAUDE_FIXTURE)).unwrap();
+
+    assert_eq!(response.stop_reason, StopReason::ToolUse);
+    assert_eq!(response.content.text.as_deref(), Some("I'll look up that member's connections."));
+    let calls = &response.content.tool_calls;
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0].id, "toolu_01A09q90qw90lq917835lq9");
+    assert_eq!(calls[0].name, "graph_query");
+    assert_eq!(calls[0].input, json!({"member_code": "M1024", "depth": 2}));
+}
+
+#[test]
+fn test_providers_normalize_to_same_call() {
+    let openai = OpenAiToolCalls.decode_response(&fixture(OPENAI_FIXTURE)).unwrap();
+    let claude = ClaudeToolCalls.decode_response(&fixture(CLAUDE_FIXTURE)).unwrap();
+
+    let (a, b) = (&openai.content.tool_calls[0], &claude.content.tool_calls[0]);
+    assert_eq!((&a.name, &a.input), (&b.name, &b.input));
+}
+
+#[test]
+fn test_openai_text_response() {
+    let response = json!({
+        "choices": [{
+            "message": {"role": "assistant", "content": "No tools needed."},
+            "finish_reason": "stop",
+        }],
+    });
+    let response = OpenAiToolCalls.decode_response(&response).unwrap();
+    assert_eq!(response.stop_reason, StopReason::EndTurn);
+    assert_eq!(response.content.text.as_deref(), Some("No tools needed."));
+    assert!(response.content.tool_calls.is_empty());
+}
+
+#[test]
+fn test_openai_legacy_function_call() {
+    let response = json!({
+        "choices": [{
+            "message": {
+                "role": "assistant",
+                "content": null,
+                "function_call": {"name": "list_segments", "arguments": "{}"},
+            },
+            "finish_reason": "function_call",
+        }],
+    });
+    let response = OpenAiToolCalls.decode_response(&response).unwrap();
+    assert_eq!(response.stop_reason, StopReason::ToolUse);
+    assert_eq!(response.content.tool_calls.len(), 1);
+    assert_eq!(response.content.tool_calls[0].name, "list_segments");
+}
+
+#[test]
+fn test_malformed_responses_are_errors() {
+    let bad_arguments = json!({
+        "choices": [{
+            "message": {
+                "tool_calls": [{"id": "call_1", "type": "function",
+                    "function": {"name": "graph_query", "arguments": "{not json"}}],
+            },
+            "finish_reason": "tool_calls",
+        }],
+    });
+    assert!(matches!(
+        OpenAiToolCalls.decode_response(&bad_arguments),
+        Err(LlmError::InvalidResponse(msg)) if msg.contains("call_1")
+    ));
+    assert!(OpenAiToolCalls.decode_response(&json!({"choices": []})).is_err());
+
+    let missing_id = json!({"content": [{"type": "tool_use", "name": "graph_query", "input": {}}]});
+    assert!(ClaudeToolCalls.decode_response(&missing_id).is_err());
+    assert!(ClaudeToolCalls.decode_response(&json!({"error": {}})).is_err());
+}
+
+#[test]
+fn test_openai_encodes_tools_and_messages() {
+    let tool = ToolDefinition {
+        name: "graph_query".to_string(),
+        description: "Query the entity graph".to_string(),
+        input_schema: json!({"type": "object", "properties": {"member_code": {"type": "string"}}}),
+    };
+    assert_eq!(
+        OpenAiToolCalls.encode_tool(&tool),
+        json!({
+            "type": "function",
+            "function": {
+                "name": "graph_query",
+                "description": "Query the entity graph",
+                "parameters": {"type": "object", "properties": {"member_code": {"type": "string"}}},
+            },
+        })
+    );
+
+    let assistant = ConversationMessage::Assistant(AssistantContent {
+        text: None,
+        tool_calls: vec![ToolCall {
+            id: "call_1".to_string(),
+            name: "graph_query".to_string(),
+            input: json!({"member_code": "M1024"}),
+        }],
+    });
+    let encoded = OpenAiToolCalls.encode_message(&assistant);
+    assert_eq!(encoded["role"], "assistant");
+    assert_eq!(encoded["content"], Value::Null);
+    assert_eq!(encoded["tool_calls"][0]["function"]["arguments"], r#"{"member_code":"M1024"}"#);
+
+    let result = ConversationMessage::ToolResult(ToolResult {
+        tool_call_id: "call_1".to_string(),
+        content: "member not found".to_string(),
+        is_error: true,
+    });
+    assert_eq!(
+        OpenAiToolCalls.encode_message(&result),
+        json!({"role": "tool", "tool_call_id": "call_1", "content": "Error: member not found"})
+    );
+}
+
+#[test]
+fn test_encoded_calls_round_trip() {
+    let assistant = AssistantContent {
+        text: Some("Checking.".to_string()),
+        tool_calls: vec![ToolCall {
+            id: "call_1".to_string(),
+            name: "graph_query".to_string(),
+            input: json!({"depth": 2}),
+        }],
+    };
+    let message = ConversationMessage::Assistant(assistant.clone());
+
+    let claude = json!({
+        "content": ClaudeToolCalls.encode_message(&message)["content"],
+        "stop_reason": "tool_use",
+    });
+    let openai = json!({
+        "choices": [{"message": OpenAiToolCalls.encode_message(&message), "finish_reason": "tool_calls"}],
+    });
+    for decoded in [
+        ClaudeToolCalls.decode_response(&claude).unwrap(),
+        OpenAiToolCalls.decode_response(&openai).unwrap(),
+    ] {
+        assert_eq!(decoded.content.text, assistant.text);
+        let call = &decoded.content.tool_calls[0];
+        assert_eq!(call.id, "call_1");
+        assert_eq!(call.name, "graph_query");
+        assert_eq!(call.input, json!({"depth": 2}));
+    }
+}
+
+#[test]
+fn test_codec_for_provider() {
+    assert!(codec_for_provider("claude").is_some());
+    assert!(codec_for_provider("anthropic").is_some());
+    assert!(codec_for_provider("openai").is_some());
+    assert!(codec_for_provider("ollama").is_none());
+}
@@ -226,6 +226,11 @@ impl Tool for McpTool {
         }
     }
 
+    /// The server doesn't say what its tools do, so assume the worst.
+    fn is_side_effecting(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Value, _context: &ToolContext) -> Result<ToolResult, ToolError> {
         let id = {
             let mut next = self.next_id.lock().await;
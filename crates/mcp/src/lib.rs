@@ -8,6 +8,7 @@
 //! - **types**: JSON-RPC 2.0 and MCP-specific protocol types
 //! - **transport**: Pluggable transport layer (stdio, channels)
 //! - **server**: MCP server wrapping a `ToolRegistry`
+//! - **validation**: Checks `tools/call` arguments against tool input schemas
 //! - **client**: MCP client connecting to server subprocesses
 //! - **error**: Unified error types
 //!
@@ -42,6 +43,7 @@ pub mod transport;
 pub mod server;
 pub mod client;
 pub mod error;
+mod validation;
 
 pub use types::*;
 pub use transport::{McpTransport, StdioTransport, ChannelTransport};
@@ -12,6 +12,7 @@ use stupid_tool_runtime::ToolRegistry;
 use crate::error::McpError;
 use crate::transport::McpTransport;
 use crate::types::*;
+use crate::validation::validate;
 
 /// MCP server that bridges a `ToolRegistry` to MCP clients.
 pub struct McpServer {
@@ -203,6 +204,14 @@ impl McpServer {
 
         tracing::debug!(tool = %call_params.name, "Handling tools/call");
 
+        if let Some(canonical) = self
+            .registry
+            .canonical_name(&call_params.name)
+            .filter(|canonical| *canonical != call_params.name)
+        {
+            tracing::warn!(alias = %call_params.name, canonical = %canonical, "Tool called by deprecated alias");
+        }
+
         let tool = match self.registry.get(&call_params.name) {
             Some(t) => t,
             None => {
@@ -211,11 +220,32 @@ impl McpServer {
             }
         };
 
+        // Clients may omit `arguments` for tools that take none.
+        let arguments = match call_params.arguments {
+            Value::Null => Value::Object(Default::default()),
+            args => args,
+        };
+
+        let violations = validate(&tool.definition().input_schema, &arguments);
+        if !violations.is_empty() {
+            tracing::debug!(tool = %call_params.name, ?violations, "Rejected tool arguments");
+            let err = McpError::InvalidParams(format!(
+                "arguments for tool '{}' do not match its input schema: {}",
+                call_params.name,
+                violations.join("; ")
+            ));
+            let mut resp = JsonRpcResponse::error(id, err.to_rpc_error().code, err.to_string());
+            if let Some(error) = resp.error.as_mut() {
+                error.data = Some(serde_json::json!({ "violations": violations }));
+            }
+            return resp;
+        }
+
         let ctx = ToolContext {
             working_directory: self.working_directory.clone(),
         };
 
-        let result = match tool.execute(call_params.arguments, &ctx).await {
+        let result = match tool.execute(arguments, &ctx).await {
             Ok(tool_result) => CallToolResult {
                 content: vec![ToolContent::Text {
                     text: tool_result.content,
@@ -327,6 +357,44 @@ mod tests {
         assert_eq!(err.code, error_codes::INVALID_PARAMS);
     }
 
+    #[tokio::test]
+    async fn test_handle_call_tool_rejects_wrong_argument_type() {
+        let mut server = McpServer::new(test_registry());
+        let req = JsonRpcRequest::new(
+            RpcId::Number(6),
+            "tools/call",
+            Some(serde_json::json!({
+                "name": "echo",
+                "arguments": {"message": 42}
+            })),
+        );
+
+        let resp = server.handle_request(&req).await;
+        assert!(resp.result.is_none());
+        let err = resp.error.unwrap();
+        assert_eq!(err.code, error_codes::INVALID_PARAMS);
+        assert!(err.message.contains("/message: expected string, got integer"));
+        assert_eq!(
+            err.data.unwrap(),
+            serde_json::json!({"violations": ["/message: expected string, got integer"]})
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handle_call_tool_rejects_missing_required_argument() {
+        let mut server = McpServer::new(test_registry());
+        let req = JsonRpcRequest::new(
+            RpcId::Number(7),
+            "tools/call",
+            Some(serde_json::json!({"name": "echo"})),
+        );
+
+        let resp = server.handle_request(&req).await;
+        let err = resp.error.unwrap();
+        assert_eq!(err.code, error_codes::INVALID_PARAMS);
+        assert!(err.message.contains("missing required property 'message'"));
+    }
+
     #[tokio::test]
     async fn test_handle_unknown_method() {
         let mut server = McpServer::new(test_registry());
@@ -0,0 +1,252 @@
+//! Validation of `tools/call` arguments against a tool's input schema.
+//!
+//! Covers the JSON Schema keywords tool definitions actually use: `type`,
+//! `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `items`, `minItems`/`maxItems`, `minLength`/`maxLength` and
+//! `minimum`/`maximum`. Unknown keywords are ignored, so a schema using
+//! anything richer is validated leniently rather than rejected.
+
+use serde_json::{Map, Value};
+
+/// Check `value` against `schema`, returning one message per violation.
+///
+/// Messages are prefixed with the JSON Pointer of the offending value
+/// (empty for the arguments object itself). An empty result means valid.
+pub(crate) fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut violations = Vec::new();
+    check(schema, value, "", &mut violations);
+    violations
+}
+
+fn check(schema: &Value, value: &Value, path: &str, out: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        // `true`/`false` schemas and other non-objects accept anything.
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        if !type_matches(expected, value) {
+            out.push(format!(
+                "{}: expected {}, got {}",
+                display(path),
+                type_names(expected),
+                type_of(value)
+            ));
+            // Further keywords would only repeat the mismatch.
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            out.push(format!(
+                "{}: {} is not one of {}",
+                display(path),
+                value,
+                Value::Array(allowed.clone())
+            ));
+        }
+    }
+    if let Some(constant) = schema.get("const") {
+        if constant != value {
+            out.push(format!("{}: expected {}, got {}", display(path), constant, value));
+        }
+    }
+
+    match value {
+        Value::Object(map) => check_object(schema, map, path, out),
+        Value::Array(items) => check_array(schema, items, path, out),
+        Value::String(s) => {
+            let len = s.chars().count() as u64;
+            if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+                if len < min {
+                    out.push(format!("{}: shorter than {} characters", display(path), min));
+                }
+            }
+            if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+                if len > max {
+                    out.push(format!("{}: longer than {} characters", display(path), max));
+                }
+            }
+        }
+        Value::Number(n) => {
+            let n = n.as_f64().unwrap_or_default();
+            if let Some(min) = schema.get("minimum").and_then(Value::as_f64) {
+                if n < min {
+                    out.push(format!("{}: {} is less than the minimum of {}", display(path), n, min));
+                }
+            }
+            if let Some(max) = schema.get("maximum").and_then(Value::as_f64) {
+                if n > max {
+                    out.push(format!("{}: {} is greater than the maximum of {}", display(path), n, max));
+                }
+            }
+        }
+        Value::Bool(_) | Value::Null => {}
+    }
+}
+
+fn check_object(schema: &Map<String, Value>, map: &Map<String, Value>, path: &str, out: &mut Vec<String>) {
+    if let Some(required) = schema.get("required").and_then(Value::as_array) {
+        for name in required.iter().filter_map(Value::as_str) {
+            if !map.contains_key(name) {
+                out.push(format!("{}: missing required property '{}'", display(path), name));
+            }
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let additional = schema.get("additionalProperties");
+    for (name, field) in map {
+        let field_path = format!("{}/{}", path, escape_pointer(name));
+        match properties.and_then(|p| p.get(name)) {
+            Some(field_schema) => check(field_schema, field, &field_path, out),
+            None => match additional {
+                Some(Value::Bool(false)) => {
+                    out.push(format!("{}: unknown property '{}'", display(path), name));
+                }
+                Some(extra) => check(extra, field, &field_path, out),
+                None => {}
+            },
+        }
+    }
+}
+
+fn check_array(schema: &Map<String, Value>, items: &[Value], path: &str, out: &mut Vec<String>) {
+    let len = items.len() as u64;
+    if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+        if len < min {
+            out.push(format!("{}: expected at least {} items, got {}", display(path), min, len));
+        }
+    }
+    if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+        if len > max {
+            out.push(format!("{}: expected at most {} items, got {}", display(path), max, len));
+        }
+    }
+    if let Some(item_schema) = schema.get("items") {
+        for (i, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{path}/{i}"), out);
+        }
+    }
+}
+
+fn type_matches(expected: &Value, value: &Value) -> bool {
+    match expected {
+        Value::String(name) => is_type(name, value),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .any(|name| is_type(name, value)),
+        _ => true,
+    }
+}
+
+fn is_type(name: &str, value: &Value) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        "number" => value.is_number(),
+        "integer" => value.as_f64().is_some_and(|n| n.fract() == 0.0),
+        // Unknown type names are not ours to reject.
+        _ => true,
+    }
+}
+
+fn type_names(expected: &Value) -> String {
+    match expected {
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .collect::<Vec<_>>()
+            .join(" or "),
+        other => other.as_str().unwrap_or("?").to_string(),
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn display(path: &str) -> &str {
+    if path.is_empty() {
+        "arguments"
+    } else {
+        path
+    }
+}
+
+/// Escape a property name for use as a JSON Pointer segment (RFC 6901).
+fn escape_pointer(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string", "minLength": 1},
+                "limit": {"type": "integer", "minimum": 1, "maximum": 100},
+                "mode": {"type": "string", "enum": ["fast", "full"]},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            },
+            "required": ["query"],
+            "additionalProperties": false
+        })
+    }
+
+    #[test]
+    fn valid_arguments_pass() {
+        let args = json!({"query": "logins", "limit": 10, "mode": "fast", "tags": ["a", "b"]});
+        assert!(validate(&schema(), &args).is_empty());
+        // Integral floats count as integers.
+        assert!(validate(&schema(), &json!({"query": "x", "limit": 5.0})).is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation_with_its_path() {
+        let args = json!({"limit": "ten", "mode": "slow", "tags": ["a", 3], "extra": true});
+        assert_eq!(
+            validate(&schema(), &args),
+            vec![
+                "arguments: missing required property 'query'",
+                "arguments: unknown property 'extra'",
+                "/limit: expected integer, got string",
+                "/mode: \"slow\" is not one of [\"fast\",\"full\"]",
+                "/tags/1: expected string, got integer",
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_ranges_and_root_type() {
+        let args = json!({"query": "", "limit": 500});
+        assert_eq!(
+            validate(&schema(), &args),
+            vec![
+                "/limit: 500 is greater than the maximum of 100",
+                "/query: shorter than 1 characters",
+            ]
+        );
+        assert_eq!(
+            validate(&schema(), &json!([1])),
+            vec!["arguments: expected object, got array"]
+        );
+    }
+}
@@ -104,12 +104,12 @@ impl Notifier for EmailNotifier {
         let email = message_builder
             .subject(&notification.subject)
             .body(notification.body.clone())
-            .map_err(|e| NotifyError::Smtp(e.to_string()))?;
+            .map_err(|e| NotifyError::Smtp(e.into()))?;
 
         self.transport
             .send(email)
             .await
-            .map_err(|e| NotifyError::Smtp(e.to_string()))?;
+            .map_err(|e| NotifyError::Smtp(e.into()))?;
 
         tracing::info!(
             channel = "email",
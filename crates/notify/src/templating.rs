@@ -1,7 +1,8 @@
 //! Minijinja template rendering for notification messages.
 //!
 //! Renders notification subject and body templates using minijinja,
-//! with access to rule metadata, detection results, and entity context.
+//! with access to rule metadata, detection results, enrichment results,
+//! and entity context.
 //!
 //! Templates are arbitrary strings (not pre-registered), so a fresh
 //! [`minijinja::Environment`] is created per render call.
@@ -17,6 +18,8 @@ pub struct TemplateContext {
     pub rule: RuleContext,
     /// Anomaly detection results.
     pub anomaly: AnomalyContext,
+    /// Enrichment query results; `none` when the rule has no enrichment step.
+    pub enrichment: Option<EnrichmentContext>,
     /// Event type: `"trigger"` or `"resolve"`.
     pub event: String,
     /// Current timestamp in ISO 8601 format.
@@ -55,6 +58,18 @@ pub struct AnomalyContext {
     pub features: HashMap<String, f64>,
 }
 
+/// Enrichment query results exposed to templates, e.g.
+/// `confirmed by {{ enrichment.hit_count }} matching events`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct EnrichmentContext {
+    /// Number of documents matching the enrichment query.
+    pub hit_count: u64,
+    /// First few matching documents, as returned by the search backend.
+    pub sample_hits: Vec<serde_json::Value>,
+    /// Query time in milliseconds.
+    pub query_time_ms: u64,
+}
+
 /// Renders notification templates using minijinja.
 ///
 /// A fresh [`minijinja::Environment`] is created per render call since
@@ -96,8 +111,7 @@ impl TemplateRenderer {
     /// rendering fails (e.g., type errors, undefined variables in strict mode).
     pub fn render(&self, template_str: &str, ctx: &TemplateContext) -> Result<String, NotifyError> {
         let env = Self::build_env();
-        env.render_str(template_str, ctx)
-            .map_err(|e| NotifyError::Template(e.to_string()))
+        Ok(env.render_str(template_str, ctx)?)
     }
 
     /// Validate that a template string parses without errors.
@@ -110,8 +124,7 @@ impl TemplateRenderer {
     pub fn validate(&self, template_str: &str) -> Result<(), NotifyError> {
         let env = Self::build_env();
         // Parse the template to check for syntax errors without evaluating.
-        env.template_from_str(template_str)
-            .map_err(|e| NotifyError::Template(e.to_string()))?;
+        env.template_from_str(template_str)?;
         Ok(())
     }
 }
@@ -181,6 +194,7 @@ mod tests {
                 ],
                 features,
             },
+            enrichment: None,
             event: "trigger".to_string(),
             now: "2026-02-16T12:00:00Z".to_string(),
         }
@@ -273,8 +287,8 @@ mod tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            NotifyError::Template(msg) => {
-                assert!(!msg.is_empty(), "Error message should not be empty");
+            NotifyError::Template(e) => {
+                assert!(!e.to_string().is_empty(), "Error message should not be empty");
             }
             other => panic!("Expected Template error, got: {:?}", other),
         }
@@ -335,4 +349,28 @@ mod tests {
         let result = renderer.render(template, &ctx).unwrap();
         assert_eq!(result, "Tags: security, login");
     }
+
+    #[test]
+    fn render_enrichment_fields() {
+        let renderer = TemplateRenderer::new();
+        let mut ctx = sample_context();
+
+        let template = "{% if enrichment %}Confirmed by {{ enrichment.hit_count }} matching events:\
+            {% for hit in enrichment.sample_hits %} {{ hit.gameName }}{% endfor %}\
+            {% else %}Not enriched{% endif %}";
+        assert_eq!(renderer.render(template, &ctx).unwrap(), "Not enriched");
+
+        ctx.enrichment = Some(EnrichmentContext {
+            hit_count: 25,
+            sample_hits: vec![
+                serde_json::json!({"gameName": "slots"}),
+                serde_json::json!({"gameName": "poker"}),
+            ],
+            query_time_ms: 12,
+        });
+        assert_eq!(
+            renderer.render(template, &ctx).unwrap(),
+            "Confirmed by 25 matching events: slots poker"
+        );
+    }
 }
@@ -9,10 +9,10 @@ pub enum NotifyError {
     Http(#[from] reqwest::Error),
 
     #[error("SMTP delivery failed: {0}")]
-    Smtp(String),
+    Smtp(#[source] Box<dyn std::error::Error + Send + Sync>),
 
     #[error("Template rendering failed: {0}")]
-    Template(String),
+    Template(#[from] minijinja::Error),
 
     #[error("Configuration error: {0}")]
     Config(String),
@@ -0,0 +1,397 @@
+//! Raw message archive for reprocessing.
+//!
+//! With archiving on, every received message is appended as one JSON line to
+//! `{dir}/{YYYY-MM-DD}.jsonl` (UTC receive date) alongside normal processing.
+//! After a parsing bug has dropped messages, [`replay`] re-feeds an archive
+//! file through the parser once the fix is deployed. Files older than the
+//! retention window are deleted when the archive rolls over to a new day.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use tracing::{info, warn};
+
+use stupid_core::Document;
+
+use crate::consumer::{QueueConsumer, QueueHealth, QueueMessage};
+use crate::dlq::DeadLetterReason;
+use crate::error::QueueError;
+use crate::parser::parse_message;
+
+/// Days of archive files kept by default.
+pub const DEFAULT_ARCHIVE_RETENTION_DAYS: u32 = 7;
+
+const ARCHIVE_EXTENSION: &str = "jsonl";
+
+/// Dated JSON-lines files of raw received messages.
+pub struct MessageArchive {
+    dir: PathBuf,
+    /// Files dated more than this many days before today are deleted; `0`
+    /// keeps everything.
+    retention_days: u32,
+    /// Day of the last retention sweep, so it runs once per day.
+    pruned_on: Mutex<Option<NaiveDate>>,
+}
+
+impl MessageArchive {
+    pub fn new(dir: impl Into<PathBuf>, retention_days: u32) -> Self {
+        Self {
+            dir: dir.into(),
+            retention_days,
+            pruned_on: Mutex::new(None),
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Archive file holding messages received on `date`.
+    pub fn path_for(&self, date: NaiveDate) -> PathBuf {
+        self.dir.join(format!("{}.{ARCHIVE_EXTENSION}", date.format("%Y-%m-%d")))
+    }
+
+    /// Append `messages` to today's archive file.
+    pub fn append(&self, messages: &[QueueMessage]) -> Result<(), QueueError> {
+        self.append_at(messages, Utc::now())
+    }
+
+    /// Append `messages` to the archive file for `now`'s date, first pruning
+    /// expired files if this is the first append of the day.
+    pub fn append_at(&self, messages: &[QueueMessage], now: DateTime<Utc>) -> Result<(), QueueError> {
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let today = now.date_naive();
+        fs::create_dir_all(&self.dir).map_err(|e| archive_error(&self.dir, e))?;
+        {
+            let mut pruned_on = self.pruned_on.lock().unwrap();
+            if *pruned_on != Some(today) {
+                *pruned_on = Some(today);
+                self.prune(today)?;
+            }
+        }
+
+        let mut lines = Vec::new();
+        for msg in messages {
+            serde_json::to_writer(&mut lines, msg)
+                .map_err(|e| QueueError::Archive(format!("failed to encode message {}: {e}", msg.id)))?;
+            lines.push(b'\n');
+        }
+        let path = self.path_for(today);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| archive_error(&path, e))?;
+        // One write per batch, so a crash leaves at most one partial line.
+        file.write_all(&lines).map_err(|e| archive_error(&path, e))
+    }
+
+    /// Delete archive files dated more than the retention window before
+    /// `today`. Returns how many were deleted.
+    pub fn prune(&self, today: NaiveDate) -> Result<usize, QueueError> {
+        if self.retention_days == 0 {
+            return Ok(0);
+        }
+        let cutoff = today - Duration::days(i64::from(self.retention_days));
+        let mut deleted = 0;
+        for (date, path) in self.dated_files()? {
+            if date < cutoff {
+                fs::remove_file(&path).map_err(|e| archive_error(&path, e))?;
+                deleted += 1;
+            }
+        }
+        if deleted > 0 {
+            info!(dir = %self.dir.display(), deleted, "Pruned expired queue archive files");
+        }
+        Ok(deleted)
+    }
+
+    /// Archive files, oldest first.
+    pub fn files(&self) -> Result<Vec<PathBuf>, QueueError> {
+        Ok(self.dated_files()?.into_iter().map(|(_, path)| path).collect())
+    }
+
+    fn dated_files(&self) -> Result<Vec<(NaiveDate, PathBuf)>, QueueError> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(archive_error(&self.dir, e)),
+        };
+        let mut files: Vec<(NaiveDate, PathBuf)> = entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == ARCHIVE_EXTENSION))
+            .filter_map(|path| {
+                let stem = path.file_stem()?.to_str()?;
+                let date = NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()?;
+                Some((date, path))
+            })
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+}
+
+fn archive_error(path: &Path, e: std::io::Error) -> QueueError {
+    QueueError::Archive(format!("{}: {e}", path.display()))
+}
+
+/// Consumer wrapper that archives every polled message before handing it on.
+///
+/// Archive failures are logged and never fail the poll, so archiving can't
+/// stall ingestion.
+pub struct ArchivingConsumer {
+    inner: Box<dyn QueueConsumer>,
+    archive: MessageArchive,
+}
+
+impl ArchivingConsumer {
+    pub fn new(inner: Box<dyn QueueConsumer>, archive: MessageArchive) -> Self {
+        Self { inner, archive }
+    }
+
+    pub fn archive(&self) -> &MessageArchive {
+        &self.archive
+    }
+}
+
+#[async_trait]
+impl QueueConsumer for ArchivingConsumer {
+    async fn poll_batch(&self, max_messages: u32) -> Result<Vec<QueueMessage>, QueueError> {
+        let messages = self.inner.poll_batch(max_messages).await?;
+        if let Err(e) = self.archive.append(&messages) {
+            warn!(count = messages.len(), error = %e, "Failed to archive queue messages");
+        }
+        Ok(messages)
+    }
+
+    async fn ack(&self, receipt_handle: &str) -> Result<(), QueueError> {
+        self.inner.ack(receipt_handle).await
+    }
+
+    async fn nack(&self, receipt_handle: &str) -> Result<(), QueueError> {
+        self.inner.nack(receipt_handle).await
+    }
+
+    async fn health_check(&self) -> Result<QueueHealth, QueueError> {
+        self.inner.health_check().await
+    }
+
+    async fn dlq_depth(&self) -> Result<Option<u64>, QueueError> {
+        self.inner.dlq_depth().await
+    }
+
+    async fn send_to_dlq(
+        &self,
+        msg: &QueueMessage,
+        reason: &DeadLetterReason,
+    ) -> Result<bool, QueueError> {
+        self.inner.send_to_dlq(msg, reason).await
+    }
+}
+
+/// Counts from replaying an archive file.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ReplayOutcome {
+    /// Messages parsed and accepted by the handler.
+    pub processed: usize,
+    /// Messages whose body still fails to parse.
+    pub parse_failures: usize,
+    /// Messages the handler rejected.
+    pub handler_failures: usize,
+    /// Lines that are not an archived message (e.g. truncated by a crash).
+    pub unreadable: usize,
+}
+
+/// Re-feed every message in `archive_path` through [`parse_message`] and
+/// `handler`, in archive order.
+///
+/// Failures are counted and skipped rather than aborting the replay; only
+/// failing to read the file is an error.
+pub fn replay<F>(archive_path: &Path, mut handler: F) -> Result<ReplayOutcome, QueueError>
+where
+    F: FnMut(&QueueMessage, Document) -> Result<(), QueueError>,
+{
+    let file = File::open(archive_path).map_err(|e| archive_error(archive_path, e))?;
+    let mut outcome = ReplayOutcome::default();
+
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| archive_error(archive_path, e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let msg: QueueMessage = match serde_json::from_str(&line) {
+            Ok(msg) => msg,
+            Err(e) => {
+                warn!(file = %archive_path.display(), line = index + 1, error = %e, "Skipping unreadable archive line");
+                outcome.unreadable += 1;
+                continue;
+            }
+        };
+        let doc = match parse_message(&msg) {
+            Ok(doc) => doc,
+            Err(e) => {
+                warn!(message_id = %msg.id, error = %e, "Archived message still fails to parse");
+                outcome.parse_failures += 1;
+                continue;
+            }
+        };
+        match handler(&msg, doc) {
+            Ok(()) => outcome.processed += 1,
+            Err(e) => {
+                warn!(message_id = %msg.id, error = %e, "Replay handler failed");
+                outcome.handler_failures += 1;
+            }
+        }
+    }
+
+    info!(
+        file = %archive_path.display(),
+        processed = outcome.processed,
+        parse_failures = outcome.parse_failures,
+        handler_failures = outcome.handler_failures,
+        unreadable = outcome.unreadable,
+        "Archive replay finished"
+    );
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+    use crate::metrics::HealthStatus;
+
+    /// Consumer that hands out a fixed batch once.
+    struct FixedConsumer {
+        batch: Mutex<Vec<QueueMessage>>,
+    }
+
+    #[async_trait]
+    impl QueueConsumer for FixedConsumer {
+        async fn poll_batch(&self, _max: u32) -> Result<Vec<QueueMessage>, QueueError> {
+            Ok(std::mem::take(&mut *self.batch.lock().unwrap()))
+        }
+
+        async fn ack(&self, _receipt_handle: &str) -> Result<(), QueueError> {
+            Ok(())
+        }
+
+        async fn nack(&self, _receipt_handle: &str) -> Result<(), QueueError> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<QueueHealth, QueueError> {
+            Ok(QueueHealth {
+                connected: true,
+                approximate_message_count: None,
+                provider: "fixed".to_string(),
+                lag_ms: None,
+                status: HealthStatus::Healthy,
+            })
+        }
+    }
+
+    fn make_msg(id: &str, body: &str) -> QueueMessage {
+        QueueMessage {
+            id: id.to_string(),
+            body: body.to_string(),
+            receipt_handle: format!("handle-{id}"),
+            timestamp: Utc::now(),
+            attempt_count: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_archives_raw_payloads() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let messages = vec![
+            make_msg("good", r#"{"event_type":"Login","memberCode":"M001"}"#),
+            make_msg("bad", "not json"),
+        ];
+        let consumer = ArchivingConsumer::new(
+            Box::new(FixedConsumer { batch: Mutex::new(messages) }),
+            MessageArchive::new(dir, DEFAULT_ARCHIVE_RETENTION_DAYS),
+        );
+
+        let polled = consumer.poll_batch(10).await.unwrap();
+        assert_eq!(polled.len(), 2, "archiving must not alter the batch");
+        assert!(consumer.poll_batch(10).await.unwrap().is_empty());
+
+        let files = consumer.archive().files().unwrap();
+        assert_eq!(files, vec![consumer.archive().path_for(Utc::now().date_naive())]);
+        let archived: Vec<QueueMessage> = fs::read_to_string(&files[0])
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(archived.len(), 2);
+        assert_eq!(archived[1].id, "bad");
+        assert_eq!(archived[1].body, "not json");
+    }
+
+    #[test]
+    fn test_replay_archive_file() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let archive = MessageArchive::new(dir, 0);
+        archive
+            .append(&[
+                make_msg("m1", r#"{"event_type":"Login","memberCode":"M001"}"#),
+                make_msg("m2", "not json"),
+                make_msg("m3", r#"{"event_type":"GameOpened","game":"slots"}"#),
+            ])
+            .unwrap();
+        let path = archive.files().unwrap().remove(0);
+        // A line cut short by a crash mid-write.
+        fs::OpenOptions::new().append(true).open(&path).unwrap().write_all(b"{\"id\":\"m4\",\"bo").unwrap();
+
+        let mut replayed = Vec::new();
+        let outcome = replay(&path, |msg, doc| {
+            if doc.event_type == "GameOpened" {
+                return Err(QueueError::Provider("downstream unavailable".into()));
+            }
+            replayed.push((msg.id.clone(), doc.event_type));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(replayed, vec![("m1".to_string(), "Login".to_string())]);
+        assert_eq!(
+            outcome,
+            ReplayOutcome { processed: 1, parse_failures: 1, handler_failures: 1, unreadable: 1 }
+        );
+        assert!(replay(&dir.join("missing.jsonl"), |_, _| Ok(())).is_err());
+    }
+
+    #[test]
+    fn test_retention_prunes_old_files_on_rollover() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = tmp.path();
+        let archive = MessageArchive::new(dir, 2);
+        let day = |d: u32| Utc.with_ymd_and_hms(2026, 3, d, 12, 0, 0).unwrap();
+        let batch = [make_msg("m", "{}")];
+
+        for d in 1..=4 {
+            archive.append_at(&batch, day(d)).unwrap();
+        }
+        // Not an archive file; never touched.
+        fs::write(dir.join("notes.txt"), "keep").unwrap();
+
+        let names: Vec<String> = archive
+            .files()
+            .unwrap()
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(names, ["2026-03-02.jsonl", "2026-03-03.jsonl", "2026-03-04.jsonl"]);
+        assert!(dir.join("notes.txt").exists());
+    }
+}
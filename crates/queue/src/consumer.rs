@@ -6,7 +6,9 @@ use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+use crate::dlq::DeadLetterReason;
 use crate::error::QueueError;
+use crate::metrics::{HealthStatus, HealthThresholds, QueueMetrics, QueueMetricsSnapshot};
 
 /// A raw message received from a queue.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,14 +34,58 @@ pub struct QueueHealth {
     pub approximate_message_count: Option<u64>,
     /// Queue provider name (e.g., "sqs", "redis").
     pub provider: String,
+    /// Age of the oldest unprocessed message in milliseconds, if known.
+    pub lag_ms: Option<u64>,
+    /// Derived health status.
+    pub status: HealthStatus,
+}
+
+impl QueueHealth {
+    /// Derive health from consumer-side metrics.
+    pub fn from_metrics(
+        provider: impl Into<String>,
+        snapshot: &QueueMetricsSnapshot,
+        thresholds: &HealthThresholds,
+    ) -> Self {
+        Self {
+            connected: snapshot.connected,
+            approximate_message_count: Some(snapshot.in_flight),
+            provider: provider.into(),
+            lag_ms: Some(snapshot.oldest_message_age_ms),
+            status: snapshot.health(thresholds),
+        }
+    }
+
+    /// Health of a consumer whose queue just answered a provider call.
+    ///
+    /// Lag and error rate come from the consumer's `metrics`; the consumer
+    /// counts as connected since the call succeeded. `approximate_message_count`
+    /// is the provider's own backlog figure, when it reports one.
+    pub fn reachable(
+        provider: impl Into<String>,
+        metrics: &QueueMetrics,
+        approximate_message_count: Option<u64>,
+    ) -> Self {
+        let mut snapshot = metrics.snapshot();
+        snapshot.connected = true;
+        Self {
+            approximate_message_count,
+            ..Self::from_metrics(provider, &snapshot, &HealthThresholds::default())
+        }
+    }
+
+    /// Whether the derived status is [`HealthStatus::Healthy`].
+    pub fn is_healthy(&self) -> bool {
+        self.status == HealthStatus::Healthy
+    }
 }
 
 impl fmt::Display for QueueHealth {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "QueueHealth {{ connected: {}, messages: {:?}, provider: {} }}",
-            self.connected, self.approximate_message_count, self.provider
+            "QueueHealth {{ connected: {}, messages: {:?}, provider: {}, status: {:?} }}",
+            self.connected, self.approximate_message_count, self.provider, self.status
         )
     }
 }
@@ -62,6 +108,8 @@ pub trait QueueConsumer: Send + Sync {
     /// Negative-acknowledge — returns the message to the queue for retry.
     ///
     /// For SQS: sets visibility timeout to 0 so message is immediately available.
+    /// For Kafka: logs the record and commits past it, since redelivering it
+    /// would replay the rest of its partition.
     async fn nack(&self, receipt_handle: &str) -> Result<(), QueueError>;
 
     /// Check queue connectivity and return health status.
@@ -71,6 +119,18 @@ pub trait QueueConsumer: Send + Sync {
     async fn dlq_depth(&self) -> Result<Option<u64>, QueueError> {
         Ok(None) // Default: DLQ not supported
     }
+
+    /// Copy a message to the dead-letter queue with the failure reason attached.
+    ///
+    /// Returns `Ok(false)` when no DLQ is configured. Does not remove the
+    /// message from the source queue — see [`crate::dlq::dead_letter`].
+    async fn send_to_dlq(
+        &self,
+        _msg: &QueueMessage,
+        _reason: &DeadLetterReason,
+    ) -> Result<bool, QueueError> {
+        Ok(false) // Default: DLQ not supported
+    }
 }
 
 #[cfg(test)]
@@ -102,10 +162,49 @@ mod tests {
             connected: true,
             approximate_message_count: Some(42),
             provider: "sqs".to_string(),
+            lag_ms: None,
+            status: HealthStatus::Healthy,
         };
         let display = format!("{}", health);
         assert!(display.contains("connected: true"));
         assert!(display.contains("42"));
+        assert!(display.contains("Healthy"));
+    }
+
+    #[test]
+    fn test_queue_health_from_metrics() {
+        let metrics = crate::metrics::QueueMetrics::new();
+        let health = QueueHealth::from_metrics(
+            "sqs",
+            &metrics.snapshot_at(0),
+            &HealthThresholds::default(),
+        );
+        assert!(!health.connected);
+        assert_eq!(health.status, HealthStatus::Disconnected);
+        assert!(!health.is_healthy());
+    }
+
+    #[test]
+    fn test_reachable_health_reports_consumer_lag() {
+        let metrics = crate::metrics::QueueMetrics::new();
+        let sent = Utc::now() - chrono::Duration::minutes(5);
+        metrics.record_poll(&[QueueMessage {
+            id: "old".to_string(),
+            body: "{}".to_string(),
+            receipt_handle: "h".to_string(),
+            timestamp: sent,
+            attempt_count: 1,
+        }]);
+
+        let health = QueueHealth::reachable("sqs", &metrics, Some(7));
+        assert!(health.connected);
+        assert_eq!(health.approximate_message_count, Some(7));
+        assert!(health.lag_ms.unwrap() >= 5 * 60 * 1000);
+        assert_eq!(health.status, HealthStatus::Lagging);
+
+        let idle = QueueHealth::reachable("sqs", &crate::metrics::QueueMetrics::new(), None);
+        assert_eq!(idle.lag_ms, Some(0));
+        assert!(idle.is_healthy());
     }
 
     #[test]
@@ -0,0 +1,356 @@
+//! Dead-letter routing for messages that cannot be processed.
+//!
+//! Unparseable payloads and messages that have been redelivered too many
+//! times are copied to the configured DLQ (original body preserved, failure
+//! reason attached as a message attribute) and then removed from the source
+//! queue. When no DLQ is configured, a message is nacked for another attempt
+//! only while `max_receive_count` bounds its retries; otherwise it is logged
+//! and removed, so a poison message can't block the queue forever.
+
+use std::fmt;
+
+use tracing::{error, info, warn};
+
+use crate::consumer::{QueueConsumer, QueueMessage};
+use crate::error::QueueError;
+
+/// Default number of receives before a message is dead-lettered.
+pub const DEFAULT_MAX_RECEIVE_COUNT: u32 = 5;
+
+/// Message attribute carrying the human-readable failure reason.
+pub const ATTR_ERROR: &str = "dlq_error";
+/// Message attribute carrying the failure kind (`parse` / `max_receive_count`).
+pub const ATTR_REASON: &str = "dlq_reason";
+/// Message attribute carrying the original message ID from the source queue.
+pub const ATTR_SOURCE_MESSAGE_ID: &str = "dlq_source_message_id";
+
+/// Why a message was routed to the dead-letter queue.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeadLetterReason {
+    /// The body could not be parsed into a document.
+    Parse(String),
+    /// The message was received more times than the configured limit.
+    MaxReceiveExceeded { attempts: u32, limit: u32 },
+}
+
+impl DeadLetterReason {
+    /// Short machine-readable kind, used as the `dlq_reason` attribute.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Parse(_) => "parse",
+            Self::MaxReceiveExceeded { .. } => "max_receive_count",
+        }
+    }
+}
+
+impl fmt::Display for DeadLetterReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::MaxReceiveExceeded { attempts, limit } => {
+                write!(f, "received {attempts} times (limit {limit})")
+            }
+        }
+    }
+}
+
+/// Counts from routing a set of failed messages.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct DeadLetterOutcome {
+    /// Messages copied to the DLQ and removed from the source queue.
+    pub dead_lettered: usize,
+    /// Messages returned to the source queue (no DLQ configured).
+    pub nacked: usize,
+    /// Messages logged and removed without a DLQ copy (no DLQ configured and
+    /// no retries left).
+    pub dropped: usize,
+    /// Messages that could not be routed due to transport errors.
+    pub failed: usize,
+}
+
+/// Whether a message has been received more often than `max_receive_count`.
+///
+/// A limit of `0` disables the check.
+pub fn exceeds_max_receive(msg: &QueueMessage, max_receive_count: u32) -> bool {
+    max_receive_count > 0 && msg.attempt_count > max_receive_count
+}
+
+/// What [`dead_letter`] did with a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Routed {
+    /// Copied to the DLQ and removed from the source queue.
+    DeadLettered,
+    /// Returned to the source queue for another attempt.
+    Nacked,
+    /// Logged and removed from the source queue.
+    Dropped,
+}
+
+/// Route a single message to the DLQ and remove it from the source queue.
+///
+/// Without a DLQ, the message is nacked back onto the source queue when
+/// `retry` is set, and otherwise logged and acked so it stops being
+/// redelivered.
+pub async fn dead_letter<C: QueueConsumer + ?Sized>(
+    consumer: &C,
+    msg: &QueueMessage,
+    reason: &DeadLetterReason,
+    retry: bool,
+) -> Result<Routed, QueueError> {
+    if consumer.send_to_dlq(msg, reason).await? {
+        consumer.ack(&msg.receipt_handle).await?;
+        info!(message_id = %msg.id, reason = reason.kind(), "Message moved to DLQ");
+        Ok(Routed::DeadLettered)
+    } else if retry {
+        consumer.nack(&msg.receipt_handle).await?;
+        Ok(Routed::Nacked)
+    } else {
+        error!(
+            message_id = %msg.id,
+            reason = reason.kind(),
+            error = %reason,
+            body = %msg.body,
+            "No DLQ configured — dropping failed message"
+        );
+        consumer.ack(&msg.receipt_handle).await?;
+        Ok(Routed::Dropped)
+    }
+}
+
+/// Dead-letter every message whose ID appears in `errors`.
+///
+/// `errors` is the failure list returned by [`crate::parse_batch`]. Without a
+/// DLQ, failures are retried only when `max_receive_count` bounds the retries.
+pub async fn route_parse_failures<C: QueueConsumer + ?Sized>(
+    consumer: &C,
+    messages: &[QueueMessage],
+    errors: &[(String, QueueError)],
+    max_receive_count: u32,
+) -> DeadLetterOutcome {
+    let mut outcome = DeadLetterOutcome::default();
+
+    for (msg_id, err) in errors {
+        let Some(msg) = messages.iter().find(|m| m.id == *msg_id) else {
+            continue;
+        };
+        let reason = DeadLetterReason::Parse(err.to_string());
+        let retry = max_receive_count > 0;
+        record(&mut outcome, msg, dead_letter(consumer, msg, &reason, retry).await);
+    }
+
+    outcome
+}
+
+/// Split off messages that exceeded `max_receive_count` and dead-letter them.
+///
+/// Returns the messages that are still eligible for processing.
+pub async fn route_exhausted<C: QueueConsumer + ?Sized>(
+    consumer: &C,
+    messages: Vec<QueueMessage>,
+    max_receive_count: u32,
+) -> (Vec<QueueMessage>, DeadLetterOutcome) {
+    let mut outcome = DeadLetterOutcome::default();
+    let mut remaining = Vec::with_capacity(messages.len());
+
+    for msg in messages {
+        if !exceeds_max_receive(&msg, max_receive_count) {
+            remaining.push(msg);
+            continue;
+        }
+        let reason = DeadLetterReason::MaxReceiveExceeded {
+            attempts: msg.attempt_count,
+            limit: max_receive_count,
+        };
+        record(&mut outcome, &msg, dead_letter(consumer, &msg, &reason, false).await);
+    }
+
+    (remaining, outcome)
+}
+
+fn record(outcome: &mut DeadLetterOutcome, msg: &QueueMessage, result: Result<Routed, QueueError>) {
+    match result {
+        Ok(Routed::DeadLettered) => outcome.dead_lettered += 1,
+        Ok(Routed::Nacked) => outcome.nacked += 1,
+        Ok(Routed::Dropped) => outcome.dropped += 1,
+        Err(e) => {
+            warn!(message_id = %msg.id, error = %e, "Failed to route message to DLQ");
+            outcome.failed += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use async_trait::async_trait;
+    use chrono::Utc;
+
+    use super::*;
+    use crate::consumer::QueueHealth;
+    use crate::metrics::HealthStatus;
+    use crate::parser::parse_batch;
+
+    /// In-memory consumer that records DLQ sends, acks, and nacks.
+    #[derive(Default)]
+    struct MockConsumer {
+        has_dlq: bool,
+        source: Mutex<Vec<QueueMessage>>,
+        dlq: Mutex<Vec<(QueueMessage, DeadLetterReason)>>,
+        nacked: Mutex<Vec<String>>,
+    }
+
+    impl MockConsumer {
+        fn with_messages(has_dlq: bool, messages: &[QueueMessage]) -> Self {
+            Self {
+                has_dlq,
+                source: Mutex::new(messages.to_vec()),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[async_trait]
+    impl QueueConsumer for MockConsumer {
+        async fn poll_batch(&self, _max: u32) -> Result<Vec<QueueMessage>, QueueError> {
+            Ok(self.source.lock().unwrap().clone())
+        }
+
+        async fn ack(&self, receipt_handle: &str) -> Result<(), QueueError> {
+            self.source
+                .lock()
+                .unwrap()
+                .retain(|m| m.receipt_handle != receipt_handle);
+            Ok(())
+        }
+
+        async fn nack(&self, receipt_handle: &str) -> Result<(), QueueError> {
+            self.nacked.lock().unwrap().push(receipt_handle.to_string());
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<QueueHealth, QueueError> {
+            Ok(QueueHealth {
+                connected: true,
+                approximate_message_count: None,
+                provider: "mock".to_string(),
+                lag_ms: None,
+                status: HealthStatus::Healthy,
+            })
+        }
+
+        async fn send_to_dlq(
+            &self,
+            msg: &QueueMessage,
+            reason: &DeadLetterReason,
+        ) -> Result<bool, QueueError> {
+            if !self.has_dlq {
+                return Ok(false);
+            }
+            self.dlq.lock().unwrap().push((msg.clone(), reason.clone()));
+            Ok(true)
+        }
+    }
+
+    fn make_msg(id: &str, body: &str, attempt_count: u32) -> QueueMessage {
+        QueueMessage {
+            id: id.to_string(),
+            body: body.to_string(),
+            receipt_handle: format!("handle-{id}"),
+            timestamp: Utc::now(),
+            attempt_count,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unparseable_message_lands_in_dlq_and_leaves_source() {
+        let messages = vec![
+            make_msg("good", r#"{"event_type":"Login"}"#, 1),
+            make_msg("bad", "not json", 1),
+        ];
+        let consumer = MockConsumer::with_messages(true, &messages);
+
+        let (_docs, errors) = parse_batch(&messages);
+        let outcome = route_parse_failures(&consumer, &messages, &errors, 5).await;
+
+        assert_eq!(outcome.dead_lettered, 1);
+        let dlq = consumer.dlq.lock().unwrap();
+        assert_eq!(dlq.len(), 1);
+        assert_eq!(dlq[0].0.id, "bad");
+        assert_eq!(dlq[0].0.body, "not json");
+        assert_eq!(dlq[0].1.kind(), "parse");
+        assert!(dlq[0].1.to_string().contains("Invalid JSON"));
+
+        let source = consumer.source.lock().unwrap();
+        assert_eq!(source.len(), 1);
+        assert_eq!(source[0].id, "good");
+    }
+
+    #[tokio::test]
+    async fn test_without_dlq_message_is_nacked_not_dropped() {
+        let messages = vec![make_msg("bad", "not json", 1)];
+        let consumer = MockConsumer::with_messages(false, &messages);
+
+        let (_docs, errors) = parse_batch(&messages);
+        let outcome = route_parse_failures(&consumer, &messages, &errors, 5).await;
+
+        assert_eq!(outcome.nacked, 1);
+        assert_eq!(outcome.dead_lettered, 0);
+        assert_eq!(consumer.source.lock().unwrap().len(), 1);
+        assert_eq!(*consumer.nacked.lock().unwrap(), vec!["handle-bad".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_without_dlq_or_retry_limit_message_is_dropped() {
+        let messages = vec![make_msg("bad", "not json", 1)];
+        let consumer = MockConsumer::with_messages(false, &messages);
+
+        let (_docs, errors) = parse_batch(&messages);
+        let outcome = route_parse_failures(&consumer, &messages, &errors, 0).await;
+
+        assert_eq!(outcome.dropped, 1);
+        assert!(consumer.source.lock().unwrap().is_empty());
+        assert!(consumer.nacked.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_message_without_dlq_is_dropped_not_retried() {
+        let messages = vec![make_msg("poison", r#"{"event_type":"Login"}"#, 6)];
+        let consumer = MockConsumer::with_messages(false, &messages);
+
+        let (remaining, outcome) = route_exhausted(&consumer, messages, 5).await;
+
+        assert!(remaining.is_empty());
+        assert_eq!(outcome.dropped, 1);
+        assert!(consumer.nacked.lock().unwrap().is_empty());
+        assert!(consumer.source.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_messages_are_dead_lettered() {
+        let messages = vec![
+            make_msg("fresh", r#"{"event_type":"Login"}"#, 2),
+            make_msg("poison", r#"{"event_type":"Login"}"#, 6),
+        ];
+        let consumer = MockConsumer::with_messages(true, &messages);
+
+        let (remaining, outcome) = route_exhausted(&consumer, messages, 5).await;
+
+        assert_eq!(outcome.dead_lettered, 1);
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, "fresh");
+        let dlq = consumer.dlq.lock().unwrap();
+        assert_eq!(
+            dlq[0].1,
+            DeadLetterReason::MaxReceiveExceeded { attempts: 6, limit: 5 }
+        );
+    }
+
+    #[test]
+    fn test_exceeds_max_receive_zero_disables() {
+        let msg = make_msg("m", "{}", 100);
+        assert!(!exceeds_max_receive(&msg, 0));
+        assert!(exceeds_max_receive(&msg, 99));
+        assert!(!exceeds_max_receive(&msg, 100));
+    }
+}
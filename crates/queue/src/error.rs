@@ -24,4 +24,55 @@ pub enum QueueError {
 
     #[error("provider error: {0}")]
     Provider(String),
+
+    #[error("dead-letter error: {0}")]
+    DeadLetter(String),
+
+    #[error("archive error: {0}")]
+    Archive(String),
+}
+
+impl QueueError {
+    /// Whether this error came from the message payload itself.
+    ///
+    /// Parse failures are permanent — retrying the same body will fail again,
+    /// so these messages belong in the DLQ rather than back on the queue.
+    pub fn is_parse(&self) -> bool {
+        matches!(self, Self::Parse(_))
+    }
+
+    /// Whether this error came from talking to the queue provider.
+    ///
+    /// Transport failures are usually transient and worth retrying.
+    pub fn is_transport(&self) -> bool {
+        matches!(
+            self,
+            Self::Connection(_)
+                | Self::Ack(_)
+                | Self::Timeout(_)
+                | Self::NotFound(_)
+                | Self::Auth(_)
+                | Self::Provider(_)
+                | Self::DeadLetter(_)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vs_transport_classification() {
+        let parse = QueueError::Parse("bad json".into());
+        assert!(parse.is_parse());
+        assert!(!parse.is_transport());
+
+        let conn = QueueError::Connection("refused".into());
+        assert!(conn.is_transport());
+        assert!(!conn.is_parse());
+
+        assert!(QueueError::DeadLetter("send failed".into()).is_transport());
+        assert!(QueueError::Timeout(500).is_transport());
+    }
 }
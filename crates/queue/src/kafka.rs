@@ -0,0 +1,272 @@
+//! Kafka consumer implementation (consumer-group based).
+//!
+//! Offsets are committed manually and only for records that have been acked,
+//! via [`OffsetTracker`]. Auto-commit is disabled so a crash between receive
+//! and processing re-delivers the record (at-least-once).
+//!
+//! Kafka can't redeliver a single record: that takes seeking the partition
+//! back, which would replay every later record in the batch, including ones
+//! already persisted. A nacked record is therefore logged and committed past;
+//! failed records should go to the DLQ instead (see [`crate::dlq`]).
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{TimeZone, Utc};
+use rdkafka::config::ClientConfig;
+use rdkafka::consumer::{CommitMode, Consumer, StreamConsumer};
+use rdkafka::message::{BorrowedMessage, Header, OwnedHeaders};
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::{Message, Offset, TopicPartitionList};
+use tracing::{debug, info, warn};
+
+use stupid_core::config::QueueConfig;
+
+use crate::consumer::{QueueConsumer, QueueHealth, QueueMessage};
+use crate::dlq::{DeadLetterReason, ATTR_ERROR, ATTR_REASON, ATTR_SOURCE_MESSAGE_ID};
+use crate::error::QueueError;
+use crate::metrics::QueueMetrics;
+use crate::offsets::{KafkaReceipt, OffsetTracker};
+
+/// How long to wait for additional records once the first one has arrived.
+const BATCH_LINGER: Duration = Duration::from_millis(10);
+
+/// Timeout for blocking metadata/watermark calls.
+const METADATA_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Kafka-backed queue consumer.
+pub struct KafkaConsumer {
+    consumer: Arc<StreamConsumer>,
+    topic: String,
+    /// DLQ topic name (taken from `dlq_url`), with its producer.
+    dlq: Option<(String, FutureProducer)>,
+    tracker: Mutex<OffsetTracker>,
+    poll_timeout: Duration,
+    /// Consumer-side counters that lag and error rate are derived from.
+    metrics: Arc<QueueMetrics>,
+}
+
+impl KafkaConsumer {
+    /// Create a new Kafka consumer from queue config and subscribe to its topic.
+    pub fn new(queue: &QueueConfig) -> Result<Self, QueueError> {
+        let kafka = &queue.kafka;
+        if kafka.brokers.is_empty() {
+            return Err(QueueError::Connection("Kafka brokers not configured".into()));
+        }
+        if kafka.topic.is_empty() {
+            return Err(QueueError::NotFound("Kafka topic not configured".into()));
+        }
+
+        let consumer: StreamConsumer = ClientConfig::new()
+            .set("bootstrap.servers", &kafka.brokers)
+            .set("group.id", &kafka.group_id)
+            .set("enable.auto.commit", "false")
+            .set("enable.auto.offset.store", "false")
+            .set("auto.offset.reset", "earliest")
+            .create()
+            .map_err(|e| QueueError::Connection(format!("Kafka consumer create failed: {e}")))?;
+
+        consumer
+            .subscribe(&[kafka.topic.as_str()])
+            .map_err(|e| QueueError::Connection(format!("Kafka subscribe failed: {e}")))?;
+
+        let dlq = match queue.dlq_url.as_deref().filter(|t| !t.is_empty()) {
+            Some(dlq_topic) => {
+                let producer: FutureProducer = ClientConfig::new()
+                    .set("bootstrap.servers", &kafka.brokers)
+                    .create()
+                    .map_err(|e| {
+                        QueueError::Connection(format!("Kafka DLQ producer create failed: {e}"))
+                    })?;
+                Some((dlq_topic.to_string(), producer))
+            }
+            None => None,
+        };
+
+        info!(
+            brokers = %kafka.brokers,
+            topic = %kafka.topic,
+            group_id = %kafka.group_id,
+            "Kafka consumer initialized"
+        );
+
+        Ok(Self {
+            consumer: Arc::new(consumer),
+            topic: kafka.topic.clone(),
+            dlq,
+            tracker: Mutex::new(OffsetTracker::new()),
+            poll_timeout: Duration::from_millis(queue.poll_interval_ms.max(100)),
+            metrics: Arc::new(QueueMetrics::new()),
+        })
+    }
+
+    /// Report health from `metrics`, the counters the poll loop updates.
+    pub fn with_metrics(mut self, metrics: Arc<QueueMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    fn to_queue_message(&self, msg: &BorrowedMessage<'_>) -> QueueMessage {
+        let receipt = KafkaReceipt::new(msg.topic(), msg.partition(), msg.offset());
+        let attempt_count = self.tracker.lock().unwrap().track(&receipt);
+
+        let body = msg
+            .payload()
+            .map(|p| String::from_utf8_lossy(p).into_owned())
+            .unwrap_or_default();
+
+        let id = msg
+            .key()
+            .and_then(|k| std::str::from_utf8(k).ok())
+            .map(str::to_string)
+            .unwrap_or_else(|| receipt.to_string());
+
+        let timestamp = msg
+            .timestamp()
+            .to_millis()
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+            .unwrap_or_else(Utc::now);
+
+        QueueMessage {
+            id,
+            body,
+            receipt_handle: receipt.to_string(),
+            timestamp,
+            attempt_count,
+        }
+    }
+
+    /// Commit every partition whose acked prefix advanced.
+    fn commit_acked(&self) -> Result<(), QueueError> {
+        let commits = self.tracker.lock().unwrap().take_commits();
+        if commits.is_empty() {
+            return Ok(());
+        }
+
+        let mut tpl = TopicPartitionList::new();
+        for c in &commits {
+            tpl.add_partition_offset(&c.topic, c.partition, Offset::Offset(c.next_offset))
+                .map_err(|e| QueueError::Ack(format!("Kafka offset list failed: {e}")))?;
+        }
+
+        self.consumer
+            .commit(&tpl, CommitMode::Async)
+            .map_err(|e| QueueError::Ack(format!("Kafka commit failed: {e}")))?;
+
+        debug!(partitions = commits.len(), "Committed Kafka offsets");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl QueueConsumer for KafkaConsumer {
+    async fn poll_batch(&self, max_messages: u32) -> Result<Vec<QueueMessage>, QueueError> {
+        let mut messages = Vec::new();
+
+        // Wait up to the poll timeout for the first record, then drain whatever
+        // is immediately available up to `max_messages`.
+        match tokio::time::timeout(self.poll_timeout, self.consumer.recv()).await {
+            Err(_) => return Ok(messages),
+            Ok(Err(e)) => {
+                return Err(QueueError::Connection(format!("Kafka receive failed: {e}")))
+            }
+            Ok(Ok(msg)) => messages.push(self.to_queue_message(&msg)),
+        }
+
+        while messages.len() < max_messages as usize {
+            match tokio::time::timeout(BATCH_LINGER, self.consumer.recv()).await {
+                Ok(Ok(msg)) => messages.push(self.to_queue_message(&msg)),
+                Ok(Err(e)) => {
+                    warn!(error = %e, "Kafka receive failed mid-batch");
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        debug!(count = messages.len(), topic = %self.topic, "Received Kafka records");
+        Ok(messages)
+    }
+
+    async fn ack(&self, receipt_handle: &str) -> Result<(), QueueError> {
+        let receipt = KafkaReceipt::parse(receipt_handle)?;
+        self.tracker.lock().unwrap().ack(&receipt)?;
+        self.commit_acked()
+    }
+
+    async fn nack(&self, receipt_handle: &str) -> Result<(), QueueError> {
+        let receipt = KafkaReceipt::parse(receipt_handle)?;
+        warn!(receipt = %receipt, "Kafka record nacked — committing past it (no per-record redelivery)");
+
+        self.tracker.lock().unwrap().ack(&receipt)?;
+        self.commit_acked()
+    }
+
+    async fn health_check(&self) -> Result<QueueHealth, QueueError> {
+        let consumer = self.consumer.clone();
+        let topic = self.topic.clone();
+
+        // Metadata and watermark calls block — keep them off the async workers.
+        let lag = tokio::task::spawn_blocking(move || -> Result<u64, QueueError> {
+            let metadata = consumer
+                .fetch_metadata(Some(&topic), METADATA_TIMEOUT)
+                .map_err(|e| QueueError::Connection(format!("Kafka metadata failed: {e}")))?;
+            let committed = consumer
+                .committed(METADATA_TIMEOUT)
+                .map_err(|e| QueueError::Connection(format!("Kafka committed failed: {e}")))?;
+
+            let mut lag = 0u64;
+            for t in metadata.topics().iter().filter(|t| t.name() == topic) {
+                for p in t.partitions() {
+                    let (low, high) = consumer
+                        .fetch_watermarks(&topic, p.id(), METADATA_TIMEOUT)
+                        .map_err(|e| QueueError::Connection(format!("Kafka watermarks failed: {e}")))?;
+                    let position = match committed
+                        .find_partition(&topic, p.id())
+                        .map(|e| e.offset())
+                    {
+                        Some(Offset::Offset(o)) => o,
+                        _ => low,
+                    };
+                    lag += (high - position).max(0) as u64;
+                }
+            }
+            Ok(lag)
+        })
+        .await
+        .map_err(|e| QueueError::Provider(format!("Kafka health task failed: {e}")))??;
+
+        // Backlog is end offset minus committed offset, summed over partitions;
+        // lag_ms is the age of the oldest record still in flight.
+        Ok(QueueHealth::reachable("kafka", &self.metrics, Some(lag)))
+    }
+
+    async fn send_to_dlq(
+        &self,
+        msg: &QueueMessage,
+        reason: &DeadLetterReason,
+    ) -> Result<bool, QueueError> {
+        let Some((dlq_topic, producer)) = &self.dlq else {
+            return Ok(false);
+        };
+
+        let error_text = reason.to_string();
+        let headers = OwnedHeaders::new()
+            .insert(Header { key: ATTR_REASON, value: Some(reason.kind()) })
+            .insert(Header { key: ATTR_ERROR, value: Some(error_text.as_str()) })
+            .insert(Header { key: ATTR_SOURCE_MESSAGE_ID, value: Some(msg.id.as_str()) });
+
+        let record = FutureRecord::to(dlq_topic)
+            .key(&msg.id)
+            .payload(&msg.body)
+            .headers(headers);
+
+        producer
+            .send(record, METADATA_TIMEOUT)
+            .await
+            .map_err(|(e, _)| QueueError::DeadLetter(format!("Kafka DLQ send failed: {e}")))?;
+
+        Ok(true)
+    }
+}
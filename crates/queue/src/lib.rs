@@ -1,12 +1,24 @@
+pub mod archive;
 pub mod batcher;
 pub mod consumer;
+pub mod dlq;
 pub mod error;
+#[cfg(feature = "kafka")]
+pub mod kafka;
+pub mod metrics;
+pub mod offsets;
 pub mod config;
 pub mod parser;
 pub mod sqs;
 
+pub use archive::{replay, ArchivingConsumer, MessageArchive, ReplayOutcome};
 pub use batcher::MicroBatcher;
 pub use consumer::{QueueConsumer, QueueMessage, QueueHealth};
+pub use dlq::{DeadLetterOutcome, DeadLetterReason};
 pub use error::QueueError;
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaConsumer;
+pub use metrics::{HealthStatus, HealthThresholds, QueueMetrics, QueueMetricsSnapshot};
+pub use offsets::{KafkaReceipt, OffsetTracker};
 pub use parser::{parse_message, parse_batch};
 pub use sqs::SqsConsumer;
@@ -0,0 +1,304 @@
+//! Per-queue consumer metrics and health derivation.
+//!
+//! Consumers update a shared [`QueueMetrics`] with lock-free counters; API
+//! handlers read a point-in-time [`QueueMetricsSnapshot`] and derive health
+//! from it using [`HealthThresholds`].
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::consumer::QueueMessage;
+
+/// Lock-free atomic counters for queue consumer observability.
+///
+/// All fields use `Ordering::Relaxed` — these are monotonic counters
+/// where eventual visibility is acceptable for dashboard/status reads.
+#[derive(Debug, Default)]
+pub struct QueueMetrics {
+    /// Whether queue ingestion is enabled in config.
+    pub enabled: AtomicBool,
+    /// Whether the consumer is currently connected to the queue.
+    pub connected: AtomicBool,
+    /// Total messages received from queue polls.
+    pub messages_received: AtomicU64,
+    /// Messages successfully parsed and ingested.
+    pub messages_processed: AtomicU64,
+    /// Messages that failed parsing or ingestion.
+    pub messages_failed: AtomicU64,
+    /// Messages moved to the dead-letter queue.
+    pub messages_dead_lettered: AtomicU64,
+    /// Messages received but not yet acked, nacked, or dead-lettered.
+    pub in_flight: AtomicU64,
+    /// Number of micro-batches flushed to the graph.
+    pub batches_processed: AtomicU64,
+    /// Cumulative processing time in microseconds (for avg latency calc).
+    pub total_processing_time_us: AtomicU64,
+    /// Epoch milliseconds of the last successful poll.
+    pub last_poll_epoch_ms: AtomicU64,
+    /// Send timestamp (epoch ms) of the oldest message still in flight; 0 if none.
+    pub oldest_in_flight_epoch_ms: AtomicU64,
+}
+
+impl QueueMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a successful poll returning `messages`.
+    pub fn record_poll(&self, messages: &[QueueMessage]) {
+        self.last_poll_epoch_ms
+            .store(Utc::now().timestamp_millis().max(0) as u64, Ordering::Relaxed);
+
+        if messages.is_empty() {
+            return;
+        }
+        self.messages_received
+            .fetch_add(messages.len() as u64, Ordering::Relaxed);
+        self.in_flight
+            .fetch_add(messages.len() as u64, Ordering::Relaxed);
+
+        if let Some(oldest) = messages.iter().map(|m| m.timestamp.timestamp_millis()).min() {
+            let oldest = oldest.max(0) as u64;
+            // Keep the earliest timestamp; 0 means "nothing in flight".
+            let _ = self.oldest_in_flight_epoch_ms.fetch_update(
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+                |current| (current == 0 || oldest < current).then_some(oldest),
+            );
+        }
+    }
+
+    /// Record a finished micro-batch.
+    ///
+    /// `dead_lettered` is the subset of `failed` that was moved to the DLQ;
+    /// `processed + failed` messages leave the in-flight set.
+    pub fn record_batch(&self, processed: u64, failed: u64, dead_lettered: u64, elapsed: Duration) {
+        self.messages_processed.fetch_add(processed, Ordering::Relaxed);
+        self.messages_failed.fetch_add(failed, Ordering::Relaxed);
+        self.messages_dead_lettered
+            .fetch_add(dead_lettered, Ordering::Relaxed);
+        self.batches_processed.fetch_add(1, Ordering::Relaxed);
+        self.total_processing_time_us
+            .fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+
+        let settled = processed + failed;
+        let _ = self
+            .in_flight
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |v| {
+                Some(v.saturating_sub(settled))
+            });
+        if self.in_flight.load(Ordering::Relaxed) == 0 {
+            self.oldest_in_flight_epoch_ms.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Take a consistent-enough view of the counters at `now_epoch_ms`.
+    pub fn snapshot_at(&self, now_epoch_ms: u64) -> QueueMetricsSnapshot {
+        let received = self.messages_received.load(Ordering::Relaxed);
+        let processed = self.messages_processed.load(Ordering::Relaxed);
+        let failed = self.messages_failed.load(Ordering::Relaxed);
+        let batches = self.batches_processed.load(Ordering::Relaxed);
+        let total_time_us = self.total_processing_time_us.load(Ordering::Relaxed);
+        let oldest = self.oldest_in_flight_epoch_ms.load(Ordering::Relaxed);
+
+        QueueMetricsSnapshot {
+            enabled: self.enabled.load(Ordering::Relaxed),
+            connected: self.connected.load(Ordering::Relaxed),
+            messages_received: received,
+            messages_processed: processed,
+            messages_failed: failed,
+            messages_dead_lettered: self.messages_dead_lettered.load(Ordering::Relaxed),
+            in_flight: self.in_flight.load(Ordering::Relaxed),
+            batches_processed: batches,
+            avg_batch_latency_ms: if batches > 0 {
+                (total_time_us as f64 / batches as f64) / 1000.0
+            } else {
+                0.0
+            },
+            last_poll_epoch_ms: self.last_poll_epoch_ms.load(Ordering::Relaxed),
+            oldest_message_age_ms: if oldest == 0 {
+                0
+            } else {
+                now_epoch_ms.saturating_sub(oldest)
+            },
+            error_rate: if processed + failed > 0 {
+                failed as f64 / (processed + failed) as f64
+            } else {
+                0.0
+            },
+        }
+    }
+
+    /// Snapshot at the current wall-clock time.
+    pub fn snapshot(&self) -> QueueMetricsSnapshot {
+        self.snapshot_at(Utc::now().timestamp_millis().max(0) as u64)
+    }
+}
+
+/// Point-in-time, serializable view of [`QueueMetrics`].
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueMetricsSnapshot {
+    pub enabled: bool,
+    pub connected: bool,
+    pub messages_received: u64,
+    pub messages_processed: u64,
+    pub messages_failed: u64,
+    pub messages_dead_lettered: u64,
+    pub in_flight: u64,
+    pub batches_processed: u64,
+    pub avg_batch_latency_ms: f64,
+    pub last_poll_epoch_ms: u64,
+    /// Age of the oldest in-flight message (consumer lag), in milliseconds.
+    pub oldest_message_age_ms: u64,
+    /// `failed / (processed + failed)`; 0 when nothing has been settled.
+    pub error_rate: f64,
+}
+
+/// Limits used to decide whether a queue consumer is healthy.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthThresholds {
+    /// Maximum acceptable age of the oldest in-flight message.
+    pub max_lag_ms: u64,
+    /// Maximum acceptable failure ratio.
+    pub max_error_rate: f64,
+    /// Settled messages required before the error rate is trusted.
+    pub min_samples: u64,
+}
+
+impl Default for HealthThresholds {
+    fn default() -> Self {
+        Self {
+            max_lag_ms: 60_000,
+            max_error_rate: 0.05,
+            min_samples: 20,
+        }
+    }
+}
+
+/// Why a queue consumer is (un)healthy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthStatus {
+    Healthy,
+    Disconnected,
+    Lagging,
+    HighErrorRate,
+}
+
+impl QueueMetricsSnapshot {
+    /// Derive health: connected, lag under threshold, and error rate low.
+    ///
+    /// The error rate is only considered once `min_samples` messages have been
+    /// settled, so one early failure doesn't flag a fresh consumer.
+    pub fn health(&self, thresholds: &HealthThresholds) -> HealthStatus {
+        if !self.connected {
+            return HealthStatus::Disconnected;
+        }
+        if self.oldest_message_age_ms > thresholds.max_lag_ms {
+            return HealthStatus::Lagging;
+        }
+        let settled = self.messages_processed + self.messages_failed;
+        if settled >= thresholds.min_samples && self.error_rate > thresholds.max_error_rate {
+            return HealthStatus::HighErrorRate;
+        }
+        HealthStatus::Healthy
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn message_sent_at(epoch_ms: i64) -> QueueMessage {
+        QueueMessage {
+            id: format!("m-{epoch_ms}"),
+            body: "{}".to_string(),
+            receipt_handle: format!("h-{epoch_ms}"),
+            timestamp: Utc.timestamp_millis_opt(epoch_ms).unwrap(),
+            attempt_count: 1,
+        }
+    }
+
+    fn connected_metrics() -> QueueMetrics {
+        let m = QueueMetrics::new();
+        m.connected.store(true, Ordering::Relaxed);
+        m
+    }
+
+    #[test]
+    fn test_healthy_when_connected_and_idle() {
+        let m = connected_metrics();
+        assert_eq!(m.snapshot_at(1_000).health(&HealthThresholds::default()), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_disconnected_is_unhealthy() {
+        let m = QueueMetrics::new();
+        assert_eq!(
+            m.snapshot_at(1_000).health(&HealthThresholds::default()),
+            HealthStatus::Disconnected
+        );
+    }
+
+    #[test]
+    fn test_lag_threshold() {
+        let m = connected_metrics();
+        m.record_poll(&[message_sent_at(10_000), message_sent_at(5_000)]);
+        let thresholds = HealthThresholds { max_lag_ms: 60_000, ..Default::default() };
+
+        // Oldest message (5s) is 60s old: exactly at the limit is still healthy.
+        let at_limit = m.snapshot_at(65_000);
+        assert_eq!(at_limit.oldest_message_age_ms, 60_000);
+        assert_eq!(at_limit.health(&thresholds), HealthStatus::Healthy);
+
+        assert_eq!(m.snapshot_at(65_001).health(&thresholds), HealthStatus::Lagging);
+    }
+
+    #[test]
+    fn test_lag_clears_when_batch_settles() {
+        let m = connected_metrics();
+        m.record_poll(&[message_sent_at(1_000), message_sent_at(2_000)]);
+        m.record_batch(2, 0, 0, Duration::from_millis(5));
+
+        let snap = m.snapshot_at(1_000_000);
+        assert_eq!(snap.in_flight, 0);
+        assert_eq!(snap.oldest_message_age_ms, 0);
+        assert_eq!(snap.health(&HealthThresholds::default()), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_error_rate_threshold() {
+        let thresholds = HealthThresholds { max_error_rate: 0.1, min_samples: 10, ..Default::default() };
+
+        let m = connected_metrics();
+        m.record_batch(90, 10, 0, Duration::ZERO);
+        let snap = m.snapshot_at(0);
+        assert!((snap.error_rate - 0.1).abs() < 1e-9);
+        assert_eq!(snap.health(&thresholds), HealthStatus::Healthy);
+
+        m.record_batch(0, 1, 0, Duration::ZERO);
+        assert_eq!(m.snapshot_at(0).health(&thresholds), HealthStatus::HighErrorRate);
+    }
+
+    #[test]
+    fn test_error_rate_ignored_below_min_samples() {
+        let thresholds = HealthThresholds { min_samples: 20, ..Default::default() };
+        let m = connected_metrics();
+        m.record_batch(1, 1, 1, Duration::ZERO);
+        let snap = m.snapshot_at(0);
+        assert_eq!(snap.error_rate, 0.5);
+        assert_eq!(snap.health(&thresholds), HealthStatus::Healthy);
+    }
+
+    #[test]
+    fn test_avg_batch_latency() {
+        let m = connected_metrics();
+        m.record_batch(1, 0, 0, Duration::from_millis(10));
+        m.record_batch(1, 0, 0, Duration::from_millis(30));
+        assert!((m.snapshot_at(0).avg_batch_latency_ms - 20.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,253 @@
+//! Partition offset tracking for log-based sources (Kafka).
+//!
+//! Kafka has no per-message delete: progress is recorded by committing the
+//! next offset to read for each partition. To keep at-least-once semantics we
+//! only commit up to the lowest offset that has not been acked yet, so a crash
+//! never skips a message that was received but not successfully processed.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use crate::error::QueueError;
+
+/// Location of a single Kafka record, encoded into [`QueueMessage::receipt_handle`].
+///
+/// [`QueueMessage::receipt_handle`]: crate::QueueMessage::receipt_handle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct KafkaReceipt {
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+}
+
+impl KafkaReceipt {
+    pub fn new(topic: impl Into<String>, partition: i32, offset: i64) -> Self {
+        Self {
+            topic: topic.into(),
+            partition,
+            offset,
+        }
+    }
+
+    /// Parse a receipt handle in `topic:partition:offset` form.
+    ///
+    /// Topic names may not contain `:`, so splitting from the right is safe.
+    pub fn parse(handle: &str) -> Result<Self, QueueError> {
+        let invalid = || QueueError::Provider(format!("invalid Kafka receipt handle: {handle}"));
+
+        let mut parts = handle.rsplitn(3, ':');
+        let offset = parts.next().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+        let partition = parts.next().and_then(|s| s.parse().ok()).ok_or_else(invalid)?;
+        let topic = parts.next().filter(|s| !s.is_empty()).ok_or_else(invalid)?;
+
+        Ok(Self::new(topic, partition, offset))
+    }
+}
+
+impl fmt::Display for KafkaReceipt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.topic, self.partition, self.offset)
+    }
+}
+
+/// Offset commit for a single partition: the next offset the group should read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetCommit {
+    pub topic: String,
+    pub partition: i32,
+    pub next_offset: i64,
+}
+
+#[derive(Debug, Default)]
+struct PartitionState {
+    /// Delivered offsets awaiting processing → whether they have been acked.
+    pending: BTreeMap<i64, bool>,
+    /// Next offset already committed to the broker.
+    committed: Option<i64>,
+    /// How many times each still-pending offset has been delivered.
+    deliveries: HashMap<i64, u32>,
+}
+
+/// Tracks delivered/acked offsets per partition and computes safe commits.
+#[derive(Debug, Default)]
+pub struct OffsetTracker {
+    partitions: HashMap<(String, i32), PartitionState>,
+}
+
+impl OffsetTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record that a record was delivered to the consumer.
+    ///
+    /// Returns the delivery attempt count for this offset (1 on first delivery,
+    /// higher when a rebalance redelivers it before it was committed).
+    pub fn track(&mut self, receipt: &KafkaReceipt) -> u32 {
+        let state = self
+            .partitions
+            .entry((receipt.topic.clone(), receipt.partition))
+            .or_default();
+        state.pending.insert(receipt.offset, false);
+        let attempts = state.deliveries.entry(receipt.offset).or_insert(0);
+        *attempts += 1;
+        *attempts
+    }
+
+    /// Mark a record as successfully processed.
+    pub fn ack(&mut self, receipt: &KafkaReceipt) -> Result<(), QueueError> {
+        let done = self
+            .partitions
+            .get_mut(&(receipt.topic.clone(), receipt.partition))
+            .and_then(|state| state.pending.get_mut(&receipt.offset))
+            .ok_or_else(|| QueueError::Ack(format!("offset {receipt} is not in flight")))?;
+        *done = true;
+        Ok(())
+    }
+
+    /// Compute commits for partitions whose contiguous acked prefix has grown.
+    ///
+    /// Acked offsets are drained from the pending set; gaps (unacked offsets)
+    /// hold back the commit so nothing unprocessed is ever skipped.
+    pub fn take_commits(&mut self) -> Vec<OffsetCommit> {
+        let mut commits = Vec::new();
+
+        for ((topic, partition), state) in &mut self.partitions {
+            let mut last_done = None;
+            while let Some((&offset, &done)) = state.pending.first_key_value() {
+                if !done {
+                    break;
+                }
+                state.pending.pop_first();
+                state.deliveries.remove(&offset);
+                last_done = Some(offset);
+            }
+
+            if let Some(offset) = last_done {
+                let next_offset = offset + 1;
+                if state.committed.is_none_or(|c| next_offset > c) {
+                    state.committed = Some(next_offset);
+                    commits.push(OffsetCommit {
+                        topic: topic.clone(),
+                        partition: *partition,
+                        next_offset,
+                    });
+                }
+            }
+        }
+
+        commits.sort_by(|a, b| (&a.topic, a.partition).cmp(&(&b.topic, b.partition)));
+        commits
+    }
+
+    /// Number of delivered records not yet acked across all partitions.
+    pub fn in_flight(&self) -> usize {
+        self.partitions
+            .values()
+            .map(|s| s.pending.values().filter(|done| !**done).count())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn receipt(partition: i32, offset: i64) -> KafkaReceipt {
+        KafkaReceipt::new("events", partition, offset)
+    }
+
+    #[test]
+    fn test_receipt_roundtrip() {
+        let r = KafkaReceipt::new("events.login", 3, 42);
+        let handle = r.to_string();
+        assert_eq!(handle, "events.login:3:42");
+        assert_eq!(KafkaReceipt::parse(&handle).unwrap(), r);
+    }
+
+    #[test]
+    fn test_receipt_parse_rejects_garbage() {
+        assert!(KafkaReceipt::parse("no-colons").is_err());
+        assert!(KafkaReceipt::parse("topic:x:1").is_err());
+        assert!(KafkaReceipt::parse(":0:1").is_err());
+    }
+
+    #[test]
+    fn test_no_commit_before_ack() {
+        let mut tracker = OffsetTracker::new();
+        tracker.track(&receipt(0, 10));
+        tracker.track(&receipt(0, 11));
+        assert!(tracker.take_commits().is_empty());
+        assert_eq!(tracker.in_flight(), 2);
+    }
+
+    #[test]
+    fn test_commit_after_all_acked() {
+        let mut tracker = OffsetTracker::new();
+        for offset in 10..13 {
+            tracker.track(&receipt(0, offset));
+        }
+        for offset in 10..13 {
+            tracker.ack(&receipt(0, offset)).unwrap();
+        }
+
+        let commits = tracker.take_commits();
+        assert_eq!(
+            commits,
+            vec![OffsetCommit { topic: "events".into(), partition: 0, next_offset: 13 }]
+        );
+        // Nothing new to commit on the next call.
+        assert!(tracker.take_commits().is_empty());
+        assert_eq!(tracker.in_flight(), 0);
+    }
+
+    #[test]
+    fn test_out_of_order_ack_waits_for_gap() {
+        let mut tracker = OffsetTracker::new();
+        for offset in 0..3 {
+            tracker.track(&receipt(0, offset));
+        }
+        tracker.ack(&receipt(0, 0)).unwrap();
+        tracker.ack(&receipt(0, 2)).unwrap();
+
+        // Offset 1 is still unprocessed: only 0 may be committed.
+        let commits = tracker.take_commits();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].next_offset, 1);
+
+        tracker.ack(&receipt(0, 1)).unwrap();
+        let commits = tracker.take_commits();
+        assert_eq!(commits[0].next_offset, 3);
+    }
+
+    #[test]
+    fn test_partitions_commit_independently() {
+        let mut tracker = OffsetTracker::new();
+        tracker.track(&receipt(0, 5));
+        tracker.track(&receipt(1, 7));
+        tracker.ack(&receipt(1, 7)).unwrap();
+
+        let commits = tracker.take_commits();
+        assert_eq!(commits.len(), 1);
+        assert_eq!(commits[0].partition, 1);
+        assert_eq!(commits[0].next_offset, 8);
+    }
+
+    #[test]
+    fn test_redelivery_counts_attempts_until_committed() {
+        let mut tracker = OffsetTracker::new();
+        assert_eq!(tracker.track(&receipt(0, 0)), 1);
+        // Redelivered (e.g. after a rebalance) before it was committed.
+        assert_eq!(tracker.track(&receipt(0, 0)), 2);
+
+        tracker.ack(&receipt(0, 0)).unwrap();
+        assert_eq!(tracker.take_commits()[0].next_offset, 1);
+        assert_eq!(tracker.track(&receipt(0, 0)), 1);
+    }
+
+    #[test]
+    fn test_ack_unknown_offset_errors() {
+        let mut tracker = OffsetTracker::new();
+        assert!(tracker.ack(&receipt(0, 99)).is_err());
+    }
+}
@@ -1,9 +1,11 @@
 //! AWS SQS consumer implementation.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use aws_credential_types::Credentials;
 use aws_sdk_sqs::config::BehaviorVersion;
-use aws_sdk_sqs::types::QueueAttributeName;
+use aws_sdk_sqs::types::{MessageAttributeValue, QueueAttributeName};
 use aws_sdk_sqs::Client;
 use chrono::{TimeZone, Utc};
 use tracing::{debug, info};
@@ -11,7 +13,20 @@ use tracing::{debug, info};
 use stupid_core::config::{AwsConfig, QueueConfig};
 
 use crate::consumer::{QueueConsumer, QueueHealth, QueueMessage};
+use crate::dlq::{DeadLetterReason, ATTR_ERROR, ATTR_REASON, ATTR_SOURCE_MESSAGE_ID};
 use crate::error::QueueError;
+use crate::metrics::QueueMetrics;
+
+/// SQS limits string attribute values to 256 KiB; keep error text well under.
+const MAX_ERROR_ATTR_LEN: usize = 1024;
+
+fn string_attribute(value: &str) -> Result<MessageAttributeValue, QueueError> {
+    MessageAttributeValue::builder()
+        .data_type("String")
+        .string_value(value)
+        .build()
+        .map_err(|e| QueueError::DeadLetter(format!("invalid DLQ attribute: {e}")))
+}
 
 /// SQS-backed queue consumer.
 pub struct SqsConsumer {
@@ -19,6 +34,8 @@ pub struct SqsConsumer {
     queue_url: String,
     dlq_url: Option<String>,
     visibility_timeout_secs: i32,
+    /// Consumer-side counters that lag and error rate are derived from.
+    metrics: Arc<QueueMetrics>,
 }
 
 impl SqsConsumer {
@@ -72,8 +89,15 @@ impl SqsConsumer {
             queue_url: queue.queue_url.clone(),
             dlq_url: queue.dlq_url.clone(),
             visibility_timeout_secs: queue.visibility_timeout_secs as i32,
+            metrics: Arc::new(QueueMetrics::new()),
         })
     }
+
+    /// Report health from `metrics`, the counters the poll loop updates.
+    pub fn with_metrics(mut self, metrics: Arc<QueueMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
 }
 
 #[async_trait]
@@ -113,7 +137,7 @@ impl QueueConsumer for SqsConsumer {
 
             let receipt_handle = msg
                 .receipt_handle()
-                .ok_or_else(|| QueueError::Parse("missing receipt handle".into()))?
+                .ok_or_else(|| QueueError::Provider("missing receipt handle".into()))?
                 .to_string();
 
             // Extract timestamp from SentTimestamp attribute (epoch millis).
@@ -194,11 +218,9 @@ impl QueueConsumer for SqsConsumer {
             .and_then(|attrs| attrs.get(&QueueAttributeName::ApproximateNumberOfMessages))
             .and_then(|v| v.parse::<u64>().ok());
 
-        Ok(QueueHealth {
-            connected: true,
-            approximate_message_count: count,
-            provider: "sqs".to_string(),
-        })
+        // SQS only exposes the oldest message's age through CloudWatch, so lag
+        // is the age of the oldest message this consumer holds in flight.
+        Ok(QueueHealth::reachable("sqs", &self.metrics, count))
     }
 
     async fn dlq_depth(&self) -> Result<Option<u64>, QueueError> {
@@ -223,4 +245,40 @@ impl QueueConsumer for SqsConsumer {
 
         Ok(count)
     }
+
+    async fn send_to_dlq(
+        &self,
+        msg: &QueueMessage,
+        reason: &DeadLetterReason,
+    ) -> Result<bool, QueueError> {
+        let dlq_url = match &self.dlq_url {
+            Some(url) if !url.is_empty() => url,
+            _ => return Ok(false),
+        };
+
+        let error_text: String = reason.to_string().chars().take(MAX_ERROR_ATTR_LEN).collect();
+        debug!(message_id = %msg.id, reason = reason.kind(), "Sending message to SQS DLQ");
+
+        let mut req = self
+            .client
+            .send_message()
+            .queue_url(dlq_url)
+            .message_body(&msg.body)
+            .message_attributes(ATTR_REASON, string_attribute(reason.kind())?)
+            .message_attributes(ATTR_ERROR, string_attribute(&error_text)?)
+            .message_attributes(ATTR_SOURCE_MESSAGE_ID, string_attribute(&msg.id)?);
+
+        // FIFO queues require a group ID; keep the original message ID for dedup.
+        if dlq_url.ends_with(".fifo") {
+            req = req
+                .message_group_id("dlq")
+                .message_deduplication_id(&msg.id);
+        }
+
+        req.send()
+            .await
+            .map_err(|e| QueueError::DeadLetter(format!("SQS DLQ send failed: {e:?}")))?;
+
+        Ok(true)
+    }
 }
@@ -0,0 +1,108 @@
+//! Kafka consumer integration test against a local broker.
+//!
+//! Run with: `KAFKA_BROKERS=localhost:9092 cargo test -p stupid-queue --features kafka-integration`
+
+#![cfg(feature = "kafka-integration")]
+
+use std::time::Duration;
+
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+
+use stupid_core::config::{AwsConfig, KafkaConfig, QueueConfig, DEFAULT_S3_MULTIPART_THRESHOLD_BYTES};
+use stupid_queue::{KafkaConsumer, QueueConsumer};
+
+fn brokers() -> String {
+    std::env::var("KAFKA_BROKERS").unwrap_or_else(|_| "localhost:9092".to_string())
+}
+
+fn queue_config(topic: &str, group_id: &str) -> QueueConfig {
+    QueueConfig {
+        enabled: true,
+        provider: "kafka".to_string(),
+        queue_url: String::new(),
+        poll_interval_ms: 5000,
+        max_batch_size: 10,
+        visibility_timeout_secs: 30,
+        micro_batch_size: 100,
+        micro_batch_timeout_ms: 1000,
+        dlq_url: None,
+        max_receive_count: 5,
+        archive_enabled: false,
+        archive_retention_days: 7,
+        aws: AwsConfig {
+            region: "local".to_string(),
+            access_key_id: None,
+            secret_access_key: None,
+            session_token: None,
+            s3_bucket: None,
+            s3_prefix: None,
+            endpoint_url: None,
+            s3_multipart_threshold_bytes: DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
+        },
+        kafka: KafkaConfig {
+            brokers: brokers(),
+            topic: topic.to_string(),
+            group_id: group_id.to_string(),
+        },
+    }
+}
+
+async fn produce(topic: &str, bodies: &[&str]) {
+    let producer: FutureProducer = ClientConfig::new()
+        .set("bootstrap.servers", brokers())
+        .create()
+        .expect("producer");
+    for body in bodies {
+        producer
+            .send(FutureRecord::<(), _>::to(topic).payload(*body), Duration::from_secs(5))
+            .await
+            .expect("produce");
+    }
+}
+
+async fn drain(consumer: &KafkaConsumer, want: usize) -> Vec<stupid_queue::QueueMessage> {
+    let mut out = Vec::new();
+    for _ in 0..10 {
+        out.extend(consumer.poll_batch(10).await.expect("poll"));
+        if out.len() >= want {
+            break;
+        }
+    }
+    out
+}
+
+#[tokio::test]
+async fn test_acked_offsets_are_not_redelivered() {
+    let topic = format!("stupid-it-{}", uuid::Uuid::new_v4());
+    let group = format!("{topic}-group");
+    produce(&topic, &[r#"{"event_type":"Login"}"#, r#"{"event_type":"Logout"}"#]).await;
+
+    let consumer = KafkaConsumer::new(&queue_config(&topic, &group)).expect("consumer");
+    let messages = drain(&consumer, 2).await;
+    assert_eq!(messages.len(), 2);
+    for msg in &messages {
+        consumer.ack(&msg.receipt_handle).await.expect("ack");
+    }
+    // Let the async commit reach the broker before the group is rejoined.
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    drop(consumer);
+
+    let rejoined = KafkaConsumer::new(&queue_config(&topic, &group)).expect("consumer");
+    let again = rejoined.poll_batch(10).await.expect("poll");
+    assert!(again.is_empty(), "acked records were redelivered: {again:?}");
+}
+
+#[tokio::test]
+async fn test_unacked_offsets_are_redelivered_to_group() {
+    let topic = format!("stupid-it-{}", uuid::Uuid::new_v4());
+    let group = format!("{topic}-group");
+    produce(&topic, &[r#"{"event_type":"Login"}"#]).await;
+
+    let consumer = KafkaConsumer::new(&queue_config(&topic, &group)).expect("consumer");
+    assert_eq!(drain(&consumer, 1).await.len(), 1);
+    drop(consumer); // never acked
+
+    let rejoined = KafkaConsumer::new(&queue_config(&topic, &group)).expect("consumer");
+    assert_eq!(drain(&rejoined, 1).await.len(), 1);
+}
@@ -45,6 +45,7 @@ pub enum ExecutionPhase {
     RateLimit,
     Notification,
     NotifyError,
+    Shadow,
     Complete,
 }
 
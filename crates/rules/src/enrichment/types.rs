@@ -65,7 +65,7 @@ pub struct EnrichmentResult {
 
 impl EnrichmentResult {
     /// A skipped enrichment always passes (fail-open behavior).
-    pub(crate) fn skipped() -> Self {
+    pub fn skipped() -> Self {
         Self {
             passed: true,
             hit_count: 0,
@@ -118,6 +118,7 @@ mod tests {
             features,
             score: 0.7,
             cluster_id: None,
+            first_seen: None,
         }
     }
 
@@ -4,9 +4,10 @@
 //! - Multi-kind YAML rule definitions (anomaly, entity schema, features, scoring, trends, patterns)
 //! - Rule inheritance via `extends` with deep-merge
 //! - Filesystem loader with hot-reload via `notify` watcher
-//! - Detection template evaluators (spike, drift, absence, threshold)
+//! - Detection template evaluators (spike, drift, absence, threshold, new_entity, expression)
 //! - Signal composition with AND/OR/NOT trees
 //! - OpenSearch enrichment queries
+//! - Shadow evaluation of candidate rule versions against the live rule
 
 pub mod audit_log;
 pub mod enrichment;
@@ -18,6 +19,7 @@ pub mod pattern_config;
 pub mod scheduler;
 pub mod schema;
 pub mod scoring_config;
+pub mod shadow;
 pub mod templates;
 pub mod trend_config;
 pub mod validation;
@@ -9,9 +9,10 @@ use std::time::Duration;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use tracing::{info, warn};
 
-use crate::schema::{AnomalyRule, RuleDocument, RuleEnvelope};
+use crate::schema::{AnomalyRule, RuleDocument};
 
 use super::error::{LoadResult, LoadStatus, Result, RuleError};
+use super::preprocess;
 use super::watcher::handle_fs_event;
 
 /// Filesystem-backed rule loader with optional hot-reload.
@@ -166,13 +167,18 @@ impl RuleLoader {
 
     /// Parse a single YAML file into a [`RuleDocument`] via two-pass deserialization.
     ///
+    /// The file is [preprocessed](super::preprocess) first; an undefined
+    /// `${ENV_VAR}` fails with [`RuleError::UndefinedEnvVar`], and one
+    /// outside `STUPID_RULE_*` with [`RuleError::DisallowedEnvVar`].
+    ///
     /// First pass: deserialize as [`RuleEnvelope`] to read the `kind` field.
     /// Second pass: reconstruct and deserialize into the kind-specific type.
     pub fn load_file(&self, path: &Path) -> Result<RuleDocument> {
         let contents = fs::read_to_string(path)?;
 
-        // First pass: extract envelope (kind + metadata).
-        let envelope: RuleEnvelope = serde_yaml::from_str(&contents)?;
+        // First pass: extract envelope (kind + metadata), after merge keys
+        // and `${ENV_VAR}` substitution.
+        let envelope = preprocess::parse_rule(&contents)?;
 
         // Basic validation.
         if envelope.metadata.id.is_empty() {
@@ -232,14 +238,25 @@ impl RuleLoader {
     /// Atomically write a rule document to a YAML file.
     ///
     /// Writes to a `.tmp` file first, then renames to the final path to
-    /// avoid partial writes on crash.
+    /// avoid partial writes on crash. Values the existing file took from
+    /// `${ENV_VAR}` placeholders are written back as those placeholders.
     pub fn write_document(&self, doc: &RuleDocument) -> Result<PathBuf> {
         let meta = doc.metadata();
         let filename = format!("{}.yml", meta.id);
         let final_path = self.rules_dir.join(&filename);
         let tmp_path = self.rules_dir.join(format!(".{}.tmp", meta.id));
 
-        let yaml = doc.to_yaml().map_err(RuleError::Parse)?;
+        let mut yaml = doc.to_yaml().map_err(RuleError::Parse)?;
+        let substitutions = fs::read_to_string(&final_path)
+            .ok()
+            .and_then(|existing| preprocess::preprocess(&existing, |name| std::env::var(name).ok()).ok())
+            .map(|(_, substitutions)| substitutions)
+            .unwrap_or_default();
+        if !substitutions.is_empty() {
+            let mut value: serde_yaml::Value = serde_yaml::from_str(&yaml)?;
+            preprocess::restore_placeholders(&mut value, &substitutions);
+            yaml = serde_yaml::to_string(&value)?;
+        }
         fs::write(&tmp_path, yaml)?;
         fs::rename(&tmp_path, &final_path)?;
 
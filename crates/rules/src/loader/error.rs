@@ -17,6 +17,14 @@ pub enum RuleError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    /// A `${VAR}` in a rule file names an environment variable that isn't set.
+    #[error("Undefined environment variable '{var}' referenced in {field}")]
+    UndefinedEnvVar { var: String, field: String },
+
+    /// A `${VAR}` in a rule file names a variable outside `STUPID_RULE_*`.
+    #[error("Environment variable '{var}' referenced in {field} is not available to rules; only STUPID_RULE_* variables are")]
+    DisallowedEnvVar { var: String, field: String },
+
     /// Filesystem watcher error.
     #[error("Notify watcher error: {0}")]
     Notify(#[from] notify::Error),
@@ -3,10 +3,13 @@
 //! Watches the rules directory for YAML file changes (create, modify, delete)
 //! and reloads affected rules into the in-memory rule set.
 //! Supports all rule kinds via two-pass deserialization (RuleEnvelope -> RuleDocument).
+//! Rule files may share fragments through YAML anchors and merge keys and
+//! reference environment variables as `${ENV_VAR}`; see [`preprocess`].
 
 mod core;
 mod error;
 mod extends;
+pub mod preprocess;
 mod watcher;
 
 #[cfg(test)]
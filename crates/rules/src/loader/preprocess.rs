@@ -0,0 +1,215 @@
+//! YAML preprocessing applied to rule files before deserialization.
+//!
+//! - Anchors and aliases (`&name` / `*name`) resolve as usual, and merge
+//!   keys (`<<: *name`) are applied, so shared fragments can be merged into
+//!   a mapping and overridden key by key.
+//! - Top-level keys starting with `x-` are dropped after merging, so they
+//!   can hold fragments that are only there to be referenced.
+//! - `${ENV_VAR}` in string values is replaced with the variable's value;
+//!   an undefined variable fails the load. `$${` is a literal `${`.
+//!
+//! Only variables named [`RULE_ENV_PREFIX`]`*` can be substituted; naming any
+//! other variable fails the load. Rules can be written through the API and
+//! read back with their values resolved, so the rest of the server's
+//! environment (AWS keys, `API_KEYS`, ...) must stay out of reach.
+//!
+//! Substitution applies to every string value except:
+//! - `apiVersion`, `kind` and everything under `metadata`, which identify
+//!   the rule and must be literal;
+//! - notification channels (any mapping with a `channel` key, under
+//!   `notifications` or `escalation`). Their secrets are resolved by the
+//!   channel at send time, so they stay out of loaded rules and API
+//!   responses, and a rule whose channel isn't configured still loads.
+//!
+//! Mapping keys are never substituted. `extends` inheritance merges the
+//! preprocessed values, so a child sees its parent's fragments expanded.
+
+use serde_yaml::Value;
+
+use crate::schema::RuleEnvelope;
+
+use super::error::{Result, RuleError};
+
+/// Prefix of top-level keys holding shared fragments.
+const FRAGMENT_PREFIX: &str = "x-";
+
+/// Prefix of the environment variables rules may reference.
+pub const RULE_ENV_PREFIX: &str = "STUPID_RULE_";
+
+/// Top-level fields that are never substituted.
+const LITERAL_TOP_LEVEL: &[&str] = &["apiVersion", "kind", "metadata"];
+
+/// One step in the path to a value inside a YAML document.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PathSegment {
+    Key(Value),
+    Index(usize),
+}
+
+/// A string value rewritten by `${VAR}` substitution.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Substitution {
+    pub path: Vec<PathSegment>,
+    /// The value as written in the file.
+    pub template: String,
+    /// The value after substitution.
+    pub resolved: String,
+}
+
+/// Parse a rule file's contents into its envelope, with merge keys applied,
+/// fragments dropped and environment variables substituted.
+pub(crate) fn parse_rule(contents: &str) -> Result<RuleEnvelope> {
+    let (value, _) = preprocess(contents, |name| std::env::var(name).ok())?;
+    Ok(serde_yaml::from_value(value)?)
+}
+
+/// Parse a rule submitted through the API the way [`parse_rule`] would load
+/// it, failing on an undefined or disallowed variable, but with `${VAR}`
+/// placeholders kept as written so resolved values are neither stored nor
+/// returned.
+pub fn parse_submitted_rule(contents: &str) -> Result<RuleEnvelope> {
+    let (mut value, substitutions) = preprocess(contents, |name| std::env::var(name).ok())?;
+    restore_placeholders(&mut value, &substitutions);
+    Ok(serde_yaml::from_value(value)?)
+}
+
+/// Preprocess `contents`, looking variables up with `env`. Returns the
+/// expanded document and the substitutions made in it.
+pub(crate) fn preprocess(
+    contents: &str,
+    env: impl Fn(&str) -> Option<String>,
+) -> Result<(Value, Vec<Substitution>)> {
+    let mut value: Value = serde_yaml::from_str(contents)?;
+    value.apply_merge()?;
+
+    let mut substitutions = Vec::new();
+    if let Value::Mapping(map) = &mut value {
+        map.retain(|key, _| !key.as_str().is_some_and(|k| k.starts_with(FRAGMENT_PREFIX)));
+        for (key, child) in map.iter_mut() {
+            if key.as_str().is_some_and(|k| LITERAL_TOP_LEVEL.contains(&k)) {
+                continue;
+            }
+            let mut path = vec![PathSegment::Key(key.clone())];
+            substitute(child, &mut path, &env, &mut substitutions)?;
+        }
+    }
+    Ok((value, substitutions))
+}
+
+/// Put the file's placeholders back into `value` wherever it still holds
+/// the value they resolved to, so rewriting a loaded rule doesn't persist
+/// secrets that were substituted into it.
+pub(crate) fn restore_placeholders(value: &mut Value, substitutions: &[Substitution]) {
+    for sub in substitutions {
+        let mut target = Some(&mut *value);
+        for segment in &sub.path {
+            target = match (target, segment) {
+                (Some(Value::Mapping(map)), PathSegment::Key(key)) => map.get_mut(key),
+                (Some(Value::Sequence(seq)), PathSegment::Index(i)) => seq.get_mut(*i),
+                _ => None,
+            };
+        }
+        if let Some(target) = target {
+            if target.as_str() == Some(sub.resolved.as_str()) {
+                *target = Value::String(sub.template.clone());
+            }
+        }
+    }
+}
+
+fn substitute(
+    value: &mut Value,
+    path: &mut Vec<PathSegment>,
+    env: &impl Fn(&str) -> Option<String>,
+    substitutions: &mut Vec<Substitution>,
+) -> Result<()> {
+    match value {
+        Value::String(s) => {
+            let resolved = expand(s, path, env)?;
+            if resolved != *s {
+                substitutions.push(Substitution {
+                    path: path.clone(),
+                    template: std::mem::replace(s, resolved.clone()),
+                    resolved,
+                });
+            }
+        }
+        Value::Sequence(seq) => {
+            for (i, item) in seq.iter_mut().enumerate() {
+                path.push(PathSegment::Index(i));
+                substitute(item, path, env, substitutions)?;
+                path.pop();
+            }
+        }
+        // Notification channel: resolved at send time.
+        Value::Mapping(map) if map.contains_key("channel") => {}
+        Value::Mapping(map) => {
+            for (key, child) in map.iter_mut() {
+                path.push(PathSegment::Key(key.clone()));
+                substitute(child, path, env, substitutions)?;
+                path.pop();
+            }
+        }
+        Value::Tagged(tagged) => substitute(&mut tagged.value, path, env, substitutions)?,
+        Value::Null | Value::Bool(_) | Value::Number(_) => {}
+    }
+    Ok(())
+}
+
+/// Replace each `${VAR}` in `input`; `$${` is a literal `${`.
+fn expand(input: &str, path: &[PathSegment], env: &impl Fn(&str) -> Option<String>) -> Result<String> {
+    let mut result = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("${");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        result.push_str(&rest[..start]);
+        let Some(len) = rest[start + 2..].find('}') else {
+            return Err(RuleError::Validation(format!(
+                "unclosed '${{' in {}: {}",
+                display_path(path),
+                input
+            )));
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        if !name.starts_with(RULE_ENV_PREFIX) {
+            return Err(RuleError::DisallowedEnvVar {
+                var: name.to_string(),
+                field: display_path(path),
+            });
+        }
+        let value = env(name).ok_or_else(|| RuleError::UndefinedEnvVar {
+            var: name.to_string(),
+            field: display_path(path),
+        })?;
+        result.push_str(&value);
+        rest = &rest[start + 2 + len + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+/// Render a path like `notifications[0].bot_token`.
+fn display_path(path: &[PathSegment]) -> String {
+    let mut out = String::new();
+    for segment in path {
+        match segment {
+            PathSegment::Key(key) => {
+                if !out.is_empty() {
+                    out.push('.');
+                }
+                match key.as_str() {
+                    Some(k) => out.push_str(k),
+                    None => out.push_str(&format!("{:?}", key)),
+                }
+            }
+            PathSegment::Index(i) => out.push_str(&format!("[{}]", i)),
+        }
+    }
+    out
+}
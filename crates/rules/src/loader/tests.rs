@@ -456,3 +456,180 @@ fn resolve_extends_no_extends_passthrough() {
     let resolved = resolve_extends(&raw).unwrap();
     assert_eq!(resolved.get("standalone").unwrap(), &rule);
 }
+
+// ── Preprocessing tests ─────────────────────────────────────────
+
+const ANCHORED_RULE_YAML: &str = r#"
+apiVersion: v1
+kind: AnomalyRule
+x-member-filters: &member-filters
+  entity_types: [Member]
+  min_score: 0.5
+x-ops-hook: &ops-hook
+  channel: webhook
+  url: "https://hooks.example.com/ops"
+  method: POST
+metadata:
+  id: anchored-rule
+  name: Anchored Rule
+  enabled: true
+schedule:
+  cron: "*/15 * * * *"
+detection:
+  template: spike
+  params:
+    feature: login_count
+    multiplier: 3.0
+filters: *member-filters
+notifications:
+  - <<: *ops-hook
+    on: [trigger]
+  - <<: *ops-hook
+    url: "https://hooks.example.com/oncall"
+"#;
+
+#[test]
+fn anchored_fragments_are_merged() {
+    let (dir, loader) = temp_loader();
+    let path = dir.path().join("anchored-rule.yml");
+    fs::write(&path, ANCHORED_RULE_YAML).unwrap();
+
+    let doc = loader.load_file(&path).unwrap();
+    let rule = doc.as_anomaly().unwrap();
+    let filters = rule.filters.as_ref().unwrap();
+    assert_eq!(filters.min_score, Some(0.5));
+
+    assert_eq!(rule.notifications.len(), 2);
+    assert_eq!(rule.notifications[0].url.as_deref(), Some("https://hooks.example.com/ops"));
+    assert_eq!(rule.notifications[0].method.as_deref(), Some("POST"));
+    // Keys set alongside the merge key override the fragment.
+    assert_eq!(rule.notifications[1].url.as_deref(), Some("https://hooks.example.com/oncall"));
+    assert_eq!(rule.notifications[1].method.as_deref(), Some("POST"));
+}
+
+#[test]
+fn extends_inherits_merged_fragments() {
+    let env = |_: &str| None;
+    let (parent, _) = preprocess::preprocess(
+        "x-base: &base\n  a: 1\n  b: 2\nmetadata:\n  id: parent\n  name: Parent\n  enabled: true\nspec:\n  <<: *base\n  c: 3",
+        env,
+    )
+    .unwrap();
+    let (child, _) = preprocess::preprocess(
+        "metadata:\n  id: child\n  name: Child\n  extends: parent\n  enabled: true\nspec:\n  b: 99",
+        env,
+    )
+    .unwrap();
+
+    let mut raw = HashMap::new();
+    raw.insert("parent".to_string(), parent);
+    raw.insert("child".to_string(), child);
+
+    let resolved = resolve_extends(&raw).unwrap();
+    let spec = &resolved["child"]["spec"];
+    assert_eq!(spec["a"].as_i64(), Some(1));
+    assert_eq!(spec["b"].as_i64(), Some(99));
+    assert_eq!(spec["c"].as_i64(), Some(3));
+    assert!(resolved["child"].get("x-base").is_none());
+}
+
+const ENV_RULE_YAML: &str = r#"
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: env-rule
+  name: Env Rule ${NOT_SUBSTITUTED}
+  enabled: true
+schedule:
+  cron: "*/15 * * * *"
+detection:
+  template: spike
+  params:
+    feature: login_count
+    multiplier: 3.0
+  enrich:
+    opensearch:
+      query:
+        index: "${STUPID_RULE_TEST_INDEX}"
+        match: { note: "cost $${price}" }
+notifications:
+  - channel: telegram
+    bot_token: "${TELEGRAM_BOT_TOKEN}"
+    chat_id: "${TELEGRAM_CHAT_ID}"
+"#;
+
+fn enrich_query(doc: &RuleDocument) -> &serde_json::Value {
+    &doc.as_anomaly().unwrap().detection.enrich.as_ref().unwrap().opensearch.as_ref().unwrap().query
+}
+
+#[test]
+fn env_vars_substituted_at_load() {
+    let (dir, loader) = temp_loader();
+    let path = dir.path().join("env-rule.yml");
+    fs::write(&path, ENV_RULE_YAML).unwrap();
+
+    std::env::set_var("STUPID_RULE_TEST_INDEX", "events-prod");
+    let doc = loader.load_file(&path).unwrap();
+    std::env::remove_var("STUPID_RULE_TEST_INDEX");
+
+    let query = enrich_query(&doc);
+    assert_eq!(query["index"], "events-prod");
+    assert_eq!(query["match"]["note"], "cost ${price}");
+    // Metadata and notification channels stay as written.
+    let rule = doc.as_anomaly().unwrap();
+    assert_eq!(rule.metadata.name, "Env Rule ${NOT_SUBSTITUTED}");
+    assert_eq!(rule.notifications[0].bot_token.as_deref(), Some("${TELEGRAM_BOT_TOKEN}"));
+}
+
+#[test]
+fn undefined_env_var_fails_load() {
+    let (dir, loader) = temp_loader();
+    let path = dir.path().join("env-rule.yml");
+    fs::write(&path, ENV_RULE_YAML.replace("STUPID_RULE_TEST_INDEX", "STUPID_RULE_TEST_UNSET")).unwrap();
+
+    let err = loader.load_file(&path).unwrap_err();
+    assert!(matches!(
+        &err,
+        RuleError::UndefinedEnvVar { var, field }
+            if var == "STUPID_RULE_TEST_UNSET" && field == "detection.enrich.opensearch.query.index"
+    ));
+    assert_eq!(
+        err.to_string(),
+        "Undefined environment variable 'STUPID_RULE_TEST_UNSET' referenced in \
+         detection.enrich.opensearch.query.index"
+    );
+}
+
+#[test]
+fn only_rule_env_vars_are_substituted() {
+    // Even a variable that is set can't be read unless it has the prefix.
+    let env = |_: &str| Some("s3cr3t".to_string());
+    let yaml = ENV_RULE_YAML.replace("STUPID_RULE_TEST_INDEX", "AWS_SECRET_ACCESS_KEY");
+    let err = preprocess::preprocess(&yaml, env).unwrap_err();
+    assert!(matches!(
+        &err,
+        RuleError::DisallowedEnvVar { var, field }
+            if var == "AWS_SECRET_ACCESS_KEY" && field == "detection.enrich.opensearch.query.index"
+    ));
+
+    let submitted = preprocess::parse_submitted_rule(&ENV_RULE_YAML.replace("STUPID_RULE_TEST_INDEX", "API_KEYS"));
+    assert!(matches!(submitted, Err(RuleError::DisallowedEnvVar { var, .. }) if var == "API_KEYS"));
+}
+
+#[test]
+fn rewriting_rule_keeps_env_placeholders() {
+    let (dir, loader) = temp_loader();
+    let path = dir.path().join("env-rule.yml");
+    fs::write(&path, ENV_RULE_YAML.replace("STUPID_RULE_TEST_INDEX", "STUPID_RULE_TEST_REWRITE_INDEX")).unwrap();
+
+    std::env::set_var("STUPID_RULE_TEST_REWRITE_INDEX", "events-staging");
+    let mut doc = loader.load_file(&path).unwrap();
+    doc.metadata_mut().enabled = false;
+    loader.write_document(&doc).unwrap();
+    std::env::remove_var("STUPID_RULE_TEST_REWRITE_INDEX");
+
+    let written = fs::read_to_string(&path).unwrap();
+    assert!(written.contains("${STUPID_RULE_TEST_REWRITE_INDEX}"), "{written}");
+    assert!(!written.contains("events-staging"));
+    assert!(!loader.documents().read().unwrap()["env-rule"].metadata().enabled);
+}
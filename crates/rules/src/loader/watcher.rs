@@ -9,7 +9,9 @@ use notify::event::{CreateKind, ModifyKind, RemoveKind};
 use notify::{Event, EventKind};
 use tracing::{info, warn};
 
-use crate::schema::{AnomalyRule, RuleDocument, RuleEnvelope};
+use crate::schema::{AnomalyRule, RuleDocument};
+
+use super::preprocess::parse_rule;
 
 /// Handle a single filesystem event from the notify watcher.
 pub(super) fn handle_fs_event(
@@ -44,7 +46,7 @@ pub(super) fn handle_fs_event(
                 // File created or modified: two-pass parse and upsert.
                 match fs::read_to_string(path) {
                     Ok(contents) => {
-                        match serde_yaml::from_str::<RuleEnvelope>(&contents)
+                        match parse_rule(&contents)
                             .map_err(|e| e.to_string())
                             .and_then(|env| env.parse_full())
                         {
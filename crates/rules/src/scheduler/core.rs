@@ -7,9 +7,11 @@ use chrono::{DateTime, Utc};
 use cron::Schedule;
 use tracing::{debug, warn};
 
-use crate::schema::AnomalyRule;
+use crate::schema::{AnomalyRule, CooldownScope};
+use crate::templates::RuleMatch;
 
 use super::cron::{is_cron_due, normalize_cron, parse_cooldown};
+use super::entity::EntityCooldowns;
 use super::entry::RuleScheduleEntry;
 
 /// Manages scheduling state for all loaded anomaly rules.
@@ -32,9 +34,14 @@ impl RuleScheduler {
     /// Synchronize scheduling entries with the current set of loaded rules.
     ///
     /// - Adds entries for new rules.
-    /// - Updates cron/cooldown/enabled for changed rules (preserves `last_triggered`).
+    /// - Updates cron/cooldown/enabled for changed rules (preserves `last_triggered`
+    ///   and per-entity trigger times).
     /// - Removes entries for rules no longer present.
+    ///
+    /// Shadow rules (`shadow_of` set) are skipped; they only run alongside
+    /// their live rule.
     pub fn sync_rules(&mut self, rules: &[AnomalyRule]) {
+        let rules: Vec<&AnomalyRule> = rules.iter().filter(|r| r.shadow_of.is_none()).collect();
         let current_ids: std::collections::HashSet<&str> =
             rules.iter().map(|r| r.metadata.id.as_str()).collect();
 
@@ -58,6 +65,7 @@ impl RuleScheduler {
                     entry.cron_expression = cron_expr;
                     entry.timezone = rule.schedule.timezone.clone();
                     entry.cooldown = cooldown;
+                    entry.cooldown_scope = rule.schedule.cooldown_scope;
                     entry.enabled = rule.metadata.enabled;
                 }
                 None => {
@@ -68,7 +76,9 @@ impl RuleScheduler {
                             cron_expression: cron_expr,
                             timezone: rule.schedule.timezone.clone(),
                             cooldown,
+                            cooldown_scope: rule.schedule.cooldown_scope,
                             last_triggered: None,
+                            entity_triggers: EntityCooldowns::default(),
                             enabled: rule.metadata.enabled,
                         },
                     );
@@ -80,8 +90,9 @@ impl RuleScheduler {
     /// Check whether a single rule should run at the given instant.
     ///
     /// Returns `false` if the rule is unknown, disabled, its cron expression
-    /// is invalid, the cron window has not arrived, or the cooldown has not
-    /// elapsed since the last trigger.
+    /// is invalid, the cron window has not arrived, or a rule-scoped cooldown
+    /// has not elapsed since the last trigger. Entity-scoped cooldowns never
+    /// block the rule itself; see [`filter_entity_cooldown`](Self::filter_entity_cooldown).
     pub fn should_run(&self, rule_id: &str, now: DateTime<Utc>) -> bool {
         let entry = match self.entries.get(rule_id) {
             Some(e) => e,
@@ -93,7 +104,11 @@ impl RuleScheduler {
         }
 
         // Check cooldown first (cheaper than cron parse).
-        if let (Some(cooldown), Some(last)) = (entry.cooldown, entry.last_triggered) {
+        let rule_cooldown = match entry.cooldown_scope {
+            CooldownScope::Rule => entry.cooldown,
+            CooldownScope::Entity => None,
+        };
+        if let (Some(cooldown), Some(last)) = (rule_cooldown, entry.last_triggered) {
             let elapsed = now.signed_duration_since(last);
             if elapsed
                 < chrono::Duration::from_std(cooldown).unwrap_or(chrono::Duration::zero())
@@ -138,6 +153,41 @@ impl RuleScheduler {
         }
     }
 
+    /// Drop matches whose entity is still in cooldown for an entity-scoped
+    /// rule, and record `now` as the trigger time of every match kept.
+    ///
+    /// Matches pass through unchanged for rule-scoped, cooldown-free, or
+    /// unknown rules.
+    pub fn filter_entity_cooldown(
+        &mut self,
+        rule_id: &str,
+        matches: Vec<RuleMatch>,
+        now: DateTime<Utc>,
+    ) -> Vec<RuleMatch> {
+        let Some(entry) = self.entries.get_mut(rule_id) else {
+            return matches;
+        };
+        let cooldown = match (entry.cooldown_scope, entry.cooldown) {
+            (CooldownScope::Entity, Some(cooldown)) => {
+                chrono::Duration::from_std(cooldown).unwrap_or(chrono::Duration::zero())
+            }
+            _ => return matches,
+        };
+
+        let before = matches.len();
+        let kept = entry.entity_triggers.filter(matches, cooldown, now);
+        if kept.len() < before {
+            debug!(
+                rule_id = %rule_id,
+                "{} matched entities still in cooldown",
+                before - kept.len(),
+            );
+        }
+
+        entry.entity_triggers.record(kept.iter().map(|m| m.entity_key.as_str()), now);
+        kept
+    }
+
     /// Return the IDs of all rules that should run at the given instant.
     pub fn due_rules(&self, now: DateTime<Utc>) -> Vec<&str> {
         self.entries
@@ -0,0 +1,61 @@
+//! Per-entity trigger times for entity-scoped cooldowns.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+
+use crate::templates::RuleMatch;
+
+/// Maximum entity trigger timestamps kept per rule.
+const MAX_ENTITY_COOLDOWNS: usize = 10_000;
+
+/// When each entity of one rule last triggered.
+///
+/// Bounded by `MAX_ENTITY_COOLDOWNS` (10,000); the oldest entries are
+/// evicted first.
+#[derive(Debug, Clone, Default)]
+pub struct EntityCooldowns {
+    triggers: HashMap<String, DateTime<Utc>>,
+}
+
+impl EntityCooldowns {
+    /// Drop matches whose entity triggered less than `cooldown` before `now`.
+    pub fn filter(&mut self, matches: Vec<RuleMatch>, cooldown: chrono::Duration, now: DateTime<Utc>) -> Vec<RuleMatch> {
+        // Expired timestamps no longer suppress anything.
+        self.triggers.retain(|_, last| now.signed_duration_since(*last) < cooldown);
+        matches
+            .into_iter()
+            .filter(|m| !self.triggers.contains_key(&m.entity_key))
+            .collect()
+    }
+
+    /// Record `now` as the trigger time of `entity_keys`.
+    pub fn record<'a>(&mut self, entity_keys: impl IntoIterator<Item = &'a str>, now: DateTime<Utc>) {
+        for key in entity_keys {
+            self.triggers.insert(key.to_string(), now);
+        }
+        if self.triggers.len() > MAX_ENTITY_COOLDOWNS {
+            let mut by_age: Vec<(DateTime<Utc>, String)> =
+                self.triggers.iter().map(|(key, at)| (*at, key.clone())).collect();
+            by_age.sort();
+            let excess = by_age.len() - MAX_ENTITY_COOLDOWNS;
+            for (_, key) in by_age.into_iter().take(excess) {
+                self.triggers.remove(&key);
+            }
+        }
+    }
+
+    /// Whether `entity_key` has a recorded trigger time.
+    pub fn contains(&self, entity_key: &str) -> bool {
+        self.triggers.contains_key(entity_key)
+    }
+
+    /// Number of entities with a recorded trigger time.
+    pub fn len(&self) -> usize {
+        self.triggers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.triggers.is_empty()
+    }
+}
@@ -4,6 +4,10 @@ use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 
+use crate::schema::CooldownScope;
+
+use super::entity::EntityCooldowns;
+
 /// Scheduling state for a single rule.
 #[derive(Debug, Clone)]
 pub struct RuleScheduleEntry {
@@ -15,8 +19,12 @@ pub struct RuleScheduleEntry {
     pub timezone: String,
     /// Minimum interval between successive triggers.
     pub cooldown: Option<Duration>,
+    /// Whether `cooldown` applies to the whole rule or to each entity.
+    pub cooldown_scope: CooldownScope,
     /// Timestamp of the last successful trigger.
     pub last_triggered: Option<DateTime<Utc>>,
+    /// Last trigger time per entity key, for entity-scoped cooldowns.
+    pub entity_triggers: EntityCooldowns,
     /// Whether the rule is enabled for evaluation.
     pub enabled: bool,
 }
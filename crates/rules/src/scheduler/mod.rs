@@ -10,6 +10,7 @@
 
 mod core;
 pub(crate) mod cron;
+mod entity;
 mod entry;
 
 #[cfg(test)]
@@ -17,4 +18,5 @@ mod tests;
 
 pub use self::core::RuleScheduler;
 pub use self::cron::parse_cooldown;
+pub use self::entity::EntityCooldowns;
 pub use self::entry::RuleScheduleEntry;
@@ -8,9 +8,12 @@ mod tests {
     use chrono::Utc;
     use cron::Schedule;
 
-    use crate::schema::{AnomalyRule, CommonMetadata, Detection, Schedule as RuleSchedule};
+    use crate::schema::{
+        AnomalyRule, CommonMetadata, CooldownScope, Detection, Schedule as RuleSchedule,
+    };
     use crate::scheduler::cron::{is_cron_due, normalize_cron, parse_cooldown};
     use crate::scheduler::RuleScheduler;
+    use crate::templates::RuleMatch;
 
     /// Helper to build a minimal AnomalyRule for testing.
     fn make_rule(id: &str, cron: &str, cooldown: Option<&str>, enabled: bool) -> AnomalyRule {
@@ -29,6 +32,7 @@ mod tests {
                 cron: cron.to_string(),
                 timezone: "UTC".to_string(),
                 cooldown: cooldown.map(String::from),
+                cooldown_scope: CooldownScope::Rule,
             },
             detection: Detection {
                 template: None,
@@ -38,6 +42,9 @@ mod tests {
             },
             filters: None,
             notifications: vec![],
+            max_notify_entities: None,
+            escalation: None,
+            shadow_of: None,
         }
     }
 
@@ -206,6 +213,17 @@ mod tests {
         assert!(sched.get("r2").is_some());
     }
 
+    #[test]
+    fn sync_rules_skips_shadow_rules() {
+        let mut sched = RuleScheduler::new();
+        let mut candidate = make_rule("r1-v2", "* * * * *", None, true);
+        candidate.shadow_of = Some("r1".to_string());
+        sched.sync_rules(&[make_rule("r1", "* * * * *", None, true), candidate]);
+
+        assert_eq!(sched.len(), 1);
+        assert!(sched.get("r1-v2").is_none());
+    }
+
     #[test]
     fn sync_rules_removes_deleted_rules() {
         let mut sched = RuleScheduler::new();
@@ -296,6 +314,105 @@ mod tests {
         sched.record_trigger("nonexistent");
     }
 
+    // -- entity-scoped cooldown --------------------------------------------
+
+    fn make_entity_rule(id: &str, cooldown: &str) -> AnomalyRule {
+        let mut rule = make_rule(id, "* * * * *", Some(cooldown), true);
+        rule.schedule.cooldown_scope = CooldownScope::Entity;
+        rule
+    }
+
+    fn matches_for(keys: &[&str]) -> Vec<RuleMatch> {
+        keys.iter()
+            .map(|key| RuleMatch {
+                entity_id: key.to_string(),
+                entity_key: key.to_string(),
+                entity_type: "Member".to_string(),
+                score: 1.0,
+                signals: vec![],
+                matched_reason: "test".to_string(),
+            })
+            .collect()
+    }
+
+    fn keys(matches: &[RuleMatch]) -> Vec<&str> {
+        matches.iter().map(|m| m.entity_key.as_str()).collect()
+    }
+
+    #[test]
+    fn entity_cooldown_does_not_block_rule() {
+        let mut sched = RuleScheduler::new();
+        sched.sync_rules(&[make_entity_rule("r1", "30m")]);
+
+        let now = Utc::now();
+        sched.record_trigger_at("r1", now);
+        assert!(sched.should_run("r1", now + chrono::Duration::minutes(5)));
+    }
+
+    #[test]
+    fn entity_cooldown_suppresses_repeat_entity() {
+        let mut sched = RuleScheduler::new();
+        sched.sync_rules(&[make_entity_rule("r1", "30m")]);
+
+        let now = Utc::now();
+        let first = sched.filter_entity_cooldown("r1", matches_for(&["M1", "M2"]), now);
+        assert_eq!(keys(&first), vec!["M1", "M2"]);
+
+        let later = now + chrono::Duration::minutes(5);
+        let second = sched.filter_entity_cooldown("r1", matches_for(&["M1"]), later);
+        assert!(second.is_empty());
+
+        // The cooldown runs from the entity's last notification, not the rule's.
+        let expired = now + chrono::Duration::minutes(31);
+        let third = sched.filter_entity_cooldown("r1", matches_for(&["M1", "M2"]), expired);
+        assert_eq!(keys(&third), vec!["M1", "M2"]);
+    }
+
+    #[test]
+    fn entity_cooldown_passes_new_entities() {
+        let mut sched = RuleScheduler::new();
+        sched.sync_rules(&[make_entity_rule("r1", "30m")]);
+
+        let now = Utc::now();
+        sched.filter_entity_cooldown("r1", matches_for(&["M1"]), now);
+
+        let later = now + chrono::Duration::minutes(5);
+        let kept = sched.filter_entity_cooldown("r1", matches_for(&["M1", "M3", "M4"]), later);
+        assert_eq!(keys(&kept), vec!["M3", "M4"]);
+
+        // Newly notified entities start their own cooldown.
+        let kept = sched.filter_entity_cooldown("r1", matches_for(&["M3"]), later);
+        assert!(kept.is_empty());
+    }
+
+    #[test]
+    fn rule_scoped_cooldown_keeps_all_matches() {
+        let mut sched = RuleScheduler::new();
+        sched.sync_rules(&[make_rule("r1", "* * * * *", Some("30m"), true)]);
+
+        let now = Utc::now();
+        sched.filter_entity_cooldown("r1", matches_for(&["M1"]), now);
+        let kept = sched.filter_entity_cooldown("r1", matches_for(&["M1"]), now);
+        assert_eq!(keys(&kept), vec!["M1"]);
+        assert!(sched.get("r1").unwrap().entity_triggers.is_empty());
+    }
+
+    #[test]
+    fn entity_cooldown_store_is_bounded() {
+        let mut sched = RuleScheduler::new();
+        sched.sync_rules(&[make_entity_rule("r1", "1h")]);
+
+        let now = Utc::now();
+        sched.filter_entity_cooldown("r1", matches_for(&["oldest"]), now);
+        let many: Vec<String> = (0..10_000).map(|i| format!("M{i}")).collect();
+        let many: Vec<&str> = many.iter().map(String::as_str).collect();
+        sched.filter_entity_cooldown("r1", matches_for(&many), now + chrono::Duration::seconds(1));
+
+        let entry = sched.get("r1").unwrap();
+        assert_eq!(entry.entity_triggers.len(), 10_000);
+        assert!(!entry.entity_triggers.contains("oldest"));
+    }
+
     // -- is_cron_due -------------------------------------------------------
 
     #[test]
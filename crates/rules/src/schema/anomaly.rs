@@ -2,7 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 
-use super::{CommonMetadata, Composition, Enrichment, Filters, NotificationChannel};
+use super::{CommonMetadata, Composition, Enrichment, EscalationPolicy, Filters, NotificationChannel};
 
 /// Top-level anomaly rule definition parsed from YAML.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -17,6 +17,18 @@ pub struct AnomalyRule {
     pub filters: Option<Filters>,
     #[serde(default)]
     pub notifications: Vec<NotificationChannel>,
+    /// Above this many matches a run sends one summary notification instead
+    /// of per-entity ones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_notify_entities: Option<usize>,
+    /// Further channels notified when a trigger goes unacknowledged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<EscalationPolicy>,
+    /// ID of the live rule this one is a candidate replacement for. A
+    /// shadow rule is never scheduled or notified on its own; it is
+    /// evaluated alongside the live rule and the match sets are compared.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_of: Option<String>,
 }
 
 /// Cron-based execution schedule with timezone and cooldown.
@@ -28,6 +40,32 @@ pub struct Schedule {
     pub timezone: String,
     #[serde(default)]
     pub cooldown: Option<String>,
+    /// Whether `cooldown` silences the whole rule or each matched entity.
+    #[serde(default)]
+    pub cooldown_scope: CooldownScope,
+}
+
+/// What a schedule cooldown applies to.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CooldownScope {
+    /// The rule does not run again until the cooldown elapses.
+    #[default]
+    Rule,
+    /// The rule keeps running; an entity that matched is suppressed until
+    /// its own cooldown elapses, while newly matched entities pass through.
+    Entity,
+}
+
+impl Schedule {
+    /// The cooldown, when it applies to each matched entity; `None` for
+    /// rule-scoped or unparseable cooldowns.
+    pub fn entity_cooldown(&self) -> Option<std::time::Duration> {
+        match self.cooldown_scope {
+            CooldownScope::Entity => crate::scheduler::parse_cooldown(self.cooldown.as_deref()?),
+            CooldownScope::Rule => None,
+        }
+    }
 }
 
 fn default_timezone() -> String {
@@ -55,6 +93,8 @@ pub enum DetectionTemplate {
     Drift,
     Absence,
     Threshold,
+    NewEntity,
+    Expression,
 }
 
 // ── Template parameters ──────────────────────────────────────────────
@@ -105,6 +145,25 @@ pub struct ThresholdParams {
     pub value: f64,
 }
 
+/// Parameters for the `new_entity` detection template.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct NewEntityParams {
+    pub feature: String,
+    pub min_value: f64,
+    /// How recently the entity must have been first seen, e.g. "24h" or "7d".
+    pub max_age: String,
+}
+
+/// Parameters for the `expression` detection template.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ExpressionParams {
+    /// Condition over feature names, e.g.
+    /// `(login_count / session_count) > 10 and error_count > 0`.
+    pub expression: String,
+}
+
 /// Comparison operators for threshold detection.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -147,4 +206,18 @@ impl Detection {
             .as_ref()
             .map(|v| serde_yaml::from_value(v.clone()))
     }
+
+    /// Parse new-entity detection parameters from the `params` field.
+    pub fn parse_new_entity_params(&self) -> Option<Result<NewEntityParams, serde_yaml::Error>> {
+        self.params
+            .as_ref()
+            .map(|v| serde_yaml::from_value(v.clone()))
+    }
+
+    /// Parse expression detection parameters from the `params` field.
+    pub fn parse_expression_params(&self) -> Option<Result<ExpressionParams, serde_yaml::Error>> {
+        self.params
+            .as_ref()
+            .map(|v| serde_yaml::from_value(v.clone()))
+    }
 }
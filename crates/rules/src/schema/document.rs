@@ -5,7 +5,7 @@ use super::{AnomalyRule, CommonMetadata, RuleKind};
 /// A fully deserialized rule of any supported kind.
 #[derive(Debug, Clone, PartialEq)]
 pub enum RuleDocument {
-    /// Anomaly detection rule (spike, drift, absence, threshold, compose).
+    /// Anomaly detection rule (spike, drift, absence, threshold, new_entity, expression, compose).
     Anomaly(AnomalyRule),
     /// Entity schema -- field mappings, extraction plans, embedding templates.
     EntitySchema(crate::entity_schema::EntitySchemaRule),
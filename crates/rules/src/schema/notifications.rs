@@ -42,6 +42,24 @@ pub struct NotificationChannel {
     pub parse_mode: Option<String>,
 }
 
+/// Escalation of a trigger nobody acknowledged, e.g. Slack first, then
+/// PagerDuty if the alert is still unacknowledged after 15 minutes.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct EscalationPolicy {
+    /// How long a trigger may go unacknowledged, e.g. "15m" or "1h30m".
+    pub after: String,
+    /// Channels notified once `after` elapses without an acknowledgement.
+    pub notifications: Vec<NotificationChannel>,
+}
+
+impl EscalationPolicy {
+    /// The acknowledgement window; `None` if `after` doesn't parse.
+    pub fn window(&self) -> Option<std::time::Duration> {
+        crate::scheduler::parse_cooldown(&self.after)
+    }
+}
+
 fn default_on_events() -> Vec<NotifyEvent> {
     vec![NotifyEvent::Trigger]
 }
@@ -33,12 +33,31 @@ pub struct ScoringConfigSpec {
     /// Default anomaly threshold for cluster-based z-score scoring.
     #[serde(default = "default_anomaly_threshold")]
     pub default_anomaly_threshold: f64,
+    /// Z-scores at which an anomalous member's insight is raised in severity.
+    #[serde(default)]
+    pub z_score_severity: ZScoreSeverity,
 }
 
 fn default_anomaly_threshold() -> f64 {
     2.0
 }
 
+/// Insight severity cutoffs for cluster z-scores above the anomaly
+/// threshold: above `critical` is Critical, above `warning` is Warning,
+/// anything lower is Info.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ZScoreSeverity {
+    pub warning: f64,
+    pub critical: f64,
+}
+
+impl Default for ZScoreSeverity {
+    fn default() -> Self {
+        Self { warning: 3.0, critical: 4.0 }
+    }
+}
+
 /// Weights for the four multi-signal anomaly detectors.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
@@ -50,9 +69,16 @@ pub struct MultiSignalWeights {
 }
 
 /// Classification thresholds — ascending boundaries for Normal/Mild/Anomalous/HighlyAnomalous.
+///
+/// In `absolute` mode the boundaries are scores; in `percentile` mode they
+/// are fractions of the current population (e.g. `highly_anomalous: 0.99`
+/// labels the top 1% HighlyAnomalous), so labels follow distribution shifts.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
 pub struct ClassificationThresholds {
+    /// How the boundaries below are interpreted.
+    #[serde(default)]
+    pub mode: ClassificationMode,
     /// Scores below this are Normal.
     pub mild: f64,
     /// Scores between mild and this are Mild.
@@ -61,6 +87,17 @@ pub struct ClassificationThresholds {
     pub highly_anomalous: f64,
 }
 
+/// Interpretation of [`ClassificationThresholds`] boundaries.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ClassificationMode {
+    /// Compare each score against the boundaries directly.
+    #[default]
+    Absolute,
+    /// Compare each score's percentile rank within the scored batch.
+    Percentile,
+}
+
 /// Z-score normalization parameters.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(deny_unknown_fields)]
@@ -154,6 +191,31 @@ spec:
         assert_eq!(rule.spec.multi_signal_weights.statistical, 0.25);
     }
 
+    #[test]
+    fn percentile_mode_parses_and_defaults_to_absolute() {
+        let yaml = include_str!("../../../data/rules/scoring/scoring-config.yml");
+        let rule: ScoringConfigRule = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(rule.spec.classification_thresholds.mode, ClassificationMode::Absolute);
+
+        let thresholds: ClassificationThresholds = serde_yaml::from_str(
+            "mode: percentile\nmild: 0.9\nanomalous: 0.95\nhighly_anomalous: 0.99\n",
+        )
+        .unwrap();
+        assert_eq!(thresholds.mode, ClassificationMode::Percentile);
+        assert_eq!(thresholds.highly_anomalous, 0.99);
+    }
+
+    #[test]
+    fn z_score_severity_defaults_when_omitted() {
+        let yaml = include_str!("../../../data/rules/scoring/scoring-config.yml");
+        let (without, _) = yaml.split_once("  z_score_severity:").unwrap();
+        let rule: ScoringConfigRule = serde_yaml::from_str(without).unwrap();
+        assert_eq!(rule.spec.z_score_severity, ZScoreSeverity { warning: 3.0, critical: 4.0 });
+
+        let severity: ZScoreSeverity = serde_yaml::from_str("warning: 2.5\ncritical: 3.5\n").unwrap();
+        assert_eq!(severity.critical, 3.5);
+    }
+
     #[test]
     fn round_trip() {
         let yaml = include_str!("../../../data/rules/scoring/scoring-config.yml");
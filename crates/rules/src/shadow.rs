@@ -0,0 +1,262 @@
+//! Shadow evaluation: compare a candidate rule version against the live rule.
+//!
+//! A candidate is an anomaly rule whose `shadow_of` names the live rule it
+//! would replace. The runner evaluates it against the same data as the live
+//! rule and records a [`ShadowDiff`] in the live rule's audit log. Only the
+//! live rule's matches are acted on.
+
+use std::collections::{BTreeSet, HashMap};
+
+use serde::Serialize;
+
+use crate::audit_log::{AuditLog, ExecutionPhase, LogLevel};
+use crate::evaluator::{RuleEvaluator, SignalScores};
+use crate::schema::AnomalyRule;
+use crate::templates::{ClusterStats, EntityData, RuleMatch};
+
+/// Entity keys listed per bucket in audit log details.
+const MAX_LISTED_KEYS: usize = 50;
+
+/// How a candidate's match set differs from the live rule's, by entity key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ShadowDiff {
+    pub live_id: String,
+    pub candidate_id: String,
+    /// Entities only the live rule matched.
+    pub only_live: Vec<String>,
+    /// Entities only the candidate matched.
+    pub only_candidate: Vec<String>,
+    /// Entities both versions matched.
+    pub both: Vec<String>,
+}
+
+impl ShadowDiff {
+    /// Compare two match sets. Keys are sorted so diffs are stable.
+    pub fn compare(
+        live_id: &str,
+        candidate_id: &str,
+        live: &[RuleMatch],
+        candidate: &[RuleMatch],
+    ) -> Self {
+        let live: BTreeSet<&str> = live.iter().map(|m| m.entity_key.as_str()).collect();
+        let candidate: BTreeSet<&str> = candidate.iter().map(|m| m.entity_key.as_str()).collect();
+        let owned = |keys: std::collections::btree_set::Difference<'_, &str>| {
+            keys.map(|k| k.to_string()).collect::<Vec<_>>()
+        };
+        Self {
+            live_id: live_id.to_string(),
+            candidate_id: candidate_id.to_string(),
+            only_live: owned(live.difference(&candidate)),
+            only_candidate: owned(candidate.difference(&live)),
+            both: live.intersection(&candidate).map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Whether both versions matched exactly the same entities.
+    pub fn is_identical(&self) -> bool {
+        self.only_live.is_empty() && self.only_candidate.is_empty()
+    }
+
+    /// Record the diff in the live rule's audit log. Identical match sets
+    /// log at info, diverging ones at warning.
+    pub fn record(&self, audit_log: &AuditLog) {
+        let level = if self.is_identical() { LogLevel::Info } else { LogLevel::Warning };
+        let listed = |keys: &[String]| keys.iter().take(MAX_LISTED_KEYS).cloned().collect::<Vec<_>>();
+        audit_log.log_with_details(
+            &self.live_id,
+            level,
+            ExecutionPhase::Shadow,
+            format!(
+                "Shadow candidate '{}': {} only live, {} only candidate, {} both",
+                self.candidate_id,
+                self.only_live.len(),
+                self.only_candidate.len(),
+                self.both.len()
+            ),
+            Some(serde_json::json!({
+                "candidate_id": self.candidate_id,
+                "only_live_count": self.only_live.len(),
+                "only_candidate_count": self.only_candidate.len(),
+                "both_count": self.both.len(),
+                "only_live": listed(&self.only_live),
+                "only_candidate": listed(&self.only_candidate),
+            })),
+            None,
+        );
+    }
+}
+
+/// Enabled shadow candidates of `live_id`, ordered by ID.
+pub fn candidates_for<'a>(rules: &'a HashMap<String, AnomalyRule>, live_id: &str) -> Vec<&'a AnomalyRule> {
+    let mut candidates: Vec<&AnomalyRule> = rules
+        .values()
+        .filter(|r| r.metadata.enabled && r.shadow_of.as_deref() == Some(live_id))
+        .collect();
+    candidates.sort_by(|a, b| a.metadata.id.cmp(&b.metadata.id));
+    candidates
+}
+
+/// Evaluate every shadow candidate of `live` on the data it was evaluated
+/// on, and record each diff against `live_matches` in the audit log.
+///
+/// Candidates that fail to evaluate are logged as errors and skipped; the
+/// caller acts on `live_matches` only, whatever the candidates return.
+pub fn evaluate_shadows(
+    audit_log: &AuditLog,
+    live: &AnomalyRule,
+    live_matches: &[RuleMatch],
+    rules: &HashMap<String, AnomalyRule>,
+    entities: &HashMap<String, EntityData>,
+    cluster_stats: &HashMap<usize, ClusterStats>,
+    signal_scores: &HashMap<String, SignalScores>,
+) -> Vec<ShadowDiff> {
+    let live_id = &live.metadata.id;
+    let mut diffs = Vec::new();
+    for candidate in candidates_for(rules, live_id) {
+        match RuleEvaluator::evaluate(candidate, entities, cluster_stats, signal_scores) {
+            Ok(candidate_matches) => {
+                let diff = ShadowDiff::compare(live_id, &candidate.metadata.id, live_matches, &candidate_matches);
+                diff.record(audit_log);
+                diffs.push(diff);
+            }
+            Err(e) => audit_log.log(
+                live_id,
+                LogLevel::Error,
+                ExecutionPhase::Shadow,
+                format!("Shadow candidate '{}' failed to evaluate: {}", candidate.metadata.id, e),
+            ),
+        }
+    }
+    diffs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit_log::LogQueryParams;
+
+    fn matches(keys: &[&str]) -> Vec<RuleMatch> {
+        keys.iter()
+            .map(|key| RuleMatch {
+                entity_id: format!("id-{}", key),
+                entity_key: key.to_string(),
+                entity_type: "Member".to_string(),
+                score: 1.0,
+                signals: vec![],
+                matched_reason: String::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn compare_splits_matches_into_buckets() {
+        let diff = ShadowDiff::compare(
+            "spike",
+            "spike-v2",
+            &matches(&["m3", "m1", "m2"]),
+            &matches(&["m4", "m2", "m3", "m5"]),
+        );
+        assert_eq!(diff.only_live, vec!["m1"]);
+        assert_eq!(diff.only_candidate, vec!["m4", "m5"]);
+        assert_eq!(diff.both, vec!["m2", "m3"]);
+        assert!(!diff.is_identical());
+        assert!(ShadowDiff::compare("a", "b", &matches(&["x"]), &matches(&["x"])).is_identical());
+    }
+
+    #[test]
+    fn record_logs_diff_under_live_rule() {
+        let log = AuditLog::new();
+        ShadowDiff::compare("spike", "spike-v2", &matches(&["m1", "m2"]), &matches(&["m2", "m3"])).record(&log);
+
+        let params = LogQueryParams { level: None, phase: Some(ExecutionPhase::Shadow), limit: None, since: None };
+        let entries = log.query("spike", &params);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, LogLevel::Warning);
+        assert_eq!(entries[0].message, "Shadow candidate 'spike-v2': 1 only live, 1 only candidate, 1 both");
+        let details = entries[0].details.as_ref().unwrap();
+        assert_eq!(details["only_live"], serde_json::json!(["m1"]));
+        assert_eq!(details["only_candidate"], serde_json::json!(["m3"]));
+        assert!(log.query("spike-v2", &params).is_empty());
+    }
+
+    fn rule(yaml: &str) -> AnomalyRule {
+        serde_yaml::from_str(yaml).unwrap()
+    }
+
+    #[test]
+    fn evaluate_shadows_diffs_two_rule_versions() {
+        let live = rule(
+            r#"
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: z-spike
+  name: Z spike
+schedule:
+  cron: "* * * * *"
+detection:
+  compose:
+    operator: and
+    conditions:
+      - signal: z_score
+        threshold: 2.0
+"#,
+        );
+        let candidate = rule(
+            r#"
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: z-spike-v2
+  name: Z spike v2
+schedule:
+  cron: "* * * * *"
+shadow_of: z-spike
+detection:
+  compose:
+    operator: and
+    conditions:
+      - signal: z_score
+        threshold: 1.0
+      - signal: dbscan_noise
+        threshold: 0.5
+"#,
+        );
+        let rules: HashMap<String, AnomalyRule> = [live.clone(), candidate]
+            .into_iter()
+            .map(|r| (r.metadata.id.clone(), r))
+            .collect();
+
+        let mut entities = HashMap::new();
+        let mut signal_scores = HashMap::new();
+        for (id, z, noise) in [("M1", 3.0, 0.8), ("M2", 1.5, 0.9), ("M3", 2.5, 0.2)] {
+            entities.insert(
+                id.to_string(),
+                EntityData {
+                    key: id.to_string(),
+                    entity_type: "Member".to_string(),
+                    features: vec![0.0; crate::templates::FEATURE_COUNT],
+                    score: 0.5,
+                    cluster_id: None,
+                    first_seen: None,
+                },
+            );
+            let scores = [("z_score", z), ("dbscan_noise", noise)];
+            signal_scores.insert(
+                id.to_string(),
+                SignalScores { scores: scores.iter().map(|(k, v)| (k.to_string(), *v)).collect() },
+            );
+        }
+
+        let log = AuditLog::new();
+        let live_matches = RuleEvaluator::evaluate(&live, &entities, &HashMap::new(), &signal_scores).unwrap();
+        let diffs = evaluate_shadows(&log, &live, &live_matches, &rules, &entities, &HashMap::new(), &signal_scores);
+
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].candidate_id, "z-spike-v2");
+        assert_eq!(diffs[0].only_live, vec!["M3"]);
+        assert_eq!(diffs[0].only_candidate, vec!["M2"]);
+        assert_eq!(diffs[0].both, vec!["M1"]);
+        assert_eq!(log.query("z-spike", &LogQueryParams { level: None, phase: None, limit: None, since: None }).len(), 1);
+    }
+}
@@ -3,14 +3,20 @@
 //! Each evaluator takes typed parameters plus a map of entity data,
 //! returning a `Vec<RuleMatch>` of entities that triggered.
 //!
-//! Templates: spike, drift, absence, threshold.
+//! Templates: spike, drift, absence, threshold, new_entity. The
+//! expression template lives in [`super::expression`].
 
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
+
+use crate::scheduler::parse_cooldown;
 use crate::schema::{
-    AbsenceParams, DetectionTemplate, DriftParams, SpikeParams, ThresholdOperator, ThresholdParams,
+    AbsenceParams, DetectionTemplate, DriftParams, ExpressionParams, NewEntityParams,
+    SpikeParams, ThresholdOperator, ThresholdParams,
 };
 
+use super::expression::evaluate_expression;
 use super::features::feature_index;
 use super::math::{compute_mean_vector, compute_population_mean, cosine_distance, euclidean_distance};
 use super::types::{ClusterStats, EntityData, RuleMatch};
@@ -48,6 +54,16 @@ pub fn evaluate_template(
                 serde_yaml::from_value(params.clone()).map_err(|e| e.to_string())?;
             Ok(evaluate_threshold(&p, entities))
         }
+        DetectionTemplate::NewEntity => {
+            let p: NewEntityParams =
+                serde_yaml::from_value(params.clone()).map_err(|e| e.to_string())?;
+            evaluate_new_entity(&p, entities, Utc::now())
+        }
+        DetectionTemplate::Expression => {
+            let p: ExpressionParams =
+                serde_yaml::from_value(params.clone()).map_err(|e| e.to_string())?;
+            evaluate_expression(&p, entities)
+        }
     }
 }
 
@@ -295,3 +311,58 @@ pub fn evaluate_threshold(
 
     matches
 }
+
+// ── New-entity evaluator ────────────────────────────────────────────
+
+/// Detect entities first seen within `max_age` of `now` whose feature value
+/// already exceeds `min_value` (e.g. bot or fraud signups).
+///
+/// Entities without a known first-seen time never match; see
+/// [`FirstSeenTracker`](super::FirstSeenTracker). Fails if `max_age` is not a
+/// duration like "24h" or "7d".
+pub fn evaluate_new_entity(
+    params: &NewEntityParams,
+    entities: &HashMap<String, EntityData>,
+    now: DateTime<Utc>,
+) -> Result<Vec<RuleMatch>, String> {
+    let max_age = parse_cooldown(&params.max_age)
+        .and_then(|d| chrono::Duration::from_std(d).ok())
+        .ok_or_else(|| format!("Invalid max_age '{}'", params.max_age))?;
+    let idx = match feature_index(&params.feature) {
+        Some(i) => i,
+        None => return Ok(Vec::new()),
+    };
+
+    let mut matches = Vec::new();
+
+    for (entity_id, data) in entities {
+        let Some(first_seen) = data.first_seen else {
+            continue;
+        };
+        let Some(&value) = data.features.get(idx) else {
+            continue;
+        };
+
+        let age = now.signed_duration_since(first_seen);
+        if age <= max_age && value > params.min_value {
+            let age_hours = age.num_seconds() as f64 / 3600.0;
+            matches.push(RuleMatch {
+                entity_id: entity_id.clone(),
+                entity_key: data.key.clone(),
+                entity_type: data.entity_type.clone(),
+                score: value,
+                signals: vec![
+                    (params.feature.clone(), value),
+                    ("min_value".to_string(), params.min_value),
+                    ("age_hours".to_string(), age_hours),
+                ],
+                matched_reason: format!(
+                    "New entity first seen {:.1}h ago (max {}) with {} {:.2} > {:.2}",
+                    age_hours, params.max_age, params.feature, value, params.min_value,
+                ),
+            });
+        }
+    }
+
+    Ok(matches)
+}
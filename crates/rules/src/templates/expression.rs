@@ -0,0 +1,527 @@
+//! Expression template: a small arithmetic + comparison DSL over features.
+//!
+//! ```text
+//! (login_count / session_count) > 10 and error_count > 0
+//! ```
+//!
+//! Supports numbers, feature names, `+ - * /`, unary `-`, parentheses,
+//! `> >= < <= == !=` and `and` / `or` / `not` (case-insensitive). The
+//! expression is parsed once into an [`Expr`] tree with feature names
+//! resolved to vector indices, so unknown features and type errors (e.g.
+//! `login_count and 1`) are rejected before any entity is evaluated.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::schema::ExpressionParams;
+
+use super::features::{feature_index, FEATURE_NAMES};
+use super::types::{EntityData, RuleMatch};
+
+/// Parsed expression tree.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    /// A feature, resolved to its index in the feature vector.
+    Feature(usize),
+    Neg(Box<Expr>),
+    Arith(Box<Expr>, ArithOp, Box<Expr>),
+    Compare(Box<Expr>, CompareOp, Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Neq,
+}
+
+impl fmt::Display for CompareOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CompareOp::Gt => ">",
+            CompareOp::Gte => ">=",
+            CompareOp::Lt => "<",
+            CompareOp::Lte => "<=",
+            CompareOp::Eq => "==",
+            CompareOp::Neq => "!=",
+        })
+    }
+}
+
+/// Whether a (sub)expression yields a number or a truth value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Number,
+    Bool,
+}
+
+impl Expr {
+    /// Parse `source` into a boolean expression over known features.
+    pub fn parse(source: &str) -> Result<Expr, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0, depth: 0, terms: 0 };
+        let expr = parser.or()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(format!("Unexpected '{}' after end of expression", token));
+        }
+        if expr.kind() != Kind::Bool {
+            return Err("Expression must be a comparison or logical condition, not a number".to_string());
+        }
+        Ok(expr)
+    }
+
+    fn kind(&self) -> Kind {
+        match self {
+            Expr::Number(_) | Expr::Feature(_) | Expr::Neg(_) | Expr::Arith(..) => Kind::Number,
+            Expr::Compare(..) | Expr::And(..) | Expr::Or(..) | Expr::Not(_) => Kind::Bool,
+        }
+    }
+
+    /// Whether the expression holds for `features`. `None` if a referenced
+    /// feature is missing or a division by zero makes it undefined.
+    pub fn matches(&self, features: &[f64]) -> Option<bool> {
+        match self {
+            Expr::Compare(lhs, op, rhs) => {
+                let (a, b) = (lhs.value(features)?, rhs.value(features)?);
+                Some(match op {
+                    CompareOp::Gt => a > b,
+                    CompareOp::Gte => a >= b,
+                    CompareOp::Lt => a < b,
+                    CompareOp::Lte => a <= b,
+                    CompareOp::Eq => (a - b).abs() < f64::EPSILON,
+                    CompareOp::Neq => (a - b).abs() >= f64::EPSILON,
+                })
+            }
+            Expr::And(lhs, rhs) => Some(lhs.matches(features)? && rhs.matches(features)?),
+            Expr::Or(lhs, rhs) => Some(lhs.matches(features)? || rhs.matches(features)?),
+            Expr::Not(inner) => Some(!inner.matches(features)?),
+            _ => None,
+        }
+    }
+
+    /// Numeric value of the expression for `features`.
+    fn value(&self, features: &[f64]) -> Option<f64> {
+        match self {
+            Expr::Number(n) => Some(*n),
+            Expr::Feature(idx) => features.get(*idx).copied(),
+            Expr::Neg(inner) => Some(-inner.value(features)?),
+            Expr::Arith(lhs, op, rhs) => {
+                let (a, b) = (lhs.value(features)?, rhs.value(features)?);
+                match op {
+                    ArithOp::Add => Some(a + b),
+                    ArithOp::Sub => Some(a - b),
+                    ArithOp::Mul => Some(a * b),
+                    ArithOp::Div if b == 0.0 => None,
+                    ArithOp::Div => Some(a / b),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The left-hand side of the first comparison, used as the match score
+    /// (e.g. the ratio in `login_count / session_count > 10`).
+    fn score_operand(&self) -> Option<&Expr> {
+        match self {
+            Expr::Compare(lhs, ..) => Some(lhs),
+            Expr::And(lhs, rhs) | Expr::Or(lhs, rhs) => lhs.score_operand().or_else(|| rhs.score_operand()),
+            Expr::Not(inner) => inner.score_operand(),
+            _ => None,
+        }
+    }
+
+    /// Feature indices referenced, in first-use order without repeats.
+    fn features(&self, out: &mut Vec<usize>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Feature(idx) => {
+                if !out.contains(idx) {
+                    out.push(*idx);
+                }
+            }
+            Expr::Neg(inner) | Expr::Not(inner) => inner.features(out),
+            Expr::Arith(lhs, _, rhs)
+            | Expr::Compare(lhs, _, rhs)
+            | Expr::And(lhs, rhs)
+            | Expr::Or(lhs, rhs) => {
+                lhs.features(out);
+                rhs.features(out);
+            }
+        }
+    }
+}
+
+// ── Tokenizer ───────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(&'static str),
+    LParen,
+    RParen,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "{}", n),
+            Token::Ident(name) => f.write_str(name),
+            Token::Op(op) => f.write_str(op),
+            Token::LParen => f.write_str("("),
+            Token::RParen => f.write_str(")"),
+        }
+    }
+}
+
+/// Operators, longest first so `>=` wins over `>`.
+const OPERATORS: [&str; 10] = [">=", "<=", "==", "!=", ">", "<", "+", "-", "*", "/"];
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = source.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c == '(' || c == ')' {
+            tokens.push(if c == '(' { Token::LParen } else { Token::RParen });
+            1
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+            let number = rest[..len].parse().map_err(|_| format!("Invalid number '{}'", &rest[..len]))?;
+            tokens.push(Token::Number(number));
+            len
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_')).unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..len].to_string()));
+            len
+        } else if let Some(op) = OPERATORS.iter().find(|op| rest.starts_with(**op)) {
+            tokens.push(Token::Op(op));
+            op.len()
+        } else {
+            return Err(format!("Unexpected character '{}'", c));
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+// ── Parser ──────────────────────────────────────────────────────────
+
+/// Deepest nesting of parentheses, `not` and unary `-` the parser accepts,
+/// so a hostile expression can't overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Most numbers and features an expression may contain. Each binary
+/// operator needs a term on either side, so this also bounds how deep a
+/// flat chain like `a + b + ...` or `x > 0 and ...` makes the tree, which
+/// evaluation and drop walk recursively.
+const MAX_TERMS: usize = 1024;
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Current nesting depth; see [`MAX_DEPTH`].
+    depth: usize,
+    /// Terms parsed so far; see [`MAX_TERMS`].
+    terms: usize,
+}
+
+impl Parser {
+    /// Parse one nested level with `parse`, failing past [`MAX_DEPTH`].
+    fn nested(&mut self, parse: impl FnOnce(&mut Self) -> Result<Expr, String>) -> Result<Expr, String> {
+        if self.depth >= MAX_DEPTH {
+            return Err(format!("Expression is nested more than {} levels deep", MAX_DEPTH));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, String> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of expression")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Consume the next token if it is the keyword `word` (any case).
+    fn keyword(&mut self, word: &str) -> bool {
+        let found = matches!(self.peek(), Some(Token::Ident(name)) if name.eq_ignore_ascii_case(word));
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+
+    /// Consume the next token if it is one of `ops`.
+    fn op(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.and()?;
+        while self.keyword("or") {
+            let rhs = self.and()?;
+            lhs = Expr::Or(Box::new(expect(lhs, Kind::Bool, "or")?), Box::new(expect(rhs, Kind::Bool, "or")?));
+        }
+        Ok(lhs)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.not()?;
+        while self.keyword("and") {
+            let rhs = self.not()?;
+            lhs = Expr::And(Box::new(expect(lhs, Kind::Bool, "and")?), Box::new(expect(rhs, Kind::Bool, "and")?));
+        }
+        Ok(lhs)
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if self.keyword("not") {
+            let inner = self.nested(Self::not)?;
+            return Ok(Expr::Not(Box::new(expect(inner, Kind::Bool, "not")?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let lhs = self.sum()?;
+        let Some(op) = self.op(&[">", ">=", "<", "<=", "==", "!="]) else {
+            return Ok(lhs);
+        };
+        let op = match op {
+            ">" => CompareOp::Gt,
+            ">=" => CompareOp::Gte,
+            "<" => CompareOp::Lt,
+            "<=" => CompareOp::Lte,
+            "==" => CompareOp::Eq,
+            _ => CompareOp::Neq,
+        };
+        let rhs = self.sum()?;
+        let symbol = op.to_string();
+        Ok(Expr::Compare(
+            Box::new(expect(lhs, Kind::Number, &symbol)?),
+            op,
+            Box::new(expect(rhs, Kind::Number, &symbol)?),
+        ))
+    }
+
+    fn sum(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.product()?;
+        while let Some(op) = self.op(&["+", "-"]) {
+            let rhs = self.product()?;
+            let op_kind = if op == "+" { ArithOp::Add } else { ArithOp::Sub };
+            lhs = Expr::Arith(Box::new(expect(lhs, Kind::Number, op)?), op_kind, Box::new(expect(rhs, Kind::Number, op)?));
+        }
+        Ok(lhs)
+    }
+
+    fn product(&mut self) -> Result<Expr, String> {
+        let mut lhs = self.unary()?;
+        while let Some(op) = self.op(&["*", "/"]) {
+            let rhs = self.unary()?;
+            let op_kind = if op == "*" { ArithOp::Mul } else { ArithOp::Div };
+            lhs = Expr::Arith(Box::new(expect(lhs, Kind::Number, op)?), op_kind, Box::new(expect(rhs, Kind::Number, op)?));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        if self.op(&["-"]).is_some() {
+            let inner = self.nested(Self::unary)?;
+            return Ok(Expr::Neg(Box::new(expect(inner, Kind::Number, "-")?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, String> {
+        if self.terms >= MAX_TERMS {
+            return Err(format!("Expression has more than {} terms", MAX_TERMS));
+        }
+        self.terms += 1;
+        match self.next()? {
+            Token::Number(n) => Ok(Expr::Number(n)),
+            Token::LParen => {
+                let inner = self.nested(Self::or)?;
+                match self.next() {
+                    Ok(Token::RParen) => Ok(inner),
+                    _ => Err("Missing closing ')'".to_string()),
+                }
+            }
+            Token::Ident(name) => {
+                if ["and", "or", "not"].iter().any(|k| name.eq_ignore_ascii_case(k)) {
+                    return Err(format!("Expected a value before '{}'", name));
+                }
+                feature_index(&name).map(Expr::Feature).ok_or_else(|| {
+                    format!("Unknown feature '{}' (known: {})", name, FEATURE_NAMES.join(", "))
+                })
+            }
+            token => Err(format!("Expected a value, found '{}'", token)),
+        }
+    }
+}
+
+/// Check that `expr` has `kind` where operator `op` uses it.
+fn expect(expr: Expr, kind: Kind, op: &str) -> Result<Expr, String> {
+    if expr.kind() == kind {
+        return Ok(expr);
+    }
+    Err(match kind {
+        Kind::Number => format!("'{}' needs numeric operands, not a condition", op),
+        Kind::Bool => format!("'{}' needs conditions, not a number", op),
+    })
+}
+
+// ── Evaluator ───────────────────────────────────────────────────────
+
+/// Match entities for which `params.expression` holds.
+///
+/// The score is the left-hand side of the expression's first comparison;
+/// signals are the referenced features. Entities for which the expression
+/// is undefined (division by zero) never match.
+pub fn evaluate_expression(
+    params: &ExpressionParams,
+    entities: &HashMap<String, EntityData>,
+) -> Result<Vec<RuleMatch>, String> {
+    let expr = Expr::parse(&params.expression)?;
+    let mut referenced = Vec::new();
+    expr.features(&mut referenced);
+    let score_operand = expr.score_operand();
+
+    let mut matches = Vec::new();
+    for (entity_id, data) in entities {
+        if expr.matches(&data.features) != Some(true) {
+            continue;
+        }
+        let signals: Vec<(String, f64)> = referenced
+            .iter()
+            .filter_map(|&idx| Some((FEATURE_NAMES[idx].to_string(), *data.features.get(idx)?)))
+            .collect();
+        let values: Vec<String> = signals.iter().map(|(name, value)| format!("{}={:.2}", name, value)).collect();
+        matches.push(RuleMatch {
+            entity_id: entity_id.clone(),
+            entity_key: data.key.clone(),
+            entity_type: data.entity_type.clone(),
+            score: score_operand.and_then(|e| e.value(&data.features)).unwrap_or(1.0),
+            signals,
+            matched_reason: format!("Expression '{}' holds ({})", params.expression, values.join(", ")),
+        });
+    }
+    Ok(matches)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::templates::FEATURE_COUNT;
+
+    fn entity(key: &str, login: f64, sessions: f64, errors: f64) -> EntityData {
+        let mut features = vec![0.0; FEATURE_COUNT];
+        features[0] = login;
+        features[3] = errors;
+        features[6] = sessions;
+        EntityData {
+            key: key.to_string(),
+            entity_type: "Member".to_string(),
+            features,
+            score: 0.5,
+            cluster_id: None,
+            first_seen: None,
+        }
+    }
+
+    #[test]
+    fn ratio_expression_matches_per_entity() {
+        let entities: HashMap<String, EntityData> = [
+            entity("M_BOT", 120.0, 4.0, 2.0),       // ratio 30, errors
+            entity("M_CLEAN", 120.0, 4.0, 0.0),     // ratio 30, no errors
+            entity("M_NORMAL", 20.0, 4.0, 5.0),     // ratio 5
+            entity("M_NO_SESSIONS", 50.0, 0.0, 1.0), // undefined ratio
+        ]
+        .into_iter()
+        .map(|e| (format!("id-{}", e.key), e))
+        .collect();
+
+        let params = ExpressionParams {
+            expression: "(login_count / session_count) > 10 AND error_count > 0".to_string(),
+        };
+        let results = evaluate_expression(&params, &entities).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entity_key, "M_BOT");
+        assert_eq!(results[0].score, 30.0);
+        let names: Vec<&str> = results[0].signals.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(names, vec!["login_count", "session_count", "error_count"]);
+
+        let either = ExpressionParams { expression: "not login_count - 2 * 10 > 0 or error_count >= 5".to_string() };
+        let mut keys: Vec<String> =
+            evaluate_expression(&either, &entities).unwrap().into_iter().map(|m| m.entity_key).collect();
+        keys.sort();
+        assert_eq!(keys, vec!["M_NORMAL"]);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_features_and_bad_syntax() {
+        let err = Expr::parse("login_count / sesion_count > 10").unwrap_err();
+        assert!(err.starts_with("Unknown feature 'sesion_count'"), "{err}");
+
+        for (source, expected) in [
+            ("login_count + 1", "must be a comparison"),
+            ("login_count and error_count > 0", "'and' needs conditions"),
+            ("(login_count > 1) * 2 > 0", "'*' needs numeric operands"),
+            ("login_count > (1", "Missing closing ')'"),
+            ("login_count > 1 1", "Unexpected '1'"),
+            ("login_count > ", "Unexpected end"),
+            ("login_count ; 1", "Unexpected character ';'"),
+        ] {
+            let err = Expr::parse(source).unwrap_err();
+            assert!(err.contains(expected), "{source}: {err}");
+        }
+    }
+
+    #[test]
+    fn parse_limits_nesting_depth() {
+        let nested = |depth: usize| format!("{}login_count > 1{}", "(".repeat(depth), ")".repeat(depth));
+        assert!(Expr::parse(&nested(MAX_DEPTH)).is_ok());
+        for source in [nested(MAX_DEPTH + 1), nested(100_000), format!("{}login_count > 1", "not ".repeat(100_000))] {
+            let err = Expr::parse(&source).unwrap_err();
+            assert!(err.contains("nested more than 64 levels"), "{err}");
+        }
+        let err = Expr::parse(&format!("{}1 > 0", "-".repeat(100_000))).unwrap_err();
+        assert!(err.contains("nested more than 64 levels"), "{err}");
+
+        // Flat chains nest the tree as deeply as they are long.
+        let sum = |terms: usize| format!("login_count > 1{}", "+1".repeat(terms - 2));
+        assert!(Expr::parse(&sum(MAX_TERMS)).is_ok());
+        let conjunction = |terms: usize| format!("login_count > 0{}", " and login_count > 0".repeat(terms / 2 - 1));
+        assert!(Expr::parse(&conjunction(MAX_TERMS)).is_ok());
+        for source in [sum(MAX_TERMS + 1), sum(1_000_000), conjunction(MAX_TERMS + 2), conjunction(1_000_000)] {
+            let err = Expr::parse(&source).unwrap_err();
+            assert!(err.contains("more than 1024 terms"), "{err}");
+        }
+    }
+}
@@ -0,0 +1,100 @@
+//! First-seen tracking of entities across rule evaluations.
+//!
+//! The `new_entity` template needs to know when each entity first appeared,
+//! which no single evaluation's data says. [`FirstSeenTracker`] remembers
+//! it between evaluations and stamps [`EntityData::first_seen`].
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::types::EntityData;
+
+/// First-seen timestamps per entity, keyed by `"{entity_type}:{key}"`.
+///
+/// Entities present in the first observation predate tracking, so their
+/// first-seen time is unknown (`None`) rather than the time tracking began;
+/// otherwise every existing entity would look brand-new once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FirstSeenTracker {
+    /// When the first observation was made.
+    tracking_since: Option<DateTime<Utc>>,
+    first_seen: HashMap<String, Option<DateTime<Utc>>>,
+}
+
+impl FirstSeenTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load a tracker persisted with [`save`](Self::save). A missing file
+    /// yields an empty tracker.
+    pub fn load(path: &Path) -> std::io::Result<Self> {
+        match std::fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Persist the tracker as JSON, replacing the file atomically.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec(self)?)?;
+        std::fs::rename(tmp, path)
+    }
+
+    /// When the first observation was made, if any.
+    pub fn tracking_since(&self) -> Option<DateTime<Utc>> {
+        self.tracking_since
+    }
+
+    /// Entities tracked so far.
+    pub fn len(&self) -> usize {
+        self.first_seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.first_seen.is_empty()
+    }
+
+    /// When the entity was first seen; `None` if unknown or predating tracking.
+    pub fn first_seen(&self, entity_type: &str, key: &str) -> Option<DateTime<Utc>> {
+        self.first_seen.get(&tracker_key(entity_type, key)).copied().flatten()
+    }
+
+    /// Record entities not seen before as first seen at `now`, then set
+    /// each entity's [`EntityData::first_seen`]. Returns how many entities
+    /// were recorded for the first time.
+    ///
+    /// Observing no entities before tracking has started is a no-op, so an
+    /// evaluation before data has loaded doesn't consume the baseline.
+    pub fn observe(&mut self, entities: &mut HashMap<String, EntityData>, now: DateTime<Utc>) -> usize {
+        let baseline = self.tracking_since.is_none();
+        if baseline && entities.is_empty() {
+            return 0;
+        }
+        self.tracking_since.get_or_insert(now);
+        let seen_at = (!baseline).then_some(now);
+
+        let before = self.first_seen.len();
+        for data in entities.values_mut() {
+            let first_seen = *self
+                .first_seen
+                .entry(tracker_key(&data.entity_type, &data.key))
+                .or_insert(seen_at);
+            data.first_seen = first_seen;
+        }
+        self.first_seen.len() - before
+    }
+}
+
+fn tracker_key(entity_type: &str, key: &str) -> String {
+    format!("{entity_type}:{key}")
+}
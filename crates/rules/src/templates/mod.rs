@@ -1,16 +1,20 @@
 //! Built-in detection template evaluators.
 //!
-//! Templates: spike, drift, absence, threshold.
+//! Templates: spike, drift, absence, threshold, new_entity, expression.
 //! Each evaluator takes typed parameters plus a map of entity data,
 //! returning a `Vec<RuleMatch>` of entities that triggered.
 
 mod evaluators;
+mod expression;
 mod features;
+mod first_seen;
 mod math;
 mod types;
 
 pub use evaluators::*;
+pub use expression::{evaluate_expression, Expr};
 pub use features::*;
+pub use first_seen::FirstSeenTracker;
 pub use types::*;
 
 #[cfg(test)]
@@ -19,7 +23,8 @@ mod tests {
     use crate::schema::{DetectionTemplate, ThresholdOperator};
     use std::collections::HashMap;
 
-    use crate::schema::{AbsenceParams, DriftParams, SpikeParams, ThresholdParams};
+    use crate::schema::{AbsenceParams, DriftParams, NewEntityParams, SpikeParams, ThresholdParams};
+    use chrono::TimeZone;
 
     /// Build a test entity with the given feature values.
     fn make_entity(key: &str, features: Vec<f64>, cluster_id: Option<usize>) -> EntityData {
@@ -29,6 +34,7 @@ mod tests {
             features,
             score: 0.5,
             cluster_id,
+            first_seen: None,
         }
     }
 
@@ -239,6 +245,7 @@ mod tests {
             features: zero_features(),
             score: 0.0,
             cluster_id: None,
+            first_seen: None,
         };
         // Ensure login + game + session = 0, score = 0
         entities.insert("e2".to_string(), inactive);
@@ -269,6 +276,7 @@ mod tests {
             features: zero_features(),
             score: 0.0,
             cluster_id: None,
+            first_seen: None,
         };
         entities.insert("e1".to_string(), data);
 
@@ -286,6 +294,88 @@ mod tests {
         );
     }
 
+    fn new_entity_params(max_age: &str) -> NewEntityParams {
+        NewEntityParams {
+            feature: "login_count".to_string(),
+            min_value: 50.0,
+            max_age: max_age.to_string(),
+        }
+    }
+
+    #[test]
+    fn new_entity_matches_only_recent_high_activity() {
+        let t0 = chrono::Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+        let mut busy = zero_features();
+        busy[0] = 200.0; // login_count
+        let mut quiet = zero_features();
+        quiet[0] = 3.0;
+
+        // An established high-activity member, seen when tracking started.
+        let mut tracker = FirstSeenTracker::new();
+        let mut entities = HashMap::new();
+        entities.insert("e1".to_string(), make_entity("M_OLD", busy.clone(), None));
+        tracker.observe(&mut entities, t0);
+        assert_eq!(entities["e1"].first_seen, None, "baseline entities predate tracking");
+
+        // A week later two members appear; only the busy one is suspicious.
+        let t1 = t0 + chrono::Duration::days(7);
+        entities.insert("e2".to_string(), make_entity("M_NEW", busy.clone(), None));
+        entities.insert("e3".to_string(), make_entity("M_NEW_QUIET", quiet, None));
+        tracker.observe(&mut entities, t1);
+        assert_eq!(tracker.first_seen("Member", "M_NEW"), Some(t1));
+
+        let now = t1 + chrono::Duration::hours(2);
+        let results = evaluate_new_entity(&new_entity_params("24h"), &entities, now).unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].entity_key, "M_NEW");
+        assert_eq!(results[0].score, 200.0);
+        assert!(results[0].matched_reason.contains("New entity"));
+
+        // Once older than max_age it no longer counts as new, even though the
+        // first-seen time survives further observations.
+        let later = t1 + chrono::Duration::days(2);
+        tracker.observe(&mut entities, later);
+        assert_eq!(entities["e2"].first_seen, Some(t1));
+        assert!(evaluate_new_entity(&new_entity_params("24h"), &entities, later).unwrap().is_empty());
+
+        // An old entity first seen long ago doesn't match either.
+        let mut old = make_entity("M_OLD2", busy, None);
+        old.first_seen = Some(t0);
+        let old_only = HashMap::from([("e4".to_string(), old)]);
+        assert!(evaluate_new_entity(&new_entity_params("7d"), &old_only, now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn new_entity_rejects_bad_max_age() {
+        let result = evaluate_new_entity(&new_entity_params("soon"), &HashMap::new(), chrono::Utc::now());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn first_seen_tracker_persists() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("first-seen.json");
+        let t0 = chrono::Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+
+        let mut tracker = FirstSeenTracker::load(&path).unwrap();
+        assert!(tracker.is_empty());
+        // Observing nothing doesn't start tracking.
+        assert_eq!(tracker.observe(&mut HashMap::new(), t0), 0);
+        assert_eq!(tracker.tracking_since(), None);
+
+        let mut entities = HashMap::from([("e1".to_string(), make_entity("M001", zero_features(), None))]);
+        assert_eq!(tracker.observe(&mut entities, t0), 1);
+        entities.insert("e2".to_string(), make_entity("M002", zero_features(), None));
+        assert_eq!(tracker.observe(&mut entities, t0 + chrono::Duration::hours(1)), 1);
+        tracker.save(&path).unwrap();
+
+        let restored = FirstSeenTracker::load(&path).unwrap();
+        assert_eq!(restored.tracking_since(), Some(t0));
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.first_seen("Member", "M001"), None);
+        assert_eq!(restored.first_seen("Member", "M002"), Some(t0 + chrono::Duration::hours(1)));
+    }
+
     #[test]
     fn evaluate_template_dispatcher() {
         let mut entities = HashMap::new();
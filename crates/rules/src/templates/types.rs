@@ -13,6 +13,9 @@ pub struct EntityData {
     pub score: f64,
     /// Cluster assignment, if available.
     pub cluster_id: Option<usize>,
+    /// When the entity was first seen (see [`FirstSeenTracker`](super::FirstSeenTracker));
+    /// `None` if unknown.
+    pub first_seen: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 /// Aggregate statistics for a single cluster, used as baseline in spike detection.
@@ -9,7 +9,7 @@ use super::ValidationResult;
 use crate::entity_schema::EntitySchemaRule;
 use crate::feature_config::FeatureConfigRule;
 use crate::pattern_config::PatternConfigRule;
-use crate::scoring_config::ScoringConfigRule;
+use crate::scoring_config::{ClassificationMode, ScoringConfigRule};
 use crate::trend_config::TrendConfigRule;
 
 // ── Common metadata validation ──────────────────────────────────────
@@ -181,6 +181,16 @@ pub fn validate_scoring_config(rule: &ScoringConfigRule, result: &mut Validation
             ),
         );
     }
+    if t.mode == ClassificationMode::Percentile
+        && [t.mild, t.anomalous, t.highly_anomalous]
+            .iter()
+            .any(|p| !(0.0..=1.0).contains(p))
+    {
+        result.error(
+            "spec.classification_thresholds",
+            "percentile thresholds must be fractions between 0.0 and 1.0",
+        );
+    }
 }
 
 // ── TrendConfig validation ──────────────────────────────────────────
@@ -337,6 +347,28 @@ mod tests {
         assert!(result.warnings.iter().any(|w| w.message.contains("sum to")));
     }
 
+    #[test]
+    fn scoring_percentile_thresholds_must_be_fractions() {
+        let mut rule = load_scoring_config();
+        let t = &mut rule.spec.classification_thresholds;
+        t.mode = ClassificationMode::Percentile;
+        t.mild = 90.0;
+        t.anomalous = 95.0;
+        t.highly_anomalous = 99.0;
+        let mut result = ValidationResult::new();
+        validate_scoring_config(&rule, &mut result);
+        assert!(!result.valid);
+        assert!(result.errors.iter().any(|e| e.message.contains("fractions")));
+
+        let t = &mut rule.spec.classification_thresholds;
+        t.mild = 0.90;
+        t.anomalous = 0.95;
+        t.highly_anomalous = 0.99;
+        let mut result = ValidationResult::new();
+        validate_scoring_config(&rule, &mut result);
+        assert!(result.valid, "{:?}", result.errors);
+    }
+
     #[test]
     fn trend_config_bad_severity_order() {
         let mut rule = load_trend_config();
@@ -9,49 +9,71 @@ pub(super) fn validate_notifications(rule: &AnomalyRule, result: &mut Validation
         return;
     }
 
+    if rule.max_notify_entities == Some(0) {
+        result.error("max_notify_entities", "max_notify_entities must be at least 1");
+    }
+
     for (i, notif) in rule.notifications.iter().enumerate() {
-        let path = format!("notifications[{i}]");
-        match notif.channel {
-            ChannelType::Webhook => {
-                if notif.url.is_none() {
-                    result.error(format!("{path}.url"), "Webhook channel requires 'url'");
-                } else if let Some(url) = &notif.url {
-                    if !url.starts_with("http://") && !url.starts_with("https://") {
-                        result.error(
-                            format!("{path}.url"),
-                            format!("URL must start with http:// or https://, got '{url}'"),
-                        );
-                    }
+        validate_channel(&format!("notifications[{i}]"), notif, result);
+    }
+
+    if let Some(escalation) = &rule.escalation {
+        if escalation.window().is_none() {
+            result.error(
+                "escalation.after",
+                format!("Invalid duration format '{}', expected e.g. '15m', '1h'", escalation.after),
+            );
+        }
+        if escalation.notifications.is_empty() {
+            result.error("escalation.notifications", "Escalation requires at least one notification channel");
+        }
+        for (i, notif) in escalation.notifications.iter().enumerate() {
+            validate_channel(&format!("escalation.notifications[{i}]"), notif, result);
+        }
+    }
+}
+
+fn validate_channel(path: &str, notif: &NotificationChannel, result: &mut ValidationResult) {
+    match notif.channel {
+        ChannelType::Webhook => {
+            if notif.url.is_none() {
+                result.error(format!("{path}.url"), "Webhook channel requires 'url'");
+            } else if let Some(url) = &notif.url {
+                if !url.starts_with("http://") && !url.starts_with("https://") {
+                    result.error(
+                        format!("{path}.url"),
+                        format!("URL must start with http:// or https://, got '{url}'"),
+                    );
                 }
             }
-            ChannelType::Email => {
-                if notif.smtp_host.is_none() {
-                    result.error(format!("{path}.smtp_host"), "Email channel requires 'smtp_host'");
-                }
-                if notif.smtp_port.is_none() {
-                    result.error(format!("{path}.smtp_port"), "Email channel requires 'smtp_port'");
-                }
-                if notif.from.is_none() {
-                    result.error(format!("{path}.from"), "Email channel requires 'from'");
-                }
-                if notif.to.is_none() {
-                    result.error(format!("{path}.to"), "Email channel requires 'to'");
-                }
+        }
+        ChannelType::Email => {
+            if notif.smtp_host.is_none() {
+                result.error(format!("{path}.smtp_host"), "Email channel requires 'smtp_host'");
             }
-            ChannelType::Telegram => {
-                if notif.bot_token.is_none() {
-                    result.error(format!("{path}.bot_token"), "Telegram channel requires 'bot_token'");
-                }
-                if notif.chat_id.is_none() {
-                    result.error(format!("{path}.chat_id"), "Telegram channel requires 'chat_id'");
-                }
+            if notif.smtp_port.is_none() {
+                result.error(format!("{path}.smtp_port"), "Email channel requires 'smtp_port'");
+            }
+            if notif.from.is_none() {
+                result.error(format!("{path}.from"), "Email channel requires 'from'");
+            }
+            if notif.to.is_none() {
+                result.error(format!("{path}.to"), "Email channel requires 'to'");
+            }
+        }
+        ChannelType::Telegram => {
+            if notif.bot_token.is_none() {
+                result.error(format!("{path}.bot_token"), "Telegram channel requires 'bot_token'");
+            }
+            if notif.chat_id.is_none() {
+                result.error(format!("{path}.chat_id"), "Telegram channel requires 'chat_id'");
             }
         }
-
-        // Check for raw secrets (not using ${ENV_VAR} syntax)
-        check_secret_value(&notif.bot_token, &format!("{path}.bot_token"), result);
-        check_secret_value(&notif.url, &format!("{path}.url"), result);
     }
+
+    // Check for raw secrets (not using ${ENV_VAR} syntax)
+    check_secret_value(&notif.bot_token, &format!("{path}.bot_token"), result);
+    check_secret_value(&notif.url, &format!("{path}.url"), result);
 }
 
 /// Warn if a value looks like a raw secret instead of `${ENV_VAR}` reference.
@@ -123,6 +145,17 @@ notifications:
         assert!(result.errors.iter().any(|e| e.path == "notifications"));
     }
 
+    #[test]
+    fn zero_max_notify_entities() {
+        let mut rule = valid_rule();
+        rule.max_notify_entities = Some(0);
+        let result = validate_rule(&rule);
+        assert!(result.errors.iter().any(|e| e.path == "max_notify_entities"));
+
+        rule.max_notify_entities = Some(100);
+        assert!(validate_rule(&rule).valid);
+    }
+
     #[test]
     fn email_missing_fields() {
         let result = validate_yaml(
@@ -207,4 +240,25 @@ notifications:
         );
         assert!(result.warnings.iter().any(|w| w.path.contains("bot_token")));
     }
+
+    #[test]
+    fn escalation_policy_checks() {
+        let mut rule = valid_rule();
+        rule.escalation = Some(EscalationPolicy {
+            after: "15m".to_string(),
+            notifications: valid_rule().notifications,
+        });
+        assert!(validate_rule(&rule).valid);
+
+        rule.escalation = Some(EscalationPolicy { after: "soon".to_string(), notifications: vec![] });
+        let result = validate_rule(&rule);
+        assert!(result.errors.iter().any(|e| e.path == "escalation.after"));
+        assert!(result.errors.iter().any(|e| e.path == "escalation.notifications"));
+
+        let mut webhook = valid_rule().notifications.remove(0);
+        webhook.url = None;
+        rule.escalation = Some(EscalationPolicy { after: "1h".to_string(), notifications: vec![webhook] });
+        let result = validate_rule(&rule);
+        assert!(result.errors.iter().any(|e| e.path == "escalation.notifications[0].url"));
+    }
 }
@@ -20,6 +20,11 @@ pub(super) fn validate_schedule(rule: &AnomalyRule, result: &mut ValidationResul
                 format!("Invalid duration format '{}', expected e.g. '30m', '1h', '2h30m'", cooldown),
             );
         }
+    } else if sched.cooldown_scope == CooldownScope::Entity {
+        result.error(
+            "schedule.cooldown_scope",
+            "cooldown_scope 'entity' requires a schedule.cooldown duration",
+        );
     }
 }
 
@@ -238,6 +243,22 @@ notifications:
             .any(|e| e.path == "schedule.cooldown"));
     }
 
+    #[test]
+    fn entity_cooldown_scope_requires_cooldown() {
+        let mut rule = valid_rule();
+        rule.schedule.cooldown = None;
+        rule.schedule.cooldown_scope = CooldownScope::Entity;
+        let result = validate_rule(&rule);
+        assert!(!result.valid);
+        assert!(result
+            .errors
+            .iter()
+            .any(|e| e.path == "schedule.cooldown_scope"));
+
+        rule.schedule.cooldown = Some("1h".to_string());
+        assert!(validate_rule(&rule).valid);
+    }
+
     #[test]
     fn cron_validation_edge_cases() {
         let mut result = ValidationResult::new();
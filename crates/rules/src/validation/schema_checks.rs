@@ -1,6 +1,8 @@
 //! Schema and detection validation: apiVersion, kind, metadata, template params, composition.
 
+use crate::scheduler::parse_cooldown;
 use crate::schema::*;
+use crate::templates::Expr;
 use super::ValidationResult;
 use super::filter_checks::validate_feature_name;
 use super::fuzzy::is_kebab_case;
@@ -142,6 +144,51 @@ fn validate_template_params(
                 result.error("detection.params", "Threshold template requires params");
             }
         }
+        DetectionTemplate::NewEntity => {
+            if let Some(parse_result) = det.parse_new_entity_params() {
+                match parse_result {
+                    Ok(params) => {
+                        validate_feature_name(&params.feature, "detection.params.feature", result);
+                        if parse_cooldown(&params.max_age).is_none() {
+                            result.error(
+                                "detection.params.max_age",
+                                format!(
+                                    "max_age must be a duration like '24h' or '7d', got '{}'",
+                                    params.max_age
+                                ),
+                            );
+                        }
+                    }
+                    Err(e) => {
+                        result.error(
+                            "detection.params",
+                            format!("Invalid new_entity params: {e}"),
+                        );
+                    }
+                }
+            } else {
+                result.error("detection.params", "NewEntity template requires params");
+            }
+        }
+        DetectionTemplate::Expression => {
+            if let Some(parse_result) = det.parse_expression_params() {
+                match parse_result {
+                    Ok(params) => {
+                        if let Err(e) = Expr::parse(&params.expression) {
+                            result.error("detection.params.expression", format!("Invalid expression: {e}"));
+                        }
+                    }
+                    Err(e) => {
+                        result.error(
+                            "detection.params",
+                            format!("Invalid expression params: {e}"),
+                        );
+                    }
+                }
+            } else {
+                result.error("detection.params", "Expression template requires params");
+            }
+        }
     }
 }
 
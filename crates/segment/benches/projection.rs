@@ -0,0 +1,99 @@
+//! Compare full document iteration against projected iteration over the
+//! fields graph building reads.
+//!
+//! Run with: `cargo bench -p stupid-segment --bench projection`
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use stupid_core::{Document, FieldValue};
+use stupid_segment::reader::SegmentReader;
+use stupid_segment::writer::SegmentWriter;
+
+const DOCS: i64 = 50_000;
+const ITERATIONS: u32 = 10;
+/// Extra payload fields per document that graph building never reads.
+const PAYLOAD_FIELDS: usize = 30;
+/// Mirrors `GRAPH_OP_FIELDS` in stupid-server's `graph_ops`.
+const GRAPH_OP_FIELDS: &[&str] = &[
+    "memberCode",
+    "fingerprint",
+    "platform",
+    "currency",
+    "rGroup",
+    "affiliateId",
+    "affiliateid",
+    "affiliateID",
+    "game",
+    "gameTrackingProvider",
+    "trackingId",
+    "popupType",
+    "url",
+    "statusCode",
+    "error",
+];
+
+fn main() {
+    let data_dir = std::env::temp_dir().join(format!("stupid-bench-{}", Uuid::new_v4()));
+    let segment_id = "Login/2025-W24";
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let mut writer = SegmentWriter::new(&data_dir, segment_id).expect("writer");
+    for i in 0..DOCS {
+        let mut fields = HashMap::new();
+        fields.insert("memberCode".to_string(), FieldValue::Text(format!("user{}", i % 1000)));
+        fields.insert("fingerprint".to_string(), FieldValue::Text(format!("fp{}", i % 5000)));
+        fields.insert("platform".to_string(), FieldValue::Text("web".to_string()));
+        fields.insert("currency".to_string(), FieldValue::Text("EUR".to_string()));
+        for f in 0..PAYLOAD_FIELDS {
+            fields.insert(format!("payload{f}"), FieldValue::Text(format!("value-{i}-{f}")));
+        }
+        writer
+            .append(&Document {
+                id: Uuid::new_v4(),
+                timestamp: base + chrono::Duration::milliseconds(i),
+                event_type: "Login".to_string(),
+                fields,
+            })
+            .expect("append");
+    }
+    writer.finalize().expect("finalize");
+    let reader = SegmentReader::open(&data_dir, segment_id).expect("reader");
+
+    let mut full_time = Duration::ZERO;
+    let mut full_fields = 0;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        full_fields = 0;
+        for doc in reader.iter() {
+            full_fields += doc.expect("doc").fields.len();
+        }
+        full_time += start.elapsed();
+    }
+
+    let mut projected_time = Duration::ZERO;
+    let mut projected_fields = 0;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        projected_fields = 0;
+        for doc in reader.iter_fields(GRAPH_OP_FIELDS) {
+            projected_fields += doc.expect("doc").fields.len();
+        }
+        projected_time += start.elapsed();
+    }
+
+    println!("docs: {DOCS}, fields/doc: {}", PAYLOAD_FIELDS + 4);
+    println!(
+        "full:      {:>8.2} ms/iter, {full_fields} fields decoded",
+        full_time.as_secs_f64() * 1000.0 / ITERATIONS as f64
+    );
+    println!(
+        "projected: {:>8.2} ms/iter, {projected_fields} fields decoded",
+        projected_time.as_secs_f64() * 1000.0 / ITERATIONS as f64
+    );
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -0,0 +1,88 @@
+//! Compare opening a segment reader per access against the shared reader
+//! cache in `SegmentManager`.
+//!
+//! Run with: `cargo bench -p stupid-segment --bench reader_cache`
+
+use std::collections::HashMap;
+use std::time::Instant;
+
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use stupid_core::config::StorageConfig;
+use stupid_core::{Document, FieldValue};
+use stupid_segment::reader::SegmentReader;
+use stupid_segment::DocumentStore;
+
+const SEGMENTS: i64 = 8;
+const DOCS_PER_SEGMENT: i64 = 10_000;
+const ACCESSES: usize = 200;
+
+fn main() {
+    let data_dir = std::env::temp_dir().join(format!("stupid-bench-{}", Uuid::new_v4()));
+    let config = StorageConfig {
+        data_dir: data_dir.clone(),
+        segment_retention_days: 100_000,
+        cache_dir: data_dir.join("cache"),
+        cache_max_gb: 1,
+        indexed_fields: vec!["member".to_string()],
+        segment_max_docs: 0,
+        segment_max_bytes: 0,
+        in_memory: false,
+    };
+    let mut store = DocumentStore::new(&config).expect("store");
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 1, 0, 0, 0).unwrap();
+    for day in 0..SEGMENTS {
+        for i in 0..DOCS_PER_SEGMENT {
+            let mut fields = HashMap::new();
+            fields.insert("member".to_string(), FieldValue::Text(format!("user{}", i % 1000)));
+            store
+                .insert(Document {
+                    id: Uuid::new_v4(),
+                    timestamp: base + chrono::Duration::days(day) + chrono::Duration::milliseconds(i),
+                    event_type: "Login".to_string(),
+                    fields,
+                })
+                .expect("insert");
+        }
+    }
+    store.flush().expect("flush");
+    drop(store);
+
+    let store = DocumentStore::new(&config).expect("reopen store");
+    let manager = store.manager();
+    let segments = manager.list_segments();
+    let opens_before = manager.reader_cache().opens();
+
+    // Round-robin lookups, as the query path does across a time range.
+    let mut uncached_hits = 0;
+    let start = Instant::now();
+    for i in 0..ACCESSES {
+        let reader = SegmentReader::open(&data_dir, &segments[i % segments.len()]).expect("open");
+        uncached_hits += reader.lookup("member", "user7").expect("lookup").len();
+    }
+    let uncached_time = start.elapsed();
+
+    let mut cached_hits = 0;
+    let start = Instant::now();
+    for i in 0..ACCESSES {
+        let reader = manager.get_reader(&segments[i % segments.len()]).expect("reader");
+        cached_hits += reader.lookup("member", "user7").expect("lookup").len();
+    }
+    let cached_time = start.elapsed();
+
+    assert_eq!(cached_hits, uncached_hits);
+    let cache_reloads = manager.reader_cache().opens() - opens_before;
+    println!("segments: {SEGMENTS} x {DOCS_PER_SEGMENT} docs, {ACCESSES} lookups");
+    println!(
+        "open per access: {:>8.2} ms/lookup, {ACCESSES} index loads",
+        uncached_time.as_secs_f64() * 1000.0 / ACCESSES as f64
+    );
+    println!(
+        "reader cache:    {:>8.2} ms/lookup, {cache_reloads} index loads",
+        cached_time.as_secs_f64() * 1000.0 / ACCESSES as f64
+    );
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -0,0 +1,103 @@
+//! Compare a naive segment scan against index predicate pushdown.
+//!
+//! Run with: `cargo bench -p stupid-segment --bench scan_pushdown`
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use stupid_core::config::StorageConfig;
+use stupid_core::{Document, FieldValue};
+use stupid_segment::{DocumentStore, ScanFilter};
+
+const DOCS: i64 = 50_000;
+const ITERATIONS: u32 = 10;
+const EVENT_TYPES: [&str; 10] = [
+    "Login",
+    "Logout",
+    "GameOpened",
+    "GameClosed",
+    "Deposit",
+    "Withdrawal",
+    "BetPlaced",
+    "BetSettled",
+    "PageView",
+    "APIError",
+];
+
+fn main() {
+    let data_dir = std::env::temp_dir().join(format!("stupid-bench-{}", Uuid::new_v4()));
+    let config = StorageConfig {
+        data_dir: data_dir.clone(),
+        segment_retention_days: 30,
+        cache_dir: data_dir.join("cache"),
+        cache_max_gb: 1,
+        indexed_fields: Vec::new(),
+        segment_max_docs: 0,
+        segment_max_bytes: 0,
+        in_memory: false,
+    };
+    let mut store = DocumentStore::new(&config).expect("store");
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    for i in 0..DOCS {
+        let mut fields = HashMap::new();
+        fields.insert("member".to_string(), FieldValue::Text(format!("user{}", i % 1000)));
+        fields.insert("score".to_string(), FieldValue::Integer(i));
+        store
+            .insert(Document {
+                id: Uuid::new_v4(),
+                timestamp: base + chrono::Duration::milliseconds(i),
+                event_type: EVENT_TYPES[i as usize % EVENT_TYPES.len()].to_string(),
+                fields,
+            })
+            .expect("insert");
+    }
+    store.flush().expect("flush");
+
+    let segment_id = store.manager().list_segments().pop().expect("segment");
+    let reader = store.manager().get_reader(&segment_id).expect("reader");
+    let index = store.index(&segment_id).expect("index");
+    let filter = ScanFilter::new().event_type("APIError").field_eq("member", "user9");
+
+    let mut naive_time = Duration::ZERO;
+    let mut naive_deserialized = 0;
+    let mut naive_matched = 0;
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        naive_deserialized = 0;
+        naive_matched = 0;
+        for doc in reader.iter() {
+            naive_deserialized += 1;
+            if filter.matches(&doc.expect("doc")) {
+                naive_matched += 1;
+            }
+        }
+        naive_time += start.elapsed();
+    }
+
+    let mut pushdown_time = Duration::ZERO;
+    let mut stats = Default::default();
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        stats = reader.scan_with_filter(index, &filter).expect("scan").1;
+        pushdown_time += start.elapsed();
+    }
+
+    assert_eq!(stats.matched, naive_matched);
+    println!("docs: {DOCS}, filter: event_type=APIError, member=user9");
+    println!(
+        "naive:    {:>8.2} ms/scan, {naive_deserialized} deserialized, {naive_matched} matched",
+        naive_time.as_secs_f64() * 1000.0 / ITERATIONS as f64
+    );
+    println!(
+        "pushdown: {:>8.2} ms/scan, {} deserialized, {} matched",
+        pushdown_time.as_secs_f64() * 1000.0 / ITERATIONS as f64,
+        stats.deserialized,
+        stats.matched
+    );
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
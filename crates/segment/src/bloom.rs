@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use stupid_core::hashing::{bloom_contains, bloom_insert, bloom_size, Fnv1a};
+use stupid_core::{DocId, StupidError};
+
+/// File name of the bloom filter, stored alongside `documents.idx`.
+pub const BLOOM_FILTER_FILE: &str = "bloom.idx";
+
+/// False-positive rate segment bloom filters are sized for.
+pub const BLOOM_FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Per-segment bloom filter over document IDs and the values of the
+/// segment's secondary-indexed fields.
+///
+/// Answers "might this segment contain the key?" without opening the
+/// segment: a `false` is definite, a `true` is wrong at roughly the rate the
+/// filter was sized for. Field values are keyed by the same string form as
+/// the [`SecondaryIndex`](crate::secondary::SecondaryIndex).
+///
+/// Persisted as a single msgpack value, written when the segment is finalized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SegmentBloom {
+    /// Fields whose values were added, besides document IDs.
+    fields: Vec<String>,
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl SegmentBloom {
+    /// An empty filter sized for `expected_keys` keys at `false_positive_rate`
+    /// (clamped to `0.0001..=0.5`).
+    pub fn with_capacity(fields: &[String], expected_keys: usize, false_positive_rate: f64) -> Self {
+        let (num_bits, num_hashes) = bloom_size(expected_keys as u64, false_positive_rate.clamp(0.0001, 0.5));
+        Self {
+            fields: fields.to_vec(),
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// A filter holding `keys` (from [`id_key`] and [`field_key`]), sized
+    /// from their count at [`BLOOM_FALSE_POSITIVE_RATE`].
+    pub fn from_keys(fields: &[String], keys: &[u64]) -> Self {
+        let mut bloom = Self::with_capacity(fields, keys.len(), BLOOM_FALSE_POSITIVE_RATE);
+        for &key in keys {
+            bloom.insert(key);
+        }
+        bloom
+    }
+
+    /// Whether values of `field` were added to the filter.
+    pub fn covers(&self, field: &str) -> bool {
+        self.fields.iter().any(|f| f == field)
+    }
+
+    /// Add a key from [`id_key`] or [`field_key`].
+    pub fn insert(&mut self, key: u64) {
+        bloom_insert(&mut self.bits, self.num_bits, self.num_hashes, key);
+    }
+
+    fn contains(&self, key: u64) -> bool {
+        bloom_contains(&self.bits, self.num_bits, self.num_hashes, key)
+    }
+
+    /// Whether a document with `id` may be in the segment.
+    pub fn may_contain_id(&self, id: &DocId) -> bool {
+        self.contains(id_key(id))
+    }
+
+    /// Whether a document whose `field` equals `value` may be in the segment.
+    ///
+    /// Always `true` for fields the filter does not cover.
+    pub fn may_contain(&self, field: &str, value: &str) -> bool {
+        !self.covers(field) || self.contains(field_key(field, value))
+    }
+
+    /// Write the filter to `path` as msgpack.
+    pub fn save(&self, path: &Path) -> Result<(), StupidError> {
+        let bytes = rmp_serde::to_vec(self).map_err(|e| StupidError::Serialize(e.into()))?;
+        fs::write(path, bytes)?;
+        Ok(())
+    }
+
+    /// Load a filter from `path`; `None` if the segment was written without one.
+    pub fn load(path: &Path) -> Result<Option<Self>, StupidError> {
+        let bytes = match fs::read(path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(StupidError::Io(e)),
+        };
+        let bloom: Self =
+            rmp_serde::from_slice(&bytes).map_err(|e| StupidError::Serialize(e.into()))?;
+        if bloom.num_bits == 0 || bloom.bits.len() as u64 != bloom.num_bits.div_ceil(64) {
+            return Err(StupidError::Serialize("bloom filter size mismatch".into()));
+        }
+        Ok(Some(bloom))
+    }
+}
+
+/// Filter key for a document ID.
+pub fn id_key(id: &DocId) -> u64 {
+    let mut hasher = Fnv1a::default();
+    hasher.write(&[0]);
+    hasher.write(id.as_bytes());
+    hasher.finish()
+}
+
+/// Filter key for `field` holding `value` (in secondary-index string form).
+pub fn field_key(field: &str, value: &str) -> u64 {
+    let mut hasher = Fnv1a::default();
+    hasher.write(&[1]);
+    hasher.write(field.as_bytes());
+    hasher.write(&[0xff]);
+    hasher.write(value.as_bytes());
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uuid::Uuid;
+
+    #[test]
+    fn never_false_negative_on_ids_or_fields() {
+        let fields = vec!["memberCode".to_string()];
+        let ids: Vec<DocId> = (0..5_000).map(|_| Uuid::new_v4()).collect();
+        let mut keys: Vec<u64> = ids.iter().map(id_key).collect();
+        keys.extend((0..5_000).map(|i| field_key("memberCode", &format!("m{i}"))));
+        let bloom = SegmentBloom::from_keys(&fields, &keys);
+
+        assert!(ids.iter().all(|id| bloom.may_contain_id(id)));
+        assert!((0..5_000).all(|i| bloom.may_contain("memberCode", &format!("m{i}"))));
+    }
+
+    #[test]
+    fn false_positive_rate_is_near_target() {
+        let ids: Vec<u64> = (0..10_000).map(|_| id_key(&Uuid::new_v4())).collect();
+        let bloom = SegmentBloom::from_keys(&[], &ids);
+
+        let false_positives = (0..10_000).filter(|_| bloom.may_contain_id(&Uuid::new_v4())).count();
+        assert!(false_positives < 300, "{false_positives} false positives in 10000");
+    }
+
+    #[test]
+    fn uncovered_fields_may_always_match() {
+        let bloom = SegmentBloom::from_keys(&["memberCode".to_string()], &[field_key("memberCode", "alice")]);
+        assert!(bloom.may_contain("memberCode", "alice"));
+        assert!(!bloom.may_contain("memberCode", "bob"));
+        assert!(bloom.may_contain("country", "DE"));
+        assert!(!bloom.covers("country"));
+    }
+
+    #[test]
+    fn save_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("bloom_test_{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(BLOOM_FILTER_FILE);
+        assert!(SegmentBloom::load(&path).unwrap().is_none());
+
+        let id = Uuid::new_v4();
+        SegmentBloom::from_keys(&[], &[id_key(&id)]).save(&path).unwrap();
+        let loaded = SegmentBloom::load(&path).unwrap().unwrap();
+        assert!(loaded.may_contain_id(&id));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,156 @@
+//! Crash-safe merging of sealed segments.
+//!
+//! A compaction writes the merged segment to `segments/{target}.compacting`,
+//! records the source segment IDs in a marker file, and then swaps it into
+//! place with directory renames. Sources are deleted only after the merged
+//! segment is in place; if the process dies part-way, [`recover`] (run when
+//! the [`SegmentManager`](crate::manager::SegmentManager) starts) either
+//! discards the incomplete output or finishes deleting the sources, so no
+//! document is ever lost or duplicated.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use stupid_core::{Document, SegmentId, StupidError};
+use tracing::{info, warn};
+
+use crate::index::{DocIndex, DocIndexEntry};
+use crate::writer::SegmentWriter;
+
+/// Marker listing the source segments of a compaction that has not finished
+/// deleting them.
+pub const COMPACTION_MARKER: &str = "compaction.json";
+
+/// Suffix of the directory a compaction is being written to.
+pub(crate) const COMPACTING_SUFFIX: &str = ".compacting";
+/// Suffix of a target segment moved aside while being replaced.
+pub(crate) const REPLACED_SUFFIX: &str = ".replaced";
+
+/// Whether a directory under `segments/` is compaction scratch space.
+pub(crate) fn is_scratch_dir(name: &str) -> bool {
+    name.ends_with(COMPACTING_SUFFIX) || name.ends_with(REPLACED_SUFFIX)
+}
+
+/// Write `docs` as a sealed segment in `dir`, returning its document index.
+///
+/// Writes `documents.dat`, `meta.json`, `documents.idx`, the secondary index
+/// (if `indexed_fields` is non-empty), and the compaction marker.
+pub(crate) fn write_merged(
+    dir: &Path,
+    target_id: &str,
+    docs: &[Document],
+    indexed_fields: &[String],
+    sources: &[SegmentId],
+) -> Result<DocIndex, StupidError> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+
+    let mut writer =
+        SegmentWriter::new_at(dir.to_path_buf(), target_id)?.with_indexed_fields(indexed_fields);
+    let mut index = DocIndex::new();
+
+    for doc in docs {
+        let length = rmp_serde::to_vec(doc)
+            .map_err(|e| StupidError::Serialize(e.into()))?
+            .len() as u32;
+        let offset = writer.append(doc)?;
+        index.add(
+            doc.id,
+            DocIndexEntry {
+                offset,
+                length,
+                timestamp: doc.timestamp,
+                event_type: doc.event_type.clone(),
+                deleted: false,
+            },
+        );
+    }
+
+    writer.finalize()?;
+    index.save(&dir.join("documents.idx"))?;
+
+    let marker = serde_json::to_string(sources).map_err(|e| StupidError::Serialize(e.into()))?;
+    fs::write(dir.join(COMPACTION_MARKER), marker)?;
+
+    Ok(index)
+}
+
+/// Delete the sources listed in `segment_dir`'s marker, then the marker.
+pub(crate) fn finish(segments_dir: &Path, segment_id: &str) -> Result<(), StupidError> {
+    let segment_dir = segments_dir.join(segment_id);
+    let marker_path = segment_dir.join(COMPACTION_MARKER);
+
+    let content = fs::read_to_string(&marker_path)?;
+    let sources: Vec<SegmentId> =
+        serde_json::from_str(&content).map_err(|e| StupidError::Serialize(e.into()))?;
+
+    for source in sources.iter().filter(|s| s.as_str() != segment_id) {
+        let source_dir = segments_dir.join(source);
+        if source_dir.exists() {
+            fs::remove_dir_all(&source_dir)?;
+        }
+    }
+
+    let replaced = replaced_dir(segments_dir, segment_id);
+    if replaced.exists() {
+        fs::remove_dir_all(&replaced)?;
+    }
+
+    fs::remove_file(&marker_path)?;
+    Ok(())
+}
+
+pub(crate) fn compacting_dir(segments_dir: &Path, target_id: &str) -> PathBuf {
+    segments_dir.join(format!("{target_id}{COMPACTING_SUFFIX}"))
+}
+
+pub(crate) fn replaced_dir(segments_dir: &Path, target_id: &str) -> PathBuf {
+    segments_dir.join(format!("{target_id}{REPLACED_SUFFIX}"))
+}
+
+/// Roll an interrupted compaction forward or back.
+///
+/// - `*.compacting` directories never reached their final name: discard.
+/// - `*.replaced` directories are restored if the merged segment never took
+///   their place, otherwise discarded.
+/// - Segments still carrying a marker finish deleting their sources.
+pub(crate) fn recover(segments_dir: &Path) -> Result<(), StupidError> {
+    let mut names = Vec::new();
+    for entry in fs::read_dir(segments_dir)? {
+        let path = entry?.path();
+        if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
+            if path.is_dir() {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    for name in &names {
+        if name.ends_with(COMPACTING_SUFFIX) {
+            warn!(dir = %name, "Discarding incomplete segment compaction");
+            fs::remove_dir_all(segments_dir.join(name))?;
+        }
+    }
+
+    for name in &names {
+        if let Some(target) = name.strip_suffix(REPLACED_SUFFIX) {
+            let target_dir = segments_dir.join(target);
+            if target_dir.exists() {
+                fs::remove_dir_all(segments_dir.join(name))?;
+            } else {
+                warn!(segment_id = %target, "Restoring segment from interrupted compaction");
+                fs::rename(segments_dir.join(name), target_dir)?;
+            }
+        }
+    }
+
+    for name in names.iter().filter(|n| !is_scratch_dir(n)) {
+        if segments_dir.join(name).join(COMPACTION_MARKER).exists() {
+            info!(segment_id = %name, "Finishing interrupted segment compaction");
+            finish(segments_dir, name)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,7 +1,13 @@
 use chrono::{DateTime, Utc};
 use stupid_core::{Document, FieldValue};
 
+use crate::index::DocIndexEntry;
+
 /// Field-level predicate for filtering documents.
+///
+/// Range predicates compare numerically and only match `Integer`/`Float`
+/// fields; text-only predicates only match `Text` fields. A predicate applied
+/// to a field of the wrong type doesn't match.
 #[derive(Debug, Clone)]
 pub enum FieldPredicate {
     /// Field equals the specified string value.
@@ -10,39 +16,65 @@ pub enum FieldPredicate {
     Contains(String),
     /// Field is greater than the specified numeric value.
     Gt(f64),
+    /// Field is greater than or equal to the specified numeric value.
+    Gte(f64),
     /// Field is less than the specified numeric value.
     Lt(f64),
+    /// Field is less than or equal to the specified numeric value.
+    Lte(f64),
+    /// Field equals any of the specified values (same rules as `Eq`).
+    In(Vec<String>),
+    /// Field is absent or null.
+    IsNull,
+    /// Field is present and not null.
+    IsNotNull,
 }
 
 impl FieldPredicate {
     /// Test if a field value matches this predicate.
     pub fn matches(&self, value: &FieldValue) -> bool {
         match self {
-            FieldPredicate::Eq(expected) => match value {
-                FieldValue::Text(s) => s == expected,
-                FieldValue::Integer(i) => expected.parse::<i64>().ok() == Some(*i),
-                FieldValue::Float(f) => expected.parse::<f64>().ok() == Some(*f),
-                FieldValue::Boolean(b) => {
-                    expected.eq_ignore_ascii_case("true") && *b
-                        || expected.eq_ignore_ascii_case("false") && !*b
-                }
-                FieldValue::Null => false,
-            },
+            FieldPredicate::Eq(expected) => value_eq(value, expected),
             FieldPredicate::Contains(substring) => match value {
                 FieldValue::Text(s) => s.contains(substring),
                 _ => false,
             },
-            FieldPredicate::Gt(threshold) => match value {
-                FieldValue::Integer(i) => (*i as f64) > *threshold,
-                FieldValue::Float(f) => *f > *threshold,
-                _ => false,
-            },
-            FieldPredicate::Lt(threshold) => match value {
-                FieldValue::Integer(i) => (*i as f64) < *threshold,
-                FieldValue::Float(f) => *f < *threshold,
-                _ => false,
-            },
+            FieldPredicate::Gt(threshold) => as_number(value).is_some_and(|n| n > *threshold),
+            FieldPredicate::Gte(threshold) => as_number(value).is_some_and(|n| n >= *threshold),
+            FieldPredicate::Lt(threshold) => as_number(value).is_some_and(|n| n < *threshold),
+            FieldPredicate::Lte(threshold) => as_number(value).is_some_and(|n| n <= *threshold),
+            FieldPredicate::In(options) => options.iter().any(|o| value_eq(value, o)),
+            FieldPredicate::IsNull => matches!(value, FieldValue::Null),
+            FieldPredicate::IsNotNull => !matches!(value, FieldValue::Null),
+        }
+    }
+
+    /// Whether this predicate matches a document that lacks the field entirely.
+    pub fn matches_missing(&self) -> bool {
+        matches!(self, FieldPredicate::IsNull)
+    }
+}
+
+/// Compare a field value to a string literal, parsing it per the field's type.
+fn value_eq(value: &FieldValue, expected: &str) -> bool {
+    match value {
+        FieldValue::Text(s) => s == expected,
+        FieldValue::Integer(i) => expected.parse::<i64>().ok() == Some(*i),
+        FieldValue::Float(f) => expected.parse::<f64>().ok() == Some(*f),
+        FieldValue::Boolean(b) => {
+            expected.eq_ignore_ascii_case("true") && *b
+                || expected.eq_ignore_ascii_case("false") && !*b
         }
+        FieldValue::Null => false,
+    }
+}
+
+/// Numeric view of a field value; `None` for non-numeric types.
+fn as_number(value: &FieldValue) -> Option<f64> {
+    match value {
+        FieldValue::Integer(i) => Some(*i as f64),
+        FieldValue::Float(f) => Some(*f),
+        _ => None,
     }
 }
 
@@ -119,6 +151,13 @@ impl ScanFilter {
         self
     }
 
+    /// Add a field greater-than-or-equal predicate.
+    pub fn field_gte(mut self, name: impl Into<String>, value: f64) -> Self {
+        self.field_filters
+            .push((name.into(), FieldPredicate::Gte(value)));
+        self
+    }
+
     /// Add a field less-than predicate.
     pub fn field_lt(mut self, name: impl Into<String>, value: f64) -> Self {
         self.field_filters
@@ -126,6 +165,38 @@ impl ScanFilter {
         self
     }
 
+    /// Add a field less-than-or-equal predicate.
+    pub fn field_lte(mut self, name: impl Into<String>, value: f64) -> Self {
+        self.field_filters
+            .push((name.into(), FieldPredicate::Lte(value)));
+        self
+    }
+
+    /// Add a field set-membership predicate.
+    pub fn field_in<I, V>(mut self, name: impl Into<String>, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<String>,
+    {
+        let values = values.into_iter().map(Into::into).collect();
+        self.field_filters
+            .push((name.into(), FieldPredicate::In(values)));
+        self
+    }
+
+    /// Add a predicate requiring the field to be absent or null.
+    pub fn field_is_null(mut self, name: impl Into<String>) -> Self {
+        self.field_filters.push((name.into(), FieldPredicate::IsNull));
+        self
+    }
+
+    /// Add a predicate requiring the field to be present and not null.
+    pub fn field_is_not_null(mut self, name: impl Into<String>) -> Self {
+        self.field_filters
+            .push((name.into(), FieldPredicate::IsNotNull));
+        self
+    }
+
     /// Test if a document matches this filter.
     pub fn matches(&self, doc: &Document) -> bool {
         // Time range check
@@ -155,12 +226,40 @@ impl ScanFilter {
                         return false;
                     }
                 }
-                None => return false, // Field not present = filter fails
+                // Field not present = filter fails, unless it asks for null
+                None => {
+                    if !predicate.matches_missing() {
+                        return false;
+                    }
+                }
             }
         }
 
         true
     }
+
+    /// Test whether a document *could* match, using only its index entry.
+    ///
+    /// Checks the time range and event type; field predicates need the full
+    /// document. A `false` result means the document can be skipped unread.
+    pub fn may_match_entry(&self, entry: &DocIndexEntry) -> bool {
+        if let Some(start) = &self.time_start {
+            if entry.timestamp < *start {
+                return false;
+            }
+        }
+        if let Some(end) = &self.time_end {
+            if entry.timestamp > *end {
+                return false;
+            }
+        }
+        if let Some(ref event_type) = self.event_type {
+            if &entry.event_type != event_type {
+                return false;
+            }
+        }
+        true
+    }
 }
 
 impl Default for ScanFilter {
@@ -240,6 +339,121 @@ mod tests {
         assert!(!pred.matches(&FieldValue::Integer(10)));
     }
 
+    #[test]
+    fn test_predicate_gte_lte_inclusive() {
+        let gte = FieldPredicate::Gte(10.0);
+        assert!(gte.matches(&FieldValue::Integer(10)));
+        assert!(gte.matches(&FieldValue::Float(10.5)));
+        assert!(!gte.matches(&FieldValue::Float(9.99)));
+
+        let lte = FieldPredicate::Lte(10.0);
+        assert!(lte.matches(&FieldValue::Integer(10)));
+        assert!(lte.matches(&FieldValue::Float(-1.0)));
+        assert!(!lte.matches(&FieldValue::Integer(11)));
+    }
+
+    #[test]
+    fn test_range_predicates_reject_non_numeric_types() {
+        for pred in [
+            FieldPredicate::Gt(0.0),
+            FieldPredicate::Gte(0.0),
+            FieldPredicate::Lt(100.0),
+            FieldPredicate::Lte(100.0),
+        ] {
+            assert!(!pred.matches(&FieldValue::Text("50".to_string())), "{pred:?}");
+            assert!(!pred.matches(&FieldValue::Boolean(true)), "{pred:?}");
+            assert!(!pred.matches(&FieldValue::Null), "{pred:?}");
+        }
+    }
+
+    #[test]
+    fn test_predicate_in_text_and_numeric() {
+        let pred = FieldPredicate::In(vec!["alice".to_string(), "7".to_string()]);
+        assert!(pred.matches(&FieldValue::Text("alice".to_string())));
+        assert!(!pred.matches(&FieldValue::Text("bob".to_string())));
+        assert!(pred.matches(&FieldValue::Integer(7)));
+        assert!(!pred.matches(&FieldValue::Integer(8)));
+        assert!(pred.matches(&FieldValue::Float(7.0)));
+        assert!(!pred.matches(&FieldValue::Null));
+
+        let empty = FieldPredicate::In(Vec::new());
+        assert!(!empty.matches(&FieldValue::Text("alice".to_string())));
+    }
+
+    #[test]
+    fn test_predicate_contains_rejects_numeric() {
+        let pred = FieldPredicate::Contains("4".to_string());
+        assert!(!pred.matches(&FieldValue::Integer(42)));
+        assert!(!pred.matches(&FieldValue::Float(4.2)));
+    }
+
+    #[test]
+    fn test_predicate_null_checks() {
+        assert!(FieldPredicate::IsNull.matches(&FieldValue::Null));
+        assert!(!FieldPredicate::IsNull.matches(&FieldValue::Text(String::new())));
+        assert!(!FieldPredicate::IsNull.matches(&FieldValue::Integer(0)));
+        assert!(FieldPredicate::IsNotNull.matches(&FieldValue::Integer(0)));
+        assert!(FieldPredicate::IsNotNull.matches(&FieldValue::Text("x".to_string())));
+        assert!(!FieldPredicate::IsNotNull.matches(&FieldValue::Null));
+    }
+
+    #[test]
+    fn test_filter_null_checks_handle_missing_fields() {
+        let with_null = make_doc("Login", Utc::now(), vec![("ip", FieldValue::Null)]);
+        let with_value = make_doc(
+            "Login",
+            Utc::now(),
+            vec![("ip", FieldValue::Text("10.0.0.1".to_string()))],
+        );
+        let missing = make_doc("Login", Utc::now(), vec![]);
+
+        let is_null = ScanFilter::new().field_is_null("ip");
+        assert!(is_null.matches(&with_null));
+        assert!(is_null.matches(&missing));
+        assert!(!is_null.matches(&with_value));
+
+        let not_null = ScanFilter::new().field_is_not_null("ip");
+        assert!(!not_null.matches(&with_null));
+        assert!(!not_null.matches(&missing));
+        assert!(not_null.matches(&with_value));
+    }
+
+    #[test]
+    fn test_filter_range_and_set_builders() {
+        let filter = ScanFilter::new()
+            .field_gte("score", 10.0)
+            .field_lte("score", 20.0)
+            .field_in("member", ["alice", "bob"]);
+
+        let doc = |member: &str, score: i64| {
+            make_doc(
+                "Game",
+                Utc::now(),
+                vec![
+                    ("member", FieldValue::Text(member.to_string())),
+                    ("score", FieldValue::Integer(score)),
+                ],
+            )
+        };
+
+        assert!(filter.matches(&doc("alice", 10)));
+        assert!(filter.matches(&doc("bob", 20)));
+        assert!(!filter.matches(&doc("carol", 15)));
+        assert!(!filter.matches(&doc("alice", 21)));
+        assert!(!filter.matches(&doc("bob", 9)));
+
+        // Numeric range on a text field doesn't match.
+        let text_score = make_doc(
+            "Game",
+            Utc::now(),
+            vec![
+                ("member", FieldValue::Text("alice".to_string())),
+                ("score", FieldValue::Text("15".to_string())),
+            ],
+        );
+        assert!(!filter.matches(&text_score));
+    }
+
     #[test]
     fn test_filter_empty_matches_all() {
         let filter = ScanFilter::new();
@@ -452,6 +666,30 @@ mod tests {
         assert_eq!(filter.field_filters.len(), 2);
     }
 
+    #[test]
+    fn test_may_match_entry_uses_time_and_event_type() {
+        let ts = Utc.with_ymd_and_hms(2025, 6, 14, 12, 0, 0).unwrap();
+        let entry = DocIndexEntry {
+            offset: 0,
+            length: 0,
+            timestamp: ts,
+            event_type: "Login".to_string(),
+            deleted: false,
+        };
+
+        assert!(ScanFilter::new().may_match_entry(&entry));
+        assert!(ScanFilter::new().event_type("Login").may_match_entry(&entry));
+        assert!(!ScanFilter::new().event_type("Logout").may_match_entry(&entry));
+        assert!(!ScanFilter::new()
+            .time_start(ts + chrono::Duration::seconds(1))
+            .may_match_entry(&entry));
+        assert!(!ScanFilter::new()
+            .time_end(ts - chrono::Duration::seconds(1))
+            .may_match_entry(&entry));
+        // Field predicates can't be decided from the index.
+        assert!(ScanFilter::new().field_eq("member", "alice").may_match_entry(&entry));
+    }
+
     #[test]
     fn test_default_filter() {
         let filter = ScanFilter::default();
@@ -18,6 +18,10 @@ pub struct DocIndexEntry {
     pub timestamp: DateTime<Utc>,
     /// Event type string (e.g. "Login", "GameOpened").
     pub event_type: String,
+    /// Tombstone: the document is deleted and skipped by reads and scans, but
+    /// its bytes stay in the segment until the next compaction.
+    #[serde(default)]
+    pub deleted: bool,
 }
 
 /// In-memory index mapping document IDs to their location and metadata within a segment.
@@ -47,11 +51,38 @@ impl DocIndex {
         self.entries.get(doc_id)
     }
 
-    /// Return the number of indexed documents.
+    /// Return the number of indexed documents, including tombstoned ones.
     pub fn len(&self) -> usize {
         self.entries.len()
     }
 
+    /// Return the number of documents that are not tombstoned.
+    pub fn live_len(&self) -> usize {
+        self.entries.values().filter(|e| !e.deleted).count()
+    }
+
+    /// Mark a document as deleted. Returns `false` if it isn't indexed or
+    /// was already tombstoned.
+    pub fn tombstone(&mut self, doc_id: &DocId) -> bool {
+        match self.entries.get_mut(doc_id) {
+            Some(entry) if !entry.deleted => {
+                entry.deleted = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether a document is tombstoned in this index.
+    pub fn is_deleted(&self, doc_id: &DocId) -> bool {
+        self.entries.get(doc_id).is_some_and(|e| e.deleted)
+    }
+
+    /// IDs of all tombstoned documents.
+    pub fn tombstoned(&self) -> impl Iterator<Item = &DocId> {
+        self.entries.iter().filter(|(_, e)| e.deleted).map(|(id, _)| id)
+    }
+
     /// Return true if the index contains no entries.
     pub fn is_empty(&self) -> bool {
         self.entries.is_empty()
@@ -73,7 +104,7 @@ impl DocIndex {
         for (doc_id, entry) in &self.entries {
             let tuple: (&DocId, &DocIndexEntry) = (doc_id, entry);
             let encoded =
-                rmp_serde::to_vec(&tuple).map_err(|e| StupidError::Serialize(e.to_string()))?;
+                rmp_serde::to_vec(&tuple).map_err(|e| StupidError::Serialize(e.into()))?;
 
             let len = encoded.len() as u32;
             file.write_all(&len.to_le_bytes())?;
@@ -102,14 +133,12 @@ impl DocIndex {
             pos += 4;
 
             if pos + len > data.len() {
-                return Err(StupidError::Serialize(
-                    "truncated index entry".to_string(),
-                ));
+                return Err(StupidError::Serialize("truncated index entry".into()));
             }
 
             let (doc_id, entry): (DocId, DocIndexEntry) =
                 rmp_serde::from_slice(&data[pos..pos + len])
-                    .map_err(|e| StupidError::Serialize(e.to_string()))?;
+                    .map_err(|e| StupidError::Serialize(e.into()))?;
 
             index.entries.insert(doc_id, entry);
             pos += len;
@@ -137,6 +166,7 @@ mod tests {
             length: 256,
             timestamp: Utc.with_ymd_and_hms(2025, 6, 14, 12, 0, 0).unwrap(),
             event_type: event_type.to_string(),
+            deleted: false,
         }
     }
 
@@ -248,4 +278,44 @@ mod tests {
         let index = DocIndex::default();
         assert!(index.is_empty());
     }
+
+    #[test]
+    fn tombstone_survives_save_and_load() {
+        let dir = std::env::temp_dir().join(format!("stupid_db_idx_{}", Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("documents.idx");
+
+        let mut index = DocIndex::new();
+        let id1 = Uuid::new_v4();
+        let id2 = Uuid::new_v4();
+        index.add(id1, sample_entry(0, "Login"));
+        index.add(id2, sample_entry(260, "Login"));
+
+        assert!(index.tombstone(&id1));
+        assert!(!index.tombstone(&id1), "already tombstoned");
+        assert!(!index.tombstone(&Uuid::new_v4()), "not indexed");
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.live_len(), 1);
+
+        index.save(&path).unwrap();
+        let loaded = DocIndex::load(&path).unwrap();
+        assert!(loaded.is_deleted(&id1));
+        assert!(!loaded.is_deleted(&id2));
+        assert_eq!(loaded.tombstoned().collect::<Vec<_>>(), vec![&id1]);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn entries_without_tombstone_flag_load_as_live() {
+        // Entries written before tombstones existed have four fields.
+        let legacy = (
+            Uuid::new_v4(),
+            (0u64, 256u32, Utc.with_ymd_and_hms(2025, 6, 14, 12, 0, 0).unwrap(), "Login"),
+        );
+        let encoded = rmp_serde::to_vec(&legacy).unwrap();
+        let (_, entry): (DocId, DocIndexEntry) = rmp_serde::from_slice(&encoded).unwrap();
+        assert_eq!(entry.event_type, "Login");
+        assert!(!entry.deleted);
+    }
 }
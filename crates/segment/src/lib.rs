@@ -1,11 +1,21 @@
+pub mod bloom;
+pub mod compaction;
 pub mod filter;
 pub mod index;
 pub mod manager;
+pub mod meta;
+pub mod projection;
 pub mod reader;
+pub mod reader_cache;
 pub mod schema;
+pub mod secondary;
 pub mod store;
+pub mod verify;
 pub mod writer;
 
 // Re-export key types
 pub use filter::{FieldPredicate, ScanFilter};
-pub use store::{DocumentStore, StoreStats};
+pub use meta::SegmentMeta;
+pub use reader_cache::{ReaderCache, DEFAULT_READER_CACHE_CAPACITY};
+pub use store::{DedupImportStats, DocumentStore, StoreStats};
+pub use verify::{RepairOutcome, SegmentIssue, SegmentProblem};
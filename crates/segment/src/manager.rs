@@ -1,35 +1,56 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use chrono::{DateTime, NaiveDate, Utc};
 use stupid_core::config::StorageConfig;
-use stupid_core::{SegmentId, StupidError};
+use stupid_core::{DocId, SegmentId, StupidError};
 use tracing::{info, warn};
 
+use crate::bloom::SegmentBloom;
+use crate::compaction;
+use crate::index::DocIndex;
+use crate::meta::SegmentMeta;
 use crate::reader::SegmentReader;
-use crate::writer::SegmentWriter;
+use crate::reader_cache::ReaderCache;
+use crate::writer::{base_segment_id, RolloverPolicy, SegmentWriter};
 
 /// Manages the lifecycle of time-partitioned segments: creation, sealing,
 /// reading, and TTL-based eviction.
 pub struct SegmentManager {
     data_dir: PathBuf,
     retention_days: u32,
+    /// Fields to build a secondary index on for new segments.
+    indexed_fields: Vec<String>,
+    /// Size limits after which new segments roll over into parts.
+    rollover: RolloverPolicy,
     /// Active writers keyed by segment ID (date string).
     writers: HashMap<SegmentId, SegmentWriter>,
-    /// Sealed segment readers keyed by segment ID.
-    readers: HashMap<SegmentId, SegmentReader>,
+    /// Sealed segments and their metadata, keyed by segment ID (rollover
+    /// parts are keyed by their own ID, e.g. `2025-06-14/part-001`).
+    sealed: HashMap<SegmentId, Option<SegmentMeta>>,
+    /// Bloom filters of sealed segments that have one, kept after their
+    /// readers are evicted so lookups can skip segments without reopening them.
+    blooms: HashMap<SegmentId, SegmentBloom>,
+    /// Open readers for sealed segments, shared across threads.
+    readers: Arc<ReaderCache>,
 }
 
 impl SegmentManager {
     /// Create a new SegmentManager, scanning `data_dir/segments/` for existing
     /// sealed segments (directories containing `documents.dat`) and opening a
-    /// reader for each.
+    /// reader for each, including its rollover parts. Segments that fail to
+    /// open are skipped; readers beyond the cache capacity are reopened on
+    /// demand.
     pub fn new(config: &StorageConfig) -> Result<Self, StupidError> {
         let segments_dir = config.data_dir.join("segments");
         fs::create_dir_all(&segments_dir)?;
+        compaction::recover(&segments_dir)?;
 
-        let mut readers = HashMap::new();
+        let mut sealed = HashMap::new();
+        let mut blooms = HashMap::new();
+        let readers = Arc::new(ReaderCache::default());
 
         let entries = fs::read_dir(&segments_dir)?;
         for entry in entries {
@@ -45,34 +66,57 @@ impl SegmentManager {
             }
 
             let segment_id = match path.file_name().and_then(|n| n.to_str()) {
-                Some(name) => name.to_string(),
-                None => continue,
+                Some(name) if !compaction::is_scratch_dir(name) => name.to_string(),
+                _ => continue,
             };
 
-            match SegmentReader::open(&config.data_dir, &segment_id) {
-                Ok(reader) => {
-                    info!(segment_id = %segment_id, "Opened existing segment reader");
-                    readers.insert(segment_id, reader);
-                }
-                Err(e) => {
-                    warn!(segment_id = %segment_id, error = %e, "Failed to open segment, skipping");
+            for part_id in SegmentReader::part_ids(&config.data_dir, &segment_id) {
+                match SegmentReader::open(&config.data_dir, &part_id) {
+                    Ok(reader) => {
+                        info!(segment_id = %part_id, "Opened existing segment reader");
+                        if let Some(bloom) = reader.bloom() {
+                            blooms.insert(part_id.clone(), bloom.clone());
+                        }
+                        sealed.insert(part_id, reader.meta().cloned());
+                        readers.insert(reader);
+                    }
+                    Err(e) => {
+                        warn!(segment_id = %part_id, error = %e, "Failed to open segment, skipping");
+                    }
                 }
             }
         }
 
         info!(
-            segment_count = readers.len(),
+            segment_count = sealed.len(),
             "SegmentManager initialized with existing segments"
         );
 
         Ok(Self {
             data_dir: config.data_dir.clone(),
             retention_days: config.segment_retention_days,
+            indexed_fields: config.indexed_fields.clone(),
+            rollover: RolloverPolicy::from_config(config),
             writers: HashMap::new(),
+            sealed,
+            blooms,
             readers,
         })
     }
 
+    /// Keep at most `capacity` segment readers open at once. Readers opened
+    /// so far are dropped and reopened on demand.
+    pub fn with_reader_cache_capacity(mut self, capacity: usize) -> Self {
+        self.readers = Arc::new(ReaderCache::new(capacity));
+        self
+    }
+
+    /// The cache holding this manager's open readers, for sharing with
+    /// other readers of the same data directory.
+    pub fn reader_cache(&self) -> &Arc<ReaderCache> {
+        &self.readers
+    }
+
     /// Derive the segment ID for a given timestamp (formatted as "YYYY-MM-DD").
     pub fn segment_id_for_timestamp(ts: &DateTime<Utc>) -> SegmentId {
         ts.format("%Y-%m-%d").to_string()
@@ -85,7 +129,9 @@ impl SegmentManager {
         segment_id: &str,
     ) -> Result<&mut SegmentWriter, StupidError> {
         if !self.writers.contains_key(segment_id) {
-            let writer = SegmentWriter::new(&self.data_dir, segment_id)?;
+            let writer = SegmentWriter::new(&self.data_dir, segment_id)?
+                .with_indexed_fields(&self.indexed_fields)
+                .with_rollover(self.rollover);
             info!(segment_id = %segment_id, "Created new segment writer");
             self.writers.insert(segment_id.to_string(), writer);
         }
@@ -93,34 +139,53 @@ impl SegmentManager {
     }
 
     /// Seal an active writer: finalize the compressed stream, write metadata,
-    /// then open the segment as a reader.
+    /// then open the segment (and any rollover parts) as readers.
     pub fn seal_segment(&mut self, segment_id: &str) -> Result<(), StupidError> {
         let writer = self
             .writers
             .remove(segment_id)
             .ok_or_else(|| StupidError::SegmentNotFound(segment_id.to_string()))?;
 
+        self.seal_writer(writer)?;
+
+        info!(segment_id = %segment_id, "Segment sealed and opened as reader");
+        Ok(())
+    }
+
+    fn seal_writer(&mut self, writer: SegmentWriter) -> Result<(), StupidError> {
+        let part_ids = writer.part_ids();
         writer.finalize()?;
 
-        let reader = SegmentReader::open(&self.data_dir, segment_id)?;
-        self.readers.insert(segment_id.to_string(), reader);
+        for part_id in part_ids {
+            self.open_sealed(&part_id)?;
+        }
+        Ok(())
+    }
 
-        info!(segment_id = %segment_id, "Segment sealed and opened as reader");
+    /// Open a freshly written segment, record it as sealed and cache its reader.
+    fn open_sealed(&mut self, segment_id: &str) -> Result<(), StupidError> {
+        let reader = SegmentReader::open(&self.data_dir, segment_id)?;
+        match reader.bloom() {
+            Some(bloom) => self.blooms.insert(segment_id.to_string(), bloom.clone()),
+            None => self.blooms.remove(segment_id),
+        };
+        self.sealed.insert(segment_id.to_string(), reader.meta().cloned());
+        self.readers.insert(reader);
         Ok(())
     }
 
     /// Evict segments whose date is older than `retention_days` from today.
-    /// Removes from the readers map and deletes the segment directory on disk.
+    /// Forgets the segment, drops its reader and deletes its directory on disk.
     /// Returns the list of evicted segment IDs.
     pub fn evict_expired(&mut self) -> Result<Vec<SegmentId>, StupidError> {
         let today = Utc::now().date_naive();
         let mut evicted = Vec::new();
 
         let expired_ids: Vec<SegmentId> = self
-            .readers
+            .sealed
             .keys()
             .filter(|sid| {
-                if let Ok(date) = NaiveDate::parse_from_str(sid, "%Y-%m-%d") {
+                if let Ok(date) = NaiveDate::parse_from_str(base_segment_id(sid), "%Y-%m-%d") {
                     let age = today.signed_duration_since(date).num_days();
                     age > self.retention_days as i64
                 } else {
@@ -131,7 +196,7 @@ impl SegmentManager {
             .collect();
 
         for sid in expired_ids {
-            self.readers.remove(&sid);
+            self.forget(&sid);
             let seg_dir = self.data_dir.join("segments").join(&sid);
             if seg_dir.exists() {
                 fs::remove_dir_all(&seg_dir)?;
@@ -143,12 +208,49 @@ impl SegmentManager {
         Ok(evicted)
     }
 
+    /// Delete a sealed segment and its rollover parts from disk.
+    ///
+    /// Returns the IDs of every segment that was removed (the segment itself
+    /// first, then its parts). Fails if the segment has an active writer.
+    pub fn delete_segment(&mut self, segment_id: &str) -> Result<Vec<SegmentId>, StupidError> {
+        if self.writers.contains_key(segment_id) {
+            return Err(StupidError::Storage(format!(
+                "segment {segment_id} has an active writer; seal it before deleting"
+            )));
+        }
+        if !self.sealed.contains_key(segment_id) {
+            return Err(StupidError::SegmentNotFound(segment_id.to_string()));
+        }
+
+        // Parts live inside the segment directory, so they go with it.
+        let part_prefix = format!("{segment_id}/");
+        let mut removed: Vec<SegmentId> = self
+            .sealed
+            .keys()
+            .filter(|sid| sid.starts_with(&part_prefix) && base_segment_id(sid) == segment_id)
+            .cloned()
+            .collect();
+        removed.sort();
+        removed.insert(0, segment_id.to_string());
+
+        for sid in &removed {
+            self.forget(sid);
+        }
+        let seg_dir = self.data_dir.join("segments").join(segment_id);
+        if seg_dir.exists() {
+            fs::remove_dir_all(&seg_dir)?;
+        }
+
+        info!(segment_id = %segment_id, parts = removed.len() - 1, "Segment deleted");
+        Ok(removed)
+    }
+
     /// List all known segment IDs (both active writers and sealed readers), sorted.
     pub fn list_segments(&self) -> Vec<SegmentId> {
         let mut ids: Vec<SegmentId> = self
             .writers
             .keys()
-            .chain(self.readers.keys())
+            .chain(self.sealed.keys())
             .cloned()
             .collect();
         ids.sort();
@@ -157,12 +259,67 @@ impl SegmentManager {
     }
 
     /// Look up a sealed segment reader by ID.
-    pub fn get_reader(&self, segment_id: &str) -> Option<&SegmentReader> {
-        self.readers.get(segment_id)
+    ///
+    /// Repeated lookups share one open reader until it is evicted from the
+    /// reader cache, after which the next lookup reopens it. Returns `None`
+    /// for unknown segments and segments that can no longer be opened.
+    pub fn get_reader(&self, segment_id: &str) -> Option<Arc<SegmentReader>> {
+        if !self.sealed.contains_key(segment_id) {
+            return None;
+        }
+        match self.readers.get_or_open(&self.data_dir, segment_id) {
+            Ok(reader) => Some(reader),
+            Err(e) => {
+                warn!(segment_id = %segment_id, error = %e, "Failed to reopen segment reader");
+                None
+            }
+        }
+    }
+
+    /// Whether a document with `id` may be in `segment_id`.
+    ///
+    /// `false` only for sealed segments whose bloom filter rules it out;
+    /// segments without a filter and active writers always may.
+    pub fn may_contain_id(&self, segment_id: &str, id: &DocId) -> bool {
+        self.blooms.get(segment_id).is_none_or(|b| b.may_contain_id(id))
+    }
+
+    /// Sealed segment IDs that may hold a document whose `field` equals
+    /// `value`, sorted. Segments whose bloom filter covers `field` and rules
+    /// the value out are skipped; all others are kept.
+    pub fn segments_may_contain(&self, field: &str, value: &str) -> Vec<SegmentId> {
+        let mut ids: Vec<SegmentId> = self
+            .sealed
+            .keys()
+            .filter(|sid| self.blooms.get(*sid).is_none_or(|b| b.may_contain(field, value)))
+            .cloned()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// Whether `segment_id` is a known sealed segment.
+    pub fn is_sealed(&self, segment_id: &str) -> bool {
+        self.sealed.contains_key(segment_id)
+    }
+
+    /// Metadata of a sealed segment; `None` if unknown or written without `meta.json`.
+    pub fn segment_meta(&self, segment_id: &str) -> Option<&SegmentMeta> {
+        self.sealed.get(segment_id)?.as_ref()
+    }
+
+    fn forget(&mut self, segment_id: &str) {
+        self.sealed.remove(segment_id);
+        self.blooms.remove(segment_id);
+        self.readers.remove(segment_id);
     }
 
     /// Return segment IDs that overlap with the given time range.
     /// Both `start` and `end` are optional (unbounded if None).
+    ///
+    /// Sealed segments with a recorded time range in `meta.json` are matched
+    /// on their actual min/max timestamps; others fall back to the date in
+    /// their segment ID.
     pub fn segments_in_range(
         &self,
         start: Option<DateTime<Utc>>,
@@ -175,7 +332,16 @@ impl SegmentManager {
             .list_segments()
             .into_iter()
             .filter(|sid| {
-                if let Ok(date) = NaiveDate::parse_from_str(sid, "%Y-%m-%d") {
+                let meta = self.segment_meta(sid);
+                if let Some(meta) = meta {
+                    if meta.document_count == 0 {
+                        return false;
+                    }
+                    if let Some(overlaps) = meta.overlaps(start, end) {
+                        return overlaps;
+                    }
+                }
+                if let Ok(date) = NaiveDate::parse_from_str(base_segment_id(sid), "%Y-%m-%d") {
                     let after_start = start_date.is_none_or(|s| date >= s);
                     let before_end = end_date.is_none_or(|e| date <= e);
                     after_start && before_end
@@ -188,14 +354,102 @@ impl SegmentManager {
         result
     }
 
+    /// Merge several sealed segments into `target_id`, ordered by timestamp.
+    ///
+    /// Rewrites `documents.dat`, `documents.idx`, and `meta.json` for the
+    /// target and deletes the sources once the merged segment is in place.
+    /// `target_id` may be one of the sources; otherwise it must not exist yet.
+    /// Returns the merged segment's document index.
+    ///
+    /// The merged segment is written to a scratch directory and renamed into
+    /// place, so a crash at any point leaves either the original segments or
+    /// the merged one (see [`compaction`](crate::compaction)).
+    pub fn compact(
+        &mut self,
+        segment_ids: &[SegmentId],
+        target_id: &str,
+    ) -> Result<DocIndex, StupidError> {
+        self.compact_excluding(segment_ids, target_id, &HashSet::new())
+    }
+
+    /// Like [`compact`](Self::compact), but drops the documents in `exclude`
+    /// (e.g. tombstoned ones) from the merged segment.
+    pub fn compact_excluding(
+        &mut self,
+        segment_ids: &[SegmentId],
+        target_id: &str,
+        exclude: &HashSet<DocId>,
+    ) -> Result<DocIndex, StupidError> {
+        if segment_ids.is_empty() {
+            return Err(StupidError::Storage("no segments to compact".to_string()));
+        }
+        for sid in segment_ids.iter().map(String::as_str).chain([target_id]) {
+            if self.writers.contains_key(sid) {
+                return Err(StupidError::Storage(format!(
+                    "segment {sid} has an active writer; seal it before compacting"
+                )));
+            }
+        }
+        let segments_dir = self.data_dir.join("segments");
+        let target_is_source = segment_ids.iter().any(|s| s == target_id);
+        if !target_is_source && segments_dir.join(target_id).exists() {
+            return Err(StupidError::Storage(format!(
+                "compaction target {target_id} already exists"
+            )));
+        }
+
+        // Gather documents; a stable sort keeps source order for equal timestamps.
+        let mut docs = Vec::new();
+        for sid in segment_ids {
+            if !self.sealed.contains_key(sid) {
+                return Err(StupidError::SegmentNotFound(sid.clone()));
+            }
+            let reader = self.readers.get_or_open(&self.data_dir, sid)?;
+            for doc in reader.iter() {
+                let doc = doc?;
+                if !exclude.contains(&doc.id) {
+                    docs.push(doc);
+                }
+            }
+        }
+        docs.sort_by_key(|d| d.timestamp);
+
+        let scratch = compaction::compacting_dir(&segments_dir, target_id);
+        let index = compaction::write_merged(
+            &scratch,
+            target_id,
+            &docs,
+            &self.indexed_fields,
+            segment_ids,
+        )?;
+
+        for sid in segment_ids {
+            self.forget(sid);
+        }
+        let target_dir = segments_dir.join(target_id);
+        if target_dir.exists() {
+            fs::rename(&target_dir, compaction::replaced_dir(&segments_dir, target_id))?;
+        }
+        fs::rename(&scratch, &target_dir)?;
+        compaction::finish(&segments_dir, target_id)?;
+
+        self.open_sealed(target_id)?;
+
+        info!(
+            sources = segment_ids.len(),
+            target = %target_id,
+            documents = docs.len(),
+            "Segments compacted"
+        );
+        Ok(index)
+    }
+
     /// Seal all active writers: drain the writers map, finalize each, and open
     /// as readers.
     pub fn flush_all(&mut self) -> Result<(), StupidError> {
         let writers: HashMap<SegmentId, SegmentWriter> = self.writers.drain().collect();
-        for (sid, writer) in writers {
-            writer.finalize()?;
-            let reader = SegmentReader::open(&self.data_dir, &sid)?;
-            self.readers.insert(sid, reader);
+        for writer in writers.into_values() {
+            self.seal_writer(writer)?;
         }
         info!("All active writers flushed and sealed");
         Ok(())
@@ -221,6 +475,10 @@ mod tests {
             segment_retention_days: 30,
             cache_dir: data_dir.join("cache"),
             cache_max_gb: 1,
+            indexed_fields: Vec::new(),
+            segment_max_docs: 0,
+            segment_max_bytes: 0,
+            in_memory: false,
         }
     }
 
@@ -282,6 +540,31 @@ mod tests {
         fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn test_seal_opens_rollover_parts() {
+        let dir = temp_dir();
+        let mut config = make_config(dir.clone());
+        config.segment_max_docs = 2;
+        let mut mgr = SegmentManager::new(&config).unwrap();
+
+        let sid = "2025-06-14";
+        let writer = mgr.get_or_create_writer(sid).unwrap();
+        for _ in 0..5 {
+            writer.append(&make_doc("Login")).unwrap();
+        }
+        assert_eq!(writer.part_id(), "2025-06-14/part-002");
+        mgr.seal_segment(sid).unwrap();
+
+        assert_eq!(
+            mgr.list_segments(),
+            vec!["2025-06-14", "2025-06-14/part-001", "2025-06-14/part-002"]
+        );
+        let total: usize = mgr.sealed.keys().map(|sid| mgr.get_reader(sid).unwrap().iter().count()).sum();
+        assert_eq!(total, 5);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_list_segments_sorted() {
         let dir = temp_dir();
@@ -351,12 +634,12 @@ mod tests {
             .unwrap();
 
         assert_eq!(mgr.writers.len(), 2);
-        assert_eq!(mgr.readers.len(), 0);
+        assert_eq!(mgr.sealed.len(), 0);
 
         mgr.flush_all().unwrap();
 
         assert_eq!(mgr.writers.len(), 0);
-        assert_eq!(mgr.readers.len(), 2);
+        assert_eq!(mgr.sealed.len(), 2);
 
         // Verify both are readable
         assert!(mgr.get_reader("2025-06-14").is_some());
@@ -373,6 +656,10 @@ mod tests {
             segment_retention_days: 5,
             cache_dir: dir.join("cache"),
             cache_max_gb: 1,
+            indexed_fields: Vec::new(),
+            segment_max_docs: 0,
+            segment_max_bytes: 0,
+            in_memory: false,
         };
         let mut mgr = SegmentManager::new(&config).unwrap();
 
@@ -394,7 +681,7 @@ mod tests {
             .unwrap();
 
         mgr.flush_all().unwrap();
-        assert_eq!(mgr.readers.len(), 2);
+        assert_eq!(mgr.sealed.len(), 2);
 
         let evicted = mgr.evict_expired().unwrap();
         assert_eq!(evicted, vec![old_sid.clone()]);
@@ -429,6 +716,188 @@ mod tests {
         fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn test_compact_rejects_active_writer() {
+        let dir = temp_dir();
+        let config = make_config(dir.clone());
+        let mut mgr = SegmentManager::new(&config).unwrap();
+
+        mgr.get_or_create_writer("2025-06-14").unwrap();
+        let result = mgr.compact(&["2025-06-14".to_string()], "2025-06-15");
+        assert!(result.is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_recover_discards_incomplete_compaction() {
+        let dir = temp_dir();
+        let config = make_config(dir.clone());
+        let mut mgr = SegmentManager::new(&config).unwrap();
+
+        let doc = make_doc("Login");
+        mgr.get_or_create_writer("2025-06-14").unwrap().append(&doc).unwrap();
+        mgr.flush_all().unwrap();
+        drop(mgr);
+
+        // Simulate a crash while the merged segment was still being written.
+        let segments_dir = dir.join("segments");
+        let scratch = compaction::compacting_dir(&segments_dir, "2025-06-14");
+        fs::create_dir_all(&scratch).unwrap();
+        fs::write(scratch.join("documents.dat"), b"partial").unwrap();
+
+        let mgr = SegmentManager::new(&config).unwrap();
+        assert!(!scratch.exists());
+        assert_eq!(mgr.list_segments(), vec!["2025-06-14"]);
+        assert_eq!(mgr.get_reader("2025-06-14").unwrap().iter().count(), 1);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_recover_finishes_deleting_sources() {
+        let dir = temp_dir();
+        let config = make_config(dir.clone());
+        let mut mgr = SegmentManager::new(&config).unwrap();
+
+        for sid in ["2025-06-14", "2025-06-15"] {
+            mgr.get_or_create_writer(sid).unwrap().append(&make_doc("Login")).unwrap();
+        }
+        mgr.flush_all().unwrap();
+        drop(mgr);
+
+        // Simulate a crash after the merged segment was renamed into place
+        // (target moved aside) but before the sources were deleted.
+        let segments_dir = dir.join("segments");
+        let sources = vec!["2025-06-14".to_string(), "2025-06-15".to_string()];
+        let docs: Vec<Document> = sources
+            .iter()
+            .flat_map(|sid| {
+                SegmentReader::open(&dir, sid)
+                    .unwrap()
+                    .iter()
+                    .map(|d| d.unwrap())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let scratch = compaction::compacting_dir(&segments_dir, "2025-06-14");
+        compaction::write_merged(&scratch, "2025-06-14", &docs, &[], &sources).unwrap();
+        fs::rename(
+            segments_dir.join("2025-06-14"),
+            compaction::replaced_dir(&segments_dir, "2025-06-14"),
+        )
+        .unwrap();
+        fs::rename(&scratch, segments_dir.join("2025-06-14")).unwrap();
+
+        let mgr = SegmentManager::new(&config).unwrap();
+        assert_eq!(mgr.list_segments(), vec!["2025-06-14"]);
+        assert_eq!(mgr.get_reader("2025-06-14").unwrap().iter().count(), 2);
+        assert!(!compaction::replaced_dir(&segments_dir, "2025-06-14").exists());
+        assert!(!segments_dir
+            .join("2025-06-14")
+            .join(compaction::COMPACTION_MARKER)
+            .exists());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_recover_restores_replaced_target() {
+        let dir = temp_dir();
+        let config = make_config(dir.clone());
+        let mut mgr = SegmentManager::new(&config).unwrap();
+
+        mgr.get_or_create_writer("2025-06-14").unwrap().append(&make_doc("Login")).unwrap();
+        mgr.flush_all().unwrap();
+        drop(mgr);
+
+        // Crash between moving the target aside and renaming the merge in.
+        let segments_dir = dir.join("segments");
+        fs::rename(
+            segments_dir.join("2025-06-14"),
+            compaction::replaced_dir(&segments_dir, "2025-06-14"),
+        )
+        .unwrap();
+
+        let mgr = SegmentManager::new(&config).unwrap();
+        assert_eq!(mgr.list_segments(), vec!["2025-06-14"]);
+        assert_eq!(mgr.get_reader("2025-06-14").unwrap().iter().count(), 1);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_delete_segment_removes_parts_and_files() {
+        let dir = temp_dir();
+        let mut config = make_config(dir.clone());
+        config.segment_max_docs = 1;
+        let mut mgr = SegmentManager::new(&config).unwrap();
+
+        for sid in ["2025-06-14", "2025-06-15"] {
+            let writer = mgr.get_or_create_writer(sid).unwrap();
+            writer.append(&make_doc("Login")).unwrap();
+            writer.append(&make_doc("Login")).unwrap();
+        }
+        mgr.get_or_create_writer("2025-06-16").unwrap();
+        mgr.seal_segment("2025-06-14").unwrap();
+        mgr.seal_segment("2025-06-15").unwrap();
+
+        let removed = mgr.delete_segment("2025-06-14").unwrap();
+        assert_eq!(removed, vec!["2025-06-14", "2025-06-14/part-001"]);
+        assert!(!dir.join("segments").join("2025-06-14").exists());
+        assert_eq!(
+            mgr.list_segments(),
+            vec!["2025-06-15", "2025-06-15/part-001", "2025-06-16"]
+        );
+
+        assert!(mgr.delete_segment("2025-06-14").is_err(), "already deleted");
+        assert!(mgr.delete_segment("2025-06-16").is_err(), "active writer");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_get_reader_shares_cached_handle() {
+        let dir = temp_dir();
+        let config = make_config(dir.clone());
+        let mut mgr = SegmentManager::new(&config).unwrap();
+        for sid in ["2025-06-14", "2025-06-15", "2025-06-16"] {
+            mgr.get_or_create_writer(sid).unwrap().append(&make_doc("Login")).unwrap();
+        }
+        mgr.flush_all().unwrap();
+        drop(mgr);
+
+        let mgr = SegmentManager::new(&config).unwrap().with_reader_cache_capacity(2);
+        let first = mgr.get_reader("2025-06-14").unwrap();
+        let second = mgr.get_reader("2025-06-14").unwrap();
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(mgr.reader_cache().opens(), 1);
+
+        // Readers are shared with worker threads.
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let reader = first.clone();
+                std::thread::spawn(move || reader.iter().count())
+            })
+            .collect();
+        assert!(handles.into_iter().all(|h| h.join().unwrap() == 1));
+
+        // Beyond the capacity the least recently used reader is evicted and
+        // reopened on the next lookup; held handles stay usable.
+        mgr.get_reader("2025-06-15").unwrap();
+        mgr.get_reader("2025-06-16").unwrap();
+        assert_eq!(mgr.reader_cache().len(), 2);
+        assert_eq!(mgr.reader_cache().opens(), 3);
+        let reopened = mgr.get_reader("2025-06-14").unwrap();
+        assert!(!Arc::ptr_eq(&first, &reopened));
+        assert_eq!(mgr.reader_cache().opens(), 4);
+        assert_eq!(first.iter().count(), 1);
+
+        assert!(mgr.get_reader("2025-01-01").is_none());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn test_seal_nonexistent_segment_errors() {
         let dir = temp_dir();
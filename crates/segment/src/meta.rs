@@ -0,0 +1,158 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use stupid_core::{Document, StupidError};
+
+/// File name of the segment metadata, stored alongside `documents.dat`.
+pub const META_FILE: &str = "meta.json";
+
+/// Segment metadata stored as `meta.json`.
+///
+/// Written by [`SegmentWriter::finalize`](crate::writer::SegmentWriter::finalize).
+/// The time range, event-type breakdown, and field names let callers answer
+/// catalog and range questions without scanning `documents.dat`. Segments
+/// written before these were recorded load with them empty.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SegmentMeta {
+    pub segment_id: String,
+    pub document_count: usize,
+    /// Compressed size of `documents.dat`.
+    pub size_bytes: u64,
+    /// Uncompressed size of the document stream.
+    pub raw_bytes: u64,
+    pub compression: String,
+    /// Earliest document timestamp, if any documents were written.
+    #[serde(default)]
+    pub min_timestamp: Option<DateTime<Utc>>,
+    /// Latest document timestamp, if any documents were written.
+    #[serde(default)]
+    pub max_timestamp: Option<DateTime<Utc>>,
+    /// Document count per event type.
+    #[serde(default)]
+    pub event_type_counts: BTreeMap<String, u64>,
+    /// Distinct field names across all documents.
+    #[serde(default)]
+    pub field_names: BTreeSet<String>,
+}
+
+impl SegmentMeta {
+    /// Create empty metadata for a segment.
+    pub fn new(segment_id: impl Into<String>) -> Self {
+        Self {
+            segment_id: segment_id.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Fold a document's timestamp, event type, and field names into the stats.
+    pub fn observe(&mut self, doc: &Document) {
+        self.document_count += 1;
+        self.min_timestamp = Some(self.min_timestamp.map_or(doc.timestamp, |t| t.min(doc.timestamp)));
+        self.max_timestamp = Some(self.max_timestamp.map_or(doc.timestamp, |t| t.max(doc.timestamp)));
+        *self.event_type_counts.entry(doc.event_type.clone()).or_insert(0) += 1;
+        for name in doc.fields.keys() {
+            if !self.field_names.contains(name) {
+                self.field_names.insert(name.clone());
+            }
+        }
+    }
+
+    /// Whether the segment's recorded time range overlaps `[start, end]`.
+    ///
+    /// Returns `None` when no time range was recorded (legacy metadata).
+    pub fn overlaps(
+        &self,
+        start: Option<DateTime<Utc>>,
+        end: Option<DateTime<Utc>>,
+    ) -> Option<bool> {
+        let (min, max) = (self.min_timestamp?, self.max_timestamp?);
+        Some(start.is_none_or(|s| max >= s) && end.is_none_or(|e| min <= e))
+    }
+
+    /// Load `meta.json` from a segment directory, if present.
+    pub fn load(segment_dir: &Path) -> Result<Option<Self>, StupidError> {
+        let path = segment_dir.join(META_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| StupidError::Serialize(e.into()))
+    }
+
+    /// Write `meta.json` into a segment directory.
+    pub fn save(&self, segment_dir: &Path) -> Result<(), StupidError> {
+        let json = serde_json::to_string_pretty(self)
+            .map_err(|e| StupidError::Serialize(e.into()))?;
+        // Write then rename, so readers never see a partly written file.
+        let tmp = segment_dir.join(format!(".{META_FILE}.tmp"));
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, segment_dir.join(META_FILE))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use stupid_core::FieldValue;
+    use uuid::Uuid;
+
+    fn doc(event_type: &str, hour: u32, fields: &[&str]) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            timestamp: Utc.with_ymd_and_hms(2025, 6, 14, hour, 0, 0).unwrap(),
+            event_type: event_type.to_string(),
+            fields: fields
+                .iter()
+                .map(|f| (f.to_string(), FieldValue::Text("x".to_string())))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn observe_tracks_range_counts_and_fields() {
+        let mut meta = SegmentMeta::new("2025-06-14");
+        meta.observe(&doc("Login", 12, &["memberCode"]));
+        meta.observe(&doc("Login", 3, &["memberCode", "ip"]));
+        meta.observe(&doc("GameOpened", 20, &["gameUid"]));
+
+        assert_eq!(meta.document_count, 3);
+        assert_eq!(meta.min_timestamp, Some(Utc.with_ymd_and_hms(2025, 6, 14, 3, 0, 0).unwrap()));
+        assert_eq!(meta.max_timestamp, Some(Utc.with_ymd_and_hms(2025, 6, 14, 20, 0, 0).unwrap()));
+        assert_eq!(meta.event_type_counts["Login"], 2);
+        assert_eq!(meta.event_type_counts["GameOpened"], 1);
+        assert_eq!(
+            meta.field_names.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec!["gameUid", "ip", "memberCode"]
+        );
+    }
+
+    #[test]
+    fn overlaps_uses_recorded_range() {
+        let mut meta = SegmentMeta::new("s");
+        assert_eq!(meta.overlaps(None, None), None);
+
+        meta.observe(&doc("Login", 10, &[]));
+        meta.observe(&doc("Login", 12, &[]));
+        let at = |h| Some(Utc.with_ymd_and_hms(2025, 6, 14, h, 0, 0).unwrap());
+
+        assert_eq!(meta.overlaps(at(11), at(11)), Some(true));
+        assert_eq!(meta.overlaps(at(12), None), Some(true));
+        assert_eq!(meta.overlaps(at(13), None), Some(false));
+        assert_eq!(meta.overlaps(None, at(9)), Some(false));
+    }
+
+    #[test]
+    fn legacy_meta_without_stats_loads() {
+        let json = r#"{"segment_id":"2025-06-14","document_count":3,"size_bytes":10,"raw_bytes":20,"compression":"zstd"}"#;
+        let meta: SegmentMeta = serde_json::from_str(json).unwrap();
+        assert_eq!(meta.document_count, 3);
+        assert!(meta.min_timestamp.is_none());
+        assert!(meta.event_type_counts.is_empty());
+    }
+}
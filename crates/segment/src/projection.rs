@@ -0,0 +1,213 @@
+//! Field-selective document decoding (projection pushdown).
+//!
+//! Segment records are msgpack-encoded [`Document`]s. Decoding one fully
+//! allocates every field key and value, even when the caller only looks at a
+//! few of them. [`decode_projected`] walks the same encoding but only
+//! materializes the requested fields; the rest are skipped in place without
+//! allocating.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::Deserializer;
+use stupid_core::{DocId, Document, FieldValue, StupidError};
+
+/// Decode a msgpack-encoded document, keeping only `fields` in
+/// [`Document::fields`]. `id`, `timestamp` and `event_type` are always decoded.
+pub fn decode_projected(bytes: &[u8], fields: &[&str]) -> Result<Document, StupidError> {
+    let mut de = rmp_serde::Deserializer::from_read_ref(bytes);
+    DocumentSeed { fields }
+        .deserialize(&mut de)
+        .map_err(|e| StupidError::Serialize(e.into()))
+}
+
+const DOCUMENT_FIELDS: &[&str] = &["id", "timestamp", "event_type", "fields"];
+
+struct DocumentSeed<'f> {
+    fields: &'f [&'f str],
+}
+
+impl<'de> DeserializeSeed<'de> for DocumentSeed<'_> {
+    type Value = Document;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Document, D::Error> {
+        deserializer.deserialize_struct("Document", DOCUMENT_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for DocumentSeed<'_> {
+    type Value = Document;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a Document")
+    }
+
+    /// Compact encoding (what `rmp_serde::to_vec` writes): fields in declaration order.
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Document, A::Error> {
+        let missing = |i| de::Error::invalid_length(i, &"a Document with 4 fields");
+        let id: DocId = seq.next_element()?.ok_or_else(|| missing(0))?;
+        let timestamp = seq.next_element()?.ok_or_else(|| missing(1))?;
+        let event_type = seq.next_element()?.ok_or_else(|| missing(2))?;
+        let fields = seq
+            .next_element_seed(FieldsSeed { fields: self.fields })?
+            .ok_or_else(|| missing(3))?;
+        Ok(Document { id, timestamp, event_type, fields })
+    }
+
+    /// Named encoding (`rmp_serde::to_vec_named`).
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Document, A::Error> {
+        let (mut id, mut timestamp, mut event_type, mut fields) = (None, None, None, None);
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "id" => id = Some(map.next_value()?),
+                "timestamp" => timestamp = Some(map.next_value()?),
+                "event_type" => event_type = Some(map.next_value()?),
+                "fields" => fields = Some(map.next_value_seed(FieldsSeed { fields: self.fields })?),
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(Document {
+            id: id.ok_or_else(|| de::Error::missing_field("id"))?,
+            timestamp: timestamp.ok_or_else(|| de::Error::missing_field("timestamp"))?,
+            event_type: event_type.ok_or_else(|| de::Error::missing_field("event_type"))?,
+            fields: fields.ok_or_else(|| de::Error::missing_field("fields"))?,
+        })
+    }
+}
+
+/// Decodes the `fields` map, skipping values whose key isn't requested.
+struct FieldsSeed<'f> {
+    fields: &'f [&'f str],
+}
+
+impl<'de> DeserializeSeed<'de> for FieldsSeed<'_> {
+    type Value = HashMap<String, FieldValue>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldsSeed<'_> {
+    type Value = HashMap<String, FieldValue>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of document fields")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut out = HashMap::with_capacity(self.fields.len());
+        while let Some(key) = map.next_key_seed(KeySeed { fields: self.fields })? {
+            match key {
+                Some(name) => {
+                    out.insert(name, map.next_value::<FieldValue>()?);
+                }
+                None => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Reads a field key, allocating it only if it is one of the requested fields.
+struct KeySeed<'f> {
+    fields: &'f [&'f str],
+}
+
+impl<'de> DeserializeSeed<'de> for KeySeed<'_> {
+    type Value = Option<String>;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed<'_> {
+    type Value = Option<String>;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a field name")
+    }
+
+    fn visit_str<E: de::Error>(self, key: &str) -> Result<Self::Value, E> {
+        Ok(self.fields.contains(&key).then(|| key.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn make_doc() -> Document {
+        let mut fields = HashMap::new();
+        fields.insert("memberCode".to_string(), FieldValue::Text("alice".to_string()));
+        fields.insert("platform".to_string(), FieldValue::Text("web".to_string()));
+        fields.insert("amount".to_string(), FieldValue::Float(12.5));
+        fields.insert("count".to_string(), FieldValue::Integer(3));
+        fields.insert("vip".to_string(), FieldValue::Boolean(true));
+        fields.insert("note".to_string(), FieldValue::Null);
+        Document {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: "Login".to_string(),
+            fields,
+        }
+    }
+
+    #[test]
+    fn test_projection_keeps_only_requested_fields() {
+        let doc = make_doc();
+        let bytes = rmp_serde::to_vec(&doc).unwrap();
+
+        let projected = decode_projected(&bytes, &["memberCode", "count", "missing"]).unwrap();
+        assert_eq!(projected.id, doc.id);
+        assert_eq!(projected.timestamp, doc.timestamp);
+        assert_eq!(projected.event_type, "Login");
+        assert_eq!(projected.fields.len(), 2);
+        assert_eq!(projected.fields["memberCode"], doc.fields["memberCode"]);
+        assert_eq!(projected.fields["count"], FieldValue::Integer(3));
+    }
+
+    #[test]
+    fn test_projection_of_all_fields_matches_full_decode() {
+        let doc = make_doc();
+        let bytes = rmp_serde::to_vec(&doc).unwrap();
+        let names: Vec<&str> = doc.fields.keys().map(String::as_str).collect();
+
+        let projected = decode_projected(&bytes, &names).unwrap();
+        assert_eq!(projected.fields, doc.fields);
+    }
+
+    #[test]
+    fn test_projection_reads_named_encoding() {
+        let doc = make_doc();
+        let bytes = rmp_serde::to_vec_named(&doc).unwrap();
+
+        let projected = decode_projected(&bytes, &["platform"]).unwrap();
+        assert_eq!(projected.id, doc.id);
+        assert_eq!(projected.fields.len(), 1);
+        assert_eq!(projected.fields["platform"], FieldValue::Text("web".to_string()));
+    }
+
+    #[test]
+    fn test_projection_with_no_fields() {
+        let doc = make_doc();
+        let bytes = rmp_serde::to_vec(&doc).unwrap();
+
+        let projected = decode_projected(&bytes, &[]).unwrap();
+        assert_eq!(projected.event_type, "Login");
+        assert!(projected.fields.is_empty());
+    }
+
+    #[test]
+    fn test_projection_rejects_garbage() {
+        assert!(decode_projected(&[0xc1, 0x00], &["memberCode"]).is_err());
+    }
+}
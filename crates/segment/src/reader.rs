@@ -1,7 +1,31 @@
+use std::collections::BTreeSet;
 use std::path::Path;
 
 use memmap2::Mmap;
-use stupid_core::{Document, SegmentId, StupidError};
+use stupid_core::{DocId, Document, SegmentId, StupidError};
+use tracing::warn;
+
+use crate::bloom::{SegmentBloom, BLOOM_FILTER_FILE};
+use crate::filter::ScanFilter;
+use crate::index::DocIndex;
+use crate::meta::SegmentMeta;
+use crate::projection::decode_projected;
+use crate::secondary::{SecondaryIndex, SECONDARY_INDEX_FILE};
+use crate::writer::PART_PREFIX;
+
+/// Counters from a filtered scan, for observing how much work the index saved.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ScanStats {
+    /// Documents considered (index entries, or every document on fallback).
+    pub examined: usize,
+    /// Documents actually deserialized from the segment data.
+    pub deserialized: usize,
+    /// Documents that matched the full filter.
+    pub matched: usize,
+}
+
+/// Magic number opening every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xB5, 0x2F, 0xFD];
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Compression {
@@ -13,6 +37,13 @@ pub struct SegmentReader {
     segment_id: SegmentId,
     /// Decompressed data (either raw mmap or zstd-decompressed buffer).
     data: SegmentData,
+    /// Secondary field index (empty if the segment was written without one).
+    secondary: SecondaryIndex,
+    /// Parsed `meta.json`, if the segment has one.
+    meta: Option<SegmentMeta>,
+    /// Bloom filter over document IDs and indexed field values, if the
+    /// segment was written with one.
+    bloom: Option<SegmentBloom>,
 }
 
 enum SegmentData {
@@ -38,11 +69,24 @@ impl SegmentReader {
             return Err(StupidError::SegmentNotFound(segment_id.to_string()));
         }
 
-        let compression = Self::detect_compression(&seg_dir);
+        // The metadata only describes the data, so a damaged meta.json is
+        // logged and the segment is read without it.
+        let meta = SegmentMeta::load(&seg_dir).unwrap_or_else(|e| {
+            warn!(segment_id = %segment_id, error = %e, "Ignoring unreadable meta.json");
+            None
+        });
 
         let file = std::fs::File::open(&doc_path)?;
         let mmap = unsafe { Mmap::map(&file)? };
 
+        let compression = match &meta {
+            Some(m) if m.compression == "zstd" => Compression::Zstd,
+            Some(_) => Compression::None,
+            // Without metadata, recognise compressed data by its frame magic.
+            None if mmap.starts_with(&ZSTD_MAGIC) => Compression::Zstd,
+            None => Compression::None,
+        };
+
         let data = match compression {
             Compression::None => SegmentData::Raw(mmap),
             Compression::Zstd => {
@@ -52,22 +96,59 @@ impl SegmentReader {
             }
         };
 
+        let secondary = SecondaryIndex::load(&seg_dir.join(SECONDARY_INDEX_FILE))?;
+        // The filter only lets lookups skip work, so a damaged one is ignored.
+        let bloom = SegmentBloom::load(&seg_dir.join(BLOOM_FILTER_FILE)).unwrap_or_else(|e| {
+            warn!(segment_id = %segment_id, error = %e, "Ignoring unreadable bloom filter");
+            None
+        });
+
         Ok(Self {
             segment_id: segment_id.to_string(),
             data,
+            secondary,
+            meta,
+            bloom,
         })
     }
 
-    fn detect_compression(seg_dir: &Path) -> Compression {
-        let meta_path = seg_dir.join("meta.json");
-        if let Ok(content) = std::fs::read_to_string(&meta_path) {
-            if let Ok(meta) = serde_json::from_str::<serde_json::Value>(&content) {
-                if meta.get("compression").and_then(|v| v.as_str()) == Some("zstd") {
-                    return Compression::Zstd;
-                }
-            }
+    /// Segment IDs of `segment_id` and its rollover parts that exist on disk,
+    /// base first and parts in order.
+    pub fn part_ids(data_dir: &Path, segment_id: &str) -> Vec<SegmentId> {
+        let seg_dir = data_dir.join("segments").join(segment_id);
+        let mut ids = Vec::new();
+        if seg_dir.join("documents.dat").exists() {
+            ids.push(segment_id.to_string());
         }
-        Compression::None
+
+        let mut parts: Vec<String> = match std::fs::read_dir(&seg_dir) {
+            Ok(entries) => entries
+                .filter_map(|e| e.ok())
+                .filter(|e| e.path().join("documents.dat").exists())
+                .filter_map(|e| e.file_name().to_str().map(str::to_string))
+                .filter(|name| name.starts_with(PART_PREFIX))
+                .collect(),
+            Err(_) => Vec::new(),
+        };
+        parts.sort();
+        ids.extend(parts.into_iter().map(|name| format!("{segment_id}/{name}")));
+        ids
+    }
+
+    /// Segment metadata from `meta.json`; `None` for segments written without one.
+    pub fn meta(&self) -> Option<&SegmentMeta> {
+        self.meta.as_ref()
+    }
+
+    /// Bloom filter from `bloom.idx`; `None` for segments written without one.
+    pub fn bloom(&self) -> Option<&SegmentBloom> {
+        self.bloom.as_ref()
+    }
+
+    /// Whether a document with `id` may be in this segment. Always `true`
+    /// without a bloom filter.
+    pub fn may_contain_id(&self, id: &DocId) -> bool {
+        self.bloom.as_ref().is_none_or(|b| b.may_contain_id(id))
     }
 
     pub fn segment_id(&self) -> &str {
@@ -90,7 +171,7 @@ impl SegmentReader {
         }
 
         rmp_serde::from_slice(&data[off + 4..off + 4 + len])
-            .map_err(|e| StupidError::Serialize(e.to_string()))
+            .map_err(|e| StupidError::Serialize(e.into()))
     }
 
     /// Iterate all documents in the segment.
@@ -101,6 +182,98 @@ impl SegmentReader {
         }
     }
 
+    /// Iterate all documents, decoding only the named `fields`.
+    ///
+    /// Each yielded document carries its `id`, `timestamp` and `event_type`,
+    /// but `fields` holds only the requested entries that are present. Use this
+    /// on load paths that read a handful of fields from every document.
+    pub fn iter_fields<'a>(&'a self, fields: &'a [&'a str]) -> ProjectedIter<'a> {
+        ProjectedIter {
+            data: self.data.as_slice(),
+            pos: 0,
+            fields,
+        }
+    }
+
+    /// Scan documents matching `filter`, consulting `index` before reading.
+    ///
+    /// Index entries whose timestamp or event type rule them out are skipped
+    /// without deserializing the document; remaining candidates are read in
+    /// file order and checked against the full filter. Results are identical to
+    /// filtering [`iter`](Self::iter), minus documents tombstoned in `index`.
+    /// Falls back to a full scan when the index is empty (e.g. segments
+    /// written without one).
+    pub fn scan_with_filter(
+        &self,
+        index: &DocIndex,
+        filter: &ScanFilter,
+    ) -> Result<(Vec<Document>, ScanStats), StupidError> {
+        let mut stats = ScanStats::default();
+        let mut results = Vec::new();
+
+        if index.is_empty() {
+            for doc_result in self.iter() {
+                let doc = doc_result?;
+                stats.examined += 1;
+                stats.deserialized += 1;
+                if filter.matches(&doc) {
+                    results.push(doc);
+                }
+            }
+            stats.matched = results.len();
+            return Ok((results, stats));
+        }
+
+        // Ordered, de-duplicated offsets keep results in file order.
+        let candidates: BTreeSet<u64> = index
+            .iter()
+            .inspect(|_| stats.examined += 1)
+            .filter(|(_, entry)| !entry.deleted && filter.may_match_entry(entry))
+            .map(|(_, entry)| entry.offset)
+            .collect();
+
+        for offset in candidates {
+            let doc = self.read_at(offset)?;
+            stats.deserialized += 1;
+            if filter.matches(&doc) {
+                results.push(doc);
+            }
+        }
+
+        stats.matched = results.len();
+        Ok((results, stats))
+    }
+
+    /// Find documents whose `field` equals `value`.
+    ///
+    /// Uses the secondary index when `field` is indexed, reading only the
+    /// matching offsets; otherwise falls back to a full scan. Returns nothing
+    /// without reading when the bloom filter rules the value out.
+    pub fn lookup(&self, field: &str, value: &str) -> Result<Vec<Document>, StupidError> {
+        if self.bloom.as_ref().is_some_and(|b| !b.may_contain(field, value)) {
+            return Ok(Vec::new());
+        }
+        match self.secondary.lookup(field, value) {
+            Some(offsets) => offsets.iter().map(|&offset| self.read_at(offset)).collect(),
+            None => {
+                let filter = ScanFilter::new().field_eq(field, value);
+                let mut results = Vec::new();
+                for doc_result in self.iter() {
+                    let doc = doc_result?;
+                    if filter.matches(&doc) {
+                        results.push(doc);
+                    }
+                }
+                Ok(results)
+            }
+        }
+    }
+
+    /// Whether `field` has a secondary index in this segment.
+    pub fn has_secondary_index(&self, field: &str) -> bool {
+        self.secondary.is_indexed(field)
+    }
+
     pub fn len(&self) -> usize {
         self.data.as_slice().len()
     }
@@ -129,7 +302,37 @@ impl<'a> Iterator for SegmentIter<'a> {
 
         let result: Result<Document, _> =
             rmp_serde::from_slice(&self.data[self.pos + 4..self.pos + 4 + len])
-                .map_err(|e| StupidError::Serialize(e.to_string()));
+                .map_err(|e| StupidError::Serialize(e.into()));
+
+        self.pos += 4 + len;
+        Some(result)
+    }
+}
+
+/// Iterator over documents decoded with only a subset of their fields.
+pub struct ProjectedIter<'a> {
+    data: &'a [u8],
+    pos: usize,
+    fields: &'a [&'a str],
+}
+
+impl<'a> Iterator for ProjectedIter<'a> {
+    type Item = Result<Document, StupidError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pos + 4 > self.data.len() {
+            return None;
+        }
+
+        let len = u32::from_le_bytes(
+            self.data[self.pos..self.pos + 4].try_into().unwrap(),
+        ) as usize;
+
+        if self.pos + 4 + len > self.data.len() {
+            return None;
+        }
+
+        let result = decode_projected(&self.data[self.pos + 4..self.pos + 4 + len], self.fields);
 
         self.pos += 4 + len;
         Some(result)
@@ -0,0 +1,163 @@
+//! Shared cache of opened segment readers.
+//!
+//! Opening a [`SegmentReader`] maps `documents.dat`, decompresses it and
+//! loads the secondary index, so callers that touch the same segment
+//! repeatedly should share one reader. [`ReaderCache`] hands out
+//! `Arc<SegmentReader>` handles keyed by segment ID and keeps at most
+//! `capacity` readers open, evicting the least recently used. Evicted
+//! readers stay valid for whoever still holds a handle.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use stupid_core::{SegmentId, StupidError};
+
+use crate::reader::SegmentReader;
+
+/// Readers kept open by default.
+pub const DEFAULT_READER_CACHE_CAPACITY: usize = 64;
+
+/// LRU-bounded, thread-safe cache of `Arc<SegmentReader>` keyed by segment ID.
+pub struct ReaderCache {
+    capacity: usize,
+    inner: Mutex<CacheInner>,
+    /// Readers opened from disk (cache misses), for observability.
+    opens: AtomicU64,
+}
+
+#[derive(Default)]
+struct CacheInner {
+    entries: HashMap<SegmentId, CacheEntry>,
+    /// Monotonic access counter; the entry with the lowest tick is evicted.
+    tick: u64,
+}
+
+struct CacheEntry {
+    reader: Arc<SegmentReader>,
+    last_used: u64,
+}
+
+impl CacheInner {
+    fn touch(&mut self, segment_id: &str) -> Option<Arc<SegmentReader>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(segment_id).map(|entry| {
+            entry.last_used = tick;
+            entry.reader.clone()
+        })
+    }
+
+    fn evict_to(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(sid, _)| sid.clone())
+            else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+impl ReaderCache {
+    /// A cache holding at most `capacity` readers (at least one).
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            inner: Mutex::new(CacheInner::default()),
+            opens: AtomicU64::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Readers currently held by the cache.
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Readers opened from disk so far.
+    pub fn opens(&self) -> u64 {
+        self.opens.load(Ordering::Relaxed)
+    }
+
+    /// The cached reader for `segment_id`, if it is currently open.
+    pub fn get(&self, segment_id: &str) -> Option<Arc<SegmentReader>> {
+        self.lock().touch(segment_id)
+    }
+
+    /// The cached reader for `segment_id`, opening it from `data_dir` on a
+    /// miss. The lock isn't held while opening, so concurrent misses on
+    /// different segments open in parallel; if two threads race on the same
+    /// segment, both get the reader that was cached first.
+    pub fn get_or_open(
+        &self,
+        data_dir: &Path,
+        segment_id: &str,
+    ) -> Result<Arc<SegmentReader>, StupidError> {
+        if let Some(reader) = self.get(segment_id) {
+            return Ok(reader);
+        }
+        let reader = SegmentReader::open(data_dir, segment_id)?;
+        self.opens.fetch_add(1, Ordering::Relaxed);
+
+        let mut inner = self.lock();
+        if let Some(existing) = inner.touch(segment_id) {
+            return Ok(existing);
+        }
+        Ok(self.insert_locked(&mut inner, segment_id, reader))
+    }
+
+    /// Cache an already opened reader, replacing any previous one.
+    pub fn insert(&self, reader: SegmentReader) -> Arc<SegmentReader> {
+        let segment_id = reader.segment_id().to_string();
+        let mut inner = self.lock();
+        inner.tick += 1;
+        self.insert_locked(&mut inner, &segment_id, reader)
+    }
+
+    fn insert_locked(
+        &self,
+        inner: &mut CacheInner,
+        segment_id: &str,
+        reader: SegmentReader,
+    ) -> Arc<SegmentReader> {
+        let reader = Arc::new(reader);
+        let last_used = inner.tick;
+        inner
+            .entries
+            .insert(segment_id.to_string(), CacheEntry { reader: reader.clone(), last_used });
+        inner.evict_to(self.capacity);
+        reader
+    }
+
+    /// Drop the cached reader for `segment_id` (e.g. after its files change).
+    pub fn remove(&self, segment_id: &str) {
+        self.lock().entries.remove(segment_id);
+    }
+
+    pub fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, CacheInner> {
+        self.inner.lock().expect("reader cache lock poisoned")
+    }
+}
+
+impl Default for ReaderCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_READER_CACHE_CAPACITY)
+    }
+}
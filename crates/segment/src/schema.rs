@@ -14,6 +14,42 @@ pub struct FieldStats {
     pub seen_count: u64,
     /// Number of documents where this field was absent or null.
     pub null_count: u64,
+    /// Every non-null type observed for this field, in the order first seen.
+    #[serde(default)]
+    pub type_history: Vec<FieldTypeEntry>,
+}
+
+/// A type a field took on, and the segment where it first appeared.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldTypeEntry {
+    pub field_type: String,
+    /// `None` when the document was observed outside a segment.
+    pub first_seen_segment: Option<String>,
+}
+
+/// A field whose type changed between observations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaConflict {
+    pub event_type: String,
+    pub field: String,
+    pub old_type: String,
+    pub new_type: String,
+    /// Segment where `new_type` was first seen.
+    pub first_seen_segment: Option<String>,
+}
+
+impl std::fmt::Display for SchemaConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}.{} changed type from {} to {}",
+            self.event_type, self.field, self.old_type, self.new_type
+        )?;
+        if let Some(segment) = &self.first_seen_segment {
+            write!(f, " in segment '{}'", segment)?;
+        }
+        Ok(())
+    }
 }
 
 impl FieldStats {
@@ -49,6 +85,9 @@ pub struct SchemaRegistry {
     pub schemas: HashMap<String, EventSchema>,
 }
 
+/// File in the data directory holding the persisted [`SchemaRegistry`].
+pub const SCHEMA_REGISTRY_FILE: &str = "schema_registry.json";
+
 /// Map a `FieldValue` variant to its type name string.
 fn field_type_name(value: &FieldValue) -> &'static str {
     match value {
@@ -60,6 +99,17 @@ fn field_type_name(value: &FieldValue) -> &'static str {
     }
 }
 
+/// Integers and floats are both numeric; a field switching between them is
+/// not a breaking change.
+fn types_compatible(a: &str, b: &str) -> bool {
+    a == b || matches!((a, b), ("integer", "float") | ("float", "integer"))
+}
+
+/// The most recent type in `history` that `new_type` is incompatible with.
+fn conflicting_type<'a>(history: &'a [FieldTypeEntry], new_type: &str) -> Option<&'a FieldTypeEntry> {
+    history.iter().rev().find(|t| !types_compatible(&t.field_type, new_type))
+}
+
 impl SchemaRegistry {
     /// Create an empty schema registry.
     pub fn new() -> SchemaRegistry {
@@ -74,6 +124,17 @@ impl SchemaRegistry {
     /// entry is created or updated. Fields previously known for this event type
     /// but absent from this document get their `null_count` incremented.
     pub fn observe(&mut self, doc: &Document) {
+        self.observe_inner(doc, None);
+    }
+
+    /// Like [`observe`](Self::observe), attributing new field types to
+    /// `segment_id`. Returns the conflicts this document introduced.
+    pub fn observe_in_segment(&mut self, doc: &Document, segment_id: &str) -> Vec<SchemaConflict> {
+        self.observe_inner(doc, Some(segment_id))
+    }
+
+    fn observe_inner(&mut self, doc: &Document, segment_id: Option<&str>) -> Vec<SchemaConflict> {
+        let mut conflicts = Vec::new();
         let schema = self
             .schemas
             .entry(doc.event_type.clone())
@@ -97,14 +158,39 @@ impl SchemaRegistry {
                     field_type: type_name.to_string(),
                     seen_count: 0,
                     null_count: schema.total_documents - 1,
+                    type_history: Vec::new(),
                 }
             });
 
             if matches!(value, FieldValue::Null) {
                 stats.null_count += 1;
             } else {
+                if stats.type_history.is_empty() && stats.seen_count > 0 {
+                    // Loaded from a registry saved before type history existed.
+                    stats.type_history.push(FieldTypeEntry {
+                        field_type: stats.field_type.clone(),
+                        first_seen_segment: None,
+                    });
+                }
                 stats.field_type = type_name.to_string();
                 stats.seen_count += 1;
+                // Each type is recorded once, so a field that keeps flipping
+                // between types reports its conflict only the first time.
+                if !stats.type_history.iter().any(|t| t.field_type == type_name) {
+                    if let Some(previous) = conflicting_type(&stats.type_history, type_name) {
+                        conflicts.push(SchemaConflict {
+                            event_type: doc.event_type.clone(),
+                            field: name.clone(),
+                            old_type: previous.field_type.clone(),
+                            new_type: type_name.to_string(),
+                            first_seen_segment: segment_id.map(str::to_string),
+                        });
+                    }
+                    stats.type_history.push(FieldTypeEntry {
+                        field_type: type_name.to_string(),
+                        first_seen_segment: segment_id.map(str::to_string),
+                    });
+                }
             }
         }
 
@@ -116,6 +202,31 @@ impl SchemaRegistry {
                 }
             }
         }
+
+        conflicts
+    }
+
+    /// Every incompatible type change recorded so far, sorted by event type
+    /// and field.
+    pub fn conflicts(&self) -> Vec<SchemaConflict> {
+        let mut conflicts = Vec::new();
+        for (event_type, schema) in &self.schemas {
+            for (field, stats) in &schema.fields {
+                for (i, entry) in stats.type_history.iter().enumerate() {
+                    if let Some(previous) = conflicting_type(&stats.type_history[..i], &entry.field_type) {
+                        conflicts.push(SchemaConflict {
+                            event_type: event_type.clone(),
+                            field: field.clone(),
+                            old_type: previous.field_type.clone(),
+                            new_type: entry.field_type.clone(),
+                            first_seen_segment: entry.first_seen_segment.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        conflicts.sort_by(|a, b| (&a.event_type, &a.field).cmp(&(&b.event_type, &b.field)));
+        conflicts
     }
 
     /// Look up the schema for a given event type.
@@ -133,12 +244,22 @@ impl SchemaRegistry {
     /// Persist the registry to a JSON file.
     pub fn save(&self, path: &Path) -> Result<(), StupidError> {
         let json = serde_json::to_string_pretty(self)
-            .map_err(|e| StupidError::Serialize(e.to_string()))?;
+            .map_err(|e| StupidError::Serialize(e.into()))?;
         std::fs::write(path, json)?;
         debug!("schema registry saved to {}", path.display());
         Ok(())
     }
 
+    /// Load the registry kept in `data_dir` (see [`SCHEMA_REGISTRY_FILE`]).
+    pub fn load_from_dir(data_dir: &Path) -> Result<SchemaRegistry, StupidError> {
+        Self::load(&data_dir.join(SCHEMA_REGISTRY_FILE))
+    }
+
+    /// Persist the registry to `data_dir` (see [`SCHEMA_REGISTRY_FILE`]).
+    pub fn save_to_dir(&self, data_dir: &Path) -> Result<(), StupidError> {
+        self.save(&data_dir.join(SCHEMA_REGISTRY_FILE))
+    }
+
     /// Load a registry from a JSON file. Returns an empty registry if the file
     /// does not exist.
     pub fn load(path: &Path) -> Result<SchemaRegistry, StupidError> {
@@ -151,7 +272,7 @@ impl SchemaRegistry {
         }
         let data = std::fs::read_to_string(path)?;
         let registry: SchemaRegistry =
-            serde_json::from_str(&data).map_err(|e| StupidError::Serialize(e.to_string()))?;
+            serde_json::from_str(&data).map_err(|e| StupidError::Serialize(e.into()))?;
         debug!("schema registry loaded from {}", path.display());
         Ok(registry)
     }
@@ -181,6 +302,7 @@ mod tests {
             field_type: "text".into(),
             seen_count: 0,
             null_count: 0,
+            type_history: Vec::new(),
         };
         assert_eq!(stats.null_rate(), 0.0);
     }
@@ -191,6 +313,7 @@ mod tests {
             field_type: "text".into(),
             seen_count: 3,
             null_count: 1,
+            type_history: Vec::new(),
         };
         assert!((stats.null_rate() - 0.25).abs() < f64::EPSILON);
     }
@@ -331,4 +454,70 @@ mod tests {
         assert!(registry.get_schema("login").unwrap().fields.contains_key("user"));
         assert!(!registry.get_schema("login").unwrap().fields.contains_key("score"));
     }
+
+    #[test]
+    fn test_type_change_is_reported_as_conflict() {
+        let mut registry = SchemaRegistry::new();
+        let amount = |v: FieldValue| make_doc("deposit", vec![("amount", v)]);
+
+        assert!(registry
+            .observe_in_segment(&amount(FieldValue::Text("10".into())), "deposit/2025-W01")
+            .is_empty());
+        // Nulls and repeats of the current type are not changes.
+        assert!(registry.observe_in_segment(&amount(FieldValue::Null), "deposit/2025-W02").is_empty());
+        assert!(registry
+            .observe_in_segment(&amount(FieldValue::Text("12".into())), "deposit/2025-W02")
+            .is_empty());
+
+        let new = registry.observe_in_segment(&amount(FieldValue::Integer(15)), "deposit/2025-W03");
+        let expected = SchemaConflict {
+            event_type: "deposit".into(),
+            field: "amount".into(),
+            old_type: "text".into(),
+            new_type: "integer".into(),
+            first_seen_segment: Some("deposit/2025-W03".into()),
+        };
+        assert_eq!(new, vec![expected.clone()]);
+        assert_eq!(registry.conflicts(), vec![expected]);
+        assert_eq!(
+            registry.conflicts()[0].to_string(),
+            "deposit.amount changed type from text to integer in segment 'deposit/2025-W03'"
+        );
+
+        let stats = &registry.get_schema("deposit").unwrap().fields["amount"];
+        assert_eq!(stats.type_history.len(), 2);
+        assert_eq!(stats.type_history[0].first_seen_segment.as_deref(), Some("deposit/2025-W01"));
+    }
+
+    #[test]
+    fn test_flip_flopping_type_is_recorded_and_reported_once() {
+        let mut registry = SchemaRegistry::new();
+        let mut reported = Vec::new();
+        for i in 0..100 {
+            let value = if i % 2 == 0 { FieldValue::Text("x".into()) } else { FieldValue::Integer(i) };
+            reported.extend(registry.observe_in_segment(&make_doc("evt", vec![("v", value)]), "s1"));
+        }
+        assert_eq!(reported.len(), 1);
+        assert_eq!(registry.conflicts(), reported);
+        assert_eq!(registry.get_schema("evt").unwrap().fields["v"].type_history.len(), 2);
+    }
+
+    #[test]
+    fn test_numeric_widening_is_not_a_conflict() {
+        let mut registry = SchemaRegistry::new();
+        registry.observe(&make_doc("evt", vec![("x", FieldValue::Integer(1))]));
+        registry.observe(&make_doc("evt", vec![("x", FieldValue::Float(1.5))]));
+        assert!(registry.conflicts().is_empty());
+
+        // History survives a save/load round trip, and old files without it still load.
+        let json = serde_json::to_string(&registry).unwrap();
+        let loaded: SchemaRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.get_schema("evt").unwrap().fields["x"].type_history.len(), 2);
+        let legacy = r#"{"schemas":{"evt":{"fields":{"x":{"field_type":"text","seen_count":1,"null_count":0}},"total_documents":1}}}"#;
+        let mut legacy: SchemaRegistry = serde_json::from_str(legacy).unwrap();
+        assert!(legacy.get_schema("evt").unwrap().fields["x"].type_history.is_empty());
+        let new = legacy.observe_in_segment(&make_doc("evt", vec![("x", FieldValue::Boolean(true))]), "s2");
+        assert_eq!(new.len(), 1);
+        assert_eq!((new[0].old_type.as_str(), new[0].first_seen_segment.as_deref()), ("text", Some("s2")));
+    }
 }
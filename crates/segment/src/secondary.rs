@@ -0,0 +1,215 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+use stupid_core::{Document, FieldValue, StupidError};
+
+/// File name of the secondary index, stored alongside `documents.idx`.
+pub const SECONDARY_INDEX_FILE: &str = "secondary.idx";
+
+/// Per-segment secondary index mapping a field value to document offsets.
+///
+/// Only fields named at construction are indexed. Values are keyed by their
+/// string form (`42`, `true`, `alice`), so lookups follow the same equality
+/// rules as [`FieldPredicate::Eq`](crate::FieldPredicate::Eq) for text,
+/// integers, and booleans.
+///
+/// Persisted as length-prefixed msgpack tuples of `(field, value, offsets)`,
+/// matching the layout of [`DocIndex`](crate::index::DocIndex).
+#[derive(Debug, Default)]
+pub struct SecondaryIndex {
+    fields: HashMap<String, HashMap<String, Vec<u64>>>,
+}
+
+impl SecondaryIndex {
+    /// Create an empty index over the given fields.
+    pub fn new(fields: &[String]) -> Self {
+        Self {
+            fields: fields
+                .iter()
+                .map(|f| (f.clone(), HashMap::new()))
+                .collect(),
+        }
+    }
+
+    /// Whether `field` is covered by this index.
+    pub fn is_indexed(&self, field: &str) -> bool {
+        self.fields.contains_key(field)
+    }
+
+    /// Names of the indexed fields.
+    pub fn indexed_fields(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// Return true if no fields are indexed.
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Record the indexed fields of a document written at `offset`.
+    pub fn observe(&mut self, doc: &Document, offset: u64) {
+        for (field, values) in &mut self.fields {
+            if let Some(key) = doc.fields.get(field).and_then(index_key) {
+                values.entry(key).or_default().push(offset);
+            }
+        }
+    }
+
+    /// Offsets of documents whose `field` equals `value`.
+    ///
+    /// Returns `None` if `field` is not indexed, and an empty slice if it is
+    /// indexed but no document has that value.
+    pub fn lookup(&self, field: &str, value: &str) -> Option<&[u64]> {
+        let values = self.fields.get(field)?;
+        Some(values.get(value).map(Vec::as_slice).unwrap_or(&[]))
+    }
+
+    /// Serialize the index to a file as length-prefixed msgpack tuples.
+    ///
+    /// Indexed fields with no values are written with an empty value so the
+    /// set of indexed fields survives a reload.
+    pub fn save(&self, path: &Path) -> Result<(), StupidError> {
+        let mut file = std::io::BufWriter::new(fs::File::create(path)?);
+
+        for (field, values) in &self.fields {
+            if values.is_empty() {
+                write_entry(&mut file, field, None, &[])?;
+            }
+            for (value, offsets) in values {
+                write_entry(&mut file, field, Some(value), offsets)?;
+            }
+        }
+
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Load an index from a length-prefixed msgpack file.
+    ///
+    /// If the file does not exist, returns an empty index.
+    pub fn load(path: &Path) -> Result<Self, StupidError> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let data = fs::read(path)?;
+        let mut index = Self::default();
+        let mut pos = 0;
+
+        while pos + 4 <= data.len() {
+            let len = u32::from_le_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+            pos += 4;
+
+            if pos + len > data.len() {
+                return Err(StupidError::Serialize("truncated secondary index entry".into()));
+            }
+
+            let (field, value, offsets): (String, Option<String>, Vec<u64>) =
+                rmp_serde::from_slice(&data[pos..pos + len])
+                    .map_err(|e| StupidError::Serialize(e.into()))?;
+
+            let values = index.fields.entry(field).or_default();
+            if let Some(value) = value {
+                values.entry(value).or_default().extend(offsets);
+            }
+            pos += len;
+        }
+
+        Ok(index)
+    }
+}
+
+fn write_entry(
+    file: &mut impl Write,
+    field: &str,
+    value: Option<&String>,
+    offsets: &[u64],
+) -> Result<(), StupidError> {
+    let encoded = rmp_serde::to_vec(&(field, value, offsets))
+        .map_err(|e| StupidError::Serialize(e.into()))?;
+    file.write_all(&(encoded.len() as u32).to_le_bytes())?;
+    file.write_all(&encoded)?;
+    Ok(())
+}
+
+/// String key used to index a field value; `Null` values are not indexed.
+pub(crate) fn index_key(value: &FieldValue) -> Option<String> {
+    match value {
+        FieldValue::Text(s) => Some(s.clone()),
+        FieldValue::Integer(i) => Some(i.to_string()),
+        FieldValue::Float(f) => Some(f.to_string()),
+        FieldValue::Boolean(b) => Some(b.to_string()),
+        FieldValue::Null => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn doc(member: &str) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            timestamp: Utc::now(),
+            event_type: "Login".to_string(),
+            fields: [("memberCode".to_string(), FieldValue::Text(member.to_string()))]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn observe_and_lookup() {
+        let mut index = SecondaryIndex::new(&["memberCode".to_string()]);
+        index.observe(&doc("alice"), 0);
+        index.observe(&doc("bob"), 100);
+        index.observe(&doc("alice"), 200);
+
+        assert_eq!(index.lookup("memberCode", "alice"), Some(&[0, 200][..]));
+        assert_eq!(index.lookup("memberCode", "bob"), Some(&[100][..]));
+        assert_eq!(index.lookup("memberCode", "carol"), Some(&[][..]));
+        assert_eq!(index.lookup("other", "alice"), None);
+    }
+
+    #[test]
+    fn non_text_values_are_keyed_by_string_form() {
+        let mut index = SecondaryIndex::new(&["score".to_string(), "vip".to_string()]);
+        let mut d = doc("alice");
+        d.fields.insert("score".to_string(), FieldValue::Integer(42));
+        d.fields.insert("vip".to_string(), FieldValue::Boolean(true));
+        index.observe(&d, 7);
+
+        assert_eq!(index.lookup("score", "42"), Some(&[7][..]));
+        assert_eq!(index.lookup("vip", "true"), Some(&[7][..]));
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("stupid-secondary-{}", Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SECONDARY_INDEX_FILE);
+
+        let mut index = SecondaryIndex::new(&["memberCode".to_string(), "empty".to_string()]);
+        index.observe(&doc("alice"), 0);
+        index.observe(&doc("alice"), 64);
+        index.save(&path).unwrap();
+
+        let loaded = SecondaryIndex::load(&path).unwrap();
+        assert!(loaded.is_indexed("memberCode"));
+        assert!(loaded.is_indexed("empty"));
+        assert_eq!(loaded.lookup("memberCode", "alice"), Some(&[0, 64][..]));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn load_missing_file_returns_empty() {
+        let path = std::env::temp_dir().join("stupid_db_nonexistent_secondary.idx");
+        let index = SecondaryIndex::load(&path).unwrap();
+        assert!(index.is_empty());
+    }
+}
@@ -1,21 +1,30 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 
 use serde::{Deserialize, Serialize};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 use stupid_core::config::StorageConfig;
-use stupid_core::{DocAddress, DocId, Document, SegmentId, StupidError};
+use stupid_core::{DocAddress, DocId, Document, FieldValue, SegmentId, StupidError};
+use stupid_ingest::dedup::{BloomSeenSet, DedupKey, Deduplicator};
 use stupid_ingest::parquet_import::ParquetImporter;
 
 use crate::filter::ScanFilter;
 use crate::index::{DocIndex, DocIndexEntry};
 use crate::manager::SegmentManager;
+use crate::meta::SegmentMeta;
 use crate::schema::SchemaRegistry;
 
 #[cfg(test)]
 mod tests;
 
+/// File in `data_dir` holding the cross-import dedup seen-set.
+const DEDUP_SEEN_SET_FILE: &str = "dedup_seen.msgpack";
+
+/// Keys a fresh dedup seen-set is sized for, at a 1% false-positive rate,
+/// before it grows.
+const DEDUP_SEEN_SET_CAPACITY: usize = 1_000_000;
+
 /// Overall statistics for the document store.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoreStats {
@@ -27,6 +36,15 @@ pub struct StoreStats {
     pub total_bytes: u64,
 }
 
+/// Outcome of [`DocumentStore::import_parquet_deduplicated`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DedupImportStats {
+    /// Documents inserted into the store.
+    pub imported: usize,
+    /// Documents skipped because their key was already seen.
+    pub duplicates: usize,
+}
+
 /// High-level API for document storage combining segment management,
 /// indexing, and schema tracking.
 pub struct DocumentStore {
@@ -62,8 +80,7 @@ impl DocumentStore {
         }
 
         // Load schema registry
-        let schema_path = data_dir.join("schema_registry.json");
-        let schema_registry = SchemaRegistry::load(&schema_path)?;
+        let schema_registry = SchemaRegistry::load_from_dir(&data_dir)?;
 
         info!(
             segments = indexes.len(),
@@ -82,7 +99,8 @@ impl DocumentStore {
     /// Insert a document into the store, returning its address.
     ///
     /// The document is appended to the appropriate segment (based on timestamp),
-    /// the index is updated, and schema statistics are tracked.
+    /// the index is updated, and schema statistics are tracked. If the segment
+    /// has rolled over, the address points at the part holding the document.
     pub fn insert(&mut self, doc: Document) -> Result<DocAddress, StupidError> {
         // Determine target segment
         let segment_id = SegmentManager::segment_id_for_timestamp(&doc.timestamp);
@@ -92,11 +110,13 @@ impl DocumentStore {
 
         // Serialize the document to get its length
         let encoded =
-            rmp_serde::to_vec(&doc).map_err(|e| StupidError::Serialize(e.to_string()))?;
+            rmp_serde::to_vec(&doc).map_err(|e| StupidError::Serialize(e.into()))?;
         let length = encoded.len() as u32;
 
-        // Append to segment (returns offset before write)
+        // Append to segment (returns offset before write). Offsets are relative
+        // to the part the writer is on, so address the document by that part.
         let offset = writer.append(&doc)?;
+        let segment_id = writer.part_id().to_string();
 
         // Update the index for this segment
         let index = self
@@ -109,11 +129,14 @@ impl DocumentStore {
             length,
             timestamp: doc.timestamp,
             event_type: doc.event_type.clone(),
+            deleted: false,
         };
         index.add(doc.id, entry);
 
         // Update schema registry
-        self.schema_registry.observe(&doc);
+        for conflict in self.schema_registry.observe_in_segment(&doc, &segment_id) {
+            warn!(%conflict, "Schema conflict");
+        }
 
         debug!(
             doc_id = %doc.id,
@@ -135,10 +158,18 @@ impl DocumentStore {
     }
 
     /// Retrieve a document by its ID, searching all segment indexes.
+    ///
+    /// Segments whose bloom filter rules the ID out are skipped.
     pub fn get_by_id(&self, id: &DocId) -> Result<Document, StupidError> {
         // Search all segment indexes for this document ID
         for (segment_id, index) in &self.indexes {
+            if !self.manager.may_contain_id(segment_id, id) {
+                continue;
+            }
             if let Some(entry) = index.get(id) {
+                if entry.deleted {
+                    break;
+                }
                 let addr = DocAddress {
                     segment_id: segment_id.clone(),
                     offset: entry.offset,
@@ -152,30 +183,32 @@ impl DocumentStore {
 
     /// Scan documents matching the given filter.
     ///
-    /// Determines the relevant segments based on the time range, iterates each
-    /// segment's documents, and applies the filter predicate.
+    /// Determines the relevant segments based on the time range, then uses each
+    /// segment's index to skip documents that can't match before reading and
+    /// applying the full filter predicate.
     pub fn scan(&self, filter: &ScanFilter) -> Result<Vec<Document>, StupidError> {
         // Determine segments in the time range
         let segment_ids = self
             .manager
             .segments_in_range(filter.time_start, filter.time_end);
 
+        let empty_index = DocIndex::new();
         let mut results = Vec::new();
+        let mut deserialized = 0;
 
         for segment_id in segment_ids {
             if let Some(reader) = self.manager.get_reader(&segment_id) {
-                for doc_result in reader.iter() {
-                    let doc = doc_result?;
-                    if filter.matches(&doc) {
-                        results.push(doc);
-                    }
-                }
+                let index = self.indexes.get(&segment_id).unwrap_or(&empty_index);
+                let (docs, stats) = reader.scan_with_filter(index, filter)?;
+                deserialized += stats.deserialized;
+                results.extend(docs);
             }
         }
 
         debug!(
             filter_event_type = ?filter.event_type,
             filter_fields = filter.field_filters.len(),
+            deserialized = deserialized,
             results = results.len(),
             "Scan completed"
         );
@@ -183,6 +216,133 @@ impl DocumentStore {
         Ok(results)
     }
 
+    /// Find documents whose `field` equals `value` across all sealed segments.
+    ///
+    /// Segments whose bloom filter rules the value out are skipped without
+    /// being opened. Segments with a secondary index on `field` are answered
+    /// from the index; others fall back to a full segment scan.
+    pub fn lookup(&self, field: &str, value: &str) -> Result<Vec<Document>, StupidError> {
+        let mut results = Vec::new();
+        for segment_id in self.manager.segments_may_contain(field, value) {
+            if let Some(reader) = self.manager.get_reader(&segment_id) {
+                let docs = reader.lookup(field, value)?;
+                match self.indexes.get(&segment_id) {
+                    Some(index) => results.extend(docs.into_iter().filter(|d| !index.is_deleted(&d.id))),
+                    None => results.extend(docs),
+                }
+            }
+        }
+        Ok(results)
+    }
+
+    /// Delete a sealed segment (and its rollover parts): removes the data,
+    /// index, and meta files and drops the segments' indexes from the store.
+    ///
+    /// Returns the number of live documents removed.
+    pub fn delete_segment(&mut self, segment_id: &str) -> Result<usize, StupidError> {
+        let removed = self.manager.delete_segment(segment_id)?;
+        let count = removed
+            .iter()
+            .filter_map(|sid| self.indexes.remove(sid))
+            .map(|index| index.live_len())
+            .sum();
+
+        info!(segment_id = %segment_id, documents = count, "Segment deleted from store");
+        Ok(count)
+    }
+
+    /// Tombstone every document in a sealed segment that matches `filter`.
+    ///
+    /// Tombstoned documents disappear from scans, lookups, and
+    /// [`get_by_id`](Self::get_by_id) immediately; the updated indexes are
+    /// persisted right away. Their bytes stay on disk until the segment is
+    /// compacted (see [`purge_deleted`](Self::purge_deleted)). Documents in
+    /// segments that are still being written, or that have no document index,
+    /// are not affected. Returns the number of documents tombstoned.
+    pub fn delete_by_filter(&mut self, filter: &ScanFilter) -> Result<usize, StupidError> {
+        let segment_ids = self
+            .manager
+            .segments_in_range(filter.time_start, filter.time_end);
+
+        let mut total = 0;
+        for segment_id in segment_ids {
+            let (Some(reader), Some(index)) = (
+                self.manager.get_reader(&segment_id),
+                self.indexes.get_mut(&segment_id),
+            ) else {
+                continue;
+            };
+            if index.is_empty() {
+                warn!(segment_id = %segment_id, "Segment has no document index, cannot tombstone");
+                continue;
+            }
+
+            let (docs, _) = reader.scan_with_filter(index, filter)?;
+            let deleted = docs.iter().filter(|d| index.tombstone(&d.id)).count();
+            if deleted > 0 {
+                index.save(&self.data_dir.join("segments").join(&segment_id).join("documents.idx"))?;
+                debug!(segment_id = %segment_id, deleted = deleted, "Documents tombstoned");
+            }
+            total += deleted;
+        }
+
+        info!(deleted = total, "Delete by filter completed");
+        Ok(total)
+    }
+
+    /// Compact every sealed segment holding tombstoned documents into itself,
+    /// physically removing those documents.
+    ///
+    /// Returns the number of documents removed.
+    pub fn purge_deleted(&mut self) -> Result<usize, StupidError> {
+        let segment_ids: Vec<SegmentId> = self
+            .indexes
+            .iter()
+            .filter(|(sid, index)| {
+                index.tombstoned().next().is_some() && self.manager.is_sealed(sid)
+            })
+            .map(|(sid, _)| sid.clone())
+            .collect();
+
+        let mut removed = 0;
+        for segment_id in segment_ids {
+            let before = self.indexes.get(&segment_id).map_or(0, DocIndex::len);
+            let after = self.compact(std::slice::from_ref(&segment_id), &segment_id)?;
+            removed += before - after;
+        }
+        Ok(removed)
+    }
+
+    /// Merge several sealed segments into `target_id`.
+    ///
+    /// Tombstoned documents are dropped from the merged segment. Replaces the
+    /// sources' indexes with the merged segment's index and persists the
+    /// schema registry so both match the new on-disk layout. Field statistics
+    /// are per event type, so merging doesn't change them.
+    /// Returns the number of documents in the merged segment.
+    pub fn compact(
+        &mut self,
+        segment_ids: &[SegmentId],
+        target_id: &str,
+    ) -> Result<usize, StupidError> {
+        let tombstoned: HashSet<DocId> = segment_ids
+            .iter()
+            .filter_map(|sid| self.indexes.get(sid))
+            .flat_map(|index| index.tombstoned().copied())
+            .collect();
+        let index = self.manager.compact_excluding(segment_ids, target_id, &tombstoned)?;
+        let count = index.len();
+
+        for sid in segment_ids {
+            self.indexes.remove(sid);
+        }
+        self.indexes.insert(target_id.to_string(), index);
+
+        self.schema_registry.save_to_dir(&self.data_dir)?;
+
+        Ok(count)
+    }
+
     /// Import documents from a Parquet file, inserting each document into the store.
     ///
     /// Returns the number of documents imported.
@@ -204,6 +364,100 @@ impl DocumentStore {
         Ok(count)
     }
 
+    /// Import a Parquet file, skipping documents whose `key` was seen earlier
+    /// in the file or in any previous deduplicated import into this store.
+    ///
+    /// Keys are remembered in a bloom-filter seen-set persisted in the data
+    /// directory. A seen-set hit only skips the document if the store still
+    /// holds the event (see [`holds_event`](Self::holds_event)), so false
+    /// positives don't lose data. Documents get content-derived IDs, and the
+    /// store is flushed before returning.
+    pub fn import_parquet_deduplicated(
+        &mut self,
+        path: &Path,
+        event_type: &str,
+        key: DedupKey,
+    ) -> Result<DedupImportStats, StupidError> {
+        let seen_set = self.load_dedup_seen_set()?;
+        let mut dedup = Deduplicator::new(key.clone()).with_seen_set(seen_set);
+        let documents = ParquetImporter::import_content_addressed(path, event_type)?;
+        let documents = dedup.retain_unique_confirmed(documents, |doc| self.holds_event(&key, doc));
+        let stats = DedupImportStats { imported: documents.len(), duplicates: dedup.duplicates() };
+
+        // Saved before the documents: if the import fails part way, the set
+        // holds keys of documents that were never stored, which the store
+        // check turns into misses. A key missing from the set instead would
+        // let its duplicate through unchecked.
+        if let Some(seen_set) = dedup.into_seen_set() {
+            self.save_dedup_seen_set(&seen_set)?;
+        }
+        for doc in documents {
+            self.insert(doc)?;
+        }
+        self.flush()?;
+
+        info!(
+            path = %path.display(),
+            event_type = event_type,
+            count = stats.imported,
+            duplicates = stats.duplicates,
+            "Deduplicated parquet import completed"
+        );
+
+        Ok(stats)
+    }
+
+    /// Whether the store holds a live document for the same event as `doc`:
+    /// one with its content ID, or one in a sealed segment with the same
+    /// `key`, found by looking up the key's first present field.
+    fn holds_event(&self, key: &DedupKey, doc: &Document) -> bool {
+        let id = doc.content_id();
+        if self.indexes.values().any(|index| index.get(&id).is_some_and(|entry| !entry.deleted)) {
+            return true;
+        }
+        let Some((field, value)) = key.field_names().iter().find_map(|name| {
+            let value = match doc.fields.get(name)? {
+                FieldValue::Text(s) => s.clone(),
+                FieldValue::Integer(i) => i.to_string(),
+                FieldValue::Float(f) => f.to_string(),
+                FieldValue::Boolean(b) => b.to_string(),
+                FieldValue::Null => return None,
+            };
+            Some((name, value))
+        }) else {
+            return false;
+        };
+        match self.lookup(field, &value) {
+            Ok(found) => found.iter().any(|other| key.key(other) == key.key(doc)),
+            Err(e) => {
+                // Can't tell, so trust the seen-set as before.
+                warn!(field = %field, error = %e, "Dedup lookup failed");
+                true
+            }
+        }
+    }
+
+    fn load_dedup_seen_set(&self) -> Result<BloomSeenSet, StupidError> {
+        let path = self.data_dir.join(DEDUP_SEEN_SET_FILE);
+        match std::fs::read(&path) {
+            Ok(bytes) => rmp_serde::from_slice(&bytes).map_err(|e| StupidError::Serialize(e.into())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(BloomSeenSet::with_capacity(DEDUP_SEEN_SET_CAPACITY, 0.01))
+            }
+            Err(e) => Err(StupidError::Io(e)),
+        }
+    }
+
+    fn save_dedup_seen_set(&self, seen_set: &BloomSeenSet) -> Result<(), StupidError> {
+        let bytes = rmp_serde::to_vec(seen_set).map_err(|e| StupidError::Serialize(e.into()))?;
+        std::fs::create_dir_all(&self.data_dir)?;
+        // Written aside and renamed, so a crash can't leave a truncated set.
+        let tmp = self.data_dir.join(format!("{DEDUP_SEEN_SET_FILE}.tmp"));
+        std::fs::write(&tmp, bytes)?;
+        std::fs::rename(&tmp, self.data_dir.join(DEDUP_SEEN_SET_FILE))?;
+        Ok(())
+    }
+
     /// Flush all active writers, persist all indexes and schema to disk.
     pub fn flush(&mut self) -> Result<(), StupidError> {
         // Seal all active writers
@@ -221,8 +475,7 @@ impl DocumentStore {
         }
 
         // Save schema registry
-        let schema_path = self.data_dir.join("schema_registry.json");
-        self.schema_registry.save(&schema_path)?;
+        self.schema_registry.save_to_dir(&self.data_dir)?;
 
         info!("DocumentStore flushed");
         Ok(())
@@ -231,25 +484,16 @@ impl DocumentStore {
     /// Return overall statistics for the store.
     pub fn stats(&self) -> StoreStats {
         let segment_count = self.manager.list_segments().len();
-        let document_count: u64 = self.indexes.values().map(|idx| idx.len() as u64).sum();
+        let document_count: u64 = self.indexes.values().map(|idx| idx.live_len() as u64).sum();
 
-        // Estimate total bytes from segment meta.json files
-        let mut total_bytes = 0u64;
-        for segment_id in self.manager.list_segments() {
-            let meta_path = self
-                .data_dir
-                .join("segments")
-                .join(&segment_id)
-                .join("meta.json");
-
-            if let Ok(content) = std::fs::read_to_string(&meta_path) {
-                if let Ok(meta) = serde_json::from_str::<serde_json::Value>(&content) {
-                    if let Some(size) = meta.get("size_bytes").and_then(|v| v.as_u64()) {
-                        total_bytes += size;
-                    }
-                }
-            }
-        }
+        // Total bytes from sealed segments' meta.json
+        let total_bytes = self
+            .manager
+            .list_segments()
+            .iter()
+            .filter_map(|sid| self.manager.segment_meta(sid))
+            .map(|meta| meta.size_bytes)
+            .sum();
 
         StoreStats {
             segment_count,
@@ -258,6 +502,20 @@ impl DocumentStore {
         }
     }
 
+    /// Access the document index for a segment, if one has been loaded or built.
+    pub fn index(&self, segment_id: &str) -> Option<&DocIndex> {
+        self.indexes.get(segment_id)
+    }
+
+    /// Metadata for every sealed segment that has a `meta.json`, sorted by ID.
+    pub fn segment_metas(&self) -> Vec<&SegmentMeta> {
+        self.manager
+            .list_segments()
+            .iter()
+            .filter_map(|sid| self.manager.segment_meta(sid))
+            .collect()
+    }
+
     /// Access the schema registry.
     pub fn schema_registry(&self) -> &SchemaRegistry {
         &self.schema_registry
@@ -17,6 +17,10 @@ fn make_config(data_dir: PathBuf) -> StorageConfig {
         segment_retention_days: 30,
         cache_dir: data_dir.join("cache"),
         cache_max_gb: 1,
+        indexed_fields: Vec::new(),
+        segment_max_docs: 0,
+        segment_max_bytes: 0,
+        in_memory: false,
     }
 }
 
@@ -283,3 +287,49 @@ fn test_insert_updates_index() {
 
     fs::remove_dir_all(&dir).ok();
 }
+
+#[test]
+fn test_dedup_seen_set_persists() {
+    let dir = temp_dir();
+    let config = make_config(dir.clone());
+    let store = DocumentStore::new(&config).unwrap();
+
+    let mut seen = store.load_dedup_seen_set().unwrap();
+    assert!(seen.is_empty());
+    assert!(seen.insert(42));
+    store.save_dedup_seen_set(&seen).unwrap();
+
+    let reopened = DocumentStore::new(&config).unwrap();
+    let seen = reopened.load_dedup_seen_set().unwrap();
+    assert_eq!(seen.len(), 1);
+    assert!(seen.contains(42));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_holds_event_confirms_seen_set_hits() {
+    let dir = temp_dir();
+    let config = make_config(dir.clone());
+    let mut store = DocumentStore::new(&config).unwrap();
+    let key = DedupKey::parse("eventId").unwrap();
+    let ts = Utc.with_ymd_and_hms(2025, 6, 14, 10, 0, 0).unwrap();
+    let event = |event_id: &str, extra: &str| {
+        let mut doc = make_doc("Login", ts);
+        doc.fields.insert("eventId".into(), FieldValue::Text(event_id.into()));
+        doc.fields.insert("extra".into(), FieldValue::Text(extra.into()));
+        doc.id = doc.content_id();
+        doc
+    };
+
+    store.insert(event("e1", "a")).unwrap();
+    // Not yet sealed: found by content ID.
+    assert!(store.holds_event(&key, &event("e1", "a")));
+    store.flush().unwrap();
+
+    // Same key, different content: found by looking up the key field.
+    assert!(store.holds_event(&key, &event("e1", "b")));
+    assert!(!store.holds_event(&key, &event("e2", "a")));
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,485 @@
+//! Integrity checks and repair for segments on disk.
+//!
+//! [`verify_segment`] cross-checks a segment's `documents.dat`, `meta.json`
+//! and `documents.idx` without trusting any of them: the data must
+//! decompress, every length-prefixed document must be complete and decode,
+//! the metadata count must match, and every index offset must point at the
+//! start of the document it names. [`repair_segment`] rebuilds the metadata
+//! and indexes from the documents that can still be read.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::Path;
+
+use stupid_core::{DocId, Document, SegmentId, StupidError};
+
+use crate::index::{DocIndex, DocIndexEntry};
+use crate::meta::SegmentMeta;
+use crate::secondary::{SecondaryIndex, SECONDARY_INDEX_FILE};
+
+/// File name of the per-segment document index written by `DocumentStore`.
+pub const INDEX_FILE: &str = "documents.idx";
+
+/// Something wrong with one segment's files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SegmentProblem {
+    /// `documents.dat` is missing.
+    MissingData,
+    /// `meta.json` exists but can't be parsed.
+    MetaUnreadable(String),
+    /// `documents.dat` failed to decompress; only the first
+    /// `recovered_bytes` of the document stream could be read.
+    DataCorrupt { error: String, recovered_bytes: u64 },
+    /// The document stream ends partway through the document at `offset`.
+    TruncatedDocument { offset: u64 },
+    /// A complete document at `offset` that doesn't deserialize.
+    UndecodableDocument { offset: u64, error: String },
+    /// `meta.json` counts a different number of documents than the data holds.
+    DocumentCountMismatch { meta: usize, actual: usize },
+    /// `documents.idx` exists but can't be parsed.
+    IndexUnreadable(String),
+    /// An index entry's offset lies beyond the end of the document stream.
+    IndexOffsetOutOfRange { doc_id: DocId, offset: u64 },
+    /// An index entry's offset is not the start of the document it names.
+    IndexEntryMismatch { doc_id: DocId, offset: u64 },
+    /// The index has a different number of entries than the data has documents.
+    IndexCountMismatch { index: usize, actual: usize },
+}
+
+impl fmt::Display for SegmentProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingData => write!(f, "documents.dat is missing"),
+            Self::MetaUnreadable(e) => write!(f, "meta.json is unreadable: {e}"),
+            Self::DataCorrupt { error, recovered_bytes } => write!(
+                f,
+                "documents.dat failed to decompress after {recovered_bytes} bytes: {error}"
+            ),
+            Self::TruncatedDocument { offset } => {
+                write!(f, "document stream is truncated at offset {offset}")
+            }
+            Self::UndecodableDocument { offset, error } => {
+                write!(f, "document at offset {offset} doesn't decode: {error}")
+            }
+            Self::DocumentCountMismatch { meta, actual } => {
+                write!(f, "meta.json counts {meta} documents but the data holds {actual}")
+            }
+            Self::IndexUnreadable(e) => write!(f, "{INDEX_FILE} is unreadable: {e}"),
+            Self::IndexOffsetOutOfRange { doc_id, offset } => {
+                write!(f, "index offset {offset} for {doc_id} is past the end of the data")
+            }
+            Self::IndexEntryMismatch { doc_id, offset } => {
+                write!(f, "index offset {offset} for {doc_id} doesn't point at that document")
+            }
+            Self::IndexCountMismatch { index, actual } => {
+                write!(f, "index has {index} entries but the data holds {actual} documents")
+            }
+        }
+    }
+}
+
+/// A problem found in a specific segment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SegmentIssue {
+    pub segment_id: SegmentId,
+    pub problem: SegmentProblem,
+}
+
+impl fmt::Display for SegmentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.segment_id, self.problem)
+    }
+}
+
+/// What [`repair_segment`] changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepairOutcome {
+    /// Documents still in the segment after repair.
+    pub documents_kept: usize,
+    /// Documents that were truncated or undecodable and were dropped.
+    pub documents_dropped: usize,
+    /// `documents.dat` was rewritten without the unreadable documents.
+    pub data_rewritten: bool,
+    /// `documents.idx` was rebuilt from the data.
+    pub index_rebuilt: bool,
+}
+
+/// A readable document found while scanning the stream.
+struct ScannedDoc {
+    id: DocId,
+    offset: u64,
+    length: u32,
+    doc: Document,
+}
+
+/// Result of reading a segment's files without trusting any of them.
+struct Scan {
+    /// Decompressed document stream (possibly only a prefix of it).
+    stream: Vec<u8>,
+    docs: Vec<ScannedDoc>,
+    meta: Option<SegmentMeta>,
+    problems: Vec<SegmentProblem>,
+    /// Truncated or undecodable documents.
+    dropped: usize,
+}
+
+impl Scan {
+    fn data_damaged(&self) -> bool {
+        self.problems.iter().any(|p| {
+            matches!(
+                p,
+                SegmentProblem::DataCorrupt { .. }
+                    | SegmentProblem::TruncatedDocument { .. }
+                    | SegmentProblem::UndecodableDocument { .. }
+            )
+        })
+    }
+}
+
+/// zstd frame magic number, little-endian.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+fn scan(seg_dir: &Path) -> Result<Scan, StupidError> {
+    let mut problems = Vec::new();
+
+    let meta = match SegmentMeta::load(seg_dir) {
+        Ok(meta) => meta,
+        Err(e) => {
+            problems.push(SegmentProblem::MetaUnreadable(e.to_string()));
+            None
+        }
+    };
+
+    let raw = std::fs::read(seg_dir.join("documents.dat"))?;
+    let compressed = match &meta {
+        Some(m) => m.compression == "zstd",
+        None => raw.starts_with(&ZSTD_MAGIC),
+    };
+
+    let stream = if compressed {
+        // Decode as far as possible so a truncated file still yields its
+        // leading documents.
+        let mut stream = Vec::new();
+        let result = zstd::stream::read::Decoder::new(raw.as_slice())
+            .and_then(|mut decoder| decoder.read_to_end(&mut stream));
+        if let Err(e) = result {
+            problems.push(SegmentProblem::DataCorrupt {
+                error: e.to_string(),
+                recovered_bytes: stream.len() as u64,
+            });
+        }
+        stream
+    } else {
+        raw
+    };
+
+    let mut docs = Vec::new();
+    let mut dropped = 0;
+    let mut pos = 0usize;
+    while pos < stream.len() {
+        if pos + 4 > stream.len() {
+            problems.push(SegmentProblem::TruncatedDocument { offset: pos as u64 });
+            dropped += 1;
+            break;
+        }
+        let len = u32::from_le_bytes(stream[pos..pos + 4].try_into().unwrap());
+        let end = pos + 4 + len as usize;
+        if end > stream.len() {
+            problems.push(SegmentProblem::TruncatedDocument { offset: pos as u64 });
+            dropped += 1;
+            break;
+        }
+        match rmp_serde::from_slice::<Document>(&stream[pos + 4..end]) {
+            Ok(doc) => docs.push(ScannedDoc { id: doc.id, offset: pos as u64, length: len, doc }),
+            Err(e) => {
+                problems.push(SegmentProblem::UndecodableDocument { offset: pos as u64, error: e.to_string() });
+                dropped += 1;
+            }
+        }
+        pos = end;
+    }
+
+    if let Some(meta) = &meta {
+        if meta.document_count != docs.len() {
+            problems.push(SegmentProblem::DocumentCountMismatch { meta: meta.document_count, actual: docs.len() });
+        }
+    }
+
+    Ok(Scan { stream, docs, meta, problems, dropped })
+}
+
+fn check_index(index: &DocIndex, scan: &Scan, problems: &mut Vec<SegmentProblem>) {
+    let by_offset: HashMap<u64, DocId> = scan.docs.iter().map(|d| (d.offset, d.id)).collect();
+    let stream_len = scan.stream.len() as u64;
+
+    for (doc_id, entry) in index.iter() {
+        if entry.offset >= stream_len {
+            problems.push(SegmentProblem::IndexOffsetOutOfRange { doc_id: *doc_id, offset: entry.offset });
+        } else if by_offset.get(&entry.offset) != Some(doc_id) {
+            problems.push(SegmentProblem::IndexEntryMismatch { doc_id: *doc_id, offset: entry.offset });
+        }
+    }
+
+    if index.len() != scan.docs.len() {
+        problems.push(SegmentProblem::IndexCountMismatch { index: index.len(), actual: scan.docs.len() });
+    }
+}
+
+/// Check one segment under `data_dir/segments/{segment_id}/`.
+///
+/// Returns every problem found; an empty list means the segment is
+/// consistent. `documents.idx` is optional and only checked when present.
+pub fn verify_segment(data_dir: &Path, segment_id: &str) -> Vec<SegmentIssue> {
+    let seg_dir = data_dir.join("segments").join(segment_id);
+    let issue = |problem| SegmentIssue { segment_id: segment_id.to_string(), problem };
+
+    if !seg_dir.join("documents.dat").exists() {
+        return vec![issue(SegmentProblem::MissingData)];
+    }
+
+    let scan = match scan(&seg_dir) {
+        Ok(scan) => scan,
+        Err(e) => {
+            return vec![issue(SegmentProblem::DataCorrupt { error: e.to_string(), recovered_bytes: 0 })];
+        }
+    };
+    let mut problems = scan.problems.clone();
+
+    let index_path = seg_dir.join(INDEX_FILE);
+    if index_path.exists() {
+        match DocIndex::load(&index_path) {
+            Ok(index) => check_index(&index, &scan, &mut problems),
+            Err(e) => problems.push(SegmentProblem::IndexUnreadable(e.to_string())),
+        }
+    }
+
+    problems.into_iter().map(issue).collect()
+}
+
+/// Rebuild a segment's metadata and indexes from its readable documents.
+///
+/// If the data itself is damaged, `documents.dat` is rewritten with only
+/// the documents that could be read, so the segment loads cleanly again.
+/// `meta.json` is always regenerated; `documents.idx` and `secondary.idx`
+/// are rebuilt when present, keeping tombstones from a readable index.
+pub fn repair_segment(data_dir: &Path, segment_id: &str) -> Result<RepairOutcome, StupidError> {
+    let seg_dir = data_dir.join("segments").join(segment_id);
+    if !seg_dir.join("documents.dat").exists() {
+        return Err(StupidError::SegmentNotFound(segment_id.to_string()));
+    }
+
+    let scan = scan(&seg_dir)?;
+    let mut outcome = RepairOutcome {
+        documents_kept: scan.docs.len(),
+        documents_dropped: scan.dropped,
+        ..Default::default()
+    };
+    let compression = scan.meta.as_ref().map_or("zstd", |m| m.compression.as_str()).to_string();
+
+    // Offsets of the documents in the (possibly rewritten) stream.
+    let mut offsets: Vec<u64> = scan.docs.iter().map(|d| d.offset).collect();
+    let mut raw_bytes = scan.stream.len() as u64;
+    if scan.data_damaged() {
+        let mut stream = Vec::with_capacity(scan.stream.len());
+        for (doc, offset) in scan.docs.iter().zip(offsets.iter_mut()) {
+            *offset = stream.len() as u64;
+            let start = doc.offset as usize;
+            stream.extend_from_slice(&scan.stream[start..start + 4 + doc.length as usize]);
+        }
+        raw_bytes = stream.len() as u64;
+
+        let bytes = if compression == "zstd" {
+            zstd::encode_all(stream.as_slice(), 3).map_err(StupidError::Io)?
+        } else {
+            stream
+        };
+        let tmp_path = seg_dir.join(".documents.dat.tmp");
+        std::fs::File::create(&tmp_path)?.write_all(&bytes)?;
+        std::fs::rename(&tmp_path, seg_dir.join("documents.dat"))?;
+        outcome.data_rewritten = true;
+    }
+
+    let mut meta = SegmentMeta::new(segment_id);
+    for doc in &scan.docs {
+        meta.observe(&doc.doc);
+    }
+    meta.size_bytes = std::fs::metadata(seg_dir.join("documents.dat"))?.len();
+    meta.raw_bytes = raw_bytes;
+    meta.compression = compression;
+    meta.save(&seg_dir)?;
+
+    let index_path = seg_dir.join(INDEX_FILE);
+    if index_path.exists() {
+        let old = DocIndex::load(&index_path).unwrap_or_default();
+        let mut index = DocIndex::new();
+        for (doc, offset) in scan.docs.iter().zip(&offsets) {
+            index.add(
+                doc.id,
+                DocIndexEntry {
+                    offset: *offset,
+                    length: doc.length,
+                    timestamp: doc.doc.timestamp,
+                    event_type: doc.doc.event_type.clone(),
+                    deleted: old.is_deleted(&doc.id),
+                },
+            );
+        }
+        index.save(&index_path)?;
+        outcome.index_rebuilt = true;
+    }
+
+    let secondary_path = seg_dir.join(SECONDARY_INDEX_FILE);
+    if outcome.data_rewritten && secondary_path.exists() {
+        match SecondaryIndex::load(&secondary_path) {
+            Ok(old) => {
+                let fields: Vec<String> = old.indexed_fields().map(str::to_string).collect();
+                let mut secondary = SecondaryIndex::new(&fields);
+                for (doc, offset) in scan.docs.iter().zip(&offsets) {
+                    secondary.observe(&doc.doc, *offset);
+                }
+                secondary.save(&secondary_path)?;
+            }
+            // Readers fall back to a full scan without it.
+            Err(_) => std::fs::remove_file(&secondary_path)?,
+        }
+    }
+
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use stupid_core::FieldValue;
+    use uuid::Uuid;
+
+    use crate::reader::SegmentReader;
+    use crate::writer::SegmentWriter;
+
+    /// Scratch data directory, removed on drop.
+    struct TempDir(std::path::PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("stupid-verify-test-{}", Uuid::new_v4()));
+            std::fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            std::fs::remove_dir_all(&self.0).ok();
+        }
+    }
+
+    fn doc(n: u32) -> Document {
+        Document {
+            id: Uuid::new_v4(),
+            timestamp: Utc.with_ymd_and_hms(2025, 6, 14, n % 24, 0, 0).unwrap(),
+            event_type: "Login".to_string(),
+            fields: [("memberCode".to_string(), FieldValue::Text(format!("M{n:04}-{}", "x".repeat(64))))]
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    /// Write a segment of `n` documents plus a `documents.idx` for it.
+    fn write_segment(data_dir: &Path, segment_id: &str, n: u32) -> Vec<Document> {
+        let docs: Vec<Document> = (0..n).map(doc).collect();
+        let mut writer = SegmentWriter::new(data_dir, segment_id).unwrap();
+        let mut index = DocIndex::new();
+        for d in &docs {
+            let offset = writer.append(d).unwrap();
+            let length = rmp_serde::to_vec(d).unwrap().len() as u32;
+            index.add(
+                d.id,
+                DocIndexEntry { offset, length, timestamp: d.timestamp, event_type: d.event_type.clone(), deleted: false },
+            );
+        }
+        writer.finalize().unwrap();
+        index.save(&data_dir.join("segments").join(segment_id).join(INDEX_FILE)).unwrap();
+        docs
+    }
+
+    fn truncate_data(data_dir: &Path, segment_id: &str, keep_ratio: f64) {
+        let path = data_dir.join("segments").join(segment_id).join("documents.dat");
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..(bytes.len() as f64 * keep_ratio) as usize]).unwrap();
+    }
+
+    #[test]
+    fn healthy_segment_has_no_issues() {
+        let tmp = TempDir::new();
+        write_segment(tmp.path(), "Login/2025-W24", 50);
+        assert_eq!(verify_segment(tmp.path(), "Login/2025-W24"), vec![]);
+    }
+
+    #[test]
+    fn missing_segment_is_reported() {
+        let tmp = TempDir::new();
+        let issues = verify_segment(tmp.path(), "Login/2025-W24");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].problem, SegmentProblem::MissingData);
+    }
+
+    #[test]
+    fn truncated_segment_is_reported() {
+        let tmp = TempDir::new();
+        write_segment(tmp.path(), "Login/2025-W24", 4000);
+        truncate_data(tmp.path(), "Login/2025-W24", 0.5);
+
+        let issues = verify_segment(tmp.path(), "Login/2025-W24");
+        let problems: Vec<_> = issues.iter().map(|i| &i.problem).collect();
+        assert!(issues.iter().all(|i| i.segment_id == "Login/2025-W24"));
+        assert!(problems.iter().any(|p| matches!(p, SegmentProblem::DataCorrupt { .. })), "{problems:?}");
+        assert!(problems.iter().any(|p| matches!(p, SegmentProblem::DocumentCountMismatch { meta: 4000, .. })));
+        assert!(problems.iter().any(|p| matches!(p, SegmentProblem::IndexOffsetOutOfRange { .. })));
+        assert!(problems.iter().any(|p| matches!(p, SegmentProblem::IndexCountMismatch { index: 4000, .. })));
+    }
+
+    #[test]
+    fn stale_index_is_reported() {
+        let tmp = TempDir::new();
+        let docs = write_segment(tmp.path(), "Login/2025-W24", 3);
+        let index_path = tmp.path().join("segments/Login/2025-W24").join(INDEX_FILE);
+        let mut index = DocIndex::load(&index_path).unwrap();
+        let mut entry = index.get(&docs[0].id).unwrap().clone();
+        entry.offset = index.get(&docs[1].id).unwrap().offset;
+        index.add(docs[0].id, entry);
+        index.save(&index_path).unwrap();
+
+        let issues = verify_segment(tmp.path(), "Login/2025-W24");
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(issues[0].problem, SegmentProblem::IndexEntryMismatch { doc_id, .. } if doc_id == docs[0].id));
+    }
+
+    #[test]
+    fn repair_rebuilds_truncated_segment() {
+        let tmp = TempDir::new();
+        let docs = write_segment(tmp.path(), "Login/2025-W24", 4000);
+        truncate_data(tmp.path(), "Login/2025-W24", 0.5);
+
+        let outcome = repair_segment(tmp.path(), "Login/2025-W24").unwrap();
+        assert!(outcome.data_rewritten);
+        assert!(outcome.index_rebuilt);
+        assert!(outcome.documents_kept > 0 && outcome.documents_kept < 4000);
+
+        assert_eq!(verify_segment(tmp.path(), "Login/2025-W24"), vec![]);
+
+        // The kept documents are the leading ones, readable through the index.
+        let reader = SegmentReader::open(tmp.path(), "Login/2025-W24").unwrap();
+        assert_eq!(reader.meta().unwrap().document_count, outcome.documents_kept);
+        let index = DocIndex::load(&tmp.path().join("segments/Login/2025-W24").join(INDEX_FILE)).unwrap();
+        for original in &docs[..outcome.documents_kept] {
+            let entry = index.get(&original.id).expect("kept document is indexed");
+            assert_eq!(reader.read_at(entry.offset).unwrap().id, original.id);
+        }
+    }
+}
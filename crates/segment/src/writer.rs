@@ -2,43 +2,160 @@ use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
+use stupid_core::config::StorageConfig;
+use stupid_core::hashing::Fnv1a;
 use stupid_core::{Document, SegmentId, StupidError};
-use tracing::info;
-
-/// Segment metadata stored as meta.json.
-#[derive(serde::Serialize)]
-struct SegmentMeta {
-    segment_id: String,
-    document_count: usize,
-    size_bytes: u64,
-    raw_bytes: u64,
-    compression: String,
+use tracing::{info, warn};
+
+use crate::bloom::{self, SegmentBloom, BLOOM_FILTER_FILE};
+use crate::meta::{SegmentMeta, META_FILE};
+use crate::secondary::{index_key, SecondaryIndex, SECONDARY_INDEX_FILE};
+
+/// Prefix of sub-segment directories created on rollover (`part-001`, ...).
+pub const PART_PREFIX: &str = "part-";
+
+/// Segment ID of rollover part `n` (1-based) of `base_id`.
+pub fn part_segment_id(base_id: &str, n: usize) -> SegmentId {
+    format!("{base_id}/{PART_PREFIX}{n:03}")
 }
 
-pub struct SegmentWriter {
-    segment_id: SegmentId,
-    segment_dir: PathBuf,
-    encoder: zstd::Encoder<'static, std::io::BufWriter<fs::File>>,
-    raw_bytes: u64,
-    doc_count: usize,
+/// Segment ID a rollover part belongs to (`Login/2025-W24/part-001` →
+/// `Login/2025-W24`). IDs that aren't parts are returned unchanged.
+pub fn base_segment_id(segment_id: &str) -> &str {
+    match segment_id.rsplit_once('/') {
+        Some((base, last)) if last.starts_with(PART_PREFIX) => base,
+        _ => segment_id,
+    }
 }
 
-impl SegmentWriter {
-    /// Create a writer under `data_dir/segments/{segment_id}/`.
-    pub fn new(data_dir: &Path, segment_id: &str) -> Result<Self, StupidError> {
-        let segment_dir = data_dir.join("segments").join(segment_id);
-        Self::open(segment_dir, segment_id)
+/// Write-ahead marker of an unfinished write, kept in the base segment
+/// directory until [`SegmentWriter::finalize`] removes it.
+pub const WRITE_MARKER_FILE: &str = "write.marker";
+
+/// Documents appended between write-ahead checkpoints by default.
+pub const DEFAULT_CHECKPOINT_INTERVAL: u64 = 10_000;
+
+/// How far an unfinished write got, as of its last checkpoint.
+///
+/// Everything up to the checkpoint is durable: earlier parts are finalized
+/// and the current part's `documents.dat` holds complete zstd frames up to
+/// `committed_bytes`. Anything written after it is discarded on resume.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WriteMarker {
+    /// Documents committed across all parts.
+    pub committed_docs: u64,
+    /// Rollover parts opened (0 = still writing the base).
+    pub parts: usize,
+    /// Length of the current part's `documents.dat` at the checkpoint.
+    pub committed_bytes: u64,
+    /// Fingerprint of the write's [`WriteSource`]; empty for writes started
+    /// with [`SegmentWriter::new`], which can't be resumed.
+    #[serde(default)]
+    pub source: String,
+}
+
+impl WriteMarker {
+    /// Load the marker from a base segment directory, if a write is unfinished.
+    pub fn load(segment_dir: &Path) -> Result<Option<Self>, StupidError> {
+        let path = segment_dir.join(WRITE_MARKER_FILE);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(&path)?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| StupidError::Serialize(e.into()))
     }
 
-    /// Create a writer at an explicit directory path.
-    ///
-    /// Use this when the default `data_dir/segments/` layout doesn't apply
-    /// (e.g., queue data stored at `data/{provider}/{queue_name}/{date}/`).
-    pub fn new_at(dir: PathBuf, segment_id: &str) -> Result<Self, StupidError> {
-        Self::open(dir, segment_id)
+    /// Replace the marker atomically, so a crash leaves the old or new one.
+    fn save(&self, segment_dir: &Path) -> Result<(), StupidError> {
+        let json = serde_json::to_string(self).map_err(|e| StupidError::Serialize(e.into()))?;
+        let tmp = segment_dir.join(format!(".{WRITE_MARKER_FILE}.tmp"));
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, segment_dir.join(WRITE_MARKER_FILE))?;
+        Ok(())
+    }
+}
+
+/// Identity of the input a write is made from: each source's name, size
+/// and modification time. A write is only resumed by a re-run over the same
+/// input, since resuming skips the documents it committed.
+#[derive(Debug, Clone, Default)]
+pub struct WriteSource(Fnv1a);
+
+impl WriteSource {
+    /// Add one source of the input, in the order it is read.
+    pub fn add(mut self, name: &str, size: u64, modified: impl std::fmt::Display) -> Self {
+        self.0.write(format!("{name}\0{size}\0{modified}\0").as_bytes());
+        self
+    }
+
+    /// Add a local file, identified by its path, size and modification time.
+    pub fn file(self, path: &Path) -> Result<Self, StupidError> {
+        let meta = fs::metadata(path)?;
+        let modified = meta
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |d| d.as_nanos());
+        Ok(self.add(&path.display().to_string(), meta.len(), modified))
+    }
+
+    fn fingerprint(&self) -> String {
+        format!("{:016x}", self.0.finish())
+    }
+}
+
+/// Size limits after which a writer rolls over to a new part.
+///
+/// `0` means unlimited. Bytes are measured on the uncompressed document
+/// stream, since the compressed size is only known once a part is finished.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RolloverPolicy {
+    pub max_docs: u64,
+    pub max_bytes: u64,
+}
+
+impl RolloverPolicy {
+    pub fn new(max_docs: u64, max_bytes: u64) -> Self {
+        Self { max_docs, max_bytes }
     }
 
-    fn open(segment_dir: PathBuf, segment_id: &str) -> Result<Self, StupidError> {
+    /// Policy from `SEGMENT_MAX_DOCS` / `SEGMENT_MAX_BYTES`.
+    pub fn from_config(config: &StorageConfig) -> Self {
+        Self::new(config.segment_max_docs, config.segment_max_bytes)
+    }
+
+    /// Whether a part holding `docs`/`bytes` must roll over before taking
+    /// another `next_bytes`. An empty part always accepts the document, so a
+    /// single oversized document still gets written.
+    fn should_roll(&self, docs: u64, bytes: u64, next_bytes: u64) -> bool {
+        docs > 0
+            && ((self.max_docs > 0 && docs >= self.max_docs)
+                || (self.max_bytes > 0 && bytes + next_bytes > self.max_bytes))
+    }
+}
+
+/// One physical segment directory being written.
+struct Part {
+    segment_id: SegmentId,
+    segment_dir: PathBuf,
+    encoder: zstd::Encoder<'static, std::io::BufWriter<fs::File>>,
+    raw_bytes: u64,
+    /// Running metadata (counts, time range, event types, field names).
+    meta: SegmentMeta,
+    /// Secondary index over configured fields, if any.
+    secondary: Option<SecondaryIndex>,
+    /// Bloom filter keys of every document ID and indexed field value,
+    /// collected until finalize so the filter can be sized from the count.
+    bloom_keys: Vec<u64>,
+    /// Resumed with committed documents that haven't been re-read into the
+    /// metadata, secondary index and bloom keys yet.
+    replay_pending: bool,
+}
+
+impl Part {
+    fn open(segment_dir: PathBuf, segment_id: &str, indexed_fields: &[String]) -> Result<Self, StupidError> {
         fs::create_dir_all(&segment_dir)?;
 
         let doc_path = segment_dir.join("documents.dat");
@@ -51,28 +168,111 @@ impl SegmentWriter {
             segment_dir,
             encoder,
             raw_bytes: 0,
-            doc_count: 0,
+            meta: SegmentMeta::new(segment_id),
+            secondary: (!indexed_fields.is_empty()).then(|| SecondaryIndex::new(indexed_fields)),
+            bloom_keys: Vec::new(),
+            replay_pending: false,
         })
     }
 
-    /// Append a document to the zstd-compressed stream.
-    pub fn append(&mut self, doc: &Document) -> Result<u64, StupidError> {
-        let doc_offset = self.raw_bytes;
+    /// Reopen a part interrupted after its last checkpoint, truncating
+    /// `documents.dat` to the `committed_bytes` it had then.
+    fn resume(segment_dir: PathBuf, segment_id: &str, committed_bytes: u64) -> Result<Self, StupidError> {
+        let doc_path = segment_dir.join("documents.dat");
+        let file = fs::OpenOptions::new().append(true).open(&doc_path)?;
+        file.set_len(committed_bytes)?;
+        let encoder = zstd::Encoder::new(std::io::BufWriter::new(file), 3).map_err(StupidError::Io)?;
 
-        let encoded =
-            rmp_serde::to_vec(doc).map_err(|e| StupidError::Serialize(e.to_string()))?;
+        // Finalize rewrites it; until then it would misreport the part.
+        if segment_dir.join(META_FILE).exists() {
+            fs::remove_file(segment_dir.join(META_FILE))?;
+        }
 
+        Ok(Self {
+            segment_id: segment_id.to_string(),
+            segment_dir,
+            encoder,
+            raw_bytes: 0,
+            meta: SegmentMeta::new(segment_id),
+            secondary: None,
+            bloom_keys: Vec::new(),
+            replay_pending: committed_bytes > 0,
+        })
+    }
+
+    /// Re-read the committed documents of a resumed part into its metadata,
+    /// secondary index and bloom keys. Deferred until the first append or
+    /// finalize so indexed fields set on the writer cover them.
+    fn replay(&mut self) -> Result<(), StupidError> {
+        if !std::mem::take(&mut self.replay_pending) {
+            return Ok(());
+        }
+        let file = fs::File::open(self.segment_dir.join("documents.dat"))?;
+        let stream = zstd::decode_all(std::io::BufReader::new(file)).map_err(StupidError::Io)?;
+
+        let mut pos = 0usize;
+        while pos + 4 <= stream.len() {
+            let len = u32::from_le_bytes(stream[pos..pos + 4].try_into().unwrap()) as usize;
+            let encoded = stream.get(pos + 4..pos + 4 + len).ok_or_else(|| {
+                StupidError::Io(std::io::Error::new(
+                    std::io::ErrorKind::UnexpectedEof,
+                    format!("Truncated document at offset {pos} in {}", self.segment_id),
+                ))
+            })?;
+            let doc: Document =
+                rmp_serde::from_slice(encoded).map_err(|e| StupidError::Serialize(e.into()))?;
+            self.observe(&doc, len);
+            pos += 4 + len;
+        }
+        info!(
+            "Segment {} resumed with {} committed docs",
+            self.segment_id, self.meta.document_count
+        );
+        Ok(())
+    }
+
+    fn append(&mut self, doc: &Document, encoded: &[u8]) -> Result<u64, StupidError> {
         let len = encoded.len() as u32;
         self.encoder.write_all(&len.to_le_bytes())?;
-        self.encoder.write_all(&encoded)?;
+        self.encoder.write_all(encoded)?;
 
-        self.raw_bytes += 4 + encoded.len() as u64;
-        self.doc_count += 1;
-        Ok(doc_offset)
+        Ok(self.observe(doc, encoded.len()))
     }
 
-    /// Finish zstd stream and write meta.json.
-    pub fn finalize(self) -> Result<(), StupidError> {
+    /// Fold a document written at the current offset into the part's
+    /// bookkeeping, returning that offset.
+    fn observe(&mut self, doc: &Document, encoded_len: usize) -> u64 {
+        let doc_offset = self.raw_bytes;
+
+        self.bloom_keys.push(bloom::id_key(&doc.id));
+        if let Some(secondary) = &mut self.secondary {
+            secondary.observe(doc, doc_offset);
+            for field in secondary.indexed_fields() {
+                if let Some(value) = doc.fields.get(field).and_then(index_key) {
+                    self.bloom_keys.push(bloom::field_key(field, &value));
+                }
+            }
+        }
+
+        self.raw_bytes += 4 + encoded_len as u64;
+        self.meta.observe(doc);
+        doc_offset
+    }
+
+    /// End the current zstd frame and sync `documents.dat`, returning its
+    /// length. Later documents go into a new frame appended after it.
+    fn checkpoint(&mut self) -> Result<u64, StupidError> {
+        // The clone shares the file offset, so the next frame follows this one.
+        let file = self.encoder.get_ref().get_ref().try_clone()?;
+        let next = zstd::Encoder::new(std::io::BufWriter::new(file), 3).map_err(StupidError::Io)?;
+
+        let buf_writer = std::mem::replace(&mut self.encoder, next).finish().map_err(StupidError::Io)?;
+        let file = buf_writer.into_inner().map_err(|e| StupidError::Io(e.into_error()))?;
+        file.sync_data()?;
+        Ok(file.metadata()?.len())
+    }
+
+    fn finalize(mut self) -> Result<(), StupidError> {
         let buf_writer = self.encoder.finish().map_err(StupidError::Io)?;
         let mut inner = buf_writer.into_inner().map_err(|e| StupidError::Io(e.into_error()))?;
         inner.flush()?;
@@ -87,23 +287,285 @@ impl SegmentWriter {
             100
         };
 
-        let meta = SegmentMeta {
-            segment_id: self.segment_id.clone(),
-            document_count: self.doc_count,
-            size_bytes: compressed_size,
-            raw_bytes: self.raw_bytes,
-            compression: "zstd".to_string(),
-        };
+        let mut bloom_fields = Vec::new();
+        if let Some(secondary) = &self.secondary {
+            secondary.save(&self.segment_dir.join(SECONDARY_INDEX_FILE))?;
+            bloom_fields.extend(secondary.indexed_fields().map(str::to_string));
+        }
+        SegmentBloom::from_keys(&bloom_fields, &self.bloom_keys)
+            .save(&self.segment_dir.join(BLOOM_FILTER_FILE))?;
 
-        let meta_path = self.segment_dir.join("meta.json");
-        let meta_json = serde_json::to_string_pretty(&meta)
-            .map_err(|e| StupidError::Serialize(e.to_string()))?;
-        fs::write(&meta_path, meta_json)?;
+        // meta.json goes last: watchers take it as the sign that every other
+        // file of the segment is complete.
+        self.meta.size_bytes = compressed_size;
+        self.meta.raw_bytes = self.raw_bytes;
+        self.meta.compression = "zstd".to_string();
+        self.meta.save(&self.segment_dir)?;
 
         info!(
             "Segment {} finalized: {} docs, {} bytes ({}% of raw {})",
-            self.segment_id, self.doc_count, compressed_size, ratio, self.raw_bytes
+            self.segment_id, self.meta.document_count, compressed_size, ratio, self.raw_bytes
         );
         Ok(())
     }
 }
+
+/// Writes documents into a zstd-compressed segment.
+///
+/// With a [`RolloverPolicy`], the writer finishes the current segment once it
+/// reaches the limit and continues in a sub-segment under the base directory
+/// (`Login/2025-W24` → `Login/2025-W24/part-001`, `part-002`, ...). Each part
+/// is a complete segment that readers and discovery open like any other.
+///
+/// Until finalized, the writer keeps a [`WriteMarker`] in the base directory,
+/// updated every checkpoint interval and on rollover. A write interrupted
+/// part-way can be continued with [`resume`](Self::resume) instead of
+/// starting over.
+pub struct SegmentWriter {
+    base_id: SegmentId,
+    base_dir: PathBuf,
+    current: Part,
+    /// Number of rollover parts opened so far (0 = still writing the base).
+    parts: usize,
+    rollover: RolloverPolicy,
+    indexed_fields: Vec<String>,
+    checkpoint_interval: u64,
+    /// Fingerprint of the input, recorded in the marker.
+    source: String,
+    /// Documents written across all parts, including resumed ones.
+    total_docs: u64,
+    /// Documents appended since the last checkpoint.
+    uncommitted_docs: u64,
+    /// Documents already committed by the interrupted write this one resumed.
+    resumed_docs: u64,
+}
+
+impl SegmentWriter {
+    /// Create a writer under `data_dir/segments/{segment_id}/`.
+    ///
+    /// An unfinished write of the same segment is discarded with a warning;
+    /// check [`unfinished`](Self::unfinished) and use
+    /// [`resume`](Self::resume) to continue it instead.
+    pub fn new(data_dir: &Path, segment_id: &str) -> Result<Self, StupidError> {
+        let segment_dir = data_dir.join("segments").join(segment_id);
+        if let Some(marker) = WriteMarker::load(&segment_dir)? {
+            warn!(
+                segment_id,
+                committed_docs = marker.committed_docs,
+                "Overwriting unfinished segment write"
+            );
+        }
+        Self::open(segment_dir, segment_id, String::new())
+    }
+
+    /// The marker of an unfinished write of `segment_id`, if there is one.
+    pub fn unfinished(data_dir: &Path, segment_id: &str) -> Result<Option<WriteMarker>, StupidError> {
+        WriteMarker::load(&data_dir.join("segments").join(segment_id))
+    }
+
+    /// Continue an unfinished write of `segment_id` from its last checkpoint,
+    /// or start a new one like [`new`](Self::new) if there is none.
+    ///
+    /// The caller skips the first [`resumed_docs`](Self::resumed_docs) of
+    /// its input, which must be the same documents in the same order as the
+    /// interrupted write, and appends the rest. An unfinished write of a
+    /// different `source` is overwritten with a warning instead, so changed
+    /// input is never partly skipped.
+    pub fn resume(data_dir: &Path, segment_id: &str, source: &WriteSource) -> Result<Self, StupidError> {
+        let segment_dir = data_dir.join("segments").join(segment_id);
+        let source = source.fingerprint();
+        let marker = match WriteMarker::load(&segment_dir)? {
+            Some(marker) if marker.source == source => marker,
+            Some(marker) => {
+                warn!(
+                    segment_id,
+                    committed_docs = marker.committed_docs,
+                    "Input differs from the unfinished segment write; overwriting it"
+                );
+                return Self::open(segment_dir, segment_id, source);
+            }
+            None => return Self::open(segment_dir, segment_id, source),
+        };
+
+        let (part_id, part_dir) = match marker.parts {
+            0 => (segment_id.to_string(), segment_dir.clone()),
+            n => (
+                part_segment_id(segment_id, n),
+                segment_dir.join(format!("{PART_PREFIX}{n:03}")),
+            ),
+        };
+        remove_parts(&segment_dir, |n| n > marker.parts)?;
+
+        Ok(Self {
+            base_id: segment_id.to_string(),
+            current: Part::resume(part_dir, &part_id, marker.committed_bytes)?,
+            base_dir: segment_dir,
+            parts: marker.parts,
+            rollover: RolloverPolicy::default(),
+            indexed_fields: Vec::new(),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            source,
+            total_docs: marker.committed_docs,
+            uncommitted_docs: 0,
+            resumed_docs: marker.committed_docs,
+        })
+    }
+
+    /// Create a writer at an explicit directory path.
+    ///
+    /// Use this when the default `data_dir/segments/` layout doesn't apply
+    /// (e.g., queue data stored at `data/{provider}/{queue_name}/{date}/`).
+    pub fn new_at(dir: PathBuf, segment_id: &str) -> Result<Self, StupidError> {
+        Self::open(dir, segment_id, String::new())
+    }
+
+    fn open(segment_dir: PathBuf, segment_id: &str, source: String) -> Result<Self, StupidError> {
+        // The base is being rewritten, so parts from a previous write are stale.
+        remove_parts(&segment_dir, |_| true)?;
+
+        let writer = Self {
+            base_id: segment_id.to_string(),
+            current: Part::open(segment_dir.clone(), segment_id, &[])?,
+            base_dir: segment_dir,
+            parts: 0,
+            rollover: RolloverPolicy::default(),
+            indexed_fields: Vec::new(),
+            checkpoint_interval: DEFAULT_CHECKPOINT_INTERVAL,
+            source,
+            total_docs: 0,
+            uncommitted_docs: 0,
+            resumed_docs: 0,
+        };
+        writer.marker(0).save(&writer.base_dir)?;
+        Ok(writer)
+    }
+
+    /// Build a secondary index over `fields`, written as `secondary.idx` on finalize.
+    /// Their values are also added to the segment's bloom filter.
+    ///
+    /// An empty list disables the secondary index.
+    pub fn with_indexed_fields(mut self, fields: &[String]) -> Self {
+        self.current.secondary = (!fields.is_empty()).then(|| SecondaryIndex::new(fields));
+        self.indexed_fields = fields.to_vec();
+        self
+    }
+
+    /// Roll over to a new part when `policy`'s limits are reached.
+    pub fn with_rollover(mut self, policy: RolloverPolicy) -> Self {
+        self.rollover = policy;
+        self
+    }
+
+    /// Checkpoint every `docs` appended documents; `0` checkpoints only on
+    /// rollover. Each checkpoint ends a zstd frame, so a very small interval
+    /// costs compression.
+    pub fn with_checkpoint_interval(mut self, docs: u64) -> Self {
+        self.checkpoint_interval = docs;
+        self
+    }
+
+    /// Documents the interrupted write had committed when this writer was
+    /// created by [`resume`](Self::resume); the caller skips that many.
+    pub fn resumed_docs(&self) -> u64 {
+        self.resumed_docs
+    }
+
+    /// Segment ID of the part the next document will be written to.
+    ///
+    /// Offsets returned by [`append`](Self::append) are relative to this part.
+    pub fn part_id(&self) -> &str {
+        &self.current.segment_id
+    }
+
+    /// Segment IDs of every part written so far, base first.
+    pub fn part_ids(&self) -> Vec<SegmentId> {
+        std::iter::once(self.base_id.clone())
+            .chain((1..=self.parts).map(|n| part_segment_id(&self.base_id, n)))
+            .collect()
+    }
+
+    /// Append a document to the zstd-compressed stream.
+    ///
+    /// Returns the document's offset within the current part; rolls over to a
+    /// new part first if the rollover policy's limits have been reached.
+    pub fn append(&mut self, doc: &Document) -> Result<u64, StupidError> {
+        self.current.replay()?;
+        let encoded =
+            rmp_serde::to_vec(doc).map_err(|e| StupidError::Serialize(e.into()))?;
+
+        if self.rollover.should_roll(
+            self.current.meta.document_count as u64,
+            self.current.raw_bytes,
+            4 + encoded.len() as u64,
+        ) {
+            self.roll()?;
+        }
+
+        let offset = self.current.append(doc, &encoded)?;
+        self.total_docs += 1;
+        self.uncommitted_docs += 1;
+        if self.checkpoint_interval > 0 && self.uncommitted_docs >= self.checkpoint_interval {
+            self.checkpoint()?;
+        }
+        Ok(offset)
+    }
+
+    /// Make everything appended so far durable and record it in the marker.
+    fn checkpoint(&mut self) -> Result<(), StupidError> {
+        let committed_bytes = self.current.checkpoint()?;
+        self.marker(committed_bytes).save(&self.base_dir)?;
+        self.uncommitted_docs = 0;
+        Ok(())
+    }
+
+    fn marker(&self, committed_bytes: u64) -> WriteMarker {
+        WriteMarker {
+            committed_docs: self.total_docs,
+            parts: self.parts,
+            committed_bytes,
+            source: self.source.clone(),
+        }
+    }
+
+    /// Finish the current part and start the next one.
+    fn roll(&mut self) -> Result<(), StupidError> {
+        let n = self.parts + 1;
+        let id = part_segment_id(&self.base_id, n);
+        let dir = self.base_dir.join(format!("{PART_PREFIX}{n:03}"));
+        let next = Part::open(dir, &id, &self.indexed_fields)?;
+
+        std::mem::replace(&mut self.current, next).finalize()?;
+        self.parts = n;
+        self.marker(0).save(&self.base_dir)?;
+        self.uncommitted_docs = 0;
+        info!(segment_id = %id, "Segment rolled over to new part");
+        Ok(())
+    }
+
+    /// Finish zstd stream, write meta.json and remove the write marker.
+    pub fn finalize(mut self) -> Result<(), StupidError> {
+        self.current.replay()?;
+        self.current.finalize()?;
+        fs::remove_file(self.base_dir.join(WRITE_MARKER_FILE))?;
+        Ok(())
+    }
+}
+
+/// Remove rollover part directories under `segment_dir` whose number
+/// matches `stale`.
+fn remove_parts(segment_dir: &Path, stale: impl Fn(usize) -> bool) -> Result<(), StupidError> {
+    let Ok(entries) = fs::read_dir(segment_dir) else {
+        return Ok(());
+    };
+    for entry in entries.flatten() {
+        let number = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.strip_prefix(PART_PREFIX))
+            .map(|n| n.parse::<usize>().unwrap_or(usize::MAX));
+        if number.is_some_and(&stale) && entry.path().is_dir() {
+            fs::remove_dir_all(entry.path())?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,81 @@
+use chrono::{TimeZone, Utc};
+use uuid::Uuid;
+
+use stupid_core::FieldValue;
+use stupid_segment::bloom::BLOOM_FILTER_FILE;
+use stupid_segment::store::DocumentStore;
+
+use crate::helpers::{make_config, make_doc_with_fields, test_data_dir};
+
+/// Three daily segments, one member each, with fixed document IDs so the
+/// filters' bits (and any false positives) are the same on every run.
+fn populate(store: &mut DocumentStore) -> Vec<Uuid> {
+    let mut ids = Vec::new();
+    for (day, member) in [(14, "alice"), (15, "bob"), (16, "carol")] {
+        for i in 0..20u128 {
+            let ts = Utc.with_ymd_and_hms(2025, 6, day, 10, 0, 0).unwrap();
+            let mut doc = make_doc_with_fields(
+                "Login",
+                ts,
+                vec![("memberCode", FieldValue::Text(member.to_string()))],
+            );
+            doc.id = Uuid::from_u128(u128::from(day) << 64 | i);
+            ids.push(doc.id);
+            store.insert(doc).unwrap();
+        }
+    }
+    store.flush().unwrap();
+    ids
+}
+
+#[test]
+fn test_bloom_filter_skips_segments_that_cannot_match() {
+    let data_dir = test_data_dir();
+    let mut config = make_config(data_dir.clone(), 30);
+    config.indexed_fields = vec!["memberCode".to_string()];
+    let ids = {
+        let mut store = DocumentStore::new(&config).unwrap();
+        populate(&mut store)
+    };
+
+    for day in ["2025-06-14", "2025-06-15", "2025-06-16"] {
+        assert!(data_dir.join("segments").join(day).join(BLOOM_FILTER_FILE).exists());
+    }
+
+    // Reopen from disk: filters are loaded with their segments.
+    let store = DocumentStore::new(&config).unwrap();
+    let manager = store.manager();
+    assert_eq!(manager.segments_may_contain("memberCode", "alice"), vec!["2025-06-14"]);
+    assert_eq!(manager.segments_may_contain("memberCode", "carol"), vec!["2025-06-16"]);
+    assert!(manager.segments_may_contain("memberCode", "dave").is_empty());
+    // Fields the filter doesn't cover can't rule a segment out.
+    assert_eq!(manager.segments_may_contain("country", "DE").len(), 3);
+
+    let alice_id = ids[0];
+    assert!(manager.may_contain_id("2025-06-14", &alice_id));
+    assert!(!manager.may_contain_id("2025-06-15", &alice_id));
+    assert!(!manager.may_contain_id("2025-06-16", &alice_id));
+
+    assert_eq!(store.lookup("memberCode", "bob").unwrap().len(), 20);
+    assert!(store.lookup("memberCode", "dave").unwrap().is_empty());
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_bloom_filter_never_hides_stored_documents() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+    let ids = populate(&mut store);
+
+    for id in &ids {
+        assert_eq!(store.get_by_id(id).unwrap().id, *id);
+    }
+    // Without a secondary index, lookups fall back to scanning every segment.
+    assert_eq!(store.manager().segments_may_contain("memberCode", "dave").len(), 3);
+    assert_eq!(store.lookup("memberCode", "alice").unwrap().len(), 20);
+    assert!(store.get_by_id(&Uuid::from_u128(1)).is_err());
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
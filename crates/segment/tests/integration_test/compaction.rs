@@ -0,0 +1,95 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use stupid_core::FieldValue;
+use stupid_segment::filter::ScanFilter;
+use stupid_segment::store::DocumentStore;
+
+use crate::helpers::{make_config, make_doc_with_fields, test_data_dir};
+
+#[test]
+fn test_compact_three_segments() {
+    let data_dir = test_data_dir();
+    let mut config = make_config(data_dir.clone(), 30);
+    config.indexed_fields = vec!["memberCode".to_string()];
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let day1 = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let mut ids = Vec::new();
+    // Within each day, insert later documents first so ordering is exercised.
+    for day in 0..3 {
+        for hour in [20, 5, 12] {
+            let doc = make_doc_with_fields(
+                "Login",
+                day1 + Duration::days(day) + Duration::hours(hour),
+                vec![("memberCode", FieldValue::Text(format!("m{hour}")))],
+            );
+            ids.push(doc.id);
+            store.insert(doc).unwrap();
+        }
+    }
+    store.flush().unwrap();
+    assert_eq!(store.manager().list_segments().len(), 3);
+
+    let sources: Vec<String> = store.manager().list_segments();
+    let count = store.compact(&sources, "2025-06-14").unwrap();
+    assert_eq!(count, 9);
+
+    assert_eq!(store.manager().list_segments(), vec!["2025-06-14"]);
+    assert!(!data_dir.join("segments").join("2025-06-15").exists());
+    assert!(!data_dir.join("segments").join("2025-06-16").exists());
+
+    // Documents are ordered by timestamp.
+    let reader = store.manager().get_reader("2025-06-14").unwrap();
+    let docs: Vec<_> = reader.iter().map(|d| d.unwrap()).collect();
+    assert_eq!(docs.len(), 9);
+    assert!(docs.windows(2).all(|w| w[0].timestamp <= w[1].timestamp));
+
+    // Lookups by ID, secondary index, and index-backed scans still work.
+    for id in &ids {
+        assert_eq!(store.get_by_id(id).unwrap().id, *id);
+    }
+    assert_eq!(store.lookup("memberCode", "m5").unwrap().len(), 3);
+    let results = store
+        .scan(&ScanFilter::new().field_eq("memberCode", "m20"))
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(store.stats().document_count, 9);
+
+    // Everything survives a reload from disk.
+    drop(store);
+    let store = DocumentStore::new(&config).unwrap();
+    assert_eq!(store.manager().list_segments(), vec!["2025-06-14"]);
+    assert_eq!(store.stats().document_count, 9);
+    assert_eq!(store.get_by_id(&ids[4]).unwrap().id, ids[4]);
+    assert_eq!(store.lookup("memberCode", "m12").unwrap().len(), 3);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_compact_into_new_segment_and_reject_existing_target() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let day1 = Utc.with_ymd_and_hms(2025, 6, 14, 10, 0, 0).unwrap();
+    for day in 0..3 {
+        store
+            .insert(make_doc_with_fields("Login", day1 + Duration::days(day), vec![]))
+            .unwrap();
+    }
+    store.flush().unwrap();
+
+    // Target exists but isn't a source: refuse rather than clobber it.
+    let err = store.compact(&["2025-06-14".to_string(), "2025-06-15".to_string()], "2025-06-16");
+    assert!(err.is_err());
+    assert_eq!(store.manager().list_segments().len(), 3);
+
+    let count = store
+        .compact(&["2025-06-15".to_string(), "2025-06-16".to_string()], "2025-06-17")
+        .unwrap();
+    assert_eq!(count, 2);
+    assert_eq!(store.manager().list_segments(), vec!["2025-06-14", "2025-06-17"]);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -0,0 +1,120 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use stupid_core::FieldValue;
+use stupid_segment::filter::ScanFilter;
+use stupid_segment::store::DocumentStore;
+
+use crate::helpers::{make_config, make_doc_with_fields, test_data_dir};
+
+#[test]
+fn test_delete_segment_removes_files_and_updates_stats() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let day1 = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let mut day1_ids = Vec::new();
+    for day in 0..2 {
+        for hour in 0..4 {
+            let doc = make_doc_with_fields(
+                "Login",
+                day1 + Duration::days(day) + Duration::hours(hour),
+                vec![("memberCode", FieldValue::Text(format!("m{hour}")))],
+            );
+            if day == 0 {
+                day1_ids.push(doc.id);
+            }
+            store.insert(doc).unwrap();
+        }
+    }
+    store.flush().unwrap();
+    assert_eq!(store.stats().document_count, 8);
+
+    let removed = store.delete_segment("2025-06-14").unwrap();
+    assert_eq!(removed, 4);
+    assert!(!data_dir.join("segments").join("2025-06-14").exists());
+
+    let stats = store.stats();
+    assert_eq!(stats.segment_count, 1);
+    assert_eq!(stats.document_count, 4);
+    assert!(store.get_by_id(&day1_ids[0]).is_err());
+    assert_eq!(store.scan(&ScanFilter::new()).unwrap().len(), 4);
+    assert!(store.delete_segment("2025-06-14").is_err());
+
+    // The deletion is permanent across a reload.
+    drop(store);
+    let store = DocumentStore::new(&config).unwrap();
+    assert_eq!(store.manager().list_segments(), vec!["2025-06-15"]);
+    assert_eq!(store.stats().document_count, 4);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_delete_by_filter_tombstones_then_compaction_reclaims_space() {
+    let data_dir = test_data_dir();
+    let mut config = make_config(data_dir.clone(), 30);
+    config.indexed_fields = vec!["memberCode".to_string()];
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let mut alice_ids = Vec::new();
+    for i in 0..40 {
+        let member = if i % 4 == 0 { "alice" } else { "bob" };
+        let doc = make_doc_with_fields(
+            "Login",
+            base + Duration::minutes(i),
+            vec![
+                ("memberCode", FieldValue::Text(member.to_string())),
+                ("payload", FieldValue::Text("x".repeat(200))),
+            ],
+        );
+        if member == "alice" {
+            alice_ids.push(doc.id);
+        }
+        store.insert(doc).unwrap();
+    }
+    store.flush().unwrap();
+    let size_before = store.stats().total_bytes;
+    let raw_before = store.segment_metas()[0].raw_bytes;
+
+    // GDPR: delete every event for one member.
+    let deleted = store
+        .delete_by_filter(&ScanFilter::new().field_eq("memberCode", "alice"))
+        .unwrap();
+    assert_eq!(deleted, 10);
+    assert_eq!(
+        store.delete_by_filter(&ScanFilter::new().field_eq("memberCode", "alice")).unwrap(),
+        0,
+        "already tombstoned"
+    );
+
+    // Tombstoned documents are hidden everywhere but still on disk.
+    assert_eq!(store.stats().document_count, 30);
+    assert_eq!(store.scan(&ScanFilter::new()).unwrap().len(), 30);
+    assert!(store.lookup("memberCode", "alice").unwrap().is_empty());
+    assert!(store.get_by_id(&alice_ids[0]).is_err());
+    assert_eq!(store.segment_metas()[0].document_count, 40);
+
+    // Tombstones survive a reload.
+    drop(store);
+    let mut store = DocumentStore::new(&config).unwrap();
+    assert_eq!(store.scan(&ScanFilter::new()).unwrap().len(), 30);
+
+    // Compaction physically drops the tombstoned records.
+    assert_eq!(store.purge_deleted().unwrap(), 10);
+    assert_eq!(store.purge_deleted().unwrap(), 0);
+
+    let meta = store.segment_metas()[0].clone();
+    assert_eq!(meta.document_count, 30);
+    assert!(meta.raw_bytes < raw_before);
+    assert!(store.stats().total_bytes < size_before);
+    assert_eq!(store.stats().document_count, 30);
+    assert!(store.lookup("memberCode", "alice").unwrap().is_empty());
+    assert_eq!(store.lookup("memberCode", "bob").unwrap().len(), 30);
+
+    let reader = store.manager().get_reader("2025-06-14").unwrap();
+    assert!(reader.iter().all(|d| !alice_ids.contains(&d.unwrap().id)));
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
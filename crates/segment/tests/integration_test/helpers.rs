@@ -21,6 +21,10 @@ pub fn make_config(data_dir: PathBuf, retention_days: u32) -> StorageConfig {
         segment_retention_days: retention_days,
         cache_dir: data_dir.join("cache"),
         cache_max_gb: 1,
+        indexed_fields: Vec::new(),
+        segment_max_docs: 0,
+        segment_max_bytes: 0,
+        in_memory: false,
     }
 }
 
@@ -1,8 +1,16 @@
-/// Integration tests for the document store covering full pipeline, parquet import,
-/// segment rotation, eviction, persistence, scan filters, and statistics.
+//! Integration tests for the document store covering full pipeline, parquet import,
+//! segment rotation and rollover, eviction, deletion, persistence, scan filters, and statistics.
 
+mod bloom_filter;
+mod compaction;
+mod deletion;
 mod helpers;
 mod pipeline;
+mod projection;
+mod rollover;
 mod rotation;
 mod scan_filters;
+mod segment_meta;
+mod secondary_index;
 mod store_ops;
+mod write_marker;
@@ -0,0 +1,47 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use stupid_core::FieldValue;
+use stupid_segment::reader::SegmentReader;
+use stupid_segment::writer::SegmentWriter;
+
+use crate::helpers::{make_doc_with_fields, test_data_dir};
+
+#[test]
+fn test_iter_fields_matches_full_iteration_on_projected_fields() {
+    let data_dir = test_data_dir();
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+
+    let mut writer = SegmentWriter::new(&data_dir, "2025-06-14").unwrap();
+    for i in 0..20 {
+        let mut fields = vec![
+            ("platform", FieldValue::Text("web".to_string())),
+            ("payload", FieldValue::Text("x".repeat(64))),
+            ("score", FieldValue::Integer(i)),
+        ];
+        if i % 2 == 0 {
+            fields.push(("memberCode", FieldValue::Text(format!("m{i}"))));
+        }
+        writer
+            .append(&make_doc_with_fields("Login", base + Duration::minutes(i), fields))
+            .unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let reader = SegmentReader::open(&data_dir, "2025-06-14").unwrap();
+    let wanted = ["memberCode", "score"];
+    let full: Vec<_> = reader.iter().map(Result::unwrap).collect();
+    let projected: Vec<_> = reader.iter_fields(&wanted).map(Result::unwrap).collect();
+
+    assert_eq!(projected.len(), full.len());
+    for (p, f) in projected.iter().zip(&full) {
+        assert_eq!(p.id, f.id);
+        assert_eq!(p.timestamp, f.timestamp);
+        assert_eq!(p.event_type, f.event_type);
+        assert!(p.fields.keys().all(|k| wanted.contains(&k.as_str())));
+        for name in wanted {
+            assert_eq!(p.fields.get(name), f.fields.get(name));
+        }
+    }
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -0,0 +1,108 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use stupid_core::FieldValue;
+use stupid_segment::filter::ScanFilter;
+use stupid_segment::reader::SegmentReader;
+use stupid_segment::store::DocumentStore;
+use stupid_segment::writer::{RolloverPolicy, SegmentWriter};
+
+use crate::helpers::{make_config, make_doc_with_fields, test_data_dir};
+
+#[test]
+fn test_large_append_rolls_over_into_discoverable_parts() {
+    let data_dir = test_data_dir();
+    let mut config = make_config(data_dir.clone(), 30);
+    config.segment_max_docs = 10;
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let mut ids = Vec::new();
+    for i in 0..35 {
+        let doc = make_doc_with_fields(
+            "Login",
+            base + Duration::minutes(i),
+            vec![("memberCode", FieldValue::Text(format!("m{i}")))],
+        );
+        ids.push(doc.id);
+        store.insert(doc).unwrap();
+    }
+    store.flush().unwrap();
+
+    let segments = store.manager().list_segments();
+    assert_eq!(
+        segments,
+        vec![
+            "2025-06-14",
+            "2025-06-14/part-001",
+            "2025-06-14/part-002",
+            "2025-06-14/part-003",
+        ]
+    );
+    for sid in &segments {
+        let meta = store.manager().segment_meta(sid).unwrap();
+        assert!(meta.document_count <= 10, "{sid} has {} docs", meta.document_count);
+    }
+    assert_eq!(store.stats().document_count, 35);
+
+    // Every document is addressable and scannable across parts.
+    for id in &ids {
+        assert_eq!(store.get_by_id(id).unwrap().id, *id);
+    }
+    let start = base + Duration::minutes(5);
+    let end = base + Duration::minutes(25);
+    let results = store.scan(&ScanFilter::time_range(start, end)).unwrap();
+    assert_eq!(results.len(), 21);
+
+    // Parts are rediscovered after a restart.
+    drop(store);
+    let store = DocumentStore::new(&config).unwrap();
+    assert_eq!(store.manager().list_segments(), segments);
+    assert_eq!(store.stats().document_count, 35);
+    assert_eq!(store.scan(&ScanFilter::new()).unwrap().len(), 35);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_writer_rolls_over_on_byte_limit() {
+    let data_dir = test_data_dir();
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+
+    let mut writer = SegmentWriter::new(&data_dir, "Login/2025-W24")
+        .unwrap()
+        .with_rollover(RolloverPolicy::new(0, 2_000));
+    for i in 0..50 {
+        let doc = make_doc_with_fields(
+            "Login",
+            base + Duration::minutes(i),
+            vec![("payload", FieldValue::Text("x".repeat(100)))],
+        );
+        writer.append(&doc).unwrap();
+    }
+    let part_ids = writer.part_ids();
+    writer.finalize().unwrap();
+
+    assert!(part_ids.len() > 1);
+    assert_eq!(part_ids[0], "Login/2025-W24");
+    assert_eq!(part_ids[1], "Login/2025-W24/part-001");
+    assert_eq!(SegmentReader::part_ids(&data_dir, "Login/2025-W24"), part_ids);
+
+    let mut total = 0;
+    for part_id in &part_ids {
+        let reader = SegmentReader::open(&data_dir, part_id).unwrap();
+        let meta = reader.meta().unwrap();
+        assert!(meta.raw_bytes <= 2_000, "{part_id} has {} raw bytes", meta.raw_bytes);
+        total += reader.iter().count();
+    }
+    assert_eq!(total, 50);
+
+    // Rewriting the segment drops parts left over from the previous write.
+    let writer = SegmentWriter::new(&data_dir, "Login/2025-W24").unwrap();
+    writer.finalize().unwrap();
+    assert_eq!(
+        SegmentReader::part_ids(&data_dir, "Login/2025-W24"),
+        vec!["Login/2025-W24"]
+    );
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -225,3 +225,91 @@ fn test_numeric_field_filters() {
 
     std::fs::remove_dir_all(&data_dir).ok();
 }
+
+#[test]
+fn test_index_pushdown_matches_naive_scan() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let event_types = ["Login", "GameOpened", "Logout", "APIError"];
+    for i in 0..200i64 {
+        store
+            .insert(make_doc_with_fields(
+                event_types[i as usize % event_types.len()],
+                base + Duration::minutes(i * 5),
+                vec![
+                    ("member", FieldValue::Text(format!("user{}", i % 7))),
+                    ("score", FieldValue::Integer(i)),
+                ],
+            ))
+            .unwrap();
+    }
+    store.flush().unwrap();
+
+    let segment_id = store.manager().list_segments().pop().unwrap();
+    let reader = store.manager().get_reader(&segment_id).unwrap();
+    let index = store.index(&segment_id).unwrap();
+
+    let filters = [
+        ScanFilter::new(),
+        ScanFilter::new().event_type("APIError"),
+        ScanFilter::new().event_type("Login").field_eq("member", "user3"),
+        ScanFilter::time_range(base + Duration::hours(2), base + Duration::hours(4)),
+        ScanFilter::time_range(base + Duration::hours(1), base + Duration::hours(10))
+            .event_type("GameOpened")
+            .field_gt("score", 50.0),
+        ScanFilter::new().event_type("NonExistent"),
+    ];
+
+    for filter in &filters {
+        let naive: Vec<_> = reader
+            .iter()
+            .map(|d| d.unwrap())
+            .filter(|d| filter.matches(d))
+            .collect();
+        let (pushed, stats) = reader.scan_with_filter(index, filter).unwrap();
+
+        let naive_ids: Vec<_> = naive.iter().map(|d| d.id).collect();
+        let pushed_ids: Vec<_> = pushed.iter().map(|d| d.id).collect();
+        assert_eq!(pushed_ids, naive_ids, "filter {filter:?}");
+        assert_eq!(stats.matched, naive.len());
+        assert_eq!(stats.examined, 200);
+    }
+
+    // A selective event-type filter only deserializes its candidates.
+    let (_, stats) = reader
+        .scan_with_filter(index, &ScanFilter::new().event_type("APIError"))
+        .unwrap();
+    assert_eq!(stats.deserialized, 50);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_pushdown_without_index_falls_back_to_full_scan() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let now = Utc::now();
+    for event_type in ["Login", "Logout", "Login"] {
+        store
+            .insert(make_doc_with_fields(event_type, now, vec![]))
+            .unwrap();
+    }
+    store.flush().unwrap();
+
+    let segment_id = store.manager().list_segments().pop().unwrap();
+    let reader = store.manager().get_reader(&segment_id).unwrap();
+    let empty = stupid_segment::index::DocIndex::new();
+
+    let (docs, stats) = reader
+        .scan_with_filter(&empty, &ScanFilter::new().event_type("Login"))
+        .unwrap();
+    assert_eq!(docs.len(), 2);
+    assert_eq!(stats.deserialized, 3);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
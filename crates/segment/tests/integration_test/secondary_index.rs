@@ -0,0 +1,91 @@
+use chrono::{TimeZone, Utc};
+
+use stupid_core::FieldValue;
+use stupid_segment::secondary::SECONDARY_INDEX_FILE;
+use stupid_segment::store::DocumentStore;
+
+use crate::helpers::{make_config, make_doc_with_fields, test_data_dir};
+
+#[test]
+fn test_secondary_index_build_persist_reload_lookup() {
+    let data_dir = test_data_dir();
+    let mut config = make_config(data_dir.clone(), 30);
+    config.indexed_fields = vec!["memberCode".to_string()];
+
+    let ts = Utc.with_ymd_and_hms(2025, 6, 14, 10, 0, 0).unwrap();
+    let members = ["alice", "bob", "alice", "carol", "alice"];
+    {
+        let mut store = DocumentStore::new(&config).unwrap();
+        for (i, member) in members.iter().enumerate() {
+            store
+                .insert(make_doc_with_fields(
+                    "Login",
+                    ts,
+                    vec![
+                        ("memberCode", FieldValue::Text(member.to_string())),
+                        ("seq", FieldValue::Integer(i as i64)),
+                    ],
+                ))
+                .unwrap();
+        }
+        store.flush().unwrap();
+
+        let reader = store.manager().get_reader("2025-06-14").unwrap();
+        assert!(reader.has_secondary_index("memberCode"));
+        assert_eq!(store.lookup("memberCode", "alice").unwrap().len(), 3);
+    }
+
+    assert!(data_dir
+        .join("segments")
+        .join("2025-06-14")
+        .join(SECONDARY_INDEX_FILE)
+        .exists());
+
+    // Reopen from disk: the index is reloaded, not rebuilt.
+    let store = DocumentStore::new(&config).unwrap();
+    let reader = store.manager().get_reader("2025-06-14").unwrap();
+    assert!(reader.has_secondary_index("memberCode"));
+
+    let alice = reader.lookup("memberCode", "alice").unwrap();
+    let seqs: Vec<_> = alice.iter().map(|d| d.fields["seq"].clone()).collect();
+    assert_eq!(
+        seqs,
+        vec![FieldValue::Integer(0), FieldValue::Integer(2), FieldValue::Integer(4)]
+    );
+
+    let bob = reader.lookup("memberCode", "bob").unwrap();
+    assert_eq!(bob.len(), 1);
+    assert!(reader.lookup("memberCode", "dave").unwrap().is_empty());
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_lookup_on_unindexed_field_falls_back_to_scan() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let ts = Utc.with_ymd_and_hms(2025, 6, 14, 10, 0, 0).unwrap();
+    for member in ["alice", "bob", "alice"] {
+        store
+            .insert(make_doc_with_fields(
+                "Login",
+                ts,
+                vec![("memberCode", FieldValue::Text(member.to_string()))],
+            ))
+            .unwrap();
+    }
+    store.flush().unwrap();
+
+    let reader = store.manager().get_reader("2025-06-14").unwrap();
+    assert!(!reader.has_secondary_index("memberCode"));
+    assert_eq!(reader.lookup("memberCode", "alice").unwrap().len(), 2);
+    assert!(!data_dir
+        .join("segments")
+        .join("2025-06-14")
+        .join(SECONDARY_INDEX_FILE)
+        .exists());
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -0,0 +1,129 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Duration, TimeZone, Utc};
+
+use stupid_core::FieldValue;
+use stupid_segment::store::DocumentStore;
+
+use crate::helpers::{make_config, make_doc_with_fields, test_data_dir};
+
+#[test]
+fn test_meta_matches_written_documents() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    let docs = vec![
+        make_doc_with_fields(
+            "Login",
+            base + Duration::hours(9),
+            vec![("memberCode", FieldValue::Text("alice".to_string()))],
+        ),
+        make_doc_with_fields(
+            "GameOpened",
+            base + Duration::hours(2),
+            vec![
+                ("memberCode", FieldValue::Text("bob".to_string())),
+                ("gameUid", FieldValue::Text("poker".to_string())),
+            ],
+        ),
+        make_doc_with_fields(
+            "Login",
+            base + Duration::hours(17),
+            vec![("ip", FieldValue::Text("10.0.0.1".to_string()))],
+        ),
+    ];
+    for doc in &docs {
+        store.insert(doc.clone()).unwrap();
+    }
+    store.flush().unwrap();
+
+    let reader = store.manager().get_reader("2025-06-14").unwrap();
+    let meta = reader.meta().expect("sealed segment has meta");
+
+    assert_eq!(meta.segment_id, "2025-06-14");
+    assert_eq!(meta.document_count, 3);
+    assert_eq!(meta.compression, "zstd");
+    assert_eq!(meta.min_timestamp, Some(base + Duration::hours(2)));
+    assert_eq!(meta.max_timestamp, Some(base + Duration::hours(17)));
+    assert_eq!(
+        meta.event_type_counts,
+        BTreeMap::from([("GameOpened".to_string(), 1), ("Login".to_string(), 2)])
+    );
+    assert_eq!(
+        meta.field_names,
+        BTreeSet::from(["gameUid", "ip", "memberCode"].map(String::from))
+    );
+
+    // Reloaded from disk, the meta is identical and feeds store stats.
+    let raw = std::fs::read_to_string(data_dir.join("segments/2025-06-14/meta.json")).unwrap();
+    let from_disk: stupid_segment::SegmentMeta = serde_json::from_str(&raw).unwrap();
+    assert_eq!(&from_disk, meta);
+    assert_eq!(store.stats().total_bytes, meta.size_bytes);
+    assert_eq!(store.segment_metas(), vec![meta]);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_damaged_meta_falls_back_to_reading_the_data() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 8, 0, 0).unwrap();
+    for i in 0..3 {
+        store
+            .insert(make_doc_with_fields(
+                "Login",
+                base + Duration::minutes(i),
+                vec![("memberCode", FieldValue::Text(format!("m{i}")))],
+            ))
+            .unwrap();
+    }
+    store.flush().unwrap();
+
+    let meta_path = data_dir.join("segments/2025-06-14/meta.json");
+    let raw = std::fs::read_to_string(&meta_path).unwrap();
+    std::fs::write(&meta_path, &raw[..raw.len() / 2]).unwrap();
+
+    let reader = stupid_segment::reader::SegmentReader::open(&data_dir, "2025-06-14").unwrap();
+    assert!(reader.meta().is_none());
+    assert_eq!(reader.iter().filter_map(Result::ok).count(), 3);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_segments_in_range_uses_recorded_time_range() {
+    let data_dir = test_data_dir();
+    let config = make_config(data_dir.clone(), 30);
+    let mut store = DocumentStore::new(&config).unwrap();
+
+    let morning = Utc.with_ymd_and_hms(2025, 6, 14, 8, 0, 0).unwrap();
+    store
+        .insert(make_doc_with_fields("Login", morning, vec![]))
+        .unwrap();
+    store
+        .insert(make_doc_with_fields("Login", morning + Duration::hours(2), vec![]))
+        .unwrap();
+    store.flush().unwrap();
+
+    let manager = store.manager();
+    // Same calendar day, but after the last document: excluded.
+    assert!(manager
+        .segments_in_range(Some(morning + Duration::hours(3)), None)
+        .is_empty());
+    // Overlapping the recorded range: included.
+    assert_eq!(
+        manager.segments_in_range(Some(morning + Duration::hours(1)), None),
+        vec!["2025-06-14"]
+    );
+    assert_eq!(
+        manager.segments_in_range(None, Some(morning)),
+        vec!["2025-06-14"]
+    );
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
@@ -0,0 +1,180 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, TimeZone, Utc};
+
+use stupid_core::{Document, FieldValue};
+use stupid_segment::reader::SegmentReader;
+use stupid_segment::writer::{RolloverPolicy, SegmentWriter, WriteMarker, WriteSource, WRITE_MARKER_FILE};
+
+use crate::helpers::{make_doc_with_fields, test_data_dir};
+
+fn docs(n: i64) -> Vec<Document> {
+    let base = Utc.with_ymd_and_hms(2025, 6, 14, 0, 0, 0).unwrap();
+    (0..n)
+        .map(|i| {
+            make_doc_with_fields(
+                "Login",
+                base + Duration::minutes(i),
+                vec![("memberCode", FieldValue::Text(format!("m{i}")))],
+            )
+        })
+        .collect()
+}
+
+/// The input file the test documents are read from.
+fn source() -> WriteSource {
+    WriteSource::default().add("Login/2025-06-14.parquet", 4096, "2025-06-15T00:00:00Z")
+}
+
+/// IDs of every document in the segment, across parts, in write order.
+fn read_ids(data_dir: &std::path::Path, segment_id: &str) -> Vec<uuid::Uuid> {
+    SegmentReader::part_ids(data_dir, segment_id)
+        .iter()
+        .flat_map(|part_id| {
+            let reader = SegmentReader::open(data_dir, part_id).unwrap();
+            reader.iter().map(|doc| doc.unwrap().id).collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn test_interrupted_import_resumes_without_duplicates() {
+    let data_dir = test_data_dir();
+    let input = docs(25);
+
+    // Interrupted after 23 docs: the writer is dropped without finalize.
+    let mut writer = SegmentWriter::resume(&data_dir, "Login/2025-W24", &source())
+        .unwrap()
+        .with_checkpoint_interval(10);
+    for doc in &input[..23] {
+        writer.append(doc).unwrap();
+    }
+    drop(writer);
+
+    let marker = SegmentWriter::unfinished(&data_dir, "Login/2025-W24").unwrap().unwrap();
+    assert_eq!(marker.committed_docs, 20);
+    assert_eq!(marker.parts, 0);
+
+    // Re-run over the same input, skipping what was committed.
+    let mut writer = SegmentWriter::resume(&data_dir, "Login/2025-W24", &source()).unwrap();
+    assert_eq!(writer.resumed_docs(), 20);
+    for doc in input.iter().skip(writer.resumed_docs() as usize) {
+        writer.append(doc).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let expected: Vec<_> = input.iter().map(|d| d.id).collect();
+    assert_eq!(read_ids(&data_dir, "Login/2025-W24"), expected);
+    let reader = SegmentReader::open(&data_dir, "Login/2025-W24").unwrap();
+    assert_eq!(reader.meta().unwrap().document_count, 25);
+    assert_eq!(SegmentWriter::unfinished(&data_dir, "Login/2025-W24").unwrap(), None);
+    assert!(!data_dir.join("segments/Login/2025-W24").join(WRITE_MARKER_FILE).exists());
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_resume_continues_in_the_rolled_over_part() {
+    let data_dir = test_data_dir();
+    let input = docs(30);
+    let rollover = RolloverPolicy::new(8, 0);
+
+    let mut writer = SegmentWriter::resume(&data_dir, "Login/2025-W24", &source())
+        .unwrap()
+        .with_rollover(rollover)
+        .with_checkpoint_interval(5);
+    for doc in &input[..19] {
+        writer.append(doc).unwrap();
+    }
+    drop(writer);
+
+    // Parts are checkpointed on rollover: base and part-001 hold 16 docs.
+    let marker = SegmentWriter::unfinished(&data_dir, "Login/2025-W24").unwrap().unwrap();
+    assert_eq!((marker.parts, marker.committed_docs), (2, 16));
+
+    let mut writer = SegmentWriter::resume(&data_dir, "Login/2025-W24", &source())
+        .unwrap()
+        .with_rollover(rollover)
+        .with_checkpoint_interval(5);
+    for doc in input.iter().skip(writer.resumed_docs() as usize) {
+        writer.append(doc).unwrap();
+    }
+    let part_ids = writer.part_ids();
+    writer.finalize().unwrap();
+
+    let ids = read_ids(&data_dir, "Login/2025-W24");
+    assert_eq!(ids.len(), 30);
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), 30);
+    assert_eq!(SegmentReader::part_ids(&data_dir, "Login/2025-W24"), part_ids);
+    for part_id in &part_ids {
+        let count = SegmentReader::open(&data_dir, part_id).unwrap().meta().unwrap().document_count;
+        assert!(count <= 8, "{part_id} has {count} docs");
+    }
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_new_overwrites_unfinished_write() {
+    let data_dir = test_data_dir();
+    let input = docs(12);
+
+    let mut writer = SegmentWriter::new(&data_dir, "Login/2025-W24")
+        .unwrap()
+        .with_checkpoint_interval(5);
+    for doc in &input {
+        writer.append(doc).unwrap();
+    }
+    drop(writer);
+    assert!(matches!(
+        SegmentWriter::unfinished(&data_dir, "Login/2025-W24").unwrap(),
+        Some(WriteMarker { committed_docs: 10, .. })
+    ));
+
+    let mut writer = SegmentWriter::new(&data_dir, "Login/2025-W24").unwrap();
+    for doc in &input[..3] {
+        writer.append(doc).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    assert_eq!(read_ids(&data_dir, "Login/2025-W24").len(), 3);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
+
+#[test]
+fn test_resume_with_different_input_starts_over() {
+    let data_dir = test_data_dir();
+    let first = docs(12);
+
+    let mut writer = SegmentWriter::resume(&data_dir, "Login/2025-W24", &source())
+        .unwrap()
+        .with_checkpoint_interval(5);
+    for doc in &first {
+        writer.append(doc).unwrap();
+    }
+    drop(writer);
+
+    // A corrected file: same name, different size and modification time.
+    let corrected = docs(7);
+    let changed = WriteSource::default().add("Login/2025-06-14.parquet", 2048, "2025-06-16T00:00:00Z");
+    let mut writer = SegmentWriter::resume(&data_dir, "Login/2025-W24", &changed).unwrap();
+    assert_eq!(writer.resumed_docs(), 0);
+    for doc in corrected.iter().skip(writer.resumed_docs() as usize) {
+        writer.append(doc).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let expected: Vec<_> = corrected.iter().map(|d| d.id).collect();
+    assert_eq!(read_ids(&data_dir, "Login/2025-W24"), expected);
+
+    // A write started with `new` records no source, so it is never resumed.
+    let mut writer = SegmentWriter::new(&data_dir, "Login/2025-W25").unwrap().with_checkpoint_interval(5);
+    for doc in &first {
+        writer.append(doc).unwrap();
+    }
+    drop(writer);
+    assert_eq!(SegmentWriter::resume(&data_dir, "Login/2025-W25", &source()).unwrap().resumed_docs(), 0);
+
+    std::fs::remove_dir_all(&data_dir).ok();
+}
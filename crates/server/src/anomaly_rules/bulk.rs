@@ -0,0 +1,243 @@
+//! Bulk enable/disable/delete of anomaly rules selected by ID or tag.
+
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::Json;
+use tracing::{info, warn};
+
+use stupid_rules::loader::RuleLoader;
+use stupid_rules::schema::AnomalyRule;
+
+use crate::state::AppState;
+
+use super::types::{BulkItemResult, BulkOperation, BulkRequest, BulkResult, BulkSelector};
+
+/// Apply an operation to every anomaly rule matched by a selector.
+///
+/// Each rule is updated independently: a failure on one rule doesn't stop
+/// the others, and the response reports the outcome per rule. Listed IDs
+/// that don't exist are reported as failures. An empty selector is
+/// rejected so a malformed request can't touch every rule.
+#[utoipa::path(
+    post,
+    path = "/anomaly-rules/bulk",
+    tag = "Anomaly Rules",
+    request_body = BulkRequest,
+    responses(
+        (status = 200, description = "Per-rule results of the bulk operation", body = BulkResult),
+        (status = 400, description = "Invalid or empty selector", body = String)
+    )
+)]
+pub(crate) async fn bulk_anomaly_rules(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<BulkRequest>,
+) -> Result<Json<BulkResult>, (StatusCode, String)> {
+    let result = apply_bulk(&state.rule_loader, &req)
+        .map_err(|msg| (StatusCode::BAD_REQUEST, msg))?;
+
+    if req.operation == BulkOperation::Delete {
+        for item in result.results.iter().filter(|r| r.success) {
+            state.rule_run_log.clear(&item.rule_id);
+        }
+    }
+
+    info!(
+        operation = ?req.operation,
+        succeeded = result.succeeded,
+        failed = result.failed,
+        "Bulk anomaly rule operation"
+    );
+    Ok(Json(result))
+}
+
+/// Validate `selector`, rejecting one that selects nothing explicitly.
+fn validate_selector(selector: &BulkSelector) -> Result<(), String> {
+    if selector.ids.is_empty() && selector.tags.is_empty() {
+        return Err("Selector must list at least one rule ID or tag".to_string());
+    }
+    if selector.ids.iter().any(|id| id.trim().is_empty()) {
+        return Err("Selector IDs must not be empty".to_string());
+    }
+    if selector.tags.iter().any(|tag| tag.trim().is_empty()) {
+        return Err("Selector tags must not be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Whether `rule` carries every tag in `tags`.
+fn has_tags(rule: &AnomalyRule, tags: &[String]) -> bool {
+    let rule_tags = rule.metadata.tags.as_deref().unwrap_or_default();
+    tags.iter().all(|tag| rule_tags.contains(tag))
+}
+
+/// Apply `req` through `loader`. `Err` means the selector was invalid and
+/// nothing was changed.
+pub(crate) fn apply_bulk(loader: &RuleLoader, req: &BulkRequest) -> Result<BulkResult, String> {
+    let selector = &req.selector;
+    validate_selector(selector)?;
+
+    // Resolve the selection up front so the rules lock isn't held while
+    // writing files (which re-locks the map).
+    let selected: Vec<(String, Option<AnomalyRule>)> = {
+        let rules = loader.rules();
+        let guard = rules.read().expect("rules lock poisoned");
+        if selector.ids.is_empty() {
+            let mut matched: Vec<_> = guard
+                .values()
+                .filter(|r| has_tags(r, &selector.tags))
+                .map(|r| (r.metadata.id.clone(), Some(r.clone())))
+                .collect();
+            matched.sort_by(|a, b| a.0.cmp(&b.0));
+            matched
+        } else {
+            let mut ids = selector.ids.clone();
+            ids.sort();
+            ids.dedup();
+            ids.into_iter()
+                .filter_map(|id| match guard.get(&id) {
+                    Some(rule) if has_tags(rule, &selector.tags) => Some((id, Some(rule.clone()))),
+                    Some(_) => None,
+                    None => Some((id, None)),
+                })
+                .collect()
+        }
+    };
+
+    let results: Vec<BulkItemResult> = selected
+        .into_iter()
+        .map(|(rule_id, rule)| {
+            let outcome = match rule {
+                None => Err(format!("Rule '{}' not found", rule_id)),
+                Some(rule) => apply_one(loader, req.operation, rule),
+            };
+            if let Err(e) = &outcome {
+                warn!(rule_id = %rule_id, error = %e, "Bulk anomaly rule operation failed");
+            }
+            BulkItemResult { rule_id, success: outcome.is_ok(), error: outcome.err() }
+        })
+        .collect();
+
+    let succeeded = results.iter().filter(|r| r.success).count();
+    Ok(BulkResult {
+        operation: req.operation,
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    })
+}
+
+fn apply_one(loader: &RuleLoader, operation: BulkOperation, mut rule: AnomalyRule) -> Result<(), String> {
+    match operation {
+        BulkOperation::Enable | BulkOperation::Disable => {
+            rule.metadata.enabled = operation == BulkOperation::Enable;
+            loader
+                .write_rule(&rule)
+                .map(|_| ())
+                .map_err(|e| format!("Failed to persist rule: {}", e))
+        }
+        BulkOperation::Delete => loader
+            .delete_rule(&rule.metadata.id)
+            .map_err(|e| format!("Failed to delete rule: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_yaml(id: &str, enabled: bool, tags: &[&str]) -> String {
+        format!(
+            r#"apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: {id}
+  name: {id}
+  enabled: {enabled}
+  tags: [{tags}]
+schedule:
+  cron: "*/15 * * * *"
+detection:
+  template: spike
+  params:
+    feature: login_count
+    multiplier: 3.0
+"#,
+            tags = tags.join(", ")
+        )
+    }
+
+    fn loader_with(rules: &[(&str, bool, &[&str])]) -> (tempfile::TempDir, RuleLoader) {
+        let dir = tempfile::tempdir().unwrap();
+        for (id, enabled, tags) in rules {
+            std::fs::write(dir.path().join(format!("{id}.yml")), rule_yaml(id, *enabled, tags)).unwrap();
+        }
+        let loader = RuleLoader::new(dir.path().to_path_buf());
+        loader.load_all().unwrap();
+        (dir, loader)
+    }
+
+    fn enabled(loader: &RuleLoader, id: &str) -> bool {
+        loader.rules().read().unwrap()[id].metadata.enabled
+    }
+
+    fn request(operation: BulkOperation, ids: &[&str], tags: &[&str]) -> BulkRequest {
+        BulkRequest {
+            operation,
+            selector: BulkSelector {
+                ids: ids.iter().map(|s| s.to_string()).collect(),
+                tags: tags.iter().map(|s| s.to_string()).collect(),
+            },
+        }
+    }
+
+    #[test]
+    fn enable_by_tag() {
+        let (_dir, loader) = loader_with(&[
+            ("fraud-a", false, &["team:fraud", "env:prod"]),
+            ("fraud-b", false, &["team:fraud"]),
+            ("ops-a", false, &["team:ops", "env:prod"]),
+        ]);
+
+        let result = apply_bulk(&loader, &request(BulkOperation::Enable, &[], &["team:fraud"])).unwrap();
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.failed, 0);
+        let ids: Vec<_> = result.results.iter().map(|r| r.rule_id.as_str()).collect();
+        assert_eq!(ids, ["fraud-a", "fraud-b"]);
+        assert!(enabled(&loader, "fraud-a") && enabled(&loader, "fraud-b"));
+        assert!(!enabled(&loader, "ops-a"));
+
+        // Multiple tags must all match.
+        let result =
+            apply_bulk(&loader, &request(BulkOperation::Disable, &[], &["team:fraud", "env:prod"])).unwrap();
+        assert_eq!(result.succeeded, 1);
+        assert!(!enabled(&loader, "fraud-a"));
+        assert!(enabled(&loader, "fraud-b"));
+    }
+
+    #[test]
+    fn reports_partial_failure() {
+        let (dir, loader) = loader_with(&[("rule-a", true, &[]), ("rule-b", true, &[])]);
+
+        let result =
+            apply_bulk(&loader, &request(BulkOperation::Delete, &["rule-a", "missing", "rule-b"], &[])).unwrap();
+        assert_eq!(result.succeeded, 2);
+        assert_eq!(result.failed, 1);
+        let missing = result.results.iter().find(|r| r.rule_id == "missing").unwrap();
+        assert!(!missing.success);
+        assert!(missing.error.as_deref().unwrap().contains("not found"));
+        assert!(!dir.path().join("rule-a.yml").exists());
+        assert!(loader.rules().read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_empty_selector() {
+        let (_dir, loader) = loader_with(&[("rule-a", true, &[])]);
+
+        assert!(apply_bulk(&loader, &request(BulkOperation::Delete, &[], &[])).is_err());
+        assert!(apply_bulk(&loader, &request(BulkOperation::Delete, &[""], &[])).is_err());
+        assert!(apply_bulk(&loader, &request(BulkOperation::Delete, &[], &[" "])).is_err());
+        assert!(loader.rules().read().unwrap().contains_key("rule-a"));
+    }
+}
@@ -157,7 +157,7 @@ pub(crate) async fn update_anomaly_rule(
     Ok(Json(rule))
 }
 
-/// Delete an anomaly rule by ID.
+/// Delete an anomaly rule by ID, along with its run log.
 #[utoipa::path(
     delete,
     path = "/anomaly-rules/{id}",
@@ -176,7 +176,10 @@ pub(crate) async fn delete_anomaly_rule(
     Path(id): Path<String>,
 ) -> StatusCode {
     match state.rule_loader.delete_rule(&id) {
-        Ok(()) => StatusCode::NO_CONTENT,
+        Ok(()) => {
+            state.rule_run_log.clear(&id);
+            StatusCode::NO_CONTENT
+        }
         Err(e) => {
             // Validation error means not found; anything else is internal.
             if matches!(e, stupid_rules::loader::RuleError::Validation(_)) {
@@ -1,5 +1,5 @@
 //! Lifecycle endpoints for anomaly rules: start, pause, run, test-notify,
-//! history, and audit logs.
+//! history, audit logs, run logs, and trigger escalations.
 
 use std::sync::Arc;
 
@@ -8,9 +8,12 @@ use axum::http::StatusCode;
 use axum::Json;
 use tracing::warn;
 
-use stupid_rules::audit_log::{LogEntry, LogQueryParams};
+use stupid_rules::audit_log::{ExecutionPhase, LogEntry, LogLevel, LogQueryParams};
 use stupid_rules::schema::AnomalyRule;
 
+use crate::rule_actions::RunOutcome;
+use crate::rule_escalation::EscalationRecord;
+use crate::rule_run_log::{RuleRunRecord, RunLogPage, RunLogParams, RunTrigger};
 use crate::state::AppState;
 
 use super::types::{
@@ -93,7 +96,13 @@ fn toggle_rule_enabled(
 ///
 /// Builds entity data and signal scores from the compute pipeline,
 /// evaluates the rule via `RuleEvaluator`, and records the trigger
-/// in history.
+/// in history. Matches are then enriched and notified like a scheduled
+/// trigger; the response reports the enrichment result and each channel's
+/// delivery outcome. Every run is recorded in the rule's run log.
+///
+/// Running a live rule also evaluates its shadow candidates and logs the
+/// match diffs. A shadow candidate run directly is evaluated but never
+/// enriched or notified.
 #[utoipa::path(
     post,
     path = "/anomaly-rules/{id}/run",
@@ -110,58 +119,75 @@ pub(crate) async fn run_anomaly_rule(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<Json<RunResult>, (StatusCode, String)> {
-    let rules = state.rule_loader.rules();
-    let guard = rules.read().expect("rules lock poisoned");
-    if !guard.contains_key(&id) {
-        return Err((StatusCode::NOT_FOUND, format!("Rule '{}' not found", id)));
-    }
-    drop(guard);
+    let rule = {
+        let rules = state.rule_loader.rules();
+        let guard = rules.read().expect("rules lock poisoned");
+        guard
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| (StatusCode::NOT_FOUND, format!("Rule '{}' not found", id)))?
+    };
 
+    let started_at = chrono::Utc::now();
     let start = std::time::Instant::now();
 
-    let rules = state.rule_loader.rules();
-    let guard = rules.read().expect("rules lock poisoned");
-    let rule = guard.get(&id).cloned().unwrap();
-    drop(guard);
-
     let (entities, cluster_stats, signal_scores) =
         crate::rule_runner::build_evaluation_context(&state);
+    let rules = state.rule_loader.rules();
 
-    let (matches_found, match_summaries) =
-        match stupid_rules::evaluator::RuleEvaluator::evaluate(
+    let matches = match stupid_rules::evaluator::RuleEvaluator::evaluate(
+        &rule,
+        &entities,
+        &cluster_stats,
+        &signal_scores,
+    ) {
+        Ok(mut matches) => {
+            matches.sort_by(|a, b| {
+                b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
+            });
+            matches
+        }
+        Err(e) => {
+            let evaluation_ms = start.elapsed().as_millis() as u64;
+            let mut record = RuleRunRecord::new(&id, RunTrigger::Manual, started_at, evaluation_ms);
+            record.error = Some(e.to_string());
+            state.rule_run_log.append(record);
+            return Ok(Json(RunResult {
+                rule_id: id,
+                matches_found: 0,
+                evaluation_ms,
+                message: format!("Evaluation error: {}", e),
+                enrichment: None,
+                notifications: Vec::new(),
+                volume_capped: false,
+                escalation: None,
+            }));
+        }
+    };
+    {
+        let guard = rules.read().expect("rules lock poisoned");
+        stupid_rules::shadow::evaluate_shadows(
+            &state.audit_log,
             &rule,
+            &matches,
+            &guard,
             &entities,
             &cluster_stats,
             &signal_scores,
-        ) {
-            Ok(mut matches) => {
-                let count = matches.len();
-                // Sort by score descending and keep top 50 for history.
-                matches.sort_by(|a, b| {
-                    b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
-                });
-                let summaries: Vec<MatchSummary> = matches
-                    .iter()
-                    .take(50)
-                    .map(|m| MatchSummary {
-                        entity_key: m.entity_key.clone(),
-                        entity_type: m.entity_type.clone(),
-                        score: m.score,
-                        reason: m.matched_reason.clone(),
-                    })
-                    .collect();
-                (count, summaries)
-            }
-            Err(e) => {
-                let evaluation_ms = start.elapsed().as_millis() as u64;
-                return Ok(Json(RunResult {
-                    rule_id: id,
-                    matches_found: 0,
-                    evaluation_ms,
-                    message: format!("Evaluation error: {}", e),
-                }));
-            }
-        };
+        );
+    }
+    let matches_found = matches.len();
+    // Keep the top 50 for history.
+    let match_summaries: Vec<MatchSummary> = matches
+        .iter()
+        .take(50)
+        .map(|m| MatchSummary {
+            entity_key: m.entity_key.clone(),
+            entity_type: m.entity_type.clone(),
+            score: m.score,
+            reason: m.matched_reason.clone(),
+        })
+        .collect();
 
     let evaluation_ms = start.elapsed().as_millis() as u64;
 
@@ -183,11 +209,62 @@ pub(crate) async fn run_anomaly_rule(
         }
     }
 
+    let outcome = if rule.shadow_of.is_some() {
+        RunOutcome {
+            enrichment: None,
+            notifications: Vec::new(),
+            volume_capped: false,
+            escalation: None,
+            suppressed: 0,
+        }
+    } else {
+        state.rule_actions.run(&rule, &matches).await
+    };
+    if outcome.volume_capped {
+        state.audit_log.log_with_details(
+            &id,
+            LogLevel::Warning,
+            ExecutionPhase::Notification,
+            format!("Volume capped: {} matches exceed max_notify_entities, sent summary only", matches_found),
+            Some(serde_json::json!({
+                "volume_capped": true,
+                "matches_found": matches_found,
+                "max_notify_entities": rule.max_notify_entities,
+            })),
+            None,
+        );
+    }
+
+    let mut record = RuleRunRecord::new(&id, RunTrigger::Manual, started_at, evaluation_ms);
+    record.matches_found = matches_found;
+    record.enrichment = outcome.enrichment.clone();
+    record.notifications = outcome.notifications.clone();
+    record.volume_capped = outcome.volume_capped;
+    record.matches_suppressed = outcome.suppressed;
+    state.rule_run_log.append(record);
+
+    let notifications = outcome.notifications;
+    let delivered = notifications.iter().filter(|n| n.success).count();
+    let message = if notifications.is_empty() {
+        format!("{} entities matched", matches_found)
+    } else {
+        format!(
+            "{} entities matched, {} of {} notifications delivered",
+            matches_found,
+            delivered,
+            notifications.len()
+        )
+    };
+
     Ok(Json(RunResult {
         rule_id: id,
         matches_found,
         evaluation_ms,
-        message: format!("{} entities matched", matches_found),
+        message,
+        enrichment: outcome.enrichment,
+        notifications,
+        volume_capped: outcome.volume_capped,
+        escalation: outcome.escalation,
     }))
 }
 
@@ -321,3 +398,104 @@ pub(crate) async fn rule_logs(
     let entries = state.audit_log.query(&id, &params);
     Ok(Json(entries))
 }
+
+/// Get the structured run log for a rule.
+///
+/// Each record covers one evaluation, scheduled or manual: when it ran, how
+/// many entities matched, the enrichment outcome, each notification's
+/// delivery result, and the error if evaluation failed. Runs are returned
+/// newest first, filtered by `since` / `until` / `matched` / `errors_only`
+/// and paginated with `offset` and `limit`.
+#[utoipa::path(
+    get,
+    path = "/anomaly-rules/{id}/runs",
+    tag = "Anomaly Rules",
+    params(
+        ("id" = String, Path, description = "Anomaly rule ID"),
+        RunLogParams
+    ),
+    responses(
+        (status = 200, description = "Page of run log records", body = RunLogPage),
+        (status = 404, description = "Rule not found", body = String)
+    )
+)]
+pub(crate) async fn rule_runs(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Query(params): Query<RunLogParams>,
+) -> Result<Json<RunLogPage>, (StatusCode, String)> {
+    // Verify rule exists.
+    {
+        let rules = state.rule_loader.rules();
+        let guard = rules.read().expect("rules lock poisoned");
+        if !guard.contains_key(&id) {
+            return Err((StatusCode::NOT_FOUND, format!("Rule '{}' not found", id)));
+        }
+    }
+
+    Ok(Json(state.rule_run_log.query(&id, &params)))
+}
+
+/// Acknowledge a trigger, cancelling its pending escalation.
+///
+/// The trigger ID is in the `trigger_id` metadata of every notification a
+/// rule with an escalation policy sends, and in the run result. Acknowledging
+/// after the trigger escalated records the acknowledgement but cannot undo
+/// the escalation.
+#[utoipa::path(
+    post,
+    path = "/anomaly-rules/{id}/triggers/{trigger_id}/ack",
+    tag = "Anomaly Rules",
+    params(
+        ("id" = String, Path, description = "Anomaly rule ID"),
+        ("trigger_id" = String, Path, description = "Trigger ID from the notification")
+    ),
+    responses(
+        (status = 200, description = "Trigger acknowledged", body = EscalationRecord),
+        (status = 404, description = "Trigger not found", body = String)
+    )
+)]
+pub(crate) async fn acknowledge_trigger(
+    State(state): State<Arc<AppState>>,
+    Path((id, trigger_id)): Path<(String, String)>,
+) -> Result<Json<EscalationRecord>, (StatusCode, String)> {
+    let record = state.rule_actions.escalations().acknowledge(&id, &trigger_id).ok_or_else(|| {
+        (StatusCode::NOT_FOUND, format!("Trigger '{}' of rule '{}' not found", trigger_id, id))
+    })?;
+    state.audit_log.log(
+        &id,
+        LogLevel::Info,
+        ExecutionPhase::Notification,
+        format!("Trigger {} acknowledged", trigger_id),
+    );
+    Ok(Json(record))
+}
+
+/// List a rule's trigger escalations, newest first: pending ones awaiting
+/// acknowledgement, acknowledged ones, and escalated ones with the delivery
+/// outcome on each escalation channel.
+#[utoipa::path(
+    get,
+    path = "/anomaly-rules/{id}/escalations",
+    tag = "Anomaly Rules",
+    params(
+        ("id" = String, Path, description = "Anomaly rule ID")
+    ),
+    responses(
+        (status = 200, description = "Trigger escalations", body = Vec<EscalationRecord>),
+        (status = 404, description = "Rule not found", body = String)
+    )
+)]
+pub(crate) async fn rule_escalations(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<EscalationRecord>>, (StatusCode, String)> {
+    {
+        let rules = state.rule_loader.rules();
+        let guard = rules.read().expect("rules lock poisoned");
+        if !guard.contains_key(&id) {
+            return Err((StatusCode::NOT_FOUND, format!("Rule '{}' not found", id)));
+        }
+    }
+    Ok(Json(state.rule_actions.escalations().for_rule(&id)))
+}
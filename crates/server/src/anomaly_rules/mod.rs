@@ -2,8 +2,10 @@
 //!
 //! Provides REST endpoints for managing anomaly detection rules stored as
 //! YAML files on disk via [`stupid_rules::loader::RuleLoader`], plus
-//! lifecycle operations (start, pause, run, test-notify, history).
+//! lifecycle operations (start, pause, run, test-notify, history, logs,
+//! trigger acknowledgement) and bulk enable/disable/delete by ID or tag.
 
+mod bulk;
 mod crud;
 mod lifecycle;
 mod types;
@@ -15,6 +17,7 @@ use axum::Router;
 
 use crate::state::AppState;
 
+pub use self::bulk::*;
 pub use self::crud::*;
 pub use self::lifecycle::*;
 pub use self::types::*;
@@ -26,6 +29,7 @@ pub use self::types::*;
 pub fn anomaly_rules_router() -> Router<Arc<AppState>> {
     Router::new()
         .route("/anomaly-rules", get(list_anomaly_rules).post(create_anomaly_rule))
+        .route("/anomaly-rules/bulk", post(bulk_anomaly_rules))
         .route(
             "/anomaly-rules/{id}",
             get(get_anomaly_rule)
@@ -38,4 +42,7 @@ pub fn anomaly_rules_router() -> Router<Arc<AppState>> {
         .route("/anomaly-rules/{id}/test-notify", post(test_notify_rule))
         .route("/anomaly-rules/{id}/history", get(rule_history))
         .route("/anomaly-rules/{id}/logs", get(rule_logs))
+        .route("/anomaly-rules/{id}/runs", get(rule_runs))
+        .route("/anomaly-rules/{id}/escalations", get(rule_escalations))
+        .route("/anomaly-rules/{id}/triggers/{trigger_id}/ack", post(acknowledge_trigger))
 }
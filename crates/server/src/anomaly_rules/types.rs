@@ -61,6 +61,15 @@ pub struct RunResult {
     pub matches_found: usize,
     pub evaluation_ms: u64,
     pub message: String,
+    /// Enrichment of the matches; absent when the rule has no enrichment step.
+    pub enrichment: Option<crate::rule_actions::EnrichmentOutcome>,
+    /// Per-channel delivery of each notified match.
+    pub notifications: Vec<crate::rule_actions::DispatchOutcome>,
+    /// More matches than `max_notify_entities`; a single summary was sent.
+    pub volume_capped: bool,
+    /// Pending escalation of this trigger; acknowledge it by `trigger_id`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escalation: Option<crate::rule_escalation::EscalationRecord>,
 }
 
 /// Result of a test notification dispatch via `POST /anomaly-rules/{id}/test-notify`.
@@ -100,3 +109,50 @@ pub struct HistoryParams {
 
 /// Shared trigger history map: rule_id -> deque of recent trigger entries.
 pub type SharedTriggerHistory = Arc<RwLock<HashMap<String, VecDeque<TriggerEntry>>>>;
+
+/// Operation applied by `POST /anomaly-rules/bulk`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BulkOperation {
+    Enable,
+    Disable,
+    Delete,
+}
+
+/// Which rules a bulk operation applies to.
+///
+/// `ids` names rules directly; `tags` selects rules carrying every listed
+/// tag. When both are given, only listed rules that carry the tags are
+/// selected. At least one must be non-empty.
+#[derive(Debug, Default, Deserialize, utoipa::ToSchema)]
+pub struct BulkSelector {
+    #[serde(default)]
+    pub ids: Vec<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Request body for `POST /anomaly-rules/bulk`.
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+pub struct BulkRequest {
+    pub operation: BulkOperation,
+    pub selector: BulkSelector,
+}
+
+/// Outcome of a bulk operation on one rule.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct BulkItemResult {
+    pub rule_id: String,
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response of `POST /anomaly-rules/bulk`: one result per selected rule.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct BulkResult {
+    pub operation: BulkOperation,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub results: Vec<BulkItemResult>,
+}
@@ -41,7 +41,7 @@ pub struct AgentMemberRequest {
 /// List all agent groups
 #[utoipa::path(
     get,
-    path = "/agent-groups",
+    path = "/api/agent-groups",
     tag = "Agent Groups",
     responses(
         (status = 200, description = "List of agent groups", body = Object),
@@ -65,7 +65,7 @@ pub async fn agent_groups_list(
 /// Create a new agent group
 #[utoipa::path(
     post,
-    path = "/agent-groups",
+    path = "/api/agent-groups",
     tag = "Agent Groups",
     request_body = CreateGroupRequest,
     responses(
@@ -100,7 +100,7 @@ pub async fn agent_groups_create(
 /// Update an agent group
 #[utoipa::path(
     put,
-    path = "/agent-groups/{name}",
+    path = "/api/agent-groups/{name}",
     tag = "Agent Groups",
     params(
         ("name" = String, Path, description = "Group name")
@@ -138,7 +138,7 @@ pub async fn agent_groups_update(
 /// Delete an agent group
 #[utoipa::path(
     delete,
-    path = "/agent-groups/{name}",
+    path = "/api/agent-groups/{name}",
     tag = "Agent Groups",
     params(
         ("name" = String, Path, description = "Group name")
@@ -174,7 +174,7 @@ pub async fn agent_groups_delete(
 /// Add an agent to a group
 #[utoipa::path(
     post,
-    path = "/agent-groups/{name}/agents",
+    path = "/api/agent-groups/{name}/agents",
     tag = "Agent Groups",
     params(
         ("name" = String, Path, description = "Group name")
@@ -212,7 +212,7 @@ pub async fn agent_groups_add_agent(
 /// Remove an agent from a group
 #[utoipa::path(
     delete,
-    path = "/agent-groups/{group_name}/{agent_name}",
+    path = "/api/agent-groups/{group_name}/{agent_name}",
     tag = "Agent Groups",
     params(
         ("group_name" = String, Path, description = "Group name"),
@@ -0,0 +1,118 @@
+//! Tool call approval endpoints for the session agentic loop.
+
+use std::sync::Arc;
+
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Deserialize;
+
+use stupid_tool_runtime::PermissionDecision;
+
+use crate::state::AppState;
+use crate::tool_approvals::PendingApproval;
+
+use super::super::QueryErrorResponse;
+
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct ToolApprovalRequest {
+    pub approved: bool,
+    /// Why the call was denied, passed back to the model.
+    pub reason: Option<String>,
+}
+
+/// List tool calls waiting for approval
+///
+/// Calls to tools in `LLM_CONFIRM_TOOLS` wait here, oldest first, until
+/// approved, denied, or timed out.
+#[utoipa::path(
+    get,
+    path = "/tool-approvals",
+    tag = "Sessions",
+    responses(
+        (status = 200, description = "Tool calls awaiting approval", body = Vec<PendingApproval>)
+    )
+)]
+pub async fn tool_approvals_list(State(state): State<Arc<AppState>>) -> Json<Vec<PendingApproval>> {
+    Json(state.tool_approvals.list())
+}
+
+/// Approve or deny a waiting tool call
+#[utoipa::path(
+    post,
+    path = "/tool-approvals/{call_id}",
+    tag = "Sessions",
+    params(
+        ("call_id" = String, Path, description = "Call ID from the stream's tool_call_started event")
+    ),
+    request_body = ToolApprovalRequest,
+    responses(
+        (status = 204, description = "Decision delivered to the waiting call"),
+        (status = 404, description = "No call with that ID is waiting", body = QueryErrorResponse)
+    )
+)]
+pub async fn tool_approvals_decide(
+    State(state): State<Arc<AppState>>,
+    Path(call_id): Path<String>,
+    Json(req): Json<ToolApprovalRequest>,
+) -> Result<StatusCode, (StatusCode, Json<QueryErrorResponse>)> {
+    let decision = if req.approved {
+        PermissionDecision::Approved
+    } else {
+        PermissionDecision::Denied(req.reason.unwrap_or_else(|| "Denied by user".to_string()))
+    };
+    if !state.tool_approvals.decide(&call_id, decision) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(QueryErrorResponse {
+                error: format!("No tool call '{}' is waiting for approval", call_id),
+            }),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    use stupid_tool_runtime::{Approver, PermissionDecision, ToolCall};
+
+    #[tokio::test]
+    async fn test_decision_endpoint_answers_pending_call() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state.clone());
+
+        let approvals = state.clone();
+        let waiting = tokio::spawn(async move {
+            let call = ToolCall {
+                id: "toolu_9".to_string(),
+                name: "file_write".to_string(),
+                input: serde_json::json!({"path": "notes.md"}),
+            };
+            approvals.tool_approvals.request_approval("call_9", &call).await
+        });
+        while state.tool_approvals.list().is_empty() {
+            tokio::task::yield_now().await;
+        }
+
+        let response = app.clone().oneshot(Request::get("/tool-approvals").body(Body::empty()).unwrap()).await.unwrap();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let pending: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(pending[0]["tool_name"], "file_write");
+        assert_eq!(pending[0]["call_id"], "call_9");
+
+        let deny = |id: &str| {
+            Request::post(format!("/tool-approvals/{id}"))
+                .header("content-type", "application/json")
+                .body(Body::from(r#"{"approved":false,"reason":"wrong file"}"#))
+                .unwrap()
+        };
+        assert_eq!(app.clone().oneshot(deny("call_9")).await.unwrap().status(), StatusCode::NO_CONTENT);
+        assert_eq!(waiting.await.unwrap(), PermissionDecision::Denied("wrong file".to_string()));
+        assert_eq!(app.oneshot(deny("call_9")).await.unwrap().status(), StatusCode::NOT_FOUND);
+    }
+}
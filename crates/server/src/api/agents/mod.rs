@@ -3,6 +3,7 @@
 //!
 //! SRP: agent/team lifecycle and session management.
 
+mod approvals;
 mod crud;
 mod execute;
 mod overview;
@@ -15,6 +16,7 @@ mod types;
 // ── Re-exports ───────────────────────────────────────────────────
 // Preserves flat `agents::foo` import paths used by api/mod.rs.
 
+pub use approvals::*;
 pub use crud::*;
 pub use execute::*;
 pub use overview::*;
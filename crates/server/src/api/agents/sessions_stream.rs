@@ -11,6 +11,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use stupid_tool_runtime::conversation::{AssistantContent, ConversationMessage};
 use stupid_tool_runtime::stream::StreamEvent;
 use stupid_tool_runtime::tool::ToolContext;
+use stupid_tool_runtime::RunOutcome;
 
 use crate::state::AppState;
 
@@ -22,7 +23,8 @@ use super::types::SessionStreamRequest;
 /// Uses the AgenticLoop from AppState with tool-use support. Each StreamEvent
 /// is sent as a JSON SSE data line. After the stream completes, the assistant's
 /// response is persisted to the session. Event types: text_delta, tool_call,
-/// tool_result, error, done.
+/// tool_result, error, done. Calls to tools in `LLM_CONFIRM_TOOLS` report an
+/// `AwaitingApproval` stage and wait for `POST /tool-approvals/{call_id}`.
 #[utoipa::path(
     post,
     path = "/sessions/{id}/stream",
@@ -109,7 +111,7 @@ pub async fn sessions_stream(
         })?.map(|s| s.messages).unwrap_or_default()
     };
 
-    let mut conversation = stupid_tool_runtime::Conversation::new(8192);
+    let mut conversation = agentic_loop.new_conversation(8192);
 
     // Load system prompt: prefer explicit request prompt, fall back to
     // auto-discovered project context (CLAUDE.md + skills + rules + agents).
@@ -166,12 +168,16 @@ pub async fn sessions_stream(
     // Clone what we need for the background task
     let task = req.task.clone();
     let max_iterations = req.max_iterations;
+    let budget = req.budget.clone();
     let session_store = state.session_store.clone();
     let session_id = id.clone();
 
     // Spawn the agentic loop in a background task
     tokio::spawn(async move {
-        let loop_with_config = agentic_loop.with_max_iterations(max_iterations);
+        let mut loop_with_config = agentic_loop.with_max_iterations(max_iterations);
+        if let Some(budget) = budget {
+            loop_with_config = loop_with_config.with_budget(budget);
+        }
 
         let result = loop_with_config
             .run_streaming(&mut conversation, task, &tool_context, tx)
@@ -193,7 +199,14 @@ pub async fn sessions_stream(
             content: response_text,
             timestamp: chrono::Utc::now(),
             agent_name: Some("assistant".to_string()),
-            status: Some(if result.is_ok() { "completed" } else { "error" }.to_string()),
+            status: Some(
+                match &result {
+                    Ok(RunOutcome::Completed { .. }) => "completed",
+                    Ok(RunOutcome::BudgetExceeded { .. }) => "budget_exceeded",
+                    Err(_) => "error",
+                }
+                .to_string(),
+            ),
             execution_time_ms: None,
             team_outputs: None,
             agents_used: None,
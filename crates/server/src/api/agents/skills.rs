@@ -17,7 +17,7 @@ use super::super::QueryErrorResponse;
 
 // ── Query params ────────────────────────────────────────────────
 
-#[derive(Deserialize)]
+#[derive(Deserialize, utoipa::IntoParams)]
 pub struct SkillListParams {
     /// Optional search text to filter skills by name, description, or tags.
     pub search: Option<String>,
@@ -26,7 +26,7 @@ pub struct SkillListParams {
 // ── Response types ──────────────────────────────────────────────
 
 /// Compact skill info returned by the list endpoint.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SkillInfo {
     pub name: String,
     pub description: String,
@@ -35,7 +35,7 @@ pub struct SkillInfo {
 }
 
 /// Full skill detail with usage information.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SkillDetail {
     pub name: String,
     pub description: String,
@@ -46,7 +46,7 @@ pub struct SkillDetail {
 }
 
 /// Request body for creating or updating a skill.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct SkillRequest {
     pub name: String,
     pub description: Option<String>,
@@ -67,7 +67,7 @@ impl SkillRequest {
     }
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, utoipa::ToSchema)]
 pub struct SkillListResponse {
     skills: Vec<SkillInfo>,
     count: usize,
@@ -135,6 +135,16 @@ fn matches_search(skill: &SkillYamlConfig, query: &str) -> bool {
 ///
 /// Returns compact skill info for all standalone skills.
 /// Supports optional `?search=text` to filter by name, description, or tags.
+#[utoipa::path(
+    get,
+    path = "/api/bundeswehr/skills",
+    tag = "Bundeswehr",
+    params(SkillListParams),
+    responses(
+        (status = 200, description = "Matching skills", body = SkillListResponse),
+        (status = 503, description = "Skill store not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn skills_list(
     State(state): State<Arc<AppState>>,
     Query(params): Query<SkillListParams>,
@@ -158,6 +168,19 @@ pub async fn skills_list(
 /// Get a single skill by name
 ///
 /// Returns full skill detail including prompt and `used_by` agent list.
+#[utoipa::path(
+    get,
+    path = "/api/bundeswehr/skills/{name}",
+    tag = "Bundeswehr",
+    params(
+        ("name" = String, Path, description = "Skill name")
+    ),
+    responses(
+        (status = 200, description = "Skill detail", body = SkillDetail),
+        (status = 404, description = "Skill not found", body = QueryErrorResponse),
+        (status = 503, description = "Skill store not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn skills_get(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
@@ -180,6 +203,18 @@ pub async fn skills_get(
 /// Create a new skill
 ///
 /// Creates a new standalone skill and writes it to disk as a YAML file.
+#[utoipa::path(
+    post,
+    path = "/api/bundeswehr/skills",
+    tag = "Bundeswehr",
+    request_body = SkillRequest,
+    responses(
+        (status = 201, description = "Skill created", body = SkillDetail),
+        (status = 409, description = "Skill already exists", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "Skill store not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn skills_create(
     State(state): State<Arc<AppState>>,
     Json(req): Json<SkillRequest>,
@@ -206,6 +241,21 @@ pub async fn skills_create(
 /// Update an existing skill
 ///
 /// Updates the skill configuration and writes changes back to disk.
+#[utoipa::path(
+    put,
+    path = "/api/bundeswehr/skills/{name}",
+    tag = "Bundeswehr",
+    params(
+        ("name" = String, Path, description = "Skill name")
+    ),
+    request_body = SkillRequest,
+    responses(
+        (status = 200, description = "Skill updated", body = SkillDetail),
+        (status = 404, description = "Skill not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "Skill store not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn skills_update(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
@@ -237,6 +287,20 @@ pub async fn skills_update(
 /// Delete a skill
 ///
 /// Removes the skill from the store and deletes its YAML file from disk.
+#[utoipa::path(
+    delete,
+    path = "/api/bundeswehr/skills/{name}",
+    tag = "Bundeswehr",
+    params(
+        ("name" = String, Path, description = "Skill name")
+    ),
+    responses(
+        (status = 200, description = "Skill deleted", body = Object),
+        (status = 404, description = "Skill not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "Skill store not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn skills_delete(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
@@ -25,6 +25,7 @@ pub struct CreateAgentRequest {
 }
 
 #[derive(Deserialize, utoipa::ToSchema)]
+#[schema(as = AgentSkillRequest)]
 pub struct SkillRequest {
     pub name: String,
     pub prompt: String,
@@ -158,6 +159,10 @@ pub struct SessionStreamRequest {
     pub system_prompt: Option<String>,
     #[serde(default = "default_max_iterations")]
     pub max_iterations: usize,
+    /// Token or dollar limit; the run stops once it is used up.
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub budget: Option<stupid_tool_runtime::Budget>,
 }
 
 pub(super) fn default_max_iterations() -> usize {
@@ -194,6 +199,7 @@ pub(super) fn parse_execution_status(s: &str) -> stupid_agent::ExecutionStatus {
         "success" => stupid_agent::ExecutionStatus::Success,
         "timeout" => stupid_agent::ExecutionStatus::Timeout,
         "partial" => stupid_agent::ExecutionStatus::Partial,
+        "budget_exceeded" => stupid_agent::ExecutionStatus::BudgetExceeded,
         _ => stupid_agent::ExecutionStatus::Error,
     }
 }
@@ -14,6 +14,7 @@ pub use query_log::athena_connections_query_log;
 pub use query_parquet::athena_query_parquet;
 pub use query_sse::athena_query_sse;
 pub use schema::{athena_connections_schema, athena_connections_schema_refresh};
+pub(crate) use schema::start_schema_refresh;
 pub use types::AthenaQueryRequest;
 
 // Re-export utoipa-generated path types so `doc.rs` can reference them
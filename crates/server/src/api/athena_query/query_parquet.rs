@@ -6,6 +6,7 @@ use axum::extract::{Path, State};
 use axum::Json;
 
 use crate::credential_store::CredentialStore;
+use crate::query_audit::{PendingQuery, QueryClient};
 use crate::state::AppState;
 
 use super::helpers::eb_athena_error;
@@ -17,7 +18,8 @@ use crate::api::QueryErrorResponse;
 /// Uses the same query execution flow as the SSE endpoint but collects all
 /// results into memory and returns a single Parquet file with proper types
 /// and Zstd compression. The response includes Content-Disposition header
-/// for browser download.
+/// for browser download. Each call is recorded in the query audit trail
+/// (`GET /audit/queries`).
 #[utoipa::path(
     post,
     path = "/athena-connections/{id}/query/parquet",
@@ -31,11 +33,33 @@ use crate::api::QueryErrorResponse;
 )]
 pub async fn athena_query_parquet(
     State(state): State<Arc<AppState>>,
+    client: QueryClient,
     Path(id): Path<String>,
     Json(req): Json<AthenaQueryRequest>,
 ) -> Result<
     axum::response::Response,
     (axum::http::StatusCode, Json<QueryErrorResponse>),
+> {
+    let audit = PendingQuery::start("/athena-connections/{id}/query/parquet", &client, req.sql.clone())
+        .with_connection(&id);
+    let result = run_query_parquet(&state, id, req).await;
+    let outcome = match &result {
+        Ok((_, rows)) => Ok(*rows),
+        Err((_, Json(e))) => Err(e.error.clone()),
+    };
+    state.query_audit.append(audit.finish(outcome));
+    result.map(|(response, _)| response)
+}
+
+/// Run the query and build the download, along with the row count when
+/// the rows passed through this server.
+async fn run_query_parquet(
+    state: &AppState,
+    id: String,
+    req: AthenaQueryRequest,
+) -> Result<
+    (axum::response::Response, Option<u64>),
+    (axum::http::StatusCode, Json<QueryErrorResponse>),
 > {
     // Route through eisenbahn if available.
     if let Some(ref eb) = state.eisenbahn {
@@ -86,13 +110,14 @@ pub async fn athena_query_parquet(
         })?;
 
         let filename = format!("{}.parquet", uuid::Uuid::new_v4());
-        return Ok(axum::response::Response::builder()
+        let response = axum::response::Response::builder()
             .status(200)
             .header("Content-Type", "application/vnd.apache.parquet")
             .header("Content-Disposition", format!("attachment; filename=\"{filename}\""))
             .header("Content-Length", bytes.len().to_string())
             .body(axum::body::Body::from(bytes))
-            .unwrap());
+            .unwrap();
+        return Ok((response, None));
     }
 
     // 1. Get credentials and connection config.
@@ -198,7 +223,8 @@ pub async fn athena_query_parquet(
     }
 
     // 6. Return as downloadable file.
-    Ok(axum::response::Response::builder()
+    let row_count = athena_result.rows.len() as u64;
+    let response = axum::response::Response::builder()
         .status(200)
         .header("Content-Type", "application/vnd.apache.parquet")
         .header(
@@ -207,5 +233,6 @@ pub async fn athena_query_parquet(
         )
         .header("Content-Length", parquet_bytes.len().to_string())
         .body(axum::body::Body::from(parquet_bytes))
-        .unwrap())
+        .unwrap();
+    Ok((response, Some(row_count)))
 }
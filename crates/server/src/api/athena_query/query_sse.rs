@@ -9,6 +9,8 @@ use axum::Json;
 use tokio_stream::wrappers::ReceiverStream;
 
 use crate::credential_store::CredentialStore;
+use crate::athena_query_log::QueryOutcome;
+use crate::query_audit::{PendingQuery, QueryClient};
 use crate::state::AppState;
 
 use super::helpers::{athena_response_to_sse, eb_athena_error};
@@ -26,6 +28,9 @@ use crate::api::QueryErrorResponse;
 /// - `rows`    -- batches of up to 100 result rows
 /// - `done`    -- final summary (total_rows, data_scanned_bytes, execution_time_ms)
 /// - `error`   -- terminal error with message
+///
+/// Each call is recorded in the query audit trail (`GET /audit/queries`)
+/// once the query reaches a terminal state.
 #[utoipa::path(
     post,
     path = "/athena-connections/{id}/query",
@@ -39,11 +44,42 @@ use crate::api::QueryErrorResponse;
 )]
 pub async fn athena_query_sse(
     State(state): State<Arc<AppState>>,
+    client: QueryClient,
     Path(id): Path<String>,
     Json(req): Json<AthenaQueryRequest>,
 ) -> Result<
     Sse<impl futures::Stream<Item = Result<Event, Infallible>>>,
     (axum::http::StatusCode, Json<QueryErrorResponse>),
+> {
+    let audit = PendingQuery::start("/athena-connections/{id}/query", &client, req.sql.clone())
+        .with_connection(&id);
+    let result = run_query_sse(state.clone(), id, req, audit.clone()).await;
+    // Once streaming has started, the background task records the outcome.
+    if let Err((_, Json(e))) = &result {
+        state.query_audit.append(audit.finish(Err(e.error.clone())));
+    }
+    result
+}
+
+/// Outcome of an Athena query for the audit trail.
+fn audit_outcome(outcome: &QueryOutcome, rows: Option<u64>, error: Option<&String>) -> Result<Option<u64>, String> {
+    match (outcome, error) {
+        (QueryOutcome::Succeeded, _) => Ok(rows),
+        (_, Some(e)) => Err(e.clone()),
+        (QueryOutcome::Cancelled, None) => Err("Query cancelled".into()),
+        (QueryOutcome::TimedOut, None) => Err("Query timed out".into()),
+        (QueryOutcome::Failed, None) => Err("Query failed".into()),
+    }
+}
+
+async fn run_query_sse(
+    state: Arc<AppState>,
+    id: String,
+    req: AthenaQueryRequest,
+    audit: PendingQuery,
+) -> Result<
+    Sse<impl futures::Stream<Item = Result<Event, Infallible>>>,
+    (axum::http::StatusCode, Json<QueryErrorResponse>),
 > {
     // Route through eisenbahn if available AND the athena service is configured.
     if let Some(ref eb) = state.eisenbahn {
@@ -128,6 +164,7 @@ pub async fn athena_query_sse(
                 }
 
                 // Log query to audit log.
+                state_for_log.query_audit.append(audit.finish(audit_outcome(&outcome, total_rows, error_message.as_ref())));
                 let now = chrono::Utc::now();
                 state_for_log.athena_query_log.append(crate::athena_query_log::AthenaQueryLogEntry {
                     entry_id: 0,
@@ -211,6 +248,10 @@ pub async fn athena_query_sse(
         // Helper: append a query log entry at each terminal state.
         macro_rules! log_query {
             ($outcome:expr, $qid:expr, $scanned:expr, $exec_ms:expr, $rows:expr, $err:expr) => {
+                let outcome = $outcome;
+                let rows: Option<u64> = $rows;
+                let error_message: Option<String> = $err;
+                state_for_log.query_audit.append(audit.finish(audit_outcome(&outcome, rows, error_message.as_ref())));
                 let now = chrono::Utc::now();
                 state_for_log.athena_query_log.append(
                     crate::athena_query_log::AthenaQueryLogEntry {
@@ -221,11 +262,11 @@ pub async fn athena_query_sse(
                         sql: log_sql.clone(),
                         database: log_db.clone(),
                         workgroup: log_wg.clone(),
-                        outcome: $outcome,
-                        error_message: $err,
+                        outcome,
+                        error_message,
                         data_scanned_bytes: $scanned,
                         engine_execution_time_ms: $exec_ms,
-                        total_rows: $rows,
+                        total_rows: rows,
                         estimated_cost_usd: crate::athena_query_log::calculate_query_cost($scanned),
                         started_at: now,
                         completed_at: now,
@@ -59,6 +59,20 @@ pub async fn athena_connections_schema_refresh(
     State(state): State<Arc<AppState>>,
     Path(id): Path<String>,
 ) -> Result<Json<serde_json::Value>, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
+    let message = start_schema_refresh(&state, &id).await?;
+    Ok(Json(serde_json::json!({ "status": "fetching", "message": message })))
+}
+
+/// Start a background schema fetch for an Athena connection, returning a
+/// short description of how it was started.
+///
+/// Shared by the refresh endpoint and the scheduled refresh of stale
+/// catalog sources.
+pub(crate) async fn start_schema_refresh(
+    state: &Arc<AppState>,
+    id: &str,
+) -> Result<&'static str, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
+    let id = id.to_string();
     // Route through eisenbahn if available (fire-and-forget style).
     if let Some(ref eb) = state.eisenbahn {
         let svc_req = stupid_eisenbahn::services::AthenaServiceRequest::SchemaRefresh {
@@ -70,7 +84,7 @@ pub async fn athena_connections_schema_refresh(
             .await
             .map_err(|e| eb_athena_error(e))?;
         // Don't wait for the full stream — the worker does the work async.
-        return Ok(Json(serde_json::json!({ "status": "fetching", "message": "Schema refresh started via eisenbahn" })));
+        return Ok("Schema refresh started via eisenbahn");
     }
 
     // Get credentials and connection config.
@@ -116,7 +130,7 @@ pub async fn athena_connections_schema_refresh(
         }
     });
 
-    Ok(Json(serde_json::json!({ "status": "fetching", "message": "Schema refresh started" })))
+    Ok("Schema refresh started")
 }
 
 /// Rebuild the catalog's external SQL sources from all enabled Athena connections.
@@ -134,40 +148,7 @@ async fn rebuild_catalog_external_sources(state: &Arc<AppState>) {
         }
     };
 
-    let sources: Vec<stupid_catalog::ExternalSource> = conns
-        .iter()
-        .filter(|c| c.enabled && c.schema.is_some())
-        .map(|c| {
-            let schema = c.schema.as_ref().unwrap();
-            stupid_catalog::ExternalSource {
-                name: c.name.clone(),
-                kind: "athena".to_string(),
-                connection_id: c.id.clone(),
-                databases: schema
-                    .databases
-                    .iter()
-                    .map(|db| stupid_catalog::ExternalDatabase {
-                        name: db.name.clone(),
-                        tables: db
-                            .tables
-                            .iter()
-                            .map(|t| stupid_catalog::ExternalTable {
-                                name: t.name.clone(),
-                                columns: t
-                                    .columns
-                                    .iter()
-                                    .map(|col| stupid_catalog::ExternalColumn {
-                                        name: col.name.clone(),
-                                        data_type: col.data_type.clone(),
-                                    })
-                                    .collect(),
-                            })
-                            .collect(),
-                    })
-                    .collect(),
-            }
-        })
-        .collect();
+    let sources: Vec<stupid_catalog::ExternalSource> = conns.iter().filter_map(|c| c.to_external_source()).collect();
     drop(athena_store);
 
     // Persist each external source to catalog/external/{kind}-{id}.json
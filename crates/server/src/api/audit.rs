@@ -0,0 +1,155 @@
+//! Query audit trail endpoint.
+
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+
+use crate::query_audit::{QueryAuditPage, QueryAuditParams};
+use crate::state::AppState;
+
+use super::QueryErrorResponse;
+
+/// List audited query executions
+///
+/// Returns entries from the query audit trail (`/query`, `/catalog/query`
+/// and the Athena query endpoints), newest first, filtered by endpoint,
+/// client, time range or failure and paginated with `offset` and `limit`.
+/// Only available when API keys are configured, since entries name clients
+/// and contain query text.
+#[utoipa::path(
+    get,
+    path = "/audit/queries",
+    tag = "Audit",
+    params(QueryAuditParams),
+    responses(
+        (status = 200, description = "Page of audit entries", body = QueryAuditPage),
+        (status = 403, description = "API keys are not configured", body = QueryErrorResponse)
+    )
+)]
+pub async fn audit_queries(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<QueryAuditParams>,
+) -> Result<Json<QueryAuditPage>, (StatusCode, Json<QueryErrorResponse>)> {
+    // The auth middleware has already checked the key; without keys there
+    // is nothing to gate on, so refuse rather than serve the trail openly.
+    if !state.api_keys.is_enabled() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(QueryErrorResponse {
+                error: "The query audit trail requires API keys to be configured (API_KEYS)".to_string(),
+            }),
+        ));
+    }
+    Ok(Json(state.query_audit.query(&params)))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    use stupid_core::config::ApiKey;
+
+    const SECRET: &str = "ci-s3cr3t-key";
+
+    async fn app(dir: &std::path::Path) -> axum::Router {
+        let key = ApiKey { label: "ci".to_string(), key: SECRET.to_string() };
+        crate::router::build_router(crate::startup::test_app_state(dir, vec![key]).await)
+    }
+
+    async fn send(app: &axum::Router, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    fn post(path: &str, body: &str) -> Request<Body> {
+        Request::post(path)
+            .header("authorization", format!("Bearer {SECRET}"))
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    }
+
+    fn audit(query: &str) -> Request<Body> {
+        Request::get(format!("/audit/queries{query}")).header("x-api-key", SECRET).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_catalog_query_is_audited_without_secrets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = app(tmp.path()).await;
+
+        let plan = r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#;
+        let (status, _) = send(&app, post("/catalog/query", plan)).await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, page) = send(&app, audit("")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["total"], 1);
+        let entry = &page["entries"][0];
+        assert_eq!(entry["entry_id"], 1);
+        assert_eq!(entry["endpoint"], "/catalog/query");
+        assert_eq!(entry["client"], "key:ci");
+        assert_eq!(entry["row_count"], 0);
+        assert!(entry["query"].as_str().unwrap().contains("\"filter\""));
+        assert!(entry["duration_ms"].is_u64());
+        assert!(entry["timestamp"].is_string());
+        assert!(entry.get("error").is_none());
+
+        let stored = std::fs::read_to_string(tmp.path().join("query-audit.jsonl")).unwrap();
+        assert_eq!(stored.lines().count(), 1);
+        assert!(!stored.contains(SECRET), "API key leaked into the audit trail: {stored}");
+        assert!(!page.to_string().contains(SECRET));
+    }
+
+    #[tokio::test]
+    async fn test_failed_queries_are_audited() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = app(tmp.path()).await;
+
+        // No LLM is configured, so planning fails.
+        let (status, _) = send(&app, post("/query", r#"{"question":"top members"}"#)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        let (status, _) = send(&app, post("/athena-connections/missing/query", r#"{"sql":"SELECT 1"}"#)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = send(&app, post("/query/stream", r#"{"question":"top devices"}"#)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+
+        let (_, page) = send(&app, audit("?errors_only=true")).await;
+        assert_eq!(page["total"], 3);
+        let stream = &page["entries"][0];
+        assert_eq!(stream["endpoint"], "/query/stream");
+        assert_eq!(stream["query"], "top devices");
+        assert!(stream["error"].is_string());
+        let athena = &page["entries"][1];
+        assert_eq!(athena["endpoint"], "/athena-connections/{id}/query");
+        assert_eq!(athena["connection_id"], "missing");
+        assert_eq!(athena["query"], "SELECT 1");
+        assert_eq!(athena["error"], "Connection not found");
+        assert!(athena["row_count"].is_null());
+        let query = &page["entries"][2];
+        assert_eq!(query["endpoint"], "/query");
+        assert_eq!(query["query"], "top members");
+        assert_eq!(query["client"], "key:ci");
+
+        let (_, page) = send(&app, audit("?endpoint=/query")).await;
+        assert_eq!(page["total"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_audit_trail_requires_auth() {
+        let tmp = tempfile::tempdir().unwrap();
+        let app = app(tmp.path()).await;
+        let request = Request::get("/audit/queries").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(request).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+
+        let open = crate::router::build_router(crate::startup::test_app_state(tmp.path(), vec![]).await);
+        let request = Request::get("/audit/queries").body(Body::empty()).unwrap();
+        assert_eq!(open.oneshot(request).await.unwrap().status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -2,11 +2,13 @@
 
 use std::sync::Arc;
 
-use axum::extract::State;
-use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
 use serde::Serialize;
 
 use crate::state::AppState;
+use super::export::{export_response, ExportFormat, ExportQueryParams, TabularRow};
 use super::ComputeQueryParams;
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -42,28 +44,74 @@ pub struct AnomalyEntry {
     pub cluster_id: Option<u64>,
 }
 
+/// Flattened for CSV/Parquet: one column per feature dimension, NULL when
+/// the member has no feature vector.
+impl TabularRow for AnomalyEntry {
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        let mut columns = vec![
+            ("id", "varchar"),
+            ("entity_type", "varchar"),
+            ("key", "varchar"),
+            ("score", "double"),
+            ("is_anomalous", "boolean"),
+            ("cluster_id", "bigint"),
+        ];
+        columns.extend(FEATURE_NAMES.iter().map(|&name| (name, "double")));
+        columns
+    }
+
+    fn cells(&self) -> Vec<Option<String>> {
+        let mut cells = vec![
+            Some(self.id.clone()),
+            Some(self.entity_type.clone()),
+            Some(self.key.clone()),
+            Some(self.score.to_string()),
+            Some(self.is_anomalous.to_string()),
+            self.cluster_id.map(|c| c.to_string()),
+        ];
+        cells.extend(FEATURE_NAMES.iter().map(|&name| {
+            self.features
+                .as_ref()
+                .and_then(|dims| dims.iter().find(|d| d.name == name))
+                .map(|d| d.value.to_string())
+        }));
+        cells
+    }
+}
+
 /// Anomaly scores from DBSCAN clustering, sorted by score descending.
 #[utoipa::path(
     get,
     path = "/compute/anomalies",
     tag = "Compute",
-    params(ComputeQueryParams),
+    params(ComputeQueryParams, ExportQueryParams),
     responses(
-        (status = 200, description = "Anomaly entries with optional features", body = Vec<AnomalyEntry>)
+        (status = 200, description = "Anomaly entries with optional features", content(
+            (Vec<AnomalyEntry> = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        ))
     )
 )]
 pub async fn compute_anomalies(
     State(state): State<Arc<AppState>>,
-    axum::extract::Query(params): axum::extract::Query<ComputeQueryParams>,
-) -> Json<Vec<AnomalyEntry>> {
-    let limit = params.limit.unwrap_or(50).min(500);
+    Query(params): Query<ComputeQueryParams>,
+    Query(export): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = ExportFormat::negotiate(export.format, &headers);
+    export_response(anomaly_entries(&state, params.limit), format, "anomalies")
+}
+
+fn anomaly_entries(state: &AppState, limit: Option<usize>) -> Vec<AnomalyEntry> {
+    let limit = limit.unwrap_or(50).min(500);
 
     // Extract anomaly data + cluster assignments from knowledge
     // (std::sync lock — must not hold across .await).
     let sorted_anomalies: Vec<(uuid::Uuid, f64, bool, Option<u64>)> = {
         let knowledge = state.knowledge.read().unwrap();
         if knowledge.anomalies.is_empty() {
-            return Json(vec![]);
+            return vec![];
         }
         let mut entries: Vec<(uuid::Uuid, f64, bool, Option<u64>)> = knowledge
             .anomalies
@@ -81,7 +129,7 @@ pub async fn compute_anomalies(
     // Pipeline NodeIds use FNV-hash UUIDs which differ from graph's random UUIDs,
     // so we look up member_key directly from pipeline features.
     let pipeline = state.pipeline.lock().unwrap();
-    let result: Vec<AnomalyEntry> = sorted_anomalies
+    sorted_anomalies
         .into_iter()
         .filter_map(|(node_id, score, is_anomalous, cluster_id)| {
             let key = pipeline.features.member_key(&node_id)?;
@@ -104,7 +152,5 @@ pub async fn compute_anomalies(
                 cluster_id,
             })
         })
-        .collect();
-
-    Json(result)
+        .collect()
 }
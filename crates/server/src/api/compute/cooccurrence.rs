@@ -1,4 +1,5 @@
-//! Entity co-occurrence matrix endpoint with optional PMI scores.
+//! Entity co-occurrence matrix endpoint with optional PMI scores, and
+//! top-k PMI associations for a single entity.
 
 use std::sync::Arc;
 
@@ -16,6 +17,21 @@ pub struct CooccurrenceQueryParams {
     pub entity_type_b: Option<String>,
     /// Maximum pairs per entity-type combination (default 50, max 500).
     pub limit: Option<usize>,
+    /// Only return entities co-occurring with this entity key (e.g. `mobile`),
+    /// ranked by PMI.
+    pub item: Option<String>,
+    /// Skip pairs seen in fewer documents than this (default 1).
+    pub min_count: Option<f64>,
+}
+
+impl CooccurrenceQueryParams {
+    /// Whether a matrix for this pair of entity types passes the type filters.
+    fn matches_types(&self, type_a: &str, type_b: &str) -> bool {
+        [&self.entity_type_a, &self.entity_type_b]
+            .into_iter()
+            .flatten()
+            .all(|filter| type_a.eq_ignore_ascii_case(filter) || type_b.eq_ignore_ascii_case(filter))
+    }
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -48,6 +64,7 @@ pub async fn compute_cooccurrence(
     axum::extract::Query(params): axum::extract::Query<CooccurrenceQueryParams>,
 ) -> Json<Vec<CooccurrenceResponse>> {
     let limit = params.limit.unwrap_or(50).min(500);
+    let min_count = params.min_count.unwrap_or(1.0);
 
     let knowledge = state.knowledge.read().unwrap();
 
@@ -59,22 +76,36 @@ pub async fn compute_cooccurrence(
             let type_a_str = type_a.to_string();
             let type_b_str = type_b.to_string();
 
-            // Apply entity type filter if specified.
-            if let Some(ref filter_a) = params.entity_type_a {
-                if !type_a_str.eq_ignore_ascii_case(filter_a) && !type_b_str.eq_ignore_ascii_case(filter_a) {
-                    continue;
-                }
+            if !params.matches_types(&type_a_str, &type_b_str) {
+                continue;
             }
-            if let Some(ref filter_b) = params.entity_type_b {
-                if !type_a_str.eq_ignore_ascii_case(filter_b) && !type_b_str.eq_ignore_ascii_case(filter_b) {
-                    continue;
+
+            if let Some(ref item) = params.item {
+                let pairs: Vec<CooccurrenceEntry> = matrix
+                    .top_associations(item, limit, min_count)
+                    .into_iter()
+                    .map(|assoc| CooccurrenceEntry {
+                        entity_a: item.clone(),
+                        entity_b: assoc.item,
+                        count: assoc.count,
+                        pmi: Some(assoc.pmi),
+                    })
+                    .collect();
+                if !pairs.is_empty() {
+                    responses.push(CooccurrenceResponse {
+                        entity_type_a: type_a_str,
+                        entity_type_b: type_b_str,
+                        pairs,
+                    });
                 }
+                continue;
             }
 
             let mut pairs: Vec<CooccurrenceEntry> = matrix
                 .counts
                 .entries
                 .iter()
+                .filter(|(_, &count)| count >= min_count)
                 .map(|((a, b), &count)| {
                     let pmi = matrix.pmi.get(&(a.clone(), b.clone())).copied();
                     CooccurrenceEntry {
@@ -106,20 +137,17 @@ pub async fn compute_cooccurrence(
             let type_a_str = type_a.to_string();
             let type_b_str = type_b.to_string();
 
-            if let Some(ref filter_a) = params.entity_type_a {
-                if !type_a_str.eq_ignore_ascii_case(filter_a) && !type_b_str.eq_ignore_ascii_case(filter_a) {
-                    continue;
-                }
-            }
-            if let Some(ref filter_b) = params.entity_type_b {
-                if !type_a_str.eq_ignore_ascii_case(filter_b) && !type_b_str.eq_ignore_ascii_case(filter_b) {
-                    continue;
-                }
+            if !params.matches_types(&type_a_str, &type_b_str) {
+                continue;
             }
 
+            let item = params.item.as_deref();
             let mut pairs: Vec<CooccurrenceEntry> = matrix
                 .entries
                 .iter()
+                .filter(|((a, b), &count)| {
+                    count >= min_count && item.is_none_or(|item| a == item || b == item)
+                })
                 .map(|((a, b), &count)| CooccurrenceEntry {
                     entity_a: a.clone(),
                     entity_b: b.clone(),
@@ -131,6 +159,9 @@ pub async fn compute_cooccurrence(
             pairs.sort_by(|a, b| b.count.partial_cmp(&a.count).unwrap_or(std::cmp::Ordering::Equal));
             pairs.truncate(limit);
 
+            if item.is_some() && pairs.is_empty() {
+                continue;
+            }
             responses.push(CooccurrenceResponse {
                 entity_type_a: type_a_str,
                 entity_type_b: type_b_str,
@@ -141,3 +172,49 @@ pub async fn compute_cooccurrence(
 
     Json(responses)
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use stupid_compute::CooccurrenceMatrix;
+    use stupid_core::EntityType;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn item_query_returns_top_associations_above_floor() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        {
+            let mut matrix = CooccurrenceMatrix::default();
+            for ((a, b), count) in [(("slots", "mobile"), 8.0), (("poker", "mobile"), 4.0), (("keno", "mobile"), 1.0)] {
+                matrix.counts.entries.insert((a.to_string(), b.to_string()), count);
+            }
+            for (key, marginal) in [("mobile", 13.0), ("slots", 10.0), ("poker", 20.0), ("keno", 1.0)] {
+                matrix.marginals.insert(key.to_string(), marginal);
+            }
+            matrix.total_docs = 29.0;
+            stupid_compute::pipeline::cooccurrence::compute_pmi(&mut matrix);
+            let mut knowledge = state.knowledge.write().unwrap();
+            knowledge.cooccurrence_pmi.insert((EntityType::Game, EntityType::Platform), matrix);
+        }
+        let app = crate::router::build_router(state);
+
+        let response = app
+            .oneshot(
+                Request::get("/compute/cooccurrence?item=mobile&min_count=2&limit=5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        let pairs = body[0]["pairs"].as_array().unwrap();
+        let partners: Vec<&str> = pairs.iter().map(|p| p["entity_b"].as_str().unwrap()).collect();
+        assert_eq!(partners, vec!["slots", "poker"]);
+        assert!(pairs.iter().all(|p| p["entity_a"] == "mobile"));
+    }
+}
@@ -0,0 +1,191 @@
+//! Downloadable CSV/Parquet output for compute endpoints.
+//!
+//! Endpoints return JSON by default. `?format=csv|parquet`, or an `Accept`
+//! header naming one of those types, returns the same rows as a file
+//! attachment instead. Parquet goes through the athena crate's writer.
+
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+
+use stupid_athena::{AthenaColumn, AthenaQueryResult, QueryMetadata};
+
+const CSV_CONTENT_TYPE: &str = "text/csv; charset=utf-8";
+const PARQUET_CONTENT_TYPE: &str = "application/vnd.apache.parquet";
+
+/// Output format of a compute endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Json,
+    Csv,
+    Parquet,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct ExportQueryParams {
+    /// Response format: `json` (default), `csv`, or `parquet`. Overrides `Accept`.
+    pub format: Option<ExportFormat>,
+}
+
+impl ExportFormat {
+    /// The explicit `format` parameter, else the first supported type in `Accept`.
+    pub fn negotiate(requested: Option<ExportFormat>, headers: &HeaderMap) -> Self {
+        if let Some(format) = requested {
+            return format;
+        }
+        let accept = headers
+            .get(header::ACCEPT)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default();
+        accept
+            .split(',')
+            .map(|part| part.split(';').next().unwrap_or_default().trim())
+            .find_map(|media_type| match media_type {
+                "text/csv" => Some(ExportFormat::Csv),
+                PARQUET_CONTENT_TYPE | "application/x-parquet" => Some(ExportFormat::Parquet),
+                "application/json" => Some(ExportFormat::Json),
+                _ => None,
+            })
+            .unwrap_or(ExportFormat::Json)
+    }
+}
+
+/// A result row that can be written as a flat table.
+pub trait TabularRow {
+    /// Column names with their Athena type (`varchar`, `bigint`, `double`, `boolean`).
+    fn columns() -> Vec<(&'static str, &'static str)>;
+
+    /// Cell values in column order; `None` is NULL.
+    fn cells(&self) -> Vec<Option<String>>;
+}
+
+/// Respond with `rows` in `format`; CSV and Parquet are sent as a
+/// `{name}.csv` / `{name}.parquet` attachment.
+pub fn export_response<T: Serialize + TabularRow>(rows: Vec<T>, format: ExportFormat, name: &str) -> Response {
+    let (body, content_type, extension) = match format {
+        ExportFormat::Json => return Json(rows).into_response(),
+        ExportFormat::Csv => (to_csv(&rows), CSV_CONTENT_TYPE, "csv"),
+        ExportFormat::Parquet => (to_parquet(&rows), PARQUET_CONTENT_TYPE, "parquet"),
+    };
+    match body {
+        Ok(bytes) => (
+            [
+                (header::CONTENT_TYPE, content_type.to_string()),
+                (header::CONTENT_DISPOSITION, format!("attachment; filename=\"{name}.{extension}\"")),
+            ],
+            bytes,
+        )
+            .into_response(),
+        Err(e) => {
+            tracing::warn!(export = %name, error = %e, "Failed to export compute results");
+            (StatusCode::INTERNAL_SERVER_ERROR, e).into_response()
+        }
+    }
+}
+
+/// Serialize `rows` as CSV with a header line.
+pub fn to_csv<T: TabularRow>(rows: &[T]) -> Result<Vec<u8>, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(T::columns().iter().map(|(name, _)| name))
+        .map_err(|e| format!("CSV write error: {e}"))?;
+    for row in rows {
+        writer
+            .write_record(row.cells().into_iter().map(Option::unwrap_or_default))
+            .map_err(|e| format!("CSV write error: {e}"))?;
+    }
+    writer.into_inner().map_err(|e| format!("CSV write error: {e}"))
+}
+
+/// Serialize `rows` as a Parquet file.
+pub fn to_parquet<T: TabularRow>(rows: &[T]) -> Result<Vec<u8>, String> {
+    let result = AthenaQueryResult {
+        columns: T::columns()
+            .into_iter()
+            .map(|(name, data_type)| AthenaColumn { name: name.to_string(), data_type: data_type.to_string() })
+            .collect(),
+        rows: rows.iter().map(TabularRow::cells).collect(),
+        metadata: QueryMetadata {
+            query_id: String::new(),
+            bytes_scanned: 0,
+            execution_time_ms: 0,
+            state: "SUCCEEDED".into(),
+            output_location: None,
+        },
+    };
+    stupid_athena::write_parquet_bytes(&result).map_err(|e| format!("Parquet write error: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::compute::PageRankEntry;
+
+    fn entries() -> Vec<PageRankEntry> {
+        vec![
+            PageRankEntry { id: "n1".into(), entity_type: "Member".into(), key: "alice".into(), score: 0.42 },
+            PageRankEntry { id: "n2".into(), entity_type: "Game".into(), key: "slots, deluxe".into(), score: 0.17 },
+        ]
+    }
+
+    #[test]
+    fn negotiate_prefers_query_then_accept() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(ExportFormat::negotiate(None, &headers), ExportFormat::Json);
+
+        headers.insert(header::ACCEPT, "text/csv;q=0.9, application/json".parse().unwrap());
+        assert_eq!(ExportFormat::negotiate(None, &headers), ExportFormat::Csv);
+        assert_eq!(ExportFormat::negotiate(Some(ExportFormat::Parquet), &headers), ExportFormat::Parquet);
+
+        headers.insert(header::ACCEPT, "application/vnd.apache.parquet".parse().unwrap());
+        assert_eq!(ExportFormat::negotiate(None, &headers), ExportFormat::Parquet);
+    }
+
+    #[test]
+    fn csv_has_header_and_quotes_values() {
+        let csv = String::from_utf8(to_csv(&entries()).unwrap()).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("id,entity_type,key,score"));
+        assert_eq!(lines.next(), Some("n1,Member,alice,0.42"));
+        assert_eq!(lines.next(), Some("n2,Game,\"slots, deluxe\",0.17"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn parquet_reads_back() {
+        use arrow::array::{Float64Array, StringArray};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let bytes = to_parquet(&entries()).unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes::Bytes::from(bytes))
+            .unwrap()
+            .build()
+            .unwrap();
+        let batches: Vec<_> = reader.map(|b| b.unwrap()).collect();
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+
+        let names: Vec<_> = batch.schema().fields().iter().map(|f| f.name().clone()).collect();
+        assert_eq!(names, ["id", "entity_type", "key", "score"]);
+        let keys = batch.column(2).as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(keys.value(1), "slots, deluxe");
+        let scores = batch.column(3).as_any().downcast_ref::<Float64Array>().unwrap();
+        assert_eq!(scores.value(0), 0.42);
+    }
+
+    #[tokio::test]
+    async fn response_sets_content_disposition() {
+        let response = export_response(entries(), ExportFormat::Csv, "pagerank");
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], CSV_CONTENT_TYPE);
+        assert_eq!(
+            response.headers()[header::CONTENT_DISPOSITION],
+            "attachment; filename=\"pagerank.csv\""
+        );
+
+        let response = export_response(entries(), ExportFormat::Json, "pagerank");
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    }
+}
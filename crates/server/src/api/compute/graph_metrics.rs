@@ -2,11 +2,13 @@
 
 use std::sync::Arc;
 
-use axum::extract::State;
-use axum::Json;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
 use serde::Serialize;
 
 use crate::state::AppState;
+use super::export::{export_response, ExportFormat, ExportQueryParams, TabularRow};
 use super::ComputeQueryParams;
 
 // ── PageRank ─────────────────────────────────────────────────────
@@ -19,27 +21,53 @@ pub struct PageRankEntry {
     pub score: f64,
 }
 
+impl TabularRow for PageRankEntry {
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        vec![("id", "varchar"), ("entity_type", "varchar"), ("key", "varchar"), ("score", "double")]
+    }
+
+    fn cells(&self) -> Vec<Option<String>> {
+        vec![
+            Some(self.id.clone()),
+            Some(self.entity_type.clone()),
+            Some(self.key.clone()),
+            Some(self.score.to_string()),
+        ]
+    }
+}
+
 /// Top nodes by PageRank score, sorted descending.
 #[utoipa::path(
     get,
     path = "/compute/pagerank",
     tag = "Compute",
-    params(ComputeQueryParams),
+    params(ComputeQueryParams, ExportQueryParams),
     responses(
-        (status = 200, description = "PageRank scores", body = Vec<PageRankEntry>)
+        (status = 200, description = "PageRank scores", content(
+            (Vec<PageRankEntry> = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        ))
     )
 )]
 pub async fn compute_pagerank(
     State(state): State<Arc<AppState>>,
-    axum::extract::Query(params): axum::extract::Query<ComputeQueryParams>,
-) -> Json<Vec<PageRankEntry>> {
-    let limit = params.limit.unwrap_or(50).min(500);
+    Query(params): Query<ComputeQueryParams>,
+    Query(export): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = ExportFormat::negotiate(export.format, &headers);
+    export_response(pagerank_entries(&state, params.limit).await, format, "pagerank")
+}
+
+async fn pagerank_entries(state: &AppState, limit: Option<usize>) -> Vec<PageRankEntry> {
+    let limit = limit.unwrap_or(50).min(500);
 
     // Clone sorted scores out of knowledge (std::sync lock — must not hold across .await).
     let sorted_scores: Vec<(uuid::Uuid, f64)> = {
         let knowledge = state.knowledge.read().unwrap();
         if knowledge.pagerank.is_empty() {
-            return Json(vec![]);
+            return vec![];
         }
         let mut entries: Vec<(uuid::Uuid, f64)> = knowledge.pagerank.iter().map(|(&k, &v)| (k, v)).collect();
         entries.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
@@ -47,7 +75,7 @@ pub async fn compute_pagerank(
     };
 
     let graph = state.graph.read().await;
-    let result: Vec<PageRankEntry> = sorted_scores
+    sorted_scores
         .into_iter()
         .filter_map(|(node_id, score)| {
             let node = graph.nodes.get(&node_id)?;
@@ -58,9 +86,7 @@ pub async fn compute_pagerank(
                 score,
             })
         })
-        .collect();
-
-    Json(result)
+        .collect()
 }
 
 // ── Communities ───────────────────────────────────────────────────
@@ -79,23 +105,51 @@ pub struct CommunityNode {
     pub key: String,
 }
 
+/// Flattened for CSV/Parquet: top nodes become a `;`-separated list of keys.
+impl TabularRow for CommunitySummary {
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        vec![("community_id", "bigint"), ("member_count", "bigint"), ("top_node_keys", "varchar")]
+    }
+
+    fn cells(&self) -> Vec<Option<String>> {
+        let keys: Vec<&str> = self.top_nodes.iter().map(|n| n.key.as_str()).collect();
+        vec![
+            Some(self.community_id.to_string()),
+            Some(self.member_count.to_string()),
+            Some(keys.join(";")),
+        ]
+    }
+}
+
 /// Louvain community detection results sorted by member count descending.
 #[utoipa::path(
     get,
     path = "/compute/communities",
     tag = "Compute",
+    params(ExportQueryParams),
     responses(
-        (status = 200, description = "Community summaries", body = Vec<CommunitySummary>)
+        (status = 200, description = "Community summaries", content(
+            (Vec<CommunitySummary> = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        ))
     )
 )]
 pub async fn compute_communities(
     State(state): State<Arc<AppState>>,
-) -> Json<Vec<CommunitySummary>> {
+    Query(export): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = ExportFormat::negotiate(export.format, &headers);
+    export_response(community_summaries(&state).await, format, "communities")
+}
+
+async fn community_summaries(state: &AppState) -> Vec<CommunitySummary> {
     // Extract community data from knowledge (std::sync lock — must not hold across .await).
     let community_members: std::collections::HashMap<u64, Vec<uuid::Uuid>> = {
         let knowledge = state.knowledge.read().unwrap();
         if knowledge.communities.is_empty() {
-            return Json(vec![]);
+            return vec![];
         }
         let mut members: std::collections::HashMap<u64, Vec<uuid::Uuid>> =
             std::collections::HashMap::new();
@@ -132,7 +186,7 @@ pub async fn compute_communities(
         .collect();
 
     summaries.sort_by(|a, b| b.member_count.cmp(&a.member_count));
-    Json(summaries)
+    summaries
 }
 
 // ── Degrees ──────────────────────────────────────────────────────
@@ -147,27 +201,62 @@ pub struct DegreeEntry {
     pub total: usize,
 }
 
+impl TabularRow for DegreeEntry {
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("id", "varchar"),
+            ("entity_type", "varchar"),
+            ("key", "varchar"),
+            ("in_deg", "bigint"),
+            ("out_deg", "bigint"),
+            ("total", "bigint"),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Option<String>> {
+        vec![
+            Some(self.id.clone()),
+            Some(self.entity_type.clone()),
+            Some(self.key.clone()),
+            Some(self.in_deg.to_string()),
+            Some(self.out_deg.to_string()),
+            Some(self.total.to_string()),
+        ]
+    }
+}
+
 /// Node degree centrality sorted by total degree descending.
 #[utoipa::path(
     get,
     path = "/compute/degrees",
     tag = "Compute",
-    params(ComputeQueryParams),
+    params(ComputeQueryParams, ExportQueryParams),
     responses(
-        (status = 200, description = "Degree entries", body = Vec<DegreeEntry>)
+        (status = 200, description = "Degree entries", content(
+            (Vec<DegreeEntry> = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        ))
     )
 )]
 pub async fn compute_degrees(
     State(state): State<Arc<AppState>>,
-    axum::extract::Query(params): axum::extract::Query<ComputeQueryParams>,
-) -> Json<Vec<DegreeEntry>> {
-    let limit = params.limit.unwrap_or(50).min(500);
+    Query(params): Query<ComputeQueryParams>,
+    Query(export): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = ExportFormat::negotiate(export.format, &headers);
+    export_response(degree_entries(&state, params.limit).await, format, "degrees")
+}
+
+async fn degree_entries(state: &AppState, limit: Option<usize>) -> Vec<DegreeEntry> {
+    let limit = limit.unwrap_or(50).min(500);
 
     // Extract degree data from knowledge (std::sync lock — must not hold across .await).
     let sorted_degrees: Vec<(uuid::Uuid, stupid_compute::DegreeInfo)> = {
         let knowledge = state.knowledge.read().unwrap();
         if knowledge.degrees.is_empty() {
-            return Json(vec![]);
+            return vec![];
         }
         let mut entries: Vec<(uuid::Uuid, stupid_compute::DegreeInfo)> =
             knowledge.degrees.iter().map(|(&k, v)| (k, v.clone())).collect();
@@ -177,7 +266,7 @@ pub async fn compute_degrees(
 
     let graph = state.graph.read().await;
 
-    let result: Vec<DegreeEntry> = sorted_degrees
+    sorted_degrees
         .into_iter()
         .filter_map(|(node_id, deg)| {
             let node = graph.nodes.get(&node_id)?;
@@ -190,7 +279,115 @@ pub async fn compute_degrees(
                 total: deg.total,
             })
         })
-        .collect();
+        .collect()
+}
+
+// ── Degree anomalies ─────────────────────────────────────────────
+
+#[derive(serde::Deserialize, utoipa::IntoParams)]
+pub struct DegreeAnomalyQueryParams {
+    /// Flag nodes more than this many standard deviations from their
+    /// entity type's mean degree (default 3.0).
+    pub threshold: Option<f64>,
+    /// Maximum number of results to return (default 50, max 500).
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct DegreeAnomalyEntry {
+    pub id: String,
+    pub entity_type: String,
+    pub key: String,
+    pub total: usize,
+    /// Mean total degree of nodes of the same entity type.
+    pub type_mean: f64,
+    /// Standard deviation of total degree within the entity type.
+    pub type_std_dev: f64,
+    /// Signed distance from the type mean in standard deviations.
+    pub z_score: f64,
+}
+
+impl TabularRow for DegreeAnomalyEntry {
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("id", "varchar"),
+            ("entity_type", "varchar"),
+            ("key", "varchar"),
+            ("total", "bigint"),
+            ("type_mean", "double"),
+            ("type_std_dev", "double"),
+            ("z_score", "double"),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Option<String>> {
+        vec![
+            Some(self.id.clone()),
+            Some(self.entity_type.clone()),
+            Some(self.key.clone()),
+            Some(self.total.to_string()),
+            Some(self.type_mean.to_string()),
+            Some(self.type_std_dev.to_string()),
+            Some(self.z_score.to_string()),
+        ]
+    }
+}
 
-    Json(result)
+/// Nodes whose degree is unusual for their entity type, sorted by
+/// deviation descending.
+#[utoipa::path(
+    get,
+    path = "/compute/degree-anomalies",
+    tag = "Compute",
+    params(DegreeAnomalyQueryParams, ExportQueryParams),
+    responses(
+        (status = 200, description = "Degree anomalies", content(
+            (Vec<DegreeAnomalyEntry> = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        ))
+    )
+)]
+pub async fn compute_degree_anomalies(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<DegreeAnomalyQueryParams>,
+    Query(export): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = ExportFormat::negotiate(export.format, &headers);
+    let entries = degree_anomaly_entries(&state, params.threshold, params.limit).await;
+    export_response(entries, format, "degree-anomalies")
+}
+
+async fn degree_anomaly_entries(
+    state: &AppState,
+    threshold: Option<f64>,
+    limit: Option<usize>,
+) -> Vec<DegreeAnomalyEntry> {
+    let threshold = threshold.unwrap_or(3.0);
+    let limit = limit.unwrap_or(50).min(500);
+
+    let graph = state.graph.read().await;
+    // std::sync lock — taken after the graph await and released before returning.
+    let anomalies = {
+        let knowledge = state.knowledge.read().unwrap();
+        stupid_compute::algorithms::degree::degree_anomalies(&graph, &knowledge.degrees, threshold)
+    };
+
+    anomalies
+        .into_iter()
+        .filter_map(|a| {
+            let node = graph.nodes.get(&a.node_id)?;
+            Some(DegreeAnomalyEntry {
+                id: a.node_id.to_string(),
+                entity_type: a.entity_type.to_string(),
+                key: node.key.clone(),
+                total: a.total,
+                type_mean: a.type_mean,
+                type_std_dev: a.type_std_dev,
+                z_score: a.z_score,
+            })
+        })
+        .take(limit)
+        .collect()
 }
@@ -0,0 +1,321 @@
+//! Insight queries and the live insight stream over Server-Sent Events.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::{Query, State};
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::Json;
+use futures::{Stream, StreamExt};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use stupid_compute::scheduler::types::{Insight, InsightSeverity};
+use stupid_compute::scheduler::InsightQuery;
+use tokio::sync::broadcast;
+
+use crate::api::QueryErrorResponse;
+use crate::state::AppState;
+
+/// Interval between heartbeat comments on an idle stream.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct InsightQueryParams {
+    /// Only insights at least this severe: `info` (default), `warning` or `critical`.
+    pub min_severity: Option<String>,
+    /// Only insights created at or after this RFC 3339 timestamp.
+    pub since: Option<String>,
+    /// Only insights created before this RFC 3339 timestamp.
+    pub until: Option<String>,
+    /// Only insights related to this node ID.
+    pub node: Option<String>,
+    /// Maximum number of insights to return (default 50, max 500).
+    pub limit: Option<usize>,
+    /// Number of matching insights to skip.
+    pub offset: Option<usize>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct InsightEntry {
+    pub id: String,
+    pub title: String,
+    pub description: String,
+    /// `Info`, `Warning` or `Critical`.
+    pub severity: String,
+    pub created_at: String,
+    pub related_nodes: Vec<String>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct InsightListResponse {
+    /// Insights matching the filters, before paging.
+    pub total: usize,
+    pub offset: usize,
+    /// Newest first.
+    pub insights: Vec<InsightEntry>,
+}
+
+type BadRequest = (axum::http::StatusCode, Json<QueryErrorResponse>);
+
+fn bad_request(error: String) -> BadRequest {
+    (axum::http::StatusCode::BAD_REQUEST, Json(QueryErrorResponse { error }))
+}
+
+/// Recent insights filtered by severity, time range and related node
+///
+/// Insights are kept in a capped store, so only the most recent 10,000 are
+/// queryable.
+#[utoipa::path(
+    get,
+    path = "/insights",
+    tag = "Compute",
+    params(InsightQueryParams),
+    responses(
+        (status = 200, description = "Matching insights, newest first", body = InsightListResponse),
+        (status = 400, description = "Invalid filter", body = QueryErrorResponse)
+    )
+)]
+pub async fn list_insights(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<InsightQueryParams>,
+) -> Result<Json<InsightListResponse>, BadRequest> {
+    let query = insight_query(&params)?;
+    let page = state.knowledge.read().unwrap().insights.query(&query);
+    Ok(Json(InsightListResponse {
+        total: page.total,
+        offset: query.offset,
+        insights: page
+            .insights
+            .into_iter()
+            .map(|i| InsightEntry {
+                id: i.id,
+                title: i.title,
+                description: i.description,
+                severity: format!("{:?}", i.severity),
+                created_at: i.created_at.to_rfc3339(),
+                related_nodes: i.related_nodes.iter().map(|n| n.to_string()).collect(),
+            })
+            .collect(),
+    }))
+}
+
+fn insight_query(params: &InsightQueryParams) -> Result<InsightQuery, BadRequest> {
+    let timestamp = |name: &str, value: &Option<String>| -> Result<Option<DateTime<Utc>>, BadRequest> {
+        value
+            .as_deref()
+            .map(|s| {
+                DateTime::parse_from_rfc3339(s)
+                    .map(|t| t.with_timezone(&Utc))
+                    .map_err(|e| bad_request(format!("invalid {name} '{s}': {e}")))
+            })
+            .transpose()
+    };
+    let min_severity = match params.min_severity.as_deref() {
+        None => None,
+        Some(s) => Some(parse_severity(s).ok_or_else(|| {
+            bad_request(format!("unknown min_severity '{s}' (expected info, warning or critical)"))
+        })?),
+    };
+    let related_node = params
+        .node
+        .as_deref()
+        .map(|s| s.parse().map_err(|_| bad_request(format!("invalid node id '{s}'"))))
+        .transpose()?;
+
+    Ok(InsightQuery {
+        min_severity,
+        since: timestamp("since", &params.since)?,
+        until: timestamp("until", &params.until)?,
+        related_node,
+        offset: params.offset.unwrap_or(0),
+        limit: Some(params.limit.unwrap_or(50).min(500)),
+    })
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct InsightStreamParams {
+    /// Only stream insights at least this severe: `info` (default), `warning` or `critical`.
+    pub min_severity: Option<String>,
+}
+
+/// SSE stream of insights as the compute pipeline generates them
+///
+/// Each new insight is sent as an `insight` event whose data is the insight
+/// JSON. Idle connections receive a heartbeat comment every 15 seconds.
+#[utoipa::path(
+    get,
+    path = "/insights/stream",
+    tag = "Compute",
+    params(InsightStreamParams),
+    responses(
+        (status = 200, description = "SSE event stream", content_type = "text/event-stream"),
+        (status = 400, description = "Unknown min_severity", body = QueryErrorResponse)
+    )
+)]
+pub async fn insights_stream(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<InsightStreamParams>,
+) -> Result<
+    Sse<impl Stream<Item = Result<Event, Infallible>>>,
+    (axum::http::StatusCode, Json<QueryErrorResponse>),
+> {
+    let min_severity = match params.min_severity.as_deref() {
+        None => InsightSeverity::Info,
+        Some(s) => parse_severity(s).ok_or_else(|| {
+            (
+                axum::http::StatusCode::BAD_REQUEST,
+                Json(QueryErrorResponse {
+                    error: format!("unknown min_severity '{s}' (expected info, warning or critical)"),
+                }),
+            )
+        })?,
+    };
+
+    let events = insight_stream(state.broadcast.subscribe(), min_severity).map(|insight| {
+        let data = serde_json::to_string(&insight).unwrap_or_default();
+        Ok(Event::default().event("insight").data(data))
+    });
+    Ok(Sse::new(events).keep_alive(KeepAlive::new().interval(HEARTBEAT_INTERVAL)))
+}
+
+/// Insights from the broadcast channel at or above `min_severity`.
+///
+/// Other broadcast messages are skipped; if the subscriber lags, the missed
+/// messages are dropped and streaming continues with the newest.
+fn insight_stream(
+    rx: broadcast::Receiver<String>,
+    min_severity: InsightSeverity,
+) -> impl Stream<Item = Insight> {
+    #[derive(Deserialize)]
+    struct Message {
+        #[serde(rename = "type")]
+        msg_type: String,
+        data: serde_json::Value,
+    }
+
+    futures::stream::unfold(rx, move |mut rx| async move {
+        loop {
+            let text = match rx.recv().await {
+                Ok(text) => text,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "insight stream lagged behind broadcast");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            };
+            let Ok(msg) = serde_json::from_str::<Message>(&text) else {
+                continue;
+            };
+            if msg.msg_type != "insight" {
+                continue;
+            }
+            if let Ok(insight) = serde_json::from_value::<Insight>(msg.data) {
+                if insight.severity >= min_severity {
+                    return Some((insight, rx));
+                }
+            }
+        }
+    })
+}
+
+fn parse_severity(s: &str) -> Option<InsightSeverity> {
+    match s.to_ascii_lowercase().as_str() {
+        "info" => Some(InsightSeverity::Info),
+        "warning" => Some(InsightSeverity::Warning),
+        "critical" => Some(InsightSeverity::Critical),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insight(id: &str, severity: InsightSeverity) -> Insight {
+        Insight {
+            id: id.to_string(),
+            title: "Anomalous behavior detected (z=4.20)".to_string(),
+            description: "Member alice has anomaly score 4.20".to_string(),
+            severity,
+            created_at: chrono::Utc::now(),
+            related_nodes: vec![],
+        }
+    }
+
+    fn insight_message(insight: &Insight) -> String {
+        serde_json::json!({ "type": "insight", "data": insight }).to_string()
+    }
+
+    #[tokio::test]
+    async fn test_pushed_insight_appears_on_stream() {
+        let (tx, rx) = broadcast::channel(16);
+        let mut stream = Box::pin(insight_stream(rx, InsightSeverity::Info));
+
+        tx.send(r#"{"type":"stats","data":{"doc_count":1}}"#.to_string()).unwrap();
+        tx.send(insight_message(&insight("a", InsightSeverity::Warning))).unwrap();
+
+        let received = tokio::time::timeout(Duration::from_secs(1), stream.next())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(received.id, "a");
+        assert_eq!(received.severity, InsightSeverity::Warning);
+    }
+
+    #[tokio::test]
+    async fn test_min_severity_filters_and_stream_ends_on_close() {
+        let (tx, rx) = broadcast::channel(16);
+        let stream = insight_stream(rx, InsightSeverity::Warning);
+
+        tx.send(insight_message(&insight("info", InsightSeverity::Info))).unwrap();
+        tx.send(insight_message(&insight("crit", InsightSeverity::Critical))).unwrap();
+        drop(tx);
+
+        let ids: Vec<String> = stream.map(|i| i.id).collect().await;
+        assert_eq!(ids, vec!["crit"]);
+    }
+
+    #[tokio::test]
+    async fn test_list_insights_filters_by_severity_and_node() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), vec![]).await;
+        let member = uuid::Uuid::new_v4();
+        {
+            let mut knowledge = state.knowledge.write().unwrap();
+            for (id, severity, nodes) in [
+                ("a", InsightSeverity::Critical, vec![member]),
+                ("b", InsightSeverity::Info, vec![member]),
+                ("c", InsightSeverity::Critical, vec![]),
+            ] {
+                knowledge.insights.push(Insight { related_nodes: nodes, ..insight(id, severity) });
+            }
+        }
+
+        let params = |min_severity: Option<&str>, node: Option<String>| InsightQueryParams {
+            min_severity: min_severity.map(str::to_string),
+            since: None,
+            until: None,
+            node,
+            limit: None,
+            offset: None,
+        };
+        let ids = |response: InsightListResponse| response.insights.into_iter().map(|i| i.id).collect::<Vec<_>>();
+
+        let Json(critical) = list_insights(State(state.clone()), Query(params(Some("critical"), None))).await.unwrap();
+        assert_eq!(ids(critical), vec!["c", "a"]);
+        let Json(for_member) =
+            list_insights(State(state.clone()), Query(params(None, Some(member.to_string())))).await.unwrap();
+        assert_eq!(ids(for_member), vec!["b", "a"]);
+
+        let bad = list_insights(State(state), Query(params(None, Some("M0042".to_string())))).await;
+        assert_eq!(bad.err().unwrap().0, axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn test_parse_severity() {
+        assert_eq!(parse_severity("Critical"), Some(InsightSeverity::Critical));
+        assert_eq!(parse_severity("warning"), Some(InsightSeverity::Warning));
+        assert_eq!(parse_severity("urgent"), None);
+    }
+}
@@ -1,17 +1,22 @@
-//! Computed analytics endpoints: PageRank, communities, degrees,
-//! patterns, co-occurrence, trends, and anomalies.
+//! Computed analytics endpoints: PageRank, communities, degrees, degree anomalies,
+//! patterns, co-occurrence, trends, anomalies, and the live insight stream.
+//! The tabular ones can also be downloaded with `?format=csv|parquet`.
 //!
 //! SRP: exposing precomputed knowledge-store results via REST.
 
 mod anomaly;
 mod cooccurrence;
+mod export;
 mod graph_metrics;
+mod insights;
 mod patterns;
 mod trends;
 
 pub use anomaly::*;
 pub use cooccurrence::*;
+pub use export::ExportFormat;
 pub use graph_metrics::*;
+pub use insights::*;
 pub use patterns::*;
 pub use trends::*;
 
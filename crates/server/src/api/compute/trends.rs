@@ -2,11 +2,19 @@
 
 use std::sync::Arc;
 
-use axum::extract::State;
-use axum::Json;
-use serde::Serialize;
+use axum::extract::{Query, State};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
 
 use crate::state::AppState;
+use super::export::{export_response, ExportFormat, ExportQueryParams, TabularRow};
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct TrendQueryParams {
+    /// Drop trends measured against fewer baseline samples than this.
+    pub min_samples: Option<usize>,
+}
 
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct TrendResponse {
@@ -15,6 +23,44 @@ pub struct TrendResponse {
     pub baseline_mean: f64,
     pub direction: String,
     pub magnitude: f64,
+    /// Baseline observations the trend was measured against.
+    pub sample_count: usize,
+    /// Standard error of the baseline mean.
+    pub standard_error: f64,
+    /// Lower bound of the 95% confidence interval of the baseline mean.
+    pub ci_lower: f64,
+    /// Upper bound of the 95% confidence interval of the baseline mean.
+    pub ci_upper: f64,
+}
+
+impl TabularRow for TrendResponse {
+    fn columns() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("metric", "varchar"),
+            ("current_value", "double"),
+            ("baseline_mean", "double"),
+            ("direction", "varchar"),
+            ("magnitude", "double"),
+            ("sample_count", "bigint"),
+            ("standard_error", "double"),
+            ("ci_lower", "double"),
+            ("ci_upper", "double"),
+        ]
+    }
+
+    fn cells(&self) -> Vec<Option<String>> {
+        vec![
+            Some(self.metric.clone()),
+            Some(self.current_value.to_string()),
+            Some(self.baseline_mean.to_string()),
+            Some(self.direction.clone()),
+            Some(self.magnitude.to_string()),
+            Some(self.sample_count.to_string()),
+            Some(self.standard_error.to_string()),
+            Some(self.ci_lower.to_string()),
+            Some(self.ci_upper.to_string()),
+        ]
+    }
 }
 
 /// Detected metric trends sorted by magnitude descending.
@@ -22,29 +68,86 @@ pub struct TrendResponse {
     get,
     path = "/compute/trends",
     tag = "Compute",
+    params(TrendQueryParams, ExportQueryParams),
     responses(
-        (status = 200, description = "Metric trends", body = Vec<TrendResponse>)
+        (status = 200, description = "Metric trends", content(
+            (Vec<TrendResponse> = "application/json"),
+            (String = "text/csv"),
+            (Vec<u8> = "application/vnd.apache.parquet"),
+        ))
     )
 )]
 pub async fn compute_trends(
     State(state): State<Arc<AppState>>,
-) -> Json<Vec<TrendResponse>> {
+    Query(params): Query<TrendQueryParams>,
+    Query(export): Query<ExportQueryParams>,
+    headers: HeaderMap,
+) -> Response {
+    let format = ExportFormat::negotiate(export.format, &headers);
+    export_response(trend_rows(&state, params.min_samples), format, "trends")
+}
+
+fn trend_rows(state: &AppState, min_samples: Option<usize>) -> Vec<TrendResponse> {
     let knowledge = state.knowledge.read().unwrap();
+    let min_samples = min_samples.unwrap_or(0);
 
     let mut trends: Vec<TrendResponse> = knowledge
         .trends
         .values()
+        .filter(|t| t.sample_count >= min_samples)
         .map(|t| TrendResponse {
             metric: t.metric_name.clone(),
             current_value: t.current,
             baseline_mean: t.baseline,
             direction: format!("{:?}", t.direction),
             magnitude: t.magnitude,
+            sample_count: t.sample_count,
+            standard_error: t.standard_error,
+            ci_lower: t.confidence_interval.lower,
+            ci_upper: t.confidence_interval.upper,
         })
         .collect();
 
     // Sort by magnitude descending.
     trends.sort_by(|a, b| b.magnitude.partial_cmp(&a.magnitude).unwrap_or(std::cmp::Ordering::Equal));
 
-    Json(trends)
+    trends
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stupid_compute::scheduler::types::{ConfidenceInterval, Trend, TrendDirection};
+
+    fn trend(metric: &str, sample_count: usize) -> Trend {
+        Trend {
+            metric_name: metric.to_string(),
+            direction: TrendDirection::Rising,
+            magnitude: 3.0,
+            baseline: 100.0,
+            current: 150.0,
+            sample_count,
+            standard_error: 1.0,
+            confidence_interval: ConfidenceInterval { lower: 98.0, upper: 102.0 },
+        }
+    }
+
+    #[tokio::test]
+    async fn min_samples_filters_low_confidence_trends() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), vec![]).await;
+        {
+            let mut knowledge = state.knowledge.write().unwrap();
+            knowledge.trends.insert("sparse".into(), trend("sparse", 3));
+            knowledge.trends.insert("dense".into(), trend("dense", 100));
+        }
+
+        let all = trend_rows(&state, None);
+        assert_eq!(all.len(), 2);
+
+        let confident = trend_rows(&state, Some(30));
+        assert_eq!(confident.len(), 1);
+        assert_eq!(confident[0].metric, "dense");
+        assert_eq!((confident[0].ci_lower, confident[0].ci_upper), (98.0, 102.0));
+    }
 }
@@ -8,6 +8,7 @@ use axum::Json;
 use crate::athena_connections::{
     AthenaConnectionCredentials, AthenaConnectionInput, AthenaConnectionSafe,
 };
+use crate::connection_test::{self, ConnectionTestResult, LiveProbe};
 use crate::credential_store::CredentialStore;
 use crate::state::AppState;
 
@@ -211,3 +212,50 @@ pub async fn athena_connections_credentials(
         Err(_) => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
+
+/// Test an Athena connection with `ListWorkGroups`.
+///
+/// Tests the submitted connection if a body is sent, otherwise the stored
+/// one. Blank keys in the body fall back to the stored connection's while
+/// the region and endpoint are unchanged.
+#[utoipa::path(
+    post,
+    path = "/athena-connections/{id}/test",
+    tag = "Athena Connections",
+    params(("id" = String, Path, description = "Athena connection ID")),
+    request_body(content = Option<AthenaConnectionInput>, description = "Unsaved Athena connection to test instead of the stored one"),
+    responses(
+        (status = 200, description = "Test outcome", body = ConnectionTestResult),
+        (status = 400, description = "Blank keys for a changed region or endpoint", body = QueryErrorResponse),
+        (status = 404, description = "Not found and no connection submitted"),
+        (status = 500, description = "Internal error")
+    )
+)]
+pub async fn athena_connections_test(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    input: Option<Json<AthenaConnectionInput>>,
+) -> Result<Json<ConnectionTestResult>, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
+    let stored = {
+        let store = state.athena_connections.read().await;
+        store.get_credentials(&id).map_err(|e| {
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(QueryErrorResponse { error: e.to_string() }),
+            )
+        })?
+    };
+    let creds = match (input, stored) {
+        (Some(Json(input)), stored) => input.to_credentials(&id, stored.as_ref()).map_err(|e| {
+            (axum::http::StatusCode::BAD_REQUEST, Json(QueryErrorResponse { error: e.to_string() }))
+        })?,
+        (None, Some(stored)) => stored,
+        (None, None) => {
+            return Err((
+                axum::http::StatusCode::NOT_FOUND,
+                Json(QueryErrorResponse { error: format!("Athena connection '{}' not found", id) }),
+            ))
+        }
+    };
+    Ok(Json(connection_test::test_athena(&LiveProbe, &creds).await))
+}
@@ -5,6 +5,7 @@ use std::sync::Arc;
 use axum::extract::{Path, State};
 use axum::Json;
 
+use crate::connection_test::{self, ConnectionTestResult, LiveProbe};
 use crate::connections::{ConnectionCredentials, ConnectionInput, ConnectionSafe};
 use crate::credential_store::CredentialStore;
 use crate::state::AppState;
@@ -161,3 +162,50 @@ pub async fn connections_credentials(
         Err(_) => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
+
+/// Test a connection's credentials with a Postgres login.
+///
+/// Tests the submitted connection if a body is sent, otherwise the stored
+/// one. Blank passwords in the body fall back to the stored connection's
+/// while the host, port and user are unchanged.
+#[utoipa::path(
+    post,
+    path = "/connections/{id}/test",
+    tag = "DB Connections",
+    params(("id" = String, Path, description = "Connection ID")),
+    request_body(content = Option<ConnectionInput>, description = "Unsaved connection to test instead of the stored one"),
+    responses(
+        (status = 200, description = "Test outcome", body = ConnectionTestResult),
+        (status = 400, description = "Invalid connection string, or blank password for a changed server", body = QueryErrorResponse),
+        (status = 404, description = "Not found and no connection submitted"),
+        (status = 500, description = "Internal error")
+    )
+)]
+pub async fn connections_test(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    input: Option<Json<ConnectionInput>>,
+) -> Result<Json<ConnectionTestResult>, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
+    let stored = {
+        let store = state.connections.read().await;
+        store.get_credentials(&id).map_err(|e| {
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(QueryErrorResponse { error: e.to_string() }),
+            )
+        })?
+    };
+    let creds = match (input, stored) {
+        (Some(Json(input)), stored) => input.to_credentials(&id, stored.as_ref()).map_err(|e| {
+            (axum::http::StatusCode::BAD_REQUEST, Json(QueryErrorResponse { error: e.to_string() }))
+        })?,
+        (None, Some(stored)) => stored,
+        (None, None) => {
+            return Err((
+                axum::http::StatusCode::NOT_FOUND,
+                Json(QueryErrorResponse { error: format!("Connection '{}' not found", id) }),
+            ))
+        }
+    };
+    Ok(Json(connection_test::test_db(&LiveProbe, &creds).await))
+}
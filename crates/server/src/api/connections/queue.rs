@@ -5,6 +5,7 @@ use std::sync::Arc;
 use axum::extract::{Path, State};
 use axum::Json;
 
+use crate::connection_test::{self, ConnectionTestResult, LiveProbe};
 use crate::credential_store::CredentialStore;
 use crate::queue_connections::{
     QueueConnectionCredentials, QueueConnectionInput, QueueConnectionSafe,
@@ -163,3 +164,51 @@ pub async fn queue_connections_credentials(
         Err(_) => Err(axum::http::StatusCode::INTERNAL_SERVER_ERROR),
     }
 }
+
+/// Test a queue connection: SQS `GetQueueAttributes`, or a TCP connect to
+/// the brokers of other providers.
+///
+/// Tests the submitted connection if a body is sent, otherwise the stored
+/// one. Blank keys in the body fall back to the stored connection's while
+/// the queue location is unchanged.
+#[utoipa::path(
+    post,
+    path = "/queue-connections/{id}/test",
+    tag = "Queue Connections",
+    params(("id" = String, Path, description = "Queue connection ID")),
+    request_body(content = Option<QueueConnectionInput>, description = "Unsaved queue connection to test instead of the stored one"),
+    responses(
+        (status = 200, description = "Test outcome", body = ConnectionTestResult),
+        (status = 400, description = "Blank keys for a changed queue location", body = QueryErrorResponse),
+        (status = 404, description = "Not found and no connection submitted"),
+        (status = 500, description = "Internal error")
+    )
+)]
+pub async fn queue_connections_test(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    input: Option<Json<QueueConnectionInput>>,
+) -> Result<Json<ConnectionTestResult>, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
+    let stored = {
+        let store = state.queue_connections.read().await;
+        store.get(&id).map_err(|e| {
+            (
+                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                Json(QueryErrorResponse { error: e.to_string() }),
+            )
+        })?
+    };
+    let config = match (input, stored) {
+        (Some(Json(input)), stored) => input.to_config(&id, stored.as_ref()).map_err(|e| {
+            (axum::http::StatusCode::BAD_REQUEST, Json(QueryErrorResponse { error: e.to_string() }))
+        })?,
+        (None, Some(stored)) => stored,
+        (None, None) => {
+            return Err((
+                axum::http::StatusCode::NOT_FOUND,
+                Json(QueryErrorResponse { error: format!("Queue connection '{}' not found", id) }),
+            ))
+        }
+    };
+    Ok(Json(connection_test::test_queue(&LiveProbe, &config).await))
+}
@@ -1,7 +1,9 @@
 //! OpenAPI documentation aggregator.
 //!
 //! Collects all `#[utoipa::path]`-annotated handlers and `ToSchema`-derived
-//! types into a single OpenAPI 3.1 spec, served via Scalar UI at `/docs`.
+//! types into a single OpenAPI 3.1 spec. The raw spec is served at
+//! `/openapi.json`, browsable via Scalar at `/docs` and Swagger UI at
+//! `/swagger-ui`.
 
 use utoipa::OpenApi;
 
@@ -18,6 +20,7 @@ use utoipa::OpenApi;
         (name = "Graph", description = "Knowledge-graph node queries and force-layout data"),
         (name = "Compute", description = "PageRank, communities, co-occurrence, trends, and anomalies"),
         (name = "Query", description = "Natural-language query via LLM-generated plans"),
+        (name = "Audit", description = "Audit trail of executed queries"),
         (name = "Embeddings", description = "Document upload, semantic search via pgvector"),
         (name = "Connections", description = "Database connection CRUD with encrypted credential storage"),
         (name = "Queue Connections", description = "Message-queue connection CRUD"),
@@ -29,6 +32,11 @@ use utoipa::OpenApi;
         (name = "Rules", description = "Generic rule CRUD (anomaly, schema, feature, scoring, trend, pattern)"),
         (name = "Agent Groups", description = "Agent group management and membership"),
         (name = "Telemetry", description = "Per-agent execution telemetry and aggregated stats"),
+        (name = "Bundeswehr", description = "Agent fleet overview, agent definitions, and standalone skills"),
+        (name = "Prompts", description = "Externalized LLM prompt templates"),
+        (name = "Ingestion", description = "Ingestion source CRUD, manual triggers, and job progress"),
+        (name = "Stille Post", description = "Report pipelines: agents, data sources, schedules, runs, reports, deliveries, and YAML import/export"),
+        (name = "Villa", description = "LLM-driven dashboard layout suggestions"),
     ),
     paths(
         // Health
@@ -37,6 +45,9 @@ use utoipa::OpenApi;
         crate::api::health::stats,
         crate::api::health::queue_status,
         crate::api::health::scheduler_metrics,
+        crate::api::health::debug_config,
+        crate::metrics::metrics,
+        crate::ws::ws_upgrade,
         // Catalog
         crate::catalog_api::get_catalog,
         crate::catalog_api::get_manifest,
@@ -44,6 +55,7 @@ use utoipa::OpenApi;
         crate::catalog_api::list_segments,
         crate::catalog_api::get_segment,
         crate::catalog_api::delete_segment,
+        crate::catalog_api::rebuild_segment,
         crate::catalog_api::list_externals,
         crate::catalog_api::get_external,
         crate::catalog_api::add_external,
@@ -53,6 +65,7 @@ use utoipa::OpenApi;
         crate::catalog_api::list_columns,
         crate::catalog_api::create_snapshot,
         crate::catalog_api::execute_query,
+        crate::api::segments::segments_import,
         // Graph
         crate::api::graph::graph_nodes,
         crate::api::graph::graph_node_by_id,
@@ -61,15 +74,22 @@ use utoipa::OpenApi;
         crate::api::compute::compute_pagerank,
         crate::api::compute::compute_communities,
         crate::api::compute::compute_degrees,
+        crate::api::compute::compute_degree_anomalies,
         crate::api::compute::compute_patterns,
         crate::api::compute::compute_cooccurrence,
         crate::api::compute::compute_trends,
         crate::api::compute::compute_anomalies,
+        crate::api::compute::list_insights,
+        crate::api::compute::insights_stream,
         // Query
         crate::api::query::query,
+        crate::api::query::query_stream,
+        // Audit
+        crate::api::audit::audit_queries,
         // Embeddings
         crate::api::embedding::upload,
         crate::api::embedding::search,
+        crate::api::embedding::embed,
         crate::api::embedding::list_documents,
         crate::api::embedding::delete_document,
         // Connections
@@ -79,6 +99,7 @@ use utoipa::OpenApi;
         crate::api::connections::connections_update,
         crate::api::connections::connections_delete,
         crate::api::connections::connections_credentials,
+        crate::api::connections::connections_test,
         // Queue Connections
         crate::api::connections::queue_connections_list,
         crate::api::connections::queue_connections_add,
@@ -86,6 +107,7 @@ use utoipa::OpenApi;
         crate::api::connections::queue_connections_update,
         crate::api::connections::queue_connections_delete,
         crate::api::connections::queue_connections_credentials,
+        crate::api::connections::queue_connections_test,
         // Athena Connections
         crate::api::connections::athena_connections_list,
         crate::api::connections::athena_connections_add,
@@ -93,6 +115,7 @@ use utoipa::OpenApi;
         crate::api::connections::athena_connections_update,
         crate::api::connections::athena_connections_delete,
         crate::api::connections::athena_connections_credentials,
+        crate::api::connections::athena_connections_test,
         // Athena Queries
         crate::api::athena_query::athena_query_sse,
         crate::api::athena_query::athena_query_parquet,
@@ -105,6 +128,18 @@ use utoipa::OpenApi;
         crate::api::agents::agents_chat,
         crate::api::agents::teams_execute,
         crate::api::agents::teams_strategies,
+        // Bundeswehr
+        crate::api::agents::agents_get,
+        crate::api::agents::agents_create,
+        crate::api::agents::agents_update,
+        crate::api::agents::agents_delete,
+        crate::api::agents::agents_reload,
+        crate::api::agents::bundeswehr_overview,
+        crate::api::agents::skills_list,
+        crate::api::agents::skills_get,
+        crate::api::agents::skills_create,
+        crate::api::agents::skills_update,
+        crate::api::agents::skills_delete,
         // Sessions
         crate::api::agents::sessions_list,
         crate::api::agents::sessions_create,
@@ -115,6 +150,8 @@ use utoipa::OpenApi;
         crate::api::agents::sessions_execute_team,
         crate::api::agents::sessions_execute,
         crate::api::agents::sessions_stream,
+        crate::api::agents::tool_approvals_list,
+        crate::api::agents::tool_approvals_decide,
         // Anomaly Rules
         crate::anomaly_rules::list_anomaly_rules,
         crate::anomaly_rules::create_anomaly_rule,
@@ -127,6 +164,10 @@ use utoipa::OpenApi;
         crate::anomaly_rules::test_notify_rule,
         crate::anomaly_rules::rule_history,
         crate::anomaly_rules::rule_logs,
+        crate::anomaly_rules::rule_runs,
+        crate::anomaly_rules::rule_escalations,
+        crate::anomaly_rules::acknowledge_trigger,
+        crate::anomaly_rules::bulk_anomaly_rules,
         // Rules
         crate::rules::list_rules,
         crate::rules::get_rule,
@@ -149,16 +190,72 @@ use utoipa::OpenApi;
         crate::api::telemetry::telemetry_events,
         crate::api::telemetry::telemetry_stats,
         crate::api::telemetry::telemetry_overview,
+        // Prompts
+        crate::api::prompts::prompts_list,
+        crate::api::prompts::prompts_get,
+        crate::api::prompts::prompts_update,
+        // Ingestion
+        crate::api::ingestion::sources::ingestion_sources_list,
+        crate::api::ingestion::sources::ingestion_sources_create,
+        crate::api::ingestion::sources::ingestion_sources_get,
+        crate::api::ingestion::sources::ingestion_sources_update,
+        crate::api::ingestion::sources::ingestion_sources_delete,
+        crate::api::ingestion::sources::ingestion_sources_trigger,
+        crate::api::ingestion::jobs::ingestion_jobs_list,
+        crate::api::ingestion::jobs::ingestion_jobs_get,
+        // Stille Post
+        crate::api::stille_post::sp_pipelines_list,
+        crate::api::stille_post::sp_pipelines_create,
+        crate::api::stille_post::sp_pipelines_get,
+        crate::api::stille_post::sp_pipelines_update,
+        crate::api::stille_post::sp_pipelines_delete,
+        crate::api::stille_post::sp_data_sources_list,
+        crate::api::stille_post::sp_data_sources_create,
+        crate::api::stille_post::sp_data_sources_get,
+        crate::api::stille_post::sp_data_sources_update,
+        crate::api::stille_post::sp_data_sources_delete,
+        crate::api::stille_post::sp_data_sources_test,
+        crate::api::stille_post::sp_data_sources_upload,
+        crate::api::stille_post::sp_deliveries_list,
+        crate::api::stille_post::sp_deliveries_create,
+        crate::api::stille_post::sp_deliveries_update,
+        crate::api::stille_post::sp_deliveries_delete,
+        crate::api::stille_post::sp_deliveries_test,
+        crate::api::stille_post::sp_schedules_list,
+        crate::api::stille_post::sp_schedules_create,
+        crate::api::stille_post::sp_schedules_update,
+        crate::api::stille_post::sp_schedules_delete,
+        crate::api::stille_post::sp_agents_list,
+        crate::api::stille_post::sp_agents_create,
+        crate::api::stille_post::sp_agents_get,
+        crate::api::stille_post::sp_agents_update,
+        crate::api::stille_post::sp_agents_delete,
+        crate::api::stille_post::sp_runs_list,
+        crate::api::stille_post::sp_runs_get,
+        crate::api::stille_post::sp_runs_create,
+        crate::api::stille_post::sp_runs_delete,
+        crate::api::stille_post::sp_reports_list,
+        crate::api::stille_post::sp_reports_get,
+        crate::api::stille_post::sp_export,
+        crate::api::stille_post::sp_import,
+        // Villa
+        crate::api::villa::suggest::suggest,
     ),
     components(schemas(
         // Shared
         crate::api::NotReadyResponse,
         crate::api::QueryErrorResponse,
+        crate::state::LoadingStatus,
         // Health
         crate::api::health::HealthResponse,
+        crate::health_check::ComponentHealth,
+        crate::health_check::ComponentStatus,
+        crate::health_check::OverallStatus,
         crate::api::health::StatsResponse,
         // Graph
         crate::api::graph::NodeResponse,
+        crate::api::graph::NodesPageResponse,
+        crate::api::segments::SegmentImportResponse,
         crate::api::graph::NodeDetailResponse,
         crate::api::graph::NeighborResponse,
         crate::api::graph::ForceGraphResponse,
@@ -169,24 +266,34 @@ use utoipa::OpenApi;
         crate::api::compute::CommunitySummary,
         crate::api::compute::CommunityNode,
         crate::api::compute::DegreeEntry,
+        crate::api::compute::DegreeAnomalyEntry,
+        crate::api::compute::InsightEntry,
+        crate::api::compute::InsightListResponse,
         crate::api::compute::PatternResponse,
         crate::api::compute::CooccurrenceEntry,
         crate::api::compute::CooccurrenceResponse,
         crate::api::compute::TrendResponse,
         crate::api::compute::FeatureDimension,
         crate::api::compute::AnomalyEntry,
+        crate::api::compute::ExportFormat,
         // Query
         crate::api::query::QueryRequest,
         crate::api::query::QueryResponse,
+        crate::query_audit::QueryAuditEntry,
+        crate::query_audit::QueryAuditPage,
         // Embeddings
         crate::api::embedding::SearchRequest,
         crate::api::embedding::UploadResponse,
         crate::api::embedding::SearchResponse,
+        crate::api::embedding::EmbedRequest,
+        crate::api::embedding::EmbedResponse,
+        crate::embedding_batch::EmbeddedText,
         crate::api::embedding::DocumentListResponse,
         // Athena Query
         crate::api::athena_query::AthenaQueryRequest,
         // Connections
         crate::connections::ConnectionSafe,
+        crate::connection_test::ConnectionTestResult,
         crate::connections::ConnectionCredentials,
         crate::connections::ConnectionInput,
         crate::queue_connections::QueueConnectionSafe,
@@ -208,6 +315,8 @@ use utoipa::OpenApi;
         crate::api::agents::SessionExecuteTeamRequest,
         crate::api::agents::SessionExecuteRequest,
         crate::api::agents::SessionStreamRequest,
+        crate::api::agents::ToolApprovalRequest,
+        crate::tool_approvals::PendingApproval,
         // Agent Groups
         crate::api::agent_groups::CreateGroupRequest,
         crate::api::agent_groups::UpdateGroupRequest,
@@ -224,8 +333,21 @@ use utoipa::OpenApi;
         crate::anomaly_rules::RuleSummary,
         crate::anomaly_rules::RunResult,
         crate::anomaly_rules::TestNotifyResult,
+        crate::rule_actions::EnrichmentOutcome,
+        crate::rule_actions::EnrichedMatch,
+        crate::rule_actions::DispatchOutcome,
+        crate::rule_escalation::EscalationRecord,
+        crate::rule_escalation::EscalationState,
         crate::anomaly_rules::MatchSummary,
         crate::anomaly_rules::TriggerEntry,
+        crate::anomaly_rules::BulkOperation,
+        crate::anomaly_rules::BulkSelector,
+        crate::anomaly_rules::BulkRequest,
+        crate::anomaly_rules::BulkItemResult,
+        crate::anomaly_rules::BulkResult,
+        crate::rule_run_log::RuleRunRecord,
+        crate::rule_run_log::RunTrigger,
+        crate::rule_run_log::RunLogPage,
         // Rules
         crate::rules::RecentTrigger,
         crate::rules::GenericRuleSummary,
@@ -236,6 +358,121 @@ use utoipa::OpenApi;
         crate::api::telemetry::TelemetryEventsResponse,
         crate::api::telemetry::TelemetryStatsResponse,
         crate::api::telemetry::TelemetryOverviewResponse,
+        // Bundeswehr
+        crate::api::agents::BundeswehrOverview,
+        crate::api::agents::SkillListResponse,
+        crate::api::agents::SkillInfo,
+        crate::api::agents::SkillDetail,
+        crate::api::agents::SkillRequest,
+        // Prompts
+        crate::api::prompts::PromptSummary,
+        crate::api::prompts::PromptDetail,
+        crate::api::prompts::UpdatePromptRequest,
+        // Ingestion
+        crate::ingestion::types::IngestionSource,
+        crate::ingestion::source_store::CreateIngestionSource,
+        crate::ingestion::source_store::UpdateIngestionSource,
+        // Stille Post
+        crate::api::stille_post::SpPipeline,
+        crate::api::stille_post::SpPipelineListItem,
+        crate::api::stille_post::SpPipelineWithSteps,
+        crate::api::stille_post::SpPipelineStep,
+        crate::api::stille_post::CreatePipelineRequest,
+        crate::api::stille_post::CreatePipelineStepRequest,
+        crate::api::stille_post::UpdatePipelineRequest,
+        crate::api::stille_post::SpDataSource,
+        crate::api::stille_post::CreateDataSourceRequest,
+        crate::api::stille_post::UpdateDataSourceRequest,
+        crate::api::stille_post::SpDelivery,
+        crate::api::stille_post::CreateDeliveryRequest,
+        crate::api::stille_post::UpdateDeliveryRequest,
+        crate::api::stille_post::SpSchedule,
+        crate::api::stille_post::SpScheduleWithPipeline,
+        crate::api::stille_post::CreateScheduleRequest,
+        crate::api::stille_post::UpdateScheduleRequest,
+        crate::api::stille_post::SpAgent,
+        crate::api::stille_post::CreateAgentRequest,
+        crate::api::stille_post::UpdateAgentRequest,
+        crate::api::stille_post::SpRun,
+        crate::api::stille_post::SpRunWithSteps,
+        crate::api::stille_post::SpStepResult,
+        crate::api::stille_post::SpReport,
+        crate::api::stille_post::TriggerRunRequest,
+        crate::api::stille_post::yaml_types::SpImportRequest,
+        crate::api::stille_post::yaml_types::SpImportResult,
+        crate::api::stille_post::yaml_types::SpImportedResource,
+        // Villa
+        crate::api::villa::types::VillaSuggestRequest,
+        crate::api::villa::types::VillaSuggestResponse,
     ))
 )]
 pub struct ApiDoc;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    /// Sources that register routes with `.route("...")`.
+    const ROUTER_SOURCES: &[&str] = &[
+        include_str!("../router.rs"),
+        include_str!("../anomaly_rules/mod.rs"),
+        include_str!("../rules/mod.rs"),
+        include_str!("../catalog_api/mod.rs"),
+    ];
+
+    /// Every path literal passed to `.route(`.
+    fn registered_paths() -> Vec<&'static str> {
+        let mut paths = Vec::new();
+        for source in ROUTER_SOURCES {
+            for (start, _) in source.match_indices(".route(") {
+                let rest = source[start + ".route(".len()..].trim_start();
+                let Some(literal) = rest.strip_prefix('"') else { continue };
+                paths.push(&literal[..literal.find('"').unwrap()]);
+            }
+        }
+        paths
+    }
+
+    #[test]
+    fn test_spec_covers_every_registered_route() {
+        let spec = ApiDoc::openapi();
+        let paths = registered_paths();
+        assert!(paths.len() > 100, "route scan found only {} paths", paths.len());
+
+        let missing: Vec<_> = paths.iter().filter(|p| !spec.paths.paths.contains_key(**p)).collect();
+        assert!(missing.is_empty(), "routes missing from the OpenAPI spec: {missing:?}");
+    }
+
+    #[test]
+    fn test_not_ready_response_is_documented() {
+        let spec = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let not_ready = &spec["paths"]["/graph/nodes"]["get"]["responses"]["503"];
+        assert_eq!(
+            not_ready["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/NotReadyResponse"
+        );
+        let schemas = &spec["components"]["schemas"];
+        assert_eq!(schemas["NotReadyResponse"]["properties"]["loading"]["$ref"], "#/components/schemas/LoadingStatus");
+        assert!(schemas["LoadingStatus"]["properties"]["phase"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_openapi_json_and_swagger_ui_are_served() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), vec![]).await;
+        let app = crate::router::build_router(state);
+
+        let response = app.clone().oneshot(Request::get("/openapi.json").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let spec: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(spec["paths"]["/sp/pipelines"]["get"].is_object());
+
+        let response = app.oneshot(Request::get("/swagger-ui/").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -8,6 +8,7 @@ use tokio::fs;
 use tracing::{info, warn};
 use uuid::Uuid;
 
+use crate::embedding_batch::EmbeddedText;
 use crate::state::AppState;
 use crate::vector_store::{self, ChunkInsert};
 
@@ -39,6 +40,20 @@ pub struct SearchResponse {
     pub results: Vec<vector_store::SearchResult>,
 }
 
+#[derive(Deserialize, utoipa::ToSchema)]
+pub struct EmbedRequest {
+    pub texts: Vec<String>,
+}
+
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct EmbedResponse {
+    /// One entry per input text, in input order.
+    pub embeddings: Vec<EmbeddedText>,
+    pub cache_hits: usize,
+    /// Provider calls made for the texts that missed the cache.
+    pub provider_calls: usize,
+}
+
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct DocumentListResponse {
     #[schema(value_type = Vec<Object>)]
@@ -253,6 +268,55 @@ pub async fn search(
     Ok(Json(SearchResponse { results }))
 }
 
+// ── POST /embeddings/embed ────────────────────────
+
+/// Most texts accepted in one embed request.
+const MAX_EMBED_TEXTS: usize = 10_000;
+
+/// Embed a batch of texts
+///
+/// Returns one embedding per text in input order. Texts embedded before are
+/// served from an in-memory cache; the rest are sent to the provider in
+/// batches, with a cap on concurrent provider calls.
+#[utoipa::path(
+    post,
+    path = "/embeddings/embed",
+    tag = "Embeddings",
+    request_body = EmbedRequest,
+    responses(
+        (status = 200, description = "Embeddings in input order", body = EmbedResponse),
+        (status = 400, description = "Too many texts", body = String),
+        (status = 503, description = "Embedding provider not configured", body = String)
+    )
+)]
+pub async fn embed(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<EmbedRequest>,
+) -> Result<Json<EmbedResponse>, (StatusCode, String)> {
+    let embedder = state
+        .embedder
+        .as_ref()
+        .ok_or((StatusCode::SERVICE_UNAVAILABLE, "Embedding provider not configured".to_string()))?;
+    if req.texts.len() > MAX_EMBED_TEXTS {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            format!("At most {} texts per request, got {}", MAX_EMBED_TEXTS, req.texts.len()),
+        ));
+    }
+
+    let outcome = state
+        .batch_embedder
+        .embed(embedder.as_ref(), &req.texts)
+        .await
+        .map_err(|e| (StatusCode::BAD_GATEWAY, format!("Embedding failed: {e}")))?;
+
+    Ok(Json(EmbedResponse {
+        cache_hits: outcome.embeddings.iter().filter(|e| e.cached).count(),
+        provider_calls: outcome.provider_calls,
+        embeddings: outcome.embeddings,
+    }))
+}
+
 // ── GET /embeddings/documents ─────────────────────
 
 /// List all embedded documents
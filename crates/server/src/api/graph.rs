@@ -7,6 +7,8 @@ use std::sync::Arc;
 use axum::extract::{Path, State};
 use axum::Json;
 use serde::Serialize;
+use stupid_core::EntityType;
+use stupid_graph::NodeFilter;
 
 use crate::state::AppState;
 
@@ -21,36 +23,50 @@ pub struct NodeResponse {
     pub key: String,
 }
 
-/// List graph nodes with optional entity type filter and limit.
+/// Page of graph nodes plus the total number of matches.
+#[derive(Serialize, utoipa::ToSchema)]
+pub struct NodesPageResponse {
+    /// Number of nodes matching the filters across all pages.
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    pub nodes: Vec<NodeResponse>,
+}
+
+/// List graph nodes, filtered by entity type and key prefix, ordered by node id.
 #[utoipa::path(
     get,
     path = "/graph/nodes",
     tag = "Graph",
     params(NodeQueryParams),
     responses(
-        (status = 200, description = "List of graph nodes", body = Vec<NodeResponse>),
+        (status = 200, description = "Page of graph nodes", body = NodesPageResponse),
         (status = 503, description = "Service not ready", body = NotReadyResponse)
     )
 )]
 pub async fn graph_nodes(
     State(state): State<Arc<AppState>>,
     axum::extract::Query(params): axum::extract::Query<NodeQueryParams>,
-) -> Result<Json<Vec<NodeResponse>>, (axum::http::StatusCode, Json<NotReadyResponse>)> {
+) -> Result<Json<NodesPageResponse>, (axum::http::StatusCode, Json<NotReadyResponse>)> {
     super::require_ready(&state).await?;
 
-    let graph = state.graph.read().await;
     let limit = params.limit.unwrap_or(100).min(1000);
-    let entity_filter = params.entity_type.as_deref();
+    let offset = params.offset.unwrap_or(0);
+
+    // An unknown entity type matches nothing, rather than being ignored.
+    let entity_type = match params.entity_type.as_deref().map(str::parse::<EntityType>) {
+        Some(Err(_)) => {
+            return Ok(Json(NodesPageResponse { total: 0, offset, limit, nodes: Vec::new() }));
+        }
+        parsed => parsed.and_then(Result::ok),
+    };
+    let filter = NodeFilter { entity_type, key_prefix: params.key_prefix.as_deref() };
 
-    let nodes: Vec<NodeResponse> = graph
+    let graph = state.graph.read().await;
+    let page = graph.nodes_page(filter, offset, limit);
+    let nodes = page
         .nodes
-        .values()
-        .filter(|n| {
-            entity_filter
-                .map(|f| n.entity_type.to_string().eq_ignore_ascii_case(f))
-                .unwrap_or(true)
-        })
-        .take(limit)
+        .into_iter()
         .map(|n| NodeResponse {
             id: n.id.to_string(),
             entity_type: n.entity_type.to_string(),
@@ -58,15 +74,19 @@ pub async fn graph_nodes(
         })
         .collect();
 
-    Ok(Json(nodes))
+    Ok(Json(NodesPageResponse { total: page.total, offset, limit, nodes }))
 }
 
 #[derive(serde::Deserialize, utoipa::IntoParams)]
 pub struct NodeQueryParams {
     /// Maximum number of nodes to return (default 100, max 1000).
     pub limit: Option<usize>,
+    /// Number of matching nodes to skip (default 0).
+    pub offset: Option<usize>,
     /// Filter by entity type (case-insensitive).
     pub entity_type: Option<String>,
+    /// Only nodes whose key starts with this, e.g. `device:`.
+    pub key_prefix: Option<String>,
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
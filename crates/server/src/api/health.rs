@@ -6,40 +6,101 @@
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
 
-use axum::extract::State;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::Json;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
+use stupid_queue::{HealthStatus, HealthThresholds};
+
+use crate::connection_test::LiveProbe;
 use crate::credential_store::CredentialStore;
+use crate::health_check::{self, ComponentHealth, OverallStatus};
 use crate::state::AppState;
 
 // ── Health & Loading ──────────────────────────────────────────────
 
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct HealthResponse {
-    pub status: &'static str,
+    pub status: OverallStatus,
     pub version: &'static str,
     pub data_ready: bool,
     pub loading_phase: &'static str,
+    /// Readiness of data loading, catalog, scheduler and queue consumers.
+    pub components: Vec<ComponentHealth>,
+    /// S3, Athena and LLM probes; only present for `?deep=true`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dependencies: Option<Vec<ComponentHealth>>,
+}
+
+#[derive(Deserialize, utoipa::IntoParams)]
+pub struct HealthParams {
+    /// Also probe the configured external dependencies. Requires an API key
+    /// when auth is enabled.
+    #[serde(default)]
+    pub deep: bool,
 }
 
-/// Basic health check returning server status and readiness.
+/// Server health with per-component readiness.
+///
+/// The status is `unhealthy` (503) when data loading failed, `starting`
+/// while it runs, and `degraded` when a non-critical component or
+/// dependency is down but the server can still serve.
+///
+/// The route is public, so without a valid API key component details are
+/// left out and `deep` is refused.
 #[utoipa::path(
     get,
     path = "/health",
     tag = "Health",
+    params(HealthParams),
     responses(
-        (status = 200, description = "Server is running", body = HealthResponse)
+        (status = 200, description = "Server is running", body = HealthResponse),
+        (status = 401, description = "`deep` without a valid API key", body = crate::api::QueryErrorResponse),
+        (status = 503, description = "A critical component is down", body = HealthResponse)
     )
 )]
-pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse> {
+pub async fn health(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<HealthParams>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<HealthResponse>), (StatusCode, Json<crate::api::QueryErrorResponse>)> {
+    let authorized = state.api_keys.authorizes(&headers);
+    if params.deep && !authorized {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            Json(crate::api::QueryErrorResponse { error: "/health?deep=true requires a valid API key".to_string() }),
+        ));
+    }
+
     let status = state.loading.to_status().await;
-    Json(HealthResponse {
-        status: "ok",
-        version: "0.1.0",
-        data_ready: status.is_ready,
-        loading_phase: status.phase,
-    })
+    let mut components = health_check::components(&state).await;
+    let dependencies = if params.deep {
+        Some(health_check::dependencies(&state, &LiveProbe).await)
+    } else {
+        None
+    };
+
+    let all_checks: Vec<_> = components.iter().chain(dependencies.iter().flatten()).cloned().collect();
+    let overall = health_check::overall_status(&all_checks);
+    let code = match overall {
+        OverallStatus::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        _ => StatusCode::OK,
+    };
+    if !authorized {
+        components = components.into_iter().map(ComponentHealth::public).collect();
+    }
+    Ok((
+        code,
+        Json(HealthResponse {
+            status: overall,
+            version: "0.1.0",
+            data_ready: status.is_ready,
+            loading_phase: status.phase,
+            components,
+            dependencies,
+        }),
+    ))
 }
 
 /// Current loading progress with phase, progress count, and elapsed time.
@@ -48,7 +109,7 @@ pub async fn health(State(state): State<Arc<AppState>>) -> Json<HealthResponse>
     path = "/loading",
     tag = "Health",
     responses(
-        (status = 200, description = "Loading progress", body = Object)
+        (status = 200, description = "Loading progress", body = crate::state::LoadingStatus)
     )
 )]
 pub async fn loading(
@@ -135,26 +196,18 @@ pub async fn queue_status(State(state): State<Arc<AppState>>) -> Json<serde_json
 
     let metrics_map = state.queue_metrics.read().unwrap();
 
+    let thresholds = HealthThresholds::default();
+
     let mut queues = serde_json::Map::new();
     for (id, m) in metrics_map.iter() {
-        let batches = m.batches_processed.load(Ordering::Relaxed);
-        let total_time_us = m.total_processing_time_us.load(Ordering::Relaxed);
-        let avg_latency_ms = if batches > 0 {
-            (total_time_us as f64 / batches as f64) / 1000.0
-        } else {
-            0.0
-        };
-
-        queues.insert(id.clone(), serde_json::json!({
-            "enabled": m.enabled.load(Ordering::Relaxed),
-            "connected": m.connected.load(Ordering::Relaxed),
-            "messages_received": m.messages_received.load(Ordering::Relaxed),
-            "messages_processed": m.messages_processed.load(Ordering::Relaxed),
-            "messages_failed": m.messages_failed.load(Ordering::Relaxed),
-            "batches_processed": batches,
-            "avg_batch_latency_ms": avg_latency_ms,
-            "last_poll_epoch_ms": m.last_poll_epoch_ms.load(Ordering::Relaxed),
-        }));
+        let snapshot = m.snapshot();
+        let health = snapshot.health(&thresholds);
+        let mut entry = serde_json::to_value(&snapshot).unwrap_or(serde_json::Value::Null);
+        if let Some(obj) = entry.as_object_mut() {
+            obj.insert("health".into(), serde_json::json!(health));
+            obj.insert("healthy".into(), serde_json::json!(health == HealthStatus::Healthy));
+        }
+        queues.insert(id.clone(), entry);
     }
 
     Json(serde_json::json!({"enabled": true, "queues": queues}))
@@ -183,3 +236,138 @@ pub async fn scheduler_metrics(
         None => Json(serde_json::json!({"status": "scheduler not started"})),
     }
 }
+
+// ── Debug config ──────────────────────────────────────────────
+
+/// Effective configuration with secrets redacted, plus the active profile
+/// and which settings came from the environment.
+///
+/// Only served when API key auth is enabled, so it is never open to anyone
+/// who can reach the port.
+#[utoipa::path(
+    get,
+    path = "/debug/config",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Redacted effective configuration", body = Object),
+        (status = 403, description = "API key auth is disabled", body = String)
+    )
+)]
+pub async fn debug_config(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<serde_json::Value>, (StatusCode, String)> {
+    if !state.api_keys.is_enabled() {
+        return Err((
+            StatusCode::FORBIDDEN,
+            "/debug/config requires API key auth (set API_KEYS)".to_string(),
+        ));
+    }
+    let config = &state.config;
+    Ok(Json(serde_json::json!({
+        "profile": config.profile_label(),
+        "available_profiles": stupid_core::Config::available_profiles(),
+        "overrides": config.overrides(),
+        "config": config.redacted(),
+    })))
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    use stupid_core::config::ApiKey;
+
+    #[tokio::test]
+    async fn test_debug_config_redacts_secrets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let key = ApiKey { label: "ops".to_string(), key: "ops-k3y".to_string() };
+        let mut state = crate::startup::test_app_state(tmp.path(), vec![key]).await;
+        let config = &mut std::sync::Arc::get_mut(&mut state).unwrap().config;
+        config.aws.secret_access_key = Some("aws-s3cr3t".to_string());
+        config.postgres.pg_url = Some("postgres://app:pg-s3cr3t@db/app".to_string());
+        config.llm.anthropic_api_key = Some("llm-s3cr3t".to_string());
+        let app = crate::router::build_router(state);
+
+        let request = Request::get("/debug/config").header("x-api-key", "ops-k3y").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let body = String::from_utf8_lossy(&bytes);
+        for secret in ["ops-k3y", "aws-s3cr3t", "pg-s3cr3t", "llm-s3cr3t"] {
+            assert!(!body.contains(secret), "{secret} leaked: {body}");
+        }
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["profile"], "default");
+        assert_eq!(json["config"]["aws"]["secret_access_key"], "***");
+    }
+
+    #[tokio::test]
+    async fn test_health_reports_components_and_fails_when_loading_failed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state.clone());
+
+        let response = app.clone().oneshot(Request::get("/health").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["status"], "ok");
+        let names: Vec<_> = json["components"].as_array().unwrap().iter().map(|c| c["name"].clone()).collect();
+        assert_eq!(names, ["loading", "catalog", "scheduler", "queues"]);
+        assert!(json.get("dependencies").is_none(), "dependencies are only probed when deep");
+
+        state.loading.set_phase(crate::state::LoadingPhase::Failed("disk full".into())).await;
+        let response = app.oneshot(Request::get("/health").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["status"], "unhealthy");
+        assert_eq!(json["components"][0]["detail"], "disk full");
+    }
+
+    #[tokio::test]
+    async fn test_health_details_need_an_api_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let key = ApiKey { label: "ops".to_string(), key: "ops-k3y".to_string() };
+        let state = crate::startup::test_app_state(tmp.path(), vec![key]).await;
+        state.loading.set_phase(crate::state::LoadingPhase::Failed("no such bucket: s3://acme-events".into())).await;
+        let app = crate::router::build_router(state);
+        let get = |uri: &str, key: Option<&str>| {
+            let mut request = Request::get(uri);
+            if let Some(key) = key {
+                request = request.header("x-api-key", key);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        let response = get("/health", None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        assert!(!String::from_utf8_lossy(&bytes).contains("acme-events"));
+
+        let response = get("/health", Some("ops-k3y")).await.unwrap();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["components"][0]["detail"], "no such bucket: s3://acme-events");
+
+        assert_eq!(get("/health?deep=true", None).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(get("/health?deep=true", Some("wrong")).await.unwrap().status(), StatusCode::UNAUTHORIZED);
+        let response = get("/health?deep=true", Some("ops-k3y")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(json["dependencies"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_debug_config_needs_auth_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state);
+
+        let response = app.oneshot(Request::get("/debug/config").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -9,6 +9,7 @@ use axum::Json;
 use serde_json::{json, Value};
 use uuid::Uuid;
 
+use crate::api::QueryErrorResponse;
 use crate::ingestion::types::IngestionJob;
 use crate::state::AppState;
 
@@ -38,6 +39,14 @@ fn job_to_json(job: &IngestionJob) -> Value {
 }
 
 /// GET /api/ingestion/jobs — list all active/recent jobs.
+#[utoipa::path(
+    get,
+    path = "/api/ingestion/jobs",
+    tag = "Ingestion",
+    responses(
+        (status = 200, description = "Active and recent ingestion jobs", body = Vec<Object>)
+    )
+)]
 pub async fn ingestion_jobs_list(
     State(state): State<Arc<AppState>>,
 ) -> Json<Value> {
@@ -47,6 +56,18 @@ pub async fn ingestion_jobs_list(
 }
 
 /// GET /api/ingestion/jobs/{id} — get a single job by UUID.
+#[utoipa::path(
+    get,
+    path = "/api/ingestion/jobs/{id}",
+    tag = "Ingestion",
+    params(
+        ("id" = String, Path, description = "Ingestion job ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Ingestion job progress", body = Object),
+        (status = 404, description = "Job not found", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_jobs_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
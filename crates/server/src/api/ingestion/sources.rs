@@ -8,10 +8,11 @@ use axum::Json;
 use serde_json::{json, Value};
 use uuid::Uuid;
 
+use crate::api::QueryErrorResponse;
 use crate::ingestion::source_store::{
     CreateIngestionSource, IngestionSourceStore, UpdateIngestionSource,
 };
-use crate::ingestion::types::TriggerKind;
+use crate::ingestion::types::{IngestionSource, TriggerKind};
 use crate::ingestion::job_runner::spawn_ingestion_job;
 use crate::state::AppState;
 
@@ -35,6 +36,16 @@ fn store_err(
 }
 
 /// GET /api/ingestion/sources
+#[utoipa::path(
+    get,
+    path = "/api/ingestion/sources",
+    tag = "Ingestion",
+    responses(
+        (status = 200, description = "All ingestion sources", body = Vec<IngestionSource>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_sources_list(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
@@ -44,6 +55,18 @@ pub async fn ingestion_sources_list(
 }
 
 /// POST /api/ingestion/sources
+#[utoipa::path(
+    post,
+    path = "/api/ingestion/sources",
+    tag = "Ingestion",
+    request_body = CreateIngestionSource,
+    responses(
+        (status = 201, description = "Source created", body = IngestionSource),
+        (status = 400, description = "Invalid source config", body = QueryErrorResponse),
+        (status = 409, description = "Source name already exists", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_sources_create(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateIngestionSource>,
@@ -59,6 +82,19 @@ pub async fn ingestion_sources_create(
 }
 
 /// GET /api/ingestion/sources/{id}
+#[utoipa::path(
+    get,
+    path = "/api/ingestion/sources/{id}",
+    tag = "Ingestion",
+    params(
+        ("id" = String, Path, description = "Ingestion source ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Ingestion source", body = IngestionSource),
+        (status = 404, description = "Source not found", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_sources_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -77,6 +113,21 @@ pub async fn ingestion_sources_get(
 }
 
 /// PUT /api/ingestion/sources/{id}
+#[utoipa::path(
+    put,
+    path = "/api/ingestion/sources/{id}",
+    tag = "Ingestion",
+    params(
+        ("id" = String, Path, description = "Ingestion source ID (UUID)")
+    ),
+    request_body = UpdateIngestionSource,
+    responses(
+        (status = 200, description = "Source updated", body = IngestionSource),
+        (status = 400, description = "Invalid source config", body = QueryErrorResponse),
+        (status = 404, description = "Source not found", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_sources_update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -90,6 +141,19 @@ pub async fn ingestion_sources_update(
 }
 
 /// DELETE /api/ingestion/sources/{id}
+#[utoipa::path(
+    delete,
+    path = "/api/ingestion/sources/{id}",
+    tag = "Ingestion",
+    params(
+        ("id" = String, Path, description = "Ingestion source ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Source deleted"),
+        (status = 404, description = "Source not found", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_sources_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -102,6 +166,19 @@ pub async fn ingestion_sources_delete(
 }
 
 /// POST /api/ingestion/sources/{id}/trigger — manually trigger an ingestion job.
+#[utoipa::path(
+    post,
+    path = "/api/ingestion/sources/{id}/trigger",
+    tag = "Ingestion",
+    params(
+        ("id" = String, Path, description = "Ingestion source ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Job started; body holds its `job_id`", body = Object),
+        (status = 404, description = "Source not found", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn ingestion_sources_trigger(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
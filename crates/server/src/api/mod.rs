@@ -6,6 +6,7 @@
 mod agent_groups;
 mod agents;
 mod athena_query;
+mod audit;
 mod compute;
 mod connections;
 pub(crate) mod doc;
@@ -14,6 +15,7 @@ mod graph;
 mod health;
 pub(crate) mod prompts;
 mod query;
+mod segments;
 pub(crate) mod stille_post;
 pub(crate) mod telemetry;
 pub(crate) mod ingestion;
@@ -29,7 +31,6 @@ use crate::state::{AppState, LoadingStatus};
 #[derive(Serialize, utoipa::ToSchema)]
 pub struct NotReadyResponse {
     pub error: &'static str,
-    #[schema(value_type = Object)]
     pub loading: LoadingStatus,
 }
 
@@ -60,13 +61,16 @@ pub(crate) async fn require_ready(
 // ── Re-exports ───────────────────────────────────────────────────
 // Preserves flat `api::foo` import paths used by main.rs route registration.
 
-pub use health::{health, loading, stats, queue_status, scheduler_metrics};
+pub use health::{health, loading, stats, queue_status, scheduler_metrics, debug_config};
 pub use graph::{graph_nodes, graph_node_by_id, graph_force};
 pub use compute::{
-    compute_pagerank, compute_communities, compute_degrees,
+    compute_pagerank, compute_communities, compute_degrees, compute_degree_anomalies,
     compute_patterns, compute_cooccurrence, compute_trends, compute_anomalies,
+    insights_stream, list_insights,
 };
-pub use query::query;
+pub use query::{query, query_stream};
+pub use audit::audit_queries;
+pub use segments::segments_import;
 pub use agents::{
     agents_list, agents_execute, agents_chat,
     agents_get, agents_create, agents_update, agents_delete, agents_reload,
@@ -76,20 +80,22 @@ pub use agents::{
     sessions_list, sessions_create, sessions_get, sessions_update, sessions_delete,
     sessions_execute_agent, sessions_execute_team, sessions_execute,
     sessions_stream,
+    tool_approvals_list, tool_approvals_decide,
 };
 pub use connections::{
     connections_list, connections_add, connections_get,
-    connections_update, connections_delete, connections_credentials,
+    connections_update, connections_delete, connections_credentials, connections_test,
     queue_connections_list, queue_connections_add, queue_connections_get,
-    queue_connections_update, queue_connections_delete, queue_connections_credentials,
+    queue_connections_update, queue_connections_delete, queue_connections_credentials, queue_connections_test,
     athena_connections_list, athena_connections_add, athena_connections_get,
-    athena_connections_update, athena_connections_delete, athena_connections_credentials,
+    athena_connections_update, athena_connections_delete, athena_connections_credentials, athena_connections_test,
 };
 pub use athena_query::{
     athena_query_sse, athena_query_parquet,
     athena_connections_schema, athena_connections_schema_refresh,
     athena_connections_query_log,
 };
+pub(crate) use athena_query::start_schema_refresh;
 pub use stille_post::{
     sp_pipelines_list, sp_pipelines_create, sp_pipelines_get,
     sp_pipelines_update, sp_pipelines_delete,
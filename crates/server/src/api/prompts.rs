@@ -41,6 +41,16 @@ pub struct UpdatePromptRequest {
 // ── Endpoints ─────────────────────────────────────────────────
 
 /// List all prompts (without full content).
+#[utoipa::path(
+    get,
+    path = "/api/prompts",
+    tag = "Prompts",
+    responses(
+        (status = 200, description = "All prompt templates", body = Vec<PromptSummary>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn prompts_list(
     State(state): State<Arc<AppState>>,
 ) -> Result<Json<Vec<PromptSummary>>, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
@@ -81,6 +91,20 @@ pub async fn prompts_list(
 }
 
 /// Get a single prompt by name (includes full content).
+#[utoipa::path(
+    get,
+    path = "/api/prompts/{name}",
+    tag = "Prompts",
+    params(
+        ("name" = String, Path, description = "Prompt name")
+    ),
+    responses(
+        (status = 200, description = "Prompt template", body = PromptDetail),
+        (status = 404, description = "Prompt not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn prompts_get(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
@@ -127,6 +151,21 @@ pub async fn prompts_get(
 }
 
 /// Update a prompt's content (writes to Postgres only, not back to file).
+#[utoipa::path(
+    put,
+    path = "/api/prompts/{name}",
+    tag = "Prompts",
+    params(
+        ("name" = String, Path, description = "Prompt name")
+    ),
+    request_body = UpdatePromptRequest,
+    responses(
+        (status = 200, description = "Prompt updated", body = PromptDetail),
+        (status = 404, description = "Prompt not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn prompts_update(
     State(state): State<Arc<AppState>>,
     Path(name): Path<String>,
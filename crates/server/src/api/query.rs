@@ -2,22 +2,71 @@
 //!
 //! SRP: NL query execution via LLM-generated query plans.
 
+use std::convert::Infallible;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
 use axum::Json;
+use futures::Stream;
 use serde::Serialize;
+use stupid_catalog::{ExecuteOptions, ExternalRows, ExternalScanner, PagedResults, QueryExecutor, QueryPlan};
+use stupid_graph::GraphStore;
+use tokio_stream::wrappers::ReceiverStream;
 
-use crate::state::AppState;
+use crate::query_audit::{PendingQuery, QueryClient};
+use crate::state::{AppState, SharedGraph};
 
 use super::QueryErrorResponse;
 
+/// Rows per page when the request doesn't say.
+const DEFAULT_LIMIT: usize = stupid_catalog::MAX_RESULT_ROWS;
+
+/// Largest page a request may ask for.
+const MAX_LIMIT: usize = 1000;
+
+/// Execution timeout when the request doesn't say.
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Largest execution timeout a request may ask for.
+const MAX_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// How long past the deadline to wait for the executor to notice it before
+/// answering without its rows.
+const DEADLINE_GRACE: Duration = Duration::from_millis(250);
+
+type QueryErr = (StatusCode, Json<QueryErrorResponse>);
+
+fn query_error(status: StatusCode, error: impl Into<String>) -> QueryErr {
+    (status, Json(QueryErrorResponse { error: error.into() }))
+}
+
 // ── Query endpoint ─────────────────────────────────────────────
 
 #[derive(serde::Deserialize, utoipa::ToSchema)]
 pub struct QueryRequest {
     pub question: String,
+    /// Result rows to skip (default 0).
+    #[serde(default)]
+    pub offset: usize,
+    /// Result rows to return (default 200, at most 1000).
+    #[serde(default)]
+    pub limit: Option<usize>,
+    /// Execution timeout in milliseconds (default 30000, at most 300000).
+    #[serde(default)]
+    pub timeout_ms: Option<u64>,
+}
+
+impl QueryRequest {
+    fn limit(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT)
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout_ms.map_or(DEFAULT_TIMEOUT, Duration::from_millis).min(MAX_TIMEOUT)
+    }
 }
 
 #[derive(Serialize, utoipa::ToSchema)]
@@ -27,12 +76,25 @@ pub struct QueryResponse {
     pub plan: stupid_catalog::QueryPlan,
     #[schema(value_type = Vec<Object>)]
     pub results: Vec<serde_json::Value>,
+    /// Rows the plan matched before paging; zero if execution timed out
+    /// before the final step.
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+    /// Execution hit the timeout and `results` is incomplete.
+    pub partial: bool,
 }
 
 /// Execute a natural-language query
 ///
 /// Sends the question to the LLM query generator which produces a query plan,
-/// executes it against the in-memory graph and catalog, and returns results.
+/// executes it against the in-memory graph, and returns one page of results.
+/// If execution exceeds the timeout, responds 504 with whatever rows were
+/// produced and `partial: true`. Paging and the timeout apply to local
+/// execution; the eisenbahn query service applies its own limits.
+/// `external_scan` steps are fetched from Athena first and count against the
+/// timeout; if they don't finish within it, responds 504 with an error.
+/// Each call is recorded in the query audit trail (`GET /audit/queries`).
 #[utoipa::path(
     post,
     path = "/query",
@@ -40,13 +102,29 @@ pub struct QueryResponse {
     request_body = QueryRequest,
     responses(
         (status = 200, description = "Query results with execution plan", body = QueryResponse),
+        (status = 504, description = "Execution timed out; partial results", body = QueryResponse),
         (status = 503, description = "Service not ready", body = QueryErrorResponse)
     )
 )]
 pub async fn query(
     State(state): State<Arc<AppState>>,
+    client: QueryClient,
     Json(req): Json<QueryRequest>,
-) -> Result<Json<QueryResponse>, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
+) -> Result<(StatusCode, Json<QueryResponse>), QueryErr> {
+    let mut audit = PendingQuery::start("/query", &client, req.question.clone());
+    let result = run_query(&state, req).await;
+    let outcome = match &result {
+        Ok((_, Json(resp))) => {
+            audit.set_plan(serde_json::to_value(&resp.plan).unwrap_or_default());
+            Ok(Some(resp.results.len() as u64))
+        }
+        Err((_, Json(e))) => Err(e.error.clone()),
+    };
+    state.query_audit.append(audit.finish(outcome));
+    result
+}
+
+async fn run_query(state: &Arc<AppState>, req: QueryRequest) -> Result<(StatusCode, Json<QueryResponse>), QueryErr> {
     // Route through eisenbahn if available.
     if let Some(ref eb) = state.eisenbahn {
         let svc_req = stupid_eisenbahn::services::QueryServiceRequest {
@@ -55,77 +133,307 @@ pub async fn query(
         let resp = eb
             .query(svc_req, Duration::from_secs(30))
             .await
-            .map_err(|e| eb_error(e))?;
+            .map_err(eb_error)?;
         let plan = serde_json::from_value(resp.plan).map_err(|e| {
-            (
-                axum::http::StatusCode::BAD_GATEWAY,
-                Json(QueryErrorResponse {
-                    error: format!("Failed to decode query plan from service: {e}"),
-                }),
-            )
+            query_error(StatusCode::BAD_GATEWAY, format!("Failed to decode query plan from service: {e}"))
         })?;
-        return Ok(Json(QueryResponse {
-            question: req.question,
-            plan,
-            results: resp.results,
-        }));
+        let total = resp.results.len();
+        return Ok((
+            StatusCode::OK,
+            Json(QueryResponse {
+                question: req.question,
+                plan,
+                results: resp.results,
+                total,
+                offset: 0,
+                limit: total,
+                partial: false,
+            }),
+        ));
     }
 
+    let plan = plan_question(state, &req.question).await?;
+    let started = Instant::now();
+    let external = scan_external(state, &plan, req.timeout()).await?;
+    let options = ExecuteOptions { offset: req.offset, limit: req.limit(), deadline: None };
+    let remaining = req.timeout().saturating_sub(started.elapsed());
+    let paged = execute_plan(state.graph.clone(), plan.clone(), external, options, remaining).await?;
+    Ok(page_response(req.question, plan, options, paged))
+}
+
+/// Stream a natural-language query's results over Server-Sent Events
+///
+/// Emits a `plan` event with the generated plan, a `row` event per result
+/// row as the executor produces it, and a final `done` event with
+/// `{total, returned, partial}`. Execution errors are sent as an `error`
+/// event. Accepts the same paging and timeout fields as `/query`, and always
+/// executes against the local graph. Each call is recorded in the query
+/// audit trail (`GET /audit/queries`) once the stream ends.
+#[utoipa::path(
+    post,
+    path = "/query/stream",
+    tag = "Query",
+    request_body = QueryRequest,
+    responses(
+        (status = 200, description = "SSE event stream", content_type = "text/event-stream"),
+        (status = 503, description = "Service not ready", body = QueryErrorResponse)
+    )
+)]
+pub async fn query_stream(
+    State(state): State<Arc<AppState>>,
+    client: QueryClient,
+    Json(req): Json<QueryRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, QueryErr> {
+    let audit = PendingQuery::start("/query/stream", &client, req.question.clone());
+    let result = run_query_stream(state.clone(), req, audit.clone()).await;
+    // Once streaming has started, the executor task records the outcome.
+    if let Err((_, Json(e))) = &result {
+        state.query_audit.append(audit.finish(Err(e.error.clone())));
+    }
+    result
+}
+
+async fn run_query_stream(
+    state: Arc<AppState>,
+    req: QueryRequest,
+    mut audit: PendingQuery,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, QueryErr> {
+    let plan = plan_question(&state, &req.question).await?;
+    audit.set_plan(serde_json::to_value(&plan).unwrap_or_default());
+    let deadline = Instant::now() + req.timeout();
+    let external = scan_external(&state, &plan, req.timeout()).await?;
+    let options = ExecuteOptions { offset: req.offset, limit: req.limit(), deadline: Some(deadline) };
+
+    let (tx, rx) = tokio::sync::mpsc::channel(64);
+    let plan_event = Event::default().event("plan").data(serde_json::to_string(&plan).unwrap_or_default());
+    let _ = tx.send(Ok(plan_event)).await;
+
+    let guard = state.graph.clone().read_owned().await;
+    tokio::task::spawn_blocking(move || {
+        let summary = QueryExecutor::execute_each_external(&plan, &guard, &external, &options, |row| {
+            let event = Event::default().event("row").data(row.to_string());
+            // A failed send means the client went away.
+            tx.blocking_send(Ok(event)).is_ok()
+        });
+        let outcome = match &summary {
+            Ok(summary) => Ok(Some(summary.returned as u64)),
+            Err(e) => Err(e.to_string()),
+        };
+        state.query_audit.append(audit.finish(outcome));
+        let last = match summary {
+            Ok(summary) => Event::default().event("done").data(
+                serde_json::json!({
+                    "total": summary.total,
+                    "returned": summary.returned,
+                    "partial": summary.timed_out,
+                })
+                .to_string(),
+            ),
+            Err(e) => Event::default().event("error").data(e.to_string()),
+        };
+        let _ = tx.blocking_send(Ok(last));
+    });
+
+    Ok(Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default()))
+}
+
+/// Generate a plan for `question` once data, query generator and catalog are
+/// available.
+async fn plan_question(state: &AppState, question: &str) -> Result<QueryPlan, QueryErr> {
     // Require data to be loaded before accepting queries.
     if !state.loading.is_ready().await {
-        return Err((
-            axum::http::StatusCode::SERVICE_UNAVAILABLE,
-            Json(QueryErrorResponse {
-                error: "Data is still loading. Check /loading for progress.".into(),
-            }),
+        return Err(query_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Data is still loading. Check /loading for progress.",
         ));
     }
 
     let qg = state.query_generator.as_ref().ok_or_else(|| {
-        (
-            axum::http::StatusCode::SERVICE_UNAVAILABLE,
-            Json(QueryErrorResponse {
-                error: "LLM query generator not configured. Set LLM_PROVIDER and API keys.".into(),
-            }),
+        query_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "LLM query generator not configured. Set LLM_PROVIDER and API keys.",
         )
     })?;
 
     let catalog_lock = state.catalog.read().await;
-    let cat = catalog_lock.as_ref().ok_or_else(|| {
-        (
-            axum::http::StatusCode::SERVICE_UNAVAILABLE,
-            Json(QueryErrorResponse {
-                error: "Catalog not yet built.".into(),
-            }),
-        )
-    })?;
-
-    let graph = state.graph.read().await;
+    let cat = catalog_lock
+        .as_ref()
+        .ok_or_else(|| query_error(StatusCode::SERVICE_UNAVAILABLE, "Catalog not yet built."))?;
 
-    let result = qg
-        .ask(&req.question, cat, &graph)
+    qg.generate_plan(question, cat)
         .await
-        .map_err(|e| {
-            (
-                axum::http::StatusCode::INTERNAL_SERVER_ERROR,
-                Json(QueryErrorResponse {
-                    error: e.to_string(),
-                }),
-            )
-        })?;
+        .map_err(|e| query_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))
+}
+
+/// Fetch the rows of the plan's `external_scan` steps from Athena, before
+/// the graph is locked, giving up after `timeout`.
+async fn scan_external(state: &AppState, plan: &QueryPlan, timeout: Duration) -> Result<ExternalRows, QueryErr> {
+    let scanner = crate::athena_query::AthenaScanner::new(state.athena_connections.clone());
+    scan_within(plan, &scanner, timeout).await
+}
+
+async fn scan_within(plan: &QueryPlan, scanner: &dyn ExternalScanner, timeout: Duration) -> Result<ExternalRows, QueryErr> {
+    match tokio::time::timeout(timeout, QueryExecutor::scan_external(plan, scanner)).await {
+        Ok(result) => result.map_err(|e| query_error(StatusCode::BAD_GATEWAY, e.to_string())),
+        Err(_) => Err(query_error(
+            StatusCode::GATEWAY_TIMEOUT,
+            format!("External scans did not finish within {} ms", timeout.as_millis()),
+        )),
+    }
+}
+
+/// Execute `plan` for one page of results, giving up after `timeout`.
+async fn execute_plan(
+    graph: SharedGraph,
+    plan: QueryPlan,
+    external: ExternalRows,
+    options: ExecuteOptions,
+    timeout: Duration,
+) -> Result<PagedResults, QueryErr> {
+    let executed = run_with_deadline(graph, timeout, move |graph, deadline| {
+        let options = ExecuteOptions { deadline: Some(deadline), ..options };
+        QueryExecutor::execute_with_external(&plan, graph, &external, &options)
+    })
+    .await;
+    match executed {
+        Some(result) => result.map_err(|e| query_error(StatusCode::INTERNAL_SERVER_ERROR, e.to_string())),
+        None => Ok(PagedResults { rows: Vec::new(), total: 0, timed_out: true }),
+    }
+}
 
-    Ok(Json(QueryResponse {
-        question: result.question,
-        plan: result.plan,
-        results: result.results,
-    }))
+/// Run `execute` on a blocking thread with a read lock on the graph.
+///
+/// `execute` receives the deadline and should stop by itself once it passes;
+/// if it hasn't returned shortly after, `None` is returned and the task is
+/// left to finish in the background.
+async fn run_with_deadline<T, F>(graph: SharedGraph, timeout: Duration, execute: F) -> Option<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&GraphStore, Instant) -> T + Send + 'static,
+{
+    let guard = graph.read_owned().await;
+    let deadline = Instant::now() + timeout;
+    let task = tokio::task::spawn_blocking(move || execute(&guard, deadline));
+    match tokio::time::timeout(timeout + DEADLINE_GRACE, task).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => std::panic::resume_unwind(e.into_panic()),
+        Err(_) => None,
+    }
+}
+
+/// 200 with the page, or 504 with the rows produced before the timeout.
+fn page_response(
+    question: String,
+    plan: QueryPlan,
+    options: ExecuteOptions,
+    paged: PagedResults,
+) -> (StatusCode, Json<QueryResponse>) {
+    let status = if paged.timed_out { StatusCode::GATEWAY_TIMEOUT } else { StatusCode::OK };
+    (
+        status,
+        Json(QueryResponse {
+            question,
+            plan,
+            results: paged.rows,
+            total: paged.total,
+            offset: options.offset,
+            limit: options.limit,
+            partial: paged.timed_out,
+        }),
+    )
 }
 
 /// Map an eisenbahn error to an HTTP error response.
-fn eb_error(e: stupid_eisenbahn::EisenbahnError) -> (axum::http::StatusCode, Json<QueryErrorResponse>) {
+fn eb_error(e: stupid_eisenbahn::EisenbahnError) -> QueryErr {
     let status = match &e {
-        stupid_eisenbahn::EisenbahnError::Timeout(_) => axum::http::StatusCode::GATEWAY_TIMEOUT,
-        _ => axum::http::StatusCode::BAD_GATEWAY,
+        stupid_eisenbahn::EisenbahnError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
+        _ => StatusCode::BAD_GATEWAY,
     };
     (status, Json(QueryErrorResponse { error: e.to_string() }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use stupid_core::EntityType;
+    use tokio::sync::RwLock;
+
+    fn member_graph(members: usize) -> SharedGraph {
+        let mut g = GraphStore::new();
+        let seg = "test".to_string();
+        for i in 0..members {
+            g.upsert_node(EntityType::Member, &format!("member-{i}"), &seg);
+        }
+        Arc::new(RwLock::new(g))
+    }
+
+    fn members_plan() -> QueryPlan {
+        serde_json::from_str(r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#).unwrap()
+    }
+
+    #[test]
+    fn test_request_limit_and_timeout_are_capped() {
+        let req: QueryRequest = serde_json::from_str(r#"{"question":"q"}"#).unwrap();
+        assert_eq!((req.offset, req.limit(), req.timeout()), (0, DEFAULT_LIMIT, DEFAULT_TIMEOUT));
+
+        let req: QueryRequest =
+            serde_json::from_str(r#"{"question":"q","limit":50000,"timeout_ms":999999999}"#).unwrap();
+        assert_eq!((req.limit(), req.timeout()), (MAX_LIMIT, MAX_TIMEOUT));
+    }
+
+    #[tokio::test]
+    async fn test_limit_returns_one_page_with_total() {
+        let graph = member_graph(5);
+        let options = ExecuteOptions { offset: 1, limit: 2, deadline: None };
+        let paged = execute_plan(graph, members_plan(), ExternalRows::new(), options, DEFAULT_TIMEOUT).await.unwrap();
+
+        let (status, Json(response)) = page_response("q".into(), members_plan(), options, paged);
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(response.results.len(), 2);
+        assert_eq!(response.total, 5);
+        assert_eq!((response.offset, response.limit), (1, 2));
+        assert!(!response.partial);
+    }
+
+    #[tokio::test]
+    async fn test_slow_execution_times_out_with_partial_flag() {
+        let graph = member_graph(1);
+        let timeout = Duration::from_millis(20);
+        let slow = run_with_deadline(graph, timeout, move |_, _| {
+            // Ignores the deadline, like a step that never checks it.
+            std::thread::sleep(timeout + DEADLINE_GRACE * 4);
+        });
+        assert!(slow.await.is_none());
+
+        let paged = PagedResults { rows: vec![serde_json::json!({"key": "m"})], total: 0, timed_out: true };
+        let (status, Json(response)) = page_response("q".into(), members_plan(), ExecuteOptions::default(), paged);
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert!(response.partial);
+        assert_eq!(response.results.len(), 1);
+    }
+
+    /// Scanner that never answers within the tests' timeouts.
+    struct StalledScanner;
+
+    #[async_trait::async_trait]
+    impl ExternalScanner for StalledScanner {
+        async fn scan(&self, _step: &stupid_catalog::ExternalScanStep) -> anyhow::Result<Vec<serde_json::Value>> {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_external_scan_times_out() {
+        let plan: QueryPlan = serde_json::from_str(
+            r#"{"steps":[{"id":"s1","type":"external_scan","connection_id":"lake","database":"db","table":"t"}]}"#,
+        )
+        .unwrap();
+        let started = Instant::now();
+        let (status, Json(err)) = scan_within(&plan, &StalledScanner, Duration::from_millis(20)).await.unwrap_err();
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert!(err.error.contains("20 ms"), "{}", err.error);
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
@@ -0,0 +1,368 @@
+//! Live segment import: write a new segment and merge it into the running
+//! graph without a restart.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::extract::multipart::{Field, MultipartError};
+use axum::extract::{Multipart, State};
+use axum::http::StatusCode;
+use axum::Json;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+use tracing::{info, warn};
+
+use crate::live::LiveMerge;
+use crate::state::AppState;
+
+use super::QueryErrorResponse;
+
+type ImportError = (StatusCode, Json<QueryErrorResponse>);
+
+fn import_error(status: StatusCode, error: impl Into<String>) -> ImportError {
+    (status, Json(QueryErrorResponse { error: error.into() }))
+}
+
+fn multipart_error(e: MultipartError) -> ImportError {
+    import_error(StatusCode::BAD_REQUEST, format!("Multipart error: {e}"))
+}
+
+fn store_error(e: std::io::Error) -> ImportError {
+    import_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Failed to store upload: {e}"))
+}
+
+/// Result of a live segment import.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct SegmentImportResponse {
+    /// Segment IDs written; more than one when the segment rolled over into parts.
+    pub segment_ids: Vec<String>,
+    /// Documents read from the source file.
+    pub documents: usize,
+    /// Documents merged into the graph.
+    pub ingested: u64,
+    /// Graph size after the merge.
+    pub node_count: usize,
+    pub edge_count: usize,
+}
+
+/// Import form fields, collected from the multipart body.
+#[derive(Default)]
+struct ImportForm {
+    segment_id: Option<String>,
+    event_type: Option<String>,
+    /// Uploaded file, spooled to disk.
+    file: Option<SpooledUpload>,
+}
+
+/// An upload spooled under `imports/`, since the importers read from a path.
+/// The file is removed when this is dropped.
+struct SpooledUpload(PathBuf);
+
+impl Drop for SpooledUpload {
+    fn drop(&mut self) {
+        if let Err(e) = std::fs::remove_file(&self.0) {
+            warn!("Failed to remove spooled upload {}: {}", self.0.display(), e);
+        }
+    }
+}
+
+/// Import a parquet, CSV or JSON Lines file as a new segment and merge it into
+/// the live graph, catalog and pipeline.
+///
+/// Multipart fields: `segment_id` (required), `event_type` (defaults to the
+/// first segment ID component), and the `file` upload.
+#[utoipa::path(
+    post,
+    path = "/segments/import",
+    tag = "Catalog",
+    request_body(content_type = "multipart/form-data", description = "segment_id, event_type, and a file upload"),
+    responses(
+        (status = 200, description = "Segment imported and merged", body = SegmentImportResponse),
+        (status = 400, description = "Invalid form or unreadable file", body = QueryErrorResponse),
+        (status = 409, description = "Segment already exists", body = QueryErrorResponse),
+        (status = 503, description = "Service not ready", body = super::NotReadyResponse)
+    )
+)]
+pub async fn segments_import(
+    State(state): State<Arc<AppState>>,
+    multipart: Multipart,
+) -> Result<Json<SegmentImportResponse>, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    super::require_ready(&state).await.map_err(IntoResponse::into_response)?;
+    import(&state, multipart).await.map(Json).map_err(IntoResponse::into_response)
+}
+
+async fn import(state: &AppState, multipart: Multipart) -> Result<SegmentImportResponse, ImportError> {
+    let form = read_form(multipart, &state.data_dir.join("imports")).await?;
+    let segment_id = form
+        .segment_id
+        .ok_or_else(|| import_error(StatusCode::BAD_REQUEST, "Missing segment_id"))?;
+    validate_segment_id(&segment_id).map_err(|e| import_error(StatusCode::BAD_REQUEST, e))?;
+    let event_type = form.event_type.unwrap_or_else(|| default_event_type(&segment_id).to_string());
+    let upload = form
+        .file
+        .ok_or_else(|| import_error(StatusCode::BAD_REQUEST, "Missing file upload"))?;
+
+    // Creating the directory claims the segment ID, so concurrent imports of
+    // the same ID can't both write into it.
+    let segment_dir = state.data_dir.join("segments").join(&segment_id);
+    let created = match segment_dir.parent() {
+        Some(parent) => match tokio::fs::create_dir_all(parent).await {
+            Ok(()) => tokio::fs::create_dir(&segment_dir).await,
+            Err(e) => Err(e),
+        },
+        None => tokio::fs::create_dir(&segment_dir).await,
+    };
+    match created {
+        Ok(()) => {}
+        Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+            return Err(import_error(
+                StatusCode::CONFLICT,
+                format!("Segment '{}' already exists", segment_id),
+            ));
+        }
+        Err(e) => {
+            return Err(import_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Failed to create segment directory: {e}"),
+            ));
+        }
+    }
+
+    let data_dir = state.data_dir.clone();
+    let write_id = segment_id.clone();
+    let written = tokio::task::spawn_blocking(move || {
+        let written = write_segment(&data_dir, &upload.0, &write_id, &event_type);
+        drop(upload);
+        written
+    })
+    .await
+    .map_err(|e| import_error(StatusCode::INTERNAL_SERVER_ERROR, format!("Import task failed: {e}")))
+    .and_then(|written| {
+        written.map_err(|e| import_error(StatusCode::BAD_REQUEST, format!("Import failed: {e:#}")))
+    });
+    let (part_ids, documents) = match written {
+        Ok(written) => written,
+        Err(e) => {
+            // Release the segment ID so a corrected file can be imported.
+            if let Err(remove_err) = tokio::fs::remove_dir_all(&segment_dir).await {
+                warn!("Failed to remove failed import {}: {}", segment_dir.display(), remove_err);
+            }
+            return Err(e);
+        }
+    };
+
+    let live = LiveMerge::new(state);
+    let claimed = live.claim(part_ids.iter().cloned()).await;
+    if claimed.len() != part_ids.len() {
+        // Only possible if the same segment ID was imported concurrently.
+        live.release(&claimed).await;
+        return Err(import_error(
+            StatusCode::CONFLICT,
+            format!("Segment '{}' is already loaded", segment_id),
+        ));
+    }
+    let ingested = live.merge(&claimed).await;
+
+    let stats = state.graph.read().await.stats();
+    info!(
+        "Imported segment '{}' live: {} documents, graph now {} nodes / {} edges",
+        segment_id, documents, stats.node_count, stats.edge_count
+    );
+    Ok(SegmentImportResponse {
+        segment_ids: part_ids,
+        documents,
+        ingested,
+        node_count: stats.node_count,
+        edge_count: stats.edge_count,
+    })
+}
+
+/// Read the form, streaming the `file` upload into `spool_dir` chunk by
+/// chunk rather than buffering it in memory.
+async fn read_form(mut multipart: Multipart, spool_dir: &Path) -> Result<ImportForm, ImportError> {
+    let mut form = ImportForm::default();
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name().unwrap_or_default() {
+            "segment_id" => form.segment_id = Some(field.text().await.map_err(multipart_error)?.trim().to_string()),
+            "event_type" => form.event_type = Some(field.text().await.map_err(multipart_error)?.trim().to_string()),
+            "file" => form.file = Some(spool_upload(field, spool_dir).await?),
+            _ => {}
+        }
+    }
+    Ok(form)
+}
+
+/// Write an uploaded file under `dir`, keeping its extension so the importer
+/// can tell the format.
+async fn spool_upload(mut field: Field<'_>, dir: &Path) -> Result<SpooledUpload, ImportError> {
+    let extension = field
+        .file_name()
+        .and_then(|name| Path::new(name).extension())
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_string();
+    tokio::fs::create_dir_all(dir).await.map_err(store_error)?;
+    let spooled = SpooledUpload(dir.join(format!("{}.{}", uuid::Uuid::new_v4(), extension)));
+    let mut file = tokio::fs::File::create(&spooled.0).await.map_err(store_error)?;
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        file.write_all(&chunk).await.map_err(store_error)?;
+    }
+    file.flush().await.map_err(store_error)?;
+    Ok(spooled)
+}
+
+/// Segment IDs are relative paths under `segments/`, e.g. `Login/2025-W24`.
+fn validate_segment_id(segment_id: &str) -> Result<(), String> {
+    let valid_component = |c: &str| {
+        !c.is_empty()
+            && c != "."
+            && c != ".."
+            && c.chars().all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_' | '.'))
+    };
+    if segment_id.split('/').all(valid_component) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid segment_id '{}': use '/'-separated names of letters, digits, '-', '_' and '.'",
+            segment_id
+        ))
+    }
+}
+
+/// `Login/2025-W24` → `Login`, mirroring how `import-dir` names event types.
+fn default_event_type(segment_id: &str) -> &str {
+    match segment_id.split_once('/') {
+        Some((event_type, _)) => event_type,
+        None => "Unknown",
+    }
+}
+
+/// Read `source` and write it as `segment_id`, returning the written part IDs
+/// and the number of documents.
+fn write_segment(
+    data_dir: &Path,
+    source: &Path,
+    segment_id: &str,
+    event_type: &str,
+) -> anyhow::Result<(Vec<String>, usize)> {
+    let documents = crate::import::read_documents(source, event_type)?;
+    let mut writer = stupid_segment::writer::SegmentWriter::new(data_dir, segment_id)?;
+    for doc in &documents {
+        writer.append(doc)?;
+    }
+    let part_ids = writer.part_ids();
+    writer.finalize()?;
+    Ok((part_ids, documents.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_validate_segment_id() {
+        assert!(validate_segment_id("Login/2025-W24").is_ok());
+        assert!(validate_segment_id("misc").is_ok());
+        assert!(validate_segment_id("../etc").is_err());
+        assert!(validate_segment_id("/abs").is_err());
+        assert!(validate_segment_id("a//b").is_err());
+        assert!(validate_segment_id("a b").is_err());
+        assert_eq!(default_event_type("Login/2025-W24"), "Login");
+        assert_eq!(default_event_type("misc"), "Unknown");
+    }
+
+    #[tokio::test]
+    async fn test_import_grows_running_graph() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state.clone());
+
+        let boundary = "segment-import-boundary";
+        let csv = "@timestamp,memberCode,fingerprint\n\
+                   2025-06-14T10:00:00Z,M001,fp-1\n\
+                   2025-06-14T11:00:00Z,M002,fp-1\n";
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"segment_id\"\r\n\r\nLogin/2025-W24\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"2025-06-14.csv\"\r\n\
+             Content-Type: text/csv\r\n\r\n{csv}\r\n--{b}--\r\n",
+            b = boundary,
+        );
+        let request = || {
+            Request::post("/segments/import")
+                .header("content-type", format!("multipart/form-data; boundary={boundary}"))
+                .body(Body::from(body.clone()))
+                .unwrap()
+        };
+
+        let nodes_before = state.graph.read().await.stats().node_count;
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["documents"], 2);
+        assert_eq!(json["ingested"], 2);
+
+        assert!(state.graph.read().await.stats().node_count > nodes_before);
+        assert_eq!(*state.segment_ids.read().await, vec!["Login/2025-W24".to_string()]);
+        assert!(!tmp.path().join("imports").read_dir().unwrap().any(|_| true));
+
+        // The same segment can't be imported twice.
+        let response = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+        assert!(!tmp.path().join("imports").read_dir().unwrap().any(|_| true));
+
+        // Server-local paths aren't accepted; only uploads are.
+        let body = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"segment_id\"\r\n\r\nLogin/2025-W25\r\n\
+             --{b}\r\nContent-Disposition: form-data; name=\"path\"\r\n\r\n/etc/passwd\r\n--{b}--\r\n",
+            b = boundary,
+        );
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post("/segments/import")
+                    .header("content-type", format!("multipart/form-data; boundary={boundary}"))
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(!tmp.path().join("segments/Login/2025-W25").exists());
+    }
+
+    #[tokio::test]
+    async fn test_failed_import_releases_the_segment_id() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state.clone());
+
+        let boundary = "segment-import-boundary";
+        let request = |file_name: &str, contents: &str| {
+            let body = format!(
+                "--{b}\r\nContent-Disposition: form-data; name=\"segment_id\"\r\n\r\nLogin/2025-W24\r\n\
+                 --{b}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{file_name}\"\r\n\r\n\
+                 {contents}\r\n--{b}--\r\n",
+                b = boundary,
+            );
+            Request::post("/segments/import")
+                .header("content-type", format!("multipart/form-data; boundary={boundary}"))
+                .body(Body::from(body))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("data.xlsx", "not a table")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert!(!tmp.path().join("segments/Login/2025-W24").exists());
+
+        let csv = "@timestamp,memberCode\n2025-06-14T10:00:00Z,M001\n";
+        let response = app.oneshot(request("data.csv", csv)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
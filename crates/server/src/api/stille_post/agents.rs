@@ -9,45 +9,61 @@ use sqlx::types::Uuid;
 
 use crate::state::AppState;
 
+use super::super::QueryErrorResponse;
+
 use super::common::{internal_error, not_found, require_pg, ApiResult};
 
 // ── Types ────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpAgent {
+    #[schema(value_type = String)]
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
     pub system_prompt: String,
     pub model: String,
+    #[schema(value_type = Object)]
     pub skills_config: serde_json::Value,
+    #[schema(value_type = Object)]
     pub mcp_servers_config: serde_json::Value,
+    #[schema(value_type = Object)]
     pub tools_config: serde_json::Value,
     pub template_id: Option<String>,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = String)]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[schema(as = SpCreateAgentRequest)]
 pub struct CreateAgentRequest {
     pub name: String,
     pub description: Option<String>,
     pub system_prompt: String,
     pub model: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub skills_config: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
     pub mcp_servers_config: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
     pub tools_config: Option<serde_json::Value>,
     pub template_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
+#[schema(as = SpUpdateAgentRequest)]
 pub struct UpdateAgentRequest {
     pub name: Option<String>,
     pub description: Option<String>,
     pub system_prompt: Option<String>,
     pub model: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub skills_config: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
     pub mcp_servers_config: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
     pub tools_config: Option<serde_json::Value>,
     pub template_id: Option<String>,
 }
@@ -55,6 +71,16 @@ pub struct UpdateAgentRequest {
 // ── Handlers ─────────────────────────────────────────────────────
 
 /// GET /sp/agents -- list all agents.
+#[utoipa::path(
+    get,
+    path = "/sp/agents",
+    tag = "Stille Post",
+    responses(
+        (status = 200, description = "All Stille Post agents", body = Vec<SpAgent>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_agents_list(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<SpAgent>>> {
@@ -72,6 +98,17 @@ pub async fn sp_agents_list(
 }
 
 /// POST /sp/agents -- create a new agent.
+#[utoipa::path(
+    post,
+    path = "/sp/agents",
+    tag = "Stille Post",
+    request_body = CreateAgentRequest,
+    responses(
+        (status = 201, description = "Agent created", body = SpAgent),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_agents_create(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateAgentRequest>,
@@ -100,6 +137,20 @@ pub async fn sp_agents_create(
 }
 
 /// GET /sp/agents/:id -- get agent by ID.
+#[utoipa::path(
+    get,
+    path = "/sp/agents/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Agent ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Agent", body = SpAgent),
+        (status = 404, description = "Agent not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_agents_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -115,6 +166,21 @@ pub async fn sp_agents_get(
 }
 
 /// PUT /sp/agents/:id -- update an agent.
+#[utoipa::path(
+    put,
+    path = "/sp/agents/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Agent ID (UUID)")
+    ),
+    request_body = UpdateAgentRequest,
+    responses(
+        (status = 200, description = "Agent updated", body = SpAgent),
+        (status = 404, description = "Agent not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_agents_update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -152,6 +218,20 @@ pub async fn sp_agents_update(
 }
 
 /// DELETE /sp/agents/:id -- delete an agent.
+#[utoipa::path(
+    delete,
+    path = "/sp/agents/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Agent ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Agent deleted"),
+        (status = 404, description = "Agent not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_agents_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -11,39 +11,57 @@ use tracing::info;
 
 use crate::state::AppState;
 
+use super::super::QueryErrorResponse;
+
 use super::common::{bad_request, internal_error, not_found, require_pg, ApiResult};
 
 // ── Types ────────────────────────────────────────────────────────
 
 const VALID_SOURCE_TYPES: &[&str] = &["athena", "s3", "api", "upload"];
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpDataSource {
+    #[schema(value_type = String)]
     pub id: Uuid,
     pub name: String,
     pub source_type: String,
+    #[schema(value_type = Object)]
     pub config_json: serde_json::Value,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = String)]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateDataSourceRequest {
     pub name: String,
     pub source_type: String,
+    #[schema(value_type = Object)]
     pub config_json: serde_json::Value,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct UpdateDataSourceRequest {
     pub name: Option<String>,
     pub source_type: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub config_json: Option<serde_json::Value>,
 }
 
 // ── Handlers ─────────────────────────────────────────────────────
 
 /// GET /sp/data-sources -- list all data sources.
+#[utoipa::path(
+    get,
+    path = "/sp/data-sources",
+    tag = "Stille Post",
+    responses(
+        (status = 200, description = "All data sources", body = Vec<SpDataSource>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_list(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<SpDataSource>>> {
@@ -59,6 +77,18 @@ pub async fn sp_data_sources_list(
 }
 
 /// POST /sp/data-sources -- create a new data source.
+#[utoipa::path(
+    post,
+    path = "/sp/data-sources",
+    tag = "Stille Post",
+    request_body = CreateDataSourceRequest,
+    responses(
+        (status = 201, description = "Data source created", body = SpDataSource),
+        (status = 400, description = "Invalid source type", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_create(
     State(state): State<Arc<AppState>>,
     Json(input): Json<CreateDataSourceRequest>,
@@ -86,6 +116,20 @@ pub async fn sp_data_sources_create(
 }
 
 /// GET /sp/data-sources/:id -- get a single data source.
+#[utoipa::path(
+    get,
+    path = "/sp/data-sources/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Data source ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Data source", body = SpDataSource),
+        (status = 404, description = "Data source not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -104,6 +148,22 @@ pub async fn sp_data_sources_get(
 }
 
 /// PUT /sp/data-sources/:id -- update a data source.
+#[utoipa::path(
+    put,
+    path = "/sp/data-sources/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Data source ID (UUID)")
+    ),
+    request_body = UpdateDataSourceRequest,
+    responses(
+        (status = 200, description = "Data source updated", body = SpDataSource),
+        (status = 400, description = "Invalid source type", body = QueryErrorResponse),
+        (status = 404, description = "Data source not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -140,6 +200,20 @@ pub async fn sp_data_sources_update(
 }
 
 /// DELETE /sp/data-sources/:id -- delete a data source.
+#[utoipa::path(
+    delete,
+    path = "/sp/data-sources/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Data source ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Data source deleted"),
+        (status = 404, description = "Data source not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -157,6 +231,20 @@ pub async fn sp_data_sources_delete(
 }
 
 /// POST /sp/data-sources/:id/test -- placeholder connection test.
+#[utoipa::path(
+    post,
+    path = "/sp/data-sources/{id}/test",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Data source ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Connection test result", body = Object),
+        (status = 404, description = "Data source not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_test(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -183,6 +271,18 @@ pub async fn sp_data_sources_test(
 /// Accepts multipart/form-data with a `file` field (max 100 MB).
 /// Saves the file to `{data_dir}/sp-uploads/{uuid}-{filename}` and creates
 /// an `sp_data_sources` record with `source_type='upload'`.
+#[utoipa::path(
+    post,
+    path = "/sp/data-sources/upload",
+    tag = "Stille Post",
+    request_body(content_type = "multipart/form-data", description = "File upload (`file` field, max 100 MB)"),
+    responses(
+        (status = 201, description = "Upload stored as a data source", body = SpDataSource),
+        (status = 400, description = "Missing or invalid file", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_data_sources_upload(
     State(state): State<Arc<AppState>>,
     mut multipart: Multipart,
@@ -9,38 +9,47 @@ use sqlx::types::Uuid;
 
 use crate::state::AppState;
 
+use super::super::QueryErrorResponse;
+
 use super::common::{bad_request, internal_error, not_found, require_pg, ApiResult};
 
 // ── Types ────────────────────────────────────────────────────────
 
 const VALID_CHANNELS: &[&str] = &["email", "webhook", "telegram"];
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpDelivery {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = Option<String>)]
     pub schedule_id: Option<Uuid>,
     pub channel: String,
+    #[schema(value_type = Object)]
     pub config_json: serde_json::Value,
     pub enabled: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateDeliveryRequest {
+    #[schema(value_type = String)]
     pub schedule_id: Uuid,
     pub channel: String,
+    #[schema(value_type = Object)]
     pub config_json: serde_json::Value,
     pub enabled: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct UpdateDeliveryRequest {
     pub channel: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub config_json: Option<serde_json::Value>,
     pub enabled: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
 pub struct DeliveryListQuery {
+    #[param(value_type = Option<String>)]
     pub schedule_id: Option<Uuid>,
 }
 
@@ -59,6 +68,17 @@ fn validate_channel(channel: &str) -> ApiResult<()> {
 // ── Handlers ─────────────────────────────────────────────────────
 
 /// GET /sp/deliveries -- list deliveries, optionally filtered by schedule_id.
+#[utoipa::path(
+    get,
+    path = "/sp/deliveries",
+    tag = "Stille Post",
+    params(DeliveryListQuery),
+    responses(
+        (status = 200, description = "Delivery configurations", body = Vec<SpDelivery>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_deliveries_list(
     State(state): State<Arc<AppState>>,
     axum::extract::Query(q): axum::extract::Query<DeliveryListQuery>,
@@ -84,6 +104,18 @@ pub async fn sp_deliveries_list(
 }
 
 /// POST /sp/deliveries -- create a delivery configuration.
+#[utoipa::path(
+    post,
+    path = "/sp/deliveries",
+    tag = "Stille Post",
+    request_body = CreateDeliveryRequest,
+    responses(
+        (status = 201, description = "Delivery created", body = SpDelivery),
+        (status = 400, description = "Invalid channel", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_deliveries_create(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreateDeliveryRequest>,
@@ -109,6 +141,22 @@ pub async fn sp_deliveries_create(
 }
 
 /// PUT /sp/deliveries/:id -- update a delivery configuration.
+#[utoipa::path(
+    put,
+    path = "/sp/deliveries/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Delivery ID (UUID)")
+    ),
+    request_body = UpdateDeliveryRequest,
+    responses(
+        (status = 200, description = "Delivery updated", body = SpDelivery),
+        (status = 400, description = "Invalid channel", body = QueryErrorResponse),
+        (status = 404, description = "Delivery not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_deliveries_update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -140,6 +188,20 @@ pub async fn sp_deliveries_update(
 }
 
 /// DELETE /sp/deliveries/:id -- delete a delivery configuration.
+#[utoipa::path(
+    delete,
+    path = "/sp/deliveries/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Delivery ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Delivery deleted"),
+        (status = 404, description = "Delivery not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_deliveries_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -160,6 +222,20 @@ pub async fn sp_deliveries_delete(
 }
 
 /// POST /sp/deliveries/:id/test -- test a delivery channel (placeholder).
+#[utoipa::path(
+    post,
+    path = "/sp/deliveries/{id}/test",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Delivery ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Channel test result", body = Object),
+        (status = 404, description = "Delivery not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_deliveries_test(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
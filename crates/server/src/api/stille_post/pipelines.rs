@@ -9,46 +9,57 @@ use sqlx::types::Uuid;
 
 use crate::state::AppState;
 
+use super::super::QueryErrorResponse;
+
 use super::common::{default_json_object, internal_error, not_found, require_pg, ApiResult};
 
 // ── Types ────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpPipeline {
+    #[schema(value_type = String)]
     pub id: Uuid,
     pub name: String,
     pub description: Option<String>,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
+    #[schema(value_type = String)]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SpPipelineWithSteps {
     #[serde(flatten)]
     pub pipeline: SpPipeline,
     pub steps: Vec<SpPipelineStep>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SpPipelineListItem {
     #[serde(flatten)]
     pub pipeline: SpPipeline,
     pub step_count: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpPipelineStep {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = String)]
     pub pipeline_id: Uuid,
+    #[schema(value_type = Option<String>)]
     pub agent_id: Option<Uuid>,
     pub step_order: i32,
+    #[schema(value_type = Object)]
     pub input_mapping: serde_json::Value,
+    #[schema(value_type = Object)]
     pub output_mapping: serde_json::Value,
     pub parallel_group: Option<i32>,
+    #[schema(value_type = Option<String>)]
     pub data_source_id: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreatePipelineRequest {
     pub name: String,
     pub description: Option<String>,
@@ -56,19 +67,23 @@ pub struct CreatePipelineRequest {
     pub steps: Vec<CreatePipelineStepRequest>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreatePipelineStepRequest {
+    #[schema(value_type = Option<String>)]
     pub agent_id: Option<Uuid>,
     pub step_order: i32,
     #[serde(default = "default_json_object")]
+    #[schema(value_type = Object)]
     pub input_mapping: serde_json::Value,
     #[serde(default = "default_json_object")]
+    #[schema(value_type = Object)]
     pub output_mapping: serde_json::Value,
     pub parallel_group: Option<i32>,
+    #[schema(value_type = Option<String>)]
     pub data_source_id: Option<Uuid>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct UpdatePipelineRequest {
     pub name: Option<String>,
     pub description: Option<String>,
@@ -78,6 +93,16 @@ pub struct UpdatePipelineRequest {
 // ── Handlers ─────────────────────────────────────────────────────
 
 /// List all pipelines with step counts.
+#[utoipa::path(
+    get,
+    path = "/sp/pipelines",
+    tag = "Stille Post",
+    responses(
+        (status = 200, description = "Pipelines with step counts", body = Vec<SpPipelineListItem>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_pipelines_list(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<SpPipelineListItem>>> {
@@ -123,6 +148,17 @@ pub async fn sp_pipelines_list(
 }
 
 /// Create a pipeline with steps (atomic transaction).
+#[utoipa::path(
+    post,
+    path = "/sp/pipelines",
+    tag = "Stille Post",
+    request_body = CreatePipelineRequest,
+    responses(
+        (status = 201, description = "Pipeline created", body = SpPipelineWithSteps),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_pipelines_create(
     State(state): State<Arc<AppState>>,
     Json(req): Json<CreatePipelineRequest>,
@@ -170,6 +206,20 @@ pub async fn sp_pipelines_create(
 }
 
 /// Get a single pipeline with all its steps.
+#[utoipa::path(
+    get,
+    path = "/sp/pipelines/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Pipeline ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Pipeline with steps", body = SpPipelineWithSteps),
+        (status = 404, description = "Pipeline not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_pipelines_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -199,6 +249,21 @@ pub async fn sp_pipelines_get(
 }
 
 /// Update a pipeline and optionally replace its steps (atomic transaction).
+#[utoipa::path(
+    put,
+    path = "/sp/pipelines/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Pipeline ID (UUID)")
+    ),
+    request_body = UpdatePipelineRequest,
+    responses(
+        (status = 200, description = "Pipeline updated", body = SpPipelineWithSteps),
+        (status = 404, description = "Pipeline not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_pipelines_update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -271,6 +336,20 @@ pub async fn sp_pipelines_update(
 }
 
 /// Delete a pipeline (cascade deletes steps via FK).
+#[utoipa::path(
+    delete,
+    path = "/sp/pipelines/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Pipeline ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Pipeline deleted"),
+        (status = 404, description = "Pipeline not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_pipelines_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -9,61 +9,82 @@ use sqlx::types::Uuid;
 
 use crate::state::AppState;
 
+use super::super::QueryErrorResponse;
+
 use super::common::{internal_error, not_found, require_pg, ApiResult};
 
 // ── Types ────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpRun {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = Option<String>)]
     pub pipeline_id: Option<Uuid>,
+    #[schema(value_type = Option<String>)]
     pub schedule_id: Option<Uuid>,
     pub status: String,
+    #[schema(value_type = Option<String>)]
     pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[schema(value_type = Option<String>)]
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub error: Option<String>,
     pub trigger_type: String,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SpRunWithSteps {
     #[serde(flatten)]
     pub run: SpRun,
     pub steps: Vec<SpStepResult>,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpStepResult {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = String)]
     pub run_id: Uuid,
+    #[schema(value_type = Option<String>)]
     pub step_id: Option<Uuid>,
+    #[schema(value_type = Option<String>)]
     pub agent_id: Option<Uuid>,
+    #[schema(value_type = Option<Object>)]
     pub input_data: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
     pub output_data: Option<serde_json::Value>,
     pub tokens_used: Option<i32>,
     pub duration_ms: Option<i32>,
     pub status: String,
 }
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpReport {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = String)]
     pub run_id: Uuid,
     pub title: String,
     pub content_html: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub content_json: Option<serde_json::Value>,
+    #[schema(value_type = Option<Object>)]
     pub render_blocks: Option<serde_json::Value>,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct TriggerRunRequest {
+    #[schema(value_type = String)]
     pub pipeline_id: Uuid,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
 pub struct RunListQuery {
+    #[param(value_type = Option<String>)]
     pub pipeline_id: Option<Uuid>,
     pub status: Option<String>,
 }
@@ -71,6 +92,17 @@ pub struct RunListQuery {
 // ── Handlers ─────────────────────────────────────────────────────
 
 /// GET /sp/runs -- list runs with optional pipeline_id and status filters.
+#[utoipa::path(
+    get,
+    path = "/sp/runs",
+    tag = "Stille Post",
+    params(RunListQuery),
+    responses(
+        (status = 200, description = "Pipeline runs", body = Vec<SpRun>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_runs_list(
     State(state): State<Arc<AppState>>,
     axum::extract::Query(q): axum::extract::Query<RunListQuery>,
@@ -96,6 +128,20 @@ pub async fn sp_runs_list(
 }
 
 /// GET /sp/runs/:id -- get a run with all step results.
+#[utoipa::path(
+    get,
+    path = "/sp/runs/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Run ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Run with step results", body = SpRunWithSteps),
+        (status = 404, description = "Run not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_runs_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -128,6 +174,17 @@ pub async fn sp_runs_get(
 }
 
 /// POST /sp/runs -- trigger a manual pipeline run (creates a pending record).
+#[utoipa::path(
+    post,
+    path = "/sp/runs",
+    tag = "Stille Post",
+    request_body = TriggerRunRequest,
+    responses(
+        (status = 201, description = "Run queued", body = SpRun),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_runs_create(
     State(state): State<Arc<AppState>>,
     Json(req): Json<TriggerRunRequest>,
@@ -149,6 +206,20 @@ pub async fn sp_runs_create(
 }
 
 /// DELETE /sp/runs/:id -- cancel or delete a pipeline run.
+#[utoipa::path(
+    delete,
+    path = "/sp/runs/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Run ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Run deleted"),
+        (status = 404, description = "Run not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_runs_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -169,6 +240,16 @@ pub async fn sp_runs_delete(
 }
 
 /// GET /sp/reports -- list all reports (newest first).
+#[utoipa::path(
+    get,
+    path = "/sp/reports",
+    tag = "Stille Post",
+    responses(
+        (status = 200, description = "Reports, newest first", body = Vec<SpReport>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_reports_list(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<SpReport>>> {
@@ -188,6 +269,20 @@ pub async fn sp_reports_list(
 }
 
 /// GET /sp/reports/:id -- get a single report.
+#[utoipa::path(
+    get,
+    path = "/sp/reports/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Report ID (UUID)")
+    ),
+    responses(
+        (status = 200, description = "Report", body = SpReport),
+        (status = 404, description = "Report not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_reports_get(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
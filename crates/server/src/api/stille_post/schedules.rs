@@ -9,51 +9,64 @@ use sqlx::types::Uuid;
 
 use crate::state::AppState;
 
+use super::super::QueryErrorResponse;
+
 use super::common::{bad_request, internal_error, not_found, require_pg, ApiResult};
 
 // ── Types ────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpSchedule {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = String)]
     pub pipeline_id: Uuid,
     pub cron_expression: String,
     pub timezone: String,
     pub enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
     pub last_run_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
     pub next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
 /// Extended schedule with joined pipeline name for list views.
-#[derive(Debug, Serialize, sqlx::FromRow)]
+#[derive(Debug, Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct SpScheduleWithPipeline {
+    #[schema(value_type = String)]
     pub id: Uuid,
+    #[schema(value_type = String)]
     pub pipeline_id: Uuid,
     pub cron_expression: String,
     pub timezone: String,
     pub enabled: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
     pub last_run_at: Option<chrono::DateTime<chrono::Utc>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<String>)]
     pub next_run_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[schema(value_type = String)]
     pub created_at: chrono::DateTime<chrono::Utc>,
     /// Pipeline name from the joined sp_pipelines table.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub pipeline_name: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateScheduleRequest {
+    #[schema(value_type = String)]
     pub pipeline_id: Uuid,
     pub cron_expression: String,
     pub timezone: Option<String>,
     pub enabled: Option<bool>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct UpdateScheduleRequest {
     pub cron_expression: Option<String>,
     pub timezone: Option<String>,
@@ -135,6 +148,16 @@ fn is_cron_value(v: &str) -> bool {
 // ── Handlers ─────────────────────────────────────────────────────
 
 /// GET /sp/schedules -- list all schedules (with pipeline name).
+#[utoipa::path(
+    get,
+    path = "/sp/schedules",
+    tag = "Stille Post",
+    responses(
+        (status = 200, description = "Schedules with pipeline names", body = Vec<SpScheduleWithPipeline>),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_schedules_list(
     State(state): State<Arc<AppState>>,
 ) -> ApiResult<Json<Vec<SpScheduleWithPipeline>>> {
@@ -154,6 +177,18 @@ pub async fn sp_schedules_list(
 }
 
 /// POST /sp/schedules -- create a new schedule.
+#[utoipa::path(
+    post,
+    path = "/sp/schedules",
+    tag = "Stille Post",
+    request_body = CreateScheduleRequest,
+    responses(
+        (status = 201, description = "Schedule created", body = SpSchedule),
+        (status = 400, description = "Invalid cron expression or timezone", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_schedules_create(
     State(state): State<Arc<AppState>>,
     Json(input): Json<CreateScheduleRequest>,
@@ -185,6 +220,22 @@ pub async fn sp_schedules_create(
 }
 
 /// PUT /sp/schedules/:id -- update a schedule (enable/disable, change cron, change timezone).
+#[utoipa::path(
+    put,
+    path = "/sp/schedules/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Schedule ID (UUID)")
+    ),
+    request_body = UpdateScheduleRequest,
+    responses(
+        (status = 200, description = "Schedule updated", body = SpSchedule),
+        (status = 400, description = "Invalid cron expression or timezone", body = QueryErrorResponse),
+        (status = 404, description = "Schedule not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_schedules_update(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
@@ -218,6 +269,20 @@ pub async fn sp_schedules_update(
 }
 
 /// DELETE /sp/schedules/:id -- delete a schedule.
+#[utoipa::path(
+    delete,
+    path = "/sp/schedules/{id}",
+    tag = "Stille Post",
+    params(
+        ("id" = String, Path, description = "Schedule ID (UUID)")
+    ),
+    responses(
+        (status = 204, description = "Schedule deleted"),
+        (status = 404, description = "Schedule not found", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_schedules_delete(
     State(state): State<Arc<AppState>>,
     Path(id): Path<Uuid>,
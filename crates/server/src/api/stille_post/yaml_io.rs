@@ -29,6 +29,16 @@ use super::super::QueryErrorResponse;
 // ── Export endpoint ──────────────────────────────────────────────
 
 /// GET /sp/export -- export all SP configuration as multi-document YAML.
+#[utoipa::path(
+    get,
+    path = "/sp/export",
+    tag = "Stille Post",
+    responses(
+        (status = 200, description = "All Stille Post configuration as multi-document YAML", content_type = "application/x-yaml", body = String),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_export(
     State(state): State<Arc<AppState>>,
 ) -> Result<impl IntoResponse, (axum::http::StatusCode, Json<QueryErrorResponse>)> {
@@ -249,6 +259,18 @@ pub async fn sp_export(
 /// Resources are created in dependency order:
 /// agents -> data sources -> pipelines -> schedules -> deliveries.
 /// Names are used as keys for cross-references (not UUIDs).
+#[utoipa::path(
+    post,
+    path = "/sp/import",
+    tag = "Stille Post",
+    request_body = SpImportRequest,
+    responses(
+        (status = 200, description = "Per-resource import outcome", body = SpImportResult),
+        (status = 400, description = "Invalid YAML", body = QueryErrorResponse),
+        (status = 500, description = "Internal error", body = QueryErrorResponse),
+        (status = 503, description = "PostgreSQL not configured", body = QueryErrorResponse)
+    )
+)]
 pub async fn sp_import(
     State(state): State<Arc<AppState>>,
     Json(req): Json<SpImportRequest>,
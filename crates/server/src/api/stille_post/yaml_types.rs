@@ -113,7 +113,7 @@ fn default_true() -> bool {
 
 // ── Import/export result types ──────────────────────────────────
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct SpImportRequest {
     pub yaml: String,
     /// If true, overwrite existing resources with same name. Default: false.
@@ -121,7 +121,7 @@ pub struct SpImportRequest {
     pub overwrite: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SpImportResult {
     pub created: Vec<SpImportedResource>,
     pub updated: Vec<SpImportedResource>,
@@ -129,7 +129,7 @@ pub struct SpImportResult {
     pub errors: Vec<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct SpImportedResource {
     pub kind: String,
     pub name: String,
@@ -8,8 +8,9 @@ use tracing::info;
 
 use stupid_tool_runtime::permission::{PermissionLevel, PermissionPolicy, PolicyChecker};
 use stupid_tool_runtime::{
-    AgenticLoop, BashExecuteTool, FileReadTool, FileWriteTool, GraphQueryTool, LlmProviderBridge,
-    PermissionChecker, RuleEvaluateTool, RuleListTool, ToolRegistry,
+    AgenticLoop, BashExecuteTool, FileEditTool, FileReadTool, FileWriteTool, GlobTool,
+    GraphQueryTool, GrepTool, LlmProviderBridge, PermissionChecker, RuleEvaluateTool,
+    RuleListTool, ToolRegistry,
 };
 
 /// Load configuration from `.env` and environment variables.
@@ -78,8 +79,11 @@ pub fn build_agent_executor(config: &stupid_core::Config) -> Option<stupid_agent
 }
 
 /// Build the agentic loop from config, using `LlmProviderBridge` to wrap the
-/// existing LLM provider into a `ToolAwareLlmProvider` with all 6 tools registered.
-pub fn build_agentic_loop(config: &stupid_core::Config) -> Option<AgenticLoop> {
+/// existing LLM provider into a `ToolAwareLlmProvider` with all 9 tools registered.
+pub fn build_agentic_loop(
+    config: &stupid_core::Config,
+    approvals: Arc<crate::tool_approvals::ToolApprovals>,
+) -> Option<AgenticLoop> {
     // Create LLM provider and wrap it through the bridge
     let llm_provider = match stupid_llm::providers::create_provider(&config.llm, &config.ollama) {
         Ok(p) => p,
@@ -98,7 +102,7 @@ pub fn build_agentic_loop(config: &stupid_core::Config) -> Option<AgenticLoop> {
         config.llm.provider.clone(),
     ));
 
-    // Register all 6 built-in tools
+    // Register all 9 built-in tools
     let mut registry = ToolRegistry::new();
     registry
         .register(BashExecuteTool)
@@ -109,6 +113,15 @@ pub fn build_agentic_loop(config: &stupid_core::Config) -> Option<AgenticLoop> {
     registry
         .register(FileWriteTool)
         .expect("register FileWriteTool");
+    registry
+        .register(FileEditTool)
+        .expect("register FileEditTool");
+    registry
+        .register(GlobTool)
+        .expect("register GlobTool");
+    registry
+        .register(GrepTool)
+        .expect("register GrepTool");
     registry
         .register(GraphQueryTool)
         .expect("register GraphQueryTool");
@@ -119,17 +132,23 @@ pub fn build_agentic_loop(config: &stupid_core::Config) -> Option<AgenticLoop> {
         .register(RuleEvaluateTool)
         .expect("register RuleEvaluateTool");
 
-    // Server-side: auto-approve all tool executions (no interactive confirmation)
+    // Server-side: auto-approve tool executions except those configured to
+    // wait for a client's approval on /tool-approvals.
     let mut policy = PermissionPolicy::new();
     policy.default = PermissionLevel::AutoApprove;
-    let permission_checker: Arc<dyn PermissionChecker> = Arc::new(PolicyChecker::new(policy));
+    for tool in &config.llm.confirm_tools {
+        policy.rules.insert(tool.clone(), PermissionLevel::RequireConfirmation);
+    }
+    let approval_timeout = std::time::Duration::from_secs(config.llm.approval_timeout_secs);
+    let permission_checker: Arc<dyn PermissionChecker> =
+        Arc::new(PolicyChecker::new(policy).with_approver(approvals, approval_timeout));
 
     let agentic_loop = AgenticLoop::new(provider, Arc::new(registry), permission_checker)
         .with_temperature(config.llm.temperature)
         .with_max_tokens(config.llm.max_tokens);
 
     info!(
-        "Agentic loop ready (provider: {}, 6 tools registered)",
+        "Agentic loop ready (provider: {}, 9 tools registered)",
         config.llm.provider
     );
     Some(agentic_loop)
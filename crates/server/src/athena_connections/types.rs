@@ -108,6 +108,45 @@ pub struct AthenaConnectionSafe {
     pub updated_at: String,
 }
 
+impl AthenaConnectionSafe {
+    /// Catalog entry for this connection's cached schema; `None` while the
+    /// connection is disabled or has no schema yet.
+    pub fn to_external_source(&self) -> Option<stupid_catalog::ExternalSource> {
+        let schema = self.schema.as_ref().filter(|_| self.enabled)?;
+        Some(stupid_catalog::ExternalSource {
+            name: self.name.clone(),
+            kind: "athena".to_string(),
+            connection_id: self.id.clone(),
+            databases: schema
+                .databases
+                .iter()
+                .map(|db| stupid_catalog::ExternalDatabase {
+                    name: db.name.clone(),
+                    tables: db
+                        .tables
+                        .iter()
+                        .map(|t| stupid_catalog::ExternalTable {
+                            name: t.name.clone(),
+                            columns: t
+                                .columns
+                                .iter()
+                                .map(|col| stupid_catalog::ExternalColumn {
+                                    name: col.name.clone(),
+                                    data_type: col.data_type.clone(),
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            last_refreshed: chrono::DateTime::parse_from_rfc3339(&schema.fetched_at)
+                .ok()
+                .map(|t| t.with_timezone(&chrono::Utc)),
+            refresh_ttl_secs: stupid_catalog::DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
+        })
+    }
+}
+
 /// Decrypted credentials for Athena client creation.
 #[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
 pub struct AthenaConnectionCredentials {
@@ -150,6 +189,40 @@ pub struct AthenaConnectionInput {
     pub color: String,
 }
 
+impl AthenaConnectionInput {
+    /// Credentials for testing this input before it is saved. Blank keys
+    /// fall back to `existing`'s, as on update, but only while the input
+    /// targets the same region and endpoint.
+    pub fn to_credentials(
+        &self,
+        id: &str,
+        existing: Option<&AthenaConnectionCredentials>,
+    ) -> anyhow::Result<AthenaConnectionCredentials> {
+        let same_target =
+            existing.is_some_and(|c| c.region == self.region && c.endpoint_url == self.endpoint_url);
+        let secret = |input: &str, stored: fn(&AthenaConnectionCredentials) -> &str| match existing {
+            Some(existing) if input.is_empty() && !stored(existing).is_empty() => {
+                anyhow::ensure!(same_target, "The region or endpoint changed; enter the credentials to test it");
+                Ok(stored(existing).to_string())
+            }
+            _ => Ok(input.to_string()),
+        };
+        Ok(AthenaConnectionCredentials {
+            id: id.to_string(),
+            name: self.name.clone(),
+            region: self.region.clone(),
+            catalog: self.catalog.clone(),
+            database: self.database.clone(),
+            workgroup: self.workgroup.clone(),
+            output_location: self.output_location.clone(),
+            access_key_id: secret(&self.access_key_id, |c| &c.access_key_id)?,
+            secret_access_key: secret(&self.secret_access_key, |c| &c.secret_access_key)?,
+            session_token: secret(&self.session_token, |c| &c.session_token)?,
+            endpoint_url: self.endpoint_url.clone(),
+        })
+    }
+}
+
 // ── On-disk format ───────────────────────────────────────────────────
 
 /// On-disk format: credentials stored as encrypted hex strings.
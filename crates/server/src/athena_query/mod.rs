@@ -4,8 +4,10 @@
 //! and helper functions for executing queries with polling.
 
 mod execution;
+mod scan;
 mod schema;
 mod types;
 
 pub use execution::*;
+pub use scan::AthenaScanner;
 pub use schema::*;
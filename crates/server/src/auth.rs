@@ -0,0 +1,157 @@
+//! API key / bearer token authentication.
+//!
+//! When keys are configured (`API_KEYS`), every route except `/health`
+//! requires `Authorization: Bearer <key>` or `X-API-Key: <key>`. `/health`
+//! checks the key itself for its detailed variants.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::{header, Extensions, HeaderMap, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use subtle::ConstantTimeEq;
+
+use stupid_core::config::ApiKey;
+
+use crate::api::QueryErrorResponse;
+
+/// Routes reachable without a key.
+pub(crate) const PUBLIC_PATHS: &[&str] = &["/health"];
+
+/// Configured API keys; auth is disabled when empty.
+#[derive(Clone, Default)]
+pub struct ApiKeys(Arc<Vec<ApiKey>>);
+
+impl ApiKeys {
+    pub fn new(keys: Vec<ApiKey>) -> Self {
+        Self(Arc::new(keys))
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.0.is_empty()
+    }
+
+    /// Label of the key matching `candidate`.
+    ///
+    /// Every key is compared in constant time, and all keys are checked, so
+    /// timing reveals neither the key contents nor which key matched.
+    pub(crate) fn verify(&self, candidate: &str) -> Option<&str> {
+        let mut matched = None;
+        for api_key in self.0.iter() {
+            if bool::from(api_key.key.as_bytes().ct_eq(candidate.as_bytes())) {
+                matched = Some(api_key.label.as_str());
+            }
+        }
+        matched
+    }
+
+    /// Whether `headers` carry a valid key, or auth is disabled.
+    pub(crate) fn authorizes(&self, headers: &HeaderMap) -> bool {
+        !self.is_enabled() || presented_key(headers).and_then(|key| self.verify(key)).is_some()
+    }
+}
+
+/// The key presented with a request, from `Authorization: Bearer` or `X-API-Key`.
+pub(crate) fn presented_key(headers: &HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    bearer
+        .or_else(|| headers.get("x-api-key").and_then(|v| v.to_str().ok()))
+        .map(str::trim)
+}
+
+/// Who sent a request: `key:{label}` for a valid API key, else `ip:{addr}`.
+/// The key itself never appears, so the result is safe to log.
+pub(crate) fn client_id(keys: &ApiKeys, headers: &HeaderMap, extensions: &Extensions) -> String {
+    if let Some(label) = presented_key(headers).and_then(|key| keys.verify(key)) {
+        return format!("key:{}", label);
+    }
+    match extensions.get::<ConnectInfo<SocketAddr>>() {
+        Some(ConnectInfo(addr)) => format!("ip:{}", addr.ip()),
+        None => "ip:unknown".to_string(),
+    }
+}
+
+/// Middleware rejecting requests without a valid key with 401.
+pub async fn require_api_key(State(keys): State<ApiKeys>, request: Request, next: Next) -> Response {
+    if !keys.is_enabled() || PUBLIC_PATHS.contains(&request.uri().path()) {
+        return next.run(request).await;
+    }
+
+    match presented_key(request.headers()).and_then(|key| keys.verify(key)) {
+        Some(label) => {
+            tracing::debug!("{} {} authorized by key '{}'", request.method(), request.uri().path(), label);
+            next.run(request).await
+        }
+        None => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(QueryErrorResponse { error: "Missing or invalid API key".to_string() }),
+        )
+            .into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::Request;
+    use tower::ServiceExt;
+
+    fn keys() -> Vec<ApiKey> {
+        vec![
+            ApiKey { label: "ci".to_string(), key: "ci-secret".to_string() },
+            ApiKey { label: "ops".to_string(), key: "ops-secret".to_string() },
+        ]
+    }
+
+    async fn status(app: &axum::Router, path: &str, header: Option<(&str, &str)>) -> StatusCode {
+        let mut request = Request::get(path);
+        if let Some((name, value)) = header {
+            request = request.header(name, value);
+        }
+        app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap().status()
+    }
+
+    #[test]
+    fn test_verify_returns_matching_label() {
+        let keys = ApiKeys::new(keys());
+        assert_eq!(keys.verify("ops-secret"), Some("ops"));
+        assert_eq!(keys.verify("ops-secre"), None);
+        assert_eq!(keys.verify(""), None);
+        assert!(!ApiKeys::default().is_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_protected_routes_require_a_valid_key() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), keys()).await;
+        let app = crate::router::build_router(state);
+
+        assert_eq!(status(&app, "/stats", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, "/stats", Some(("authorization", "Bearer wrong"))).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, "/stats", Some(("authorization", "ci-secret"))).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, "/anomaly-rules", None).await, StatusCode::UNAUTHORIZED);
+        assert_eq!(status(&app, "/docs", None).await, StatusCode::UNAUTHORIZED);
+
+        assert_eq!(status(&app, "/stats", Some(("authorization", "Bearer ci-secret"))).await, StatusCode::OK);
+        assert_eq!(status(&app, "/stats", Some(("x-api-key", "ops-secret"))).await, StatusCode::OK);
+        assert_eq!(status(&app, "/health", None).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_no_keys_disables_auth() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state);
+
+        assert_eq!(status(&app, "/stats", None).await, StatusCode::OK);
+    }
+}
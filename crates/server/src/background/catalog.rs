@@ -65,40 +65,7 @@ pub(super) async fn sync_catalog_with_external_sources(
     {
         let athena_store = athena_connections.read().await;
         if let Ok(conns) = athena_store.list() {
-            let sources: Vec<stupid_catalog::ExternalSource> = conns
-                .iter()
-                .filter(|c| c.enabled && c.schema.is_some())
-                .map(|c| {
-                    let schema = c.schema.as_ref().unwrap();
-                    stupid_catalog::ExternalSource {
-                        name: c.name.clone(),
-                        kind: "athena".to_string(),
-                        connection_id: c.id.clone(),
-                        databases: schema
-                            .databases
-                            .iter()
-                            .map(|db| stupid_catalog::ExternalDatabase {
-                                name: db.name.clone(),
-                                tables: db
-                                    .tables
-                                    .iter()
-                                    .map(|t| stupid_catalog::ExternalTable {
-                                        name: t.name.clone(),
-                                        columns: t
-                                            .columns
-                                            .iter()
-                                            .map(|col| stupid_catalog::ExternalColumn {
-                                                name: col.name.clone(),
-                                                data_type: col.data_type.clone(),
-                                            })
-                                            .collect(),
-                                    })
-                                    .collect(),
-                            })
-                            .collect(),
-                    }
-                })
-                .collect();
+            let sources: Vec<stupid_catalog::ExternalSource> = conns.iter().filter_map(|c| c.to_external_source()).collect();
 
             if !sources.is_empty() {
                 info!("Persisting {} Athena source(s) to catalog/external/", sources.len());
@@ -2,19 +2,22 @@ use std::sync::Arc;
 
 use tracing::info;
 
-use crate::state::{self, SharedGraph, SharedPipeline};
+use crate::live::warm_compute_and_publish;
+use crate::state::{self, ComputePhase, LoadingState, SharedGraph, SharedPipeline};
 
 /// Run initial graph algorithms (PageRank, degree centrality, community detection)
 /// and the hot_connect + warm_compute pipeline, then start the background scheduler.
 pub(super) async fn run_compute(
     segments: &[String],
     effective_data_dir: &std::path::Path,
+    readers: &stupid_segment::ReaderCache,
     shared_graph: SharedGraph,
     knowledge: stupid_compute::SharedKnowledgeState,
     pipeline: SharedPipeline,
     app_state: &Arc<state::AppState>,
 ) {
     let sched_config = stupid_compute::SchedulerConfig::default();
+    let p3_interval = std::time::Duration::from_secs(sched_config.p3_interval_seconds);
     let mut scheduler = stupid_compute::Scheduler::new(sched_config, knowledge.clone());
 
     let p2_interval = std::time::Duration::from_secs(3600);
@@ -30,41 +33,48 @@ pub(super) async fn run_compute(
     scheduler.register_task(Arc::new(
         stupid_compute::AnomalyDetectionTask::new(p2_interval),
     ));
+    scheduler.register_task(Arc::new(
+        stupid_compute::scheduler::tasks::FullKmeansTask::new(pipeline.clone(), p3_interval),
+    ));
 
     scheduler.add_dependency("entity_extraction", "pagerank");
     scheduler.add_dependency("entity_extraction", "community_detection");
 
-    run_initial_algorithms(&shared_graph, &knowledge).await;
-    run_pipeline(segments, effective_data_dir, &pipeline, &knowledge);
+    let loading = &app_state.loading;
+    run_initial_algorithms(&shared_graph, &knowledge, loading).await;
+    loading.set_compute_phase(ComputePhase::Computing("pipeline")).await;
+    run_pipeline(segments, effective_data_dir, readers, &pipeline, &knowledge, &app_state.broadcast);
 
     let shutdown = scheduler.shutdown_signal();
     let metrics = scheduler.metrics_handle();
-    {
-        let mut sched_lock = app_state.scheduler.write().await;
-        *sched_lock = Some(state::SchedulerHandle { shutdown, metrics });
-    }
-
-    std::thread::spawn(move || {
+    let thread = std::thread::spawn(move || {
         scheduler.run();
     });
+
+    let mut sched_lock = app_state.scheduler.write().await;
+    *sched_lock = Some(state::SchedulerHandle { shutdown, metrics, thread });
 }
 
 /// Run PageRank, degree centrality, and community detection on the current graph.
 async fn run_initial_algorithms(
     shared_graph: &SharedGraph,
     knowledge: &stupid_compute::SharedKnowledgeState,
+    loading: &LoadingState,
 ) {
     info!("Running initial PageRank, degree, community computations...");
     let graph_read = shared_graph.read().await;
 
+    loading.set_compute_phase(ComputePhase::Computing("pagerank")).await;
     let t = std::time::Instant::now();
     let pagerank = stupid_compute::algorithms::pagerank::pagerank_default(&graph_read);
     info!("  pagerank done in {:.1}s ({} nodes)", t.elapsed().as_secs_f64(), pagerank.len());
 
+    loading.set_compute_phase(ComputePhase::Computing("degree_centrality")).await;
     let t = std::time::Instant::now();
     let degrees = stupid_compute::algorithms::degree::degree_centrality(&graph_read);
     info!("  degree_centrality done in {:.1}s ({} nodes)", t.elapsed().as_secs_f64(), degrees.len());
 
+    loading.set_compute_phase(ComputePhase::Computing("community_detection")).await;
     let t = std::time::Instant::now();
     let communities = stupid_compute::algorithms::communities::label_propagation_default(&graph_read);
     info!("  community_detection done in {:.1}s ({} communities)", t.elapsed().as_secs_f64(), communities.len());
@@ -89,15 +99,17 @@ async fn run_initial_algorithms(
 fn run_pipeline(
     segments: &[String],
     effective_data_dir: &std::path::Path,
+    readers: &stupid_segment::ReaderCache,
     pipeline: &SharedPipeline,
     knowledge: &stupid_compute::SharedKnowledgeState,
+    broadcast_tx: &tokio::sync::broadcast::Sender<String>,
 ) {
     info!("Running compute pipeline (hot_connect + warm_compute)...");
     let pipeline_start = std::time::Instant::now();
     let mut all_docs: Vec<stupid_core::Document> = Vec::new();
 
     for seg_id in segments {
-        let reader = match stupid_segment::reader::SegmentReader::open(effective_data_dir, seg_id) {
+        let reader = match readers.get_or_open(effective_data_dir, seg_id) {
             Ok(r) => r,
             Err(_) => continue,
         };
@@ -120,7 +132,7 @@ fn run_pipeline(
         if all_docs.len() > 100_000 {
             let mut pipe = pipeline.lock().unwrap();
             let mut state = knowledge.write().unwrap();
-            pipe.warm_compute(&mut state, &all_docs);
+            warm_compute_and_publish(&mut pipe, &mut state, &all_docs, broadcast_tx);
             all_docs.clear();
         }
     }
@@ -128,7 +140,7 @@ fn run_pipeline(
     if !all_docs.is_empty() {
         let mut pipe = pipeline.lock().unwrap();
         let mut state = knowledge.write().unwrap();
-        pipe.warm_compute(&mut state, &all_docs);
+        warm_compute_and_publish(&mut pipe, &mut state, &all_docs, broadcast_tx);
     }
 
     let k = knowledge.read().unwrap();
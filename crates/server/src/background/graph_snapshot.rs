@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use tracing::info;
+
+use stupid_core::GraphMapping;
+use stupid_graph::{GraphManifest, GraphStore};
+
+/// Snapshot and manifest locations under `{data_dir}/graph/`.
+fn paths(data_dir: &Path) -> (PathBuf, PathBuf) {
+    let dir = data_dir.join("graph");
+    (dir.join("snapshot.msgpack.zst"), dir.join("manifest.json"))
+}
+
+/// Load the persisted graph if its manifest matches `segments` and `mapping`.
+///
+/// Returns the graph and its document count, or `None` when the graph must be
+/// rebuilt from segments.
+pub(super) async fn load_fresh_snapshot(
+    data_dir: &Path,
+    segments: &[String],
+    mapping: &GraphMapping,
+) -> Option<(GraphStore, u64)> {
+    let (snapshot_path, manifest_path) = paths(data_dir);
+    let manifest = match GraphManifest::load(&manifest_path) {
+        Ok(Some(manifest)) if manifest.is_fresh(segments, mapping) => manifest,
+        Ok(Some(_)) => {
+            info!("Persisted graph snapshot is stale — rebuilding");
+            return None;
+        }
+        Ok(None) => {
+            info!("No persisted graph snapshot — building from segments");
+            return None;
+        }
+        Err(e) => {
+            tracing::warn!("Failed to load graph manifest: {} — rebuilding", e);
+            return None;
+        }
+    };
+
+    let start = std::time::Instant::now();
+    match tokio::task::spawn_blocking(move || GraphStore::load_snapshot(&snapshot_path)).await {
+        Ok(Ok(graph)) => {
+            info!(
+                "Loaded graph snapshot in {:.1}s ({} segments)",
+                start.elapsed().as_secs_f64(),
+                segments.len()
+            );
+            Some((graph, manifest.doc_count))
+        }
+        Ok(Err(e)) => {
+            tracing::warn!("Failed to load graph snapshot: {} — rebuilding", e);
+            None
+        }
+        Err(e) => {
+            tracing::warn!("Graph snapshot load task failed: {} — rebuilding", e);
+            None
+        }
+    }
+}
+
+/// Persist `graph` and its manifest, handing the graph back once written.
+///
+/// Failures are logged; the graph is returned either way.
+pub(super) async fn save_snapshot(
+    data_dir: &Path,
+    graph: GraphStore,
+    segments: &[String],
+    mapping: Arc<GraphMapping>,
+    doc_count: u64,
+) -> GraphStore {
+    let (snapshot_path, manifest_path) = paths(data_dir);
+    let segments = segments.to_vec();
+    let start = std::time::Instant::now();
+
+    let task = tokio::task::spawn_blocking(move || {
+        let result = (|| {
+            if let Some(dir) = snapshot_path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            // Drop the old manifest first so a half-written snapshot is never
+            // mistaken for a fresh one.
+            if manifest_path.exists() {
+                std::fs::remove_file(&manifest_path)?;
+            }
+            graph.save_snapshot(&snapshot_path)?;
+            GraphManifest::new(&segments, &mapping, doc_count).save(&manifest_path)
+        })();
+        (graph, result)
+    });
+
+    match task.await {
+        Ok((graph, Ok(()))) => {
+            info!("Graph snapshot saved in {:.1}s", start.elapsed().as_secs_f64());
+            graph
+        }
+        Ok((graph, Err(e))) => {
+            tracing::warn!("Failed to save graph snapshot: {}", e);
+            graph
+        }
+        // The blocking task owned the graph, so there's nothing to fall back to.
+        Err(e) => std::panic::resume_unwind(e.into_panic()),
+    }
+}
@@ -8,11 +8,12 @@ use tracing::info;
 use stupid_storage::StorageEngine;
 
 use crate::graph_ops::{apply_graph_op, extract_graph_ops, GraphOp};
-use crate::state::{self, LoadingPhase, LoadingState, SharedGraph, SharedPipeline};
+use crate::state::{self, ComputePhase, LoadingPhase, LoadingState, SharedGraph, SharedPipeline};
 
 use super::catalog::{build_and_persist_catalog, sync_catalog_with_external_sources};
 use super::compute::run_compute;
 use super::discovery::discover_segments;
+use super::graph_snapshot::{load_fresh_snapshot, save_snapshot};
 
 struct SegmentResult {
     seg_id: String,
@@ -58,10 +59,13 @@ pub(crate) async fn background_load(
             // Pre-fetch S3 segments to local cache.
             // SegmentReader requires local files, so we download before graph building.
             info!("Downloading {} remote segments to local cache...", remote.len());
+            loading.set_phase(LoadingPhase::DownloadingSegments).await;
+            loading.set_progress(0, remote.len() as u64);
             let mut cache_dir = None;
             for (i, seg_id) in remote.iter().enumerate() {
                 let dir = storage.segment_data_dir(seg_id).await?;
                 cache_dir = Some(dir);
+                loading.set_progress(i as u64 + 1, remote.len() as u64);
                 if (i + 1) % 10 == 0 || i + 1 == remote.len() {
                     info!("  Downloaded {}/{} segments", i + 1, remote.len());
                 }
@@ -100,7 +104,7 @@ async fn load_segments_and_compute(
         *ids = segments.to_vec();
     }
 
-    // Phase 2: Build graph from segments.
+    // Phase 2: Load the graph snapshot if fresh, otherwise build from segments.
     loading.set_phase(LoadingPhase::LoadingSegments).await;
     let reader_threads = 4usize;
     info!(
@@ -108,9 +112,24 @@ async fn load_segments_and_compute(
         segments.len(), reader_threads
     );
 
-    let (graph, doc_count) = build_graph(
-        effective_data_dir, segments, reader_threads, &loading, total,
-    ).await;
+    // One reader cache shared by the graph build's workers and the compute
+    // pass, bounded so only the segments being worked on stay decompressed.
+    let readers = Arc::new(stupid_segment::ReaderCache::new(reader_threads));
+
+    let mapping = app_state.graph_mapping.clone();
+    let (graph, doc_count) = match load_fresh_snapshot(&app_state.data_dir, segments, &mapping).await {
+        Some(loaded) => {
+            loading.set_progress(total, total);
+            loaded
+        }
+        None => {
+            let (graph, doc_count) = build_graph(
+                effective_data_dir, segments, reader_threads, &readers, &loading, total, mapping.clone(),
+            ).await;
+            let graph = save_snapshot(&app_state.data_dir, graph, segments, mapping, doc_count).await;
+            (graph, doc_count)
+        }
+    };
 
     doc_count_shared.store(doc_count, Ordering::Relaxed);
 
@@ -133,6 +152,7 @@ async fn load_segments_and_compute(
 
     // ── Catalog: check freshness, load or rebuild ──
     info!("Building catalog...");
+    loading.set_compute_phase(ComputePhase::BuildingCatalog).await;
     let catalog_store = &app_state.catalog_store;
     {
         let manifest_fresh = match catalog_store.load_manifest() {
@@ -181,9 +201,11 @@ async fn load_segments_and_compute(
 
     info!("Starting compute scheduler in background...");
     run_compute(
-        segments, effective_data_dir,
+        segments, effective_data_dir, &readers,
         shared_graph, knowledge, pipeline, &app_state,
     ).await;
+    loading.set_compute_phase(ComputePhase::Ready).await;
+    info!("Compute results ready");
 
     Ok(())
 }
@@ -194,8 +216,10 @@ async fn build_graph(
     effective_data_dir: &std::path::Path,
     segments: &[String],
     reader_threads: usize,
+    readers: &Arc<stupid_segment::ReaderCache>,
     loading: &Arc<LoadingState>,
     total: u64,
+    mapping: Arc<stupid_core::GraphMapping>,
 ) -> (stupid_graph::GraphStore, u64) {
     use rayon::prelude::*;
     use std::sync::atomic::AtomicU64;
@@ -208,6 +232,7 @@ async fn build_graph(
 
     let segments_for_pool: Vec<String> = segments.to_vec();
     let data_dir_for_pool = effective_data_dir.to_path_buf();
+    let readers_for_pool = readers.clone();
     let skipped = Arc::new(AtomicU64::new(0));
     let skipped_clone = skipped.clone();
 
@@ -216,11 +241,13 @@ async fn build_graph(
             .num_threads(reader_threads)
             .build()
             .expect("Failed to create segment reader thread pool");
+        // Decode only the fields the mapping reads.
+        let fields = mapping.fields();
 
         pool.install(|| {
             segments_for_pool.par_iter().for_each(|seg_id| {
                 let seg_start = std::time::Instant::now();
-                let reader = match stupid_segment::reader::SegmentReader::open(&data_dir_for_pool, seg_id) {
+                let reader = match readers_for_pool.get_or_open(&data_dir_for_pool, seg_id) {
                     Ok(r) => r,
                     Err(e) => {
                         tracing::warn!("Skipping segment '{}': {}", seg_id, e);
@@ -235,10 +262,10 @@ async fn build_graph(
 
                 let mut ops = Vec::new();
                 let mut doc_count = 0u64;
-                for doc_result in reader.iter() {
+                for doc_result in reader.iter_fields(&fields) {
                     match doc_result {
                         Ok(doc) => {
-                            extract_graph_ops(&doc, seg_id, &mut ops);
+                            extract_graph_ops(&doc, &mapping, &mut ops);
                             doc_count += 1;
                         }
                         Err(e) => {
@@ -1,6 +1,7 @@
 mod catalog;
 mod compute;
 mod discovery;
+mod graph_snapshot;
 mod loader;
 
 pub(crate) use discovery::discover_segments;
@@ -14,6 +14,9 @@ use super::types::{store_err, RebuildResponse};
 // ── Catalog metadata ────────────────────────────────────────────
 
 /// Return the current merged entity/schema catalog.
+///
+/// Field statistics carry their distinct-value estimates but not the
+/// HyperLogLog sketches behind them.
 #[utoipa::path(
     get,
     path = "/catalog",
@@ -29,7 +32,7 @@ pub(crate) async fn get_catalog(
     crate::api::require_ready(&state).await?;
     let catalog_lock = state.catalog.read().await;
     match catalog_lock.as_ref() {
-        Some(cat) => Ok(Json(cat.clone())),
+        Some(cat) => Ok(Json(cat.clone().without_sketches())),
         None => {
             let status = state.loading.to_status().await;
             Err((
@@ -41,6 +41,7 @@ pub fn catalog_router() -> Router<Arc<AppState>> {
             "/catalog/segments/{id}",
             get(get_segment).delete(delete_segment),
         )
+        .route("/catalog/segments/{id}/rebuild", post(rebuild_segment))
         // External sources: list (lightweight) + create
         .route(
             "/catalog/externals",
@@ -115,6 +116,8 @@ mod tests {
                     }],
                 },
             ],
+            last_refreshed: None,
+            refresh_ttl_secs: stupid_catalog::DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
         }
     }
 
@@ -126,6 +129,8 @@ mod tests {
                 kind: "athena".into(),
                 connection_id: "staging".into(),
                 databases: vec![],
+                last_refreshed: None,
+                refresh_ttl_secs: stupid_catalog::DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
             },
             ExternalSource {
                 name: "Production Postgres".into(),
@@ -135,6 +140,8 @@ mod tests {
                     name: "app".into(),
                     tables: vec![],
                 }],
+                last_refreshed: None,
+                refresh_ttl_secs: stupid_catalog::DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
             },
         ]
     }
@@ -2,30 +2,57 @@
 
 use std::sync::Arc;
 
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::http::StatusCode;
 use axum::Json;
 
 use crate::api::QueryErrorResponse;
+use crate::query_audit::{PendingQuery, QueryClient};
 use crate::state::AppState;
+use super::types::{store_err, CatalogQueryParams};
 
 // ── Query execution ─────────────────────────────────────────────
 
 /// Execute a structured query plan against the knowledge graph.
+///
+/// With `?snapshot=`, the plan runs against a historical catalog snapshot
+/// instead, answering metadata questions such as how many members there
+/// were last Tuesday. Each call is recorded in the query audit trail (`GET /audit/queries`).
 #[utoipa::path(
     post,
     path = "/catalog/query",
     tag = "Catalog",
+    params(CatalogQueryParams),
     request_body = super::types::QueryExecuteRequest,
     responses(
         (status = 200, description = "Query results", body = [Object]),
         (status = 400, description = "Invalid query plan", body = QueryErrorResponse),
+        (status = 404, description = "No snapshot matches `snapshot`", body = QueryErrorResponse),
         (status = 503, description = "Service not ready", body = crate::api::NotReadyResponse)
     )
 )]
 pub(crate) async fn execute_query(
     State(state): State<Arc<AppState>>,
+    client: QueryClient,
+    Query(params): Query<CatalogQueryParams>,
     Json(body): Json<serde_json::Value>,
+) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, Json<QueryErrorResponse>)> {
+    let audit = PendingQuery::start("/catalog/query", &client, body.to_string());
+    let result = match params.snapshot {
+        Some(snapshot) => run_snapshot_query(&state, &snapshot, body),
+        None => run_query(&state, body).await,
+    };
+    let outcome = match &result {
+        Ok(Json(rows)) => Ok(Some(rows.len() as u64)),
+        Err((_, Json(e))) => Err(e.error.clone()),
+    };
+    state.query_audit.append(audit.finish(outcome));
+    result
+}
+
+async fn run_query(
+    state: &AppState,
+    body: serde_json::Value,
 ) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, Json<QueryErrorResponse>)> {
     // Route through eisenbahn if available.
     if let Some(ref eb) = state.eisenbahn {
@@ -43,7 +70,7 @@ pub(crate) async fn execute_query(
     }
 
     // Require graph to be loaded.
-    crate::api::require_ready(&state).await.map_err(|(status, body)| {
+    crate::api::require_ready(state).await.map_err(|(status, body)| {
         (
             status,
             Json(QueryErrorResponse {
@@ -78,6 +105,51 @@ pub(crate) async fn execute_query(
     Ok(Json(results))
 }
 
+/// Run a catalog-metadata plan against the snapshot named by `snapshot`.
+fn run_snapshot_query(
+    state: &AppState,
+    snapshot: &str,
+    body: serde_json::Value,
+) -> Result<Json<Vec<serde_json::Value>>, (StatusCode, Json<QueryErrorResponse>)> {
+    let found = match chrono::DateTime::parse_from_rfc3339(snapshot) {
+        Ok(at) => state
+            .catalog_store
+            .snapshot_at(at.with_timezone(&chrono::Utc))
+            .map(|found| found.map(|(_, catalog)| catalog)),
+        Err(_) => state.catalog_store.load_snapshot(snapshot),
+    };
+    let catalog = found.map_err(store_err)?.ok_or_else(|| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(QueryErrorResponse {
+                error: format!("No catalog snapshot matches '{snapshot}'"),
+            }),
+        )
+    })?;
+
+    let plan: stupid_catalog::plan::QueryPlan = serde_json::from_value(body).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(QueryErrorResponse {
+                error: format!("Invalid query plan: {e}"),
+            }),
+        )
+    })?;
+
+    let results = stupid_catalog::QueryExecutor::with_catalog(&catalog)
+        .execute(&plan)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(QueryErrorResponse {
+                    error: format!("Query execution failed: {e}"),
+                }),
+            )
+        })?;
+
+    Ok(Json(results))
+}
+
 /// Map an eisenbahn error to an HTTP error response for catalog queries.
 fn eb_catalog_error(e: stupid_eisenbahn::EisenbahnError) -> (StatusCode, Json<QueryErrorResponse>) {
     let status = match &e {
@@ -86,3 +158,85 @@ fn eb_catalog_error(e: stupid_eisenbahn::EisenbahnError) -> (StatusCode, Json<Qu
     };
     (status, Json(QueryErrorResponse { error: e.to_string() }))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use chrono::TimeZone;
+    use stupid_catalog::{Catalog, CatalogEntry};
+    use tower::ServiceExt;
+
+    async fn send(app: &axum::Router, uri: &str, plan: &str) -> (StatusCode, serde_json::Value) {
+        let request = Request::post(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(plan.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap_or_default())
+    }
+
+    fn catalog(members: usize) -> Catalog {
+        Catalog {
+            entity_types: vec![CatalogEntry {
+                entity_type: "Member".to_string(),
+                node_count: members,
+                sample_keys: vec!["alice".to_string()],
+                fields: vec![],
+            }],
+            edge_types: vec![],
+            total_nodes: members,
+            total_edges: 0,
+            external_sources: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_query_against_older_snapshot() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), vec![]).await;
+        let monday = chrono::Utc.with_ymd_and_hms(2026, 2, 16, 9, 0, 0).unwrap();
+        let wednesday = chrono::Utc.with_ymd_and_hms(2026, 2, 18, 9, 0, 0).unwrap();
+        state.catalog_store.save_snapshot_at(&catalog(40), monday).unwrap();
+        state.catalog_store.save_snapshot_at(&catalog(75), wednesday).unwrap();
+        let app = crate::router::build_router(state);
+
+        let count = r#"{"steps":[{"id":"agg","type":"aggregate","group_by":"entity_type"}]}"#;
+        let (status, rows) = send(&app, "/catalog/query?snapshot=2026-02-17T12:00:00Z", count).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(rows, serde_json::json!([{"group": "Member", "count": 40}]));
+
+        let filter = r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#;
+        let (status, rows) = send(&app, "/catalog/query?snapshot=2026-02-18T09-00-00", filter).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(rows[0]["node_count"], 75);
+
+        // The live graph is empty, so the same plan finds nothing there.
+        let (status, rows) = send(&app, "/catalog/query", filter).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(rows, serde_json::json!([]));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_query_errors() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), vec![]).await;
+        let monday = chrono::Utc.with_ymd_and_hms(2026, 2, 16, 9, 0, 0).unwrap();
+        state.catalog_store.save_snapshot_at(&catalog(40), monday).unwrap();
+        let app = crate::router::build_router(state);
+
+        let filter = r#"{"steps":[{"id":"s1","type":"filter","entity_type":"Member"}]}"#;
+        let (status, _) = send(&app, "/catalog/query?snapshot=2026-01-01T00:00:00Z", filter).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let traversal = r#"{"steps":[
+            {"id":"s1","type":"filter","entity_type":"Member"},
+            {"id":"s2","type":"traversal","edge_type":"LoggedInFrom","depends_on":["s1"]}
+        ]}"#;
+        let (status, body) = send(&app, "/catalog/query?snapshot=2026-02-16T09-00-00", traversal).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("needs the graph"));
+    }
+}
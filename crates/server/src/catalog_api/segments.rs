@@ -1,4 +1,4 @@
-//! Segment partial catalog endpoints: list, get, delete.
+//! Segment partial catalog endpoints: list, get, delete, rebuild.
 
 use std::sync::Arc;
 
@@ -100,3 +100,61 @@ pub(crate) async fn delete_segment(
         segment_count,
     }))
 }
+
+/// Recompute one segment's partial catalog from the graph and swap it into
+/// the merged catalog, leaving the other segments' partials untouched.
+#[utoipa::path(
+    post,
+    path = "/catalog/segments/{id}/rebuild",
+    tag = "Catalog",
+    params(
+        ("id" = String, Path, description = "Segment ID (URL-encoded if contains /)")
+    ),
+    responses(
+        (status = 200, description = "Segment partial rebuilt, catalog re-merged", body = RebuildResponse),
+        (status = 404, description = "Segment not loaded", body = QueryErrorResponse),
+        (status = 500, description = "Store error", body = QueryErrorResponse)
+    )
+)]
+pub(crate) async fn rebuild_segment(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<RebuildResponse>, (StatusCode, Json<QueryErrorResponse>)> {
+    let decoded = urlencoding::decode(&id).map(|c| c.into_owned()).unwrap_or(id);
+    if !state.segment_ids.read().await.contains(&decoded) {
+        return Err((
+            StatusCode::NOT_FOUND,
+            Json(QueryErrorResponse {
+                error: format!("Segment '{decoded}' is not loaded."),
+            }),
+        ));
+    }
+
+    let partial = {
+        let graph = state.graph.read().await;
+        stupid_catalog::PartialCatalog::from_graph_segment(&graph, &decoded)
+    };
+    let catalog = state
+        .catalog_store
+        .replace_segment(&decoded, &partial)
+        .map_err(store_err)?;
+
+    let segment_count = state.catalog_store.list_partials().unwrap_or_default().len();
+
+    // Update in-memory catalog.
+    {
+        let mut lock = state.catalog.write().await;
+        *lock = Some(catalog.clone());
+    }
+
+    info!(
+        "Segment '{}' partial rebuilt via API: {} nodes, {} edges in segment",
+        decoded, partial.node_count, partial.edge_count
+    );
+
+    Ok(Json(RebuildResponse {
+        total_nodes: catalog.total_nodes,
+        total_edges: catalog.total_edges,
+        segment_count,
+    }))
+}
@@ -26,6 +26,17 @@ pub struct SnapshotResponse {
     pub filename: String,
 }
 
+/// Query-string parameters for `POST /catalog/query`.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct CatalogQueryParams {
+    /// Run against a historical catalog snapshot instead of the live graph:
+    /// a snapshot name (e.g. `2026-02-16T04-16-00`) or an RFC 3339
+    /// timestamp, which picks the latest snapshot taken at or before it.
+    /// Only catalog-metadata plans (entity-type filters and `entity_type`
+    /// aggregates) can run against a snapshot.
+    pub snapshot: Option<String>,
+}
+
 /// Schema type for OpenAPI documentation of the query plan request body.
 #[derive(Serialize, Deserialize, utoipa::ToSchema)]
 #[allow(dead_code)]
@@ -13,13 +13,23 @@ pub async fn dispatch(config: &stupid_core::Config, args: &[String]) -> anyhow::
         Some("import") => {
             let path = args
                 .get(2)
-                .expect("Usage: server import <parquet_path> <segment_id>");
+                .expect("Usage: server import <file_path> <segment_id>");
             let segment_id = args
                 .get(3)
-                .expect("Usage: server import <parquet_path> <segment_id>");
+                .expect("Usage: server import <file_path> <segment_id>");
             import::import(config, Path::new(path), segment_id)?;
             Ok(true)
         }
+        Some("import-dedup") => {
+            let path = args
+                .get(2)
+                .expect("Usage: server import-dedup <file_path> <key_fields>");
+            let key_fields = args
+                .get(3)
+                .expect("Usage: server import-dedup <file_path> <key_fields>");
+            import::import_deduplicated(config, Path::new(path), key_fields)?;
+            Ok(true)
+        }
         Some("import-dir") => {
             let path = args
                 .get(2)
@@ -44,6 +54,11 @@ pub async fn dispatch(config: &stupid_core::Config, args: &[String]) -> anyhow::
             export::export(config, do_segments, do_graph).await?;
             Ok(true)
         }
+        Some("verify") => {
+            let repair = args.iter().skip(2).any(|a| a == "--repair");
+            export::verify(config, repair).await?;
+            Ok(true)
+        }
         Some("serve") => Ok(false),
         _ => {
             print_usage();
@@ -66,9 +81,11 @@ pub fn parse_serve_args(args: &[String]) -> (Option<String>, bool) {
 fn print_usage() {
     println!("stupid-db v0.1.0");
     println!("Usage: server.exe <command>");
-    println!("  import <parquet_path> <segment_id>  Import single parquet file");
-    println!("  import-dir <directory>               Import all parquet files recursively");
+    println!("  import <file_path> <segment_id>     Import a single parquet, CSV or JSONL file");
+    println!("  import-dedup <file_path> <key_fields> Import a parquet file, skipping events already imported");
+    println!("  import-dir <directory>               Import all parquet, CSV and JSONL files recursively");
     println!("  import-s3 <s3-prefix>                Import parquet files from S3");
     println!("  export [--segments|--graph|--all]     Export to S3 (default: --all)");
+    println!("  verify [--repair]                    Check segment integrity (--repair rebuilds damaged segments)");
     println!("  serve [segment_id] [--eisenbahn]     Start HTTP server (--eisenbahn enables ZMQ broker)");
 }
@@ -0,0 +1,405 @@
+//! Liveness checks for DB, queue, Athena and S3 connections.
+//!
+//! Each check makes the cheapest call that proves the credentials work:
+//! a Postgres login + `SELECT 1`, SQS `GetQueueAttributes`, Athena
+//! `ListWorkGroups`, an S3 list of the configured prefix, or a TCP connect
+//! for brokers and HTTP endpoints without a client here.
+//! Results never contain the credentials, even when a client error echoes them.
+
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use serde::Serialize;
+
+use stupid_core::config::AwsConfig;
+
+use crate::athena_connections::AthenaConnectionCredentials;
+use crate::connections::ConnectionCredentials;
+use crate::queue_connections::QueueConnectionConfig;
+
+/// How long a single check may take before it counts as failed.
+const PROBE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Outcome of a connection test.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ConnectionTestResult {
+    pub ok: bool,
+    /// What was checked on success, or why it failed.
+    pub message: String,
+    pub latency_ms: u64,
+}
+
+/// The calls a connection test makes, mockable in tests.
+#[async_trait]
+pub trait ConnectionProbe: Send + Sync {
+    async fn postgres(&self, creds: &ConnectionCredentials) -> Result<(), String>;
+    async fn sqs(&self, config: &QueueConnectionConfig) -> Result<(), String>;
+    async fn athena(&self, creds: &AthenaConnectionCredentials) -> Result<(), String>;
+    async fn s3(&self, aws: &AwsConfig) -> Result<(), String>;
+    async fn tcp(&self, addr: &str) -> Result<(), String>;
+}
+
+/// Probe making real network calls.
+pub struct LiveProbe;
+
+#[async_trait]
+impl ConnectionProbe for LiveProbe {
+    async fn postgres(&self, creds: &ConnectionCredentials) -> Result<(), String> {
+        use sqlx::postgres::{PgConnectOptions, PgSslMode};
+        use sqlx::{ConnectOptions, Connection};
+
+        let mut conn = PgConnectOptions::new()
+            .host(&creds.host)
+            .port(creds.port)
+            .database(&creds.database)
+            .username(&creds.username)
+            .password(&creds.password)
+            .ssl_mode(if creds.ssl { PgSslMode::Require } else { PgSslMode::Prefer })
+            .connect()
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query("SELECT 1").execute(&mut conn).await.map_err(|e| e.to_string())?;
+        let _ = conn.close().await;
+        Ok(())
+    }
+
+    async fn sqs(&self, config: &QueueConnectionConfig) -> Result<(), String> {
+        use stupid_queue::QueueConsumer;
+
+        let consumer = stupid_queue::SqsConsumer::new(&config.to_aws_config(), &config.to_queue_config())
+            .await
+            .map_err(|e| e.to_string())?;
+        consumer.health_check().await.map(|_| ()).map_err(|e| e.to_string())
+    }
+
+    async fn athena(&self, creds: &AthenaConnectionCredentials) -> Result<(), String> {
+        let client = crate::athena_query::build_athena_client(creds).await;
+        client
+            .list_work_groups()
+            .max_results(1)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|e| aws_sdk_athena::error::DisplayErrorContext(&e).to_string())
+    }
+
+    async fn s3(&self, aws: &AwsConfig) -> Result<(), String> {
+        let backend = stupid_storage::S3Backend::new(aws).map_err(|e| e.to_string())?;
+        backend.check().await.map_err(|e| e.to_string())
+    }
+
+    async fn tcp(&self, addr: &str) -> Result<(), String> {
+        tokio::net::TcpStream::connect(addr).await.map(|_| ()).map_err(|e| e.to_string())
+    }
+}
+
+/// Log in to a Postgres connection.
+pub async fn test_db(probe: &dyn ConnectionProbe, creds: &ConnectionCredentials) -> ConnectionTestResult {
+    let start = Instant::now();
+    let checked = format!("Logged in to {}:{}/{}", creds.host, creds.port, creds.database);
+    let result = timed(probe.postgres(creds)).await.map(|()| checked);
+    finish(start, result, &[&creds.password])
+}
+
+/// Reach a queue with its provider's cheapest call.
+pub async fn test_queue(probe: &dyn ConnectionProbe, config: &QueueConnectionConfig) -> ConnectionTestResult {
+    let start = Instant::now();
+    let result = match config.provider.as_str() {
+        "sqs" => timed(probe.sqs(config)).await.map(|()| format!("Read attributes of {}", config.queue_url)),
+        "kafka" => reach_any(probe, config.kafka_brokers.split(',').map(str::trim)).await,
+        provider => match broker_address(&config.queue_url, provider) {
+            Some(addr) => reach_any(probe, std::iter::once(addr.as_str())).await,
+            None => Err(format!("Can't find a host and port in queue URL for provider '{}'", provider)),
+        },
+    };
+    finish(
+        start,
+        result,
+        &[&config.access_key_id, &config.secret_access_key, &config.session_token],
+    )
+}
+
+/// List Athena workgroups with a connection's credentials.
+pub async fn test_athena(probe: &dyn ConnectionProbe, creds: &AthenaConnectionCredentials) -> ConnectionTestResult {
+    let start = Instant::now();
+    let checked = format!("Listed workgroups in {}", creds.region);
+    let result = timed(probe.athena(creds)).await.map(|()| checked);
+    finish(
+        start,
+        result,
+        &[&creds.access_key_id, &creds.secret_access_key, &creds.session_token],
+    )
+}
+
+/// List the top level of the configured S3 bucket and prefix.
+pub async fn test_s3(probe: &dyn ConnectionProbe, aws: &AwsConfig) -> ConnectionTestResult {
+    let start = Instant::now();
+    let checked = format!(
+        "Listed s3://{}/{}",
+        aws.s3_bucket.as_deref().unwrap_or_default(),
+        aws.s3_prefix.as_deref().unwrap_or_default()
+    );
+    let result = timed(probe.s3(aws)).await.map(|()| checked);
+    let secrets = [&aws.access_key_id, &aws.secret_access_key, &aws.session_token];
+    finish(start, result, &secrets.map(|s| s.as_deref().unwrap_or_default()))
+}
+
+/// Connect to the host of an HTTP(S) endpoint such as an LLM provider's API.
+pub async fn test_endpoint(probe: &dyn ConnectionProbe, endpoint: &str) -> ConnectionTestResult {
+    let start = Instant::now();
+    let result = match broker_address(endpoint, "http") {
+        Some(addr) => reach_any(probe, std::iter::once(addr.as_str())).await,
+        None => Err(format!("Can't find a host and port in '{}'", endpoint)),
+    };
+    finish(start, result, &[])
+}
+
+/// Connect to the first reachable address.
+async fn reach_any<'a>(
+    probe: &dyn ConnectionProbe,
+    addrs: impl Iterator<Item = &'a str>,
+) -> Result<String, String> {
+    let mut last_error = "No broker address configured".to_string();
+    for addr in addrs.filter(|a| !a.is_empty()) {
+        match timed(probe.tcp(addr)).await {
+            Ok(()) => return Ok(format!("Connected to {}", addr)),
+            Err(e) => last_error = format!("{}: {}", addr, e),
+        }
+    }
+    Err(last_error)
+}
+
+/// `host:port` of a broker URL such as `redis://cache:6379/0`, using the
+/// provider's default port when the URL has none.
+fn broker_address(queue_url: &str, provider: &str) -> Option<String> {
+    let url = url::Url::parse(queue_url).ok()?;
+    let default_port = match provider {
+        "redis" => Some(6379),
+        "nats" => Some(4222),
+        "mqtt" => Some(1883),
+        _ => url.port_or_known_default(),
+    };
+    Some(format!("{}:{}", url.host_str()?, url.port().or(default_port)?))
+}
+
+async fn timed(check: impl std::future::Future<Output = Result<(), String>>) -> Result<(), String> {
+    tokio::time::timeout(PROBE_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("Timed out after {}s", PROBE_TIMEOUT.as_secs())))
+}
+
+fn finish(start: Instant, result: Result<String, String>, secrets: &[&str]) -> ConnectionTestResult {
+    let (ok, message) = match result {
+        Ok(message) => (true, message),
+        Err(error) => (false, redact(error, secrets)),
+    };
+    ConnectionTestResult {
+        ok,
+        message,
+        latency_ms: start.elapsed().as_millis() as u64,
+    }
+}
+
+/// Mask any secret a client error happens to echo.
+fn redact(mut message: String, secrets: &[&str]) -> String {
+    for secret in secrets.iter().filter(|s| !s.is_empty()) {
+        message = message.replace(secret, "********");
+    }
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every call with the same result.
+    struct MockProbe(Result<(), String>);
+
+    #[async_trait]
+    impl ConnectionProbe for MockProbe {
+        async fn postgres(&self, _: &ConnectionCredentials) -> Result<(), String> {
+            self.0.clone()
+        }
+        async fn sqs(&self, _: &QueueConnectionConfig) -> Result<(), String> {
+            self.0.clone()
+        }
+        async fn athena(&self, _: &AthenaConnectionCredentials) -> Result<(), String> {
+            self.0.clone()
+        }
+        async fn s3(&self, _: &AwsConfig) -> Result<(), String> {
+            self.0.clone()
+        }
+        async fn tcp(&self, _: &str) -> Result<(), String> {
+            self.0.clone()
+        }
+    }
+
+    fn db_creds() -> ConnectionCredentials {
+        ConnectionCredentials {
+            id: "db".into(),
+            name: "db".into(),
+            host: "pg".into(),
+            port: 5432,
+            database: "app".into(),
+            username: "app".into(),
+            password: "hunter2".into(),
+            ssl: false,
+        }
+    }
+
+    fn athena_creds() -> AthenaConnectionCredentials {
+        serde_json::from_value::<crate::athena_connections::AthenaConnectionInput>(serde_json::json!({
+            "name": "lake",
+            "database": "events",
+            "output_location": "s3://results/",
+            "access_key_id": "AKIAEXAMPLE",
+            "secret_access_key": "s3cr3t-key",
+        }))
+        .unwrap()
+        .to_credentials("lake", None)
+        .unwrap()
+    }
+
+    fn queue_config(provider: &str, queue_url: &str) -> QueueConnectionConfig {
+        serde_json::from_value::<crate::queue_connections::QueueConnectionInput>(serde_json::json!({
+            "name": "events",
+            "provider": provider,
+            "queue_url": queue_url,
+            "secret_access_key": "s3cr3t-key",
+            "kafka_brokers": "k1:9092, k2:9092",
+        }))
+        .unwrap()
+        .to_config("events", None)
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_success_reports_what_was_checked() {
+        let ok = MockProbe(Ok(()));
+
+        let result = test_db(&ok, &db_creds()).await;
+        assert!(result.ok);
+        assert_eq!(result.message, "Logged in to pg:5432/app");
+
+        assert!(test_athena(&ok, &athena_creds()).await.ok);
+        assert!(test_queue(&ok, &queue_config("sqs", "https://sqs/q")).await.ok);
+        assert_eq!(test_queue(&ok, &queue_config("kafka", "")).await.message, "Connected to k1:9092");
+        assert_eq!(
+            test_queue(&ok, &queue_config("redis", "redis://cache/0")).await.message,
+            "Connected to cache:6379"
+        );
+        assert_eq!(
+            test_endpoint(&ok, "https://api.openai.com/v1").await.message,
+            "Connected to api.openai.com:443"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_auth_failure_gives_reason_without_secrets() {
+        let denied = MockProbe(Err("password authentication failed for user \"app\" (password hunter2)".into()));
+        let result = test_db(&denied, &db_creds()).await;
+        assert!(!result.ok);
+        assert!(result.message.contains("password authentication failed"));
+        assert!(!result.message.contains("hunter2"));
+
+        let denied = MockProbe(Err("UnrecognizedClientException: invalid signature for s3cr3t-key".into()));
+        let result = test_athena(&denied, &athena_creds()).await;
+        assert!(!result.ok);
+        assert!(result.message.starts_with("UnrecognizedClientException"));
+        assert!(!result.message.contains("s3cr3t-key"));
+
+        let result = test_queue(&denied, &queue_config("sqs", "https://sqs/q")).await;
+        assert!(!result.ok);
+        assert!(!result.message.contains("s3cr3t-key"));
+
+        let aws = AwsConfig {
+            region: "eu-west-1".into(),
+            access_key_id: Some("AKIAEXAMPLE".into()),
+            secret_access_key: Some("s3cr3t-key".into()),
+            session_token: None,
+            s3_bucket: Some("segments".into()),
+            s3_prefix: None,
+            endpoint_url: None,
+            s3_multipart_threshold_bytes: stupid_core::config::DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
+        };
+        let result = test_s3(&denied, &aws).await;
+        assert!(!result.ok);
+        assert!(!result.message.contains("s3cr3t-key"));
+
+        let result = test_queue(&MockProbe(Ok(())), &queue_config("nats", "not a url")).await;
+        assert!(!result.ok);
+        assert!(!test_endpoint(&MockProbe(Ok(())), "localhost").await.ok);
+    }
+
+    #[test]
+    fn test_submitted_input_falls_back_to_stored_secrets() {
+        let stored = athena_creds();
+        let input: crate::athena_connections::AthenaConnectionInput = serde_json::from_value(serde_json::json!({
+            "name": "lake",
+            "database": "events",
+            "output_location": "s3://results/",
+        }))
+        .unwrap();
+        let creds = input.to_credentials("lake", Some(&stored)).unwrap();
+        assert_eq!(creds.secret_access_key, "s3cr3t-key");
+        assert_eq!(creds.access_key_id, "AKIAEXAMPLE");
+    }
+
+    #[test]
+    fn test_stored_secrets_are_not_sent_to_a_changed_target() {
+        let stored = queue_config("sqs", "https://sqs/q");
+        let input = |queue_url: &str| {
+            serde_json::from_value::<crate::queue_connections::QueueConnectionInput>(serde_json::json!({
+                "name": "events",
+                "provider": "sqs",
+                "queue_url": queue_url,
+                "kafka_brokers": "k1:9092, k2:9092",
+            }))
+            .unwrap()
+        };
+
+        let config = input("https://sqs/q").to_config("events", Some(&stored)).unwrap();
+        assert_eq!(config.secret_access_key, "s3cr3t-key");
+        assert!(input("https://attacker/q").to_config("events", Some(&stored)).is_err());
+
+        let athena: crate::athena_connections::AthenaConnectionInput = serde_json::from_value(serde_json::json!({
+            "name": "lake",
+            "database": "events",
+            "output_location": "s3://results/",
+            "endpoint_url": "https://attacker",
+        }))
+        .unwrap();
+        assert!(athena.to_credentials("lake", Some(&athena_creds())).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_endpoint_tests_submitted_or_stored_connection() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state);
+
+        let response = app
+            .clone()
+            .oneshot(Request::post("/connections/missing/test").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        // Nothing listens on port 1, so the login fails fast.
+        let body = r#"{"name":"local","host":"127.0.0.1","port":1,"database":"app","password":"hunter2"}"#;
+        let request = Request::post("/connections/local/test")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let result: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(result["ok"], false);
+        assert!(!String::from_utf8_lossy(&bytes).contains("hunter2"));
+    }
+}
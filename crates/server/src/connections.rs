@@ -167,6 +167,36 @@ impl ConnectionInput {
             self.ssl,
         ))
     }
+
+    /// Credentials for testing this input before it is saved. A blank
+    /// password falls back to `existing`'s, as on update, but only while the
+    /// input logs in as the same user on the same server.
+    pub fn to_credentials(
+        &self,
+        id: &str,
+        existing: Option<&ConnectionCredentials>,
+    ) -> anyhow::Result<ConnectionCredentials> {
+        let (host, port, database, username, mut password, ssl) = self.resolve()?;
+        if password.is_empty() {
+            if let Some(existing) = existing {
+                anyhow::ensure!(
+                    existing.host == host && existing.port == port && existing.username == username,
+                    "The host, port or user changed; enter the password to test it"
+                );
+                password = existing.password.clone();
+            }
+        }
+        Ok(ConnectionCredentials {
+            id: id.to_string(),
+            name: self.name.clone(),
+            host,
+            port,
+            database,
+            username,
+            password,
+            ssl,
+        })
+    }
 }
 
 /// On-disk format: passwords stored as encrypted hex strings.
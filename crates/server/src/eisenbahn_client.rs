@@ -58,6 +58,8 @@ pub struct EisenbahnClient {
     agent_client: Option<Arc<ZmqRequestClient>>,
     athena_client: Option<Arc<ZmqRequestClient>>,
     catalog_client: Option<Arc<ZmqRequestClient>>,
+    /// Events received per topic, exposed on `/metrics`.
+    events_received: std::sync::Mutex<std::collections::BTreeMap<String, u64>>,
 }
 
 impl EisenbahnClient {
@@ -103,6 +105,7 @@ impl EisenbahnClient {
             agent_client,
             athena_client,
             catalog_client,
+            events_received: Default::default(),
         });
 
         Ok(client)
@@ -179,20 +182,19 @@ impl EisenbahnClient {
         }
     }
 
-    /// Convert an eisenbahn message into a JSON string and broadcast to WebSocket clients.
+    /// Wrap an eisenbahn message in an `event` envelope and broadcast it to WebSocket clients.
     fn handle_event(&self, msg: Message) {
-        // Build a simple JSON envelope for WebSocket consumers
-        let json = serde_json::json!({
+        *self.events_received.lock().unwrap().entry(msg.topic.clone()).or_default() += 1;
+
+        let text = crate::ws::envelope("event", serde_json::json!({
             "source": "eisenbahn",
             "topic": msg.topic,
             "timestamp": msg.timestamp.to_rfc3339(),
             "correlation_id": msg.correlation_id.to_string(),
-        });
+        }));
 
-        if let Ok(text) = serde_json::to_string(&json) {
-            // Best-effort broadcast — if no WebSocket clients are listening, that's fine
-            let _ = self.ws_broadcast.send(text);
-        }
+        // Best-effort broadcast — if no WebSocket clients are listening, that's fine
+        let _ = self.ws_broadcast.send(text);
     }
 
     /// Publish an event message to a topic (best-effort, non-panicking).
@@ -266,6 +268,11 @@ impl EisenbahnClient {
         Ok(reply.decode::<CatalogQueryResponse>()?)
     }
 
+    /// Events received so far, per topic.
+    pub fn events_received(&self) -> std::collections::BTreeMap<String, u64> {
+        self.events_received.lock().unwrap().clone()
+    }
+
     /// Check if a specific service is available.
     pub fn has_service(&self, name: &str) -> bool {
         match name {
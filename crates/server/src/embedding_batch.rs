@@ -0,0 +1,210 @@
+//! Batch embedding with an LRU cache and a provider concurrency cap.
+//!
+//! Texts already in the cache are answered from it; the rest are
+//! de-duplicated, split into provider-sized batches and sent with at most
+//! `max_concurrent` batches in flight across all requests.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use futures::{StreamExt, TryStreamExt};
+use serde::Serialize;
+use stupid_ingest::embedding::{Embedder, EmbeddingCache, EmbeddingError};
+use tokio::sync::Semaphore;
+
+/// Texts sent to the provider per call.
+const BATCH_SIZE: usize = 64;
+/// Provider calls in flight at once, shared by all requests.
+const MAX_CONCURRENT_BATCHES: usize = 4;
+/// Embeddings kept in the cache.
+const CACHE_CAPACITY: usize = 10_000;
+
+/// One embedded input text.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct EmbeddedText {
+    pub embedding: Vec<f32>,
+    /// Whether the embedding came from the cache instead of the provider.
+    pub cached: bool,
+}
+
+/// Result of a batch, in input order.
+#[derive(Debug)]
+pub struct BatchOutcome {
+    pub embeddings: Vec<EmbeddedText>,
+    /// Provider calls made for the texts that missed the cache.
+    pub provider_calls: usize,
+}
+
+/// Shared cache and concurrency limit for batch embedding.
+pub struct BatchEmbedder {
+    cache: Mutex<EmbeddingCache>,
+    permits: Semaphore,
+    batch_size: usize,
+    max_concurrent: usize,
+}
+
+impl BatchEmbedder {
+    pub fn new(batch_size: usize, max_concurrent: usize, cache_capacity: usize) -> Self {
+        Self {
+            cache: Mutex::new(EmbeddingCache::new(cache_capacity)),
+            permits: Semaphore::new(max_concurrent.max(1)),
+            batch_size: batch_size.max(1),
+            max_concurrent: max_concurrent.max(1),
+        }
+    }
+
+    /// Embed `texts`, returning one embedding per text in the same order.
+    pub async fn embed(&self, embedder: &dyn Embedder, texts: &[String]) -> Result<BatchOutcome, EmbeddingError> {
+        let mut found: Vec<Option<EmbeddedText>> = Vec::with_capacity(texts.len());
+        let mut misses: Vec<&str> = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::new();
+        {
+            let mut cache = self.cache.lock().unwrap();
+            for text in texts {
+                let hit = cache.get(text);
+                if hit.is_none() && seen.insert(text) {
+                    misses.push(text);
+                }
+                found.push(hit.map(|embedding| EmbeddedText { embedding, cached: true }));
+            }
+        }
+
+        // `buffered` yields batches in submission order, so results line up with `misses`.
+        let calls: Vec<_> = misses.chunks(self.batch_size).map(|batch| self.embed_chunk(embedder, batch)).collect();
+        let batches: Vec<Vec<Vec<f32>>> = futures::stream::iter(calls)
+            .buffered(self.max_concurrent)
+            .try_collect()
+            .await?;
+        let provider_calls = batches.len();
+
+        let mut fresh: HashMap<&str, Vec<f32>> = HashMap::with_capacity(misses.len());
+        {
+            let mut cache = self.cache.lock().unwrap();
+            for (text, embedding) in misses.iter().zip(batches.into_iter().flatten()) {
+                cache.put(text, embedding.clone());
+                fresh.insert(text, embedding);
+            }
+        }
+
+        let embeddings = texts
+            .iter()
+            .zip(found)
+            .map(|(text, hit)| {
+                hit.unwrap_or_else(|| EmbeddedText {
+                    embedding: fresh[text.as_str()].clone(),
+                    cached: false,
+                })
+            })
+            .collect();
+        Ok(BatchOutcome { embeddings, provider_calls })
+    }
+
+    /// One provider call, once a concurrency permit is free.
+    async fn embed_chunk(&self, embedder: &dyn Embedder, batch: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+        let _permit = self.permits.acquire().await.expect("semaphore never closed");
+        let embeddings = embedder.embed_batch(batch).await?;
+        if embeddings.len() != batch.len() {
+            return Err(EmbeddingError::Api(format!(
+                "expected {} embeddings, got {}",
+                batch.len(),
+                embeddings.len()
+            )));
+        }
+        Ok(embeddings)
+    }
+}
+
+impl Default for BatchEmbedder {
+    fn default() -> Self {
+        Self::new(BATCH_SIZE, MAX_CONCURRENT_BATCHES, CACHE_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use async_trait::async_trait;
+
+    /// Embeds each text as `[len, first byte]`, recording batch sizes and
+    /// the most batches seen in flight at once.
+    #[derive(Default)]
+    struct FakeEmbedder {
+        batches: Mutex<Vec<usize>>,
+        in_flight: AtomicUsize,
+        max_in_flight: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl Embedder for FakeEmbedder {
+        async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, EmbeddingError> {
+            let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+            let call = {
+                let mut batches = self.batches.lock().unwrap();
+                batches.push(texts.len());
+                batches.len()
+            };
+            // Earlier batches take longer, so they finish out of order.
+            tokio::time::sleep(Duration::from_millis(40 / call as u64)).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+            Ok(texts.iter().map(|t| vec![t.len() as f32, t.as_bytes()[0] as f32]).collect())
+        }
+
+        fn dimensions(&self) -> usize {
+            2
+        }
+    }
+
+    fn texts(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{}{}", (b'a' + (i % 26) as u8) as char, "x".repeat(i))).collect()
+    }
+
+    #[tokio::test]
+    async fn splits_misses_into_capped_batches() {
+        let fake = FakeEmbedder::default();
+        let batcher = BatchEmbedder::new(10, 2, 100);
+
+        let outcome = batcher.embed(&fake, &texts(45)).await.unwrap();
+        assert_eq!(outcome.provider_calls, 5);
+        assert_eq!(*fake.batches.lock().unwrap(), vec![10, 10, 10, 10, 5]);
+        assert!(fake.max_in_flight.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[tokio::test]
+    async fn preserves_input_order() {
+        let fake = FakeEmbedder::default();
+        let batcher = BatchEmbedder::new(3, 4, 100);
+        let input = texts(20);
+
+        let outcome = batcher.embed(&fake, &input).await.unwrap();
+        assert_eq!(outcome.embeddings.len(), input.len());
+        for (text, embedded) in input.iter().zip(&outcome.embeddings) {
+            assert_eq!(embedded.embedding, vec![text.len() as f32, text.as_bytes()[0] as f32]);
+        }
+    }
+
+    #[tokio::test]
+    async fn cached_and_duplicate_texts_skip_the_provider() {
+        let fake = FakeEmbedder::default();
+        let batcher = BatchEmbedder::new(10, 2, 100);
+
+        let first = vec!["alpha".to_string(), "beta".to_string(), "alpha".to_string()];
+        let outcome = batcher.embed(&fake, &first).await.unwrap();
+        assert_eq!(*fake.batches.lock().unwrap(), vec![2]);
+        assert!(outcome.embeddings.iter().all(|e| !e.cached));
+        assert_eq!(outcome.embeddings[0].embedding, outcome.embeddings[2].embedding);
+
+        let second = vec!["beta".to_string(), "gamma".to_string()];
+        let outcome = batcher.embed(&fake, &second).await.unwrap();
+        assert_eq!(*fake.batches.lock().unwrap(), vec![2, 1]);
+        assert_eq!(outcome.embeddings.iter().map(|e| e.cached).collect::<Vec<_>>(), vec![true, false]);
+
+        let outcome = batcher.embed(&fake, &first).await.unwrap();
+        assert_eq!(outcome.provider_calls, 0);
+        assert!(outcome.embeddings.iter().all(|e| e.cached));
+    }
+}
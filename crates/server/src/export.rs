@@ -1,4 +1,4 @@
-use tracing::info;
+use tracing::{info, warn};
 
 use stupid_storage::{StorageEngine, S3Exporter, S3Importer};
 
@@ -15,12 +15,30 @@ pub(crate) async fn import_s3(config: &stupid_core::Config, s3_prefix: &str) ->
         &storage.backend,
         s3_prefix,
         &storage.data_dir,
+        storage.uploads.as_ref(),
     )
     .await?;
     info!(
         "S3 import complete: {} documents in {} segments",
         docs, segments
     );
+
+    info!("Waiting for segment uploads to finish...");
+    storage.flush().await;
+    if let Some(status) = storage.upload_status() {
+        let failed: Vec<_> = status.failed().collect();
+        for (segment_id, error) in &failed {
+            warn!("Segment '{}' was not uploaded: {}", segment_id, error);
+        }
+        info!(
+            "Segment uploads: {} uploaded, {} failed",
+            status.uploaded,
+            failed.len()
+        );
+        if !failed.is_empty() {
+            anyhow::bail!("{} segment upload(s) failed", failed.len());
+        }
+    }
     Ok(())
 }
 
@@ -58,3 +76,31 @@ pub(crate) async fn export(config: &stupid_core::Config, export_segments: bool,
 
     Ok(())
 }
+
+/// Check every segment's files for consistency and, with `repair`, rebuild
+/// the damaged ones from their readable documents.
+pub(crate) async fn verify(config: &stupid_core::Config, repair: bool) -> anyhow::Result<()> {
+    let storage = StorageEngine::from_config(config)?;
+    let issues = storage.verify().await?;
+    if issues.is_empty() {
+        info!("All segments are consistent");
+        return Ok(());
+    }
+    for issue in &issues {
+        warn!("{}", issue);
+    }
+
+    if !repair {
+        anyhow::bail!("{} segment issue(s) found; rerun with --repair to fix", issues.len());
+    }
+
+    let failed = storage
+        .repair(&issues)?
+        .into_iter()
+        .filter(|(_, outcome)| outcome.is_err())
+        .count();
+    if failed > 0 {
+        anyhow::bail!("{} segment(s) could not be repaired", failed);
+    }
+    Ok(())
+}
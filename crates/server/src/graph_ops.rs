@@ -8,6 +8,7 @@ use tracing::info;
 // extract these tiny ops (~50-100 bytes each). The consumer replays
 // them into the single-threaded GraphStore.
 
+#[derive(Debug, PartialEq)]
 pub(crate) enum GraphOp {
     /// Upsert a node and add edges from it.
     Node {
@@ -18,97 +19,59 @@ pub(crate) enum GraphOp {
 }
 
 /// Extract graph ops from a document (runs in rayon worker, no GraphStore needed).
-pub(crate) fn extract_graph_ops(doc: &stupid_core::Document, _seg_id: &str, ops: &mut Vec<GraphOp>) {
-    use stupid_core::{EdgeType, EntityType};
-
-    let member_code = match doc.fields.get("memberCode").and_then(|v| v.as_str()) {
-        Some(s) if !s.trim().is_empty() && s.trim() != "None" && s.trim() != "null" => s.trim().to_string(),
-        _ => return,
+///
+/// Edges come from `mapping` (see [`stupid_core::GraphMapping`]); documents
+/// without a member code or with an unmapped event type produce no ops.
+pub(crate) fn extract_graph_ops(
+    doc: &stupid_core::Document,
+    mapping: &stupid_core::GraphMapping,
+    ops: &mut Vec<GraphOp>,
+) {
+    use stupid_core::{EntityType, MEMBER_FIELD};
+
+    let Some(member_code) = doc.field_str(MEMBER_FIELD) else {
+        return;
     };
-
-    let member_key = format!("member:{}", member_code);
-    let mut edges = Vec::new();
-
-    let get = |name: &str| -> Option<String> {
-        doc.fields.get(name).and_then(|v| v.as_str()).and_then(|s| {
-            let t = s.trim();
-            if t.is_empty() || t == "None" || t == "null" || t == "undefined" { None }
-            else { Some(t.to_string()) }
-        })
+    let Some(edge_mappings) = mapping.edges_for(&doc.event_type) else {
+        return;
     };
 
-    match doc.event_type.as_str() {
-        "Login" => {
-            if let Some(fp) = get("fingerprint") {
-                edges.push((EntityType::Device, format!("device:{}", fp), EdgeType::LoggedInFrom));
-            }
-            if let Some(p) = get("platform") {
-                edges.push((EntityType::Platform, format!("platform:{}", p), EdgeType::PlaysOnPlatform));
-            }
-            if let Some(c) = get("currency") {
-                edges.push((EntityType::Currency, format!("currency:{}", c), EdgeType::UsesCurrency));
-            }
-            if let Some(g) = get("rGroup") {
-                edges.push((EntityType::VipGroup, format!("vipgroup:{}", g), EdgeType::BelongsToGroup));
-            }
-            let aff = get("affiliateId").or_else(|| get("affiliateid")).or_else(|| get("affiliateID"));
-            if let Some(a) = aff {
-                edges.push((EntityType::Affiliate, format!("affiliate:{}", a), EdgeType::ReferredBy));
-            }
-        }
-        "GameOpened" | "GridClick" => {
-            if let Some(g) = get("game") {
-                edges.push((EntityType::Game, format!("game:{}", g), EdgeType::OpenedGame));
-                if let Some(p) = get("gameTrackingProvider") {
-                    // Provider linked to game, not member — handled separately below.
-                    ops.push(GraphOp::Node {
-                        entity_type: EntityType::Game,
-                        key: format!("game:{}", g),
-                        edges: vec![(EntityType::Provider, format!("provider:{}", p), EdgeType::ProvidedBy)],
-                    });
-                }
-            }
-            if let Some(p) = get("platform") {
-                edges.push((EntityType::Platform, format!("platform:{}", p), EdgeType::PlaysOnPlatform));
-            }
-            if let Some(c) = get("currency") {
-                edges.push((EntityType::Currency, format!("currency:{}", c), EdgeType::UsesCurrency));
-            }
-        }
-        "PopupModule" | "PopUpModule" => {
-            let popup_key = get("trackingId").or_else(|| get("popupType"));
-            if let Some(pk) = popup_key {
-                edges.push((EntityType::Popup, format!("popup:{}", pk), EdgeType::SawPopup));
-            }
-            if let Some(p) = get("platform") {
-                edges.push((EntityType::Platform, format!("platform:{}", p), EdgeType::PlaysOnPlatform));
-            }
-        }
-        "API Error" => {
-            let error_key = match (get("url"), get("statusCode")) {
-                (Some(url), Some(code)) => Some(format!("error:{}:{}", code, url)),
-                (Some(url), None) => Some(format!("error:{}", url)),
-                _ => get("error").map(|e| format!("error:{}", e)),
-            };
-            if let Some(ek) = error_key {
-                edges.push((EntityType::Error, ek, EdgeType::HitError));
-            }
-            if let Some(p) = get("platform") {
-                edges.push((EntityType::Platform, format!("platform:{}", p), EdgeType::PlaysOnPlatform));
-            }
+    let mut edges = Vec::new();
+    for edge_mapping in edge_mappings {
+        let Some(target_key) = edge_mapping.target_key(doc) else {
+            continue;
+        };
+        // Edges from the target itself (e.g. game → provider), not the member.
+        let target_edges = mapped_edges(doc, &edge_mapping.then);
+        if !target_edges.is_empty() {
+            ops.push(GraphOp::Node {
+                entity_type: edge_mapping.entity,
+                key: target_key.clone(),
+                edges: target_edges,
+            });
         }
-        _ => return,
+        edges.push((edge_mapping.entity, target_key, edge_mapping.edge));
     }
 
     if !edges.is_empty() {
         ops.push(GraphOp::Node {
             entity_type: EntityType::Member,
-            key: member_key,
+            key: format!("member:{}", member_code),
             edges,
         });
     }
 }
 
+fn mapped_edges(
+    doc: &stupid_core::Document,
+    mappings: &[stupid_core::EdgeMapping],
+) -> Vec<(stupid_core::EntityType, String, stupid_core::EdgeType)> {
+    mappings
+        .iter()
+        .filter_map(|m| m.target_key(doc).map(|key| (m.entity, key, m.edge)))
+        .collect()
+}
+
 /// Replay a graph op into the GraphStore (runs on consumer thread).
 pub(crate) fn apply_graph_op(op: &GraphOp, graph: &mut stupid_graph::GraphStore, seg_id: &str) {
     match op {
@@ -170,3 +133,103 @@ pub(crate) fn build_graph_multi(data_dir: &Path, segment_ids: &[String]) -> anyh
 
     Ok((graph, total_docs))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use stupid_core::{Document, EdgeType, EntityType, FieldValue, GraphMapping};
+
+    const MAPPING: &str = r#"
+events:
+  Deposit:
+    - fields: [paymentMethod]
+      entity: Device
+      edge: LoggedInFrom
+    - fields: [game]
+      entity: Game
+      edge: OpenedGame
+      then:
+        - fields: [gameTrackingProvider]
+          entity: Provider
+          edge: ProvidedBy
+"#;
+
+    fn doc(event_type: &str, fields: &[(&str, &str)]) -> Document {
+        Document {
+            id: uuid::Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: event_type.to_string(),
+            fields: fields
+                .iter()
+                .map(|(k, v)| (k.to_string(), FieldValue::Text(v.to_string())))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn config_mapping_produces_graph_ops() {
+        let mapping = GraphMapping::from_yaml(MAPPING).unwrap();
+        let doc = doc(
+            "Deposit",
+            &[
+                ("memberCode", "alice"),
+                ("paymentMethod", "card"),
+                ("game", "slots"),
+                ("gameTrackingProvider", "pg"),
+            ],
+        );
+
+        let mut ops = Vec::new();
+        extract_graph_ops(&doc, &mapping, &mut ops);
+        assert_eq!(
+            ops,
+            vec![
+                GraphOp::Node {
+                    entity_type: EntityType::Game,
+                    key: "game:slots".into(),
+                    edges: vec![(EntityType::Provider, "provider:pg".into(), EdgeType::ProvidedBy)],
+                },
+                GraphOp::Node {
+                    entity_type: EntityType::Member,
+                    key: "member:alice".into(),
+                    edges: vec![
+                        (EntityType::Device, "device:card".into(), EdgeType::LoggedInFrom),
+                        (EntityType::Game, "game:slots".into(), EdgeType::OpenedGame),
+                    ],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn unmapped_events_and_missing_member_are_ignored() {
+        let mapping = GraphMapping::from_yaml(MAPPING).unwrap();
+        let mut ops = Vec::new();
+        extract_graph_ops(&doc("Login", &[("memberCode", "alice"), ("paymentMethod", "card")]), &mapping, &mut ops);
+        extract_graph_ops(&doc("Deposit", &[("memberCode", "null"), ("paymentMethod", "card")]), &mapping, &mut ops);
+        extract_graph_ops(&doc("Deposit", &[("memberCode", "alice")]), &mapping, &mut ops);
+        assert!(ops.is_empty());
+    }
+
+    #[test]
+    fn builtin_mapping_matches_api_error_keys() {
+        let mapping = GraphMapping::default();
+        let mut ops = Vec::new();
+        extract_graph_ops(
+            &doc("API Error", &[("memberCode", "bob"), ("url", "/api/bet"), ("statusCode", "500"), ("platform", "web")]),
+            &mapping,
+            &mut ops,
+        );
+        assert_eq!(
+            ops,
+            vec![GraphOp::Node {
+                entity_type: EntityType::Member,
+                key: "member:bob".into(),
+                edges: vec![
+                    (EntityType::Error, "error:500:/api/bet".into(), EdgeType::HitError),
+                    (EntityType::Platform, "platform:web".into(), EdgeType::PlaysOnPlatform),
+                ],
+            }]
+        );
+    }
+}
@@ -0,0 +1,324 @@
+//! Component readiness and dependency checks behind `/health`.
+//!
+//! Components are in-process state — data loading, the catalog, the compute
+//! scheduler and queue consumers — and are always reported. Dependencies are
+//! external services (S3, Athena connections, the LLM provider); they are
+//! only probed for `/health?deep=true`, and only when configured.
+//!
+//! Only data loading is critical: the overall status is `unhealthy` when it
+//! failed and `starting` while it runs. Anything else being down makes the
+//! server `degraded`, since it can still serve queries from what it loaded.
+
+use serde::Serialize;
+
+use stupid_queue::{HealthStatus, HealthThresholds};
+
+use crate::athena_connections::AthenaConnectionStore;
+use crate::connection_test::{self, ConnectionProbe, ConnectionTestResult};
+use crate::credential_store::CredentialStore;
+use crate::state::AppState;
+
+/// State of one component or dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ComponentStatus {
+    Up,
+    /// Still initializing after startup.
+    Starting,
+    Down,
+    /// Not set up on this server; never counts against the overall status.
+    NotConfigured,
+}
+
+/// Aggregate status of the server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OverallStatus {
+    Ok,
+    /// A non-critical component or dependency is down; the server still serves.
+    Degraded,
+    /// A critical component is still starting.
+    Starting,
+    /// A critical component is down.
+    Unhealthy,
+}
+
+/// Status of one component or dependency.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct ComponentHealth {
+    pub name: String,
+    pub status: ComponentStatus,
+    /// Whether the server can't serve without it.
+    pub critical: bool,
+    /// Phase, failure reason or what was checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+    /// Duration of the probe, for dependencies.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
+
+impl ComponentHealth {
+    fn new(name: impl Into<String>, status: ComponentStatus, critical: bool) -> Self {
+        Self { name: name.into(), status, critical, detail: None, latency_ms: None }
+    }
+
+    fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// This check without details that may name buckets, paths or
+    /// connections, for callers without an API key. Startup phases are kept.
+    pub fn public(self) -> Self {
+        let detail = if self.status == ComponentStatus::Starting { self.detail } else { None };
+        Self { detail, ..self }
+    }
+
+    /// A non-critical dependency from a connection test.
+    fn probed(name: impl Into<String>, result: ConnectionTestResult) -> Self {
+        let status = if result.ok { ComponentStatus::Up } else { ComponentStatus::Down };
+        Self {
+            latency_ms: Some(result.latency_ms),
+            ..Self::new(name, status, false).with_detail(result.message)
+        }
+    }
+}
+
+/// The worst status any check implies; `ok` for no checks.
+pub fn overall_status(checks: &[ComponentHealth]) -> OverallStatus {
+    checks
+        .iter()
+        .map(|c| match (c.status, c.critical) {
+            (ComponentStatus::Down, true) => OverallStatus::Unhealthy,
+            (ComponentStatus::Starting, true) => OverallStatus::Starting,
+            (ComponentStatus::Down, false) => OverallStatus::Degraded,
+            _ => OverallStatus::Ok,
+        })
+        .max()
+        .unwrap_or(OverallStatus::Ok)
+}
+
+/// Readiness of the in-process components.
+pub async fn components(state: &AppState) -> Vec<ComponentHealth> {
+    let loading = state.loading.to_status().await;
+    let loading_health = match loading.phase {
+        "ready" => ComponentHealth::new("loading", ComponentStatus::Up, true),
+        "failed" => ComponentHealth::new("loading", ComponentStatus::Down, true)
+            .with_detail(loading.error.clone().unwrap_or_default()),
+        phase => ComponentHealth::new("loading", ComponentStatus::Starting, true).with_detail(phase),
+    };
+
+    // The catalog and scheduler come up in the compute phase after loading.
+    let catalog = if state.catalog.read().await.is_some() {
+        ComponentHealth::new("catalog", ComponentStatus::Up, false)
+    } else if loading.phase == "failed" || loading.compute_ready {
+        ComponentHealth::new("catalog", ComponentStatus::Down, false).with_detail("catalog not built")
+    } else {
+        ComponentHealth::new("catalog", ComponentStatus::Starting, false).with_detail(loading.compute_phase)
+    };
+
+    let scheduler = match state.scheduler.read().await.as_ref() {
+        Some(handle) if !handle.thread.is_finished() => {
+            ComponentHealth::new("scheduler", ComponentStatus::Up, false)
+        }
+        Some(_) => ComponentHealth::new("scheduler", ComponentStatus::Down, false)
+            .with_detail("scheduler thread exited"),
+        None if loading.phase == "failed" || loading.compute_ready => {
+            ComponentHealth::new("scheduler", ComponentStatus::Down, false).with_detail("scheduler not started")
+        }
+        None => ComponentHealth::new("scheduler", ComponentStatus::Starting, false)
+            .with_detail(loading.compute_phase),
+    };
+
+    vec![loading_health, catalog, scheduler, queue_consumers(state)]
+}
+
+/// Queue consumers are up when every consumer is healthy.
+fn queue_consumers(state: &AppState) -> ComponentHealth {
+    let metrics = state.queue_metrics.read().expect("queue_metrics lock poisoned");
+    if metrics.is_empty() {
+        return ComponentHealth::new("queues", ComponentStatus::NotConfigured, false);
+    }
+    let thresholds = HealthThresholds::default();
+    let mut unhealthy: Vec<String> = metrics
+        .iter()
+        .filter_map(|(id, m)| {
+            let health = m.snapshot().health(&thresholds);
+            (health != HealthStatus::Healthy).then(|| {
+                let reason = serde_json::to_value(health).ok();
+                format!("{}: {}", id, reason.as_ref().and_then(|r| r.as_str()).unwrap_or("unhealthy"))
+            })
+        })
+        .collect();
+    if unhealthy.is_empty() {
+        return ComponentHealth::new("queues", ComponentStatus::Up, false)
+            .with_detail(format!("{} consumer(s) healthy", metrics.len()));
+    }
+    unhealthy.sort();
+    ComponentHealth::new("queues", ComponentStatus::Down, false).with_detail(unhealthy.join(", "))
+}
+
+/// Probe every configured external dependency concurrently.
+///
+/// S3 and the LLM provider are listed as `not_configured` when unset; each
+/// stored Athena connection is probed as `athena:{id}`.
+pub async fn dependencies(state: &AppState, probe: &dyn ConnectionProbe) -> Vec<ComponentHealth> {
+    let config = &state.config;
+
+    let s3 = async {
+        if config.aws.is_configured() {
+            ComponentHealth::probed("s3", connection_test::test_s3(probe, &config.aws).await)
+        } else {
+            ComponentHealth::new("s3", ComponentStatus::NotConfigured, false)
+        }
+    };
+
+    let llm = async {
+        match llm_endpoint(config) {
+            Some(endpoint) => {
+                ComponentHealth::probed("llm", connection_test::test_endpoint(probe, &endpoint).await)
+            }
+            None => ComponentHealth::new("llm", ComponentStatus::NotConfigured, false),
+        }
+    };
+
+    let athena_creds = athena_credentials(&*state.athena_connections.read().await);
+    let athena = futures::future::join_all(athena_creds.into_iter().map(|(id, creds)| async move {
+        let name = format!("athena:{}", id);
+        match creds {
+            Ok(creds) => ComponentHealth::probed(name, connection_test::test_athena(probe, &creds).await),
+            Err(e) => ComponentHealth::new(name, ComponentStatus::Down, false).with_detail(e),
+        }
+    }));
+
+    let (s3, llm, athena) = tokio::join!(s3, llm, athena);
+    let mut checks = vec![s3, llm];
+    checks.extend(athena);
+    checks
+}
+
+/// Credentials of every stored Athena connection, or why they can't be read.
+fn athena_credentials(
+    store: &AthenaConnectionStore,
+) -> Vec<(String, Result<crate::athena_connections::AthenaConnectionCredentials, String>)> {
+    let connections = match store.list() {
+        Ok(connections) => connections,
+        Err(e) => return vec![("*".to_string(), Err(format!("Failed to list connections: {}", e)))],
+    };
+    connections
+        .into_iter()
+        .map(|c| {
+            let creds = match store.get_credentials(&c.id) {
+                Ok(Some(creds)) => Ok(creds),
+                Ok(None) => Err("connection was deleted".to_string()),
+                Err(e) => Err(format!("Failed to read credentials: {}", e)),
+            };
+            (c.id, creds)
+        })
+        .collect()
+}
+
+/// Base URL of the configured LLM provider's API, if one is configured.
+fn llm_endpoint(config: &stupid_core::Config) -> Option<String> {
+    let llm = &config.llm;
+    if !llm.is_configured() {
+        return None;
+    }
+    let endpoint = match llm.provider.as_str() {
+        "openai" => llm.openai_base_url.clone().unwrap_or_else(|| "https://api.openai.com".to_string()),
+        "anthropic" => "https://api.anthropic.com".to_string(),
+        "gemini" | "google" => "https://generativelanguage.googleapis.com".to_string(),
+        "ollama" => config.ollama.url.clone(),
+        _ => return None,
+    };
+    Some(endpoint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use async_trait::async_trait;
+    use stupid_core::config::AwsConfig;
+
+    use crate::athena_connections::AthenaConnectionCredentials;
+    use crate::connections::ConnectionCredentials;
+    use crate::queue_connections::QueueConnectionConfig;
+
+    fn check(status: ComponentStatus, critical: bool) -> ComponentHealth {
+        ComponentHealth::new("c", status, critical)
+    }
+
+    #[test]
+    fn test_overall_status_takes_the_worst_check() {
+        use ComponentStatus::*;
+
+        assert_eq!(overall_status(&[]), OverallStatus::Ok);
+        assert_eq!(overall_status(&[check(Up, true), check(NotConfigured, false)]), OverallStatus::Ok);
+        // Non-critical components still starting don't hold the server back.
+        assert_eq!(overall_status(&[check(Up, true), check(Starting, false)]), OverallStatus::Ok);
+        assert_eq!(overall_status(&[check(Up, true), check(Down, false)]), OverallStatus::Degraded);
+        assert_eq!(overall_status(&[check(Starting, true), check(Down, false)]), OverallStatus::Starting);
+        assert_eq!(
+            overall_status(&[check(Down, true), check(Starting, true), check(Down, false)]),
+            OverallStatus::Unhealthy
+        );
+    }
+
+    /// Every dependency is reachable except over TCP.
+    struct TcpDown;
+
+    #[async_trait]
+    impl ConnectionProbe for TcpDown {
+        async fn postgres(&self, _: &ConnectionCredentials) -> Result<(), String> {
+            Ok(())
+        }
+        async fn sqs(&self, _: &QueueConnectionConfig) -> Result<(), String> {
+            Ok(())
+        }
+        async fn athena(&self, _: &AthenaConnectionCredentials) -> Result<(), String> {
+            Ok(())
+        }
+        async fn s3(&self, _: &AwsConfig) -> Result<(), String> {
+            Ok(())
+        }
+        async fn tcp(&self, _: &str) -> Result<(), String> {
+            Err("Connection refused".to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_down_optional_dependency_degrades() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let config = &mut std::sync::Arc::get_mut(&mut state).unwrap().config;
+        config.llm.provider = "ollama".to_string();
+        config.ollama.url = "http://ollama.internal:11434".to_string();
+
+        let mut checks = components(&state).await;
+        let loading = checks.iter().find(|c| c.name == "loading").unwrap();
+        assert_eq!((loading.status, loading.critical), (ComponentStatus::Up, true));
+        assert_eq!(overall_status(&checks), OverallStatus::Ok);
+
+        let deps = dependencies(&state, &TcpDown).await;
+        let status_of = |name: &str| deps.iter().find(|c| c.name == name).unwrap().status;
+        assert_eq!(status_of("s3"), ComponentStatus::NotConfigured);
+        assert_eq!(status_of("llm"), ComponentStatus::Down);
+        checks.extend(deps);
+        assert_eq!(overall_status(&checks), OverallStatus::Degraded);
+    }
+
+    #[tokio::test]
+    async fn test_failed_loading_is_unhealthy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        state.loading.set_phase(crate::state::LoadingPhase::Failed("segments unreadable".into())).await;
+
+        let checks = components(&state).await;
+        let loading = checks.iter().find(|c| c.name == "loading").unwrap();
+        assert_eq!(loading.detail.as_deref(), Some("segments unreadable"));
+        assert_eq!(checks.iter().find(|c| c.name == "catalog").unwrap().status, ComponentStatus::Down);
+        assert_eq!(overall_status(&checks), OverallStatus::Unhealthy);
+    }
+}
@@ -1,57 +1,115 @@
 use std::path::Path;
 
-use tracing::info;
+use stupid_segment::schema::SchemaRegistry;
+use stupid_segment::writer::{RolloverPolicy, WriteSource};
+use tracing::{info, warn};
 
 /// Parse ISO week from a date-like filename stem (e.g., "2025-06-14" -> "2025-W24").
 pub(crate) fn date_to_iso_week(date_stem: &str) -> String {
-    use chrono::{Datelike, NaiveDate};
-
-    if let Ok(date) = NaiveDate::parse_from_str(date_stem, "%Y-%m-%d") {
-        let iso = date.iso_week();
-        format!("{}-W{:02}", iso.year(), iso.week())
-    } else {
+    match stupid_core::Document::parse_timestamp(date_stem) {
+        Some(ts) => stupid_core::iso_week_label(&ts),
         // Can't parse date — put in "misc" bucket
-        "misc".to_string()
+        None => "misc".to_string(),
     }
 }
 
-/// A group of parquet files that will be merged into one weekly segment.
+/// File extensions `import-dir` picks up; see [`read_documents`].
+const IMPORT_EXTENSIONS: &[&str] = &["parquet", "csv", "jsonl", "ndjson"];
+
+/// Read a parquet, CSV or JSON Lines file, chosen by extension.
+/// Malformed CSV/JSONL rows are skipped (and logged) rather than failing the file.
+pub(crate) fn read_documents(path: &Path, event_type: &str) -> anyhow::Result<Vec<stupid_core::Document>> {
+    use stupid_ingest::column_mapping::ColumnMapping;
+
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    let documents = match extension.to_ascii_lowercase().as_str() {
+        "parquet" => stupid_ingest::parquet_import::ParquetImporter::import(path, event_type)?,
+        "csv" => {
+            stupid_ingest::csv_import::CsvImporter::new(ColumnMapping::default())
+                .import(path, event_type)?
+                .documents
+        }
+        "jsonl" | "ndjson" => {
+            stupid_ingest::jsonl_import::JsonlImporter::new(ColumnMapping::default())
+                .import(path, event_type)?
+                .documents
+        }
+        _ => anyhow::bail!("Unsupported file type: {}", path.display()),
+    };
+    Ok(documents)
+}
+
+/// Observe `documents` in the schema registry, warning about fields whose
+/// type changed since earlier imports.
+fn track_schema(registry: &mut SchemaRegistry, segment_id: &str, documents: &[stupid_core::Document]) {
+    for doc in documents {
+        for conflict in registry.observe_in_segment(doc, segment_id) {
+            warn!("Schema conflict: {}", conflict);
+        }
+    }
+}
+
+fn save_schema(registry: &SchemaRegistry, data_dir: &Path) {
+    if let Err(e) = registry.save_to_dir(data_dir) {
+        warn!("Failed to save schema registry: {}", e);
+    }
+}
+
+/// A group of data files that will be merged into one weekly segment.
 pub(crate) struct ImportGroup {
     pub segment_id: String,
     pub event_type: String,
     pub files: Vec<std::path::PathBuf>,
 }
 
-pub(crate) fn import(config: &stupid_core::Config, parquet_path: &Path, segment_id: &str) -> anyhow::Result<()> {
-    info!("Importing {} as segment '{}'", parquet_path.display(), segment_id);
-
-    let event_type = parquet_path
-        .parent()
+/// Event type of an imported file: the name of its parent directory.
+fn event_type_of(path: &Path) -> &str {
+    path.parent()
         .and_then(|p| p.file_name())
         .and_then(|n| n.to_str())
-        .unwrap_or("Unknown");
+        .unwrap_or("Unknown")
+}
+
+pub(crate) fn import(config: &stupid_core::Config, path: &Path, segment_id: &str) -> anyhow::Result<()> {
+    info!("Importing {} as segment '{}'", path.display(), segment_id);
 
-    let documents = stupid_ingest::parquet_import::ParquetImporter::import(parquet_path, event_type)?;
-    info!("Read {} documents from parquet", documents.len());
+    let event_type = event_type_of(path);
+
+    let documents = read_documents(path, event_type)?;
+    info!("Read {} documents from {}", documents.len(), path.display());
 
     let data_dir = &config.storage.data_dir;
-    let mut writer = stupid_segment::writer::SegmentWriter::new(data_dir, segment_id)?;
+    let mut schema = SchemaRegistry::load_from_dir(data_dir)?;
+    track_schema(&mut schema, segment_id, &documents);
+
+    // A re-run after a failed import picks up after the documents it committed.
+    let source = WriteSource::default().file(path)?;
+    let mut writer = stupid_segment::writer::SegmentWriter::resume(data_dir, segment_id, &source)?
+        .with_rollover(RolloverPolicy::from_config(&config.storage));
+    if writer.resumed_docs() > 0 {
+        info!("Resuming segment '{}' after {} committed documents", segment_id, writer.resumed_docs());
+    }
 
-    for doc in &documents {
+    for doc in documents.iter().skip(writer.resumed_docs() as usize) {
         writer.append(doc)?;
     }
+    let part_ids = writer.part_ids();
     writer.finalize()?;
+    save_schema(&schema, data_dir);
+    if part_ids.len() > 1 {
+        info!("Segment '{}' rolled over into {} parts", segment_id, part_ids.len());
+    }
 
-    // Build graph from segment
-    let reader = stupid_segment::reader::SegmentReader::open(data_dir, segment_id)?;
+    // Build graph from segment (all parts)
     let mut graph = stupid_graph::GraphStore::new();
-    let seg_id = segment_id.to_string();
-
     let mut doc_count = 0u64;
-    for doc_result in reader.iter() {
-        let doc = doc_result?;
-        stupid_connector::entity_extract::EntityExtractor::extract(&doc, &mut graph, &seg_id);
-        doc_count += 1;
+    for part_id in &part_ids {
+        let reader = stupid_segment::reader::SegmentReader::open(data_dir, part_id)?;
+        for doc_result in reader.iter() {
+            let doc = doc_result?;
+            stupid_connector::entity_extract::EntityExtractor::extract(&doc, &mut graph, part_id);
+            doc_count += 1;
+        }
     }
 
     info!("Processed {} documents for entity extraction", doc_count);
@@ -70,44 +128,62 @@ pub(crate) fn import(config: &stupid_core::Config, parquet_path: &Path, segment_
     Ok(())
 }
 
+/// Import a parquet file into the document store, skipping events whose
+/// `key_fields` (comma-separated) match an event imported before, by this
+/// or any earlier `import-dedup` run. Documents go to their weekly segment.
+pub(crate) fn import_deduplicated(config: &stupid_core::Config, path: &Path, key_fields: &str) -> anyhow::Result<()> {
+    let key = stupid_ingest::dedup::DedupKey::parse(key_fields)
+        .ok_or_else(|| anyhow::anyhow!("No dedup key fields given"))?;
+    info!("Importing {} deduplicated by {:?}", path.display(), key.field_names());
+
+    let mut store = stupid_segment::DocumentStore::new(&config.storage)?;
+    let stats = store.import_parquet_deduplicated(path, event_type_of(path), key)?;
+    info!("Imported {} documents, skipped {} duplicates", stats.imported, stats.duplicates);
+    Ok(())
+}
+
 pub(crate) fn import_dir(config: &stupid_core::Config, dir_path: &Path) -> anyhow::Result<()> {
     use rayon::prelude::*;
     use std::collections::BTreeMap;
     use std::sync::atomic::{AtomicU64, Ordering};
 
-    info!("Scanning {} for parquet files...", dir_path.display());
+    info!("Scanning {} for parquet, CSV and JSONL files...", dir_path.display());
 
-    let mut parquet_files: Vec<std::path::PathBuf> = Vec::new();
+    let mut data_files: Vec<std::path::PathBuf> = Vec::new();
     for entry in walkdir::WalkDir::new(dir_path)
         .follow_links(true)
         .into_iter()
         .filter_map(|e| e.ok())
     {
         let path = entry.path();
-        if path.is_file() && path.extension().map(|e| e == "parquet").unwrap_or(false) {
-            parquet_files.push(path.to_path_buf());
+        let supported = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .is_some_and(|e| IMPORT_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()));
+        if path.is_file() && supported {
+            data_files.push(path.to_path_buf());
         }
     }
 
-    parquet_files.sort();
-    info!("Found {} parquet files", parquet_files.len());
+    data_files.sort();
+    info!("Found {} data files", data_files.len());
 
-    if parquet_files.is_empty() {
-        anyhow::bail!("No .parquet files found in {}", dir_path.display());
+    if data_files.is_empty() {
+        anyhow::bail!("No .parquet, .csv or .jsonl files found in {}", dir_path.display());
     }
 
     // Group files by (event_type, iso_week)
     let mut groups: BTreeMap<String, ImportGroup> = BTreeMap::new();
 
-    for parquet_path in &parquet_files {
-        let event_type = parquet_path
+    for data_path in &data_files {
+        let event_type = data_path
             .parent()
             .and_then(|p| p.file_name())
             .and_then(|n| n.to_str())
             .unwrap_or("Unknown")
             .to_string();
 
-        let date_stem = parquet_path
+        let date_stem = data_path
             .file_stem()
             .and_then(|n| n.to_str())
             .unwrap_or("unknown");
@@ -123,7 +199,7 @@ pub(crate) fn import_dir(config: &stupid_core::Config, dir_path: &Path) -> anyho
                 files: Vec::new(),
             })
             .files
-            .push(parquet_path.clone());
+            .push(data_path.clone());
     }
 
     let group_list: Vec<ImportGroup> = groups.into_values().collect();
@@ -138,12 +214,21 @@ pub(crate) fn import_dir(config: &stupid_core::Config, dir_path: &Path) -> anyho
     let completed = AtomicU64::new(0);
     let failed = AtomicU64::new(0);
     let data_dir = &config.storage.data_dir;
+    let rollover = RolloverPolicy::from_config(&config.storage);
+    let schema = std::sync::Mutex::new(SchemaRegistry::load_from_dir(data_dir)?);
     let start = std::time::Instant::now();
 
     // Parallel import: one group = one segment, each group processes independently
     group_list.par_iter().for_each(|group| {
-        let mut writer = match stupid_segment::writer::SegmentWriter::new(data_dir, &group.segment_id) {
-            Ok(w) => w,
+        let source = group
+            .files
+            .iter()
+            .try_fold(WriteSource::default(), |source, file| source.file(file));
+        let writer = source.and_then(|source| {
+            stupid_segment::writer::SegmentWriter::resume(data_dir, &group.segment_id, &source)
+        });
+        let mut writer = match writer {
+            Ok(w) => w.with_rollover(rollover),
             Err(e) => {
                 tracing::warn!("Failed to create segment '{}': {}", group.segment_id, e);
                 failed.fetch_add(1, Ordering::Relaxed);
@@ -152,23 +237,30 @@ pub(crate) fn import_dir(config: &stupid_core::Config, dir_path: &Path) -> anyho
         };
 
         let mut group_docs = 0u64;
+        // Documents a previous run committed, skipped across the group's files.
+        let mut skip = writer.resumed_docs() as usize;
+        if skip > 0 {
+            info!("Resuming segment '{}' after {} committed documents", group.segment_id, skip);
+        }
 
-        for parquet_path in &group.files {
-            let documents = match stupid_ingest::parquet_import::ParquetImporter::import(
-                parquet_path,
-                &group.event_type,
-            ) {
+        for data_path in &group.files {
+            let documents = match read_documents(data_path, &group.event_type) {
                 Ok(docs) => docs,
                 Err(e) => {
-                    tracing::warn!("Failed to read {}: {}", parquet_path.display(), e);
+                    tracing::warn!("Failed to read {}: {}", data_path.display(), e);
                     continue;
                 }
             };
+            track_schema(&mut schema.lock().unwrap(), &group.segment_id, &documents);
 
-            for doc in &documents {
+            let skipped = skip.min(documents.len());
+            skip -= skipped;
+            for doc in &documents[skipped..] {
                 if let Err(e) = writer.append(doc) {
+                    // Left unfinished, so a re-run resumes from the last checkpoint.
                     tracing::warn!("Write error in '{}': {}", group.segment_id, e);
-                    break;
+                    failed.fetch_add(1, Ordering::Relaxed);
+                    return;
                 }
             }
 
@@ -195,14 +287,16 @@ pub(crate) fn import_dir(config: &stupid_core::Config, dir_path: &Path) -> anyho
         }
     });
 
+    save_schema(&schema.into_inner().unwrap(), data_dir);
+
     let elapsed = start.elapsed();
     let final_docs = total_docs.load(Ordering::Relaxed);
     let final_done = completed.load(Ordering::Relaxed);
     let final_failed = failed.load(Ordering::Relaxed);
     info!(
-        "Import complete: {} segments ({} parquet files), {} docs total in {:.1}s ({} failed)",
+        "Import complete: {} segments ({} files), {} docs total in {:.1}s ({} failed)",
         final_done,
-        parquet_files.len(),
+        data_files.len(),
         final_docs,
         elapsed.as_secs_f64(),
         final_failed
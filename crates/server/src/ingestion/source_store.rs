@@ -22,24 +22,28 @@ const NON_SCHEDULABLE_TYPES: &[&str] = &["parquet", "csv_json"];
 // ── Request types ────────────────────────────────────────────────────
 
 /// Request body for creating an ingestion source.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct CreateIngestionSource {
     pub name: String,
     pub source_type: String,
+    #[schema(value_type = Object)]
     pub config_json: serde_json::Value,
     /// Defaults to `"summary"` if not provided.
     pub zmq_granularity: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub schedule_json: Option<serde_json::Value>,
     /// Defaults to `true` if not provided.
     pub enabled: Option<bool>,
 }
 
 /// Request body for updating an ingestion source (all fields optional).
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, utoipa::ToSchema)]
 pub struct UpdateIngestionSource {
     pub name: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub config_json: Option<serde_json::Value>,
     pub zmq_granularity: Option<String>,
+    #[schema(value_type = Option<Object>)]
     pub schedule_json: Option<serde_json::Value>,
     pub enabled: Option<bool>,
 }
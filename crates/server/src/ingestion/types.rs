@@ -166,18 +166,25 @@ pub struct IngestionSchedule {
 // ── Database row ─────────────────────────────────────────────────────
 
 /// Row from the `ingestion_sources` table.
-#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow)]
+#[derive(Debug, Clone, serde::Serialize, sqlx::FromRow, utoipa::ToSchema)]
 pub struct IngestionSource {
+    #[schema(value_type = String)]
     pub id: Uuid,
     pub name: String,
     pub source_type: String,
+    #[schema(value_type = Object)]
     pub config_json: serde_json::Value,
     pub zmq_granularity: String,
+    #[schema(value_type = Option<Object>)]
     pub schedule_json: Option<serde_json::Value>,
     pub enabled: bool,
+    #[schema(value_type = String)]
     pub created_at: DateTime<Utc>,
+    #[schema(value_type = String)]
     pub updated_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>)]
     pub last_run_at: Option<DateTime<Utc>>,
+    #[schema(value_type = Option<String>)]
     pub next_run_at: Option<DateTime<Utc>>,
 }
 
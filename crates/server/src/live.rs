@@ -2,104 +2,278 @@ use std::collections::HashSet;
 use std::path::PathBuf;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use axum::extract::State;
-use axum::response::IntoResponse;
-use futures::{SinkExt, StreamExt};
 use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
-use serde::Serialize;
 use tokio::sync::{broadcast, RwLock};
 use tracing::{error, info, warn};
 
 use crate::state::AppState;
-
-// ── WebSocket Messages ──────────────────────────────────────────
-
-#[derive(Serialize)]
-struct WsMessage<T: Serialize> {
-    #[serde(rename = "type")]
-    msg_type: &'static str,
-    data: T,
-}
-
-fn ws_json<T: Serialize>(msg_type: &'static str, data: T) -> String {
-    serde_json::to_string(&WsMessage { msg_type, data }).unwrap_or_default()
+use crate::ws::envelope;
+
+/// Run `warm_compute` and broadcast each insight it generates as an
+/// `insight` message (consumed by `/ws` and `/insights/stream`).
+pub(crate) fn warm_compute_and_publish(
+    pipeline: &mut stupid_compute::Pipeline,
+    state: &mut stupid_compute::KnowledgeState,
+    docs: &[stupid_core::Document],
+    broadcast_tx: &broadcast::Sender<String>,
+) {
+    let last_before = state.insights.back().map(|i| i.id.clone());
+    pipeline.warm_compute(state, docs);
+
+    // Insights are appended at the back, so the new ones follow the previous last.
+    let first_new = last_before
+        .and_then(|id| state.insights.iter().rposition(|i| i.id == id))
+        .map_or(0, |pos| pos + 1);
+    for insight in state.insights.iter().skip(first_new) {
+        let _ = broadcast_tx.send(envelope("insight", insight));
+    }
 }
 
-// ── WebSocket Handler ───────────────────────────────────────────
+// ── Live Merge ──────────────────────────────────────────────────
 
-pub async fn ws_upgrade(
-    ws: WebSocketUpgrade,
-    State(state): State<Arc<AppState>>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_ws(socket, state))
+/// Handles for merging new segments into the running server's state.
+///
+/// Shared by the segment watcher and `POST /segments/import`.
+#[derive(Clone)]
+pub(crate) struct LiveMerge {
+    data_dir: PathBuf,
+    graph: crate::state::SharedGraph,
+    knowledge: stupid_compute::SharedKnowledgeState,
+    pipeline: crate::state::SharedPipeline,
+    segment_ids: Arc<RwLock<Vec<String>>>,
+    doc_count: Arc<std::sync::atomic::AtomicU64>,
+    broadcast_tx: broadcast::Sender<String>,
+    catalog: Arc<RwLock<Option<stupid_catalog::Catalog>>>,
+    catalog_store: Arc<stupid_catalog::CatalogStore>,
 }
 
-async fn handle_ws(socket: WebSocket, state: Arc<AppState>) {
-    let (mut sender, mut receiver) = socket.split();
-    let mut rx = state.broadcast.subscribe();
+impl LiveMerge {
+    pub(crate) fn new(state: &AppState) -> Self {
+        Self {
+            data_dir: state.data_dir.clone(),
+            graph: state.graph.clone(),
+            knowledge: state.knowledge.clone(),
+            pipeline: state.pipeline.clone(),
+            segment_ids: state.segment_ids.clone(),
+            doc_count: state.doc_count.clone(),
+            broadcast_tx: state.broadcast.clone(),
+            catalog: state.catalog.clone(),
+            catalog_store: state.catalog_store.clone(),
+        }
+    }
+
+    /// Mark the segments that aren't loaded yet as loaded and return them.
+    ///
+    /// Claiming before ingesting keeps the watcher and the import endpoint
+    /// from merging the same segment twice.
+    pub(crate) async fn claim(&self, segments: impl IntoIterator<Item = String>) -> Vec<String> {
+        let mut ids = self.segment_ids.write().await;
+        let mut claimed = Vec::new();
+        for seg_id in segments {
+            if !ids.contains(&seg_id) && !claimed.contains(&seg_id) {
+                ids.push(seg_id.clone());
+                claimed.push(seg_id);
+            }
+        }
+        claimed
+    }
 
-    // Send current stats as initial state.
-    let initial = build_stats_message(&state).await;
-    if sender.send(Message::Text(initial.into())).await.is_err() {
-        return;
+    /// Undo a [`claim`](Self::claim) for segments that were never merged.
+    pub(crate) async fn release(&self, segments: &[String]) {
+        self.segment_ids.write().await.retain(|id| !segments.contains(id));
     }
 
-    // Forward broadcast messages to this client.
-    let send_task = tokio::spawn(async move {
-        while let Ok(msg) = rx.recv().await {
-            if sender.send(Message::Text(msg.into())).await.is_err() {
-                break;
+    /// Ingest claimed segments into the graph, persist their partial
+    /// catalogs, recompute graph algorithms, run the pipeline on the new
+    /// documents and broadcast updated stats. Returns the documents ingested.
+    ///
+    /// Segments that fail to open are released, so a later event for them
+    /// retries the merge.
+    pub(crate) async fn merge(&self, new_segments: &[String]) -> u64 {
+        // Ingest new segments into the graph and collect docs for pipeline.
+        let mut total_new_docs = 0u64;
+        let mut new_docs: Vec<stupid_core::Document> = Vec::new();
+        let mut unreadable = Vec::new();
+        {
+            let mut graph_lock = self.graph.write().await;
+            for seg_id in new_segments {
+                match stupid_segment::reader::SegmentReader::open(&self.data_dir, seg_id) {
+                    Ok(reader) => {
+                        let mut seg_docs = 0u64;
+                        for doc_result in reader.iter() {
+                            match doc_result {
+                                Ok(doc) => {
+                                    stupid_connector::entity_extract::EntityExtractor::extract(
+                                        &doc, &mut graph_lock, seg_id,
+                                    );
+                                    new_docs.push(doc);
+                                    seg_docs += 1;
+                                }
+                                Err(e) => {
+                                    warn!("Bad document in new segment '{}': {}", seg_id, e);
+                                }
+                            }
+                        }
+                        total_new_docs += seg_docs;
+                        info!("  Ingested {} docs from new segment '{}'", seg_docs, seg_id);
+                    }
+                    Err(e) => {
+                        warn!("Failed to read new segment '{}', will retry on its next change: {}", seg_id, e);
+                        unreadable.push(seg_id.clone());
+                    }
+                }
             }
         }
-    });
+        self.doc_count.fetch_add(total_new_docs, Ordering::Relaxed);
+        if !unreadable.is_empty() {
+            self.release(&unreadable).await;
+        }
+        let new_segments: Vec<String> =
+            new_segments.iter().filter(|id| !unreadable.contains(id)).cloned().collect();
+        if new_segments.is_empty() {
+            return 0;
+        }
 
-    // Consume incoming messages (pings, close frames) but ignore content.
-    let recv_task = tokio::spawn(async move {
-        while let Some(Ok(msg)) = receiver.next().await {
-            if matches!(msg, Message::Close(_)) {
-                break;
+        // Update persisted catalog with partial catalogs for new segments.
+        {
+            let graph_read = self.graph.read().await;
+            for seg_id in &new_segments {
+                let partial = stupid_catalog::PartialCatalog::from_graph_segment(&graph_read, seg_id);
+                match self.catalog_store.add_segment(seg_id, &partial) {
+                    Ok(updated_cat) => {
+                        let mut cat_lock = self.catalog.write().await;
+                        *cat_lock = Some(updated_cat);
+                    }
+                    Err(e) => {
+                        warn!("Failed to persist catalog for new segment '{}': {}", seg_id, e);
+                    }
+                }
             }
         }
-    });
+        info!("Catalog updated with {} new segment(s)", new_segments.len());
+
+        // Recompute algorithms on the updated graph into shared KnowledgeState.
+        info!("Recomputing graph algorithms after ingesting {} new docs...", total_new_docs);
+        {
+            let graph_read = self.graph.read().await;
+            let pagerank = stupid_compute::algorithms::pagerank::pagerank_default(&graph_read);
+            let degrees = stupid_compute::algorithms::degree::degree_centrality(&graph_read);
+            let communities = stupid_compute::algorithms::communities::label_propagation_default(&graph_read);
+            drop(graph_read);
+            let mut state = self.knowledge.write().unwrap();
+            state.pagerank = pagerank;
+            state.degrees = degrees;
+            state.communities = communities;
+        }
+        info!("Compute algorithms updated after live ingestion");
+
+        // Run pipeline on new docs (hot_connect + warm_compute).
+        if !new_docs.is_empty() {
+            let mut pipe = self.pipeline.lock().unwrap();
+            let mut state = self.knowledge.write().unwrap();
+            pipe.hot_connect(&new_docs, &mut state);
+            warm_compute_and_publish(&mut pipe, &mut state, &new_docs, &self.broadcast_tx);
+            info!(
+                "Pipeline updated: {} anomalies, {} trends, {} clusters",
+                state.anomalies.len(), state.trends.len(), state.clusters.len()
+            );
+        }
 
-    // Wait for either task to finish (client disconnect or broadcast channel close).
-    tokio::select! {
-        _ = send_task => {},
-        _ = recv_task => {},
+        // Broadcast updated stats to all connected WebSocket clients.
+        let graph_read = self.graph.read().await;
+        let gs = graph_read.stats();
+        let seg_count = self.segment_ids.read().await.len();
+        drop(graph_read);
+
+        let stats_msg = envelope("stats", serde_json::json!({
+            "doc_count": self.doc_count.load(Ordering::Relaxed),
+            "segment_count": seg_count,
+            "node_count": gs.node_count,
+            "edge_count": gs.edge_count,
+            "nodes_by_type": gs.nodes_by_type,
+            "edges_by_type": gs.edges_by_type,
+        }));
+
+        let _ = self.broadcast_tx.send(stats_msg);
+
+        // Broadcast a segment update notification.
+        let seg_msg = envelope("segments", serde_json::json!({
+            "new_segments": new_segments,
+            "total": seg_count,
+        }));
+        let _ = self.broadcast_tx.send(seg_msg);
+
+        total_new_docs
     }
 }
 
-async fn build_stats_message(state: &AppState) -> String {
-    let graph = state.graph.read().await;
-    let gs = graph.stats();
-    let segment_ids = state.segment_ids.read().await;
-
-    ws_json("stats", serde_json::json!({
-        "doc_count": state.doc_count.load(Ordering::Relaxed),
-        "segment_count": segment_ids.len(),
-        "node_count": gs.node_count,
-        "edge_count": gs.edge_count,
-        "nodes_by_type": gs.nodes_by_type,
-        "edges_by_type": gs.edges_by_type,
-    }))
+// ── Segment Watcher ─────────────────────────────────────────────
+
+/// Files whose events mean a segment is being written.
+const SEGMENT_FILES: [&str; 3] = ["documents.dat", "documents.idx", stupid_segment::meta::META_FILE];
+
+/// How long a segment's files must stay quiet before it is considered written.
+const DEBOUNCE_WINDOW: Duration = Duration::from_secs(2);
+
+/// Upper bound on how long a steady stream of events can postpone a merge.
+const MAX_DEBOUNCE_DELAY: Duration = Duration::from_secs(30);
+
+/// Coalesces filesystem events into batches of written segments.
+///
+/// Every event pushes the batch back by the window; once no event has
+/// arrived for a full window (or the first one is `max_delay` old) the batch
+/// is flushed. Only segments whose `meta.json` exists are returned, since
+/// finalize writes it last; the rest come back with their `meta.json` event.
+struct SegmentDebouncer {
+    window: Duration,
+    max_delay: Duration,
+    pending: HashSet<String>,
+    first_event: Option<Instant>,
+    last_event: Option<Instant>,
 }
 
-// ── Segment Watcher ─────────────────────────────────────────────
+impl SegmentDebouncer {
+    fn new(window: Duration, max_delay: Duration) -> Self {
+        Self {
+            window,
+            max_delay,
+            pending: HashSet::new(),
+            first_event: None,
+            last_event: None,
+        }
+    }
 
-pub async fn start_segment_watcher(
-    data_dir: PathBuf,
-    graph: crate::state::SharedGraph,
-    knowledge: stupid_compute::SharedKnowledgeState,
-    pipeline: crate::state::SharedPipeline,
-    segment_ids: Arc<RwLock<Vec<String>>>,
-    doc_count: Arc<std::sync::atomic::AtomicU64>,
-    broadcast_tx: broadcast::Sender<String>,
-    catalog: Arc<RwLock<Option<stupid_catalog::Catalog>>>,
-    catalog_store: Arc<stupid_catalog::CatalogStore>,
-) {
-    let segments_dir = data_dir.join("segments");
+    /// Record an event for a segment.
+    fn observe(&mut self, seg_id: String, now: Instant) {
+        self.pending.insert(seg_id);
+        self.first_event.get_or_insert(now);
+        self.last_event = Some(now);
+    }
+
+    /// When the pending batch should be flushed, if there is one.
+    fn deadline(&self) -> Option<Instant> {
+        let quiet = self.last_event? + self.window;
+        Some(quiet.min(self.first_event? + self.max_delay))
+    }
+
+    /// Take the finalized segments of the batch once its deadline has passed.
+    fn take_ready(&mut self, now: Instant, is_finalized: impl Fn(&str) -> bool) -> Vec<String> {
+        match self.deadline() {
+            Some(deadline) if deadline <= now => {}
+            _ => return Vec::new(),
+        }
+        self.first_event = None;
+        self.last_event = None;
+        let mut ready: Vec<String> = self.pending.drain().filter(|id| is_finalized(id)).collect();
+        ready.sort();
+        ready
+    }
+}
+
+pub async fn start_segment_watcher(live: LiveMerge) {
+    let segments_dir = live.data_dir.join("segments");
     if !segments_dir.exists() {
         // Create segments dir if it doesn't exist so we can watch it.
         if let Err(e) = std::fs::create_dir_all(&segments_dir) {
@@ -127,7 +301,7 @@ pub async fn start_segment_watcher(
                         EventKind::Create(_) | EventKind::Modify(_)
                     ) {
                         for path in event.paths {
-                            if path.file_name().map(|n| n == "documents.dat").unwrap_or(false) {
+                            if path.file_name().is_some_and(|n| SEGMENT_FILES.iter().any(|f| n == *f)) {
                                 let tx = tx.clone();
                                 rt.spawn(async move {
                                     let _ = tx.send(path).await;
@@ -159,151 +333,132 @@ pub async fn start_segment_watcher(
     });
 
     // Process new segment notifications with debouncing.
-    let mut pending: HashSet<String> = HashSet::new();
-    let mut debounce_timer = tokio::time::interval(tokio::time::Duration::from_secs(3));
-    debounce_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut debouncer = SegmentDebouncer::new(DEBOUNCE_WINDOW, MAX_DEBOUNCE_DELAY);
 
     loop {
+        let deadline = debouncer.deadline();
         tokio::select! {
             Some(path) = notify_rx.recv() => {
                 // Extract segment_id from path.
                 if let Some(seg_id) = extract_segment_id(&path, &segments_dir) {
                     // Check if already loaded.
-                    let known = segment_ids.read().await;
-                    if !known.contains(&seg_id) {
-                        pending.insert(seg_id);
+                    let known = live.segment_ids.read().await.contains(&seg_id);
+                    if !known {
+                        debouncer.observe(seg_id, Instant::now());
                     }
                 }
             }
-            _ = debounce_timer.tick() => {
-                if pending.is_empty() {
+            _ = tokio::time::sleep_until(deadline.map_or_else(tokio::time::Instant::now, Into::into)), if deadline.is_some() => {
+                let finalized = |seg_id: &str| {
+                    segments_dir.join(seg_id).join(stupid_segment::meta::META_FILE).exists()
+                };
+                let ready = debouncer.take_ready(Instant::now(), finalized);
+
+                // Segments imported through the API meanwhile are already claimed.
+                let new_segments = live.claim(ready).await;
+                if new_segments.is_empty() {
                     continue;
                 }
-
-                let new_segments: Vec<String> = pending.drain().collect();
                 info!("Detected {} new segment(s): {:?}", new_segments.len(), &new_segments);
-
-                // Ingest new segments into the graph and collect docs for pipeline.
-                let mut total_new_docs = 0u64;
-                let mut new_docs: Vec<stupid_core::Document> = Vec::new();
-                {
-                    let mut graph_lock = graph.write().await;
-                    for seg_id in &new_segments {
-                        match stupid_segment::reader::SegmentReader::open(&data_dir, seg_id) {
-                            Ok(reader) => {
-                                let mut seg_docs = 0u64;
-                                for doc_result in reader.iter() {
-                                    match doc_result {
-                                        Ok(doc) => {
-                                            stupid_connector::entity_extract::EntityExtractor::extract(
-                                                &doc, &mut graph_lock, seg_id,
-                                            );
-                                            new_docs.push(doc);
-                                            seg_docs += 1;
-                                        }
-                                        Err(e) => {
-                                            warn!("Bad document in new segment '{}': {}", seg_id, e);
-                                        }
-                                    }
-                                }
-                                total_new_docs += seg_docs;
-                                info!("  Ingested {} docs from new segment '{}'", seg_docs, seg_id);
-                            }
-                            Err(e) => {
-                                warn!("Failed to read new segment '{}': {}", seg_id, e);
-                            }
-                        }
-                    }
-                }
-
-                // Update segment tracking.
-                {
-                    let mut ids = segment_ids.write().await;
-                    for seg_id in &new_segments {
-                        if !ids.contains(seg_id) {
-                            ids.push(seg_id.clone());
-                        }
-                    }
-                }
-                doc_count.fetch_add(total_new_docs, Ordering::Relaxed);
-
-                // Update persisted catalog with partial catalogs for new segments.
-                {
-                    let graph_read = graph.read().await;
-                    for seg_id in &new_segments {
-                        let partial = stupid_catalog::PartialCatalog::from_graph_segment(&graph_read, seg_id);
-                        match catalog_store.add_segment(seg_id, &partial) {
-                            Ok(updated_cat) => {
-                                let mut cat_lock = catalog.write().await;
-                                *cat_lock = Some(updated_cat);
-                            }
-                            Err(e) => {
-                                warn!("Failed to persist catalog for new segment '{}': {}", seg_id, e);
-                            }
-                        }
-                    }
-                }
-                info!("Catalog updated with {} new segment(s)", new_segments.len());
-
-                // Recompute algorithms on the updated graph into shared KnowledgeState.
-                info!("Recomputing graph algorithms after ingesting {} new docs...", total_new_docs);
-                {
-                    let graph_read = graph.read().await;
-                    let pagerank = stupid_compute::algorithms::pagerank::pagerank_default(&graph_read);
-                    let degrees = stupid_compute::algorithms::degree::degree_centrality(&graph_read);
-                    let communities = stupid_compute::algorithms::communities::label_propagation_default(&graph_read);
-                    drop(graph_read);
-                    let mut state = knowledge.write().unwrap();
-                    state.pagerank = pagerank;
-                    state.degrees = degrees;
-                    state.communities = communities;
-                }
-                info!("Compute algorithms updated after live ingestion");
-
-                // Run pipeline on new docs (hot_connect + warm_compute).
-                if !new_docs.is_empty() {
-                    let mut pipe = pipeline.lock().unwrap();
-                    let mut state = knowledge.write().unwrap();
-                    pipe.hot_connect(&new_docs, &mut state);
-                    pipe.warm_compute(&mut state, &new_docs);
-                    info!(
-                        "Pipeline updated: {} anomalies, {} trends, {} clusters",
-                        state.anomalies.len(), state.trends.len(), state.clusters.len()
-                    );
-                }
-
-                // Broadcast updated stats to all connected WebSocket clients.
-                let graph_read = graph.read().await;
-                let gs = graph_read.stats();
-                let seg_count = segment_ids.read().await.len();
-                drop(graph_read);
-
-                let stats_msg = ws_json("stats", serde_json::json!({
-                    "doc_count": doc_count.load(Ordering::Relaxed),
-                    "segment_count": seg_count,
-                    "node_count": gs.node_count,
-                    "edge_count": gs.edge_count,
-                    "nodes_by_type": gs.nodes_by_type,
-                    "edges_by_type": gs.edges_by_type,
-                }));
-
-                let _ = broadcast_tx.send(stats_msg);
-
-                // Broadcast a segment update notification.
-                let seg_msg = ws_json("segments", serde_json::json!({
-                    "new_segments": new_segments,
-                    "total": seg_count,
-                }));
-                let _ = broadcast_tx.send(seg_msg);
+                live.merge(&new_segments).await;
             }
         }
     }
 }
 
-/// Extract segment_id from a documents.dat path relative to segments_dir.
+/// Extract segment_id from the path of a file inside a segment directory.
 fn extract_segment_id(path: &std::path::Path, segments_dir: &std::path::Path) -> Option<String> {
     let parent = path.parent()?;
     let rel = parent.strip_prefix(segments_dir).ok()?;
     let seg_id = rel.to_str()?;
-    Some(seg_id.replace('\\', "/"))
+    (!seg_id.is_empty()).then(|| seg_id.replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Replays `(offset_ms, segment, file)` events against a debouncer polled
+    /// every 100ms, returning each non-empty batch it flushes. A segment
+    /// counts as finalized once its `meta.json` event has been replayed.
+    fn replay(events: &[(u64, &str, &str)], until_ms: u64) -> Vec<Vec<String>> {
+        let segments_dir = std::path::Path::new("/data/segments");
+        let start = Instant::now();
+        let mut debouncer = SegmentDebouncer::new(DEBOUNCE_WINDOW, MAX_DEBOUNCE_DELAY);
+        let mut finalized = HashSet::new();
+        let mut batches = Vec::new();
+        for tick in (0..=until_ms).step_by(100) {
+            let now = start + Duration::from_millis(tick);
+            for (_, seg, file) in events.iter().filter(|(at, _, _)| (tick..tick + 100).contains(at)) {
+                let seg_id = extract_segment_id(&segments_dir.join(seg).join(file), segments_dir).unwrap();
+                if *file == stupid_segment::meta::META_FILE {
+                    finalized.insert(seg_id.clone());
+                }
+                debouncer.observe(seg_id, now);
+            }
+            let ready = debouncer.take_ready(now, |id| finalized.contains(id));
+            if !ready.is_empty() {
+                batches.push(ready);
+            }
+        }
+        batches
+    }
+
+    #[test]
+    fn test_staggered_writes_trigger_single_reload() {
+        let events = [
+            (0, "2025-06-14", "documents.dat"),
+            (400, "2025-06-14", "documents.dat"),
+            (1200, "2025-06-15", "documents.dat"),
+            (1900, "2025-06-14", "documents.idx"),
+            (2500, "2025-06-15", "documents.idx"),
+            (2600, "2025-06-14", "meta.json"),
+            (3100, "2025-06-15", "meta.json"),
+        ];
+        let batches = replay(&events, 10_000);
+        assert_eq!(batches, vec![vec!["2025-06-14".to_string(), "2025-06-15".to_string()]]);
+    }
+
+    #[test]
+    fn test_segment_without_meta_is_not_loaded() {
+        let events = [(0, "2025-06-14", "documents.dat"), (300, "2025-06-14", "documents.idx")];
+        assert!(replay(&events, 10_000).is_empty());
+
+        // The segment is picked up once finalize writes its meta.json.
+        let events = [(0, "2025-06-14", "documents.dat"), (5000, "2025-06-14", "meta.json")];
+        assert_eq!(replay(&events, 10_000), vec![vec!["2025-06-14".to_string()]]);
+    }
+
+    #[test]
+    fn test_continuous_events_flush_after_max_delay() {
+        let mut events: Vec<(u64, &str, &str)> = (0..400).map(|i| (i * 100, "2025-06-15", "documents.dat")).collect();
+        events.push((0, "2025-06-14", "meta.json"));
+        let batches = replay(&events, 40_000);
+        assert_eq!(batches, vec![vec!["2025-06-14".to_string()]]);
+    }
+
+    #[tokio::test]
+    async fn test_unreadable_segment_is_released_for_retry() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let live = LiveMerge::new(&state);
+
+        let claimed = live.claim(["Login/2025-W24".to_string()]).await;
+        assert_eq!(live.merge(&claimed).await, 0);
+        assert!(state.segment_ids.read().await.is_empty());
+
+        // Once the segment is readable, the next event merges it.
+        let doc = stupid_core::Document {
+            id: uuid::Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: "Login".to_string(),
+            fields: std::collections::HashMap::new(),
+        };
+        let mut writer = stupid_segment::writer::SegmentWriter::new(tmp.path(), "Login/2025-W24").unwrap();
+        writer.append(&doc).unwrap();
+        writer.finalize().unwrap();
+        let claimed = live.claim(["Login/2025-W24".to_string()]).await;
+        assert_eq!(live.merge(&claimed).await, 1);
+        assert_eq!(*state.segment_ids.read().await, vec!["Login/2025-W24".to_string()]);
+    }
 }
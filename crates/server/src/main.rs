@@ -1,35 +1,50 @@
 mod anomaly_rules;
 mod api;
 mod app_config;
+mod auth;
 mod catalog_api;
 mod cli;
 mod db;
 mod eisenbahn_client;
+mod embedding_batch;
 mod vector_store;
 mod router;
 mod rules;
 mod startup;
+mod tool_approvals;
 mod athena_connections;
 mod athena_query;
 mod athena_query_log;
 mod background;
+mod connection_test;
 mod connections;
 mod credential_store;
 mod export;
 mod graph_ops;
+mod health_check;
 mod import;
 mod ingestion;
 mod live;
+mod metrics;
+mod query_audit;
 mod queue;
 mod queue_connections;
+mod rate_limit;
+mod rule_actions;
+mod rule_escalation;
 mod rule_runner;
+mod rule_run_log;
+mod schema_refresh;
+mod shutdown;
 mod state;
+mod ws;
 
 use tracing::info;
 
 /// Initialize shared state, spawn background tasks, and start the HTTP server.
 async fn serve(config: &stupid_core::Config, segment_id: Option<&str>, eisenbahn: bool) -> anyhow::Result<()> {
     config.log_summary();
+    config.effective();
 
     let (state, ctx) = startup::build_app_state(config, eisenbahn).await?;
     let eb_client = ctx.eb_client.clone();
@@ -42,7 +57,7 @@ async fn serve(config: &stupid_core::Config, segment_id: Option<&str>, eisenbahn
     info!("Server listening on http://localhost:{} (data loading in background)", config.server.port);
 
     // Spawn all background tasks (data loader, rule evaluator, file watcher, queue consumers).
-    startup::spawn_background_tasks(config, state, ctx, segment_id)?;
+    let tasks = startup::spawn_background_tasks(config, state.clone(), ctx, segment_id)?;
 
     // Start the eisenbahn event loop and worker runner if connected.
     if let Some(ref eb) = eb_client {
@@ -50,7 +65,12 @@ async fn serve(config: &stupid_core::Config, segment_id: Option<&str>, eisenbahn
         info!("eisenbahn client active — server registered as api-gateway worker");
     }
 
-    axum::serve(listener, app).await?;
+    axum::serve(listener, app.into_make_service_with_connect_info::<std::net::SocketAddr>())
+        .with_graceful_shutdown(shutdown::signal())
+        .await?;
+    info!("HTTP server stopped");
+
+    shutdown::shutdown(&state, tasks).await;
     Ok(())
 }
 
@@ -62,6 +82,7 @@ async fn main() -> anyhow::Result<()> {
         .init();
 
     let config = app_config::load_config();
+    config.validate()?;
     let args: Vec<String> = std::env::args().collect();
 
     // Dispatch non-serve subcommands; returns false for `serve`.
@@ -0,0 +1,207 @@
+//! Per-route HTTP request metrics and the Prometheus `/metrics` endpoint.
+//!
+//! Request counts by status, and a latency histogram, are kept per method and
+//! route template (`/graph/nodes/{id}`, not the concrete path) so label
+//! cardinality stays bounded. Queue consumer and eisenbahn metrics are
+//! rendered alongside, so one scrape covers the whole server.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use axum::extract::{MatchedPath, Request, State};
+use axum::http::header;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::state::AppState;
+
+/// Upper bounds (seconds) of the latency histogram buckets.
+const LATENCY_BUCKETS: [f64; 11] = [0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Counters for one method + route.
+#[derive(Default)]
+struct RouteStats {
+    by_status: BTreeMap<u16, u64>,
+    /// Requests per latency bucket (not cumulative); the last slot is `+Inf`.
+    buckets: [u64; LATENCY_BUCKETS.len() + 1],
+    latency_sum: f64,
+    count: u64,
+}
+
+/// Request metrics shared between the middleware and `/metrics`.
+#[derive(Clone, Default)]
+pub struct HttpMetrics {
+    routes: Arc<Mutex<BTreeMap<(String, String), RouteStats>>>,
+}
+
+impl HttpMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn record(&self, method: &str, route: &str, status: u16, seconds: f64) {
+        let mut routes = self.routes.lock().unwrap();
+        let stats = routes.entry((method.to_string(), route.to_string())).or_default();
+        *stats.by_status.entry(status).or_default() += 1;
+        let bucket = LATENCY_BUCKETS.iter().position(|&le| seconds <= le).unwrap_or(LATENCY_BUCKETS.len());
+        stats.buckets[bucket] += 1;
+        stats.latency_sum += seconds;
+        stats.count += 1;
+    }
+
+    fn render(&self, out: &mut String) {
+        let routes = self.routes.lock().unwrap();
+
+        header(out, "stupid_http_requests_total", "counter", "HTTP requests by method, route and status code.");
+        for ((method, route), stats) in routes.iter() {
+            for (status, count) in &stats.by_status {
+                let _ = writeln!(
+                    out,
+                    "stupid_http_requests_total{{method=\"{}\",route=\"{}\",status=\"{}\"}} {}",
+                    method, escape(route), status, count
+                );
+            }
+        }
+
+        header(out, "stupid_http_request_duration_seconds", "histogram", "HTTP request latency by method and route.");
+        for ((method, route), stats) in routes.iter() {
+            let labels = format!("method=\"{}\",route=\"{}\"", method, escape(route));
+            let mut cumulative = 0;
+            for (i, count) in stats.buckets.iter().enumerate() {
+                cumulative += count;
+                let le = LATENCY_BUCKETS.get(i).map_or("+Inf".to_string(), f64::to_string);
+                let _ = writeln!(out, "stupid_http_request_duration_seconds_bucket{{{},le=\"{}\"}} {}", labels, le, cumulative);
+            }
+            let _ = writeln!(out, "stupid_http_request_duration_seconds_sum{{{}}} {}", labels, stats.latency_sum);
+            let _ = writeln!(out, "stupid_http_request_duration_seconds_count{{{}}} {}", labels, stats.count);
+        }
+    }
+}
+
+/// Middleware recording each matched request in [`HttpMetrics`].
+///
+/// Applied as a route layer, so it only sees requests that reached a route.
+pub async fn track_requests(State(metrics): State<HttpMetrics>, request: Request, next: Next) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| request.uri().path().to_string(), |p| p.as_str().to_string());
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics.record(&method, &route, response.status().as_u16(), start.elapsed().as_secs_f64());
+    response
+}
+
+/// Request, queue consumer and eisenbahn metrics in Prometheus text format.
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    tag = "Health",
+    responses(
+        (status = 200, description = "Prometheus text exposition", body = String, content_type = "text/plain")
+    )
+)]
+pub async fn metrics(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    let mut out = String::new();
+    state.http_metrics.render(&mut out);
+    render_queues(&state.queue_metrics.read().unwrap(), &mut out);
+    if let Some(eb) = &state.eisenbahn {
+        render_eisenbahn(eb, &mut out);
+    }
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4")], out)
+}
+
+/// Metric name, type, help text and value of one queue consumer series.
+type QueueSeries = (&'static str, &'static str, &'static str, fn(&stupid_queue::QueueMetricsSnapshot) -> f64);
+
+fn render_queues(queues: &HashMap<String, Arc<stupid_queue::QueueMetrics>>, out: &mut String) {
+    let snapshots: BTreeMap<_, _> = queues.iter().map(|(id, m)| (id.as_str(), m.snapshot())).collect();
+    let series: [QueueSeries; 8] = [
+        ("stupid_queue_connected", "gauge", "Whether the consumer is connected (1) or not (0).", |s| u8::from(s.connected).into()),
+        ("stupid_queue_messages_received_total", "counter", "Messages received from the queue.", |s| s.messages_received as f64),
+        ("stupid_queue_messages_processed_total", "counter", "Messages parsed and ingested.", |s| s.messages_processed as f64),
+        ("stupid_queue_messages_failed_total", "counter", "Messages that failed parsing or ingestion.", |s| s.messages_failed as f64),
+        ("stupid_queue_messages_dead_lettered_total", "counter", "Messages moved to the dead-letter queue.", |s| s.messages_dead_lettered as f64),
+        ("stupid_queue_in_flight", "gauge", "Messages received but not yet settled.", |s| s.in_flight as f64),
+        ("stupid_queue_batches_processed_total", "counter", "Micro-batches flushed to the graph.", |s| s.batches_processed as f64),
+        ("stupid_queue_oldest_message_age_seconds", "gauge", "Age of the oldest in-flight message.", |s| s.oldest_message_age_ms as f64 / 1000.0),
+    ];
+    for (name, kind, help, value) in series {
+        header(out, name, kind, help);
+        for (id, snapshot) in &snapshots {
+            let _ = writeln!(out, "{}{{queue=\"{}\"}} {}", name, escape(id), value(snapshot));
+        }
+    }
+}
+
+fn render_eisenbahn(eb: &crate::eisenbahn_client::EisenbahnClient, out: &mut String) {
+    header(out, "stupid_eisenbahn_events_received_total", "counter", "Eisenbahn events received by topic.");
+    for (topic, count) in eb.events_received() {
+        let _ = writeln!(out, "stupid_eisenbahn_events_received_total{{topic=\"{}\"}} {}", escape(&topic), count);
+    }
+    header(out, "stupid_eisenbahn_service_available", "gauge", "Whether an eisenbahn service client is connected.");
+    for service in ["query", "agent", "athena", "catalog"] {
+        let _ = writeln!(out, "stupid_eisenbahn_service_available{{service=\"{}\"}} {}", service, u8::from(eb.has_service(service)));
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Escape a label value per the Prometheus text format.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let metrics = HttpMetrics::new();
+        metrics.record("GET", "/stats", 200, 0.003);
+        metrics.record("GET", "/stats", 500, 0.2);
+        metrics.record("GET", "/stats", 200, 60.0);
+
+        let mut out = String::new();
+        metrics.render(&mut out);
+        assert!(out.contains("stupid_http_requests_total{method=\"GET\",route=\"/stats\",status=\"200\"} 2\n"));
+        assert!(out.contains("stupid_http_requests_total{method=\"GET\",route=\"/stats\",status=\"500\"} 1\n"));
+        assert!(out.contains("_bucket{method=\"GET\",route=\"/stats\",le=\"0.005\"} 1\n"));
+        assert!(out.contains("_bucket{method=\"GET\",route=\"/stats\",le=\"0.25\"} 2\n"));
+        assert!(out.contains("_bucket{method=\"GET\",route=\"/stats\",le=\"10\"} 2\n"));
+        assert!(out.contains("_bucket{method=\"GET\",route=\"/stats\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("stupid_http_request_duration_seconds_count{method=\"GET\",route=\"/stats\"} 3\n"));
+    }
+
+    #[tokio::test]
+    async fn test_requests_are_counted_per_route_template() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state);
+
+        let get = |path: &str| Request::get(path).body(Body::empty()).unwrap();
+        for path in ["/stats", "/stats", "/graph/nodes/not-a-uuid"] {
+            app.clone().oneshot(get(path)).await.unwrap();
+        }
+
+        let response = app.oneshot(get("/metrics")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let body = String::from_utf8(bytes.to_vec()).unwrap();
+        assert!(body.contains("stupid_http_requests_total{method=\"GET\",route=\"/stats\",status=\"200\"} 2\n"));
+        assert!(body.contains("route=\"/graph/nodes/{id}\""));
+        assert!(body.contains("# TYPE stupid_queue_in_flight gauge"));
+    }
+}
@@ -0,0 +1,8 @@
+mod store;
+mod types;
+
+pub use store::*;
+pub use types::*;
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,121 @@
+use std::collections::VecDeque;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+
+use super::types::{QueryAuditEntry, QueryAuditPage, QueryAuditParams};
+
+/// Append-only audit trail of queries executed through the API.
+///
+/// Every entry is appended as one JSON line to
+/// `{data_dir}/query-audit.jsonl` and never rewritten. The most recent
+/// 10 000 entries are also kept in memory to serve `GET /audit/queries`.
+pub struct QueryAuditLog {
+    path: PathBuf,
+    recent: Mutex<Option<Recent>>,
+    pub(crate) max_recent: usize,
+}
+
+struct Recent {
+    entries: VecDeque<QueryAuditEntry>,
+    next_id: u64,
+}
+
+impl QueryAuditLog {
+    /// Create an audit trail storing its file under `data_dir`.
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            path: data_dir.join("query-audit.jsonl"),
+            recent: Mutex::new(None),
+            max_recent: 10_000,
+        }
+    }
+
+    /// Read the tail of the file (lazy, on first access).
+    fn load(&self) -> Recent {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) => {
+                if e.kind() != std::io::ErrorKind::NotFound {
+                    tracing::warn!("Failed to read query audit log {}: {}", self.path.display(), e);
+                }
+                String::new()
+            }
+        };
+
+        let mut entries = VecDeque::new();
+        let mut last_id = 0;
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            match serde_json::from_str::<QueryAuditEntry>(line) {
+                Ok(entry) => {
+                    last_id = last_id.max(entry.entry_id);
+                    entries.push_back(entry);
+                    if entries.len() > self.max_recent {
+                        entries.pop_front();
+                    }
+                }
+                Err(e) => tracing::warn!("Skipping malformed query audit entry: {}", e),
+            }
+        }
+        Recent { entries, next_id: last_id + 1 }
+    }
+
+    /// Append a finished query. Assigns `entry_id` and writes the entry to
+    /// disk before returning.
+    pub fn append(&self, mut entry: QueryAuditEntry) {
+        let mut guard = self.recent.lock().expect("query_audit lock poisoned");
+        let recent = guard.get_or_insert_with(|| self.load());
+
+        entry.entry_id = recent.next_id;
+        recent.next_id += 1;
+
+        let result = serde_json::to_string(&entry).map_err(|e| e.to_string()).and_then(|line| {
+            if let Some(dir) = self.path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)
+                .and_then(|mut file| writeln!(file, "{}", line))
+                .map_err(|e| e.to_string())
+        });
+        if let Err(e) = result {
+            tracing::warn!("Failed to append to query audit log {}: {}", self.path.display(), e);
+        }
+
+        recent.entries.push_back(entry);
+        while recent.entries.len() > self.max_recent {
+            recent.entries.pop_front();
+        }
+    }
+
+    /// Query recent entries with filters, newest first.
+    pub fn query(&self, params: &QueryAuditParams) -> QueryAuditPage {
+        let since: Option<DateTime<Utc>> = params.since.as_ref().and_then(|s| s.parse().ok());
+        let until: Option<DateTime<Utc>> = params.until.as_ref().and_then(|s| s.parse().ok());
+        let offset = params.offset.unwrap_or(0) as usize;
+        let limit = params.limit.unwrap_or(100) as usize;
+
+        let mut guard = self.recent.lock().expect("query_audit lock poisoned");
+        let recent = guard.get_or_insert_with(|| self.load());
+        let matching: Vec<&QueryAuditEntry> = recent
+            .entries
+            .iter()
+            .rev() // newest first
+            .filter(|e| params.endpoint.as_ref().is_none_or(|ep| &e.endpoint == ep))
+            .filter(|e| params.client.as_ref().is_none_or(|c| &e.client == c))
+            .filter(|e| since.is_none_or(|s| e.timestamp >= s))
+            .filter(|e| until.is_none_or(|u| e.timestamp < u))
+            .filter(|e| !params.errors_only.unwrap_or(false) || e.error.is_some())
+            .collect();
+
+        QueryAuditPage {
+            total: matching.len(),
+            offset,
+            entries: matching.into_iter().skip(offset).take(limit).cloned().collect(),
+        }
+    }
+}
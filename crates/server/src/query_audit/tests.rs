@@ -0,0 +1,70 @@
+use super::*;
+
+fn entry(endpoint: &str, client: &str, error: Option<&str>) -> QueryAuditEntry {
+    let pending = PendingQuery::start(endpoint, &QueryClient(client.to_string()), "SELECT 1");
+    pending.finish(match error {
+        Some(e) => Err(e.to_string()),
+        None => Ok(Some(1)),
+    })
+}
+
+#[test]
+fn test_append_and_query_newest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = QueryAuditLog::new(dir.path());
+
+    log.append(entry("/query", "key:ci", None));
+    log.append(entry("/catalog/query", "key:ops", None));
+    log.append(entry("/query", "ip:10.0.0.1", Some("boom")));
+
+    let page = log.query(&QueryAuditParams::default());
+    assert_eq!(page.total, 3);
+    let ids: Vec<_> = page.entries.iter().map(|e| e.entry_id).collect();
+    assert_eq!(ids, [3, 2, 1]);
+
+    let by_endpoint = log.query(&QueryAuditParams { endpoint: Some("/query".into()), ..Default::default() });
+    assert_eq!(by_endpoint.total, 2);
+    let by_client = log.query(&QueryAuditParams { client: Some("key:ops".into()), ..Default::default() });
+    assert_eq!(by_client.entries[0].endpoint, "/catalog/query");
+    let errors = log.query(&QueryAuditParams { errors_only: Some(true), ..Default::default() });
+    assert_eq!(errors.total, 1);
+    assert_eq!(errors.entries[0].error.as_deref(), Some("boom"));
+    assert_eq!(errors.entries[0].row_count, None);
+
+    let paged = log.query(&QueryAuditParams { offset: Some(1), limit: Some(1), ..Default::default() });
+    assert_eq!(paged.total, 3);
+    assert_eq!(paged.entries[0].entry_id, 2);
+}
+
+#[test]
+fn test_entries_are_appended_and_survive_restart() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = QueryAuditLog::new(dir.path());
+    log.append(entry("/query", "key:ci", None));
+    log.append(entry("/query", "key:ci", None));
+
+    let content = std::fs::read_to_string(dir.path().join("query-audit.jsonl")).unwrap();
+    assert_eq!(content.lines().count(), 2);
+
+    // A fresh store continues the trail instead of overwriting it.
+    let reopened = QueryAuditLog::new(dir.path());
+    reopened.append(entry("/catalog/query", "key:ci", None));
+    let content = std::fs::read_to_string(dir.path().join("query-audit.jsonl")).unwrap();
+    assert_eq!(content.lines().count(), 3);
+    let page = reopened.query(&QueryAuditParams::default());
+    assert_eq!(page.entries[0].entry_id, 3);
+    assert_eq!(page.total, 3);
+}
+
+#[test]
+fn test_memory_window_is_bounded() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut log = QueryAuditLog::new(dir.path());
+    log.max_recent = 2;
+    for _ in 0..5 {
+        log.append(entry("/query", "key:ci", None));
+    }
+    assert_eq!(log.query(&QueryAuditParams::default()).total, 2);
+    let content = std::fs::read_to_string(dir.path().join("query-audit.jsonl")).unwrap();
+    assert_eq!(content.lines().count(), 5);
+}
@@ -0,0 +1,147 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::state::AppState;
+
+// ---------------------------------------------------------------------------
+// Core audit entry
+// ---------------------------------------------------------------------------
+
+/// One query executed through the API, as recorded in the audit trail.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct QueryAuditEntry {
+    /// Position in the trail, starting at 1.
+    pub entry_id: u64,
+    /// When the request arrived.
+    #[schema(value_type = String)]
+    pub timestamp: DateTime<Utc>,
+    /// Route template that served the query, e.g. `/catalog/query`.
+    pub endpoint: String,
+    /// `key:{label}` for the API key used, else `ip:{addr}`. Never the key.
+    pub client: String,
+    /// Athena connection the SQL ran against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub connection_id: Option<String>,
+    /// The question, SQL, or JSON query plan as submitted.
+    pub query: String,
+    /// Plan generated for a natural-language question.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    pub plan: Option<serde_json::Value>,
+    /// Rows returned; `None` when the query failed or the count is unknown.
+    #[serde(default)]
+    pub row_count: Option<u64>,
+    pub duration_ms: u64,
+    /// Set when the query failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A query that has started but not finished; [`record`](Self::record)
+/// writes its audit entry.
+#[derive(Debug, Clone)]
+pub struct PendingQuery {
+    entry: QueryAuditEntry,
+    started: Instant,
+}
+
+impl PendingQuery {
+    /// Start timing a query of `query` by `client` on `endpoint`.
+    pub fn start(endpoint: &str, client: &QueryClient, query: impl Into<String>) -> Self {
+        Self {
+            entry: QueryAuditEntry {
+                entry_id: 0,
+                timestamp: Utc::now(),
+                endpoint: endpoint.to_string(),
+                client: client.0.clone(),
+                connection_id: None,
+                query: query.into(),
+                plan: None,
+                row_count: None,
+                duration_ms: 0,
+                error: None,
+            },
+            started: Instant::now(),
+        }
+    }
+
+    pub fn with_connection(mut self, connection_id: &str) -> Self {
+        self.entry.connection_id = Some(connection_id.to_string());
+        self
+    }
+
+    pub fn set_plan(&mut self, plan: serde_json::Value) {
+        self.entry.plan = Some(plan);
+    }
+
+    /// The finished entry: `Ok(rows)` for a query that returned `rows`
+    /// rows (if known), `Err(message)` for one that failed.
+    pub fn finish(&self, outcome: Result<Option<u64>, String>) -> QueryAuditEntry {
+        let mut entry = self.entry.clone();
+        entry.duration_ms = self.started.elapsed().as_millis() as u64;
+        match outcome {
+            Ok(rows) => entry.row_count = rows,
+            Err(error) => entry.error = Some(error),
+        }
+        entry
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Client extractor
+// ---------------------------------------------------------------------------
+
+/// Who sent a request, as recorded in the audit trail; see
+/// [`crate::auth::client_id`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryClient(pub String);
+
+impl FromRequestParts<Arc<AppState>> for QueryClient {
+    type Rejection = std::convert::Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, state: &Arc<AppState>) -> Result<Self, Self::Rejection> {
+        Ok(Self(crate::auth::client_id(&state.api_keys, &parts.headers, &parts.extensions)))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// REST query parameters
+// ---------------------------------------------------------------------------
+
+/// Query-string parameters for the `GET /audit/queries` endpoint.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct QueryAuditParams {
+    /// Only entries for this route template, e.g. `/query`.
+    pub endpoint: Option<String>,
+    /// Only entries from this client, e.g. `key:ci`.
+    pub client: Option<String>,
+    /// ISO 8601 lower bound on `timestamp` (inclusive).
+    pub since: Option<String>,
+    /// ISO 8601 upper bound on `timestamp` (exclusive).
+    pub until: Option<String>,
+    /// Only failed queries.
+    pub errors_only: Option<bool>,
+    /// Filtered entries to skip, newest first (default 0).
+    pub offset: Option<u32>,
+    /// Maximum entries to return (default 100).
+    pub limit: Option<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// Response types
+// ---------------------------------------------------------------------------
+
+/// One page of the query audit trail.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct QueryAuditPage {
+    /// Entries matching the filters, before pagination.
+    pub total: usize,
+    pub offset: usize,
+    /// Entries on this page, newest first.
+    pub entries: Vec<QueryAuditEntry>,
+}
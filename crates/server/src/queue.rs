@@ -1,7 +1,7 @@
-//! Background SQS queue consumer tasks — store-driven.
+//! Background queue consumer tasks (SQS / Kafka) — store-driven.
 //!
 //! Reads enabled queue connections from `QueueConnectionStore`, spawns one
-//! consumer per connection. Each consumer polls SQS, accumulates into
+//! consumer per connection. Each consumer polls its source, accumulates into
 //! micro-batches, parses into Documents, persists to segments, applies graph
 //! extraction, runs the compute pipeline, and broadcasts updates to WebSocket
 //! clients.
@@ -10,12 +10,17 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::Ordering;
 
+use tokio_util::sync::CancellationToken;
 use tracing::{error, info, warn};
 
-use stupid_queue::{MicroBatcher, QueueConsumer, SqsConsumer, parse_batch};
+use stupid_queue::dlq::{route_exhausted, route_parse_failures};
+use stupid_queue::{
+    ArchivingConsumer, MessageArchive, MicroBatcher, QueueConsumer, QueueError, QueueMetrics, SqsConsumer,
+    parse_batch,
+};
 
 use crate::queue_connections::QueueConnectionConfig;
-use crate::state::{AppState, QueueMetrics};
+use crate::state::AppState;
 
 /// Extract the queue name from the queue URL.
 ///
@@ -29,18 +34,41 @@ fn queue_name_from_url(url: &str) -> String {
         .to_string()
 }
 
-/// Spawn one SQS consumer per enabled queue connection in the store.
+/// Connect the consumer backend matching the connection's provider.
+///
+/// The consumer reports health from `metrics`, which the poll loop updates.
+async fn connect_consumer(
+    aws_config: &stupid_core::config::AwsConfig,
+    queue_config: &stupid_core::config::QueueConfig,
+    metrics: Arc<QueueMetrics>,
+) -> Result<Box<dyn QueueConsumer>, QueueError> {
+    match queue_config.provider.as_str() {
+        #[cfg(feature = "queue-kafka")]
+        "kafka" => Ok(Box::new(stupid_queue::KafkaConsumer::new(queue_config)?.with_metrics(metrics))),
+        #[cfg(not(feature = "queue-kafka"))]
+        "kafka" => Err(QueueError::Provider(
+            "Kafka support not compiled in (enable the `queue-kafka` feature)".into(),
+        )),
+        _ => Ok(Box::new(SqsConsumer::new(aws_config, queue_config).await?.with_metrics(metrics))),
+    }
+}
+
+/// Spawn one queue consumer per enabled queue connection in the store.
 ///
 /// Waits for initial data loading to complete before starting consumption,
-/// so graph and compute state are fully initialized.
-pub async fn spawn_queue_consumers(app_state: Arc<AppState>) {
+/// so graph and compute state are fully initialized. Returns once every
+/// consumer has stopped after `shutdown` is cancelled.
+pub async fn spawn_queue_consumers(app_state: Arc<AppState>, shutdown: CancellationToken) {
     // Wait for initial data load to finish before consuming queue messages.
     info!("Queue consumers waiting for initial data load...");
     loop {
         if app_state.loading.is_ready().await {
             break;
         }
-        tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        tokio::select! {
+            _ = shutdown.cancelled() => return,
+            _ = tokio::time::sleep(std::time::Duration::from_secs(2)) => {}
+        }
     }
 
     // Read all queue connections from the encrypted store.
@@ -63,18 +91,23 @@ pub async fn spawn_queue_consumers(app_state: Arc<AppState>) {
 
     info!("Spawning {} queue consumer(s) from connection store", enabled.len());
 
-    for config in enabled {
-        let state = app_state.clone();
-        tokio::spawn(async move {
-            run_queue_consumer(config, state).await;
-        });
+    let consumers: Vec<_> = enabled
+        .into_iter()
+        .map(|config| tokio::spawn(run_queue_consumer(config, app_state.clone(), shutdown.clone())))
+        .collect();
+    for consumer in consumers {
+        if let Err(e) = consumer.await {
+            error!("Queue consumer task failed: {}", e);
+        }
     }
 }
 
 /// Run a single queue consumer for the given connection config.
 ///
-/// Creates per-queue metrics, connects to SQS, and enters the poll loop.
-async fn run_queue_consumer(config: QueueConnectionConfig, app_state: Arc<AppState>) {
+/// Creates per-queue metrics, connects to the provider, and enters the poll loop.
+/// Once `shutdown` is cancelled, the messages already received are processed
+/// and the consumer returns.
+async fn run_queue_consumer(config: QueueConnectionConfig, app_state: Arc<AppState>, shutdown: CancellationToken) {
     let queue_id = config.id.clone();
     let queue_name = config.name.clone();
 
@@ -86,27 +119,46 @@ async fn run_queue_consumer(config: QueueConnectionConfig, app_state: Arc<AppSta
         map.insert(queue_id.clone(), metrics.clone());
     }
 
-    // Convert store config to the types SqsConsumer::new() expects.
+    // Convert store config to the types the consumer constructors expect.
     let aws_config = config.to_aws_config();
     let queue_config = config.to_queue_config();
 
-    if queue_config.queue_url.is_empty() {
+    // Kafka is addressed by topic; every other provider by queue URL.
+    let source = if queue_config.provider == "kafka" {
+        queue_config.kafka.topic.clone()
+    } else {
+        queue_config.queue_url.clone()
+    };
+
+    if source.is_empty() {
         warn!(
             queue_id = %queue_id,
             queue_name = %queue_name,
-            "Queue URL is empty — skipping consumer"
+            provider = %queue_config.provider,
+            "Queue URL/topic is empty — skipping consumer"
         );
         return;
     }
 
-    // Create SQS consumer with decrypted credentials.
-    let consumer = match SqsConsumer::new(&aws_config, &queue_config).await {
+    // Compute the queue storage directory: data/{provider}/{queue_name}/
+    let url_queue_name = queue_name_from_url(&source);
+    let queue_base_dir = app_state.data_dir.join(&config.provider).join(&url_queue_name);
+    info!(
+        queue_dir = %queue_base_dir.display(),
+        provider = %config.provider,
+        queue_name = %url_queue_name,
+        "Queue data will be stored at"
+    );
+
+    // Create the consumer with decrypted credentials.
+    let consumer = match connect_consumer(&aws_config, &queue_config, metrics.clone()).await {
         Ok(c) => {
             info!(
                 queue_id = %queue_id,
                 queue_name = %queue_name,
-                queue_url = %queue_config.queue_url,
-                "SQS consumer connected"
+                provider = %queue_config.provider,
+                source = %source,
+                "Queue consumer connected"
             );
             metrics.connected.store(true, Ordering::Relaxed);
             c
@@ -115,22 +167,29 @@ async fn run_queue_consumer(config: QueueConnectionConfig, app_state: Arc<AppSta
             error!(
                 queue_id = %queue_id,
                 queue_name = %queue_name,
-                "Failed to create SQS consumer: {} — consumer disabled",
+                "Failed to create queue consumer: {} — consumer disabled",
                 e
             );
             return;
         }
     };
 
-    // Compute the queue storage directory: data/{provider}/{queue_name}/
-    let url_queue_name = queue_name_from_url(&queue_config.queue_url);
-    let queue_base_dir = app_state.data_dir.join(&config.provider).join(&url_queue_name);
-    info!(
-        queue_dir = %queue_base_dir.display(),
-        provider = %config.provider,
-        queue_name = %url_queue_name,
-        "Queue data will be stored at"
-    );
+    // Raw payloads go to data/{provider}/{queue_name}/archive/ for replay.
+    let consumer: Box<dyn QueueConsumer> = if queue_config.archive_enabled {
+        let archive_dir = queue_base_dir.join("archive");
+        info!(
+            queue_id = %queue_id,
+            archive_dir = %archive_dir.display(),
+            retention_days = queue_config.archive_retention_days,
+            "Queue message archiving enabled"
+        );
+        Box::new(ArchivingConsumer::new(
+            consumer,
+            MessageArchive::new(archive_dir, queue_config.archive_retention_days),
+        ))
+    } else {
+        consumer
+    };
 
     let mut batcher = MicroBatcher::new(
         queue_config.micro_batch_size,
@@ -148,37 +207,31 @@ async fn run_queue_consumer(config: QueueConnectionConfig, app_state: Arc<AppSta
     );
 
     loop {
-        // Poll SQS for messages.
-        match consumer.poll_batch(queue_config.max_batch_size).await {
+        // Poll the source for messages. A poll dropped on shutdown acks
+        // nothing, so its messages are redelivered later.
+        let polled = tokio::select! {
+            _ = shutdown.cancelled() => break,
+            polled = consumer.poll_batch(queue_config.max_batch_size) => polled,
+        };
+        match polled {
             Ok(messages) if !messages.is_empty() => {
-                metrics.messages_received.fetch_add(messages.len() as u64, Ordering::Relaxed);
-                metrics.last_poll_epoch_ms.store(
-                    std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_millis() as u64,
-                    Ordering::Relaxed,
-                );
+                metrics.record_poll(&messages);
                 batcher.push(messages);
             }
             Ok(_) => {
                 // Empty poll — update last poll time.
-                metrics.last_poll_epoch_ms.store(
-                    std::time::SystemTime::now()
-                        .duration_since(std::time::UNIX_EPOCH)
-                        .unwrap_or_default()
-                        .as_millis() as u64,
-                    Ordering::Relaxed,
-                );
+                metrics.record_poll(&[]);
             }
             Err(e) => {
                 warn!(
                     queue_id = %queue_id,
-                    "SQS poll error: {} — retrying in {:?}", e, poll_interval
+                    "Queue poll error: {} — retrying in {:?}", e, poll_interval
                 );
                 metrics.connected.store(false, Ordering::Relaxed);
-                tokio::time::sleep(poll_interval).await;
-                continue;
+                tokio::select! {
+                    _ = shutdown.cancelled() => break,
+                    _ = tokio::time::sleep(poll_interval) => continue,
+                }
             }
         }
 
@@ -186,11 +239,37 @@ async fn run_queue_consumer(config: QueueConnectionConfig, app_state: Arc<AppSta
 
         // Flush if size or time threshold is met.
         if let Some(batch) = batcher.try_flush() {
-            process_batch(&batch, &consumer, &app_state, &queue_base_dir, &metrics).await;
+            process_batch(
+                batch,
+                consumer.as_ref(),
+                queue_config.max_receive_count,
+                &app_state,
+                &queue_base_dir,
+                &metrics,
+            )
+            .await;
+        }
+
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            _ = tokio::time::sleep(poll_interval) => {}
         }
+    }
 
-        tokio::time::sleep(poll_interval).await;
+    // Ingest what was received but not yet flushed before the writer is finalized.
+    if !batcher.is_empty() {
+        process_batch(
+            batcher.flush(),
+            consumer.as_ref(),
+            queue_config.max_receive_count,
+            &app_state,
+            &queue_base_dir,
+            &metrics,
+        )
+        .await;
     }
+    metrics.connected.store(false, Ordering::Relaxed);
+    info!(queue_id = %queue_id, "Queue consumer stopped");
 }
 
 /// Persist parsed documents to a daily segment file under the queue directory.
@@ -265,31 +344,44 @@ fn build_message_summaries(docs: &[stupid_core::Document]) -> Vec<serde_json::Va
         .collect()
 }
 
-/// Process a flushed micro-batch: parse → persist → graph → pipeline → ack/nack.
+/// Process a flushed micro-batch: DLQ → parse → persist → graph → pipeline → ack.
+///
+/// Messages past `max_receive_count` and unparseable messages are moved to the
+/// DLQ before the rest are ingested. Without a DLQ they are nacked while
+/// retries remain, and logged and dropped otherwise.
 async fn process_batch(
-    messages: &[stupid_queue::QueueMessage],
-    consumer: &SqsConsumer,
+    batch: Vec<stupid_queue::QueueMessage>,
+    consumer: &dyn QueueConsumer,
+    max_receive_count: u32,
     app_state: &Arc<AppState>,
     queue_base_dir: &PathBuf,
     metrics: &Arc<QueueMetrics>,
 ) {
     let batch_start = std::time::Instant::now();
 
+    let (messages, exhausted) = route_exhausted(consumer, batch, max_receive_count).await;
+    let messages = messages.as_slice();
+
     let (docs, errors) = parse_batch(messages);
+    let dead_lettered = route_parse_failures(consumer, messages, &errors, max_receive_count).await;
 
-    for (msg_id, err) in &errors {
-        warn!(message_id = %msg_id, error = %err, "Nacking unparseable message");
-        if let Some(handle) = messages.iter()
-            .find(|m| m.id == *msg_id)
-            .map(|m| &m.receipt_handle)
-        {
-            if let Err(e) = consumer.nack(handle).await {
-                warn!(message_id = %msg_id, "Failed to nack: {}", e);
-            }
-        }
+    let routed = exhausted.dead_lettered + dead_lettered.dead_lettered;
+    let nacked = exhausted.nacked + dead_lettered.nacked;
+    let dropped = exhausted.dropped + dead_lettered.dropped;
+    if routed > 0 || nacked > 0 || dropped > 0 {
+        warn!(
+            dead_lettered = routed,
+            nacked,
+            dropped,
+            routing_failures = exhausted.failed + dead_lettered.failed,
+            "Routed failed queue messages"
+        );
     }
+    let failed = (exhausted.dead_lettered + exhausted.nacked + exhausted.dropped + exhausted.failed + errors.len()) as u64;
+    let routed = routed as u64;
 
     if docs.is_empty() {
+        metrics.record_batch(0, failed, routed, batch_start.elapsed());
         return;
     }
 
@@ -300,7 +392,7 @@ async fn process_batch(
     let graph_ops_count = {
         let mut all_ops = Vec::new();
         for doc in &docs {
-            crate::graph_ops::extract_graph_ops(doc, "queue", &mut all_ops);
+            crate::graph_ops::extract_graph_ops(doc, &app_state.graph_mapping, &mut all_ops);
         }
 
         let mut graph = app_state.graph.write().await;
@@ -320,15 +412,14 @@ async fn process_batch(
         .doc_count
         .fetch_add(docs.len() as u64, std::sync::atomic::Ordering::Relaxed);
 
-    let _ = app_state.broadcast.send(
+    let _ = app_state.broadcast.send(crate::ws::envelope(
+        "queue_batch",
         serde_json::json!({
-            "type": "queue_batch",
             "docs": docs.len(),
             "graph_ops": graph_ops_count,
             "messages": message_summaries,
-        })
-        .to_string(),
-    );
+        }),
+    ));
 
     let error_ids: std::collections::HashSet<&str> = errors.iter().map(|(id, _)| id.as_str()).collect();
     for msg in messages {
@@ -340,10 +431,7 @@ async fn process_batch(
     }
 
     let elapsed = batch_start.elapsed();
-    metrics.messages_processed.fetch_add(docs.len() as u64, Ordering::Relaxed);
-    metrics.messages_failed.fetch_add(errors.len() as u64, Ordering::Relaxed);
-    metrics.batches_processed.fetch_add(1, Ordering::Relaxed);
-    metrics.total_processing_time_us.fetch_add(elapsed.as_micros() as u64, Ordering::Relaxed);
+    metrics.record_batch(docs.len() as u64, failed, routed, elapsed);
 
     info!(
         docs = docs.len(),
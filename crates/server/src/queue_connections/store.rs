@@ -94,6 +94,12 @@ impl CredentialStore for QueueConnectionStore {
             visibility_timeout_secs: input.visibility_timeout_secs,
             micro_batch_size: input.micro_batch_size,
             micro_batch_timeout_ms: input.micro_batch_timeout_ms,
+            max_receive_count: input.max_receive_count,
+            archive_enabled: input.archive_enabled,
+            archive_retention_days: input.archive_retention_days,
+            kafka_brokers: input.kafka_brokers.clone(),
+            kafka_topic: input.kafka_topic.clone(),
+            kafka_group_id: input.kafka_group_id.clone(),
             color: input.color.clone(),
             created_at: created_at.to_string(),
             updated_at: updated_at.to_string(),
@@ -142,6 +148,12 @@ impl CredentialStore for QueueConnectionStore {
             visibility_timeout_secs: input.visibility_timeout_secs,
             micro_batch_size: input.micro_batch_size,
             micro_batch_timeout_ms: input.micro_batch_timeout_ms,
+            max_receive_count: input.max_receive_count,
+            archive_enabled: input.archive_enabled,
+            archive_retention_days: input.archive_retention_days,
+            kafka_brokers: input.kafka_brokers.clone(),
+            kafka_topic: input.kafka_topic.clone(),
+            kafka_group_id: input.kafka_group_id.clone(),
             color: input.color.clone(),
             created_at: created_at.to_string(),
             updated_at: updated_at.to_string(),
@@ -170,6 +182,12 @@ impl CredentialStore for QueueConnectionStore {
             visibility_timeout_secs: stored.visibility_timeout_secs,
             micro_batch_size: stored.micro_batch_size,
             micro_batch_timeout_ms: stored.micro_batch_timeout_ms,
+            max_receive_count: stored.max_receive_count,
+            archive_enabled: stored.archive_enabled,
+            archive_retention_days: stored.archive_retention_days,
+            kafka_brokers: stored.kafka_brokers.clone(),
+            kafka_topic: stored.kafka_topic.clone(),
+            kafka_group_id: stored.kafka_group_id.clone(),
             color: stored.color.clone(),
             created_at: stored.created_at.clone(),
             updated_at: stored.updated_at.clone(),
@@ -194,6 +212,12 @@ impl CredentialStore for QueueConnectionStore {
             visibility_timeout_secs: config.visibility_timeout_secs,
             micro_batch_size: config.micro_batch_size,
             micro_batch_timeout_ms: config.micro_batch_timeout_ms,
+            max_receive_count: config.max_receive_count,
+            archive_enabled: config.archive_enabled,
+            archive_retention_days: config.archive_retention_days,
+            kafka_brokers: config.kafka_brokers.clone(),
+            kafka_topic: config.kafka_topic.clone(),
+            kafka_group_id: config.kafka_group_id.clone(),
             color: config.color.clone(),
             created_at: config.created_at.clone(),
             updated_at: config.updated_at.clone(),
@@ -233,6 +257,12 @@ impl CredentialStore for QueueConnectionStore {
             visibility_timeout_secs: stored.visibility_timeout_secs,
             micro_batch_size: stored.micro_batch_size,
             micro_batch_timeout_ms: stored.micro_batch_timeout_ms,
+            max_receive_count: stored.max_receive_count,
+            archive_enabled: stored.archive_enabled,
+            archive_retention_days: stored.archive_retention_days,
+            kafka_brokers: stored.kafka_brokers.clone(),
+            kafka_topic: stored.kafka_topic.clone(),
+            kafka_group_id: stored.kafka_group_id.clone(),
             color: stored.color.clone(),
             created_at: stored.created_at.clone(),
             updated_at: stored.updated_at.clone(),
@@ -24,6 +24,12 @@ fn make_input(name: &str) -> QueueConnectionInput {
         visibility_timeout_secs: default_visibility_timeout_secs(),
         micro_batch_size: default_micro_batch_size(),
         micro_batch_timeout_ms: default_micro_batch_timeout_ms(),
+        max_receive_count: default_max_receive_count(),
+        archive_enabled: false,
+        archive_retention_days: default_archive_retention_days(),
+        kafka_brokers: String::new(),
+        kafka_topic: String::new(),
+        kafka_group_id: default_kafka_group_id(),
         color: default_color(),
     }
 }
@@ -36,6 +36,18 @@ pub(super) fn default_micro_batch_timeout_ms() -> u64 {
     1000
 }
 
+pub(super) fn default_max_receive_count() -> u32 {
+    5
+}
+
+pub(super) fn default_archive_retention_days() -> u32 {
+    stupid_queue::archive::DEFAULT_ARCHIVE_RETENTION_DAYS
+}
+
+pub(super) fn default_kafka_group_id() -> String {
+    "stupid-db".to_string()
+}
+
 pub(super) fn default_color() -> String {
     "#ff8a00".to_string()
 }
@@ -61,6 +73,12 @@ pub struct QueueConnectionConfig {
     pub visibility_timeout_secs: u32,
     pub micro_batch_size: usize,
     pub micro_batch_timeout_ms: u64,
+    pub max_receive_count: u32,
+    pub archive_enabled: bool,
+    pub archive_retention_days: u32,
+    pub kafka_brokers: String,
+    pub kafka_topic: String,
+    pub kafka_group_id: String,
     pub color: String,
     pub created_at: String,
     pub updated_at: String,
@@ -88,6 +106,12 @@ pub struct QueueConnectionSafe {
     pub visibility_timeout_secs: u32,
     pub micro_batch_size: usize,
     pub micro_batch_timeout_ms: u64,
+    pub max_receive_count: u32,
+    pub archive_enabled: bool,
+    pub archive_retention_days: u32,
+    pub kafka_brokers: String,
+    pub kafka_topic: String,
+    pub kafka_group_id: String,
     pub color: String,
     pub created_at: String,
     pub updated_at: String,
@@ -131,10 +155,11 @@ impl QueueConnectionConfig {
             s3_bucket: None,
             s3_prefix: None,
             endpoint_url: self.endpoint_url.clone(),
+            s3_multipart_threshold_bytes: stupid_core::config::DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
         }
     }
 
-    /// Build a `QueueConfig` suitable for `SqsConsumer::new()`.
+    /// Build a `QueueConfig` suitable for `SqsConsumer::new()` / `KafkaConsumer::new()`.
     pub fn to_queue_config(&self) -> stupid_core::config::QueueConfig {
         stupid_core::config::QueueConfig {
             enabled: self.enabled,
@@ -146,7 +171,15 @@ impl QueueConnectionConfig {
             micro_batch_size: self.micro_batch_size,
             micro_batch_timeout_ms: self.micro_batch_timeout_ms,
             dlq_url: self.dlq_url.clone(),
+            max_receive_count: self.max_receive_count,
+            archive_enabled: self.archive_enabled,
+            archive_retention_days: self.archive_retention_days,
             aws: self.to_aws_config(),
+            kafka: stupid_core::config::KafkaConfig {
+                brokers: self.kafka_brokers.clone(),
+                topic: self.kafka_topic.clone(),
+                group_id: self.kafka_group_id.clone(),
+            },
         }
     }
 }
@@ -182,10 +215,76 @@ pub struct QueueConnectionInput {
     pub micro_batch_size: usize,
     #[serde(default = "default_micro_batch_timeout_ms")]
     pub micro_batch_timeout_ms: u64,
+    #[serde(default = "default_max_receive_count")]
+    pub max_receive_count: u32,
+    /// Archive raw received messages under the queue's data directory.
+    #[serde(default)]
+    pub archive_enabled: bool,
+    /// Days of message archives to keep; 0 keeps all.
+    #[serde(default = "default_archive_retention_days")]
+    pub archive_retention_days: u32,
+    /// Comma-separated Kafka bootstrap brokers (provider "kafka" only).
+    #[serde(default)]
+    pub kafka_brokers: String,
+    /// Kafka topic to consume (provider "kafka" only).
+    #[serde(default)]
+    pub kafka_topic: String,
+    /// Kafka consumer group (provider "kafka" only).
+    #[serde(default = "default_kafka_group_id")]
+    pub kafka_group_id: String,
     #[serde(default = "default_color")]
     pub color: String,
 }
 
+impl QueueConnectionInput {
+    /// Config for testing this input before it is saved. Blank keys fall
+    /// back to `existing`'s, as on update, but only while the input points at
+    /// the same queue: stored secrets are never sent anywhere else.
+    pub fn to_config(&self, id: &str, existing: Option<&QueueConnectionConfig>) -> anyhow::Result<QueueConnectionConfig> {
+        let same_target = existing.is_some_and(|c| {
+            c.provider == self.provider
+                && c.queue_url == self.queue_url
+                && c.endpoint_url == self.endpoint_url
+                && c.region == self.region
+                && c.kafka_brokers == self.kafka_brokers
+        });
+        let secret = |input: &str, stored: fn(&QueueConnectionConfig) -> &str| match existing {
+            Some(existing) if input.is_empty() && !stored(existing).is_empty() => {
+                anyhow::ensure!(same_target, "The queue location changed; enter the credentials to test it");
+                Ok(stored(existing).to_string())
+            }
+            _ => Ok(input.to_string()),
+        };
+        Ok(QueueConnectionConfig {
+            id: id.to_string(),
+            name: self.name.clone(),
+            queue_url: self.queue_url.clone(),
+            dlq_url: self.dlq_url.clone(),
+            provider: self.provider.clone(),
+            enabled: self.enabled,
+            region: self.region.clone(),
+            access_key_id: secret(&self.access_key_id, |c| &c.access_key_id)?,
+            secret_access_key: secret(&self.secret_access_key, |c| &c.secret_access_key)?,
+            session_token: secret(&self.session_token, |c| &c.session_token)?,
+            endpoint_url: self.endpoint_url.clone(),
+            poll_interval_ms: self.poll_interval_ms,
+            max_batch_size: self.max_batch_size,
+            visibility_timeout_secs: self.visibility_timeout_secs,
+            micro_batch_size: self.micro_batch_size,
+            micro_batch_timeout_ms: self.micro_batch_timeout_ms,
+            max_receive_count: self.max_receive_count,
+            archive_enabled: self.archive_enabled,
+            archive_retention_days: self.archive_retention_days,
+            kafka_brokers: self.kafka_brokers.clone(),
+            kafka_topic: self.kafka_topic.clone(),
+            kafka_group_id: self.kafka_group_id.clone(),
+            color: self.color.clone(),
+            created_at: String::new(),
+            updated_at: String::new(),
+        })
+    }
+}
+
 // ── On-disk format ───────────────────────────────────────────────────
 
 /// On-disk format: credentials stored as encrypted hex strings.
@@ -207,6 +306,18 @@ pub(crate) struct StoredQueueConnection {
     pub(super) visibility_timeout_secs: u32,
     pub(super) micro_batch_size: usize,
     pub(super) micro_batch_timeout_ms: u64,
+    #[serde(default = "default_max_receive_count")]
+    pub(super) max_receive_count: u32,
+    #[serde(default)]
+    pub(super) archive_enabled: bool,
+    #[serde(default = "default_archive_retention_days")]
+    pub(super) archive_retention_days: u32,
+    #[serde(default)]
+    pub(super) kafka_brokers: String,
+    #[serde(default)]
+    pub(super) kafka_topic: String,
+    #[serde(default = "default_kafka_group_id")]
+    pub(super) kafka_group_id: String,
     pub(super) color: String,
     pub(super) created_at: String,
     pub(super) updated_at: String,
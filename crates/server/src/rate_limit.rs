@@ -0,0 +1,225 @@
+//! Per-client token-bucket rate limiting.
+//!
+//! Clients are identified by the label of the API key they present, or by
+//! their IP address when auth is disabled. Each client gets one bucket per
+//! configured route limit (`RATE_LIMITS`); a route uses the longest matching
+//! prefix, falling back to `*`. Requests over the limit get 429 with
+//! `Retry-After`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+
+use stupid_core::config::{RateLimitConfig, RouteLimit};
+
+use crate::api::QueryErrorResponse;
+use crate::auth::{self, ApiKeys};
+
+/// Route that falls back to when no other limit matches.
+const DEFAULT_ROUTE: &str = "*";
+
+/// Bucket count above which idle (fully refilled) buckets are dropped.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// Tokens available to one client on one route.
+struct TokenBucket {
+    tokens: f64,
+    refilled_at: Instant,
+}
+
+impl TokenBucket {
+    fn full(limit: &RouteLimit, now: Instant) -> Self {
+        Self { tokens: limit.burst as f64, refilled_at: now }
+    }
+
+    fn refill(&mut self, limit: &RouteLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_minute as f64 / 60.0).min(limit.burst as f64);
+        self.refilled_at = now;
+    }
+
+    /// Take one token, or return how long until one is available.
+    fn try_take(&mut self, limit: &RouteLimit, now: Instant) -> Result<(), Duration> {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) * 60.0 / limit.per_minute as f64))
+        }
+    }
+}
+
+/// Shared limiter state; cheap to clone.
+#[derive(Clone)]
+pub struct RateLimiter {
+    enabled: bool,
+    limits: Arc<Vec<RouteLimit>>,
+    api_keys: ApiKeys,
+    buckets: Arc<Mutex<HashMap<(String, String), TokenBucket>>>,
+}
+
+impl RateLimiter {
+    pub fn new(config: &RateLimitConfig, api_keys: ApiKeys) -> Self {
+        Self {
+            enabled: config.enabled && !config.routes.is_empty(),
+            limits: Arc::new(config.routes.clone()),
+            api_keys,
+            buckets: Arc::default(),
+        }
+    }
+
+    /// The limit for `path`: the longest route matching it on a segment
+    /// boundary, else `*`.
+    fn limit_for(&self, path: &str) -> Option<&RouteLimit> {
+        self.limits
+            .iter()
+            .filter(|limit| {
+                let route = limit.route.trim_end_matches('/');
+                path == route || path.strip_prefix(route).is_some_and(|rest| rest.starts_with('/'))
+            })
+            .max_by_key(|limit| limit.route.len())
+            .or_else(|| self.limits.iter().find(|limit| limit.route == DEFAULT_ROUTE))
+    }
+
+    /// Count a request from `client` to `path` at `now`.
+    fn check(&self, client: &str, path: &str, now: Instant) -> Result<(), Duration> {
+        let Some(limit) = self.limit_for(path) else {
+            return Ok(());
+        };
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() >= PRUNE_THRESHOLD {
+            buckets.retain(|(_, route), bucket| {
+                let Some(limit) = self.limits.iter().find(|l| &l.route == route) else {
+                    return false;
+                };
+                bucket.refill(limit, now);
+                bucket.tokens < limit.burst as f64
+            });
+        }
+        buckets
+            .entry((client.to_string(), limit.route.clone()))
+            .or_insert_with(|| TokenBucket::full(limit, now))
+            .try_take(limit, now)
+    }
+
+    /// Who a request is counted against: its API key label, else its IP.
+    fn client_of(&self, request: &Request) -> String {
+        auth::client_id(&self.api_keys, request.headers(), request.extensions())
+    }
+}
+
+/// Middleware rejecting requests over the client's limit with 429.
+pub async fn limit_requests(State(limiter): State<RateLimiter>, request: Request, next: Next) -> Response {
+    let path = request.uri().path();
+    if !limiter.enabled || auth::PUBLIC_PATHS.contains(&path) {
+        return next.run(request).await;
+    }
+
+    let client = limiter.client_of(&request);
+    match limiter.check(&client, path, Instant::now()) {
+        Ok(()) => next.run(request).await,
+        Err(retry_after) => {
+            tracing::debug!("{} {} rate limited for {}", request.method(), path, client);
+            let seconds = retry_after.as_secs_f64().ceil().max(1.0) as u64;
+            (
+                StatusCode::TOO_MANY_REQUESTS,
+                [(header::RETRY_AFTER, seconds.to_string())],
+                Json(QueryErrorResponse { error: format!("Rate limit exceeded, retry in {}s", seconds) }),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use axum::body::Body;
+    use axum::routing::get;
+    use axum::{middleware, Router};
+    use stupid_core::config::ApiKey;
+    use tower::ServiceExt;
+
+    fn limit(route: &str, burst: u32, per_minute: u32) -> RouteLimit {
+        RouteLimit { route: route.to_string(), burst, per_minute }
+    }
+
+    fn limiter(routes: Vec<RouteLimit>, api_keys: Vec<ApiKey>) -> RateLimiter {
+        RateLimiter::new(&RateLimitConfig { enabled: true, routes }, ApiKeys::new(api_keys))
+    }
+
+    #[test]
+    fn test_blocks_after_burst_and_refills_over_time() {
+        let limiter = limiter(vec![limit("*", 3, 60)], vec![]);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(limiter.check("ip:1.2.3.4", "/stats", start).is_ok());
+        }
+        let retry = limiter.check("ip:1.2.3.4", "/stats", start).unwrap_err();
+        assert_eq!(retry, Duration::from_secs(1));
+        // Other clients have their own bucket.
+        assert!(limiter.check("ip:5.6.7.8", "/stats", start).is_ok());
+
+        // 60/min refills one token per second, never above the burst.
+        let later = start + Duration::from_millis(1500);
+        assert!(limiter.check("ip:1.2.3.4", "/stats", later).is_ok());
+        assert!(limiter.check("ip:1.2.3.4", "/stats", later).is_err());
+
+        let much_later = later + Duration::from_secs(3600);
+        for _ in 0..3 {
+            assert!(limiter.check("ip:1.2.3.4", "/stats", much_later).is_ok());
+        }
+        assert!(limiter.check("ip:1.2.3.4", "/stats", much_later).is_err());
+    }
+
+    #[test]
+    fn test_routes_use_longest_matching_limit() {
+        let limiter = limiter(vec![limit("*", 100, 100), limit("/agents", 5, 5), limit("/agents/execute", 1, 1)], vec![]);
+        assert_eq!(limiter.limit_for("/agents/execute").unwrap().route, "/agents/execute");
+        assert_eq!(limiter.limit_for("/agents/list").unwrap().route, "/agents");
+        assert_eq!(limiter.limit_for("/agentsx").unwrap().route, "*");
+        assert_eq!(limiter.limit_for("/stats").unwrap().route, "*");
+
+        let now = Instant::now();
+        assert!(limiter.check("c", "/agents/execute", now).is_ok());
+        assert!(limiter.check("c", "/agents/execute", now).is_err());
+        assert!(limiter.check("c", "/stats", now).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_middleware_returns_429_with_retry_after() {
+        let keys = vec![
+            ApiKey { label: "ci".to_string(), key: "ci-secret".to_string() },
+            ApiKey { label: "ops".to_string(), key: "ops-secret".to_string() },
+        ];
+        let limiter = limiter(vec![limit("*", 100, 100), limit("/query", 2, 6)], keys);
+        let app = Router::new()
+            .route("/query", get(|| async { "ok" }))
+            .route("/health", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(limiter, limit_requests));
+        let send = |path: &str, key: &str| {
+            let request = Request::get(path).header("x-api-key", key).body(Body::empty()).unwrap();
+            app.clone().oneshot(request)
+        };
+
+        assert_eq!(send("/query", "ci-secret").await.unwrap().status(), StatusCode::OK);
+        assert_eq!(send("/query", "ci-secret").await.unwrap().status(), StatusCode::OK);
+        let limited = send("/query", "ci-secret").await.unwrap();
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(limited.headers()[header::RETRY_AFTER], "10");
+
+        assert_eq!(send("/query", "ops-secret").await.unwrap().status(), StatusCode::OK);
+        for _ in 0..5 {
+            assert_eq!(send("/health", "ci-secret").await.unwrap().status(), StatusCode::OK);
+        }
+    }
+}
@@ -5,14 +5,16 @@
 use std::sync::Arc;
 
 use axum::extract::DefaultBodyLimit;
+use axum::middleware;
 use axum::routing::{get, post};
 use axum::Router;
 use tower_http::cors::CorsLayer;
 use utoipa::OpenApi;
 use utoipa_scalar::{Scalar, Servable};
+use utoipa_swagger_ui::SwaggerUi;
 
 use crate::state::AppState;
-use crate::{anomaly_rules, api, catalog_api, live, rules};
+use crate::{anomaly_rules, api, auth, catalog_api, metrics, rate_limit, rules, ws};
 
 /// Build the complete application router with all routes and middleware.
 pub fn build_router(state: Arc<AppState>) -> Router {
@@ -20,20 +22,31 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/health", get(api::health))
         .route("/loading", get(api::loading))
         .route("/stats", get(api::stats))
+        .route("/metrics", get(metrics::metrics))
         .route("/graph/nodes", get(api::graph_nodes))
         .route("/graph/nodes/{id}", get(api::graph_node_by_id))
         .route("/graph/force", get(api::graph_force))
+        .route(
+            "/segments/import",
+            post(api::segments_import).layer(DefaultBodyLimit::max(1024 * 1024 * 1024)), // 1GB
+        )
         // /catalog routes handled by catalog_api::catalog_router()
         .route("/compute/pagerank", get(api::compute_pagerank))
         .route("/compute/communities", get(api::compute_communities))
         .route("/compute/degrees", get(api::compute_degrees))
+        .route("/compute/degree-anomalies", get(api::compute_degree_anomalies))
         .route("/compute/patterns", get(api::compute_patterns))
         .route("/compute/cooccurrence", get(api::compute_cooccurrence))
         .route("/compute/trends", get(api::compute_trends))
         .route("/compute/anomalies", get(api::compute_anomalies))
+        .route("/insights", get(api::list_insights))
+        .route("/insights/stream", get(api::insights_stream))
         .route("/scheduler/metrics", get(api::scheduler_metrics))
+        .route("/debug/config", get(api::debug_config))
         .route("/queue/status", get(api::queue_status))
         .route("/query", post(api::query))
+        .route("/query/stream", post(api::query_stream))
+        .route("/audit/queries", get(api::audit_queries))
         .route("/agents/list", get(api::agents_list))
         .route("/agents/execute", post(api::agents_execute))
         .route("/agents/chat", post(api::agents_chat))
@@ -68,6 +81,8 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         )
         .route("/sessions/{id}/execute", post(api::sessions_execute))
         .route("/sessions/{id}/stream", post(api::sessions_stream))
+        .route("/tool-approvals", get(api::tool_approvals_list))
+        .route("/tool-approvals/{call_id}", post(api::tool_approvals_decide))
         .route(
             "/connections",
             get(api::connections_list).post(api::connections_add),
@@ -82,6 +97,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
             "/connections/{id}/credentials",
             get(api::connections_credentials),
         )
+        .route("/connections/{id}/test", post(api::connections_test))
         // Bundeswehr fleet overview
         .route("/api/bundeswehr/overview", get(api::bundeswehr_overview))
         // Bundeswehr skill CRUD
@@ -144,7 +160,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
         .route("/api/ingestion/jobs/{id}", get(api::ingestion_jobs_get))
         // Villa layout engine
         .route("/api/villa/suggest", post(api::villa_suggest))
-        .route("/ws", get(live::ws_upgrade));
+        .route("/ws", get(ws::ws_upgrade));
 
     let app = app
         .route(
@@ -161,6 +177,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
             "/queue-connections/{id}/credentials",
             get(api::queue_connections_credentials),
         )
+        .route("/queue-connections/{id}/test", post(api::queue_connections_test))
         .route(
             "/athena-connections",
             get(api::athena_connections_list).post(api::athena_connections_add),
@@ -175,6 +192,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
             "/athena-connections/{id}/credentials",
             get(api::athena_connections_credentials),
         )
+        .route("/athena-connections/{id}/test", post(api::athena_connections_test))
         .route(
             "/athena-connections/{id}/query",
             post(api::athena_query_sse),
@@ -276,6 +294,7 @@ pub fn build_router(state: Arc<AppState>) -> Router {
             post(api::embedding::upload).layer(DefaultBodyLimit::max(1024 * 1024 * 1024)), // 1GB
         )
         .route("/embeddings/search", post(api::embedding::search))
+        .route("/embeddings/embed", post(api::embedding::embed))
         .route(
             "/embeddings/documents",
             get(api::embedding::list_documents),
@@ -285,10 +304,18 @@ pub fn build_router(state: Arc<AppState>) -> Router {
             axum::routing::delete(api::embedding::delete_document),
         );
 
+    // Auth sits inside CORS so preflight requests are answered without a key.
+    let api_keys = state.api_keys.clone();
+    let rate_limiter = state.rate_limiter.clone();
+    let http_metrics = state.http_metrics.clone();
     app.merge(anomaly_rules::anomaly_rules_router())
         .merge(rules::rules_router())
         .merge(catalog_api::catalog_router())
-        .layer(CorsLayer::permissive())
+        .route_layer(middleware::from_fn_with_state(http_metrics, metrics::track_requests))
         .with_state(state)
         .merge(Scalar::with_url("/docs", api::doc::ApiDoc::openapi()))
+        .merge(SwaggerUi::new("/swagger-ui").url("/openapi.json", api::doc::ApiDoc::openapi()))
+        .layer(middleware::from_fn_with_state(rate_limiter, rate_limit::limit_requests))
+        .layer(middleware::from_fn_with_state(api_keys, auth::require_api_key))
+        .layer(CorsLayer::permissive())
 }
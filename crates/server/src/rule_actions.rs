@@ -0,0 +1,1011 @@
+//! What happens after an anomaly rule matches: OpenSearch enrichment of the
+//! matches, then notification dispatch to the rule's channels, and for rules
+//! with an escalation policy, a timer that escalates the trigger unless it is
+//! acknowledged in time (see [`crate::rule_escalation`]).
+//!
+//! The rules crate only defines the [`OpenSearchQuery`] trait; the HTTP
+//! client lives here. Notifiers are built from the rule's channel config by
+//! a replaceable [`NotifierBuilder`], so tests can capture deliveries.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use stupid_core::config::OpenSearchConfig;
+use stupid_notify::templating::{AnomalyContext, EnrichmentContext, RuleContext, TemplateContext, TemplateRenderer};
+use stupid_notify::traits::{DispatchResult, Notification, Notifier, NotifyError};
+use stupid_notify::Dispatcher;
+use stupid_rules::enrichment::{EnrichmentEngine, EnrichmentError, EnrichmentResult, OpenSearchQuery, SearchResult};
+use stupid_rules::scheduler::EntityCooldowns;
+use stupid_rules::schema::{AnomalyRule, ChannelType, NotificationChannel, NotifyEvent};
+use stupid_rules::templates::RuleMatch;
+
+use crate::rule_escalation::{EscalationRecord, EscalationTracker};
+
+/// Matches notified per run, highest score first, so a broad rule can't
+/// flood its channels.
+const MAX_NOTIFIED_MATCHES: usize = 10;
+
+/// Matches enriched per run, highest score first. Lower-scored matches are
+/// neither enriched nor notified.
+const MAX_ENRICHED_MATCHES: usize = 50;
+
+/// Enrichment lookups in flight at once.
+const ENRICHMENT_CONCURRENCY: usize = 8;
+
+/// Lookup timeout when the rule's enrichment step doesn't set `timeout_ms`.
+const DEFAULT_ENRICHMENT_TIMEOUT_MS: u64 = 5000;
+
+/// Builds a notifier for one of a rule's notification channels.
+pub type NotifierBuilder =
+    Arc<dyn Fn(&NotificationChannel) -> Result<Box<dyn Notifier>, NotifyError> + Send + Sync>;
+
+/// Enrichment of a run's matches.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EnrichmentOutcome {
+    /// Matches that were enriched, at most the top 50 by score.
+    pub checked: usize,
+    /// Matches whose hit count was within the rule's bounds.
+    pub passed: usize,
+    pub matches: Vec<EnrichedMatch>,
+}
+
+/// Enrichment result for one match.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct EnrichedMatch {
+    pub entity_key: String,
+    pub hits: u64,
+    pub passed: bool,
+    pub query_time_ms: u64,
+}
+
+/// Delivery of one match's notification to one channel.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct DispatchOutcome {
+    pub channel: String,
+    pub entity_key: String,
+    pub success: bool,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl From<DispatchResult> for DispatchOutcome {
+    fn from(result: DispatchResult) -> Self {
+        Self {
+            channel: result.channel,
+            entity_key: result.entity_key,
+            success: result.success,
+            error: result.error,
+            duration_ms: result.duration_ms,
+        }
+    }
+}
+
+/// What a rule run's matches led to.
+#[derive(Debug, Clone)]
+pub struct RunOutcome {
+    /// Absent when the rule has no enrichment step or the run was volume-capped.
+    pub enrichment: Option<EnrichmentOutcome>,
+    pub notifications: Vec<DispatchOutcome>,
+    /// Matches exceeded the rule's `max_notify_entities`, so one summary
+    /// notification was sent instead of per-entity ones.
+    pub volume_capped: bool,
+    /// Pending escalation of this trigger, when the rule has an escalation
+    /// policy and notifications were sent.
+    pub escalation: Option<EscalationRecord>,
+    /// Matches skipped because their entity was notified within the rule's
+    /// entity-scoped cooldown.
+    pub suppressed: usize,
+}
+
+/// Enrichment engine, notifier construction and trigger escalations shared
+/// by rule runs.
+pub struct RuleActions {
+    enrichment: EnrichmentEngine,
+    build_notifier: NotifierBuilder,
+    escalations: Arc<EscalationTracker>,
+    /// Entities notified per rule, for rules with an entity-scoped cooldown.
+    entity_cooldowns: Mutex<HashMap<String, EntityCooldowns>>,
+}
+
+impl RuleActions {
+    pub fn new(enrichment: EnrichmentEngine, build_notifier: NotifierBuilder) -> Self {
+        Self {
+            enrichment,
+            build_notifier,
+            escalations: Arc::new(EscalationTracker::new()),
+            entity_cooldowns: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Escalation state of every trigger sent by a rule with an escalation policy.
+    pub fn escalations(&self) -> &EscalationTracker {
+        &self.escalations
+    }
+
+    /// Enrich against the configured OpenSearch cluster, if any, and build
+    /// real notifiers.
+    pub fn from_config(opensearch: &OpenSearchConfig) -> Self {
+        let enrichment = if opensearch.is_configured() {
+            EnrichmentEngine::new(Box::new(OpenSearchClient::new(opensearch)))
+        } else {
+            EnrichmentEngine::disabled()
+        };
+        Self::new(enrichment, Arc::new(build_notifier))
+    }
+
+    /// Enrich `matches` (sorted by score, descending) per the rule's
+    /// enrichment step, then notify the rule's trigger channels about the
+    /// top matches that passed.
+    ///
+    /// When there are more matches than the rule's `max_notify_entities`,
+    /// enrichment is skipped and each channel gets a single summary instead.
+    ///
+    /// If the rule has an escalation policy, the notifications carry a
+    /// `trigger_id` and the trigger escalates unless acknowledged in time.
+    ///
+    /// With an entity-scoped cooldown, entities notified within the cooldown
+    /// are dropped first, and the entities notified now start theirs.
+    pub async fn run(&self, rule: &AnomalyRule, matches: &[RuleMatch]) -> RunOutcome {
+        let trigger_id = rule.escalation.as_ref().map(|_| uuid::Uuid::new_v4().to_string());
+        let kept = self.filter_entity_cooldown(rule, matches);
+        let suppressed = matches.len() - kept.len();
+        let matches = kept.as_slice();
+
+        if let Some(cap) = rule.max_notify_entities.filter(|&cap| matches.len() > cap) {
+            let mut notification = volume_capped_notification(rule, matches.len(), cap);
+            if let Some(trigger_id) = &trigger_id {
+                notification.metadata.insert("trigger_id".to_string(), trigger_id.clone());
+            }
+            let channels = trigger_channels(rule);
+            let notifications = broadcast(&self.build_notifier, &rule.metadata.id, channels, &notification).await;
+            let entity_keys: Vec<String> = matches.iter().map(|m| m.entity_key.clone()).collect();
+            self.start_entity_cooldown(rule, &entity_keys);
+            return RunOutcome {
+                enrichment: None,
+                notifications,
+                volume_capped: true,
+                escalation: trigger_id.and_then(|id| self.escalate_unless_acknowledged(rule, id, entity_keys)),
+                suppressed,
+            };
+        }
+
+        let rule_id = &rule.metadata.id;
+        let enrich = rule.detection.enrich.as_ref().and_then(|e| e.opensearch.as_ref());
+
+        let mut notified = Vec::new();
+        let enrichment = match enrich {
+            Some(config) => {
+                // Lookups run concurrently; a lookup that overruns the rule's
+                // timeout fails open, like a failed query.
+                let timeout = Duration::from_millis(config.timeout_ms.unwrap_or(DEFAULT_ENRICHMENT_TIMEOUT_MS));
+                let lookups: Vec<_> = matches
+                    .iter()
+                    .take(MAX_ENRICHED_MATCHES)
+                    .enumerate()
+                    .map(|(i, rule_match)| async move {
+                        let lookup = self.enrichment.enrich(rule_id, config, rule_match);
+                        let result = tokio::time::timeout(timeout, lookup).await.unwrap_or_else(|_| {
+                            warn!(rule_id = %rule_id, entity = %rule_match.entity_key, "Enrichment lookup timed out, skipping");
+                            EnrichmentResult::skipped()
+                        });
+                        (i, rule_match, result)
+                    })
+                    .collect();
+                let mut results: Vec<_> =
+                    stream::iter(lookups).buffer_unordered(ENRICHMENT_CONCURRENCY).collect().await;
+                results.sort_by_key(|(i, _, _)| *i);
+
+                let mut enriched = Vec::with_capacity(results.len());
+                for (_, rule_match, result) in results {
+                    enriched.push(EnrichedMatch {
+                        entity_key: rule_match.entity_key.clone(),
+                        hits: result.hit_count,
+                        passed: result.passed,
+                        query_time_ms: result.query_time_ms,
+                    });
+                    if result.passed && notified.len() < MAX_NOTIFIED_MATCHES {
+                        notified.push((rule_match, Some(result)));
+                    }
+                }
+                Some(EnrichmentOutcome {
+                    checked: enriched.len(),
+                    passed: enriched.iter().filter(|m| m.passed).count(),
+                    matches: enriched,
+                })
+            }
+            None => {
+                notified.extend(matches.iter().take(MAX_NOTIFIED_MATCHES).map(|m| (m, None)));
+                None
+            }
+        };
+
+        if notified.is_empty() {
+            return RunOutcome {
+                enrichment,
+                notifications: Vec::new(),
+                volume_capped: false,
+                escalation: None,
+                suppressed,
+            };
+        }
+
+        // Channels that fail to build, or whose templates fail to render,
+        // are reported like failed deliveries.
+        let mut outcomes = Vec::new();
+        let (channels, broken) = build_channels(&self.build_notifier, rule_id, trigger_channels(rule));
+        for (channel, error) in broken {
+            outcomes.extend(notified.iter().map(|(m, _)| failed(channel, &m.entity_key, error.clone())));
+        }
+
+        let renderer = TemplateRenderer::new();
+        for (rule_match, result) in &notified {
+            let context = template_context(rule, rule_match, result.as_ref());
+            for (channel, dispatcher) in &channels {
+                let mut notification = match trigger_notification(&renderer, channel, rule_match, &context) {
+                    Ok(notification) => notification,
+                    Err(e) => {
+                        outcomes.push(failed(channel, &rule_match.entity_key, e.to_string()));
+                        continue;
+                    }
+                };
+                if let Some(trigger_id) = &trigger_id {
+                    notification.metadata.insert("trigger_id".to_string(), trigger_id.clone());
+                }
+                outcomes.extend(dispatcher.dispatch(rule_id, &notification).await.into_iter().map(DispatchOutcome::from));
+            }
+        }
+
+        let entity_keys: Vec<String> = notified.iter().map(|(m, _)| m.entity_key.clone()).collect();
+        self.start_entity_cooldown(rule, &entity_keys);
+        let escalation = trigger_id.and_then(|id| self.escalate_unless_acknowledged(rule, id, entity_keys));
+        RunOutcome { enrichment, notifications: outcomes, volume_capped: false, escalation, suppressed }
+    }
+
+    /// `matches` without the entities still in the rule's entity-scoped cooldown.
+    fn filter_entity_cooldown(&self, rule: &AnomalyRule, matches: &[RuleMatch]) -> Vec<RuleMatch> {
+        let Some(cooldown) = rule.schedule.entity_cooldown() else {
+            return matches.to_vec();
+        };
+        let cooldown = chrono::Duration::from_std(cooldown).unwrap_or(chrono::Duration::zero());
+        let mut cooldowns = self.entity_cooldowns.lock().expect("entity cooldown lock");
+        cooldowns
+            .entry(rule.metadata.id.clone())
+            .or_default()
+            .filter(matches.to_vec(), cooldown, chrono::Utc::now())
+    }
+
+    /// Start the entity-scoped cooldown of the entities just notified.
+    fn start_entity_cooldown(&self, rule: &AnomalyRule, entity_keys: &[String]) {
+        if rule.schedule.entity_cooldown().is_none() {
+            return;
+        }
+        let mut cooldowns = self.entity_cooldowns.lock().expect("entity cooldown lock");
+        cooldowns
+            .entry(rule.metadata.id.clone())
+            .or_default()
+            .record(entity_keys.iter().map(String::as_str), chrono::Utc::now());
+    }
+
+    /// Register `trigger_id` with the escalation tracker and start its timer:
+    /// once the rule's escalation window elapses without an acknowledgement,
+    /// the escalation channels are notified. `None` if the rule has no
+    /// escalation policy or its window doesn't parse.
+    fn escalate_unless_acknowledged(
+        &self,
+        rule: &AnomalyRule,
+        trigger_id: String,
+        entity_keys: Vec<String>,
+    ) -> Option<EscalationRecord> {
+        let window = rule.escalation.as_ref()?.window()?;
+        let record = self.escalations.register(&trigger_id, &rule.metadata.id, entity_keys, window);
+
+        let escalations = self.escalations.clone();
+        let build_notifier = self.build_notifier.clone();
+        let rule = rule.clone();
+        let pending = record.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(window).await;
+            if !escalations.begin_escalation(&trigger_id) {
+                return;
+            }
+            info!(rule_id = %rule.metadata.id, trigger_id = %trigger_id, "Escalating unacknowledged trigger");
+            let policy = rule.escalation.as_ref().expect("escalation policy checked above");
+            let notification = escalation_notification(&rule, &pending);
+            let outcomes =
+                broadcast(&build_notifier, &rule.metadata.id, policy.notifications.iter(), &notification).await;
+            escalations.record_escalations(&trigger_id, outcomes);
+        });
+        Some(record)
+    }
+}
+
+/// A rule's channels that fire on trigger.
+fn trigger_channels(rule: &AnomalyRule) -> impl Iterator<Item = &NotificationChannel> {
+    rule.notifications.iter().filter(|c| c.on.contains(&NotifyEvent::Trigger))
+}
+
+/// Channels with their dispatcher.
+type BuiltChannels<'a> = Vec<(&'a NotificationChannel, Dispatcher)>;
+/// Channels whose notifier failed to build, with the error.
+type BrokenChannels<'a> = Vec<(&'a NotificationChannel, String)>;
+
+/// One dispatcher per channel, since each renders its own templates, plus
+/// the channels whose notifier failed to build.
+fn build_channels<'a>(
+    build_notifier: &NotifierBuilder,
+    rule_id: &str,
+    channels: impl Iterator<Item = &'a NotificationChannel>,
+) -> (BuiltChannels<'a>, BrokenChannels<'a>) {
+    let mut built = Vec::new();
+    let mut broken = Vec::new();
+    for channel in channels {
+        match build_notifier(channel) {
+            Ok(notifier) => {
+                let dispatcher = Dispatcher::new(HashMap::from([(rule_id.to_string(), vec![notifier])]));
+                built.push((channel, dispatcher));
+            }
+            Err(e) => broken.push((channel, e.to_string())),
+        }
+    }
+    (built, broken)
+}
+
+/// Send the same `notification` to each of `channels`, without rendering
+/// channel templates.
+async fn broadcast<'a>(
+    build_notifier: &NotifierBuilder,
+    rule_id: &str,
+    channels: impl Iterator<Item = &'a NotificationChannel>,
+    notification: &Notification,
+) -> Vec<DispatchOutcome> {
+    let (channels, broken) = build_channels(build_notifier, rule_id, channels);
+    let mut outcomes: Vec<_> = broken.into_iter().map(|(channel, error)| failed(channel, "", error)).collect();
+    for (_, dispatcher) in &channels {
+        outcomes.extend(dispatcher.dispatch(rule_id, notification).await.into_iter().map(DispatchOutcome::from));
+    }
+    outcomes
+}
+
+/// A delivery that never reached the notifier.
+fn failed(channel: &NotificationChannel, entity_key: &str, error: String) -> DispatchOutcome {
+    DispatchOutcome {
+        channel: channel_name(&channel.channel).to_string(),
+        entity_key: entity_key.to_string(),
+        success: false,
+        error: Some(error),
+        duration_ms: 0,
+    }
+}
+
+/// Build the notifier for a rule channel from its config.
+pub fn build_notifier(channel: &NotificationChannel) -> Result<Box<dyn Notifier>, NotifyError> {
+    let missing = |field: &str| NotifyError::Config(format!("{} channel requires `{}`", channel_name(&channel.channel), field));
+    Ok(match channel.channel {
+        ChannelType::Webhook => Box::new(stupid_notify::webhook::WebhookNotifier::from_config(
+            channel.url.clone().ok_or_else(|| missing("url"))?,
+            channel.method.clone(),
+            channel.headers.clone(),
+            channel.body_template.clone(),
+            Arc::new(stupid_notify::templating::TemplateRenderer::new()),
+        )?),
+        ChannelType::Email => Box::new(stupid_notify::email::EmailNotifier::from_config(
+            channel.smtp_host.as_deref().ok_or_else(|| missing("smtp_host"))?,
+            channel.smtp_port,
+            channel.tls,
+            channel.from.as_deref().ok_or_else(|| missing("from"))?,
+            channel.to.as_deref().unwrap_or_default(),
+        )?),
+        ChannelType::Telegram => Box::new(stupid_notify::telegram::TelegramNotifier::from_config(
+            channel.bot_token.clone().ok_or_else(|| missing("bot_token"))?,
+            channel.chat_id.clone().ok_or_else(|| missing("chat_id"))?,
+            channel.parse_mode.clone(),
+        )?),
+    })
+}
+
+fn channel_name(channel: &ChannelType) -> &'static str {
+    match channel {
+        ChannelType::Webhook => "webhook",
+        ChannelType::Email => "email",
+        ChannelType::Telegram => "telegram",
+    }
+}
+
+/// Template context for a trigger notification, including the match's
+/// enrichment result when the rule has an enrichment step.
+fn template_context(rule: &AnomalyRule, rule_match: &RuleMatch, enrichment: Option<&EnrichmentResult>) -> TemplateContext {
+    TemplateContext {
+        rule: RuleContext {
+            id: rule.metadata.id.clone(),
+            name: rule.metadata.name.clone(),
+            description: rule.metadata.description.clone(),
+            tags: rule.metadata.tags.clone().unwrap_or_default(),
+        },
+        anomaly: AnomalyContext {
+            key: rule_match.entity_key.clone(),
+            score: rule_match.score,
+            classification: String::new(),
+            entity_type: rule_match.entity_type.clone(),
+            cluster_id: None,
+            signals: rule_match.signals.clone(),
+            features: rule_match.signals.iter().cloned().collect(),
+        },
+        enrichment: enrichment.map(|result| EnrichmentContext {
+            hit_count: result.hit_count,
+            sample_hits: result.sample_hits.clone(),
+            query_time_ms: result.query_time_ms,
+        }),
+        event: "trigger".to_string(),
+        now: chrono::Utc::now().to_rfc3339(),
+    }
+}
+
+/// Render a channel's `subject` and `template`, falling back to a summary
+/// subject and the match reason.
+fn trigger_notification(
+    renderer: &TemplateRenderer,
+    channel: &NotificationChannel,
+    rule_match: &RuleMatch,
+    context: &TemplateContext,
+) -> Result<Notification, NotifyError> {
+    let subject = match &channel.subject {
+        Some(template) => renderer.render(template, context)?,
+        None => format!("[{}] {} {}", context.rule.name, rule_match.entity_type, rule_match.entity_key),
+    };
+    let body = match &channel.template {
+        Some(template) => renderer.render(template, context)?,
+        None => rule_match.matched_reason.clone(),
+    };
+
+    let mut metadata = HashMap::from([
+        ("rule_id".to_string(), context.rule.id.clone()),
+        ("rule_name".to_string(), context.rule.name.clone()),
+        ("event".to_string(), context.event.clone()),
+        ("anomaly_key".to_string(), rule_match.entity_key.clone()),
+        ("entity_type".to_string(), rule_match.entity_type.clone()),
+        ("score".to_string(), format!("{:.4}", rule_match.score)),
+    ]);
+    if let Some(enrichment) = &context.enrichment {
+        metadata.insert("enrichment_hits".to_string(), enrichment.hit_count.to_string());
+    }
+    Ok(Notification { subject, body, metadata })
+}
+
+/// Summary sent instead of per-entity notifications when a run matches more
+/// than `max_notify_entities`. Channel templates are skipped since there is
+/// no single anomaly to render.
+fn volume_capped_notification(rule: &AnomalyRule, match_count: usize, cap: usize) -> Notification {
+    let metadata = HashMap::from([
+        ("rule_id".to_string(), rule.metadata.id.clone()),
+        ("rule_name".to_string(), rule.metadata.name.clone()),
+        ("event".to_string(), "trigger".to_string()),
+        ("match_count".to_string(), match_count.to_string()),
+        ("volume_capped".to_string(), "true".to_string()),
+    ]);
+    Notification {
+        subject: format!("[{}] {} matches, threshold exceeded", rule.metadata.name, match_count),
+        body: format!(
+            "{} entities matched, over the limit of {} per notification run; suppressing details.",
+            match_count, cap
+        ),
+        metadata,
+    }
+}
+
+/// Entities named in an escalation notification; the rest are counted.
+const ESCALATION_LISTED_ENTITIES: usize = 10;
+
+/// Sent to a rule's escalation channels when a trigger went unacknowledged
+/// for the policy's window. Names the first [`ESCALATION_LISTED_ENTITIES`]
+/// entities, since a volume-capped trigger can cover thousands.
+fn escalation_notification(rule: &AnomalyRule, record: &EscalationRecord) -> Notification {
+    let after = rule.escalation.as_ref().map(|p| p.after.as_str()).unwrap_or_default();
+    let metadata = HashMap::from([
+        ("rule_id".to_string(), rule.metadata.id.clone()),
+        ("rule_name".to_string(), rule.metadata.name.clone()),
+        ("event".to_string(), "escalation".to_string()),
+        ("trigger_id".to_string(), record.trigger_id.clone()),
+        ("triggered_at".to_string(), record.triggered_at.to_rfc3339()),
+        ("entity_count".to_string(), record.entity_keys.len().to_string()),
+    ]);
+    let mut entities = record.entity_keys.iter().take(ESCALATION_LISTED_ENTITIES).cloned().collect::<Vec<_>>().join(", ");
+    let unlisted = record.entity_keys.len().saturating_sub(ESCALATION_LISTED_ENTITIES);
+    if unlisted > 0 {
+        entities.push_str(&format!(" and {} more", unlisted));
+    }
+    Notification {
+        subject: format!("[{}] Escalated: unacknowledged for {}", rule.metadata.name, after),
+        body: format!(
+            "Trigger {} of rule '{}' was not acknowledged within {}. Entities: {}",
+            record.trigger_id,
+            rule.metadata.name,
+            after,
+            entities
+        ),
+        metadata,
+    }
+}
+
+/// Runs enrichment queries with `_search` on the configured index.
+struct OpenSearchClient {
+    url: String,
+    username: Option<String>,
+    password: Option<String>,
+    client: reqwest::Client,
+}
+
+impl OpenSearchClient {
+    fn new(config: &OpenSearchConfig) -> Self {
+        Self {
+            url: format!("{}/{}/_search", config.base_url(), config.index),
+            username: config.username.clone(),
+            password: config.password.clone(),
+            client: reqwest::Client::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl OpenSearchQuery for OpenSearchClient {
+    async fn search(&self, query_body: &serde_json::Value, timeout_ms: u64) -> Result<SearchResult, EnrichmentError> {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .timeout(std::time::Duration::from_millis(timeout_ms))
+            .json(&serde_json::json!({ "query": query_body, "size": 3, "track_total_hits": true }));
+        if let Some(username) = &self.username {
+            request = request.basic_auth(username, self.password.as_ref());
+        }
+
+        let response = request.send().await.map_err(|e| {
+            if e.is_timeout() {
+                EnrichmentError::Timeout(timeout_ms)
+            } else {
+                EnrichmentError::QueryFailed(e.to_string())
+            }
+        })?;
+        if !response.status().is_success() {
+            return Err(EnrichmentError::QueryFailed(format!("OpenSearch returned {}", response.status())));
+        }
+        let body: serde_json::Value = response.json().await.map_err(|e| EnrichmentError::QueryFailed(e.to_string()))?;
+
+        Ok(SearchResult {
+            total_hits: body["hits"]["total"]["value"].as_u64().unwrap_or(0),
+            sample_hits: body["hits"]["hits"].as_array().cloned().unwrap_or_default(),
+            took_ms: body["took"].as_u64().unwrap_or(0),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Mutex;
+
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use stupid_core::document::{Document, FieldValue};
+    use tower::ServiceExt;
+
+    /// OpenSearch stand-in returning a fixed hit count per member code.
+    struct MockSearch(HashMap<&'static str, u64>);
+
+    #[async_trait]
+    impl OpenSearchQuery for MockSearch {
+        async fn search(&self, query_body: &serde_json::Value, _timeout_ms: u64) -> Result<SearchResult, EnrichmentError> {
+            let member = query_body["term"]["memberCode"].as_str().unwrap_or_default();
+            Ok(SearchResult {
+                total_hits: self.0.get(member).copied().unwrap_or(0),
+                sample_hits: vec![serde_json::json!({"memberCode": member, "event": "Login"})],
+                took_ms: 1,
+            })
+        }
+    }
+
+    /// Records notifications instead of sending them, or fails every send.
+    struct CaptureNotifier {
+        name: &'static str,
+        sent: Arc<Mutex<Vec<Notification>>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl Notifier for CaptureNotifier {
+        async fn send(&self, notification: &Notification) -> Result<(), NotifyError> {
+            if self.fail {
+                return Err(NotifyError::Config("chat not found".to_string()));
+            }
+            self.sent.lock().unwrap().push(notification.clone());
+            Ok(())
+        }
+        fn channel_name(&self) -> &str {
+            self.name
+        }
+    }
+
+    const RULE: &str = r#"
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: busy-logins
+  name: Busy Logins
+  enabled: true
+schedule:
+  cron: '*/15 * * * *'
+detection:
+  template: threshold
+  params:
+    feature: login_count
+    operator: gte
+    value: 2
+  enrich:
+    opensearch:
+      query: {"term": {"memberCode": "{{ anomaly.key }}"}}
+      min_hits: 10
+notifications:
+- channel: webhook
+  url: http://hooks.invalid/alert
+  subject: '{{ rule.name }}: {{ anomaly.key }}'
+  template: >-
+    {{ anomaly.key }} confirmed by {{ enrichment.hit_count }} matching events:
+    {% for hit in enrichment.sample_hits %}{{ hit.event }}{% endfor %}
+- channel: telegram
+  bot_token: token
+  chat_id: '42'
+"#;
+
+    fn login(member: &str) -> Document {
+        Document {
+            id: uuid::Uuid::new_v4(),
+            timestamp: chrono::Utc::now(),
+            event_type: "Login".to_string(),
+            fields: HashMap::from([("memberCode".to_string(), FieldValue::Text(member.to_string()))]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_reports_enrichment_and_channel_outcomes() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let capture = sent.clone();
+        let build: NotifierBuilder = Arc::new(move |channel: &NotificationChannel| {
+            Ok(Box::new(CaptureNotifier {
+                name: channel_name(&channel.channel),
+                sent: capture.clone(),
+                fail: channel.channel == ChannelType::Telegram,
+            }) as Box<dyn Notifier>)
+        });
+        let search = MockSearch(HashMap::from([("M1", 25), ("M2", 3)]));
+        Arc::get_mut(&mut state).unwrap().rule_actions =
+            RuleActions::new(EnrichmentEngine::new(Box::new(search)), build);
+
+        {
+            let mut pipeline = state.pipeline.lock().unwrap();
+            for member in ["M1", "M1", "M1", "M2", "M2", "M3"] {
+                pipeline.features.update(&login(member));
+            }
+        }
+        std::fs::create_dir_all(tmp.path().join("rules")).unwrap();
+        state.rule_loader.write_rule(&serde_yaml::from_str(RULE).unwrap()).unwrap();
+
+        let app = crate::router::build_router(state);
+        let request = Request::post("/anomaly-rules/busy-logins/run").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let result: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert_eq!(result["matches_found"], 2);
+        let enrichment = &result["enrichment"];
+        assert_eq!((enrichment["checked"].as_u64(), enrichment["passed"].as_u64()), (Some(2), Some(1)));
+        let enriched: HashMap<&str, (u64, bool)> = enrichment["matches"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| (m["entity_key"].as_str().unwrap(), (m["hits"].as_u64().unwrap(), m["passed"].as_bool().unwrap())))
+            .collect();
+        assert_eq!(enriched, HashMap::from([("M1", (25, true)), ("M2", (3, false))]));
+
+        // Only M1 passed enrichment, so only M1 is notified, on both channels.
+        let notifications = result["notifications"].as_array().unwrap();
+        assert_eq!(notifications.len(), 2);
+        assert!(notifications.iter().all(|n| n["entity_key"] == "M1"));
+        let webhook = notifications.iter().find(|n| n["channel"] == "webhook").unwrap();
+        assert_eq!(webhook["success"], true);
+        let telegram = notifications.iter().find(|n| n["channel"] == "telegram").unwrap();
+        assert_eq!(telegram["success"], false);
+        assert!(telegram["error"].as_str().unwrap().contains("chat not found"));
+        assert_eq!(result["message"], "2 entities matched, 1 of 2 notifications delivered");
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].metadata["anomaly_key"], "M1");
+        assert_eq!(sent[0].metadata["enrichment_hits"], "25");
+        assert_eq!(sent[0].subject, "Busy Logins: M1");
+        assert_eq!(sent[0].body, "M1 confirmed by 25 matching events: Login");
+    }
+
+    const CAPPED_RULE: &str = r#"
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: any-login
+  name: Any Login
+  enabled: true
+schedule:
+  cron: '*/15 * * * *'
+detection:
+  template: threshold
+  params:
+    feature: login_count
+    operator: gte
+    value: 1
+notifications:
+- channel: webhook
+  url: http://hooks.invalid/alert
+max_notify_entities: 3
+"#;
+
+    fn capture_actions(sent: Arc<Mutex<Vec<Notification>>>) -> RuleActions {
+        let build: NotifierBuilder = Arc::new(move |channel: &NotificationChannel| {
+            Ok(Box::new(CaptureNotifier { name: channel_name(&channel.channel), sent: sent.clone(), fail: false })
+                as Box<dyn Notifier>)
+        });
+        RuleActions::new(EnrichmentEngine::disabled(), build)
+    }
+
+    fn rule_matches(count: usize) -> Vec<RuleMatch> {
+        (0..count)
+            .map(|i| RuleMatch {
+                entity_id: format!("M{i}"),
+                entity_key: format!("M{i}"),
+                entity_type: "Member".to_string(),
+                score: 1.0,
+                signals: vec![],
+                matched_reason: "login_count >= 1".to_string(),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_run_under_volume_cap_notifies_each_entity() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let actions = capture_actions(sent.clone());
+        let rule: AnomalyRule = serde_yaml::from_str(CAPPED_RULE).unwrap();
+
+        let outcome = actions.run(&rule, &rule_matches(3)).await;
+        assert!(!outcome.volume_capped);
+        assert_eq!(outcome.notifications.len(), 3);
+        let keys: Vec<_> = sent.lock().unwrap().iter().map(|n| n.metadata["anomaly_key"].clone()).collect();
+        assert_eq!(keys, ["M0", "M1", "M2"]);
+    }
+
+    #[derive(Default)]
+    struct InFlight {
+        current: std::sync::atomic::AtomicUsize,
+        max: std::sync::atomic::AtomicUsize,
+    }
+
+    #[tokio::test]
+    async fn test_entity_cooldown_suppresses_repeat_notifications() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let actions = capture_actions(sent.clone());
+        let rule: AnomalyRule = serde_yaml::from_str(&CAPPED_RULE.replace(
+            "  cron: '*/15 * * * *'",
+            "  cron: '*/15 * * * *'\n  cooldown: 30m\n  cooldown_scope: entity",
+        ))
+        .unwrap();
+
+        let outcome = actions.run(&rule, &rule_matches(2)).await;
+        assert_eq!((outcome.notifications.len(), outcome.suppressed), (2, 0));
+
+        // Inside the window the same entities are not notified again.
+        let outcome = actions.run(&rule, &rule_matches(2)).await;
+        assert_eq!((outcome.notifications.len(), outcome.suppressed), (0, 2));
+        assert_eq!(sent.lock().unwrap().len(), 2);
+
+        // A newly matched entity still is.
+        let outcome = actions.run(&rule, &rule_matches(3)).await;
+        assert_eq!((outcome.notifications.len(), outcome.suppressed), (1, 2));
+        assert_eq!(sent.lock().unwrap()[2].metadata["anomaly_key"], "M2");
+    }
+
+    /// OpenSearch stand-in that hangs on `M0` and tracks lookups in flight.
+    struct SlowSearch(Arc<InFlight>);
+
+    #[async_trait]
+    impl OpenSearchQuery for SlowSearch {
+        async fn search(&self, query_body: &serde_json::Value, _timeout_ms: u64) -> Result<SearchResult, EnrichmentError> {
+            use std::sync::atomic::Ordering;
+
+            let in_flight = self.0.current.fetch_add(1, Ordering::SeqCst) + 1;
+            self.0.max.fetch_max(in_flight, Ordering::SeqCst);
+            if query_body["term"]["memberCode"] == "M0" {
+                std::future::pending::<()>().await;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+            self.0.current.fetch_sub(1, Ordering::SeqCst);
+            Ok(SearchResult { total_hits: 1, sample_hits: vec![], took_ms: 20 })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_enrichment_is_capped_concurrent_and_times_out() {
+        let in_flight = Arc::new(InFlight::default());
+        let build: NotifierBuilder = Arc::new(|_: &NotificationChannel| Err(NotifyError::Config("unused".into())));
+        let actions = RuleActions::new(EnrichmentEngine::new(Box::new(SlowSearch(in_flight.clone()))), build);
+        let rule: AnomalyRule = serde_yaml::from_str(&RULE.replace(
+            "      min_hits: 10",
+            "      min_hits: 1\n      rate_limit: 1000\n      timeout_ms: 200",
+        ))
+        .unwrap();
+
+        let outcome = actions.run(&rule, &rule_matches(60)).await;
+        let enrichment = outcome.enrichment.unwrap();
+        assert_eq!(enrichment.checked, 50);
+        let keys: Vec<_> = enrichment.matches.iter().map(|m| m.entity_key.clone()).collect();
+        assert_eq!(keys, (0..50).map(|i| format!("M{i}")).collect::<Vec<_>>(), "kept in score order");
+        // The hanging lookup timed out and failed open.
+        assert!(enrichment.matches[0].passed);
+        assert_eq!(enrichment.matches[0].hits, 0);
+
+        let max_in_flight = in_flight.max.load(std::sync::atomic::Ordering::SeqCst);
+        assert!((2..=ENRICHMENT_CONCURRENCY).contains(&max_in_flight), "{max_in_flight} in flight");
+    }
+
+    #[tokio::test]
+    async fn test_run_over_volume_cap_sends_summary_and_audits() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        Arc::get_mut(&mut state).unwrap().rule_actions = capture_actions(sent.clone());
+
+        {
+            let mut pipeline = state.pipeline.lock().unwrap();
+            for member in ["M1", "M2", "M3", "M4", "M5"] {
+                pipeline.features.update(&login(member));
+            }
+        }
+        std::fs::create_dir_all(tmp.path().join("rules")).unwrap();
+        state.rule_loader.write_rule(&serde_yaml::from_str(CAPPED_RULE).unwrap()).unwrap();
+
+        let app = crate::router::build_router(state.clone());
+        let request = Request::post("/anomaly-rules/any-login/run").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let result: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+
+        assert_eq!(result["matches_found"], 5);
+        assert_eq!(result["volume_capped"], true);
+        assert_eq!(result["notifications"].as_array().unwrap().len(), 1);
+
+        let sent = sent.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].subject, "[Any Login] 5 matches, threshold exceeded");
+        assert_eq!(sent[0].metadata["volume_capped"], "true");
+        assert!(!sent[0].metadata.contains_key("anomaly_key"));
+
+        let params = stupid_rules::audit_log::LogQueryParams { level: None, phase: None, limit: None, since: None };
+        let logs = state.audit_log.query("any-login", &params);
+        let capped = logs.iter().find(|e| e.message.starts_with("Volume capped")).unwrap();
+        assert_eq!(capped.details.as_ref().unwrap()["max_notify_entities"], 3);
+    }
+
+    const ESCALATING_RULE: &str = r#"
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: critical-login
+  name: Critical Login
+  enabled: true
+schedule:
+  cron: '*/15 * * * *'
+detection:
+  template: threshold
+  params:
+    feature: login_count
+    operator: gte
+    value: 1
+notifications:
+- channel: webhook
+  url: http://hooks.invalid/slack
+escalation:
+  after: 1s
+  notifications:
+  - channel: webhook
+    url: http://hooks.invalid/pagerduty
+"#;
+
+    /// Longer than the rule's 1s escalation window.
+    const PAST_WINDOW: std::time::Duration = std::time::Duration::from_millis(1500);
+
+    fn events(sent: &Mutex<Vec<Notification>>) -> Vec<String> {
+        sent.lock().unwrap().iter().map(|n| n.metadata["event"].clone()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_trigger_acknowledged_in_time_does_not_escalate() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        Arc::get_mut(&mut state).unwrap().rule_actions = capture_actions(sent.clone());
+
+        state.pipeline.lock().unwrap().features.update(&login("M1"));
+        std::fs::create_dir_all(tmp.path().join("rules")).unwrap();
+        state.rule_loader.write_rule(&serde_yaml::from_str(ESCALATING_RULE).unwrap()).unwrap();
+        let app = crate::router::build_router(state.clone());
+
+        let request = Request::post("/anomaly-rules/critical-login/run").body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let result: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(result["escalation"]["state"], "pending");
+        let trigger_id = result["escalation"]["trigger_id"].as_str().unwrap().to_string();
+        assert_eq!(sent.lock().unwrap()[0].metadata["trigger_id"], trigger_id);
+
+        let ack = format!("/anomaly-rules/critical-login/triggers/{trigger_id}/ack");
+        let response = app.clone().oneshot(Request::post(&ack).body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let unknown = Request::post("/anomaly-rules/critical-login/triggers/nope/ack").body(Body::empty()).unwrap();
+        assert_eq!(app.clone().oneshot(unknown).await.unwrap().status(), StatusCode::NOT_FOUND);
+
+        tokio::time::sleep(PAST_WINDOW).await;
+        assert_eq!(events(&sent), ["trigger"]);
+
+        let request = Request::get("/anomaly-rules/critical-login/escalations").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        let records: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(records[0]["state"], "acknowledged");
+        assert!(records[0]["acknowledged_at"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_unacknowledged_trigger_escalates_after_window() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let actions = capture_actions(sent.clone());
+        let rule: AnomalyRule = serde_yaml::from_str(ESCALATING_RULE).unwrap();
+
+        let outcome = actions.run(&rule, &rule_matches(2)).await;
+        let trigger_id = outcome.escalation.unwrap().trigger_id;
+        assert_eq!(events(&sent), ["trigger", "trigger"]);
+
+        tokio::time::sleep(PAST_WINDOW).await;
+        assert_eq!(events(&sent), ["trigger", "trigger", "escalation"]);
+        let escalation = sent.lock().unwrap()[2].clone();
+        assert_eq!(escalation.metadata["trigger_id"], trigger_id);
+        assert_eq!(escalation.subject, "[Critical Login] Escalated: unacknowledged for 1s");
+        assert!(escalation.body.ends_with("Entities: M0, M1"));
+
+        let record = &actions.escalations().for_rule("critical-login")[0];
+        assert_eq!(record.state, crate::rule_escalation::EscalationState::Escalated);
+        assert_eq!(record.escalations.len(), 1);
+        assert!(record.escalations[0].success);
+    }
+
+    #[test]
+    fn test_escalation_notification_lists_first_entities() {
+        let rule: AnomalyRule = serde_yaml::from_str(ESCALATING_RULE).unwrap();
+        let keys: Vec<String> = (0..25).map(|i| format!("M{i}")).collect();
+        let record = crate::rule_escalation::EscalationTracker::new().register(
+            "t1",
+            "critical-login",
+            keys,
+            std::time::Duration::from_secs(1),
+        );
+
+        let notification = escalation_notification(&rule, &record);
+        assert!(notification.body.ends_with("Entities: M0, M1, M2, M3, M4, M5, M6, M7, M8, M9 and 15 more"));
+        assert_eq!(notification.metadata["entity_count"], "25");
+    }
+}
@@ -0,0 +1,184 @@
+//! Escalation of unacknowledged rule triggers.
+//!
+//! When a rule with an `escalation` policy notifies its trigger channels, the
+//! run registers a pending escalation under a trigger ID that every trigger
+//! notification carries in its `trigger_id` metadata. Acknowledging the
+//! trigger (`POST /anomaly-rules/{id}/triggers/{trigger_id}/ack`) before the
+//! policy's window elapses cancels it; otherwise the escalation channels are
+//! notified once the window is up.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::rule_actions::DispatchOutcome;
+
+/// Resolved (acknowledged or escalated) records kept per rule.
+const MAX_RESOLVED_PER_RULE: usize = 100;
+
+/// Where a trigger's escalation stands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum EscalationState {
+    /// Waiting for an acknowledgement.
+    Pending,
+    /// Acknowledged before the window elapsed; no escalation.
+    Acknowledged,
+    /// The window elapsed unacknowledged; escalation channels were notified.
+    Escalated,
+}
+
+/// One trigger's escalation.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct EscalationRecord {
+    pub trigger_id: String,
+    pub rule_id: String,
+    pub state: EscalationState,
+    /// Entities the trigger notified about.
+    pub entity_keys: Vec<String>,
+    #[schema(value_type = String)]
+    pub triggered_at: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub escalate_at: DateTime<Utc>,
+    #[schema(value_type = Option<String>)]
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// Delivery of the escalation notification to each escalation channel.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub escalations: Vec<DispatchOutcome>,
+}
+
+/// In-memory escalation state for every trigger of every rule.
+#[derive(Default)]
+pub struct EscalationTracker {
+    records: Mutex<HashMap<String, EscalationRecord>>,
+}
+
+impl EscalationTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the escalation window for `trigger_id`.
+    pub(crate) fn register(
+        &self,
+        trigger_id: &str,
+        rule_id: &str,
+        entity_keys: Vec<String>,
+        window: std::time::Duration,
+    ) -> EscalationRecord {
+        let now = Utc::now();
+        let record = EscalationRecord {
+            trigger_id: trigger_id.to_string(),
+            rule_id: rule_id.to_string(),
+            state: EscalationState::Pending,
+            entity_keys,
+            triggered_at: now,
+            // A window too long to represent never escalates in practice.
+            escalate_at: chrono::Duration::from_std(window)
+                .ok()
+                .and_then(|window| now.checked_add_signed(window))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+            acknowledged_at: None,
+            escalations: Vec::new(),
+        };
+        let mut records = self.records.lock().expect("escalations lock poisoned");
+        prune_resolved(&mut records, rule_id);
+        records.insert(record.trigger_id.clone(), record.clone());
+        record
+    }
+
+    /// Acknowledge a trigger of `rule_id`, cancelling its escalation if it
+    /// is still pending. `None` if the trigger is unknown.
+    pub fn acknowledge(&self, rule_id: &str, trigger_id: &str) -> Option<EscalationRecord> {
+        let mut records = self.records.lock().expect("escalations lock poisoned");
+        let record = records.get_mut(trigger_id).filter(|r| r.rule_id == rule_id)?;
+        if record.acknowledged_at.is_none() {
+            record.acknowledged_at = Some(Utc::now());
+        }
+        if record.state == EscalationState::Pending {
+            record.state = EscalationState::Acknowledged;
+        }
+        Some(record.clone())
+    }
+
+    /// Move a pending trigger to escalated; `false` if it was acknowledged.
+    pub(crate) fn begin_escalation(&self, trigger_id: &str) -> bool {
+        let mut records = self.records.lock().expect("escalations lock poisoned");
+        match records.get_mut(trigger_id) {
+            Some(record) if record.state == EscalationState::Pending => {
+                record.state = EscalationState::Escalated;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record the delivery outcomes of an escalated trigger.
+    pub(crate) fn record_escalations(&self, trigger_id: &str, outcomes: Vec<DispatchOutcome>) {
+        let mut records = self.records.lock().expect("escalations lock poisoned");
+        if let Some(record) = records.get_mut(trigger_id) {
+            record.escalations = outcomes;
+        }
+    }
+
+    /// Escalation records for `rule_id`, newest first.
+    pub fn for_rule(&self, rule_id: &str) -> Vec<EscalationRecord> {
+        let records = self.records.lock().expect("escalations lock poisoned");
+        let mut matching: Vec<_> = records.values().filter(|r| r.rule_id == rule_id).cloned().collect();
+        matching.sort_by_key(|r| std::cmp::Reverse(r.triggered_at));
+        matching
+    }
+}
+
+/// Drop the oldest resolved records of `rule_id` beyond the retention cap.
+fn prune_resolved(records: &mut HashMap<String, EscalationRecord>, rule_id: &str) {
+    let mut resolved: Vec<(DateTime<Utc>, String)> = records
+        .values()
+        .filter(|r| r.rule_id == rule_id && r.state != EscalationState::Pending)
+        .map(|r| (r.triggered_at, r.trigger_id.clone()))
+        .collect();
+    if resolved.len() < MAX_RESOLVED_PER_RULE {
+        return;
+    }
+    resolved.sort();
+    for (_, trigger_id) in resolved.iter().take(resolved.len() + 1 - MAX_RESOLVED_PER_RULE) {
+        records.remove(trigger_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn acknowledged_trigger_cannot_escalate() {
+        let tracker = EscalationTracker::new();
+        tracker.register("t1", "rule-a", vec!["M1".to_string()], Duration::from_secs(60));
+
+        assert!(tracker.acknowledge("rule-b", "t1").is_none(), "trigger belongs to another rule");
+        let record = tracker.acknowledge("rule-a", "t1").unwrap();
+        assert_eq!(record.state, EscalationState::Acknowledged);
+        assert!(!tracker.begin_escalation("t1"));
+
+        tracker.register("t2", "rule-a", vec![], Duration::from_secs(60));
+        assert!(tracker.begin_escalation("t2"));
+        assert!(!tracker.begin_escalation("t2"), "escalates only once");
+        // A late acknowledgement is recorded without undoing the escalation.
+        let late = tracker.acknowledge("rule-a", "t2").unwrap();
+        assert_eq!(late.state, EscalationState::Escalated);
+        assert!(late.acknowledged_at.is_some());
+        assert_eq!(tracker.for_rule("rule-a").len(), 2);
+    }
+
+    #[test]
+    fn out_of_range_window_does_not_overflow() {
+        let tracker = EscalationTracker::new();
+        let record = tracker.register("t1", "rule-a", vec![], Duration::MAX);
+        assert_eq!(record.escalate_at, DateTime::<Utc>::MAX_UTC);
+        let record = tracker.register("t2", "rule-a", vec![], Duration::from_secs(i64::MAX as u64 / 1000));
+        assert_eq!(record.escalate_at, DateTime::<Utc>::MAX_UTC);
+    }
+}
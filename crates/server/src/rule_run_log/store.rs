@@ -0,0 +1,132 @@
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use chrono::{DateTime, Utc};
+
+use super::types::{RuleRunRecord, RunLogPage, RunLogParams};
+
+/// Per-rule structured run log with file-backed persistence.
+///
+/// In-memory ring buffer per rule (500 runs FIFO) with immediate JSON
+/// persistence to `{data_dir}/rule-runs/{rule_id}.json`, so runs survive
+/// restarts and can be inspected after the fact.
+pub struct RuleRunLog {
+    dir: PathBuf,
+    runs: RwLock<HashMap<String, VecDeque<RuleRunRecord>>>,
+    pub(crate) max_runs_per_rule: usize,
+}
+
+impl RuleRunLog {
+    /// Create a run log storing its files under `data_dir`.
+    pub fn new(data_dir: &Path) -> Self {
+        Self {
+            dir: data_dir.join("rule-runs"),
+            runs: RwLock::new(HashMap::new()),
+            max_runs_per_rule: 500,
+        }
+    }
+
+    pub(crate) fn log_path(&self, rule_id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", rule_id))
+    }
+
+    /// Load a rule's runs from disk (lazy, on first access).
+    fn ensure_loaded(&self, rule_id: &str) {
+        if self.runs.read().expect("run_log lock poisoned").contains_key(rule_id) {
+            return;
+        }
+
+        let path = self.log_path(rule_id);
+        let loaded: VecDeque<RuleRunRecord> = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                tracing::warn!("Failed to parse rule run log {}: {}", path.display(), e);
+                VecDeque::new()
+            }),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => VecDeque::new(),
+            Err(e) => {
+                tracing::warn!("Failed to read rule run log {}: {}", path.display(), e);
+                VecDeque::new()
+            }
+        };
+
+        self.runs
+            .write()
+            .expect("run_log lock poisoned")
+            .entry(rule_id.to_string())
+            .or_insert(loaded);
+    }
+
+    /// Persist a rule's runs to disk.
+    fn persist(&self, rule_id: &str) {
+        let runs = self.runs.read().expect("run_log lock poisoned");
+        let Some(deque) = runs.get(rule_id) else {
+            return;
+        };
+        let path = self.log_path(rule_id);
+        let result = std::fs::create_dir_all(&self.dir)
+            .map_err(|e| e.to_string())
+            .and_then(|()| serde_json::to_string(deque).map_err(|e| e.to_string()))
+            .and_then(|json| std::fs::write(&path, json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to persist rule run log to {}: {}", path.display(), e);
+        }
+    }
+
+    /// Append a finished run and persist the rule's log immediately.
+    pub fn append(&self, record: RuleRunRecord) {
+        let rule_id = record.rule_id.clone();
+        self.ensure_loaded(&rule_id);
+
+        {
+            let mut runs = self.runs.write().expect("run_log lock poisoned");
+            let deque = runs.entry(rule_id.clone()).or_default();
+            deque.push_back(record);
+            while deque.len() > self.max_runs_per_rule {
+                deque.pop_front();
+            }
+        }
+
+        self.persist(&rule_id);
+    }
+
+    /// Query a rule's runs with filters, newest first.
+    pub fn query(&self, rule_id: &str, params: &RunLogParams) -> RunLogPage {
+        self.ensure_loaded(rule_id);
+
+        let since: Option<DateTime<Utc>> = params.since.as_ref().and_then(|s| s.parse().ok());
+        let until: Option<DateTime<Utc>> = params.until.as_ref().and_then(|s| s.parse().ok());
+        let offset = params.offset.unwrap_or(0) as usize;
+        let limit = params.limit.unwrap_or(100) as usize;
+
+        let runs = self.runs.read().expect("run_log lock poisoned");
+        let matching: Vec<&RuleRunRecord> = runs
+            .get(rule_id)
+            .into_iter()
+            .flatten()
+            .rev() // newest first
+            .filter(|r| since.is_none_or(|s| r.started_at >= s))
+            .filter(|r| until.is_none_or(|u| r.started_at < u))
+            .filter(|r| params.matched.is_none_or(|m| (r.matches_found > 0) == m))
+            .filter(|r| !params.errors_only.unwrap_or(false) || r.error.is_some())
+            .collect();
+
+        RunLogPage {
+            rule_id: rule_id.to_string(),
+            total: matching.len(),
+            offset,
+            runs: matching.into_iter().skip(offset).take(limit).cloned().collect(),
+        }
+    }
+
+    /// Delete a rule's run log (in-memory + disk file).
+    pub fn clear(&self, rule_id: &str) {
+        self.runs.write().expect("run_log lock poisoned").remove(rule_id);
+        let path = self.log_path(rule_id);
+        if let Err(e) = std::fs::remove_file(&path) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                tracing::warn!("Failed to remove rule run log {}: {}", path.display(), e);
+            }
+        }
+    }
+}
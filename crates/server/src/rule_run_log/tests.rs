@@ -0,0 +1,105 @@
+use chrono::{Duration, TimeZone, Utc};
+
+use super::*;
+
+fn run(rule_id: &str, minute: u32, matches_found: usize) -> RuleRunRecord {
+    let started_at = Utc.with_ymd_and_hms(2026, 3, 1, 12, minute, 0).unwrap();
+    let mut record = RuleRunRecord::new(rule_id, RunTrigger::Scheduled, started_at, 5);
+    record.finished_at = started_at + Duration::milliseconds(5);
+    record.matches_found = matches_found;
+    record
+}
+
+fn params() -> RunLogParams {
+    RunLogParams::default()
+}
+
+#[test]
+fn test_append_and_query_newest_first() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = RuleRunLog::new(dir.path());
+
+    log.append(run("rule-a", 0, 2));
+    log.append(run("rule-a", 1, 0));
+    log.append(run("rule-b", 2, 7));
+
+    let page = log.query("rule-a", &params());
+    assert_eq!(page.total, 2);
+    let minutes: Vec<_> = page.runs.iter().map(|r| r.started_at.format("%M").to_string()).collect();
+    assert_eq!(minutes, ["01", "00"]);
+    assert!(page.runs.iter().all(|r| r.rule_id == "rule-a"));
+    assert_eq!(log.query("rule-c", &params()).total, 0);
+}
+
+#[test]
+fn test_query_filters_and_paginates() {
+    let dir = tempfile::tempdir().unwrap();
+    let log = RuleRunLog::new(dir.path());
+    for minute in 0..10 {
+        let mut record = run("rule-a", minute, (minute % 2) as usize);
+        if minute == 4 {
+            record.error = Some("Evaluation failed".into());
+        }
+        log.append(record);
+    }
+
+    // Time range: [12:02, 12:07)
+    let ranged = log.query(
+        "rule-a",
+        &RunLogParams {
+            since: Some("2026-03-01T12:02:00Z".into()),
+            until: Some("2026-03-01T12:07:00Z".into()),
+            ..params()
+        },
+    );
+    assert_eq!(ranged.total, 5);
+    assert_eq!(ranged.runs.first().unwrap().started_at.format("%M").to_string(), "06");
+    assert_eq!(ranged.runs.last().unwrap().started_at.format("%M").to_string(), "02");
+
+    // Runs that matched nothing, second page of two.
+    let quiet = log.query(
+        "rule-a",
+        &RunLogParams { matched: Some(false), offset: Some(2), limit: Some(2), ..params() },
+    );
+    assert_eq!(quiet.total, 5);
+    assert_eq!(quiet.offset, 2);
+    let minutes: Vec<_> = quiet.runs.iter().map(|r| r.started_at.format("%M").to_string()).collect();
+    assert_eq!(minutes, ["04", "02"]);
+
+    let errors = log.query("rule-a", &RunLogParams { errors_only: Some(true), ..params() });
+    assert_eq!(errors.total, 1);
+    assert_eq!(errors.runs[0].error.as_deref(), Some("Evaluation failed"));
+}
+
+#[test]
+fn test_runs_persist_across_instances() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut record = run("rule-a", 0, 3);
+    record.volume_capped = true;
+    let run_id = record.run_id.clone();
+    RuleRunLog::new(dir.path()).append(record);
+
+    let reloaded = RuleRunLog::new(dir.path()).query("rule-a", &params());
+    assert_eq!(reloaded.total, 1);
+    assert_eq!(reloaded.runs[0].run_id, run_id);
+    assert_eq!(reloaded.runs[0].matches_found, 3);
+    assert!(reloaded.runs[0].volume_capped);
+}
+
+#[test]
+fn test_fifo_eviction_and_clear() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut log = RuleRunLog::new(dir.path());
+    log.max_runs_per_rule = 3;
+    for minute in 0..5 {
+        log.append(run("rule-a", minute, 1));
+    }
+
+    let page = log.query("rule-a", &params());
+    assert_eq!(page.total, 3);
+    assert_eq!(page.runs.last().unwrap().started_at.format("%M").to_string(), "02");
+
+    log.clear("rule-a");
+    assert!(!log.log_path("rule-a").exists());
+    assert_eq!(log.query("rule-a", &params()).total, 0);
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::rule_actions::{DispatchOutcome, EnrichmentOutcome};
+
+// ---------------------------------------------------------------------------
+// Enums
+// ---------------------------------------------------------------------------
+
+/// What started a rule run.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RunTrigger {
+    /// The background runner found the rule due on its cron schedule.
+    Scheduled,
+    /// `POST /anomaly-rules/{id}/run`.
+    Manual,
+}
+
+// ---------------------------------------------------------------------------
+// Core log record
+// ---------------------------------------------------------------------------
+
+/// Structured record of one rule evaluation and what it led to.
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct RuleRunRecord {
+    pub run_id: String,
+    pub rule_id: String,
+    pub trigger: RunTrigger,
+    #[schema(value_type = String)]
+    pub started_at: DateTime<Utc>,
+    #[schema(value_type = String)]
+    pub finished_at: DateTime<Utc>,
+    pub evaluation_ms: u64,
+    /// Entities the rule matched.
+    pub matches_found: usize,
+    /// Matches dropped by an entity-scoped cooldown before being recorded.
+    #[serde(default)]
+    pub matches_suppressed: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enrichment: Option<EnrichmentOutcome>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notifications: Vec<DispatchOutcome>,
+    #[serde(default)]
+    pub volume_capped: bool,
+    /// Set when evaluation failed; the run produced no matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RuleRunRecord {
+    /// A record for a run of `rule_id` that started at `started_at` and
+    /// finishes now, with no matches or actions yet.
+    pub fn new(rule_id: &str, trigger: RunTrigger, started_at: DateTime<Utc>, evaluation_ms: u64) -> Self {
+        Self {
+            run_id: uuid::Uuid::new_v4().to_string(),
+            rule_id: rule_id.to_string(),
+            trigger,
+            started_at,
+            finished_at: Utc::now(),
+            evaluation_ms,
+            matches_found: 0,
+            matches_suppressed: 0,
+            enrichment: None,
+            notifications: Vec::new(),
+            volume_capped: false,
+            error: None,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// REST query parameters
+// ---------------------------------------------------------------------------
+
+/// Query-string parameters for the `GET /anomaly-rules/{id}/runs` endpoint.
+#[derive(Debug, Default, Deserialize, utoipa::IntoParams)]
+pub struct RunLogParams {
+    /// ISO 8601 lower bound on `started_at` (inclusive).
+    pub since: Option<String>,
+    /// ISO 8601 upper bound on `started_at` (exclusive).
+    pub until: Option<String>,
+    /// `true` for runs that matched at least one entity, `false` for runs
+    /// that matched none.
+    pub matched: Option<bool>,
+    /// Only runs whose evaluation failed.
+    pub errors_only: Option<bool>,
+    /// Filtered runs to skip, newest first (default 0).
+    pub offset: Option<u32>,
+    /// Maximum runs to return (default 100).
+    pub limit: Option<u32>,
+}
+
+// ---------------------------------------------------------------------------
+// Response types
+// ---------------------------------------------------------------------------
+
+/// One page of a rule's run log.
+#[derive(Debug, Serialize, utoipa::ToSchema)]
+pub struct RunLogPage {
+    pub rule_id: String,
+    /// Runs matching the filters, before pagination.
+    pub total: usize,
+    pub offset: usize,
+    /// Runs on this page, newest first.
+    pub runs: Vec<RuleRunRecord>,
+}
@@ -5,7 +5,8 @@
 //! knowledge state according to their cron schedules.
 
 use std::collections::{HashMap, VecDeque};
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
 
 use chrono::Utc;
 use tracing::{debug, info, warn};
@@ -15,9 +16,10 @@ use stupid_rules::evaluator::{RuleEvaluator, SignalScores};
 use stupid_rules::scheduler::RuleScheduler;
 
 use crate::anomaly_rules::MatchSummary;
-use stupid_rules::templates::{ClusterStats, EntityData};
+use stupid_rules::templates::{ClusterStats, EntityData, FirstSeenTracker};
 
 use crate::anomaly_rules::TriggerEntry;
+use crate::rule_run_log::{RuleRunRecord, RunTrigger};
 use crate::state::AppState;
 
 /// Interval between scheduler ticks (seconds).
@@ -25,6 +27,35 @@ const TICK_INTERVAL_SECS: u64 = 60;
 /// Maximum trigger history entries per rule.
 const MAX_HISTORY_ENTRIES: usize = 500;
 
+/// Entity first-seen tracker shared by every rule evaluation, persisted to
+/// `{data_dir}/entity-first-seen.json` so `new_entity` rules survive restarts.
+pub struct EntityFirstSeen {
+    path: PathBuf,
+    tracker: Mutex<FirstSeenTracker>,
+}
+
+impl EntityFirstSeen {
+    pub fn new(data_dir: &Path) -> Self {
+        let path = data_dir.join("entity-first-seen.json");
+        let tracker = FirstSeenTracker::load(&path).unwrap_or_else(|e| {
+            warn!(path = %path.display(), error = %e, "Failed to load entity first-seen tracker, starting fresh");
+            FirstSeenTracker::new()
+        });
+        Self { path, tracker: Mutex::new(tracker) }
+    }
+
+    /// Stamp `entities` with their first-seen times, persisting the tracker
+    /// when new entities were recorded.
+    fn observe(&self, entities: &mut HashMap<String, EntityData>) {
+        let mut tracker = self.tracker.lock().expect("first-seen lock poisoned");
+        if tracker.observe(entities, Utc::now()) > 0 {
+            if let Err(e) = tracker.save(&self.path) {
+                warn!(path = %self.path.display(), error = %e, "Failed to persist entity first-seen tracker");
+            }
+        }
+    }
+}
+
 /// Build `EntityData` and `SignalScores` maps from the compute pipeline state.
 ///
 /// This bridges the gap between `stupid_compute`'s internal representation
@@ -70,6 +101,7 @@ pub fn build_evaluation_context(
                 features,
                 score,
                 cluster_id: cluster_id.map(|c| c as usize),
+                first_seen: None,
             },
         );
 
@@ -104,25 +136,30 @@ pub fn build_evaluation_context(
     drop(knowledge);
     drop(pipeline);
 
+    state.entity_first_seen.observe(&mut entities);
+
     (entities, cluster_stats, signal_scores_map)
 }
 
 /// Main rule evaluation loop. Spawned as a tokio task.
 ///
-/// 1. Waits for data loading to complete (polls `LoadingState`).
+/// 1. Waits for data loading and initial compute to complete (polls `LoadingState`).
 /// 2. On each 60s tick, syncs rules, finds due rules, evaluates them.
-/// 3. Records trigger history and audit log entries.
+/// 3. Evaluates each due rule's shadow candidates on the same data and logs
+///    the match diff to the live rule's audit log.
+/// 4. Drops matches still in an entity-scoped cooldown, then records trigger
+///    history, audit log entries, and a run log record per evaluation.
 pub async fn run_rule_loop(state: Arc<AppState>) {
     info!("Rule auto-runner started, waiting for data loading...");
 
-    // Wait until data loading completes or fails (max 5 minutes).
+    // Wait until compute results are available or loading fails (max 5 minutes).
     // Rules should still run even without loaded data — they'll just get
     // 0 matches against an empty pipeline, but the scheduler remains active
     // so the sidebar shows trigger history.
     let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(300);
     loop {
         let status = state.loading.to_status().await;
-        if status.is_ready {
+        if status.compute_ready {
             break;
         }
         if status.phase == "failed" {
@@ -204,26 +241,17 @@ pub async fn run_rule_loop(state: Arc<AppState>) {
                     "Rule due for evaluation",
                 );
 
+                let started_at = Utc::now();
                 let start = std::time::Instant::now();
                 match RuleEvaluator::evaluate(rule, &entities, &cluster_stats, &signal_scores) {
                     Ok(mut matches) => {
                         let evaluation_ms = start.elapsed().as_millis() as u64;
                         let matches_found = matches.len();
 
-                        // Sort by score descending and keep top 50 for history.
+                        // Sort by score descending; history keeps the top 50.
                         matches.sort_by(|a, b| {
                             b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal)
                         });
-                        let summaries: Vec<MatchSummary> = matches
-                            .iter()
-                            .take(50)
-                            .map(|m| MatchSummary {
-                                entity_key: m.entity_key.clone(),
-                                entity_type: m.entity_type.clone(),
-                                score: m.score,
-                                reason: m.matched_reason.clone(),
-                            })
-                            .collect();
 
                         state_clone.audit_log.log(
                             rule_id,
@@ -235,7 +263,17 @@ pub async fn run_rule_loop(state: Arc<AppState>) {
                             ),
                         );
 
-                        results.push((rule_id.clone(), matches_found, evaluation_ms, summaries));
+                        stupid_rules::shadow::evaluate_shadows(
+                            &state_clone.audit_log,
+                            rule,
+                            &matches,
+                            &guard,
+                            &entities,
+                            &cluster_stats,
+                            &signal_scores,
+                        );
+
+                        results.push((rule_id.clone(), matches, evaluation_ms, started_at));
                     }
                     Err(e) => {
                         state_clone.audit_log.log(
@@ -245,22 +283,54 @@ pub async fn run_rule_loop(state: Arc<AppState>) {
                             format!("Evaluation failed: {}", e),
                         );
                         warn!(rule_id = %rule_id, error = %e, "Rule evaluation failed");
+
+                        let evaluation_ms = start.elapsed().as_millis() as u64;
+                        let mut record =
+                            RuleRunRecord::new(rule_id, RunTrigger::Scheduled, started_at, evaluation_ms);
+                        record.error = Some(e.to_string());
+                        state_clone.rule_run_log.append(record);
                     }
                 }
             }
 
-            // Record trigger history.
-            {
-                let mut history = state_clone
-                    .trigger_history
-                    .write()
-                    .expect("trigger_history lock");
-                for (rule_id, matches_found, evaluation_ms, match_summaries) in &results {
+            results
+        })
+        .await;
+
+        match eval_result {
+            Ok(results) => {
+                let mut history = state.trigger_history.write().expect("trigger_history lock");
+                for (rule_id, matches, evaluation_ms, started_at) in results {
+                    let matches_found = matches.len();
+                    // Entity-scoped cooldowns drop entities that were recorded recently.
+                    let matches = scheduler.filter_entity_cooldown(&rule_id, matches, now);
+                    if matches.len() < matches_found {
+                        state.audit_log.log(
+                            &rule_id,
+                            LogLevel::Info,
+                            ExecutionPhase::ScheduleCheck,
+                            format!(
+                                "{} of {} matched entities suppressed by entity cooldown",
+                                matches_found - matches.len(),
+                                matches_found
+                            ),
+                        );
+                    }
+
                     let entry = TriggerEntry {
                         timestamp: Utc::now().to_rfc3339(),
-                        matches_found: *matches_found,
-                        evaluation_ms: *evaluation_ms,
-                        matches: match_summaries.clone(),
+                        matches_found: matches.len(),
+                        evaluation_ms,
+                        matches: matches
+                            .iter()
+                            .take(50)
+                            .map(|m| MatchSummary {
+                                entity_key: m.entity_key.clone(),
+                                entity_type: m.entity_type.clone(),
+                                score: m.score,
+                                reason: m.matched_reason.clone(),
+                            })
+                            .collect(),
                     };
                     let deque = history
                         .entry(rule_id.clone())
@@ -269,17 +339,14 @@ pub async fn run_rule_loop(state: Arc<AppState>) {
                     while deque.len() > MAX_HISTORY_ENTRIES {
                         deque.pop_front();
                     }
-                }
-            }
 
-            results
-        })
-        .await;
+                    let mut record =
+                        RuleRunRecord::new(&rule_id, RunTrigger::Scheduled, started_at, evaluation_ms);
+                    record.matches_found = matches_found;
+                    record.matches_suppressed = matches_found - matches.len();
+                    state.rule_run_log.append(record);
 
-        match eval_result {
-            Ok(results) => {
-                for (rule_id, matches_found, evaluation_ms, _) in &results {
-                    scheduler.record_trigger(rule_id);
+                    scheduler.record_trigger(&rule_id);
                     info!(
                         rule_id = %rule_id,
                         matches = matches_found,
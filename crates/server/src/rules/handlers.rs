@@ -8,6 +8,7 @@ use axum::response::IntoResponse;
 use axum::Json;
 use tracing::warn;
 
+use stupid_rules::loader::{preprocess, RuleError};
 use stupid_rules::schema::{RuleEnvelope, RuleKind};
 
 use crate::state::AppState;
@@ -138,6 +139,16 @@ pub(crate) async fn get_rule_yaml(
 
 // ── Create / Update / Delete ────────────────────────────────────────
 
+/// Preprocess a submitted rule the way the loader will, so merge keys apply
+/// and a `${VAR}` the loader couldn't resolve is rejected up front.
+/// Placeholders are kept as written.
+fn parse_submitted(body: &str) -> Result<RuleEnvelope, (StatusCode, String)> {
+    preprocess::parse_submitted_rule(body).map_err(|e| match e {
+        RuleError::Parse(e) => (StatusCode::BAD_REQUEST, format!("Invalid YAML: {}", e)),
+        e => (StatusCode::BAD_REQUEST, e.to_string()),
+    })
+}
+
 /// Create a new rule from a YAML body. Supports all 6 rule kinds.
 #[utoipa::path(
     post,
@@ -146,7 +157,7 @@ pub(crate) async fn get_rule_yaml(
     request_body(content = String, content_type = "application/yaml", description = "Rule definition in YAML format"),
     responses(
         (status = 201, description = "Rule created", body = Object),
-        (status = 400, description = "Invalid YAML", body = String),
+        (status = 400, description = "Invalid YAML or an unavailable `${VAR}`", body = String),
         (status = 409, description = "Rule already exists", body = String)
     )
 )]
@@ -155,9 +166,7 @@ pub(crate) async fn create_rule(
     body: String,
 ) -> Result<(StatusCode, impl IntoResponse), (StatusCode, String)> {
     // Two-pass parse: envelope -> full document.
-    let envelope: RuleEnvelope = serde_yaml::from_str(&body).map_err(|e| {
-        (StatusCode::BAD_REQUEST, format!("Invalid YAML: {}", e))
-    })?;
+    let envelope = parse_submitted(&body)?;
 
     let doc = envelope.parse_full().map_err(|e| {
         (StatusCode::BAD_REQUEST, format!("Failed to parse rule: {}", e))
@@ -203,7 +212,7 @@ pub(crate) async fn create_rule(
     request_body(content = String, content_type = "application/yaml", description = "Updated rule definition in YAML format"),
     responses(
         (status = 200, description = "Rule updated", body = Object),
-        (status = 400, description = "Invalid YAML", body = String),
+        (status = 400, description = "Invalid YAML or an unavailable `${VAR}`", body = String),
         (status = 404, description = "Rule not found", body = String)
     )
 )]
@@ -212,9 +221,7 @@ pub(crate) async fn update_rule(
     Path(id): Path<String>,
     body: String,
 ) -> Result<impl IntoResponse, (StatusCode, String)> {
-    let envelope: RuleEnvelope = serde_yaml::from_str(&body).map_err(|e| {
-        (StatusCode::BAD_REQUEST, format!("Invalid YAML: {}", e))
-    })?;
+    let envelope = parse_submitted(&body)?;
 
     let mut doc = envelope.parse_full().map_err(|e| {
         (StatusCode::BAD_REQUEST, format!("Failed to parse rule: {}", e))
@@ -373,3 +380,73 @@ pub(crate) async fn recent_triggers(
     Json(all)
 }
 
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    const RULE: &str = r#"
+x-spike: &spike
+  template: spike
+  params:
+    feature: login_count
+    multiplier: 3.0
+apiVersion: v1
+kind: AnomalyRule
+metadata:
+  id: api-rule
+  name: API Rule
+  enabled: true
+schedule:
+  cron: "*/15 * * * *"
+detection:
+  <<: *spike
+  enrich:
+    opensearch:
+      query:
+        index: "${INDEX_VAR}"
+"#;
+
+    async fn post(app: &axum::Router, body: String) -> (StatusCode, String) {
+        let request = Request::post("/rules").header("content-type", "application/yaml").body(Body::from(body)).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = http_body_util::BodyExt::collect(response.into_body()).await.unwrap().to_bytes();
+        (status, String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    #[tokio::test]
+    async fn test_created_rule_cannot_read_server_secrets() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state.clone());
+
+        let (status, body) = post(&app, RULE.replace("INDEX_VAR", "AWS_SECRET_ACCESS_KEY")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("'AWS_SECRET_ACCESS_KEY'") && body.contains("not available to rules"), "{body}");
+        assert!(!state.rule_loader.rules_dir().join("api-rule.yml").exists());
+
+        let (status, body) = post(&app, RULE.replace("INDEX_VAR", "STUPID_RULE_HANDLER_TEST_UNSET")).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("Undefined environment variable"), "{body}");
+    }
+
+    #[tokio::test]
+    async fn test_created_rule_is_preprocessed_but_keeps_placeholders() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+        let app = crate::router::build_router(state.clone());
+
+        std::env::set_var("STUPID_RULE_HANDLER_TEST_INDEX", "events-prod");
+        let (status, body) = post(&app, RULE.replace("INDEX_VAR", "STUPID_RULE_HANDLER_TEST_INDEX")).await;
+        std::env::remove_var("STUPID_RULE_HANDLER_TEST_INDEX");
+        assert_eq!(status, StatusCode::CREATED, "{body}");
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        // The merge key was applied, and the placeholder wasn't resolved.
+        assert_eq!(json["detection"]["template"], "spike");
+        assert_eq!(json["detection"]["enrich"]["opensearch"]["query"]["index"], "${STUPID_RULE_HANDLER_TEST_INDEX}");
+        let written = std::fs::read_to_string(state.rule_loader.rules_dir().join("api-rule.yml")).unwrap();
+        assert!(!written.contains("events-prod"), "{written}");
+    }
+}
@@ -0,0 +1,118 @@
+//! Scheduled refresh of stale external source schemas.
+//!
+//! External sources are snapshotted into the catalog, and their schemas
+//! drift. Every poll this loop asks the catalog store which sources have
+//! outlived their TTL and starts a schema refresh for each, the same way
+//! `POST /athena-connections/{id}/schema/refresh` does. A successful refresh
+//! stamps a new `last_refreshed`, so the source drops off the due list.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{debug, info, warn};
+
+use crate::credential_store::CredentialStore;
+use crate::state::AppState;
+
+/// How often to look for stale sources.
+const POLL_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Run the refresh loop forever.
+pub async fn run_schema_refresh_loop(state: Arc<AppState>) {
+    info!("Schema refresh scheduler started (poll interval: {}s)", POLL_INTERVAL.as_secs());
+
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        refresh_due_sources(&state).await;
+    }
+}
+
+/// Start a refresh for every due Athena source that isn't already fetching.
+/// Returns the connection IDs refreshes were started for.
+async fn refresh_due_sources(state: &Arc<AppState>) -> Vec<String> {
+    let due = match state.catalog_store.external_sources_due_for_refresh(Utc::now()) {
+        Ok(due) => due,
+        Err(e) => {
+            warn!("Schema refresh: failed to list external sources: {}", e);
+            return Vec::new();
+        }
+    };
+
+    let mut started = Vec::new();
+    for source in due.into_iter().filter(|s| s.kind == "athena") {
+        let fetching = {
+            let store = state.athena_connections.read().await;
+            matches!(store.get(&source.connection_id), Ok(Some(c)) if c.schema_status == "fetching")
+        };
+        if fetching {
+            debug!("Schema refresh: '{}' is already fetching", source.connection_id);
+            continue;
+        }
+
+        match crate::api::start_schema_refresh(state, &source.connection_id).await {
+            Ok(_) => {
+                info!(
+                    "Schema refresh: started for stale source '{}' (last refreshed {})",
+                    source.connection_id,
+                    source.last_refreshed.map_or("never".to_string(), |t| t.to_rfc3339())
+                );
+                started.push(source.connection_id);
+            }
+            Err((status, body)) => {
+                warn!("Schema refresh: '{}' not started ({}): {}", source.connection_id, status, body.error);
+            }
+        }
+    }
+    started
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn source(id: &str, hours_ago: Option<i64>) -> stupid_catalog::ExternalSource {
+        stupid_catalog::ExternalSource {
+            name: id.to_string(),
+            kind: "athena".to_string(),
+            connection_id: id.to_string(),
+            databases: vec![],
+            last_refreshed: hours_ago.map(|h| Utc::now() - chrono::Duration::hours(h)),
+            refresh_ttl_secs: stupid_catalog::DEFAULT_EXTERNAL_REFRESH_TTL_SECS,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_only_stale_idle_sources_are_refreshed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), Vec::new()).await;
+
+        // Nothing listens on port 1, so the spawned fetches fail fast.
+        let add = |name: &str| -> crate::athena_connections::AthenaConnectionInput {
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "database": "events",
+                "output_location": "s3://results/",
+                "access_key_id": "AKIATEST",
+                "secret_access_key": "test",
+                "endpoint_url": "http://127.0.0.1:1",
+            }))
+            .unwrap()
+        };
+        let (idle, busy) = {
+            let store = state.athena_connections.read().await;
+            let idle = store.add(&add("Idle Lake")).unwrap().id;
+            let busy = store.add(&add("Busy Lake")).unwrap().id;
+            store.update_schema_status(&busy, "fetching").unwrap();
+            (idle, busy)
+        };
+
+        state.catalog_store.save_external_source(&source(&idle, Some(48))).unwrap();
+        state.catalog_store.save_external_source(&source(&busy, Some(48))).unwrap();
+        state.catalog_store.save_external_source(&source("fresh", Some(1))).unwrap();
+        // Due, but its connection is gone, so nothing can be started.
+        state.catalog_store.save_external_source(&source("deleted", None)).unwrap();
+
+        assert_eq!(refresh_due_sources(&state).await, vec![idle]);
+    }
+}
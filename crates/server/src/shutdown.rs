@@ -0,0 +1,217 @@
+//! Graceful shutdown: wait for SIGINT/SIGTERM, stop background work, flush state.
+//!
+//! `axum::serve` stops accepting connections once [`signal`] resolves; then
+//! [`shutdown`] cancels the background tasks, joins the scheduler thread and
+//! writes out anything that would otherwise be left half-finished on disk.
+
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+use crate::state::{AppState, SchedulerHandle};
+
+/// How long each background task gets to stop after cancellation.
+const TASK_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long the scheduler gets to finish its current tick.
+const SCHEDULER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Background tasks spawned at startup, cancelled together on shutdown.
+#[derive(Default)]
+pub struct BackgroundTasks {
+    token: CancellationToken,
+    tasks: Vec<(&'static str, JoinHandle<()>)>,
+}
+
+impl BackgroundTasks {
+    pub fn new() -> Self {
+        Self { token: CancellationToken::new(), tasks: Vec::new() }
+    }
+
+    /// Spawn `task`; it is dropped at its next `.await` once shutdown begins.
+    pub fn spawn<F>(&mut self, name: &'static str, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let token = self.token.clone();
+        let handle = tokio::spawn(async move {
+            tokio::select! {
+                _ = token.cancelled() => {}
+                _ = task => {}
+            }
+        });
+        self.tasks.push((name, handle));
+    }
+
+    /// Spawn the future `task` builds from the shutdown token.
+    ///
+    /// Unlike [`spawn`](Self::spawn), the task is not dropped when shutdown
+    /// begins: it watches the token itself and returns once it has finished
+    /// the work in hand, and shutdown waits for it.
+    pub fn spawn_with_token<F, Fut>(&mut self, name: &'static str, task: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(task(self.token.clone()));
+        self.tasks.push((name, handle));
+    }
+
+    /// Cancel every task and wait up to `timeout` for each to stop.
+    pub async fn shutdown(self, timeout: Duration) {
+        self.token.cancel();
+        for (name, handle) in self.tasks {
+            match tokio::time::timeout(timeout, handle).await {
+                Ok(Ok(())) => info!("{} stopped", name),
+                Ok(Err(e)) => warn!("{} ended with an error: {}", name, e),
+                Err(_) => warn!("{} did not stop within {:?}", name, timeout),
+            }
+        }
+    }
+}
+
+/// Resolve on Ctrl+C, or SIGTERM on Unix.
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl+C, shutting down"),
+        _ = terminate => info!("Received SIGTERM, shutting down"),
+    }
+}
+
+/// Stop background work and flush state after the HTTP server has stopped.
+pub async fn shutdown(state: &AppState, tasks: BackgroundTasks) {
+    // Cancel tasks first so the loader can't start a scheduler we'd miss, and
+    // so queue consumers are done writing before their segment is finalized.
+    tasks.shutdown(TASK_TIMEOUT).await;
+
+    if let Some(scheduler) = state.scheduler.write().await.take() {
+        if stop_scheduler(scheduler, SCHEDULER_TIMEOUT).await {
+            info!("Scheduler stopped");
+        } else {
+            warn!("Scheduler did not stop within {:?}", SCHEDULER_TIMEOUT);
+        }
+    }
+
+    if let Some(catalog) = state.catalog.read().await.as_ref() {
+        match state.catalog_store.save_current(catalog) {
+            Ok(()) => info!("Catalog flushed"),
+            Err(e) => error!("Failed to flush catalog: {}", e),
+        }
+    }
+
+    let queue_writer = state.queue_writer.lock().unwrap().take();
+    if let Some((segment_id, writer)) = queue_writer {
+        match writer.finalize() {
+            Ok(()) => info!("Queue segment '{}' finalized", segment_id),
+            Err(e) => error!("Failed to finalize queue segment '{}': {}", segment_id, e),
+        }
+    }
+
+    info!("Shutdown complete");
+}
+
+/// Signal the scheduler loop to stop and wait up to `timeout` for its thread
+/// to exit. Returns whether the thread was joined.
+async fn stop_scheduler(scheduler: SchedulerHandle, timeout: Duration) -> bool {
+    scheduler.shutdown.store(true, std::sync::atomic::Ordering::Relaxed);
+
+    let deadline = Instant::now() + timeout;
+    while !scheduler.thread.is_finished() {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    if scheduler.thread.join().is_err() {
+        error!("Scheduler thread panicked");
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_stop_scheduler_joins_thread() {
+        let scheduler = stupid_compute::Scheduler::new(
+            stupid_compute::SchedulerConfig::default(),
+            stupid_compute::scheduler::state::new_shared_state(),
+        );
+        let shutdown = scheduler.shutdown_signal();
+        let metrics = scheduler.metrics_handle();
+        let thread = std::thread::spawn(move || scheduler.run());
+
+        let handle = SchedulerHandle { shutdown, metrics, thread };
+        assert!(stop_scheduler(handle, Duration::from_secs(5)).await);
+    }
+
+    #[tokio::test]
+    async fn test_background_tasks_are_cancelled() {
+        let dropped = Arc::new(AtomicBool::new(false));
+
+        /// Sets the flag when the cancelled task's future is dropped.
+        struct DropFlag(Arc<AtomicBool>);
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        let mut tasks = BackgroundTasks::new();
+        let flag = DropFlag(dropped.clone());
+        tasks.spawn("forever", async move {
+            let _flag = flag;
+            std::future::pending::<()>().await;
+        });
+        tasks.spawn("finished", async {});
+
+        tokio::time::timeout(Duration::from_secs(5), tasks.shutdown(Duration::from_secs(1)))
+            .await
+            .expect("shutdown should not hang");
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_token_tasks_finish_their_work_before_shutdown_returns() {
+        let finished = Arc::new(AtomicBool::new(false));
+
+        let mut tasks = BackgroundTasks::new();
+        let flag = finished.clone();
+        tasks.spawn_with_token("consumer", |token| async move {
+            token.cancelled().await;
+            // Work in hand when shutdown began, e.g. a batch being written.
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            flag.store(true, Ordering::SeqCst);
+        });
+
+        tasks.shutdown(Duration::from_secs(1)).await;
+        assert!(finished.load(Ordering::SeqCst));
+    }
+}
@@ -2,12 +2,15 @@
 
 use std::sync::Arc;
 
+use anyhow::Context;
+
 use tokio::sync::RwLock;
 use tracing::{error, info};
 
 use stupid_storage::StorageEngine;
 
 use crate::app_config;
+use crate::shutdown::BackgroundTasks;
 use crate::state::{AppState, LoadingState};
 use crate::{athena_connections, background, connections, db, eisenbahn_client, live, queue, queue_connections, rule_runner};
 
@@ -20,8 +23,6 @@ pub struct StartupContext {
     pub segment_ids_shared: Arc<RwLock<Vec<String>>>,
     pub doc_count_shared: Arc<std::sync::atomic::AtomicU64>,
     pub loading: Arc<LoadingState>,
-    pub watcher_broadcast_tx: tokio::sync::broadcast::Sender<String>,
-    pub catalog_store: Arc<stupid_catalog::CatalogStore>,
     pub eb_client: Option<Arc<eisenbahn_client::EisenbahnClient>>,
 }
 
@@ -49,7 +50,6 @@ pub async fn build_app_state(config: &stupid_core::Config, eisenbahn: bool) -> a
     let loading = Arc::new(LoadingState::new());
 
     let (broadcast_tx, _) = tokio::sync::broadcast::channel::<String>(64);
-    let watcher_broadcast_tx = broadcast_tx.clone();
 
     let queue_metrics = Arc::new(std::sync::RwLock::new(std::collections::HashMap::new()));
 
@@ -123,6 +123,39 @@ pub async fn build_app_state(config: &stupid_core::Config, eisenbahn: bool) -> a
         }
     }
 
+    // Warm-compute z-score thresholds come from the enabled ScoringConfig, if
+    // any. In percentile mode its classification thresholds decide instead,
+    // since percentiles can't be expressed as z-score cutoffs.
+    {
+        let documents = rule_loader.documents();
+        let documents = documents.read().expect("rule documents lock poisoned");
+        let scoring = documents
+            .values()
+            .filter_map(|doc| doc.as_scoring_config())
+            .find(|rule| rule.metadata.enabled);
+        if let Some(rule) = scoring {
+            let compiled = rule.compile();
+            let thresholds = stupid_compute::AnomalyThresholds::from_scoring_config(&compiled);
+            info!(?thresholds, "Anomaly thresholds from scoring config '{}'", rule.metadata.id);
+            let mut pipeline = pipeline.lock().expect("pipeline lock poisoned");
+            pipeline.set_anomaly_thresholds(thresholds);
+            if compiled.classification_thresholds.mode == stupid_rules::scoring_config::ClassificationMode::Percentile {
+                info!("Classifying anomalies by percentile per scoring config '{}'", rule.metadata.id);
+                pipeline.set_scoring_config(compiled);
+            }
+        }
+    }
+
+    // Load the graph extraction mapping (built-in unless overridden in the data dir).
+    let graph_mapping_path = config.storage.data_dir.join("graph_mapping.yaml");
+    let graph_mapping = stupid_core::GraphMapping::load_or_builtin(&graph_mapping_path)
+        .with_context(|| format!("failed to load {}", graph_mapping_path.display()))?;
+    info!(
+        "Graph mapping ready: {} event types (override: {})",
+        graph_mapping.events.len(),
+        graph_mapping_path.display()
+    );
+
     // Initialize PostgreSQL connection pool and run migrations.
     let pg_pool = db::init_pg_pool(&config.postgres).await;
 
@@ -147,13 +180,15 @@ pub async fn build_app_state(config: &stupid_core::Config, eisenbahn: bool) -> a
         None
     };
 
+    let api_keys = crate::auth::ApiKeys::new(config.server.api_keys.clone());
+    let tool_approvals = Arc::new(crate::tool_approvals::ToolApprovals::new());
     let state = Arc::new(AppState {
         graph: shared_graph.clone(),
         knowledge: knowledge.clone(),
         pipeline: pipeline.clone(),
         scheduler: RwLock::new(None),
         catalog: catalog.clone(),
-        catalog_store: catalog_store.clone(),
+        catalog_store,
         query_generator,
         segment_ids: segment_ids_shared.clone(),
         doc_count: doc_count_shared.clone(),
@@ -163,24 +198,38 @@ pub async fn build_app_state(config: &stupid_core::Config, eisenbahn: bool) -> a
         queue_writer: Arc::new(std::sync::Mutex::new(None)),
         data_dir: config.storage.data_dir.clone(),
         agent_executor: app_config::build_agent_executor(config),
-        agentic_loop: app_config::build_agentic_loop(config),
+        agentic_loop: app_config::build_agentic_loop(config, tool_approvals.clone()),
+        tool_approvals,
         connections: Arc::new(RwLock::new(conn_store)),
         queue_connections: Arc::new(RwLock::new(queue_conn_store)),
         athena_connections: Arc::new(RwLock::new(athena_conn_store)),
         embedder: app_config::build_embedder(config),
+        batch_embedder: crate::embedding_batch::BatchEmbedder::default(),
         session_store: Arc::new(RwLock::new(session_store)),
         group_store: Arc::new(RwLock::new(group_store)),
         eisenbahn: eb_client.clone(),
         rule_loader,
         trigger_history: Arc::new(std::sync::RwLock::new(std::collections::HashMap::new())),
         audit_log: stupid_rules::audit_log::AuditLog::new(),
+        rule_actions: crate::rule_actions::RuleActions::from_config(&config.opensearch),
+        rule_run_log: crate::rule_run_log::RuleRunLog::new(&config.storage.data_dir),
+        entity_first_seen: crate::rule_runner::EntityFirstSeen::new(&config.storage.data_dir),
         athena_query_log: crate::athena_query_log::AthenaQueryLog::new(&config.storage.data_dir),
+        query_audit: crate::query_audit::QueryAuditLog::new(&config.storage.data_dir),
         pg_pool,
         telemetry_store: Arc::new(RwLock::new(telemetry_store)),
         agent_store,
         skill_store,
         ingestion_jobs: crate::ingestion::IngestionJobStore::new(),
+        graph_mapping: Arc::new(graph_mapping),
+        config: config.clone(),
+        rate_limiter: crate::rate_limit::RateLimiter::new(&config.server.rate_limit, api_keys.clone()),
+        api_keys,
+        http_metrics: crate::metrics::HttpMetrics::new(),
     });
+    if !state.api_keys.is_enabled() {
+        tracing::warn!("API_KEYS not set — API authentication disabled");
+    }
 
     let ctx = StartupContext {
         shared_graph,
@@ -190,35 +239,47 @@ pub async fn build_app_state(config: &stupid_core::Config, eisenbahn: bool) -> a
         segment_ids_shared,
         doc_count_shared,
         loading,
-        watcher_broadcast_tx,
-        catalog_store,
         eb_client,
     };
 
     Ok((state, ctx))
 }
 
+/// App state over an empty `data_dir`, marked ready, with LLM and PostgreSQL
+/// disabled so it doesn't depend on the environment.
+#[cfg(test)]
+pub(crate) async fn test_app_state(
+    data_dir: &std::path::Path,
+    api_keys: Vec<stupid_core::config::ApiKey>,
+) -> Arc<AppState> {
+    let mut config = stupid_core::Config::for_profile("");
+    config.storage.data_dir = data_dir.to_path_buf();
+    config.postgres.pg_url = None;
+    // No LLM: the query generator would need prompt templates from the repo root.
+    config.llm.provider = "none".to_string();
+    config.server.api_keys = api_keys;
+
+    let (state, _ctx) = build_app_state(&config, false).await.unwrap();
+    state.loading.set_phase(crate::state::LoadingPhase::Ready).await;
+    state
+}
+
 /// Spawn all background tasks (data loading, rule evaluation, file watcher, queue consumers).
+///
+/// The returned [`BackgroundTasks`] cancels them on shutdown.
 pub fn spawn_background_tasks(
     config: &stupid_core::Config,
     state: Arc<AppState>,
     ctx: StartupContext,
     segment_id: Option<&str>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<BackgroundTasks> {
+    let mut tasks = BackgroundTasks::new();
     let data_dir = config.storage.data_dir.clone();
-    let watcher_data_dir = data_dir.clone();
     let storage = StorageEngine::from_config(config)?;
     let single_segment = segment_id.map(|s| s.to_string());
 
-    // Clones for the segment watcher (needs its own references).
-    let watcher_graph = ctx.shared_graph.clone();
-    let watcher_knowledge = ctx.knowledge.clone();
-    let watcher_pipeline = state.pipeline.clone();
-    let watcher_segments = ctx.segment_ids_shared.clone();
-    let watcher_doc_count = ctx.doc_count_shared.clone();
-
     let state_for_loader = state.clone();
-    tokio::spawn(async move {
+    tasks.spawn("Background loader", async move {
         let result = background::background_load(
             storage,
             data_dir,
@@ -239,41 +300,29 @@ pub fn spawn_background_tasks(
     });
 
     // Spawn background rule evaluation loop (waits for loading internally).
-    tokio::spawn(rule_runner::run_rule_loop(state.clone()));
+    tasks.spawn("Rule loop", rule_runner::run_rule_loop(state.clone()));
+
+    // Spawn scheduled refresh of stale external source schemas.
+    tasks.spawn("Schema refresh", crate::schema_refresh::run_schema_refresh_loop(state.clone()));
 
     // Spawn segment file watcher for live updates.
-    let watcher_catalog = state.catalog.clone();
-    let watcher_catalog_store = ctx.catalog_store;
-    tokio::spawn(async move {
-        live::start_segment_watcher(
-            watcher_data_dir,
-            watcher_graph,
-            watcher_knowledge,
-            watcher_pipeline,
-            watcher_segments,
-            watcher_doc_count,
-            ctx.watcher_broadcast_tx,
-            watcher_catalog,
-            watcher_catalog_store,
-        )
-        .await;
-    });
+    tasks.spawn("Segment watcher", live::start_segment_watcher(live::LiveMerge::new(&state)));
 
     // Spawn queue consumers from the encrypted connection store.
     {
         let queue_state = state.clone();
-        tokio::spawn(async move {
-            queue::spawn_queue_consumers(queue_state).await;
+        tasks.spawn_with_token("Queue consumers", move |shutdown| {
+            queue::spawn_queue_consumers(queue_state, shutdown)
         });
     }
 
     // Spawn ingestion scheduler (cron-based polling for due sources).
     {
         let sched_state = state.clone();
-        tokio::spawn(crate::ingestion::scheduler::run_ingestion_scheduler(sched_state));
+        tasks.spawn("Ingestion scheduler", crate::ingestion::scheduler::run_ingestion_scheduler(sched_state));
     }
 
-    Ok(())
+    Ok(tasks)
 }
 
 /// Seed SpAgent prompts from version-controlled YAML files into PostgreSQL.
@@ -484,6 +533,25 @@ async fn seed_prompts(pool: &sqlx::PgPool, data_dir: &std::path::Path) {
 #[cfg(test)]
 mod tests {
     use crate::api::stille_post::yaml_types::{SpAgentSpec, SpYamlEnvelope, SpYamlKind};
+    use stupid_rules::scoring_config::ClassificationMode;
+
+    #[tokio::test]
+    async fn test_percentile_scoring_config_reaches_the_pipeline() {
+        let tmp = tempfile::tempdir().unwrap();
+        let rules_dir = tmp.path().join("rules");
+        std::fs::create_dir_all(&rules_dir).unwrap();
+        let yaml = include_str!("../../../data/rules/scoring/scoring-config.yml")
+            .replace("    mild: 0.3\n", "    mode: percentile\n    mild: 0.8\n")
+            .replace("anomalous: 0.5", "anomalous: 0.9")
+            .replace("highly_anomalous: 0.7", "highly_anomalous: 0.975");
+        std::fs::write(rules_dir.join("scoring-config.yml"), yaml).unwrap();
+
+        let state = super::test_app_state(tmp.path(), Vec::new()).await;
+        let pipeline = state.pipeline.lock().unwrap();
+        let scoring = pipeline.scoring_config().expect("percentile config applied");
+        assert_eq!(scoring.classification_thresholds.mode, ClassificationMode::Percentile);
+        assert_eq!(scoring.classification_thresholds.highly_anomalous, 0.975);
+    }
 
     /// Verify all YAML files in data/stille-post/ with kind=SpAgent parse correctly.
     /// Handles both single-document and multi-document YAML files.
@@ -1,5 +1,5 @@
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -16,12 +16,13 @@ pub type SharedGraph = Arc<RwLock<GraphStore>>;
 pub type SharedPipeline = Arc<std::sync::Mutex<stupid_compute::Pipeline>>;
 
 /// Handle to the background scheduler thread for shutdown and metrics.
-#[allow(dead_code)] // shutdown used for future graceful server shutdown
 pub struct SchedulerHandle {
     /// Signal to stop the scheduler loop.
     pub shutdown: Arc<std::sync::atomic::AtomicBool>,
     /// Shared scheduler metrics (read-only from API handlers).
     pub metrics: Arc<std::sync::RwLock<stupid_compute::SchedulerMetrics>>,
+    /// The thread running the scheduler loop, joined on shutdown.
+    pub thread: std::thread::JoinHandle<()>,
 }
 
 /// Active segment writer for queue-ingested documents.
@@ -40,7 +41,7 @@ pub struct AppState {
     pub doc_count: Arc<AtomicU64>,
     pub loading: Arc<LoadingState>,
     pub broadcast: broadcast::Sender<String>,
-    pub queue_metrics: Arc<std::sync::RwLock<std::collections::HashMap<String, Arc<QueueMetrics>>>>,
+    pub queue_metrics: Arc<std::sync::RwLock<std::collections::HashMap<String, Arc<stupid_queue::QueueMetrics>>>>,
     /// Segment writer for persisting queue-ingested documents to disk.
     pub queue_writer: QueueWriter,
     /// Root data directory for segment storage.
@@ -49,6 +50,8 @@ pub struct AppState {
     pub agent_executor: Option<stupid_agent::AgentExecutor>,
     /// Agentic loop for tool-aware LLM interaction (streaming, tool use).
     pub agentic_loop: Option<AgenticLoop>,
+    /// Agentic loop tool calls waiting for a client to approve them.
+    pub tool_approvals: Arc<crate::tool_approvals::ToolApprovals>,
     /// Encrypted connection credential store.
     pub connections: Arc<tokio::sync::RwLock<crate::connections::ConnectionStore>>,
     /// Encrypted queue connection store.
@@ -57,6 +60,8 @@ pub struct AppState {
     pub athena_connections: Arc<tokio::sync::RwLock<crate::athena_connections::AthenaConnectionStore>>,
     /// Embedding backend for vector search features.
     pub embedder: Option<Arc<dyn stupid_ingest::embedding::Embedder>>,
+    /// Embedding cache and provider concurrency cap for `/embeddings/embed`.
+    pub batch_embedder: crate::embedding_batch::BatchEmbedder,
     /// Session store for agent chat history persistence.
     pub session_store: Arc<tokio::sync::RwLock<stupid_agent::session::SessionStore>>,
     /// Agent group store for managing agent-to-group mappings.
@@ -69,8 +74,16 @@ pub struct AppState {
     pub trigger_history: crate::anomaly_rules::SharedTriggerHistory,
     /// Audit log for anomaly rule evaluation.
     pub audit_log: stupid_rules::audit_log::AuditLog,
+    /// Enrichment and notification dispatch for rule matches.
+    pub rule_actions: crate::rule_actions::RuleActions,
+    /// Persisted structured log of every rule run.
+    pub rule_run_log: crate::rule_run_log::RuleRunLog,
+    /// When each entity was first seen, for `new_entity` rules.
+    pub entity_first_seen: crate::rule_runner::EntityFirstSeen,
     /// Per-connection Athena query audit log with cost tracking.
     pub athena_query_log: crate::athena_query_log::AthenaQueryLog,
+    /// Append-only audit trail of `/query`, `/catalog/query` and Athena queries.
+    pub query_audit: crate::query_audit::QueryAuditLog,
     /// PostgreSQL connection pool for pgvector embedding storage.
     pub pg_pool: Option<sqlx::PgPool>,
     /// Per-agent execution telemetry store (JSONL-backed).
@@ -81,52 +94,29 @@ pub struct AppState {
     pub skill_store: Option<Arc<stupid_agent::SkillStore>>,
     /// In-memory store for active and recent ingestion jobs.
     pub ingestion_jobs: crate::ingestion::IngestionJobStore,
-}
-
-/// Lock-free atomic counters for queue consumer observability.
-///
-/// All fields use `Ordering::Relaxed` — these are monotonic counters
-/// where eventual visibility is acceptable for dashboard/status reads.
-pub struct QueueMetrics {
-    /// Whether queue ingestion is enabled in config.
-    pub enabled: AtomicBool,
-    /// Whether the consumer is currently connected to the queue.
-    pub connected: AtomicBool,
-    /// Total messages received from queue polls.
-    pub messages_received: AtomicU64,
-    /// Messages successfully parsed and ingested.
-    pub messages_processed: AtomicU64,
-    /// Messages that failed parsing or ingestion.
-    pub messages_failed: AtomicU64,
-    /// Number of micro-batches flushed to the graph.
-    pub batches_processed: AtomicU64,
-    /// Cumulative processing time in microseconds (for avg latency calc).
-    pub total_processing_time_us: AtomicU64,
-    /// Epoch milliseconds of the last successful poll.
-    pub last_poll_epoch_ms: AtomicU64,
-}
-
-impl QueueMetrics {
-    pub fn new() -> Self {
-        Self {
-            enabled: AtomicBool::new(false),
-            connected: AtomicBool::new(false),
-            messages_received: AtomicU64::new(0),
-            messages_processed: AtomicU64::new(0),
-            messages_failed: AtomicU64::new(0),
-            batches_processed: AtomicU64::new(0),
-            total_processing_time_us: AtomicU64::new(0),
-            last_poll_epoch_ms: AtomicU64::new(0),
-        }
-    }
+    /// Event type → graph edge mapping used when extracting graph ops.
+    pub graph_mapping: Arc<stupid_core::GraphMapping>,
+    /// Configuration the server was started with, served redacted on `/debug/config`.
+    pub config: stupid_core::Config,
+    /// API keys checked by the auth middleware (empty = auth disabled).
+    pub api_keys: crate::auth::ApiKeys,
+    /// Per-client token buckets checked by the rate limit middleware.
+    pub rate_limiter: crate::rate_limit::RateLimiter,
+    /// Per-route request counts and latencies, served on `/metrics`.
+    pub http_metrics: crate::metrics::HttpMetrics,
 }
 
 /// Tracks background data loading progress.
+///
+/// `phase` reaches [`LoadingPhase::Ready`] as soon as the graph is queryable;
+/// catalog building and compute continue afterwards and are tracked
+/// separately in `compute_phase`.
 pub struct LoadingState {
     pub phase: RwLock<LoadingPhase>,
-    /// Number of segments loaded so far.
+    pub compute_phase: RwLock<ComputePhase>,
+    /// Segments downloaded or loaded so far in the current phase.
     pub progress: AtomicU64,
-    /// Total segments to load.
+    /// Total segments to download or load in the current phase.
     pub total: AtomicU64,
     pub started_at: Instant,
 }
@@ -135,6 +125,7 @@ impl LoadingState {
     pub fn new() -> Self {
         Self {
             phase: RwLock::new(LoadingPhase::Discovering),
+            compute_phase: RwLock::new(ComputePhase::Pending),
             progress: AtomicU64::new(0),
             total: AtomicU64::new(0),
             started_at: Instant::now(),
@@ -145,6 +136,10 @@ impl LoadingState {
         *self.phase.write().await = phase;
     }
 
+    pub async fn set_compute_phase(&self, phase: ComputePhase) {
+        *self.compute_phase.write().await = phase;
+    }
+
     pub fn set_progress(&self, progress: u64, total: u64) {
         self.progress.store(progress, Ordering::Relaxed);
         self.total.store(total, Ordering::Relaxed);
@@ -156,9 +151,16 @@ impl LoadingState {
 
     pub async fn to_status(&self) -> LoadingStatus {
         let phase = self.phase.read().await;
+        let compute_phase = self.compute_phase.read().await;
         LoadingStatus {
             phase: phase.label(),
             is_ready: matches!(*phase, LoadingPhase::Ready),
+            compute_phase: compute_phase.label(),
+            compute_task: match &*compute_phase {
+                ComputePhase::Computing(task) => Some(*task),
+                _ => None,
+            },
+            compute_ready: matches!(*compute_phase, ComputePhase::Ready),
             progress: self.progress.load(Ordering::Relaxed),
             total: self.total.load(Ordering::Relaxed),
             elapsed_seconds: self.started_at.elapsed().as_secs_f64(),
@@ -171,12 +173,13 @@ impl LoadingState {
 }
 
 #[derive(Debug)]
-#[allow(dead_code)] // BuildingCatalog/RunningCompute used by live watcher, not cold start
 pub enum LoadingPhase {
     Discovering,
+    /// Pre-fetching remote segments to the local cache (see `progress`/`total`).
+    DownloadingSegments,
+    /// Reading segments into the graph (see `progress`/`total`).
     LoadingSegments,
-    BuildingCatalog,
-    RunningCompute,
+    /// The graph is queryable.
     Ready,
     Failed(String),
 }
@@ -185,23 +188,116 @@ impl LoadingPhase {
     pub fn label(&self) -> &'static str {
         match self {
             Self::Discovering => "discovering_segments",
+            Self::DownloadingSegments => "downloading_segments",
             Self::LoadingSegments => "loading_segments",
-            Self::BuildingCatalog => "building_catalog",
-            Self::RunningCompute => "running_compute",
             Self::Ready => "ready",
             Self::Failed(_) => "failed",
         }
     }
 }
 
+/// Post-load work that runs after the graph is [`LoadingPhase::Ready`].
+#[derive(Debug)]
+pub enum ComputePhase {
+    /// Waiting for the graph to load.
+    Pending,
+    BuildingCatalog,
+    /// Running the named compute task (e.g. `pagerank`).
+    Computing(&'static str),
+    /// Catalog and initial compute results are available.
+    Ready,
+}
+
+impl ComputePhase {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::BuildingCatalog => "building_catalog",
+            Self::Computing(_) => "computing",
+            Self::Ready => "ready",
+        }
+    }
+}
+
 /// Serializable loading status for API responses.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, utoipa::ToSchema)]
 pub struct LoadingStatus {
     pub phase: &'static str,
+    /// The graph is queryable.
     pub is_ready: bool,
+    pub compute_phase: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compute_task: Option<&'static str>,
+    /// Catalog and compute results are available.
+    pub compute_ready: bool,
     pub progress: u64,
     pub total: u64,
     pub elapsed_seconds: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_cold_start_phase_transitions() {
+        let loading = LoadingState::new();
+        let status = loading.to_status().await;
+        assert_eq!(status.phase, "discovering_segments");
+        assert!(!status.is_ready);
+        assert_eq!(status.compute_phase, "pending");
+
+        loading.set_phase(LoadingPhase::DownloadingSegments).await;
+        loading.set_progress(3, 10);
+        let status = loading.to_status().await;
+        assert_eq!(status.phase, "downloading_segments");
+        assert_eq!((status.progress, status.total), (3, 10));
+        assert!(!status.is_ready);
+
+        loading.set_phase(LoadingPhase::LoadingSegments).await;
+        assert!(!loading.is_ready().await);
+
+        // Ready means the graph is queryable, even though compute hasn't run.
+        loading.set_phase(LoadingPhase::Ready).await;
+        assert!(loading.is_ready().await);
+        assert!(!loading.to_status().await.compute_ready);
+
+        loading.set_compute_phase(ComputePhase::BuildingCatalog).await;
+        assert_eq!(loading.to_status().await.compute_phase, "building_catalog");
+
+        loading.set_compute_phase(ComputePhase::Computing("pagerank")).await;
+        let status = loading.to_status().await;
+        assert_eq!(status.compute_phase, "computing");
+        assert_eq!(status.compute_task, Some("pagerank"));
+        assert!(status.is_ready && !status.compute_ready);
+
+        loading.set_compute_phase(ComputePhase::Ready).await;
+        let status = loading.to_status().await;
+        assert!(status.is_ready && status.compute_ready);
+        assert_eq!(status.compute_task, None);
+    }
+
+    #[tokio::test]
+    async fn test_failed_phase_reports_error() {
+        let loading = LoadingState::new();
+        loading.set_phase(LoadingPhase::Failed("no segments".into())).await;
+        let status = loading.to_status().await;
+        assert_eq!(status.phase, "failed");
+        assert_eq!(status.error.as_deref(), Some("no segments"));
+        assert!(!status.is_ready && !status.compute_ready);
+    }
+
+    #[tokio::test]
+    async fn test_status_serializes_compute_fields() {
+        let loading = LoadingState::new();
+        loading.set_phase(LoadingPhase::Ready).await;
+        loading.set_compute_phase(ComputePhase::Computing("pipeline")).await;
+        let json = serde_json::to_value(loading.to_status().await).unwrap();
+        assert_eq!(json["phase"], "ready");
+        assert_eq!(json["compute_phase"], "computing");
+        assert_eq!(json["compute_task"], "pipeline");
+        assert_eq!(json["compute_ready"], false);
+    }
+}
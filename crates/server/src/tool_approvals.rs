@@ -0,0 +1,208 @@
+//! Pending approvals for agent tool calls that need confirmation.
+//!
+//! Tools listed in `LLM_CONFIRM_TOOLS` don't run until a client decides.
+//! The agentic loop streams an `AwaitingApproval` stage for the call over
+//! `/sessions/{id}/stream`; clients list waiting calls on `GET /tool-approvals`
+//! and answer with `POST /tool-approvals/{call_id}`. Calls are keyed by the
+//! loop's `call_id`, since providers may reuse their own IDs. Calls nobody
+//! answers within `LLM_APPROVAL_TIMEOUT_SECS` are denied by the checker.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::sync::oneshot;
+
+use stupid_tool_runtime::{Approver, PermissionDecision, ToolCall};
+
+/// A tool call waiting for a decision.
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+pub struct PendingApproval {
+    /// The loop's ID for the call, as in the stream's `ToolCallStarted`.
+    pub call_id: String,
+    /// The provider's ID for the call.
+    pub tool_call_id: String,
+    pub tool_name: String,
+    #[schema(value_type = Object)]
+    pub input: serde_json::Value,
+    #[schema(value_type = String)]
+    pub requested_at: DateTime<Utc>,
+}
+
+/// A waiting call, tagged with the request that queued it.
+struct Pending {
+    request: u64,
+    call: PendingApproval,
+    reply: oneshot::Sender<PermissionDecision>,
+}
+
+/// Tool calls awaiting approval, keyed by call ID.
+#[derive(Default)]
+pub struct ToolApprovals {
+    pending: Mutex<HashMap<String, Pending>>,
+    next_request: AtomicU64,
+}
+
+impl ToolApprovals {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Calls waiting for a decision, oldest first.
+    pub fn list(&self) -> Vec<PendingApproval> {
+        let pending = self.pending.lock().expect("tool approvals lock poisoned");
+        let mut calls: Vec<_> = pending.values().map(|p| p.call.clone()).collect();
+        calls.sort_by_key(|c| c.requested_at);
+        calls
+    }
+
+    /// Approve or deny a waiting call; `false` if no call with that ID is
+    /// waiting (unknown, already decided, or timed out).
+    pub fn decide(&self, call_id: &str, decision: PermissionDecision) -> bool {
+        let entry = self.pending.lock().expect("tool approvals lock poisoned").remove(call_id);
+        match entry {
+            Some(pending) => pending.reply.send(decision).is_ok(),
+            None => false,
+        }
+    }
+}
+
+/// Removes a call from the queue when its approval request ends, including
+/// when the checker's timeout drops it. Leaves the entry alone if another
+/// request has since queued a call under the same ID.
+struct PendingGuard<'a> {
+    approvals: &'a ToolApprovals,
+    call_id: String,
+    request: u64,
+}
+
+impl Drop for PendingGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.approvals.pending.lock() {
+            if pending.get(&self.call_id).is_some_and(|p| p.request == self.request) {
+                pending.remove(&self.call_id);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Approver for ToolApprovals {
+    async fn request_approval(&self, call_id: &str, call: &ToolCall) -> PermissionDecision {
+        let (reply, decision) = oneshot::channel();
+        let request = self.next_request.fetch_add(1, Ordering::Relaxed);
+        let pending = PendingApproval {
+            call_id: call_id.to_string(),
+            tool_call_id: call.id.clone(),
+            tool_name: call.name.clone(),
+            input: call.input.clone(),
+            requested_at: Utc::now(),
+        };
+        self.pending
+            .lock()
+            .expect("tool approvals lock poisoned")
+            .insert(call_id.to_string(), Pending { request, call: pending, reply });
+        let _guard = PendingGuard { approvals: self, call_id: call_id.to_string(), request };
+
+        decision
+            .await
+            .unwrap_or_else(|_| PermissionDecision::Denied("Approval request was dropped".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use stupid_tool_runtime::permission::{PermissionPolicy, PolicyChecker};
+    use stupid_tool_runtime::PermissionChecker;
+
+    fn call() -> ToolCall {
+        ToolCall {
+            id: "toolu_1".to_string(),
+            name: "bash_execute".to_string(),
+            input: serde_json::json!({"command": "make deploy"}),
+        }
+    }
+
+    /// Wait until `id` shows up in the queue.
+    async fn wait_for_pending(approvals: &ToolApprovals, id: &str) {
+        while !approvals.list().iter().any(|p| p.call_id == id) {
+            tokio::task::yield_now().await;
+        }
+    }
+
+    fn checker(approvals: &Arc<ToolApprovals>, timeout: Duration) -> Arc<PolicyChecker> {
+        Arc::new(PolicyChecker::new(PermissionPolicy::new()).with_approver(approvals.clone(), timeout))
+    }
+
+    #[tokio::test]
+    async fn test_client_decisions_reach_the_waiting_call() {
+        let approvals = Arc::new(ToolApprovals::new());
+        let checker = checker(&approvals, Duration::from_secs(30));
+
+        let waiting = tokio::spawn({
+            let checker = checker.clone();
+            async move { checker.request_approval("call_1", &call()).await }
+        });
+        wait_for_pending(&approvals, "call_1").await;
+        assert!(approvals.decide("call_1", PermissionDecision::Approved));
+        assert_eq!(waiting.await.unwrap(), PermissionDecision::Approved);
+        assert!(approvals.list().is_empty());
+        assert!(!approvals.decide("call_1", PermissionDecision::Approved), "already decided");
+
+        let waiting = tokio::spawn({
+            let checker = checker.clone();
+            async move { checker.request_approval("call_2", &call()).await }
+        });
+        wait_for_pending(&approvals, "call_2").await;
+        approvals.decide("call_2", PermissionDecision::Denied("not now".to_string()));
+        assert_eq!(waiting.await.unwrap(), PermissionDecision::Denied("not now".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_unanswered_call_is_denied_and_dequeued() {
+        let approvals = Arc::new(ToolApprovals::new());
+        let decision = checker(&approvals, Duration::from_millis(20)).request_approval("call_3", &call()).await;
+
+        assert!(matches!(decision, PermissionDecision::Denied(reason) if reason.starts_with("No approval")));
+        assert!(approvals.list().is_empty());
+        assert!(!approvals.decide("call_3", PermissionDecision::Approved));
+    }
+
+    #[tokio::test]
+    async fn test_calls_are_keyed_by_loop_call_id() {
+        let approvals = Arc::new(ToolApprovals::new());
+        let checker = checker(&approvals, Duration::from_secs(30));
+
+        // Two calls the provider gave the same ID wait separately.
+        let first = tokio::spawn({
+            let checker = checker.clone();
+            async move { checker.request_approval("call_4", &call()).await }
+        });
+        let second = tokio::spawn({
+            let checker = checker.clone();
+            async move { checker.request_approval("call_5", &call()).await }
+        });
+        wait_for_pending(&approvals, "call_4").await;
+        wait_for_pending(&approvals, "call_5").await;
+        assert!(approvals.decide("call_5", PermissionDecision::Approved));
+        assert_eq!(second.await.unwrap(), PermissionDecision::Approved);
+        assert_eq!(approvals.list().len(), 1);
+
+        // A call re-queued under the same ID outlives the request it replaced.
+        let replacement = tokio::spawn({
+            let checker = checker.clone();
+            async move { checker.request_approval("call_4", &call()).await }
+        });
+        assert!(matches!(first.await.unwrap(), PermissionDecision::Denied(reason) if reason.contains("dropped")));
+        wait_for_pending(&approvals, "call_4").await;
+        assert!(approvals.decide("call_4", PermissionDecision::Approved));
+        assert_eq!(replacement.await.unwrap(), PermissionDecision::Approved);
+    }
+}
@@ -0,0 +1,343 @@
+//! `/ws` protocol: topic subscriptions over typed `{type, data}` envelopes.
+//!
+//! Clients send JSON text frames:
+//!
+//! ```text
+//! {"subscribe": ["insights", "loading", "queue"]}
+//! {"unsubscribe": ["queue"]}
+//! "ping"
+//! ```
+//!
+//! The server replies to these with `subscribed`, `pong` or `error`
+//! envelopes and forwards broadcast messages only for subscribed topics.
+//! A client that never subscribes receives every topic, so older clients
+//! keep working. Outbound messages are capped per connection; anything
+//! dropped is reported in a `rate_limited` envelope once the window resets.
+
+use std::collections::BTreeSet;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::IntoResponse;
+use futures::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+use crate::state::AppState;
+
+/// Interval between server pings.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+/// Close the connection after this long without any frame from the client.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(90);
+/// Maximum broadcast messages forwarded to one connection per second.
+const MAX_MESSAGES_PER_SEC: u32 = 50;
+
+// ── Envelopes ───────────────────────────────────────────────────
+
+#[derive(Serialize)]
+struct WsMessage<T: Serialize> {
+    #[serde(rename = "type")]
+    msg_type: &'static str,
+    data: T,
+}
+
+/// Serialize a `{type, data}` envelope for the broadcast channel.
+pub(crate) fn envelope<T: Serialize>(msg_type: &'static str, data: T) -> String {
+    serde_json::to_string(&WsMessage { msg_type, data }).unwrap_or_default()
+}
+
+/// Broadcast topics a client can subscribe to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Topic {
+    /// `insight` messages from the compute pipeline.
+    Insights,
+    /// `stats` and `segments` updates as segments are loaded.
+    Loading,
+    /// `queue_batch` messages from queue consumers.
+    Queue,
+    /// `event` messages forwarded from eisenbahn.
+    Events,
+}
+
+impl Topic {
+    const ALL: [Topic; 4] = [Topic::Insights, Topic::Loading, Topic::Queue, Topic::Events];
+
+    /// The topic an envelope of `msg_type` belongs to.
+    fn of(msg_type: &str) -> Option<Self> {
+        match msg_type {
+            "insight" => Some(Topic::Insights),
+            "stats" | "segments" => Some(Topic::Loading),
+            "queue_batch" => Some(Topic::Queue),
+            "event" => Some(Topic::Events),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum ClientMessage {
+    Subscribe(Vec<Topic>),
+    Unsubscribe(Vec<Topic>),
+    Ping,
+}
+
+// ── Per-connection state ────────────────────────────────────────
+
+/// Topics a connection wants; `None` until the client first subscribes.
+#[derive(Default)]
+struct Subscriptions {
+    topics: Option<BTreeSet<Topic>>,
+}
+
+impl Subscriptions {
+    /// Whether a broadcast message should be forwarded to this client.
+    fn wants(&self, text: &str) -> bool {
+        #[derive(Deserialize)]
+        struct Header {
+            #[serde(rename = "type")]
+            msg_type: String,
+        }
+
+        let Some(topics) = &self.topics else {
+            return true;
+        };
+        serde_json::from_str::<Header>(text)
+            .ok()
+            .and_then(|header| Topic::of(&header.msg_type))
+            .is_some_and(|topic| topics.contains(&topic))
+    }
+
+    /// Apply a client frame, returning the reply to send.
+    fn handle(&mut self, text: &str) -> String {
+        let msg = match serde_json::from_str::<ClientMessage>(text) {
+            Ok(msg) => msg,
+            Err(e) => {
+                return envelope("error", serde_json::json!({ "message": format!("invalid message: {e}") }));
+            }
+        };
+        let topics = self.topics.get_or_insert_with(|| match msg {
+            // Unsubscribing before subscribing narrows the implicit "everything".
+            ClientMessage::Unsubscribe(_) => Topic::ALL.into_iter().collect(),
+            _ => BTreeSet::new(),
+        });
+        match msg {
+            ClientMessage::Subscribe(add) => topics.extend(add),
+            ClientMessage::Unsubscribe(remove) => topics.retain(|t| !remove.contains(t)),
+            ClientMessage::Ping => return envelope("pong", serde_json::Value::Null),
+        }
+        envelope("subscribed", serde_json::json!({ "topics": topics }))
+    }
+}
+
+/// Fixed one-second window on outbound broadcast messages.
+struct RateLimiter {
+    max_per_sec: u32,
+    window_start: Instant,
+    sent: u32,
+    dropped: u64,
+}
+
+impl RateLimiter {
+    fn new(max_per_sec: u32) -> Self {
+        Self { max_per_sec, window_start: Instant::now(), sent: 0, dropped: 0 }
+    }
+
+    /// Start a new window if the current one has elapsed, returning how
+    /// many messages the previous window dropped.
+    fn roll(&mut self, now: Instant) -> Option<u64> {
+        if now.duration_since(self.window_start) < Duration::from_secs(1) {
+            return None;
+        }
+        self.window_start = now;
+        self.sent = 0;
+        Some(std::mem::take(&mut self.dropped)).filter(|&n| n > 0)
+    }
+
+    /// Count a message against the current window; `false` means drop it.
+    fn allow(&mut self) -> bool {
+        if self.sent < self.max_per_sec {
+            self.sent += 1;
+            true
+        } else {
+            self.dropped += 1;
+            false
+        }
+    }
+}
+
+// ── Handler ─────────────────────────────────────────────────────
+
+/// Upgrade to a WebSocket carrying topic-subscribed live updates.
+#[utoipa::path(
+    get,
+    path = "/ws",
+    tag = "Health",
+    responses(
+        (status = 101, description = "Switched to the WebSocket protocol; see the module docs for frames")
+    )
+)]
+pub async fn ws_upgrade(
+    ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
+) -> impl IntoResponse {
+    ws.on_upgrade(move |socket| handle_ws(socket, state))
+}
+
+async fn handle_ws(socket: WebSocket, state: Arc<AppState>) {
+    let (mut sender, mut receiver) = socket.split();
+    let mut rx = state.broadcast.subscribe();
+
+    // Send current stats as initial state.
+    let initial = build_stats_message(&state).await;
+    if sender.send(Message::Text(initial.into())).await.is_err() {
+        return;
+    }
+
+    let mut subscriptions = Subscriptions::default();
+    let mut limiter = RateLimiter::new(MAX_MESSAGES_PER_SEC);
+    let mut last_seen = Instant::now();
+    let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+    heartbeat.tick().await;
+
+    loop {
+        let outgoing = tokio::select! {
+            msg = rx.recv() => match msg {
+                Ok(text) if subscriptions.wants(&text) => {
+                    if let Some(dropped) = limiter.roll(Instant::now()) {
+                        let notice = envelope("rate_limited", serde_json::json!({ "dropped": dropped }));
+                        if sender.send(Message::Text(notice.into())).await.is_err() {
+                            break;
+                        }
+                    }
+                    if !limiter.allow() {
+                        continue;
+                    }
+                    Message::Text(text.into())
+                }
+                Ok(_) => continue,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(skipped, "WebSocket client lagged behind broadcast");
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            frame = receiver.next() => {
+                let Some(Ok(frame)) = frame else { break };
+                last_seen = Instant::now();
+                match frame {
+                    Message::Text(text) => Message::Text(subscriptions.handle(&text).into()),
+                    Message::Close(_) => break,
+                    // Pings are answered by axum; pongs only refresh `last_seen`.
+                    _ => continue,
+                }
+            },
+            _ = heartbeat.tick() => {
+                if last_seen.elapsed() > CLIENT_TIMEOUT {
+                    debug!("Closing idle WebSocket connection");
+                    let _ = sender.send(Message::Close(None)).await;
+                    break;
+                }
+                Message::Ping(Default::default())
+            },
+        };
+        if sender.send(outgoing).await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn build_stats_message(state: &AppState) -> String {
+    let graph = state.graph.read().await;
+    let gs = graph.stats();
+    let segment_ids = state.segment_ids.read().await;
+
+    envelope("stats", serde_json::json!({
+        "doc_count": state.doc_count.load(Ordering::Relaxed),
+        "segment_count": segment_ids.len(),
+        "node_count": gs.node_count,
+        "edge_count": gs.edge_count,
+        "nodes_by_type": gs.nodes_by_type,
+        "edges_by_type": gs.edges_by_type,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_tungstenite::tungstenite::Message as ClientFrame;
+
+    #[test]
+    fn subscriptions_filter_by_topic() {
+        let mut subs = Subscriptions::default();
+        let queue = envelope("queue_batch", serde_json::json!({ "docs": 1 }));
+        assert!(subs.wants(&queue), "unsubscribed clients get everything");
+
+        let reply = subs.handle(r#"{"subscribe": ["insights", "loading"]}"#);
+        assert_eq!(reply, r#"{"type":"subscribed","data":{"topics":["insights","loading"]}}"#);
+        assert!(subs.wants(&envelope("stats", 1)));
+        assert!(!subs.wants(&queue));
+        assert!(!subs.wants("not json"));
+
+        subs.handle(r#"{"unsubscribe": ["loading"]}"#);
+        assert!(!subs.wants(&envelope("segments", 1)));
+        assert_eq!(subs.handle(r#""ping""#), r#"{"type":"pong","data":null}"#);
+        assert!(subs.handle(r#"{"subscribe": ["bogus"]}"#).starts_with(r#"{"type":"error""#));
+    }
+
+    #[test]
+    fn rate_limiter_reports_drops_on_next_window() {
+        let mut limiter = RateLimiter::new(2);
+        let start = limiter.window_start;
+        assert!(limiter.allow() && limiter.allow());
+        assert!(!limiter.allow());
+        assert_eq!(limiter.roll(start + Duration::from_millis(500)), None);
+        assert_eq!(limiter.roll(start + Duration::from_secs(1)), Some(1));
+        assert!(limiter.allow());
+    }
+
+    async fn next_text<S>(client: &mut S) -> serde_json::Value
+    where
+        S: futures::Stream<Item = Result<ClientFrame, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        loop {
+            if let ClientFrame::Text(text) = client.next().await.unwrap().unwrap() {
+                return serde_json::from_str(&text).unwrap();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn client_receives_only_subscribed_topics() {
+        let tmp = tempfile::tempdir().unwrap();
+        let state = crate::startup::test_app_state(tmp.path(), vec![]).await;
+        let broadcast = state.broadcast.clone();
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, crate::router::build_router(state)).await.unwrap();
+        });
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/ws")).await.unwrap();
+        assert_eq!(next_text(&mut client).await["type"], "stats");
+
+        client
+            .send(ClientFrame::Text(r#"{"subscribe": ["insights"]}"#.into()))
+            .await
+            .unwrap();
+        assert_eq!(next_text(&mut client).await["type"], "subscribed");
+
+        broadcast.send(envelope("stats", serde_json::json!({}))).unwrap();
+        broadcast.send(envelope("queue_batch", serde_json::json!({ "docs": 3 }))).unwrap();
+        broadcast.send(envelope("insight", serde_json::json!({ "id": "i-1" }))).unwrap();
+
+        let msg = next_text(&mut client).await;
+        assert_eq!(msg["type"], "insight");
+        assert_eq!(msg["data"]["id"], "i-1");
+    }
+}
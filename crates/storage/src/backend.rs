@@ -1,12 +1,17 @@
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 
 use object_store::aws::AmazonS3Builder;
 use object_store::local::LocalFileSystem;
+#[cfg(any(test, feature = "test-utils"))]
+use object_store::memory::InMemory;
+use object_store::signer::Signer;
 use object_store::ObjectStore;
 use tracing::info;
+use url::Url;
 
-use stupid_core::config::AwsConfig;
+use stupid_core::config::{AwsConfig, DEFAULT_S3_MULTIPART_THRESHOLD_BYTES};
 
 use crate::error::StorageError;
 
@@ -14,6 +19,9 @@ use crate::error::StorageError;
 pub enum StorageBackend {
     Local(LocalBackend),
     S3(S3Backend),
+    /// In-memory object store for tests; nothing is written to disk.
+    #[cfg(any(test, feature = "test-utils"))]
+    Memory(MemoryBackend),
 }
 
 impl StorageBackend {
@@ -22,6 +30,8 @@ impl StorageBackend {
         match self {
             StorageBackend::Local(b) => b.store.as_ref(),
             StorageBackend::S3(b) => b.store.as_ref(),
+            #[cfg(any(test, feature = "test-utils"))]
+            StorageBackend::Memory(b) => b.store.as_ref(),
         }
     }
 
@@ -30,11 +40,14 @@ impl StorageBackend {
         match self {
             StorageBackend::Local(b) => b.store.clone(),
             StorageBackend::S3(b) => b.store.clone(),
+            #[cfg(any(test, feature = "test-utils"))]
+            StorageBackend::Memory(b) => b.store.clone(),
         }
     }
 
+    /// Whether segments live in the object store rather than under `data_dir`.
     pub fn is_remote(&self) -> bool {
-        matches!(self, StorageBackend::S3(_))
+        !matches!(self, StorageBackend::Local(_))
     }
 
     /// S3 key prefix for segments (e.g. "production/segments/").
@@ -42,6 +55,16 @@ impl StorageBackend {
         match self {
             StorageBackend::Local(_) => "",
             StorageBackend::S3(b) => &b.prefix,
+            #[cfg(any(test, feature = "test-utils"))]
+            StorageBackend::Memory(_) => "",
+        }
+    }
+
+    /// Files at least this large are uploaded in parts.
+    pub fn multipart_threshold(&self) -> u64 {
+        match self {
+            StorageBackend::S3(b) => b.multipart_threshold,
+            _ => DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
         }
     }
 }
@@ -55,8 +78,7 @@ pub struct LocalBackend {
 impl LocalBackend {
     pub fn new(data_dir: &std::path::Path) -> Result<Self, StorageError> {
         let canonical = std::fs::canonicalize(data_dir).unwrap_or_else(|_| data_dir.to_path_buf());
-        let store = LocalFileSystem::new_with_prefix(&canonical)
-            .map_err(|e| StorageError::Other(format!("local filesystem error: {e}")))?;
+        let store = LocalFileSystem::new_with_prefix(&canonical)?;
         info!("Storage: local backend at {}", canonical.display());
         Ok(Self {
             store: Arc::new(store),
@@ -65,11 +87,18 @@ impl LocalBackend {
     }
 }
 
+/// Longest validity S3 accepts for a SigV4 presigned URL.
+pub const MAX_PRESIGNED_URL_TTL: Duration = Duration::from_secs(7 * 24 * 3600);
+
 /// S3 backend.
 pub struct S3Backend {
     pub store: Arc<dyn ObjectStore>,
+    /// Signs presigned URLs with the store's credentials.
+    pub signer: Arc<dyn Signer>,
     pub bucket: String,
     pub prefix: String,
+    /// Files at least this large are uploaded in parts.
+    pub multipart_threshold: u64,
 }
 
 impl S3Backend {
@@ -111,7 +140,7 @@ impl S3Backend {
             builder = builder.with_url(&url);
         }
 
-        let store = builder.build()?;
+        let store = Arc::new(builder.build()?);
 
         let prefix = aws
             .s3_prefix
@@ -126,11 +155,69 @@ impl S3Backend {
         );
 
         Ok(Self {
-            store: Arc::new(store),
+            store: store.clone(),
+            signer: store,
             bucket: bucket.to_string(),
             prefix,
+            multipart_threshold: aws.s3_multipart_threshold_bytes,
         })
     }
+
+    /// A URL granting GET on object `key` for `ttl`, without AWS credentials.
+    ///
+    /// `key` is the full object key and must lie under the configured prefix,
+    /// so links can only be handed out for objects this instance manages.
+    /// `ttl` must be non-zero and at most [`MAX_PRESIGNED_URL_TTL`].
+    pub async fn presigned_get_url(&self, key: &str, ttl: Duration) -> Result<Url, StorageError> {
+        if ttl.is_zero() || ttl > MAX_PRESIGNED_URL_TTL {
+            return Err(StorageError::InvalidRequest(format!(
+                "presigned URL TTL must be between 1s and {}s, got {}s",
+                MAX_PRESIGNED_URL_TTL.as_secs(),
+                ttl.as_secs()
+            )));
+        }
+        let path = object_store::path::Path::parse(key)
+            .map_err(|e| StorageError::InvalidRequest(format!("invalid object key '{}': {}", key, e)))?;
+        let prefix = object_store::path::Path::from(self.prefix.as_str());
+        if path.as_ref().is_empty() || path == prefix || !path.prefix_matches(&prefix) {
+            return Err(StorageError::InvalidRequest(format!(
+                "object key '{}' is outside the storage prefix '{}'",
+                key, self.prefix
+            )));
+        }
+        Ok(self.signer.signed_url(reqwest::Method::GET, &path, ttl).await?)
+    }
+
+    /// List the top level of the prefix — the cheapest call that proves the
+    /// bucket is reachable with the configured credentials.
+    pub async fn check(&self) -> Result<(), StorageError> {
+        let prefix = object_store::path::Path::from(self.prefix.as_str());
+        self.store.list_with_delimiter(Some(&prefix)).await?;
+        Ok(())
+    }
+}
+
+/// In-memory backend (test-only).
+#[cfg(any(test, feature = "test-utils"))]
+pub struct MemoryBackend {
+    pub store: Arc<dyn ObjectStore>,
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl MemoryBackend {
+    pub fn new() -> Self {
+        info!("Storage: in-memory backend");
+        Self {
+            store: Arc::new(InMemory::new()),
+        }
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl Default for MemoryBackend {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 #[cfg(test)]
@@ -145,4 +232,95 @@ mod tests {
         assert!(!StorageBackend::Local(backend).is_remote());
         std::fs::remove_dir_all(&tmp).ok();
     }
+
+    /// Signs URLs as `https://signed.example/<key>?X-Amz-Expires=<ttl>`.
+    #[derive(Debug, Default)]
+    struct MockSigner {
+        calls: std::sync::Mutex<Vec<(reqwest::Method, String, Duration)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Signer for MockSigner {
+        async fn signed_url(
+            &self,
+            method: reqwest::Method,
+            path: &object_store::path::Path,
+            expires_in: Duration,
+        ) -> object_store::Result<Url> {
+            self.calls.lock().unwrap().push((method, path.to_string(), expires_in));
+            let url = format!("https://signed.example/{}?X-Amz-Expires={}", path, expires_in.as_secs());
+            Ok(Url::parse(&url).unwrap())
+        }
+    }
+
+    fn mock_s3(prefix: &str) -> (S3Backend, Arc<MockSigner>) {
+        let signer = Arc::new(MockSigner::default());
+        let backend = S3Backend {
+            store: Arc::new(InMemory::new()),
+            signer: signer.clone(),
+            bucket: "analytics".to_string(),
+            prefix: prefix.to_string(),
+            multipart_threshold: DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
+        };
+        (backend, signer)
+    }
+
+    #[tokio::test]
+    async fn presigned_get_url_signs_keys_under_prefix() {
+        let (s3, signer) = mock_s3("prod");
+        let url = s3
+            .presigned_get_url("prod/segments/Login/2025-W24/documents.dat", Duration::from_secs(900))
+            .await
+            .unwrap();
+        assert_eq!(url.host_str(), Some("signed.example"));
+        assert_eq!(url.path(), "/prod/segments/Login/2025-W24/documents.dat");
+        assert_eq!(url.query(), Some("X-Amz-Expires=900"));
+        let calls = signer.calls.lock().unwrap().clone();
+        assert_eq!(
+            calls,
+            vec![(reqwest::Method::GET, "prod/segments/Login/2025-W24/documents.dat".to_string(), Duration::from_secs(900))]
+        );
+
+        for key in ["", "prod", "other/graph/stats.json", "prod-evil/graph/stats.json", "prod/../secret", "prod//x"] {
+            let err = s3.presigned_get_url(key, Duration::from_secs(60)).await.unwrap_err();
+            assert!(matches!(err, StorageError::InvalidRequest(_)), "{key}: {err}");
+        }
+        for ttl in [Duration::ZERO, MAX_PRESIGNED_URL_TTL + Duration::from_secs(1)] {
+            let err = s3.presigned_get_url("prod/graph/stats.json", ttl).await.unwrap_err();
+            assert!(matches!(err, StorageError::InvalidRequest(_)));
+        }
+        assert_eq!(signer.calls.lock().unwrap().len(), 1, "rejected requests must not be signed");
+    }
+
+    #[tokio::test]
+    async fn presigned_get_url_uses_s3_credentials_offline() {
+        let aws = AwsConfig {
+            region: "eu-central-1".to_string(),
+            access_key_id: Some("AKIDEXAMPLE".to_string()),
+            secret_access_key: Some("secret".to_string()),
+            session_token: None,
+            s3_bucket: Some("analytics".to_string()),
+            s3_prefix: Some("prod/".to_string()),
+            endpoint_url: None,
+            s3_multipart_threshold_bytes: DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
+        };
+        let s3 = S3Backend::new(&aws).unwrap();
+        let url = s3
+            .presigned_get_url("prod/graph/stats.json", Duration::from_secs(3600))
+            .await
+            .unwrap();
+        assert!(url.as_str().contains("analytics"), "{url}");
+        assert!(url.path().ends_with("/prod/graph/stats.json"), "{url}");
+        let query: std::collections::HashMap<_, _> = url.query_pairs().into_owned().collect();
+        assert_eq!(query["X-Amz-Expires"], "3600");
+        assert!(query["X-Amz-Credential"].starts_with("AKIDEXAMPLE/"));
+        assert!(query.contains_key("X-Amz-Signature"));
+    }
+
+    #[test]
+    fn memory_backend_is_remote_without_prefix() {
+        let backend = StorageBackend::Memory(MemoryBackend::new());
+        assert!(backend.is_remote());
+        assert_eq!(backend.prefix(), "");
+    }
 }
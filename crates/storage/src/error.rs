@@ -1,3 +1,4 @@
+use stupid_core::BoxError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -12,11 +13,34 @@ pub enum StorageError {
     Core(#[from] stupid_core::StupidError),
 
     #[error("parquet error: {0}")]
-    Parquet(String),
+    Parquet(#[source] BoxError),
+
+    #[error("serialization error: {0}")]
+    Serialize(#[from] serde_json::Error),
 
     #[error("not configured: {0}")]
     NotConfigured(String),
 
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+
     #[error("{0}")]
     Other(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn core_io_error_chain_is_preserved() {
+        let io = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "segments/2025-W01");
+        let err = StorageError::from(stupid_core::StupidError::from(io));
+
+        let chain: Vec<String> = std::iter::successors(err.source(), |e| (*e).source())
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(chain, vec!["IO error: segments/2025-W01", "segments/2025-W01"]);
+    }
+}
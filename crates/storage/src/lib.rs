@@ -1,34 +1,61 @@
 pub mod backend;
 pub mod cache;
 pub mod error;
+pub mod multipart;
 pub mod s3_export;
 pub mod s3_import;
+pub mod upload_queue;
 
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use futures::TryStreamExt;
-use tracing::info;
+use stupid_segment::verify::{repair_segment, verify_segment};
+use stupid_segment::{RepairOutcome, SegmentIssue};
+use tracing::{info, warn};
 
-pub use backend::{LocalBackend, S3Backend, StorageBackend};
+pub use backend::{LocalBackend, S3Backend, StorageBackend, MAX_PRESIGNED_URL_TTL};
+#[cfg(any(test, feature = "test-utils"))]
+pub use backend::MemoryBackend;
 pub use cache::SegmentCache;
 pub use error::StorageError;
+pub use multipart::upload_file;
 pub use s3_export::S3Exporter;
 pub use s3_import::S3Importer;
+pub use upload_queue::{SegmentUploadState, UploadQueue, UploadQueueConfig, UploadStatus};
+
+/// One segment's result from [`StorageEngine::repair`]: its ID and how the
+/// repair went.
+pub type SegmentRepair = (String, Result<RepairOutcome, stupid_core::StupidError>);
 
 /// High-level storage engine: config-driven backend with optional cache.
 pub struct StorageEngine {
     pub backend: StorageBackend,
     pub cache: Option<SegmentCache>,
     pub data_dir: PathBuf,
+    /// Background uploads of finalized segments; `None` for local storage.
+    pub uploads: Option<UploadQueue>,
 }
 
 impl StorageEngine {
-    /// Create a StorageEngine from config. Selects local or S3 based on AwsConfig.
+    /// Create a StorageEngine from config. Selects local or S3 based on AwsConfig,
+    /// or the in-memory backend when `storage.in_memory` is set.
     pub fn from_config(config: &stupid_core::Config) -> Result<Self, StorageError> {
         let data_dir = config.storage.data_dir.clone();
 
-        if config.aws.is_configured() {
+        if config.storage.in_memory {
+            Self::in_memory(data_dir)
+        } else if config.aws.is_configured() {
             let s3 = S3Backend::new(&config.aws)?;
+            let uploads = UploadQueue::new(
+                s3.store.clone(),
+                s3.prefix.clone(),
+                &data_dir,
+                UploadQueueConfig {
+                    multipart_threshold: config.aws.s3_multipart_threshold_bytes,
+                    ..Default::default()
+                },
+            );
             let cache = SegmentCache::new(
                 &config.storage.cache_dir,
                 config.storage.cache_max_gb,
@@ -37,6 +64,7 @@ impl StorageEngine {
                 backend: StorageBackend::S3(s3),
                 cache: Some(cache),
                 data_dir,
+                uploads: Some(uploads),
             })
         } else {
             // Ensure data dir exists for local backend
@@ -46,16 +74,140 @@ impl StorageEngine {
                 backend: StorageBackend::Local(local),
                 cache: None,
                 data_dir,
+                uploads: None,
             })
         }
     }
 
+    #[cfg(any(test, feature = "test-utils"))]
+    fn in_memory(data_dir: PathBuf) -> Result<Self, StorageError> {
+        Ok(Self {
+            backend: StorageBackend::Memory(MemoryBackend::new()),
+            cache: None,
+            data_dir,
+            uploads: None,
+        })
+    }
+
+    #[cfg(not(any(test, feature = "test-utils")))]
+    fn in_memory(_data_dir: PathBuf) -> Result<Self, StorageError> {
+        Err(StorageError::NotConfigured(
+            "in-memory storage requires the stupid-storage `test-utils` feature".into(),
+        ))
+    }
+
+    /// Hand off a segment that has been finalized under `data_dir`.
+    ///
+    /// For S3 the segment is queued for background upload and this returns
+    /// immediately; for local storage it is already in place.
+    pub fn finalize(&self, segment_id: &str) {
+        if let Some(uploads) = &self.uploads {
+            uploads.enqueue(segment_id);
+        }
+    }
+
+    /// Wait for all queued segment uploads to finish (no-op for local storage).
+    pub async fn flush(&self) {
+        if let Some(uploads) = &self.uploads {
+            uploads.flush().await;
+        }
+    }
+
+    /// Progress of background segment uploads, or `None` for local storage.
+    pub fn upload_status(&self) -> Option<UploadStatus> {
+        self.uploads.as_ref().map(UploadQueue::status)
+    }
+
     /// Discover all segment IDs (local or S3).
     pub async fn discover_segments(&self) -> Result<Vec<String>, StorageError> {
         match &self.backend {
             StorageBackend::Local(_) => Ok(discover_local_segments(&self.data_dir)),
             StorageBackend::S3(_) => self.discover_s3_segments().await,
+            #[cfg(any(test, feature = "test-utils"))]
+            StorageBackend::Memory(_) => self.discover_s3_segments().await,
+        }
+    }
+
+    /// Read one file of a segment (e.g. `documents.dat`) straight from the
+    /// backend store, bypassing the cache.
+    pub async fn fetch_segment_file(
+        &self,
+        segment_id: &str,
+        filename: &str,
+    ) -> Result<bytes::Bytes, StorageError> {
+        let key = S3Exporter::s3_key(self.backend.prefix(), segment_id, filename);
+        let path = object_store::path::Path::from(key.as_str());
+        Ok(self.backend.store().get(&path).await?.bytes().await?)
+    }
+
+    /// Object key of one file of a segment, as stored in the backend.
+    pub fn segment_object_key(&self, segment_id: &str, filename: &str) -> String {
+        S3Exporter::s3_key(self.backend.prefix(), segment_id, filename)
+    }
+
+    /// A time-limited download link for object `key` that needs no AWS
+    /// credentials (see [`S3Backend::presigned_get_url`]).
+    ///
+    /// Only the S3 backend can sign URLs; other backends return
+    /// [`StorageError::NotConfigured`].
+    pub async fn presigned_get_url(&self, key: &str, ttl: Duration) -> Result<url::Url, StorageError> {
+        match &self.backend {
+            StorageBackend::S3(s3) => s3.presigned_get_url(key, ttl).await,
+            _ => Err(StorageError::NotConfigured(
+                "presigned URLs require the S3 backend".into(),
+            )),
+        }
+    }
+
+    /// Check every segment for consistency between `documents.dat`,
+    /// `meta.json` and `documents.idx`, returning the problems found.
+    ///
+    /// Remote segments are checked through the local cache, downloading any
+    /// that aren't cached yet.
+    pub async fn verify(&self) -> Result<Vec<SegmentIssue>, StorageError> {
+        let segments = self.discover_segments().await?;
+        let mut issues = Vec::new();
+        for segment_id in &segments {
+            let data_dir = self.segment_data_dir(segment_id).await?;
+            issues.extend(verify_segment(&data_dir, segment_id));
         }
+        info!("Verified {} segments: {} issues", segments.len(), issues.len());
+        Ok(issues)
+    }
+
+    /// Repair each segment named in `issues` by rebuilding its metadata and
+    /// indexes from the documents that can still be read (see
+    /// [`repair_segment`]). Only local storage can be repaired in place.
+    ///
+    /// Returns each segment's outcome; a failed repair doesn't stop the rest.
+    pub fn repair(
+        &self,
+        issues: &[SegmentIssue],
+    ) -> Result<Vec<SegmentRepair>, StorageError> {
+        if !matches!(self.backend, StorageBackend::Local(_)) {
+            return Err(StorageError::NotConfigured(
+                "segment repair only works on local storage".into(),
+            ));
+        }
+
+        let mut segment_ids: Vec<&str> = issues.iter().map(|i| i.segment_id.as_str()).collect();
+        segment_ids.sort_unstable();
+        segment_ids.dedup();
+
+        Ok(segment_ids
+            .into_iter()
+            .map(|segment_id| {
+                let outcome = repair_segment(&self.data_dir, segment_id);
+                match &outcome {
+                    Ok(o) => info!(
+                        "Repaired segment {}: {} documents kept, {} dropped",
+                        segment_id, o.documents_kept, o.documents_dropped
+                    ),
+                    Err(e) => warn!("Failed to repair segment {}: {}", segment_id, e),
+                }
+                (segment_id.to_string(), outcome)
+            })
+            .collect())
     }
 
     /// Discover segments from S3 by listing objects under segments/ prefix.
@@ -115,6 +267,10 @@ impl StorageEngine {
             (StorageBackend::S3(_), None) => Err(StorageError::NotConfigured(
                 "S3 backend requires cache to be configured".into(),
             )),
+            #[cfg(any(test, feature = "test-utils"))]
+            (StorageBackend::Memory(_), _) => Err(StorageError::NotConfigured(
+                "in-memory backend has no segment directory; use fetch_segment_file".into(),
+            )),
         }
     }
 }
@@ -158,4 +314,99 @@ mod tests {
         assert!(segments.is_empty());
         std::fs::remove_dir_all(&tmp).ok();
     }
+
+    #[tokio::test]
+    async fn verify_and_repair_truncated_local_segment() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut config = stupid_core::Config::from_env();
+        config.storage.data_dir = tmp.path().to_path_buf();
+        config.storage.in_memory = false;
+        config.aws.s3_bucket = None;
+        let engine = StorageEngine::from_config(&config).unwrap();
+
+        for segment_id in ["Login/2025-W24", "Login/2025-W25"] {
+            let mut writer = stupid_segment::writer::SegmentWriter::new(tmp.path(), segment_id).unwrap();
+            for n in 0..4000 {
+                let doc = stupid_core::Document {
+                    id: uuid::Uuid::new_v4(),
+                    timestamp: chrono::Utc::now(),
+                    event_type: "Login".to_string(),
+                    fields: [("n".to_string(), stupid_core::FieldValue::Text(format!("{n:0>128}")))]
+                        .into_iter()
+                        .collect(),
+                };
+                writer.append(&doc).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+        assert!(engine.verify().await.unwrap().is_empty());
+
+        let dat = tmp.path().join("segments/Login/2025-W25/documents.dat");
+        let bytes = std::fs::read(&dat).unwrap();
+        std::fs::write(&dat, &bytes[..bytes.len() / 2]).unwrap();
+
+        let issues = engine.verify().await.unwrap();
+        assert!(!issues.is_empty());
+        assert!(issues.iter().all(|i| i.segment_id == "Login/2025-W25"));
+
+        let repaired = engine.repair(&issues).unwrap();
+        assert_eq!(repaired.len(), 1);
+        assert!(repaired[0].1.as_ref().unwrap().data_rewritten);
+        assert!(engine.verify().await.unwrap().is_empty());
+    }
+
+    fn memory_config(data_dir: PathBuf) -> stupid_core::Config {
+        let mut config = stupid_core::Config::from_env();
+        config.storage.data_dir = data_dir.clone();
+        config.storage.cache_dir = data_dir.join("cache");
+        config.storage.in_memory = true;
+        config
+    }
+
+    #[tokio::test]
+    async fn memory_backend_discover_fetch_export_without_disk() {
+        let data_dir = std::env::temp_dir().join(format!("stupid-storage-memory-{}", uuid::Uuid::new_v4()));
+        let engine = StorageEngine::from_config(&memory_config(data_dir.clone())).unwrap();
+        assert!(matches!(engine.backend, StorageBackend::Memory(_)));
+        assert!(engine.discover_segments().await.unwrap().is_empty());
+
+        let files = vec![
+            ("segments/Login/2025-W24/documents.dat".to_string(), b"docs-a".to_vec()),
+            ("segments/Login/2025-W24/meta.json".to_string(), b"{}".to_vec()),
+            ("segments/Bet/2025-W24/documents.dat".to_string(), b"docs-b".to_vec()),
+        ];
+        let uploaded = S3Exporter::upload_files_parallel(&engine.backend, files, 2)
+            .await
+            .unwrap();
+        assert_eq!(uploaded, 3);
+        S3Exporter::export_graph(&engine.backend, &serde_json::json!({"nodes": 3}))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            engine.discover_segments().await.unwrap(),
+            vec!["Bet/2025-W24", "Login/2025-W24"]
+        );
+        let docs = engine
+            .fetch_segment_file("Login/2025-W24", "documents.dat")
+            .await
+            .unwrap();
+        assert_eq!(&docs[..], b"docs-a");
+        assert!(engine.fetch_segment_file("Bet/2025-W24", "meta.json").await.is_err());
+
+        // Already-exported segments are skipped without reading data_dir.
+        let ids = vec!["Login/2025-W24".to_string()];
+        let (uploaded, skipped) = S3Exporter::export_segments(&engine.backend, &data_dir, &ids)
+            .await
+            .unwrap();
+        assert_eq!((uploaded, skipped), (0, 1));
+
+        assert!(engine.segment_data_dir("Login/2025-W24").await.is_err());
+        let key = engine.segment_object_key("Login/2025-W24", "documents.dat");
+        assert!(matches!(
+            engine.presigned_get_url(&key, Duration::from_secs(60)).await,
+            Err(StorageError::NotConfigured(_))
+        ));
+        assert!(!data_dir.exists(), "memory backend must not touch the filesystem");
+    }
 }
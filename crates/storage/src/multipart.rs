@@ -0,0 +1,272 @@
+//! Uploads of local files to the object store.
+//!
+//! Files below a size threshold go up in a single put. Larger ones use a
+//! multipart upload: the file is streamed in fixed-size parts with several
+//! in flight at once, which also lifts S3's 5 GiB limit on a single put. A
+//! multipart upload that fails is aborted, so S3 doesn't keep (and bill
+//! for) its orphaned parts.
+
+use std::path::Path as LocalPath;
+
+use object_store::path::Path;
+use object_store::{MultipartUpload, ObjectStore};
+use tokio::io::AsyncReadExt;
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+
+use crate::error::StorageError;
+
+/// Size of each part but the last. S3 requires at least 5 MiB and allows at
+/// most 10,000 parts, so this covers objects up to ~160 GiB.
+const PART_SIZE: usize = 16 * 1024 * 1024;
+
+/// Parts uploaded concurrently per file.
+const PART_CONCURRENCY: usize = 8;
+
+/// Upload `local_path` to `location`, using a multipart upload when the file
+/// is at least `multipart_threshold` bytes (see
+/// [`AwsConfig::s3_multipart_threshold_bytes`](stupid_core::config::AwsConfig)).
+pub async fn upload_file(
+    store: &dyn ObjectStore,
+    location: &Path,
+    local_path: &LocalPath,
+    multipart_threshold: u64,
+) -> Result<(), StorageError> {
+    upload_file_in_parts(store, location, local_path, multipart_threshold, PART_SIZE).await
+}
+
+async fn upload_file_in_parts(
+    store: &dyn ObjectStore,
+    location: &Path,
+    local_path: &LocalPath,
+    multipart_threshold: u64,
+    part_size: usize,
+) -> Result<(), StorageError> {
+    let len = tokio::fs::metadata(local_path).await?.len();
+    if len < multipart_threshold {
+        let data = tokio::fs::read(local_path).await?;
+        store.put(location, bytes::Bytes::from(data).into()).await?;
+        return Ok(());
+    }
+
+    debug!("Multipart upload of {} ({} bytes) to {}", local_path.display(), len, location);
+    let mut upload = store.put_multipart(location).await?;
+    if let Err(e) = upload_parts(upload.as_mut(), local_path, part_size).await {
+        if let Err(abort_err) = upload.abort().await {
+            warn!("Failed to abort multipart upload to {}: {}", location, abort_err);
+        }
+        return Err(e);
+    }
+    if let Err(e) = upload.complete().await {
+        if let Err(abort_err) = upload.abort().await {
+            warn!("Failed to abort multipart upload to {}: {}", location, abort_err);
+        }
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Stream the file into `upload` in `part_size` parts, keeping up to
+/// [`PART_CONCURRENCY`] in flight. Returns once every part is uploaded.
+async fn upload_parts(
+    upload: &mut dyn MultipartUpload,
+    local_path: &LocalPath,
+    part_size: usize,
+) -> Result<(), StorageError> {
+    let mut file = tokio::fs::File::open(local_path).await?;
+    // Dropping the set on error cancels the parts still in flight.
+    let mut in_flight = JoinSet::new();
+    loop {
+        while in_flight.len() >= PART_CONCURRENCY {
+            join_part(&mut in_flight).await?;
+        }
+        let mut part = Vec::with_capacity(part_size);
+        (&mut file).take(part_size as u64).read_to_end(&mut part).await?;
+        if part.is_empty() {
+            break;
+        }
+        in_flight.spawn(upload.put_part(bytes::Bytes::from(part).into()));
+    }
+    while !in_flight.is_empty() {
+        join_part(&mut in_flight).await?;
+    }
+    Ok(())
+}
+
+async fn join_part(in_flight: &mut JoinSet<object_store::Result<()>>) -> Result<(), StorageError> {
+    match in_flight.join_next().await {
+        Some(Ok(result)) => Ok(result?),
+        Some(Err(e)) => Err(StorageError::Other(format!("part upload task failed: {}", e))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use async_trait::async_trait;
+    use futures::stream::BoxStream;
+    use object_store::memory::InMemory;
+    use object_store::{
+        GetOptions, GetResult, ListResult, ObjectMeta, PutMultipartOpts, PutOptions, PutPayload,
+        PutResult, UploadPart,
+    };
+
+    /// In-memory store recording which upload path each object took.
+    #[derive(Debug, Default)]
+    struct RecordingStore {
+        inner: InMemory,
+        puts: AtomicUsize,
+        multipart: Arc<MultipartCounts>,
+        /// Fail the part with this index (0-based) of every multipart upload.
+        fail_part: Option<usize>,
+    }
+
+    #[derive(Debug, Default)]
+    struct MultipartCounts {
+        uploads: AtomicUsize,
+        parts: AtomicUsize,
+        aborted: AtomicBool,
+    }
+
+    #[derive(Debug)]
+    struct RecordingUpload {
+        inner: Box<dyn MultipartUpload>,
+        counts: Arc<MultipartCounts>,
+        next_part: usize,
+        fail_part: Option<usize>,
+    }
+
+    #[async_trait]
+    impl MultipartUpload for RecordingUpload {
+        fn put_part(&mut self, data: PutPayload) -> UploadPart {
+            let index = self.next_part;
+            self.next_part += 1;
+            self.counts.parts.fetch_add(1, Ordering::SeqCst);
+            if self.fail_part == Some(index) {
+                return Box::pin(async {
+                    Err(object_store::Error::Generic {
+                        store: "RecordingStore",
+                        source: "connection reset".into(),
+                    })
+                });
+            }
+            self.inner.put_part(data)
+        }
+
+        async fn complete(&mut self) -> object_store::Result<PutResult> {
+            self.inner.complete().await
+        }
+
+        async fn abort(&mut self) -> object_store::Result<()> {
+            self.counts.aborted.store(true, Ordering::SeqCst);
+            self.inner.abort().await
+        }
+    }
+
+    impl std::fmt::Display for RecordingStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "RecordingStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for RecordingStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> object_store::Result<PutResult> {
+            self.puts.fetch_add(1, Ordering::SeqCst);
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> object_store::Result<Box<dyn MultipartUpload>> {
+            self.multipart.uploads.fetch_add(1, Ordering::SeqCst);
+            Ok(Box::new(RecordingUpload {
+                inner: self.inner.put_multipart_opts(location, opts).await?,
+                counts: self.multipart.clone(),
+                next_part: 0,
+                fail_part: self.fail_part,
+            }))
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> object_store::Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> object_store::Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, object_store::Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    fn write_file(dir: &std::path::Path, name: &str, len: usize) -> std::path::PathBuf {
+        let path = dir.join(name);
+        let data: Vec<u8> = (0..len).map(|i| (i % 251) as u8).collect();
+        std::fs::write(&path, data).unwrap();
+        path
+    }
+
+    #[tokio::test]
+    async fn small_files_use_a_single_put_and_large_files_multipart() {
+        let tmp = tempfile::tempdir().unwrap();
+        let small = write_file(tmp.path(), "meta.json", 999);
+        let large = write_file(tmp.path(), "documents.dat", 10_000);
+        let store = RecordingStore::default();
+
+        upload_file_in_parts(&store, &Path::from("seg/meta.json"), &small, 1000, 4096).await.unwrap();
+        assert_eq!(store.puts.load(Ordering::SeqCst), 1);
+        assert_eq!(store.multipart.uploads.load(Ordering::SeqCst), 0);
+
+        upload_file_in_parts(&store, &Path::from("seg/documents.dat"), &large, 1000, 4096).await.unwrap();
+        assert_eq!(store.puts.load(Ordering::SeqCst), 1);
+        assert_eq!(store.multipart.uploads.load(Ordering::SeqCst), 1);
+        assert_eq!(store.multipart.parts.load(Ordering::SeqCst), 3);
+        assert!(!store.multipart.aborted.load(Ordering::SeqCst));
+
+        for (key, local) in [("seg/meta.json", &small), ("seg/documents.dat", &large)] {
+            let uploaded = store.get(&Path::from(key)).await.unwrap().bytes().await.unwrap();
+            assert_eq!(uploaded.as_ref(), std::fs::read(local).unwrap().as_slice(), "{key}");
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_part_aborts_the_upload() {
+        let tmp = tempfile::tempdir().unwrap();
+        let large = write_file(tmp.path(), "documents.dat", 10_000);
+        let store = RecordingStore {
+            fail_part: Some(1),
+            ..Default::default()
+        };
+
+        let location = Path::from("seg/documents.dat");
+        let err = upload_file_in_parts(&store, &location, &large, 1000, 4096).await.unwrap_err();
+        assert!(err.to_string().contains("connection reset"), "{err}");
+        assert!(store.multipart.aborted.load(Ordering::SeqCst));
+        assert!(store.head(&location).await.is_err(), "aborted upload must not be visible");
+    }
+}
@@ -6,13 +6,15 @@ use tracing::info;
 
 use crate::backend::StorageBackend;
 use crate::error::StorageError;
+use crate::multipart::upload_file;
 
 /// Export local segments and graph data to S3.
 pub struct S3Exporter;
 
 impl S3Exporter {
     /// Upload local segments to S3.
-    /// Skips segments that already exist in S3 (incremental).
+    /// Skips segments that already exist in S3 (incremental). Files over the
+    /// backend's multipart threshold are uploaded in parts.
     pub async fn export_segments(
         backend: &StorageBackend,
         data_dir: &Path,
@@ -20,6 +22,7 @@ impl S3Exporter {
     ) -> Result<(usize, usize), StorageError> {
         let store = backend.store();
         let prefix = backend.prefix();
+        let multipart_threshold = backend.multipart_threshold();
         let start = std::time::Instant::now();
 
         let mut uploaded = 0usize;
@@ -43,14 +46,9 @@ impl S3Exporter {
                     continue;
                 }
 
-                let data = tokio::fs::read(&local_path)
-                    .await
-                    .map_err(StorageError::Io)?;
                 let key = Self::s3_key(prefix, segment_id, filename);
                 let path = object_store::path::Path::from(key.as_str());
-                store
-                    .put(&path, bytes::Bytes::from(data).into())
-                    .await?;
+                upload_file(store, &path, &local_path, multipart_threshold).await?;
             }
 
             uploaded += 1;
@@ -85,8 +83,7 @@ impl S3Exporter {
         let store = backend.store();
         let prefix = backend.prefix();
 
-        let json = serde_json::to_string_pretty(graph_stats)
-            .map_err(|e| StorageError::Other(e.to_string()))?;
+        let json = serde_json::to_string_pretty(graph_stats)?;
 
         let key = if prefix.is_empty() {
             "graph/stats.json".to_string()
@@ -136,7 +133,7 @@ impl S3Exporter {
         Ok(success)
     }
 
-    fn s3_key(prefix: &str, segment_id: &str, filename: &str) -> String {
+    pub(crate) fn s3_key(prefix: &str, segment_id: &str, filename: &str) -> String {
         if prefix.is_empty() {
             format!("segments/{}/{}", segment_id, filename)
         } else {
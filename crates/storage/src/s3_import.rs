@@ -4,15 +4,17 @@ use std::sync::atomic::{AtomicU64, Ordering};
 
 use arrow::array::{Array, StringArray};
 use bytes::Bytes;
-use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 use tracing::info;
 
-use stupid_core::{DocId, Document, FieldValue};
+use stupid_core::{is_null_sentinel, iso_week_label, DocId, Document, FieldValue};
+use stupid_segment::writer::WriteSource;
 
 use crate::backend::StorageBackend;
 use crate::error::StorageError;
+use crate::upload_queue::UploadQueue;
 
 /// S3 parquet import: list, download, and convert parquet files from S3 into local segments.
 pub struct S3Importer;
@@ -22,6 +24,7 @@ pub struct S3Importer;
 pub struct S3ParquetFile {
     pub key: String,
     pub size: usize,
+    pub last_modified: DateTime<Utc>,
     pub event_type: String,
     pub date_stem: String,
 }
@@ -62,6 +65,7 @@ impl S3Importer {
                 files.push(S3ParquetFile {
                     key,
                     size: meta.size,
+                    last_modified: meta.last_modified,
                     event_type,
                     date_stem,
                 });
@@ -96,10 +100,15 @@ impl S3Importer {
     }
 
     /// Import all parquet files from S3 prefix into local segments.
+    ///
+    /// When `uploads` is given, each segment is queued for upload as soon as
+    /// it is finalized; callers flush the queue before exiting. Segments an
+    /// interrupted run left unfinished are resumed rather than rewritten.
     pub async fn import_all(
         backend: &StorageBackend,
         prefix: &str,
         data_dir: &Path,
+        uploads: Option<&UploadQueue>,
     ) -> Result<(u64, usize), StorageError> {
         let files = Self::list_parquet(backend, prefix).await?;
         if files.is_empty() {
@@ -122,11 +131,16 @@ impl S3Importer {
         let store = backend.store();
 
         for group in &groups {
+            let source = group.files.iter().fold(WriteSource::default(), |source, file| {
+                source.add(&file.key, file.size as u64, file.last_modified.to_rfc3339())
+            });
             let mut writer =
-                stupid_segment::writer::SegmentWriter::new(data_dir, &group.segment_id)
+                stupid_segment::writer::SegmentWriter::resume(data_dir, &group.segment_id, &source)
                     .map_err(StorageError::Core)?;
 
             let mut group_docs = 0u64;
+            // Documents a previous run committed, skipped across the group's files.
+            let mut skip = writer.resumed_docs() as usize;
 
             for file in &group.files {
                 let path = object_store::path::Path::from(file.key.as_str());
@@ -136,13 +150,18 @@ impl S3Importer {
                 let documents =
                     parquet_bytes_to_documents(&data, &group.event_type)?;
 
-                for doc in &documents {
+                let skipped = skip.min(documents.len());
+                skip -= skipped;
+                for doc in &documents[skipped..] {
                     writer.append(doc).map_err(StorageError::Core)?;
                 }
                 group_docs += documents.len() as u64;
             }
 
             writer.finalize().map_err(StorageError::Core)?;
+            if let Some(uploads) = uploads {
+                uploads.enqueue(&group.segment_id);
+            }
             total_docs.fetch_add(group_docs, Ordering::Relaxed);
             let done = completed.fetch_add(1, Ordering::Relaxed) + 1;
 
@@ -195,15 +214,15 @@ pub fn parquet_bytes_to_documents(
 ) -> Result<Vec<Document>, StorageError> {
     let bytes = Bytes::copy_from_slice(data);
     let builder = ParquetRecordBatchReaderBuilder::try_new(bytes)
-        .map_err(|e| StorageError::Parquet(e.to_string()))?;
+        .map_err(|e| StorageError::Parquet(e.into()))?;
     let reader = builder
         .build()
-        .map_err(|e| StorageError::Parquet(e.to_string()))?;
+        .map_err(|e| StorageError::Parquet(e.into()))?;
 
     let mut documents = Vec::new();
 
     for batch_result in reader {
-        let batch = batch_result.map_err(|e| StorageError::Parquet(e.to_string()))?;
+        let batch = batch_result.map_err(|e| StorageError::Parquet(e.into()))?;
         let schema = batch.schema();
         let num_rows = batch.num_rows();
 
@@ -229,11 +248,11 @@ pub fn parquet_bytes_to_documents(
                     continue;
                 }
                 let val = arr.value(row_idx).trim();
-                if val.is_empty() || val == "None" || val == "null" || val == "undefined" {
+                if is_null_sentinel(val) {
                     continue;
                 }
                 if col_name == "@timestamp" {
-                    if let Ok(ts) = val.parse::<DateTime<Utc>>() {
+                    if let Some(ts) = Document::parse_timestamp(val) {
                         timestamp = Some(ts);
                     }
                 }
@@ -254,11 +273,9 @@ pub fn parquet_bytes_to_documents(
 
 /// Parse ISO week from a date-like string (e.g., "2025-06-14" -> "2025-W24").
 fn date_to_iso_week(date_stem: &str) -> String {
-    if let Ok(date) = NaiveDate::parse_from_str(date_stem, "%Y-%m-%d") {
-        let iso = date.iso_week();
-        format!("{}-W{:02}", iso.year(), iso.week())
-    } else {
-        "misc".to_string()
+    match Document::parse_timestamp(date_stem) {
+        Some(ts) => iso_week_label(&ts),
+        None => "misc".to_string(),
     }
 }
 
@@ -289,6 +306,7 @@ mod tests {
     #[test]
     fn iso_week_parsing() {
         assert_eq!(date_to_iso_week("2025-06-14"), "2025-W24");
+        assert_eq!(date_to_iso_week("2024-12-30"), "2025-W01");
         assert_eq!(date_to_iso_week("invalid"), "misc");
     }
 }
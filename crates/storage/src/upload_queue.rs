@@ -0,0 +1,437 @@
+//! Background segment uploads for remote backends.
+//!
+//! [`UploadQueue::enqueue`] returns immediately; a fixed pool of workers
+//! copies each finalized segment's files to the object store, retrying
+//! failed puts with exponential backoff. [`UploadQueue::flush`] waits for
+//! everything queued so far, and [`UploadQueue::status`] reports progress
+//! and segments that exhausted their retries.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use object_store::ObjectStore;
+use serde::Serialize;
+use stupid_core::config::DEFAULT_S3_MULTIPART_THRESHOLD_BYTES;
+use tokio::sync::{mpsc, watch};
+use tracing::{debug, info, warn};
+
+use crate::error::StorageError;
+use crate::multipart::upload_file;
+use crate::s3_export::S3Exporter;
+
+/// Files copied for each segment, matching [`S3Exporter::export_segments`].
+const SEGMENT_FILES: [&str; 2] = ["documents.dat", "meta.json"];
+
+/// Worker pool size and retry policy for an [`UploadQueue`].
+#[derive(Debug, Clone)]
+pub struct UploadQueueConfig {
+    /// Number of segments uploaded concurrently.
+    pub workers: usize,
+    /// Attempts per segment before it is reported as failed.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled on each further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the retry delay.
+    pub max_backoff: Duration,
+    /// Files at least this large are uploaded in parts.
+    pub multipart_threshold: u64,
+}
+
+impl Default for UploadQueueConfig {
+    fn default() -> Self {
+        Self {
+            workers: 4,
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            multipart_threshold: DEFAULT_S3_MULTIPART_THRESHOLD_BYTES,
+        }
+    }
+}
+
+/// Upload state of a single segment.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum SegmentUploadState {
+    Queued,
+    Uploading { attempt: u32 },
+    Uploaded { attempts: u32 },
+    Failed { attempts: u32, error: String },
+}
+
+/// Snapshot of the queue returned by [`UploadQueue::status`].
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UploadStatus {
+    /// Segments enqueued but not yet finished (queued or uploading).
+    pub pending: usize,
+    pub uploaded: usize,
+    /// Per-segment state, keyed by segment ID.
+    pub segments: BTreeMap<String, SegmentUploadState>,
+}
+
+impl UploadStatus {
+    /// Segments that exhausted their retries, with the last error.
+    pub fn failed(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.segments.iter().filter_map(|(id, state)| match state {
+            SegmentUploadState::Failed { error, .. } => Some((id.as_str(), error.as_str())),
+            _ => None,
+        })
+    }
+}
+
+struct Shared {
+    store: Arc<dyn ObjectStore>,
+    prefix: String,
+    data_dir: PathBuf,
+    config: UploadQueueConfig,
+    states: Mutex<BTreeMap<String, SegmentUploadState>>,
+    /// Segments enqueued and not yet uploaded or failed.
+    pending: watch::Sender<usize>,
+}
+
+/// Queue of finalized segments waiting to be copied to the object store.
+pub struct UploadQueue {
+    shared: Arc<Shared>,
+    sender: mpsc::UnboundedSender<String>,
+    /// Taken by the first `enqueue`, which spawns the workers.
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<String>>>,
+}
+
+impl UploadQueue {
+    /// Create a queue uploading segments from `data_dir/segments/` to
+    /// `store` under `prefix`. Workers start on the first enqueue.
+    pub fn new(
+        store: Arc<dyn ObjectStore>,
+        prefix: impl Into<String>,
+        data_dir: impl Into<PathBuf>,
+        config: UploadQueueConfig,
+    ) -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            shared: Arc::new(Shared {
+                store,
+                prefix: prefix.into(),
+                data_dir: data_dir.into(),
+                config,
+                states: Mutex::new(BTreeMap::new()),
+                pending: watch::channel(0).0,
+            }),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+
+    /// Queue a finalized segment for upload and return immediately.
+    ///
+    /// Must be called from within a tokio runtime. Re-enqueueing a segment
+    /// that is already queued or uploading is a no-op.
+    pub fn enqueue(&self, segment_id: &str) {
+        {
+            let mut states = self.shared.states.lock().expect("upload states lock");
+            if matches!(
+                states.get(segment_id),
+                Some(SegmentUploadState::Queued | SegmentUploadState::Uploading { .. })
+            ) {
+                return;
+            }
+            states.insert(segment_id.to_string(), SegmentUploadState::Queued);
+        }
+        self.shared.pending.send_modify(|n| *n += 1);
+        self.start_workers();
+
+        if self.sender.send(segment_id.to_string()).is_err() {
+            // Only possible if every worker has exited, which they never do
+            // while the queue is alive.
+            self.shared.finish(segment_id, SegmentUploadState::Failed {
+                attempts: 0,
+                error: "upload workers stopped".to_string(),
+            });
+        }
+    }
+
+    /// Wait until every segment enqueued so far is uploaded or has failed.
+    pub async fn flush(&self) {
+        let mut pending = self.shared.pending.subscribe();
+        // The sender lives in `self`, so the channel cannot close here.
+        let _ = pending.wait_for(|n| *n == 0).await;
+    }
+
+    /// Current queue progress and per-segment upload state.
+    pub fn status(&self) -> UploadStatus {
+        let states = self.shared.states.lock().expect("upload states lock");
+        let mut status = UploadStatus {
+            segments: states.clone(),
+            ..Default::default()
+        };
+        for state in states.values() {
+            match state {
+                SegmentUploadState::Queued | SegmentUploadState::Uploading { .. } => {
+                    status.pending += 1
+                }
+                SegmentUploadState::Uploaded { .. } => status.uploaded += 1,
+                SegmentUploadState::Failed { .. } => {}
+            }
+        }
+        status
+    }
+
+    fn start_workers(&self) {
+        let Some(receiver) = self.receiver.lock().expect("upload receiver lock").take() else {
+            return;
+        };
+        let receiver = Arc::new(tokio::sync::Mutex::new(receiver));
+        let workers = self.shared.config.workers.max(1);
+        for _ in 0..workers {
+            let shared = self.shared.clone();
+            let receiver = receiver.clone();
+            tokio::spawn(async move {
+                loop {
+                    let next = receiver.lock().await.recv().await;
+                    let Some(segment_id) = next else {
+                        break;
+                    };
+                    shared.upload_with_retry(&segment_id).await;
+                }
+            });
+        }
+        debug!("Started {} segment upload workers", workers);
+    }
+}
+
+impl Shared {
+    async fn upload_with_retry(&self, segment_id: &str) {
+        let mut backoff = self.config.initial_backoff;
+        let mut attempt = 1;
+        loop {
+            self.set_state(segment_id, SegmentUploadState::Uploading { attempt });
+            match self.upload(segment_id).await {
+                Ok(()) => {
+                    info!("Uploaded segment '{}' (attempt {})", segment_id, attempt);
+                    self.finish(segment_id, SegmentUploadState::Uploaded { attempts: attempt });
+                    return;
+                }
+                Err(e) if attempt < self.config.max_attempts => {
+                    warn!(
+                        "Upload of segment '{}' failed (attempt {}), retrying in {:?}: {}",
+                        segment_id, attempt, backoff, e
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(self.config.max_backoff);
+                    attempt += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        "Upload of segment '{}' failed after {} attempts: {}",
+                        segment_id, attempt, e
+                    );
+                    self.finish(segment_id, SegmentUploadState::Failed {
+                        attempts: attempt,
+                        error: e.to_string(),
+                    });
+                    return;
+                }
+            }
+        }
+    }
+
+    async fn upload(&self, segment_id: &str) -> Result<(), StorageError> {
+        let seg_dir = self.data_dir.join("segments").join(segment_id);
+        for filename in SEGMENT_FILES {
+            let local_path = seg_dir.join(filename);
+            if !local_path.exists() {
+                continue;
+            }
+            let key = S3Exporter::s3_key(&self.prefix, segment_id, filename);
+            let path = object_store::path::Path::from(key.as_str());
+            upload_file(self.store.as_ref(), &path, &local_path, self.config.multipart_threshold).await?;
+        }
+        Ok(())
+    }
+
+    fn set_state(&self, segment_id: &str, state: SegmentUploadState) {
+        self.states
+            .lock()
+            .expect("upload states lock")
+            .insert(segment_id.to_string(), state);
+    }
+
+    /// Record a terminal state and release one pending slot.
+    fn finish(&self, segment_id: &str, state: SegmentUploadState) {
+        self.set_state(segment_id, state);
+        self.pending.send_modify(|n| *n = n.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use async_trait::async_trait;
+    use futures::stream::BoxStream;
+    use object_store::memory::InMemory;
+    use object_store::path::Path;
+    use object_store::{
+        GetOptions, GetResult, ListResult, MultipartUpload, ObjectMeta, PutMultipartOpts,
+        PutOptions, PutPayload, PutResult,
+    };
+
+    /// In-memory store whose first `failures` puts return an error.
+    #[derive(Debug)]
+    struct FlakyStore {
+        inner: InMemory,
+        failures: AtomicUsize,
+    }
+
+    impl std::fmt::Display for FlakyStore {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "FlakyStore")
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for FlakyStore {
+        async fn put_opts(
+            &self,
+            location: &Path,
+            payload: PutPayload,
+            opts: PutOptions,
+        ) -> object_store::Result<PutResult> {
+            let remaining = self.failures.load(Ordering::SeqCst);
+            if remaining > 0 {
+                self.failures.store(remaining - 1, Ordering::SeqCst);
+                return Err(object_store::Error::Generic {
+                    store: "FlakyStore",
+                    source: "connection reset".into(),
+                });
+            }
+            self.inner.put_opts(location, payload, opts).await
+        }
+
+        async fn put_multipart_opts(
+            &self,
+            location: &Path,
+            opts: PutMultipartOpts,
+        ) -> object_store::Result<Box<dyn MultipartUpload>> {
+            self.inner.put_multipart_opts(location, opts).await
+        }
+
+        async fn get_opts(&self, location: &Path, options: GetOptions) -> object_store::Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn delete(&self, location: &Path) -> object_store::Result<()> {
+            self.inner.delete(location).await
+        }
+
+        fn list(&self, prefix: Option<&Path>) -> BoxStream<'_, object_store::Result<ObjectMeta>> {
+            self.inner.list(prefix)
+        }
+
+        async fn list_with_delimiter(&self, prefix: Option<&Path>) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    fn write_segments(data_dir: &std::path::Path, ids: &[&str]) {
+        for id in ids {
+            let dir = data_dir.join("segments").join(id);
+            std::fs::create_dir_all(&dir).unwrap();
+            std::fs::write(dir.join("documents.dat"), format!("docs {id}")).unwrap();
+            std::fs::write(dir.join("meta.json"), "{}").unwrap();
+        }
+    }
+
+    fn fast_retries(workers: usize) -> UploadQueueConfig {
+        UploadQueueConfig {
+            workers,
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn enqueue_then_flush_uploads_everything() {
+        let tmp = tempfile::tempdir().unwrap();
+        let ids = ["Login/2025-W01", "Login/2025-W02", "GameOpened/2025-W01"];
+        write_segments(tmp.path(), &ids);
+
+        let store = Arc::new(InMemory::new());
+        let queue = UploadQueue::new(store.clone(), "prod", tmp.path(), fast_retries(2));
+        for id in ids {
+            queue.enqueue(id);
+        }
+        queue.flush().await;
+
+        let status = queue.status();
+        assert_eq!(status.pending, 0);
+        assert_eq!(status.uploaded, 3);
+        for id in ids {
+            let path = Path::from(format!("prod/segments/{id}/documents.dat"));
+            let bytes = store.get(&path).await.unwrap().bytes().await.unwrap();
+            assert_eq!(bytes.as_ref(), format!("docs {id}").as_bytes());
+            assert!(store.head(&Path::from(format!("prod/segments/{id}/meta.json"))).await.is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn transient_failure_is_retried() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_segments(tmp.path(), &["Login/2025-W01"]);
+
+        let store = Arc::new(FlakyStore {
+            inner: InMemory::new(),
+            failures: AtomicUsize::new(1),
+        });
+        let queue = UploadQueue::new(store.clone(), "", tmp.path(), fast_retries(1));
+        queue.enqueue("Login/2025-W01");
+        queue.flush().await;
+
+        let status = queue.status();
+        assert_eq!(
+            status.segments["Login/2025-W01"],
+            SegmentUploadState::Uploaded { attempts: 2 }
+        );
+        assert!(store
+            .head(&Path::from("segments/Login/2025-W01/documents.dat"))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn exhausted_retries_surface_in_status() {
+        let tmp = tempfile::tempdir().unwrap();
+        write_segments(tmp.path(), &["Login/2025-W01"]);
+
+        let store = Arc::new(FlakyStore {
+            inner: InMemory::new(),
+            failures: AtomicUsize::new(usize::MAX),
+        });
+        let queue = UploadQueue::new(store, "", tmp.path(), fast_retries(1));
+        queue.enqueue("Login/2025-W01");
+        queue.flush().await;
+
+        let status = queue.status();
+        assert_eq!(status.pending, 0);
+        let failed: Vec<_> = status.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].0, "Login/2025-W01");
+        assert!(failed[0].1.contains("connection reset"));
+        assert!(matches!(
+            status.segments["Login/2025-W01"],
+            SegmentUploadState::Failed { attempts: 3, .. }
+        ));
+    }
+}
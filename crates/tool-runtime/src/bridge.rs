@@ -15,6 +15,7 @@ use std::pin::Pin;
 use crate::conversation::ConversationMessage;
 use crate::provider::{LlmError, ToolAwareLlmProvider};
 use crate::stream::{StopReason, StreamEvent};
+use crate::tokens::{estimate_tokens, TokenCounter, DEFAULT_CONTEXT_WINDOW};
 use crate::tool::ToolDefinition;
 
 /// A simple chat message for non-streaming LLM providers.
@@ -52,6 +53,16 @@ pub trait SimpleLlmProvider: Send + Sync {
         temperature: f32,
         max_tokens: u32,
     ) -> Result<String, BridgeError>;
+
+    /// Number of tokens `text` occupies in the model's context.
+    fn count_tokens(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+
+    /// Maximum tokens (prompt plus completion) the model accepts.
+    fn context_window(&self) -> usize {
+        DEFAULT_CONTEXT_WINDOW
+    }
 }
 
 /// Wraps a `SimpleLlmProvider` into a `ToolAwareLlmProvider`.
@@ -70,6 +81,16 @@ impl LlmProviderBridge {
     }
 }
 
+impl TokenCounter for LlmProviderBridge {
+    fn count_tokens(&self, text: &str) -> usize {
+        self.inner.count_tokens(text)
+    }
+
+    fn context_window(&self) -> usize {
+        self.inner.context_window()
+    }
+}
+
 #[async_trait]
 impl ToolAwareLlmProvider for LlmProviderBridge {
     async fn stream_with_tools(
@@ -0,0 +1,138 @@
+//! Token and dollar budgets for agent runs.
+//!
+//! A [`Budget`] caps what one run may spend, in tokens, in US dollars, or
+//! both. Usage accumulates in a [`TokenUsage`] as responses arrive — from
+//! the provider's [`StreamEvent::Usage`](crate::StreamEvent::Usage) reports
+//! where available, otherwise counted with the provider's tokenizer.
+
+use serde::{Deserialize, Serialize};
+
+/// Tokens consumed so far, split by direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+impl TokenUsage {
+    pub fn total(&self) -> u64 {
+        self.input_tokens + self.output_tokens
+    }
+
+    pub fn add(&mut self, other: TokenUsage) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+    }
+}
+
+/// Model prices in US dollars per million tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenPricing {
+    pub input_per_million: f64,
+    pub output_per_million: f64,
+}
+
+impl TokenPricing {
+    /// Dollar cost of `usage` at these prices.
+    pub fn cost_usd(&self, usage: &TokenUsage) -> f64 {
+        (usage.input_tokens as f64 * self.input_per_million
+            + usage.output_tokens as f64 * self.output_per_million)
+            / 1_000_000.0
+    }
+}
+
+/// Spending limit for one run. Unset limits don't apply; a dollar limit
+/// needs `pricing` to convert tokens to dollars and is ignored without it.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Budget {
+    /// Maximum input plus output tokens.
+    #[serde(default)]
+    pub max_tokens: Option<u64>,
+    /// Maximum spend in US dollars.
+    #[serde(default)]
+    pub max_cost_usd: Option<f64>,
+    #[serde(default)]
+    pub pricing: Option<TokenPricing>,
+}
+
+impl Budget {
+    /// A budget of `max` input plus output tokens.
+    pub fn tokens(max: u64) -> Self {
+        Self { max_tokens: Some(max), ..Default::default() }
+    }
+
+    /// A budget of `max_usd` dollars at `pricing`.
+    pub fn dollars(max_usd: f64, pricing: TokenPricing) -> Self {
+        Self { max_cost_usd: Some(max_usd), pricing: Some(pricing), ..Default::default() }
+    }
+
+    /// Whether `usage` has used up the budget.
+    pub fn is_exhausted(&self, usage: &TokenUsage) -> bool {
+        self.remaining_tokens(usage) == Some(0)
+    }
+
+    /// Whether `usage` has gone over the budget.
+    pub fn is_exceeded(&self, usage: &TokenUsage) -> bool {
+        self.max_tokens.is_some_and(|max| usage.total() > max)
+            || self.dollar_limit().is_some_and(|(max, pricing)| pricing.cost_usd(usage) > max)
+    }
+
+    /// Output tokens that can still be spent after `usage`, under whichever
+    /// limit is tighter. `None` when the budget sets no usable limit.
+    pub fn remaining_tokens(&self, usage: &TokenUsage) -> Option<u64> {
+        let by_tokens = self.max_tokens.map(|max| max.saturating_sub(usage.total()));
+        let by_cost = self.dollar_limit().map(|(max, pricing)| {
+            let left = max - pricing.cost_usd(usage);
+            if left <= 0.0 {
+                0
+            } else if pricing.output_per_million <= 0.0 {
+                u64::MAX
+            } else {
+                (left * 1_000_000.0 / pricing.output_per_million) as u64
+            }
+        });
+        match (by_tokens, by_cost) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    fn dollar_limit(&self) -> Option<(f64, &TokenPricing)> {
+        Some((self.max_cost_usd?, self.pricing.as_ref()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(input_tokens: u64, output_tokens: u64) -> TokenUsage {
+        TokenUsage { input_tokens, output_tokens }
+    }
+
+    #[test]
+    fn test_token_budget() {
+        let budget = Budget::tokens(1000);
+        assert!(!budget.is_exceeded(&usage(600, 400)));
+        assert!(budget.is_exhausted(&usage(600, 400)));
+        assert!(budget.is_exceeded(&usage(600, 401)));
+        assert_eq!(budget.remaining_tokens(&usage(300, 200)), Some(500));
+        assert_eq!(Budget::default().remaining_tokens(&usage(1, 1)), None);
+    }
+
+    #[test]
+    fn test_dollar_budget_uses_pricing() {
+        let pricing = TokenPricing { input_per_million: 3.0, output_per_million: 15.0 };
+        let budget = Budget::dollars(0.03, pricing);
+        // 5000 in = $0.015; 500 out = $0.0075.
+        assert!(!budget.is_exceeded(&usage(5000, 500)));
+        // $0.0075 left buys 500 more output tokens.
+        assert_eq!(budget.remaining_tokens(&usage(5000, 500)), Some(500));
+        assert!(budget.is_exceeded(&usage(5000, 1001)));
+
+        let unpriced = Budget { max_cost_usd: Some(0.0), ..Default::default() };
+        assert!(!unpriced.is_exceeded(&usage(1_000_000, 1_000_000)));
+    }
+}
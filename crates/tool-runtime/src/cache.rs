@@ -0,0 +1,117 @@
+//! Result cache for deterministic, read-only tools.
+//!
+//! A [`ToolResultCache`] remembers successful results of tools that opt in
+//! via [`Tool::is_cacheable`](crate::Tool::is_cacheable), keyed by tool name
+//! and canonicalized input, so identical calls in later turns reuse the
+//! earlier result until it is older than the cache's TTL.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::tool::ToolResult;
+
+/// Cached tool results with a time-to-live.
+pub struct ToolResultCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<(String, String), (Instant, ToolResult)>>,
+}
+
+impl ToolResultCache {
+    /// An empty cache whose entries expire `ttl` after they were stored.
+    pub fn new(ttl: Duration) -> Self {
+        Self { ttl, entries: Mutex::new(HashMap::new()) }
+    }
+
+    /// The stored result for `tool_name` called with `input`, and how long
+    /// ago it was stored, unless it has expired.
+    pub fn get(&self, tool_name: &str, input: &Value) -> Option<(ToolResult, Duration)> {
+        let key = (tool_name.to_string(), canonical_input(input));
+        let mut entries = self.entries.lock().expect("tool cache lock poisoned");
+        let age = entries.get(&key)?.0.elapsed();
+        if age > self.ttl {
+            entries.remove(&key);
+            return None;
+        }
+        entries.get(&key).map(|(_, result)| (result.clone(), age))
+    }
+
+    /// Store `result` for `tool_name` called with `input`. Error results
+    /// are not stored.
+    pub fn insert(&self, tool_name: &str, input: &Value, result: &ToolResult) {
+        if result.is_error {
+            return;
+        }
+        let key = (tool_name.to_string(), canonical_input(input));
+        self.entries
+            .lock()
+            .expect("tool cache lock poisoned")
+            .insert(key, (Instant::now(), result.clone()));
+    }
+
+    /// Drop every entry, e.g. after a tool changed what cached results saw.
+    pub fn clear(&self) {
+        self.entries.lock().expect("tool cache lock poisoned").clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().expect("tool cache lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// `input` serialized with object keys sorted at every level, so inputs
+/// that differ only in key order share a cache entry.
+pub fn canonical_input(input: &Value) -> String {
+    fn sorted(value: &Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                Value::Object(keys.into_iter().map(|k| (k.clone(), sorted(&map[k]))).collect())
+            }
+            Value::Array(items) => Value::Array(items.iter().map(sorted).collect()),
+            other => other.clone(),
+        }
+    }
+    sorted(input).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(content: &str, is_error: bool) -> ToolResult {
+        ToolResult { tool_call_id: "call_1".to_string(), content: content.to_string(), is_error }
+    }
+
+    #[test]
+    fn test_key_ignores_object_key_order() {
+        let cache = ToolResultCache::new(Duration::from_secs(60));
+        let input: Value = serde_json::from_str(r#"{"path":"a.txt","range":{"to":9,"from":1}}"#).unwrap();
+        let reordered: Value = serde_json::from_str(r#"{"range":{"from":1,"to":9},"path":"a.txt"}"#).unwrap();
+        cache.insert("file_read", &input, &result("hello", false));
+
+        let (hit, _) = cache.get("file_read", &reordered).unwrap();
+        assert_eq!(hit.content, "hello");
+        assert!(cache.get("grep", &input).is_none());
+    }
+
+    #[test]
+    fn test_errors_are_not_cached_and_entries_expire() {
+        let cache = ToolResultCache::new(Duration::ZERO);
+        let input = serde_json::json!({"path": "a.txt"});
+        cache.insert("file_read", &input, &result("no such file", true));
+        assert!(cache.is_empty());
+
+        cache.insert("file_read", &input, &result("hello", false));
+        std::thread::sleep(Duration::from_millis(2));
+        assert!(cache.get("file_read", &input).is_none());
+        assert!(cache.is_empty());
+    }
+}
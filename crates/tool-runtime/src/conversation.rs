@@ -1,5 +1,8 @@
+use crate::tokens::{EstimatingCounter, TokenCounter};
 use crate::tool::{ToolCall, ToolResult};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::sync::Arc;
 
 /// A message in the conversation history.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -24,10 +27,12 @@ pub struct AssistantContent {
 /// Manages conversation history with context window awareness.
 pub struct Conversation {
     messages: Vec<ConversationMessage>,
-    /// Maximum approximate token count before truncation
+    /// Maximum token count (system prompt included) before truncation
     max_tokens: usize,
     /// System prompt (always retained)
     system_prompt: Option<String>,
+    /// Tokenizer used to measure the history against `max_tokens`
+    counter: Arc<dyn TokenCounter>,
 }
 
 impl Conversation {
@@ -36,9 +41,21 @@ impl Conversation {
             messages: Vec::new(),
             max_tokens,
             system_prompt: None,
+            counter: Arc::new(EstimatingCounter),
         }
     }
 
+    /// Count tokens with `counter` (typically the provider) instead of the
+    /// character-based estimate.
+    pub fn with_token_counter(mut self, counter: Arc<dyn TokenCounter>) -> Self {
+        self.counter = counter;
+        self
+    }
+
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
     pub fn with_system_prompt(mut self, prompt: String) -> Self {
         self.system_prompt = Some(prompt);
         self
@@ -70,34 +87,91 @@ impl Conversation {
         self.messages.clear();
     }
 
-    /// Approximate token count using character count / 4 heuristic.
+    /// Token count of the message history, as measured by the token counter.
     pub fn approximate_tokens(&self) -> usize {
-        let char_count: usize = self
-            .messages
+        let counter = &self.counter;
+        self.messages
             .iter()
             .map(|m| match m {
-                ConversationMessage::User(text) => text.len(),
+                ConversationMessage::User(text) => counter.count_tokens(text),
                 ConversationMessage::Assistant(content) => {
-                    content.text.as_ref().map_or(0, |t| t.len())
+                    content.text.as_ref().map_or(0, |t| counter.count_tokens(t))
                         + content
                             .tool_calls
                             .iter()
-                            .map(|tc| tc.input.to_string().len())
+                            .map(|tc| counter.count_tokens(&tc.input.to_string()))
                             .sum::<usize>()
                 }
-                ConversationMessage::ToolResult(result) => result.content.len(),
+                ConversationMessage::ToolResult(result) => counter.count_tokens(&result.content),
             })
-            .sum();
-        char_count / 4
+            .sum()
+    }
+
+    /// Whether every tool call and tool result is correctly paired, as
+    /// providers require: each tool result answers a call from the assistant
+    /// message just before it (only other results may sit in between), each
+    /// call is answered at most once, and every call is answered before the
+    /// next user or assistant message. Calls in the final assistant message
+    /// may still be awaiting their results.
+    pub fn is_valid(&self) -> bool {
+        let mut pending: HashSet<&str> = HashSet::new();
+        for message in &self.messages {
+            match message {
+                ConversationMessage::User(_) => {
+                    if !pending.is_empty() {
+                        return false;
+                    }
+                }
+                ConversationMessage::Assistant(content) => {
+                    if !pending.is_empty() {
+                        return false;
+                    }
+                    pending.extend(content.tool_calls.iter().map(|tc| tc.id.as_str()));
+                }
+                ConversationMessage::ToolResult(result) => {
+                    if !pending.remove(result.tool_call_id.as_str()) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
     }
 
     /// Drop oldest messages (keeping system prompt) when over token limit.
+    /// The system prompt's tokens count against the limit too.
+    ///
+    /// An assistant message is dropped together with the tool results that
+    /// answer its calls, so trimming never orphans either side of a pair.
     fn maybe_truncate(&mut self) {
-        while self.approximate_tokens() > self.max_tokens && self.messages.len() > 2 {
+        let budget = self
+            .max_tokens
+            .saturating_sub(self.system_prompt.as_deref().map_or(0, |p| self.counter.count_tokens(p)));
+        while self.approximate_tokens() > budget {
+            let unit = self.leading_unit_len();
             // Keep at least the last 2 messages (current turn)
-            self.messages.remove(0);
+            if self.messages.len() - unit < 2 {
+                break;
+            }
+            self.messages.drain(..unit);
         }
     }
+
+    /// Number of messages at the front that must be dropped together: an
+    /// assistant message plus the tool results answering its calls, or a
+    /// single message otherwise.
+    fn leading_unit_len(&self) -> usize {
+        let Some(ConversationMessage::Assistant(content)) = self.messages.first() else {
+            return 1;
+        };
+        let call_ids: HashSet<&str> = content.tool_calls.iter().map(|tc| tc.id.as_str()).collect();
+        1 + self.messages[1..]
+            .iter()
+            .take_while(|m| {
+                matches!(m, ConversationMessage::ToolResult(r) if call_ids.contains(r.tool_call_id.as_str()))
+            })
+            .count()
+    }
 }
 
 impl Default for Conversation {
@@ -155,6 +229,128 @@ mod tests {
         assert!(conv.messages().len() <= 4);
     }
 
+    /// Counts one token per word.
+    struct WordCounter;
+
+    impl TokenCounter for WordCounter {
+        fn count_tokens(&self, text: &str) -> usize {
+            text.split_whitespace().count()
+        }
+    }
+
+    #[test]
+    fn test_truncation_uses_token_counter() {
+        let mut conv = Conversation::new(12).with_token_counter(Arc::new(WordCounter));
+        for _ in 0..5 {
+            conv.add_user_message("one two three four".to_string());
+        }
+        assert_eq!(conv.approximate_tokens(), 12);
+        assert_eq!(conv.messages().len(), 3);
+    }
+
+    #[test]
+    fn test_system_prompt_counts_against_budget() {
+        let mut conv = Conversation::new(12)
+            .with_system_prompt("you are a helpful agent".to_string())
+            .with_token_counter(Arc::new(WordCounter));
+        for _ in 0..5 {
+            conv.add_user_message("one two three four".to_string());
+        }
+        assert_eq!(conv.messages().len(), 2, "5 system tokens leave room for 7");
+    }
+
+    fn tool_call_turn(conv: &mut Conversation, id: &str, output: &str) {
+        conv.add_assistant_response(AssistantContent {
+            text: None,
+            tool_calls: vec![ToolCall {
+                id: id.to_string(),
+                name: "bash_execute".to_string(),
+                input: serde_json::json!({"command": "ls"}),
+            }],
+        });
+        conv.add_tool_result(ToolResult {
+            tool_call_id: id.to_string(),
+            content: output.to_string(),
+            is_error: false,
+        });
+    }
+
+    #[test]
+    fn test_truncation_keeps_tool_pairs() {
+        // Budgets from 1 to 30 words cut the history at every possible point.
+        for max_tokens in 1..30 {
+            let mut conv = Conversation::new(max_tokens).with_token_counter(Arc::new(WordCounter));
+            for i in 0..4 {
+                conv.add_user_message("list the files please".to_string());
+                tool_call_turn(&mut conv, &format!("call_{i}"), "file1.txt file2.txt file3.txt");
+                conv.add_assistant_response(AssistantContent {
+                    text: Some("there are three files".to_string()),
+                    tool_calls: vec![],
+                });
+            }
+            assert!(conv.is_valid(), "orphaned tool message at max_tokens={max_tokens}");
+            assert!(!matches!(conv.messages()[0], ConversationMessage::ToolResult(_)));
+        }
+    }
+
+    #[test]
+    fn test_truncation_drops_call_with_all_results() {
+        let mut conv = Conversation::new(8).with_token_counter(Arc::new(WordCounter));
+        conv.add_assistant_response(AssistantContent {
+            text: None,
+            tool_calls: ["call_a", "call_b"]
+                .iter()
+                .map(|id| ToolCall {
+                    id: id.to_string(),
+                    name: "bash_execute".to_string(),
+                    input: serde_json::json!({}),
+                })
+                .collect(),
+        });
+        for id in ["call_a", "call_b"] {
+            conv.add_tool_result(ToolResult {
+                tool_call_id: id.to_string(),
+                content: "ok".to_string(),
+                is_error: false,
+            });
+        }
+        conv.add_user_message("one two three".to_string());
+        conv.add_user_message("four five six".to_string());
+
+        assert_eq!(conv.messages().len(), 2);
+        assert!(conv.messages().iter().all(|m| matches!(m, ConversationMessage::User(_))));
+    }
+
+    #[test]
+    fn test_is_valid_detects_orphans() {
+        let mut conv = Conversation::new(100_000);
+        conv.add_user_message("List files".to_string());
+        tool_call_turn(&mut conv, "call_1", "file1.txt");
+        assert!(conv.is_valid());
+
+        // A call still awaiting its result is fine at the end...
+        conv.add_assistant_response(AssistantContent {
+            text: None,
+            tool_calls: vec![ToolCall {
+                id: "call_2".to_string(),
+                name: "bash_execute".to_string(),
+                input: serde_json::json!({}),
+            }],
+        });
+        assert!(conv.is_valid());
+        // ...but not once the conversation moves on without it.
+        conv.add_user_message("never mind".to_string());
+        assert!(!conv.is_valid());
+
+        let mut orphan = Conversation::new(100_000);
+        orphan.add_tool_result(ToolResult {
+            tool_call_id: "call_1".to_string(),
+            content: "file1.txt".to_string(),
+            is_error: false,
+        });
+        assert!(!orphan.is_valid());
+    }
+
     #[test]
     fn test_serialization() {
         let mut conv = Conversation::new(100_000);
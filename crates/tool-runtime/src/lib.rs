@@ -8,18 +8,24 @@ pub mod conversation;
 pub mod stream;
 pub mod bridge;
 pub mod context;
+pub mod tokens;
+pub mod budget;
+pub mod cache;
 
 pub use tool::{Tool, ToolDefinition, ToolCall, ToolResult};
 pub use context::load_project_context;
 pub use registry::ToolRegistry;
-pub use runtime::AgenticLoop;
+pub use runtime::{AgenticLoop, RunOutcome};
 pub use provider::ToolAwareLlmProvider;
-pub use permission::{PermissionLevel, PermissionPolicy, PermissionChecker, PermissionDecision};
+pub use permission::{Approver, PermissionLevel, PermissionPolicy, PermissionChecker, PermissionDecision};
 pub use conversation::Conversation;
-pub use stream::StreamEvent;
+pub use stream::{StreamEvent, ToolCallStage};
+pub use tokens::{estimate_tokens, EstimatingCounter, TokenCounter};
+pub use budget::{Budget, TokenPricing, TokenUsage};
+pub use cache::ToolResultCache;
 pub use bridge::{BridgeError, LlmProviderBridge, SimpleLlmProvider, SimpleMessage, SimpleRole};
 pub use tools::{
-    BashExecuteTool, FileReadTool, FileWriteTool,
+    BashExecuteTool, FileEditTool, FileReadTool, FileWriteTool, GlobTool, GrepTool,
     GraphQueryTool, RuleListTool, RuleEvaluateTool,
     ListRulesTool, GetRuleYamlTool, ValidateRuleTool, DryRunRuleTool, SaveRuleTool,
 };
@@ -2,6 +2,13 @@ use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::tool::ToolCall;
+
+/// How long [`PolicyChecker`] waits for an approver before denying a call.
+pub const DEFAULT_APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
 
 /// Permission level for a tool.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -73,16 +80,62 @@ pub trait PermissionChecker: Send + Sync {
         tool_name: &str,
         input: &Value,
     ) -> PermissionDecision;
+
+    /// Decide a call that [`check_permission`](Self::check_permission)
+    /// answered with [`PermissionDecision::NeedsConfirmation`].
+    ///
+    /// `call_id` is the ID the agentic loop assigned the call, as in the
+    /// stream's `ToolCallStarted`; unlike the provider's `call.id` it is
+    /// unique. Must return `Approved` or `Denied`. The default denies, since
+    /// there is no one to confirm with.
+    async fn request_approval(&self, _call_id: &str, call: &ToolCall) -> PermissionDecision {
+        no_approver(&call.name)
+    }
 }
 
-/// A simple policy-based permission checker (no interactive prompting).
+/// Asks a person to approve a tool call that needs confirmation.
+///
+/// The CLI prompts on the terminal; the server queues the call until a
+/// client approves or denies it. `call_id` is the loop-assigned ID of the
+/// call, unique for the life of the process.
+#[async_trait]
+pub trait Approver: Send + Sync {
+    async fn request_approval(&self, call_id: &str, call: &ToolCall) -> PermissionDecision;
+}
+
+fn no_approver(tool_name: &str) -> PermissionDecision {
+    PermissionDecision::Denied(format!(
+        "Tool '{}' requires confirmation and no approver is available",
+        tool_name
+    ))
+}
+
+/// A simple policy-based permission checker.
+///
+/// Calls the policy marks [`RequireConfirmation`](PermissionLevel::RequireConfirmation)
+/// go to the approver set with [`with_approver`](Self::with_approver), and
+/// are denied without one.
 pub struct PolicyChecker {
     policy: PermissionPolicy,
+    approver: Option<Arc<dyn Approver>>,
+    approval_timeout: Duration,
 }
 
 impl PolicyChecker {
     pub fn new(policy: PermissionPolicy) -> Self {
-        Self { policy }
+        Self {
+            policy,
+            approver: None,
+            approval_timeout: DEFAULT_APPROVAL_TIMEOUT,
+        }
+    }
+
+    /// Ask `approver` about calls that need confirmation, denying any it
+    /// hasn't decided within `timeout`.
+    pub fn with_approver(mut self, approver: Arc<dyn Approver>, timeout: Duration) -> Self {
+        self.approver = Some(approver);
+        self.approval_timeout = timeout;
+        self
     }
 }
 
@@ -101,6 +154,21 @@ impl PermissionChecker for PolicyChecker {
             }
         }
     }
+
+    async fn request_approval(&self, call_id: &str, call: &ToolCall) -> PermissionDecision {
+        let Some(approver) = &self.approver else {
+            return no_approver(&call.name);
+        };
+        match tokio::time::timeout(self.approval_timeout, approver.request_approval(call_id, call)).await {
+            Ok(PermissionDecision::NeedsConfirmation) => no_approver(&call.name),
+            Ok(decision) => decision,
+            Err(_) => PermissionDecision::Denied(format!(
+                "No approval for tool '{}' within {}s",
+                call.name,
+                self.approval_timeout.as_secs()
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -179,4 +247,63 @@ mod tests {
             PermissionDecision::NeedsConfirmation
         );
     }
+
+    /// Answers every request with `decision` after `delay`.
+    struct FixedApprover {
+        decision: PermissionDecision,
+        delay: Duration,
+    }
+
+    #[async_trait]
+    impl Approver for FixedApprover {
+        async fn request_approval(&self, _call_id: &str, _call: &ToolCall) -> PermissionDecision {
+            tokio::time::sleep(self.delay).await;
+            self.decision.clone()
+        }
+    }
+
+    fn confirm_call() -> ToolCall {
+        ToolCall {
+            id: "call_1".to_string(),
+            name: "bash_execute".to_string(),
+            input: serde_json::json!({"command": "rm -rf build"}),
+        }
+    }
+
+    fn checker_with(decision: PermissionDecision, delay: Duration) -> PolicyChecker {
+        let approver = Arc::new(FixedApprover { decision, delay });
+        PolicyChecker::new(PermissionPolicy::new()).with_approver(approver, Duration::from_secs(5))
+    }
+
+    #[tokio::test]
+    async fn test_approver_approves() {
+        let checker = checker_with(PermissionDecision::Approved, Duration::ZERO);
+        assert_eq!(checker.request_approval("call_1", &confirm_call()).await, PermissionDecision::Approved);
+    }
+
+    #[tokio::test]
+    async fn test_approver_denies() {
+        let denied = PermissionDecision::Denied("not on prod".to_string());
+        let checker = checker_with(denied.clone(), Duration::ZERO);
+        assert_eq!(checker.request_approval("call_1", &confirm_call()).await, denied);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_approval_timeout_denies() {
+        let checker = checker_with(PermissionDecision::Approved, Duration::from_secs(60));
+        let decision = checker.request_approval("call_1", &confirm_call()).await;
+        assert_eq!(
+            decision,
+            PermissionDecision::Denied("No approval for tool 'bash_execute' within 5s".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_no_approver_denies() {
+        let checker = PolicyChecker::new(PermissionPolicy::new());
+        assert!(matches!(
+            checker.request_approval("call_1", &confirm_call()).await,
+            PermissionDecision::Denied(_)
+        ));
+    }
 }
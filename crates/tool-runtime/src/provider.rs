@@ -1,5 +1,6 @@
 use crate::conversation::ConversationMessage;
 use crate::stream::StreamEvent;
+use crate::tokens::TokenCounter;
 use crate::tool::ToolDefinition;
 use async_trait::async_trait;
 use futures::Stream;
@@ -10,8 +11,11 @@ use std::pin::Pin;
 /// This trait lives in tool-runtime (not in crates/llm) because it's
 /// defined by the consumer (the agentic loop), not the provider.
 /// Implementations live in crates/llm or adapter crates.
+///
+/// The [`TokenCounter`] supertrait lets the loop size a conversation to the
+/// provider's model; its methods default to a character-based estimate.
 #[async_trait]
-pub trait ToolAwareLlmProvider: Send + Sync {
+pub trait ToolAwareLlmProvider: TokenCounter {
     /// Stream a response from the LLM with tool definitions available.
     async fn stream_with_tools(
         &self,
@@ -103,6 +107,8 @@ pub mod mock {
         }
     }
 
+    impl TokenCounter for MockLlmProvider {}
+
     #[async_trait]
     impl ToolAwareLlmProvider for MockLlmProvider {
         async fn stream_with_tools(
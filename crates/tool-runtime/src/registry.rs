@@ -4,30 +4,59 @@ use std::sync::Arc;
 
 /// Manages available tools, their schemas, and lookup.
 /// Thread-safe via Arc wrapping of individual tools.
+///
+/// Renamed tools can keep their old names as deprecated aliases; lookups
+/// through an alias return the canonical tool.
 pub struct ToolRegistry {
     tools: HashMap<String, Arc<dyn Tool>>,
+    /// Deprecated name → canonical tool name.
+    aliases: HashMap<String, String>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
         Self {
             tools: HashMap::new(),
+            aliases: HashMap::new(),
         }
     }
 
     /// Register a tool. Returns error if name already registered.
     pub fn register(&mut self, tool: impl Tool + 'static) -> Result<(), RegistryError> {
         let def = tool.definition();
-        if self.tools.contains_key(&def.name) {
+        if self.tools.contains_key(&def.name) || self.aliases.contains_key(&def.name) {
             return Err(RegistryError::DuplicateName(def.name));
         }
         self.tools.insert(def.name, Arc::new(tool));
         Ok(())
     }
 
-    /// Look up a tool by name.
+    /// Make `old` a deprecated alias for the registered tool `new`.
+    ///
+    /// Aliases are not advertised by [`list`](Self::list); they only keep
+    /// existing sessions and configs that use the old name working.
+    pub fn alias(&mut self, old: impl Into<String>, new: &str) -> Result<(), RegistryError> {
+        let old = old.into();
+        if self.tools.contains_key(&old) || self.aliases.contains_key(&old) {
+            return Err(RegistryError::DuplicateName(old));
+        }
+        let canonical = self.canonical_name(new).ok_or_else(|| RegistryError::UnknownTool(new.to_string()))?;
+        let canonical = canonical.to_string();
+        self.aliases.insert(old, canonical);
+        Ok(())
+    }
+
+    /// Resolve a tool name or alias to the canonical tool name.
+    pub fn canonical_name<'a>(&'a self, name: &'a str) -> Option<&'a str> {
+        if self.tools.contains_key(name) {
+            return Some(name);
+        }
+        self.aliases.get(name).map(String::as_str)
+    }
+
+    /// Look up a tool by name or alias.
     pub fn get(&self, name: &str) -> Option<Arc<dyn Tool>> {
-        self.tools.get(name).cloned()
+        self.canonical_name(name).and_then(|n| self.tools.get(n)).cloned()
     }
 
     /// List all registered tool definitions (for sending to LLM).
@@ -55,6 +84,8 @@ impl Default for ToolRegistry {
 pub enum RegistryError {
     #[error("Tool with name '{0}' is already registered")]
     DuplicateName(String),
+    #[error("Cannot alias unknown tool '{0}'")]
+    UnknownTool(String),
 }
 
 #[cfg(test)]
@@ -79,6 +110,25 @@ mod tests {
         assert!(registry.register(EchoTool).is_err());
     }
 
+    #[test]
+    fn test_alias_resolves_to_canonical_tool() {
+        let mut registry = ToolRegistry::new();
+        registry.register(EchoTool).unwrap();
+        registry.alias("say", "echo").unwrap();
+        // Chained aliases point straight at the canonical tool.
+        registry.alias("shout", "say").unwrap();
+
+        assert_eq!(registry.canonical_name("say"), Some("echo"));
+        assert_eq!(registry.canonical_name("shout"), Some("echo"));
+        assert_eq!(registry.canonical_name("echo"), Some("echo"));
+        assert_eq!(registry.get("say").unwrap().definition().name, "echo");
+        assert_eq!(registry.list().len(), 1);
+
+        assert!(matches!(registry.alias("echo", "say"), Err(RegistryError::DuplicateName(_))));
+        assert!(matches!(registry.alias("say", "echo"), Err(RegistryError::DuplicateName(_))));
+        assert!(matches!(registry.alias("old", "missing"), Err(RegistryError::UnknownTool(_))));
+    }
+
     #[test]
     fn test_list_definitions() {
         let mut registry = ToolRegistry::new();
@@ -1,13 +1,31 @@
+use crate::budget::{Budget, TokenUsage};
+use crate::cache::ToolResultCache;
 use crate::conversation::{AssistantContent, Conversation};
 use crate::permission::{PermissionChecker, PermissionDecision};
 use crate::provider::{LlmError, ToolAwareLlmProvider};
 use crate::registry::ToolRegistry;
-use crate::stream::{StopReason, StreamEvent};
+use crate::stream::{StopReason, StreamEvent, ToolCallStage};
+use crate::tokens::TokenCounter;
 use crate::tool::{ToolCall, ToolContext, ToolResult};
 use futures::StreamExt;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
+use std::time::Instant;
 use tracing::{debug, info, warn};
 
+/// Source of tool call IDs, unique for the life of the process.
+static NEXT_CALL_ID: AtomicU64 = AtomicU64::new(1);
+
+fn next_call_id() -> String {
+    format!("call_{}", NEXT_CALL_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// A tool call requested by the model, with the ID the loop assigned it.
+struct PendingCall {
+    call_id: String,
+    call: ToolCall,
+}
+
 /// The core agentic loop that orchestrates LLM ↔ Tool execution.
 ///
 /// Flow: User → LLM → ToolCalls → Execute → Results → LLM → ... → Final Text
@@ -19,6 +37,30 @@ pub struct AgenticLoop {
     max_iterations: usize,
     temperature: f32,
     max_tokens: u32,
+    dry_run: bool,
+    budget: Option<Budget>,
+    cache: Option<Arc<ToolResultCache>>,
+}
+
+/// How a run through the loop ended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The model finished its turn or the loop ran out of iterations.
+    Completed { usage: TokenUsage },
+    /// The budget was used up before the model finished. The conversation
+    /// holds everything produced up to and including the response that used
+    /// it up; tool calls from that response were not executed and are
+    /// answered with error results.
+    BudgetExceeded { usage: TokenUsage },
+}
+
+impl RunOutcome {
+    /// Tokens the run consumed.
+    pub fn usage(&self) -> TokenUsage {
+        match self {
+            RunOutcome::Completed { usage } | RunOutcome::BudgetExceeded { usage } => *usage,
+        }
+    }
 }
 
 impl AgenticLoop {
@@ -34,6 +76,9 @@ impl AgenticLoop {
             max_iterations: 10,
             temperature: 0.0,
             max_tokens: 4096,
+            dry_run: false,
+            budget: None,
+            cache: None,
         }
     }
 
@@ -52,25 +97,81 @@ impl AgenticLoop {
         self
     }
 
+    /// Plan-only mode: tools marked [`is_side_effecting`](crate::Tool::is_side_effecting)
+    /// are not run; each call gets a synthetic "would execute" result instead,
+    /// so the transcript shows what the agent would have done. Read-only
+    /// tools still run, so the model plans against real data.
+    pub fn with_dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Stop the run once its token usage crosses `budget`.
+    ///
+    /// Usage is checked after each response: once the budget is used up the
+    /// response is kept but its tool calls are not run. Responses are also
+    /// capped to the tokens the budget has left.
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
+    /// Reuse results of [`is_cacheable`](crate::Tool::is_cacheable) tools from
+    /// `cache` when called again with the same input, instead of running
+    /// them. A hit is reported as [`ToolCallStage::CacheHit`]. The cache is
+    /// cleared whenever a side-effecting tool runs, since it may have changed
+    /// what the cached results saw. Share one cache across turns (and
+    /// clones of the loop) to reuse results between them.
+    pub fn with_result_cache(mut self, cache: Arc<ToolResultCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Start a conversation sized to the provider's model.
+    ///
+    /// The history budget is `max_context_tokens`, capped so that the history
+    /// plus a full response (`max_tokens`) fits the provider's context window.
+    /// Tokens are counted with the provider's tokenizer.
+    pub fn new_conversation(&self, max_context_tokens: usize) -> Conversation {
+        let window = self
+            .provider
+            .context_window()
+            .saturating_sub(self.max_tokens as usize);
+        let counter: Arc<dyn TokenCounter> = self.provider.clone();
+        Conversation::new(max_context_tokens.min(window)).with_token_counter(counter)
+    }
+
     /// Run a single user turn through the agentic loop, streaming events through a channel.
     ///
-    /// Each `StreamEvent` is sent through `tx` as it arrives from the LLM stream,
-    /// plus `ToolExecutionStart` / `ToolExecutionResult` events for tool execution.
-    /// The conversation is mutated in place as the loop progresses.
+    /// Each `StreamEvent` is sent through `tx` as it arrives from the LLM stream.
+    /// For every tool call the model requests, the loop assigns a `call_id`
+    /// and adds `ToolCallStarted`, `ToolCallProgress` and `ToolCallCompleted`
+    /// events carrying it. The conversation is mutated in place as the loop
+    /// progresses.
+    ///
+    /// With a [`Budget`], the loop sends [`StreamEvent::BudgetExceeded`] and
+    /// returns [`RunOutcome::BudgetExceeded`] once usage crosses it.
     pub async fn run_streaming(
         &self,
         conversation: &mut Conversation,
         user_message: String,
         tool_context: &ToolContext,
         tx: tokio::sync::mpsc::Sender<StreamEvent>,
-    ) -> Result<(), AgenticLoopError> {
+    ) -> Result<RunOutcome, AgenticLoopError> {
         conversation.add_user_message(user_message);
+        let mut usage = TokenUsage::default();
 
         for iteration in 0..self.max_iterations {
             debug!(iteration, "Starting agentic loop iteration");
 
             // Get tool definitions
             let tools = self.registry.list();
+            let max_tokens = match self.budget.as_ref().and_then(|b| b.remaining_tokens(&usage)) {
+                Some(remaining) => self.max_tokens.min(remaining.try_into().unwrap_or(u32::MAX)).max(1),
+                None => self.max_tokens,
+            };
+            let prompt_tokens = conversation.approximate_tokens()
+                + conversation.system_prompt().map_or(0, |p| self.provider.count_tokens(p));
 
             // Stream LLM response
             let mut stream = self
@@ -80,21 +181,24 @@ impl AgenticLoop {
                     conversation.system_prompt().map(String::from),
                     tools,
                     self.temperature,
-                    self.max_tokens,
+                    max_tokens,
                 )
                 .await
                 .map_err(AgenticLoopError::LlmError)?;
 
             // Collect events from this turn
             let mut text_parts = Vec::new();
-            let mut tool_calls: Vec<ToolCall> = Vec::new();
+            let mut tool_calls: Vec<PendingCall> = Vec::new();
             let mut current_tool_args = String::new();
             let mut current_tool_id = String::new();
             let mut current_tool_name = String::new();
+            let mut current_call_id = String::new();
             let mut stop_reason = StopReason::EndTurn;
+            let mut reported: Option<TokenUsage> = None;
 
             while let Some(event_result) = stream.next().await {
                 let event = event_result.map_err(AgenticLoopError::LlmError)?;
+                let mut lifecycle = None;
                 match &event {
                     StreamEvent::TextDelta { text } => {
                         text_parts.push(text.clone());
@@ -103,6 +207,12 @@ impl AgenticLoop {
                         current_tool_id = id.clone();
                         current_tool_name = name.clone();
                         current_tool_args.clear();
+                        current_call_id = next_call_id();
+                        lifecycle = Some(StreamEvent::ToolCallStarted {
+                            call_id: current_call_id.clone(),
+                            provider_id: id.clone(),
+                            name: name.clone(),
+                        });
                     }
                     StreamEvent::ToolCallDelta { arguments_delta, .. } => {
                         current_tool_args.push_str(arguments_delta);
@@ -110,10 +220,17 @@ impl AgenticLoop {
                     StreamEvent::ToolCallEnd { .. } => {
                         let input: serde_json::Value =
                             serde_json::from_str(&current_tool_args).unwrap_or_default();
-                        tool_calls.push(ToolCall {
-                            id: current_tool_id.clone(),
-                            name: current_tool_name.clone(),
-                            input,
+                        lifecycle = Some(StreamEvent::ToolCallProgress {
+                            call_id: current_call_id.clone(),
+                            stage: ToolCallStage::InputReady { input: input.clone() },
+                        });
+                        tool_calls.push(PendingCall {
+                            call_id: current_call_id.clone(),
+                            call: ToolCall {
+                                id: current_tool_id.clone(),
+                                name: current_tool_name.clone(),
+                                input,
+                            },
                         });
                     }
                     StreamEvent::MessageEnd { stop_reason: reason } => {
@@ -122,11 +239,22 @@ impl AgenticLoop {
                     StreamEvent::Error { message } => {
                         warn!(message, "Stream error");
                     }
-                    // ToolExecution* events are only emitted by us, not received from LLM
-                    StreamEvent::ToolExecutionStart { .. }
-                    | StreamEvent::ToolExecutionResult { .. } => {}
+                    StreamEvent::Usage { input_tokens, output_tokens } => {
+                        reported.get_or_insert_with(TokenUsage::default).add(TokenUsage {
+                            input_tokens: *input_tokens,
+                            output_tokens: *output_tokens,
+                        });
+                    }
+                    // Lifecycle events are only emitted by us, not received from LLM
+                    StreamEvent::ToolCallStarted { .. }
+                    | StreamEvent::ToolCallProgress { .. }
+                    | StreamEvent::ToolCallCompleted { .. }
+                    | StreamEvent::BudgetExceeded { .. } => {}
                 }
                 tx.send(event).await.map_err(|_| AgenticLoopError::ChannelClosed)?;
+                if let Some(lifecycle) = lifecycle {
+                    tx.send(lifecycle).await.map_err(|_| AgenticLoopError::ChannelClosed)?;
+                }
             }
 
             // Add assistant response to conversation
@@ -135,9 +263,14 @@ impl AgenticLoop {
             } else {
                 Some(text_parts.join(""))
             };
+            // Providers that don't report usage are counted with their tokenizer.
+            usage.add(reported.unwrap_or_else(|| TokenUsage {
+                input_tokens: prompt_tokens as u64,
+                output_tokens: self.count_output_tokens(text.as_deref(), &tool_calls),
+            }));
             conversation.add_assistant_response(AssistantContent {
                 text,
-                tool_calls: tool_calls.clone(),
+                tool_calls: tool_calls.iter().map(|pending| pending.call.clone()).collect(),
             });
 
             // If no tool calls, we're done
@@ -146,6 +279,22 @@ impl AgenticLoop {
                 break;
             }
 
+            if self.budget.as_ref().is_some_and(|b| b.is_exhausted(&usage)) {
+                warn!(iteration, tokens = usage.total(), "Agentic loop stopped: budget exceeded");
+                // Answer the skipped calls so the conversation stays valid.
+                for pending in &tool_calls {
+                    conversation.add_tool_result(ToolResult {
+                        tool_call_id: pending.call.id.clone(),
+                        content: "Not executed: run budget exceeded".to_string(),
+                        is_error: true,
+                    });
+                }
+                tx.send(StreamEvent::BudgetExceeded { usage })
+                    .await
+                    .map_err(|_| AgenticLoopError::ChannelClosed)?;
+                return Ok(RunOutcome::BudgetExceeded { usage });
+            }
+
             // Execute tool calls, streaming execution events
             info!(count = tool_calls.len(), "Executing tool calls");
             let results = self
@@ -158,7 +307,17 @@ impl AgenticLoop {
             }
         }
 
-        Ok(())
+        Ok(RunOutcome::Completed { usage })
+    }
+
+    /// Tokens in a response, as the provider's tokenizer counts them.
+    fn count_output_tokens(&self, text: Option<&str>, tool_calls: &[PendingCall]) -> u64 {
+        let text_tokens = text.map_or(0, |t| self.provider.count_tokens(t));
+        let call_tokens: usize = tool_calls
+            .iter()
+            .map(|pending| self.provider.count_tokens(&pending.call.input.to_string()))
+            .sum();
+        (text_tokens + call_tokens) as u64
     }
 
     /// Run a single user turn through the agentic loop.
@@ -192,31 +351,66 @@ impl AgenticLoop {
         Ok(all_events)
     }
 
-    /// Execute tool calls with streaming events for start/result of each tool.
+    /// Execute tool calls, streaming progress and completion of each call.
     async fn execute_tool_calls_streaming(
         &self,
-        tool_calls: &[ToolCall],
+        tool_calls: &[PendingCall],
         context: &ToolContext,
         tx: &tokio::sync::mpsc::Sender<StreamEvent>,
     ) -> Result<Vec<ToolResult>, AgenticLoopError> {
         let mut results = Vec::new();
+        let progress = |call_id: &str, stage| StreamEvent::ToolCallProgress { call_id: call_id.to_string(), stage };
 
-        for call in tool_calls {
-            // Emit start event
-            tx.send(StreamEvent::ToolExecutionStart {
-                id: call.id.clone(),
-                name: call.name.clone(),
-            })
-            .await
-            .map_err(|_| AgenticLoopError::ChannelClosed)?;
+        for PendingCall { call_id, call } in tool_calls {
+            if let Some(canonical) = self
+                .registry
+                .canonical_name(&call.name)
+                .filter(|canonical| *canonical != call.name)
+            {
+                warn!(alias = %call.name, canonical = %canonical, "Tool called by deprecated alias");
+                let stage = ToolCallStage::AliasDeprecated {
+                    alias: call.name.clone(),
+                    canonical: canonical.to_string(),
+                };
+                tx.send(progress(call_id, stage))
+                    .await
+                    .map_err(|_| AgenticLoopError::ChannelClosed)?;
+            }
 
-            let result = self.execute_single_tool(call, context).await;
+            let start = Instant::now();
+            let mut decision = self.check_permission(call).await;
+            // Nothing runs in dry-run mode, so there is nothing to confirm.
+            if decision == PermissionDecision::NeedsConfirmation
+                && !(self.dry_run && self.is_side_effecting(&call.name))
+            {
+                tx.send(progress(call_id, ToolCallStage::AwaitingApproval))
+                    .await
+                    .map_err(|_| AgenticLoopError::ChannelClosed)?;
+                decision = self.request_approval(call_id, call).await;
+                debug!(tool = %call.name, ?decision, "Tool call approval decided");
+            }
+            let result = match self.cached_result(call, &decision) {
+                Some((result, age)) => {
+                    debug!(tool = %call.name, "Tool result served from cache");
+                    let stage = ToolCallStage::CacheHit { age_ms: age.as_millis() as u64 };
+                    tx.send(progress(call_id, stage))
+                        .await
+                        .map_err(|_| AgenticLoopError::ChannelClosed)?;
+                    result
+                }
+                None => {
+                    tx.send(progress(call_id, ToolCallStage::Executing))
+                        .await
+                        .map_err(|_| AgenticLoopError::ChannelClosed)?;
+                    self.execute_single_tool(call, decision, context).await
+                }
+            };
 
-            // Emit result event
-            tx.send(StreamEvent::ToolExecutionResult {
-                id: result.tool_call_id.clone(),
+            tx.send(StreamEvent::ToolCallCompleted {
+                call_id: call_id.clone(),
                 content: result.content.clone(),
                 is_error: result.is_error,
+                duration_ms: start.elapsed().as_millis() as u64,
             })
             .await
             .map_err(|_| AgenticLoopError::ChannelClosed)?;
@@ -227,26 +421,57 @@ impl AgenticLoop {
         Ok(results)
     }
 
+    /// Check permissions under the canonical name so aliases can't sidestep
+    /// rules written for the renamed tool.
+    async fn check_permission(&self, call: &ToolCall) -> PermissionDecision {
+        let tool_name = self.canonical_name(call);
+        self.permission_checker.check_permission(tool_name, &call.input).await
+    }
+
+    /// Ask the permission checker's approver about a call, under its
+    /// canonical name like [`check_permission`](Self::check_permission).
+    async fn request_approval(&self, call_id: &str, call: &ToolCall) -> PermissionDecision {
+        let canonical = ToolCall {
+            name: self.canonical_name(call).to_string(),
+            ..call.clone()
+        };
+        self.permission_checker.request_approval(call_id, &canonical).await
+    }
+
+    fn canonical_name<'a>(&'a self, call: &'a ToolCall) -> &'a str {
+        self.registry.canonical_name(&call.name).unwrap_or(&call.name)
+    }
+
+    /// The cached result of an approved call to a cacheable tool, with its
+    /// age, if there is one.
+    fn cached_result(&self, call: &ToolCall, decision: &PermissionDecision) -> Option<(ToolResult, std::time::Duration)> {
+        let cache = self.cache.as_ref()?;
+        if !matches!(decision, PermissionDecision::Approved) || !self.is_cacheable(&call.name) {
+            return None;
+        }
+        let (mut result, age) = cache.get(self.canonical_name(call), &call.input)?;
+        result.tool_call_id = call.id.clone();
+        Some((result, age))
+    }
+
     async fn execute_single_tool(
         &self,
         call: &ToolCall,
+        decision: PermissionDecision,
         context: &ToolContext,
     ) -> ToolResult {
-        // Check permissions
-        let decision = self
-            .permission_checker
-            .check_permission(&call.name, &call.input)
-            .await;
-
         match decision {
             PermissionDecision::Denied(reason) => ToolResult {
                 tool_call_id: call.id.clone(),
                 content: format!("Permission denied: {}", reason),
                 is_error: true,
             },
+            // Nothing runs in dry-run mode, so there is nothing to confirm.
+            PermissionDecision::NeedsConfirmation if self.dry_run && self.is_side_effecting(&call.name) => {
+                Self::dry_run_result(call)
+            }
             PermissionDecision::NeedsConfirmation => {
-                // In the agentic loop, we can't prompt interactively.
-                // The CLI layer handles this before reaching here.
+                // Approval already ran; a checker that still can't decide denies.
                 ToolResult {
                     tool_call_id: call.id.clone(),
                     content: "Tool requires user confirmation".to_string(),
@@ -255,11 +480,20 @@ impl AgenticLoop {
             }
             PermissionDecision::Approved => {
                 match self.registry.get(&call.name) {
+                    Some(tool) if self.dry_run && tool.is_side_effecting() => Self::dry_run_result(call),
                     Some(tool) => {
                         let tool_ctx = ToolContext {
                             working_directory: context.working_directory.clone(),
                         };
-                        match tool.execute(call.input.clone(), &tool_ctx).await {
+                        let executed = tool.execute(call.input.clone(), &tool_ctx).await;
+                        if let Some(cache) = &self.cache {
+                            if tool.is_side_effecting() {
+                                cache.clear();
+                            } else if let (true, Ok(result)) = (tool.is_cacheable(), &executed) {
+                                cache.insert(self.canonical_name(call), &call.input, result);
+                            }
+                        }
+                        match executed {
                             Ok(mut result) => {
                                 result.tool_call_id = call.id.clone();
                                 result
@@ -280,6 +514,25 @@ impl AgenticLoop {
             }
         }
     }
+
+    fn is_side_effecting(&self, tool_name: &str) -> bool {
+        self.registry.get(tool_name).is_some_and(|tool| tool.is_side_effecting())
+    }
+
+    fn is_cacheable(&self, tool_name: &str) -> bool {
+        self.registry
+            .get(tool_name)
+            .is_some_and(|tool| tool.is_cacheable() && !tool.is_side_effecting())
+    }
+
+    /// Stand-in result for a side-effecting call skipped in dry-run mode.
+    fn dry_run_result(call: &ToolCall) -> ToolResult {
+        ToolResult {
+            tool_call_id: call.id.clone(),
+            content: format!("[dry run] would execute {} with input {}", call.name, call.input),
+            is_error: false,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -317,6 +570,14 @@ mod tests {
         (agentic_loop, provider)
     }
 
+    #[test]
+    fn test_new_conversation_fits_context_window() {
+        let (agentic_loop, _) = setup_test_loop();
+        // Mock window is the 8192 default; 4096 is reserved for the response.
+        assert_eq!(agentic_loop.new_conversation(100_000).max_tokens(), 4096);
+        assert_eq!(agentic_loop.new_conversation(1000).max_tokens(), 1000);
+    }
+
     #[tokio::test]
     async fn test_simple_text_response() {
         let (agentic_loop, provider) = setup_test_loop();
@@ -425,40 +686,41 @@ mod tests {
         // 3. ToolCallDelta
         // 4. ToolCallEnd
         // 5. MessageEnd (ToolUse)
-        // 6. ToolExecutionStart
-        // 7. ToolExecutionResult
+        // 6. ToolCallProgress (Executing)
+        // 7. ToolCallCompleted
         // 8. TextDelta ("All done!")
         // 9. MessageEnd (EndTurn)
-        assert!(events.len() >= 9, "Expected at least 9 events, got {}", events.len());
+        // plus ToolCallStarted / ToolCallProgress (InputReady) after 2 and 4.
+        assert!(events.len() >= 11, "Expected at least 11 events, got {}", events.len());
 
         // First event should be TextDelta
         assert!(matches!(&events[0], StreamEvent::TextDelta { text } if text == "Let me echo that."));
 
-        // ToolCallStart should come before ToolExecutionStart
+        // ToolCallStart should come before execution starts
         let tool_call_start_idx = events
             .iter()
             .position(|e| matches!(e, StreamEvent::ToolCallStart { .. }))
             .expect("should have ToolCallStart");
         let tool_exec_start_idx = events
             .iter()
-            .position(|e| matches!(e, StreamEvent::ToolExecutionStart { .. }))
-            .expect("should have ToolExecutionStart");
+            .position(|e| matches!(e, StreamEvent::ToolCallProgress { stage: ToolCallStage::Executing, .. }))
+            .expect("should have ToolCallProgress (Executing)");
         let tool_exec_result_idx = events
             .iter()
-            .position(|e| matches!(e, StreamEvent::ToolExecutionResult { .. }))
-            .expect("should have ToolExecutionResult");
+            .position(|e| matches!(e, StreamEvent::ToolCallCompleted { .. }))
+            .expect("should have ToolCallCompleted");
 
         assert!(
             tool_call_start_idx < tool_exec_start_idx,
-            "ToolCallStart should come before ToolExecutionStart"
+            "ToolCallStart should come before execution"
         );
         assert!(
             tool_exec_start_idx < tool_exec_result_idx,
-            "ToolExecutionStart should come before ToolExecutionResult"
+            "Execution should start before ToolCallCompleted"
         );
 
         // Verify the tool execution result content
-        if let StreamEvent::ToolExecutionResult { content, is_error, .. } = &events[tool_exec_result_idx] {
+        if let StreamEvent::ToolCallCompleted { content, is_error, .. } = &events[tool_exec_result_idx] {
             assert_eq!(content, "streaming");
             assert!(!is_error);
         }
@@ -469,4 +731,483 @@ mod tests {
             StreamEvent::MessageEnd { stop_reason: StopReason::EndTurn }
         ));
     }
+
+    /// Queue one `name` tool call, then a closing text turn.
+    fn queue_tool_call(provider: &MockLlmProvider, name: &str, message: &str) {
+        provider.queue_text("Done!");
+        provider.queue_response(vec![
+            StreamEvent::ToolCallStart {
+                id: "call_a1".to_string(),
+                name: name.to_string(),
+            },
+            StreamEvent::ToolCallDelta {
+                id: "call_a1".to_string(),
+                arguments_delta: serde_json::json!({ "message": message }).to_string(),
+            },
+            StreamEvent::ToolCallEnd {
+                id: "call_a1".to_string(),
+            },
+            StreamEvent::MessageEnd {
+                stop_reason: StopReason::ToolUse,
+            },
+        ]);
+    }
+
+    fn aliased_loop(policy: PermissionPolicy) -> (AgenticLoop, Arc<MockLlmProvider>) {
+        let provider = Arc::new(MockLlmProvider::new());
+        let mut registry = ToolRegistry::new();
+        registry.register(EchoTool).unwrap();
+        registry.alias("say", "echo").unwrap();
+
+        let agentic_loop = AgenticLoop::new(
+            provider.clone() as Arc<dyn ToolAwareLlmProvider>,
+            Arc::new(registry),
+            Arc::new(PolicyChecker::new(policy)) as Arc<dyn PermissionChecker>,
+        );
+        (agentic_loop, provider)
+    }
+
+    #[tokio::test]
+    async fn test_alias_runs_canonical_tool_and_warns() {
+        let mut policy = PermissionPolicy::new();
+        policy.default = PermissionLevel::AutoApprove;
+        let (agentic_loop, provider) = aliased_loop(policy);
+        queue_tool_call(&provider, "say", "via alias");
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+        let events = agentic_loop.run(&mut conv, "Say it".to_string(), &ctx).await.unwrap();
+
+        let deprecated = events
+            .iter()
+            .position(|e| {
+                matches!(
+                    e,
+                    StreamEvent::ToolCallProgress {
+                        stage: ToolCallStage::AliasDeprecated { alias, canonical },
+                        ..
+                    } if alias == "say" && canonical == "echo"
+                )
+            })
+            .expect("should warn about the deprecated alias");
+        let result = events
+            .iter()
+            .position(|e| {
+                matches!(
+                    e,
+                    StreamEvent::ToolCallCompleted { content, is_error: false, .. }
+                        if content == "via alias"
+                )
+            })
+            .expect("alias should execute the echo tool");
+        assert!(deprecated < result);
+    }
+
+    #[tokio::test]
+    async fn test_alias_permission_checked_under_canonical_name() {
+        let mut policy = PermissionPolicy::new();
+        policy.default = PermissionLevel::AutoApprove;
+        policy.rules.insert("echo".to_string(), PermissionLevel::Deny);
+        let (agentic_loop, provider) = aliased_loop(policy);
+        queue_tool_call(&provider, "say", "blocked");
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+        let events = agentic_loop.run(&mut conv, "Say it".to_string(), &ctx).await.unwrap();
+
+        assert!(events.iter().any(|e| matches!(
+            e,
+            StreamEvent::ToolCallCompleted { content, is_error: true, .. }
+                if content.starts_with("Permission denied")
+        )));
+    }
+
+    /// Approves every call, recording the call IDs and tool names it was
+    /// asked about.
+    #[derive(Default)]
+    struct RecordingApprover {
+        asked: std::sync::Mutex<Vec<(String, String)>>,
+    }
+
+    #[async_trait::async_trait]
+    impl crate::permission::Approver for RecordingApprover {
+        async fn request_approval(&self, call_id: &str, call: &ToolCall) -> PermissionDecision {
+            self.asked.lock().unwrap().push((call_id.to_string(), call.name.clone()));
+            PermissionDecision::Approved
+        }
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_waits_for_approver() {
+        let provider = Arc::new(MockLlmProvider::new());
+        let mut registry = ToolRegistry::new();
+        registry.register(EchoTool).unwrap();
+        registry.alias("say", "echo").unwrap();
+        let approver = Arc::new(RecordingApprover::default());
+        let checker = PolicyChecker::new(PermissionPolicy::new())
+            .with_approver(approver.clone(), std::time::Duration::from_secs(5));
+        let agentic_loop = AgenticLoop::new(
+            provider.clone() as Arc<dyn ToolAwareLlmProvider>,
+            Arc::new(registry),
+            Arc::new(checker) as Arc<dyn PermissionChecker>,
+        );
+        queue_tool_call(&provider, "say", "approved");
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+        let events = agentic_loop.run(&mut conv, "Say it".to_string(), &ctx).await.unwrap();
+
+        let started = lifecycle_ids(&events)[0].to_string();
+        assert_eq!(
+            *approver.asked.lock().unwrap(),
+            [(started, "echo".to_string())],
+            "asked by the loop's call ID, under the canonical name"
+        );
+        let stages: Vec<_> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::ToolCallProgress { stage, .. } => Some(stage.clone()),
+                _ => None,
+            })
+            .filter(|s| matches!(s, ToolCallStage::AwaitingApproval | ToolCallStage::Executing))
+            .collect();
+        assert_eq!(stages, [ToolCallStage::AwaitingApproval, ToolCallStage::Executing]);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            StreamEvent::ToolCallCompleted { content, is_error: false, .. } if content == "approved"
+        )));
+    }
+
+    /// The loop-assigned `call_id` of every lifecycle event, in order.
+    fn lifecycle_ids(events: &[StreamEvent]) -> Vec<&str> {
+        events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::ToolCallStarted { call_id, .. }
+                | StreamEvent::ToolCallProgress { call_id, .. }
+                | StreamEvent::ToolCallCompleted { call_id, .. } => Some(call_id.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_call_id_flows_through_lifecycle() {
+        let (agentic_loop, provider) = setup_test_loop();
+        provider.queue_text("Done!");
+        let mut response = Vec::new();
+        for (id, message) in [("toolu_1", "first"), ("toolu_2", "second")] {
+            response.extend([
+                StreamEvent::ToolCallStart { id: id.to_string(), name: "echo".to_string() },
+                StreamEvent::ToolCallDelta {
+                    id: id.to_string(),
+                    arguments_delta: serde_json::json!({ "message": message }).to_string(),
+                },
+                StreamEvent::ToolCallEnd { id: id.to_string() },
+            ]);
+        }
+        response.push(StreamEvent::MessageEnd { stop_reason: StopReason::ToolUse });
+        provider.queue_response(response);
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+        let events = agentic_loop.run(&mut conv, "Echo twice".to_string(), &ctx).await.unwrap();
+
+        let started: Vec<(&str, &str)> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::ToolCallStarted { call_id, provider_id, name } => {
+                    assert_eq!(name, "echo");
+                    Some((call_id.as_str(), provider_id.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(started.len(), 2);
+        assert_eq!((started[0].1, started[1].1), ("toolu_1", "toolu_2"));
+        let (first, second) = (started[0].0, started[1].0);
+        assert_ne!(first, second);
+
+        // Started, InputReady for each call as it streams, then Executing and
+        // Completed for each call in turn.
+        assert_eq!(
+            lifecycle_ids(&events),
+            vec![first, first, second, second, first, first, second, second]
+        );
+        let completed: Vec<(&str, &str)> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::ToolCallCompleted { call_id, content, is_error: false, .. } => {
+                    Some((call_id.as_str(), content.as_str()))
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completed, vec![(first, "first"), (second, "second")]);
+        assert!(events.iter().any(|e| matches!(
+            e,
+            StreamEvent::ToolCallProgress { call_id, stage: ToolCallStage::InputReady { input } }
+                if call_id == second && input["message"] == "second"
+        )));
+    }
+
+    #[tokio::test]
+    async fn test_call_ids_are_not_reused_across_turns() {
+        let (agentic_loop, provider) = setup_test_loop();
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+
+        let mut ids = Vec::new();
+        for turn in ["one", "two"] {
+            // Providers may repeat their own IDs; the loop's must not.
+            queue_tool_call(&provider, "echo", turn);
+            let events = agentic_loop.run(&mut conv, turn.to_string(), &ctx).await.unwrap();
+            let turn_ids = lifecycle_ids(&events);
+            assert!(turn_ids.iter().all(|id| *id == turn_ids[0]));
+            ids.push(turn_ids[0].to_string());
+        }
+        assert_ne!(ids[0], ids[1]);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_stubs_side_effecting_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "existing notes").unwrap();
+
+        let provider = Arc::new(MockLlmProvider::new());
+        let mut registry = ToolRegistry::new();
+        registry.register(crate::tools::FileReadTool).unwrap();
+        registry.register(crate::tools::FileWriteTool).unwrap();
+        let mut policy = PermissionPolicy::new();
+        policy.default = PermissionLevel::AutoApprove;
+        let agentic_loop = AgenticLoop::new(
+            provider.clone() as Arc<dyn ToolAwareLlmProvider>,
+            Arc::new(registry),
+            Arc::new(PolicyChecker::new(policy)) as Arc<dyn PermissionChecker>,
+        )
+        .with_dry_run(true);
+
+        provider.queue_text("Done!");
+        let mut response = Vec::new();
+        for (id, name, input) in [
+            ("toolu_r", "file_read", serde_json::json!({ "path": "notes.txt" })),
+            ("toolu_w", "file_write", serde_json::json!({ "path": "out.txt", "content": "new" })),
+        ] {
+            response.extend([
+                StreamEvent::ToolCallStart { id: id.to_string(), name: name.to_string() },
+                StreamEvent::ToolCallDelta { id: id.to_string(), arguments_delta: input.to_string() },
+                StreamEvent::ToolCallEnd { id: id.to_string() },
+            ]);
+        }
+        response.push(StreamEvent::MessageEnd { stop_reason: StopReason::ToolUse });
+        provider.queue_response(response);
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: dir.path().to_path_buf(),
+        };
+        let events = agentic_loop.run(&mut conv, "Copy notes".to_string(), &ctx).await.unwrap();
+
+        let completed: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::ToolCallCompleted { content, is_error: false, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completed.len(), 2);
+        assert!(completed[0].contains("existing notes"), "{}", completed[0]);
+        assert!(completed[1].starts_with("[dry run] would execute file_write"), "{}", completed[1]);
+        assert!(!dir.path().join("out.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_budget_halts_run_mid_loop() {
+        let (agentic_loop, provider) = setup_test_loop();
+        let agentic_loop = agentic_loop.with_budget(Budget::tokens(1000));
+
+        // Popped last-first: two tool-call turns of 600 tokens each, then text.
+        provider.queue_text("Never reached");
+        for (id, message) in [("call_2", "second"), ("call_1", "first")] {
+            provider.queue_response(vec![
+                StreamEvent::ToolCallStart { id: id.to_string(), name: "echo".to_string() },
+                StreamEvent::ToolCallDelta {
+                    id: id.to_string(),
+                    arguments_delta: serde_json::json!({ "message": message }).to_string(),
+                },
+                StreamEvent::ToolCallEnd { id: id.to_string() },
+                StreamEvent::Usage { input_tokens: 400, output_tokens: 200 },
+                StreamEvent::MessageEnd { stop_reason: StopReason::ToolUse },
+            ]);
+        }
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+        let (tx, mut rx) = tokio::sync::mpsc::channel(256);
+        let outcome = agentic_loop
+            .run_streaming(&mut conv, "Echo twice".to_string(), &ctx, tx)
+            .await
+            .unwrap();
+
+        let usage = TokenUsage { input_tokens: 800, output_tokens: 400 };
+        assert_eq!(outcome, RunOutcome::BudgetExceeded { usage });
+        let mut events = Vec::new();
+        while let Some(event) = rx.recv().await {
+            events.push(event);
+        }
+        assert!(matches!(events.last(), Some(StreamEvent::BudgetExceeded { usage: u }) if *u == usage));
+
+        // Only the first turn's tool ran; the second was answered, not executed.
+        let completed: Vec<&str> = events
+            .iter()
+            .filter_map(|e| match e {
+                StreamEvent::ToolCallCompleted { content, .. } => Some(content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(completed, vec!["first"]);
+        assert!(conv.is_valid());
+        match conv.messages().last() {
+            Some(crate::conversation::ConversationMessage::ToolResult(result)) => {
+                assert_eq!(result.tool_call_id, "call_2");
+                assert!(result.is_error);
+            }
+            other => panic!("expected a tool result, got {:?}", other),
+        }
+        // The final text response is still queued: it was never requested.
+        assert!(matches!(
+            &provider.complete_with_tools(vec![], None, vec![], 0.0, 10).await.unwrap()[0],
+            StreamEvent::TextDelta { text } if text == "Never reached"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unreported_usage_is_counted_with_tokenizer() {
+        let (agentic_loop, provider) = setup_test_loop();
+        provider.queue_text("abcdef");
+
+        let mut conv = Conversation::new(100_000);
+        let ctx = ToolContext {
+            working_directory: std::path::PathBuf::from("/tmp"),
+        };
+        let (tx, _rx) = tokio::sync::mpsc::channel(256);
+        let outcome = agentic_loop
+            .run_streaming(&mut conv, "Hi there".to_string(), &ctx, tx)
+            .await
+            .unwrap();
+
+        // estimate_tokens: "Hi there" = 3, "abcdef" = 2.
+        let usage = TokenUsage { input_tokens: 3, output_tokens: 2 };
+        assert_eq!(outcome, RunOutcome::Completed { usage });
+    }
+
+    fn cached_loop(dir: &std::path::Path) -> (AgenticLoop, Arc<MockLlmProvider>, ToolContext) {
+        let provider = Arc::new(MockLlmProvider::new());
+        let mut registry = ToolRegistry::new();
+        registry.register(crate::tools::FileReadTool).unwrap();
+        registry.register(crate::tools::FileWriteTool).unwrap();
+        let mut policy = PermissionPolicy::new();
+        policy.default = PermissionLevel::AutoApprove;
+        let agentic_loop = AgenticLoop::new(
+            provider.clone() as Arc<dyn ToolAwareLlmProvider>,
+            Arc::new(registry),
+            Arc::new(PolicyChecker::new(policy)) as Arc<dyn PermissionChecker>,
+        )
+        .with_result_cache(Arc::new(ToolResultCache::new(std::time::Duration::from_secs(60))));
+        let ctx = ToolContext { working_directory: dir.to_path_buf() };
+        (agentic_loop, provider, ctx)
+    }
+
+    /// Run one turn in which the model calls `name` with `input`; returns
+    /// the tool's output and whether it came from the cache.
+    async fn call_tool(
+        agentic_loop: &AgenticLoop,
+        provider: &MockLlmProvider,
+        ctx: &ToolContext,
+        conv: &mut Conversation,
+        name: &str,
+        input: serde_json::Value,
+    ) -> (String, bool) {
+        provider.queue_text("Done!");
+        provider.queue_response(vec![
+            StreamEvent::ToolCallStart { id: "toolu_1".to_string(), name: name.to_string() },
+            StreamEvent::ToolCallDelta { id: "toolu_1".to_string(), arguments_delta: input.to_string() },
+            StreamEvent::ToolCallEnd { id: "toolu_1".to_string() },
+            StreamEvent::MessageEnd { stop_reason: StopReason::ToolUse },
+        ]);
+        let events = agentic_loop.run(conv, format!("Use {name}"), ctx).await.unwrap();
+        let cache_hit = events.iter().any(|e| {
+            matches!(e, StreamEvent::ToolCallProgress { stage: ToolCallStage::CacheHit { .. }, .. })
+        });
+        let executed = events.iter().any(|e| {
+            matches!(e, StreamEvent::ToolCallProgress { stage: ToolCallStage::Executing, .. })
+        });
+        assert_ne!(cache_hit, executed);
+        let content = events
+            .iter()
+            .find_map(|e| match e {
+                StreamEvent::ToolCallCompleted { content, .. } => Some(content.clone()),
+                _ => None,
+            })
+            .unwrap();
+        (content, cache_hit)
+    }
+
+    #[tokio::test]
+    async fn test_repeated_identical_call_hits_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "first").unwrap();
+        std::fs::write(dir.path().join("b.txt"), "other").unwrap();
+        let (agentic_loop, provider, ctx) = cached_loop(dir.path());
+        let mut conv = Conversation::new(100_000);
+
+        let (content, hit) =
+            call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_read", serde_json::json!({ "path": "a.txt" })).await;
+        assert!(!hit);
+        assert!(content.contains("first"), "{content}");
+
+        // Changed behind the loop's back: a hit still returns the old result.
+        std::fs::write(dir.path().join("a.txt"), "second").unwrap();
+        let (content, hit) =
+            call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_read", serde_json::json!({ "path": "a.txt" })).await;
+        assert!(hit);
+        assert!(content.contains("first"), "{content}");
+
+        let (content, hit) =
+            call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_read", serde_json::json!({ "path": "b.txt" })).await;
+        assert!(!hit, "different args must miss");
+        assert!(content.contains("other"), "{content}");
+    }
+
+    #[tokio::test]
+    async fn test_side_effecting_tool_is_not_cached_and_clears_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.txt"), "first").unwrap();
+        let (agentic_loop, provider, ctx) = cached_loop(dir.path());
+        let mut conv = Conversation::new(100_000);
+        let read = serde_json::json!({ "path": "a.txt" });
+        let write = serde_json::json!({ "path": "a.txt", "content": "second" });
+
+        call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_read", read.clone()).await;
+        let (_, hit) = call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_write", write.clone()).await;
+        assert!(!hit);
+        let (_, hit) = call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_write", write).await;
+        assert!(!hit, "mutating tools always run");
+
+        let (content, hit) = call_tool(&agentic_loop, &provider, &ctx, &mut conv, "file_read", read).await;
+        assert!(!hit);
+        assert!(content.contains("second"), "{content}");
+    }
 }
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::budget::TokenUsage;
+
 /// Events emitted during streaming LLM responses.
 /// Provider-agnostic — translated from Claude/OpenAI formats in the provider layer.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,19 +32,57 @@ pub enum StreamEvent {
     Error {
         message: String,
     },
-    /// A tool is about to be executed
-    ToolExecutionStart {
-        id: String,
+    /// Tokens the provider billed for part of the current response. A
+    /// response may report usage in several events; they add up.
+    Usage {
+        input_tokens: u64,
+        output_tokens: u64,
+    },
+    /// The run's [`Budget`](crate::Budget) was used up and the loop stopped
+    /// after the response that crossed it. Emitted by the agentic loop.
+    BudgetExceeded {
+        usage: TokenUsage,
+    },
+    /// The model requested a tool call. Emitted by the agentic loop right
+    /// after the provider's `ToolCallStart`; `call_id` identifies the call
+    /// in every later lifecycle event, `provider_id` matches the provider's
+    /// `ToolCallStart`/`ToolCallDelta`/`ToolCallEnd` events.
+    ToolCallStarted {
+        call_id: String,
+        provider_id: String,
         name: String,
     },
-    /// A tool has finished executing
-    ToolExecutionResult {
-        id: String,
+    /// A tool call moved through a stage between start and completion.
+    ToolCallProgress {
+        call_id: String,
+        stage: ToolCallStage,
+    },
+    /// A tool call finished executing.
+    ToolCallCompleted {
+        call_id: String,
         content: String,
         is_error: bool,
+        duration_ms: u64,
     },
 }
 
+/// Stages reported by [`StreamEvent::ToolCallProgress`], in order.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ToolCallStage {
+    /// The model finished streaming the call's arguments.
+    InputReady { input: serde_json::Value },
+    /// The call used a deprecated alias and runs as its canonical tool.
+    AliasDeprecated { alias: String, canonical: String },
+    /// The call needs confirmation; the loop waits for an approval
+    /// decision before running or denying it.
+    AwaitingApproval,
+    /// The tool is executing.
+    Executing,
+    /// The tool was not run; its result is an identical earlier call's,
+    /// stored `age_ms` milliseconds ago.
+    CacheHit { age_ms: u64 },
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum StopReason {
     /// Normal end of response
@@ -0,0 +1,58 @@
+//! Token counting and context-window sizing.
+//!
+//! [`Conversation`](crate::Conversation) budgets its history in tokens. How
+//! text maps to tokens depends on the model, so the count comes from a
+//! [`TokenCounter`] — normally the provider itself. Providers without a real
+//! tokenizer fall back to [`estimate_tokens`].
+
+/// Context window assumed when a provider doesn't know its model's limit.
+pub const DEFAULT_CONTEXT_WINDOW: usize = 8192;
+
+/// Conservative token estimate for text whose tokenizer is unknown.
+///
+/// Counts one token per three characters, rounded up. English prose averages
+/// closer to four characters per token, so this over-estimates slightly and
+/// keeps callers inside the real limit.
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(3)
+}
+
+/// Counts tokens the way a particular model does and knows its context size.
+pub trait TokenCounter: Send + Sync {
+    /// Number of tokens `text` occupies in the model's context.
+    fn count_tokens(&self, text: &str) -> usize {
+        estimate_tokens(text)
+    }
+
+    /// Maximum tokens (prompt plus completion) the model accepts.
+    fn context_window(&self) -> usize {
+        DEFAULT_CONTEXT_WINDOW
+    }
+}
+
+/// A [`TokenCounter`] that only uses [`estimate_tokens`] and the default window.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EstimatingCounter;
+
+impl TokenCounter for EstimatingCounter {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_rounds_up_per_character() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("a"), 1);
+        assert_eq!(estimate_tokens("hello world"), 4);
+        // Characters, not bytes: "ü" is two bytes but one character.
+        assert_eq!(estimate_tokens("grüße"), 2);
+    }
+
+    #[test]
+    fn test_estimate_is_conservative_for_prose() {
+        // 9 tokens with cl100k_base.
+        let text = "The quick brown fox jumps over the lazy dog.";
+        assert!(estimate_tokens(text) >= 9);
+    }
+}
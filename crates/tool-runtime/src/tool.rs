@@ -51,6 +51,21 @@ pub trait Tool: Send + Sync {
     /// Returns the tool's definition (name, description, JSON Schema).
     fn definition(&self) -> ToolDefinition;
 
+    /// Whether running the tool changes anything outside the conversation
+    /// (files, processes, persisted state). Side-effecting tools are stubbed
+    /// out when the agentic loop runs in dry-run mode.
+    fn is_side_effecting(&self) -> bool {
+        false
+    }
+
+    /// Whether the same input always produces the same result, so the
+    /// agentic loop may reuse an earlier result from its
+    /// [`ToolResultCache`](crate::cache::ToolResultCache). Ignored for
+    /// side-effecting tools, which always run.
+    fn is_cacheable(&self) -> bool {
+        false
+    }
+
     /// Execute the tool with the given JSON input.
     async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError>;
 }
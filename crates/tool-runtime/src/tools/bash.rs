@@ -71,6 +71,10 @@ impl Tool for BashExecuteTool {
         }
     }
 
+    fn is_side_effecting(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError> {
         let command = input
             .get("command")
@@ -0,0 +1,435 @@
+//! Targeted file edits by unique find-and-replace or unified-diff patch.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::debug;
+
+use super::walk::resolve_within;
+use crate::tool::{Tool, ToolContext, ToolDefinition, ToolError, ToolResult};
+
+/// Unchanged lines shown around the changed region in the diff preview.
+const PREVIEW_CONTEXT_LINES: usize = 2;
+
+/// Edit an existing file in place without rewriting it wholesale.
+///
+/// Takes either `old_string`/`new_string`, where `old_string` must occur
+/// exactly once, or a unified-diff `patch`. The result carries a diff of
+/// the file before and after the edit.
+pub struct FileEditTool;
+
+#[async_trait]
+impl Tool for FileEditTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "file_edit".to_string(),
+            description: "Edit a file by replacing a unique old_string with new_string, \
+                or by applying a unified-diff patch. Returns a diff of the change."
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "File path to edit (relative to working directory or absolute)"
+                    },
+                    "old_string": {
+                        "type": "string",
+                        "description": "Exact text to replace; must occur exactly once in the file"
+                    },
+                    "new_string": {
+                        "type": "string",
+                        "description": "Replacement text for old_string"
+                    },
+                    "patch": {
+                        "type": "string",
+                        "description": "Unified diff to apply instead of old_string/new_string"
+                    }
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    fn is_side_effecting(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError> {
+        let path_str = input
+            .get("path")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidInput("missing 'path' field".to_string()))?;
+
+        let old_string = input.get("old_string").and_then(|v| v.as_str());
+        let new_string = input.get("new_string").and_then(|v| v.as_str());
+        let patch = input.get("patch").and_then(|v| v.as_str());
+
+        let path = resolve_within(&context.working_directory, path_str)?;
+
+        let before = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            ToolError::ExecutionFailed(format!("failed to read '{}': {e}", path.display()))
+        })?;
+
+        let after = match (old_string, new_string, patch) {
+            (Some(old), Some(new), None) => replace_unique(&before, old, new)?,
+            (None, None, Some(patch)) => apply_patch(&before, patch)?,
+            _ => {
+                return Err(ToolError::InvalidInput(
+                    "provide either 'old_string' and 'new_string', or 'patch'".to_string(),
+                ))
+            }
+        };
+
+        debug!(path = %path.display(), "editing file");
+
+        tokio::fs::write(&path, &after).await.map_err(|e| {
+            ToolError::ExecutionFailed(format!("failed to write '{}': {e}", path.display()))
+        })?;
+
+        Ok(ToolResult {
+            tool_call_id: String::new(),
+            content: format!(
+                "Edited {}\n{}",
+                path.display(),
+                diff_preview(path_str, &before, &after)
+            ),
+            is_error: false,
+        })
+    }
+}
+
+/// Replace the single occurrence of `old` in `text`.
+fn replace_unique(text: &str, old: &str, new: &str) -> Result<String, ToolError> {
+    if old.is_empty() {
+        return Err(ToolError::InvalidInput("'old_string' must not be empty".to_string()));
+    }
+    if old == new {
+        return Err(ToolError::InvalidInput(
+            "'old_string' and 'new_string' are identical".to_string(),
+        ));
+    }
+    match text.matches(old).count() {
+        0 => Err(ToolError::InvalidInput("'old_string' not found in file".to_string())),
+        1 => Ok(text.replacen(old, new, 1)),
+        n => Err(ToolError::InvalidInput(format!(
+            "'old_string' is ambiguous: {n} matches; include more surrounding text"
+        ))),
+    }
+}
+
+/// One `@@` section of a unified diff.
+struct Hunk {
+    /// 1-based line the hunk expects to start at in the original.
+    old_start: usize,
+    old: Vec<String>,
+    new: Vec<String>,
+}
+
+/// Parse the hunks of a single-file unified diff. File headers (`---`,
+/// `+++`) and `\ No newline at end of file` markers are skipped.
+fn parse_hunks(patch: &str) -> Result<Vec<Hunk>, ToolError> {
+    let mut hunks = Vec::new();
+    let mut lines = patch.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        let Some(header) = line.strip_prefix("@@ ") else {
+            continue;
+        };
+        let (old_start, mut old_left, mut new_left) = parse_hunk_header(header)
+            .ok_or_else(|| ToolError::InvalidInput(format!("malformed hunk header: {line}")))?;
+        let mut hunk = Hunk {
+            old_start,
+            old: Vec::new(),
+            new: Vec::new(),
+        };
+
+        while old_left > 0 || new_left > 0 {
+            let Some(body) = lines.next() else {
+                return Err(ToolError::InvalidInput(format!(
+                    "hunk at line {old_start} ends early"
+                )));
+            };
+            // Some editors strip the single space from blank context lines.
+            let (marker, content) = match body.chars().next() {
+                Some(c) => (c, &body[c.len_utf8()..]),
+                None => (' ', ""),
+            };
+            match marker {
+                ' ' if old_left > 0 && new_left > 0 => {
+                    hunk.old.push(content.to_string());
+                    hunk.new.push(content.to_string());
+                    old_left -= 1;
+                    new_left -= 1;
+                }
+                '-' if old_left > 0 => {
+                    hunk.old.push(content.to_string());
+                    old_left -= 1;
+                }
+                '+' if new_left > 0 => {
+                    hunk.new.push(content.to_string());
+                    new_left -= 1;
+                }
+                '\\' => {}
+                _ => {
+                    return Err(ToolError::InvalidInput(format!(
+                        "unexpected line in hunk at line {old_start}: {body}"
+                    )))
+                }
+            }
+        }
+        while lines.peek().is_some_and(|l| l.starts_with('\\')) {
+            lines.next();
+        }
+        hunks.push(hunk);
+    }
+
+    if hunks.is_empty() {
+        return Err(ToolError::InvalidInput("patch contains no hunks".to_string()));
+    }
+    Ok(hunks)
+}
+
+/// `-a,b +c,d @@` → `(a, b, d)`; an omitted count means 1.
+fn parse_hunk_header(header: &str) -> Option<(usize, usize, usize)> {
+    let mut parts = header.split_whitespace();
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let range = |r: &str| -> Option<(usize, usize)> {
+        match r.split_once(',') {
+            Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+            None => Some((r.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_count) = range(old)?;
+    let (_, new_count) = range(new)?;
+    Some((old_start, old_count, new_count))
+}
+
+/// Apply a unified diff to `text`. Each hunk must match at the line it
+/// names or, failing that, at exactly one place after the previous hunk.
+/// The file keeps its line endings (`\r\n` if its first line has one).
+fn apply_patch(text: &str, patch: &str) -> Result<String, ToolError> {
+    let newline = match text.find('\n') {
+        Some(end) if text[..end].ends_with('\r') => "\r\n",
+        _ => "\n",
+    };
+    let hunks = parse_hunks(patch)?;
+    let lines: Vec<&str> = text.lines().collect();
+    let mut out: Vec<&str> = Vec::with_capacity(lines.len());
+    let mut cursor = 0;
+
+    for (i, hunk) in hunks.iter().enumerate() {
+        let matches_at = |pos: usize| {
+            pos + hunk.old.len() <= lines.len()
+                && lines[pos..pos + hunk.old.len()]
+                    .iter()
+                    .zip(&hunk.old)
+                    .all(|(a, b)| *a == b)
+        };
+        let expected = hunk.old_start.saturating_sub(1).max(cursor);
+        let pos = if matches_at(expected) {
+            expected
+        } else {
+            let found: Vec<usize> = (cursor..=lines.len()).filter(|&p| matches_at(p)).collect();
+            match found.as_slice() {
+                [pos] => *pos,
+                [] => {
+                    return Err(ToolError::InvalidInput(format!(
+                        "hunk {} does not apply: context not found",
+                        i + 1
+                    )))
+                }
+                _ => {
+                    return Err(ToolError::InvalidInput(format!(
+                        "hunk {} is ambiguous: context matches {} places",
+                        i + 1,
+                        found.len()
+                    )))
+                }
+            }
+        };
+
+        out.extend_from_slice(&lines[cursor..pos]);
+        out.extend(hunk.new.iter().map(String::as_str));
+        cursor = pos + hunk.old.len();
+    }
+    out.extend_from_slice(&lines[cursor..]);
+
+    let mut result = out.join(newline);
+    if text.ends_with('\n') && !result.is_empty() {
+        result.push_str(newline);
+    }
+    Ok(result)
+}
+
+/// Single-hunk unified diff covering the changed region of `before` → `after`.
+fn diff_preview(path: &str, before: &str, after: &str) -> String {
+    let a: Vec<&str> = before.lines().collect();
+    let b: Vec<&str> = after.lines().collect();
+
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    let start = prefix.saturating_sub(PREVIEW_CONTEXT_LINES);
+    let a_end = (a.len() - suffix + PREVIEW_CONTEXT_LINES).min(a.len());
+    let b_end = (b.len() - suffix + PREVIEW_CONTEXT_LINES).min(b.len());
+
+    let mut diff = format!(
+        "--- a/{path}\n+++ b/{path}\n@@ -{},{} +{},{} @@\n",
+        start + 1,
+        a_end - start,
+        start + 1,
+        b_end - start
+    );
+    for line in &a[start..prefix] {
+        diff.push_str(&format!(" {line}\n"));
+    }
+    for line in &a[prefix..a.len() - suffix] {
+        diff.push_str(&format!("-{line}\n"));
+    }
+    for line in &b[prefix..b.len() - suffix] {
+        diff.push_str(&format!("+{line}\n"));
+    }
+    for line in &a[a.len() - suffix..a_end] {
+        diff.push_str(&format!(" {line}\n"));
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup(content: &str) -> (tempfile::TempDir, ToolContext) {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("main.rs"), content).unwrap();
+        let ctx = ToolContext {
+            working_directory: dir.path().to_path_buf(),
+        };
+        (dir, ctx)
+    }
+
+    #[tokio::test]
+    async fn test_unique_replace() {
+        let (dir, ctx) = setup("fn main() {\n    let x = 1;\n    println!(\"{x}\");\n}\n");
+
+        let result = FileEditTool
+            .execute(
+                serde_json::json!({
+                    "path": "main.rs",
+                    "old_string": "let x = 1;",
+                    "new_string": "let x = 2;"
+                }),
+                &ctx,
+            )
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        assert!(result.content.contains("@@ -1,4 +1,4 @@"));
+        assert!(result.content.contains("-    let x = 1;\n+    let x = 2;\n"));
+
+        let edited = std::fs::read_to_string(dir.path().join("main.rs")).unwrap();
+        assert_eq!(edited, "fn main() {\n    let x = 2;\n    println!(\"{x}\");\n}\n");
+    }
+
+    #[tokio::test]
+    async fn test_ambiguous_match_rejected() {
+        let (dir, ctx) = setup("a = 1\nb = 1\n");
+
+        let err = FileEditTool
+            .execute(
+                serde_json::json!({"path": "main.rs", "old_string": "= 1", "new_string": "= 2"}),
+                &ctx,
+            )
+            .await
+            .unwrap_err();
+
+        assert!(matches!(&err, ToolError::InvalidInput(msg) if msg.contains("2 matches")));
+        let unchanged = std::fs::read_to_string(dir.path().join("main.rs")).unwrap();
+        assert_eq!(unchanged, "a = 1\nb = 1\n");
+
+        let err = FileEditTool
+            .execute(
+                serde_json::json!({"path": "main.rs", "old_string": "c = 1", "new_string": "c = 2"}),
+                &ctx,
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, ToolError::InvalidInput(msg) if msg.contains("not found")));
+    }
+
+    #[tokio::test]
+    async fn test_path_escape_rejected() {
+        let (_dir, ctx) = setup("x\n");
+        let outside = tempfile::tempdir().unwrap();
+        let target = outside.path().join("secret.txt");
+        std::fs::write(&target, "x\n").unwrap();
+
+        for path in ["../secret.txt", target.to_str().unwrap()] {
+            let err = FileEditTool
+                .execute(
+                    serde_json::json!({"path": path, "old_string": "x", "new_string": "y"}),
+                    &ctx,
+                )
+                .await
+                .unwrap_err();
+            assert!(matches!(err, ToolError::PermissionDenied(_)), "{path}");
+        }
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "x\n");
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch() {
+        let (dir, ctx) = setup("one\ntwo\nthree\nfour\nfive\nsix\n");
+        let patch = "--- a/main.rs\n+++ b/main.rs\n\
+            @@ -1,3 +1,3 @@\n one\n-two\n+TWO\n three\n\
+            @@ -5,2 +5,3 @@\n five\n six\n+seven\n";
+
+        let result = FileEditTool
+            .execute(serde_json::json!({"path": "main.rs", "patch": patch}), &ctx)
+            .await
+            .unwrap();
+
+        assert!(!result.is_error);
+        let edited = std::fs::read_to_string(dir.path().join("main.rs")).unwrap();
+        assert_eq!(edited, "one\nTWO\nthree\nfour\nfive\nsix\nseven\n");
+
+        let err = FileEditTool
+            .execute(serde_json::json!({"path": "main.rs", "patch": "@@ -1 +1 @@\n-missing\n+x\n"}), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidInput(_)));
+    }
+
+    #[tokio::test]
+    async fn test_apply_patch_keeps_crlf_and_non_ascii() {
+        let (dir, ctx) = setup("grüße\r\ntwo\r\nthree\r\n");
+        let patch = "@@ -1,3 +1,3 @@\n grüße\n-two\n+zwei – 2\n three\n";
+
+        FileEditTool
+            .execute(serde_json::json!({"path": "main.rs", "patch": patch}), &ctx)
+            .await
+            .unwrap();
+        let edited = std::fs::read_to_string(dir.path().join("main.rs")).unwrap();
+        assert_eq!(edited, "grüße\r\nzwei – 2\r\nthree\r\n");
+
+        // A hunk line starting with a multibyte character is rejected, not a panic.
+        let err = FileEditTool
+            .execute(serde_json::json!({"path": "main.rs", "patch": "@@ -1 +1 @@\nüber\n+x\n"}), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(&err, ToolError::InvalidInput(msg) if msg.contains("über")), "{err}");
+    }
+
+    #[tokio::test]
+    async fn test_definition() {
+        let def = FileEditTool.definition();
+        assert_eq!(def.name, "file_edit");
+    }
+}
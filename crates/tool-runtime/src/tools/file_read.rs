@@ -63,6 +63,10 @@ impl Tool for FileReadTool {
         }
     }
 
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError> {
         let path_str = input
             .get("path")
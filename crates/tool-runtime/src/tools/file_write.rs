@@ -53,6 +53,10 @@ impl Tool for FileWriteTool {
         }
     }
 
+    fn is_side_effecting(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError> {
         let path_str = input
             .get("path")
@@ -0,0 +1,167 @@
+//! File discovery by glob pattern.
+
+use async_trait::async_trait;
+use serde_json::Value;
+use tracing::debug;
+
+use super::walk::{glob_to_regex, resolve_search_root, walk_files};
+use crate::tool::{Tool, ToolContext, ToolDefinition, ToolError, ToolResult};
+
+/// Paths returned per call before the listing is cut short.
+const MAX_GLOB_RESULTS: usize = 200;
+
+/// Find files under the working directory whose path matches a glob,
+/// skipping anything `.gitignore` excludes.
+pub struct GlobTool;
+
+#[async_trait]
+impl Tool for GlobTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "glob".to_string(),
+            description: "Find files by glob pattern (e.g. '**/*.rs', 'src/**/mod.rs'). \
+                Ignores files excluded by .gitignore."
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Glob matched against paths relative to the search directory"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Sub-directory to search (default: working directory)"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError> {
+        let pattern = input
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidInput("missing 'pattern' field".to_string()))?;
+        let matcher = glob_to_regex(pattern)
+            .map_err(|e| ToolError::InvalidInput(format!("invalid glob '{pattern}': {e}")))?;
+
+        let root = resolve_search_root(
+            &context.working_directory,
+            input.get("path").and_then(|v| v.as_str()),
+        )?;
+        let working_dir = context.working_directory.canonicalize().unwrap_or_default();
+
+        debug!(root = %root.display(), pattern, "globbing files");
+
+        let matches: Vec<String> = tokio::task::spawn_blocking(move || {
+            walk_files(&root)
+                .into_iter()
+                .filter(|f| matcher.is_match(&f.relative))
+                .map(|f| {
+                    f.path
+                        .strip_prefix(&working_dir)
+                        .unwrap_or(&f.path)
+                        .display()
+                        .to_string()
+                })
+                .collect()
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("glob task failed: {e}")))?;
+
+        let content = if matches.is_empty() {
+            format!("No files match '{pattern}'")
+        } else {
+            let mut listing = matches
+                .iter()
+                .take(MAX_GLOB_RESULTS)
+                .cloned()
+                .collect::<Vec<_>>()
+                .join("\n");
+            if matches.len() > MAX_GLOB_RESULTS {
+                listing.push_str(&format!(
+                    "\n[{} more files not shown; narrow the pattern]",
+                    matches.len() - MAX_GLOB_RESULTS
+                ));
+            }
+            listing
+        };
+
+        Ok(ToolResult {
+            tool_call_id: String::new(),
+            content,
+            is_error: false,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(files: &[&str]) -> (tempfile::TempDir, ToolContext) {
+        let dir = tempfile::tempdir().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "").unwrap();
+        }
+        let ctx = ToolContext {
+            working_directory: dir.path().to_path_buf(),
+        };
+        (dir, ctx)
+    }
+
+    #[tokio::test]
+    async fn test_glob_matches_files() {
+        let (dir, ctx) = tree(&["Cargo.toml", "src/lib.rs", "src/tools/glob.rs", "target/gen.rs", "README.md"]);
+        std::fs::write(dir.path().join(".gitignore"), "target/\n").unwrap();
+
+        let result = GlobTool
+            .execute(serde_json::json!({"pattern": "**/*.rs"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "src/lib.rs\nsrc/tools/glob.rs");
+
+        let result = GlobTool
+            .execute(serde_json::json!({"pattern": "*.rs", "path": "src/tools"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "src/tools/glob.rs");
+
+        let result = GlobTool
+            .execute(serde_json::json!({"pattern": "*.py"}), &ctx)
+            .await
+            .unwrap();
+        assert!(result.content.starts_with("No files match"));
+    }
+
+    #[tokio::test]
+    async fn test_glob_caps_results() {
+        let names: Vec<String> = (0..MAX_GLOB_RESULTS + 5).map(|i| format!("f{i:04}.txt")).collect();
+        let (_dir, ctx) = tree(&names.iter().map(String::as_str).collect::<Vec<_>>());
+
+        let result = GlobTool
+            .execute(serde_json::json!({"pattern": "*.txt"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.content.lines().count(), MAX_GLOB_RESULTS + 1);
+        assert!(result.content.ends_with("[5 more files not shown; narrow the pattern]"));
+    }
+
+    #[tokio::test]
+    async fn test_glob_confined_to_working_directory() {
+        let (_dir, ctx) = tree(&["a.rs"]);
+        let err = GlobTool
+            .execute(serde_json::json!({"pattern": "*", "path": "../"}), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+    }
+}
@@ -42,6 +42,10 @@ impl Tool for GraphQueryTool {
         }
     }
 
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Value, _context: &ToolContext) -> Result<ToolResult, ToolError> {
         let query_type = input
             .get("query_type")
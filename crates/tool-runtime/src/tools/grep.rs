@@ -0,0 +1,234 @@
+//! Regex search over file contents.
+
+use async_trait::async_trait;
+use regex::RegexBuilder;
+use serde_json::Value;
+use std::io::Read;
+use tracing::debug;
+
+use super::walk::{glob_to_regex, resolve_search_root, walk_files};
+use crate::tool::{Tool, ToolContext, ToolDefinition, ToolError, ToolResult};
+
+/// Matching lines returned per call before the output is cut short.
+const MAX_GREP_MATCHES: usize = 100;
+/// Matched lines longer than this are truncated in the output.
+const MAX_LINE_CHARS: usize = 300;
+/// Files larger than this are skipped.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+
+/// Search file contents under the working directory with a regex,
+/// returning `path:line:text` for each matching line.
+pub struct GrepTool;
+
+#[async_trait]
+impl Tool for GrepTool {
+    fn definition(&self) -> ToolDefinition {
+        ToolDefinition {
+            name: "grep".to_string(),
+            description: "Search file contents with a regular expression. Returns \
+                'path:line:text' for each matching line. Ignores files excluded by .gitignore, \
+                binary files and files over 1 MiB."
+                .to_string(),
+            input_schema: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "pattern": {
+                        "type": "string",
+                        "description": "Regular expression to search for (Rust regex syntax)"
+                    },
+                    "path": {
+                        "type": "string",
+                        "description": "Sub-directory to search (default: working directory)"
+                    },
+                    "include": {
+                        "type": "string",
+                        "description": "Only search files whose relative path matches this glob (e.g. '**/*.rs')"
+                    },
+                    "case_insensitive": {
+                        "type": "boolean",
+                        "description": "Match regardless of case (default: false)"
+                    }
+                },
+                "required": ["pattern"]
+            }),
+        }
+    }
+
+    fn is_cacheable(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, input: Value, context: &ToolContext) -> Result<ToolResult, ToolError> {
+        let pattern = input
+            .get("pattern")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| ToolError::InvalidInput("missing 'pattern' field".to_string()))?;
+        let case_insensitive = input
+            .get("case_insensitive")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(case_insensitive)
+            .build()
+            .map_err(|e| ToolError::InvalidInput(format!("invalid regex '{pattern}': {e}")))?;
+        let include = input
+            .get("include")
+            .and_then(|v| v.as_str())
+            .map(|glob| {
+                glob_to_regex(glob)
+                    .map_err(|e| ToolError::InvalidInput(format!("invalid glob '{glob}': {e}")))
+            })
+            .transpose()?;
+
+        let root = resolve_search_root(
+            &context.working_directory,
+            input.get("path").and_then(|v| v.as_str()),
+        )?;
+        let working_dir = context.working_directory.canonicalize().unwrap_or_default();
+
+        debug!(root = %root.display(), pattern, "grepping files");
+
+        let (lines, truncated) = tokio::task::spawn_blocking(move || {
+            let mut lines = Vec::new();
+            for file in walk_files(&root) {
+                if include.as_ref().is_some_and(|glob| !glob.is_match(&file.relative)) {
+                    continue;
+                }
+                let Some(text) = read_text(&file.path) else {
+                    continue;
+                };
+                let display = file.path.strip_prefix(&working_dir).unwrap_or(&file.path).display().to_string();
+                for (number, line) in text.lines().enumerate() {
+                    if !regex.is_match(line) {
+                        continue;
+                    }
+                    if lines.len() == MAX_GREP_MATCHES {
+                        return (lines, true);
+                    }
+                    lines.push(format!("{display}:{}:{}", number + 1, clip(line)));
+                }
+            }
+            (lines, false)
+        })
+        .await
+        .map_err(|e| ToolError::ExecutionFailed(format!("grep task failed: {e}")))?;
+
+        let content = if lines.is_empty() {
+            format!("No matches for '{pattern}'")
+        } else {
+            let mut output = lines.join("\n");
+            if truncated {
+                output.push_str(&format!(
+                    "\n[stopped after {MAX_GREP_MATCHES} matches; narrow the pattern or path]"
+                ));
+            }
+            output
+        };
+
+        Ok(ToolResult {
+            tool_call_id: String::new(),
+            content,
+            is_error: false,
+        })
+    }
+}
+
+/// File contents as UTF-8 text, or `None` for large, binary or unreadable files.
+fn read_text(path: &std::path::Path) -> Option<String> {
+    let file = std::fs::File::open(path).ok()?;
+    if file.metadata().ok()?.len() > MAX_FILE_BYTES {
+        return None;
+    }
+    let mut bytes = Vec::new();
+    file.take(MAX_FILE_BYTES).read_to_end(&mut bytes).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+fn clip(line: &str) -> String {
+    match line.char_indices().nth(MAX_LINE_CHARS) {
+        Some((end, _)) => format!("{}...", &line[..end]),
+        None => line.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree(files: &[(&str, &str)]) -> (tempfile::TempDir, ToolContext) {
+        let dir = tempfile::tempdir().unwrap();
+        for (file, content) in files {
+            let path = dir.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+        let ctx = ToolContext {
+            working_directory: dir.path().to_path_buf(),
+        };
+        (dir, ctx)
+    }
+
+    #[tokio::test]
+    async fn test_grep_regex_reports_file_and_line() {
+        let (_dir, ctx) = tree(&[
+            ("src/lib.rs", "mod walk;\npub fn walk_files() {}\nfn helper() {}\n"),
+            ("src/main.rs", "fn main() {\n    walk_files();\n}\n"),
+            ("notes.txt", "pub fn walk_files is documented here\n"),
+            ("target/out.rs", "pub fn walk_files() {}\n"),
+            (".gitignore", "target/\n"),
+        ]);
+
+        let result = GrepTool
+            .execute(serde_json::json!({"pattern": r"pub fn \w+_files\(", "include": "**/*.rs"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.content, "src/lib.rs:2:pub fn walk_files() {}");
+
+        let result = GrepTool
+            .execute(
+                serde_json::json!({"pattern": "WALK_FILES", "path": "src", "case_insensitive": true}),
+                &ctx,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            result.content,
+            "src/lib.rs:2:pub fn walk_files() {}\nsrc/main.rs:2:    walk_files();"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_grep_caps_matches_and_skips_binary() {
+        let many = "hit\n".repeat(MAX_GREP_MATCHES + 10);
+        let (dir, ctx) = tree(&[("a.txt", many.as_str())]);
+        std::fs::write(dir.path().join("blob.bin"), b"hit\0\x01").unwrap();
+
+        let result = GrepTool
+            .execute(serde_json::json!({"pattern": "hit"}), &ctx)
+            .await
+            .unwrap();
+        assert_eq!(result.content.lines().count(), MAX_GREP_MATCHES + 1);
+        assert!(!result.content.contains("blob.bin"));
+        assert!(result.content.ends_with("narrow the pattern or path]"));
+    }
+
+    #[tokio::test]
+    async fn test_grep_rejects_bad_input() {
+        let (_dir, ctx) = tree(&[("a.txt", "x")]);
+
+        let err = GrepTool
+            .execute(serde_json::json!({"pattern": "("}), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::InvalidInput(_)));
+
+        let err = GrepTool
+            .execute(serde_json::json!({"pattern": "x", "path": "../.."}), &ctx)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ToolError::PermissionDenied(_)));
+    }
+}
@@ -292,6 +292,10 @@ impl Tool for SaveRuleTool {
         }
     }
 
+    fn is_side_effecting(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, input: Value, _context: &ToolContext) -> Result<ToolResult, ToolError> {
         let yaml = input
             .get("yaml")
@@ -0,0 +1,266 @@
+//! Directory walking shared by the glob and grep tools, and the path
+//! resolution they share with `file_edit`.
+//!
+//! Walks stay inside the working directory, never follow symlinks, skip
+//! `.git`, and honour `.gitignore` files at every level of the tree.
+
+use regex::Regex;
+use std::path::{Path, PathBuf};
+
+use crate::tool::ToolError;
+
+/// A file found by [`walk_files`].
+pub(crate) struct WalkedFile {
+    pub path: PathBuf,
+    /// Path relative to the walk root, `/`-separated.
+    pub relative: String,
+}
+
+/// Resolve an optional sub-directory of `base` to search, refusing anything
+/// that lands outside it.
+pub(crate) fn resolve_search_root(base: &Path, requested: Option<&str>) -> Result<PathBuf, ToolError> {
+    let root = canonical_root(base)?;
+    match requested.filter(|r| !r.is_empty() && *r != ".") {
+        Some(requested) => resolve_under(&root, requested),
+        None => Ok(root),
+    }
+}
+
+/// Resolve an existing path, relative to `base` or absolute, following
+/// symlinks and refusing anything that lands outside `base`.
+pub(crate) fn resolve_within(base: &Path, requested: &str) -> Result<PathBuf, ToolError> {
+    resolve_under(&canonical_root(base)?, requested)
+}
+
+fn canonical_root(base: &Path) -> Result<PathBuf, ToolError> {
+    base.canonicalize().map_err(|e| {
+        ToolError::ExecutionFailed(format!(
+            "failed to resolve working directory '{}': {e}",
+            base.display()
+        ))
+    })
+}
+
+fn resolve_under(root: &Path, requested: &str) -> Result<PathBuf, ToolError> {
+    if requested.contains("..") {
+        return Err(ToolError::PermissionDenied(
+            "path traversal ('..') not allowed".to_string(),
+        ));
+    }
+
+    let candidate = root.join(requested);
+    let resolved = candidate.canonicalize().map_err(|e| {
+        ToolError::ExecutionFailed(format!("failed to resolve '{}': {e}", candidate.display()))
+    })?;
+    if !resolved.starts_with(root) {
+        return Err(ToolError::PermissionDenied(format!(
+            "'{requested}' is outside the working directory"
+        )));
+    }
+    Ok(resolved)
+}
+
+/// Every non-ignored regular file under `root`, in sorted path order.
+pub(crate) fn walk_files(root: &Path) -> Vec<WalkedFile> {
+    let mut files = Vec::new();
+    let mut rules = Vec::new();
+    walk_dir(root, "", &mut rules, &mut files);
+    files
+}
+
+fn walk_dir(dir: &Path, relative: &str, rules: &mut Vec<IgnoreRule>, files: &mut Vec<WalkedFile>) {
+    let inherited = rules.len();
+    if let Ok(text) = std::fs::read_to_string(dir.join(".gitignore")) {
+        rules.extend(text.lines().filter_map(|line| IgnoreRule::parse(line, relative)));
+    }
+
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        rules.truncate(inherited);
+        return;
+    };
+    let mut entries: Vec<_> = entries.flatten().collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name == ".git" || file_type.is_symlink() {
+            continue;
+        }
+        let child = if relative.is_empty() {
+            name
+        } else {
+            format!("{relative}/{name}")
+        };
+        if is_ignored(rules, &child, file_type.is_dir()) {
+            continue;
+        }
+        if file_type.is_dir() {
+            walk_dir(&entry.path(), &child, rules, files);
+        } else if file_type.is_file() {
+            files.push(WalkedFile {
+                path: entry.path(),
+                relative: child,
+            });
+        }
+    }
+    rules.truncate(inherited);
+}
+
+/// The last rule matching a path decides; `!` rules re-include.
+fn is_ignored(rules: &[IgnoreRule], relative: &str, is_dir: bool) -> bool {
+    rules
+        .iter()
+        .rev()
+        .find(|rule| rule.matches(relative, is_dir))
+        .is_some_and(|rule| !rule.negate)
+}
+
+/// One pattern line from a `.gitignore`.
+struct IgnoreRule {
+    /// Directory holding the `.gitignore`, relative to the walk root.
+    base: String,
+    pattern: Regex,
+    negate: bool,
+    dir_only: bool,
+}
+
+impl IgnoreRule {
+    fn parse(line: &str, base: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negate, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        // A pattern with a slash is relative to the .gitignore's directory;
+        // one without matches a name at any depth.
+        let anchored = line.contains('/');
+        let line = line.trim_start_matches('/');
+        let glob = if anchored {
+            line.to_string()
+        } else {
+            format!("**/{line}")
+        };
+        Some(Self {
+            base: base.to_string(),
+            pattern: glob_to_regex(&glob).ok()?,
+            negate,
+            dir_only,
+        })
+    }
+
+    fn matches(&self, relative: &str, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let within = if self.base.is_empty() {
+            Some(relative)
+        } else {
+            relative
+                .strip_prefix(self.base.as_str())
+                .and_then(|rest| rest.strip_prefix('/'))
+        };
+        within.is_some_and(|path| self.pattern.is_match(path))
+    }
+}
+
+/// Compile a glob into an anchored regex over `/`-separated relative paths.
+///
+/// `*` and `?` stay within one path segment, `**` spans segments (`**/`
+/// also matches zero directories), `[...]`/`[!...]` are character classes
+/// and `{a,b}` is an alternation.
+pub(crate) fn glob_to_regex(glob: &str) -> Result<Regex, regex::Error> {
+    let mut re = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut in_braces = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                re.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' || c == '[' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            }
+            '{' => {
+                in_braces = true;
+                re.push_str("(?:");
+            }
+            '}' if in_braces => {
+                in_braces = false;
+                re.push(')');
+            }
+            ',' if in_braces => re.push('|'),
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    Regex::new(&re)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_to_regex() {
+        let rs = glob_to_regex("**/*.rs").unwrap();
+        assert!(rs.is_match("main.rs"));
+        assert!(rs.is_match("src/tools/walk.rs"));
+        assert!(!rs.is_match("src/main.rsx"));
+
+        let top = glob_to_regex("src/*.{rs,toml}").unwrap();
+        assert!(top.is_match("src/lib.rs"));
+        assert!(top.is_match("src/Cargo.toml"));
+        assert!(!top.is_match("src/tools/lib.rs"));
+
+        assert!(glob_to_regex("file_[!b]?.txt").unwrap().is_match("file_a1.txt"));
+        assert!(!glob_to_regex("file_[!b]?.txt").unwrap().is_match("file_b1.txt"));
+    }
+
+    #[test]
+    fn test_walk_honours_gitignore() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in ["src/lib.rs", "target/debug/out.rs", "logs/a.log", "logs/keep.log", "sub/gen.rs", ".git/HEAD"] {
+            std::fs::create_dir_all(root.join(file).parent().unwrap()).unwrap();
+            std::fs::write(root.join(file), "x").unwrap();
+        }
+        std::fs::write(root.join(".gitignore"), "# build output\ntarget/\n*.log\n!keep.log\n").unwrap();
+        std::fs::write(root.join("sub/.gitignore"), "/gen.rs\n").unwrap();
+
+        let files: Vec<String> = walk_files(root).into_iter().map(|f| f.relative).collect();
+        assert_eq!(files, vec![".gitignore", "logs/keep.log", "src/lib.rs", "sub/.gitignore"]);
+    }
+}
@@ -14,11 +14,17 @@ type RustStreamEvent =
   | { ToolCallStart: { id: string; name: string } }
   | { ToolCallDelta: { id: string; arguments_delta: string } }
   | { ToolCallEnd: { id: string } }
-  | { ToolExecutionStart: { id: string; name: string } }
-  | { ToolExecutionResult: { id: string; content: string; is_error: boolean } }
+  | { ToolCallStarted: { call_id: string; provider_id: string; name: string } }
+  | { ToolCallProgress: { call_id: string; stage: ToolCallStage } }
+  | { ToolCallCompleted: { call_id: string; content: string; is_error: boolean; duration_ms: number } }
   | { MessageEnd: { stop_reason: "EndTurn" | "ToolUse" | "MaxTokens" | "StopSequence" } }
   | { Error: { message: string } };
 
+type ToolCallStage =
+  | { InputReady: { input: unknown } }
+  | { AliasDeprecated: { alias: string; canonical: string } }
+  | "Executing";
+
 // ── AI SDK UIMessage types (from useChat) ──────────────────────────────
 
 interface UIMessage {
@@ -207,6 +213,7 @@ async function translateRustSSE(
     string,
     { name: string; argChunks: string[] }
   >();
+  const callIds = new Map<string, { providerId: string; name: string }>();
 
   // Emit message start
   writer.write({ type: "start" });
@@ -243,6 +250,7 @@ async function translateRustSSE(
           setTextPartId: (id) => { textPartId = id; },
           nextTextId: () => `text-${textIdCounter++}`,
           toolCallBuffers,
+          callIds,
         });
       }
     }
@@ -266,6 +274,8 @@ interface TranslationState {
   setTextPartId: (id: string | null) => void;
   nextTextId: () => string;
   toolCallBuffers: Map<string, { name: string; argChunks: string[] }>;
+  /** Runtime-assigned call ID → the provider's tool call ID the UI keys on. */
+  callIds: Map<string, { providerId: string; name: string }>;
 }
 
 function processEvent(
@@ -337,21 +347,36 @@ function processEvent(
     return;
   }
 
-  if ("ToolExecutionStart" in event) {
-    // Emit as a custom data part for UI feedback
-    writer.write({
-      type: "data-tool-execution",
-      data: {
-        toolCallId: event.ToolExecutionStart.id,
-        toolName: event.ToolExecutionStart.name,
-        status: "running",
-      },
-    });
+  if ("ToolCallStarted" in event) {
+    const { call_id, provider_id, name } = event.ToolCallStarted;
+    state.callIds.set(call_id, { providerId: provider_id, name });
+    return;
+  }
+
+  if ("ToolCallProgress" in event) {
+    const { call_id, stage } = event.ToolCallProgress;
+    const call = state.callIds.get(call_id);
+    if (call && stage === "Executing") {
+      // Emit as a custom data part for UI feedback
+      writer.write({
+        type: "data-tool-execution",
+        data: {
+          toolCallId: call.providerId,
+          callId: call_id,
+          toolName: call.name,
+          status: "running",
+        },
+      });
+    }
     return;
   }
 
-  if ("ToolExecutionResult" in event) {
-    const { id, content, is_error } = event.ToolExecutionResult;
+  if ("ToolCallCompleted" in event) {
+    const { call_id, content, is_error } = event.ToolCallCompleted;
+    const call = state.callIds.get(call_id);
+    if (!call) return;
+    const id = call.providerId;
+    state.callIds.delete(call_id);
     if (is_error) {
       writer.write({
         type: "tool-output-error",
@@ -542,15 +542,20 @@ export default function QueueMonitorPage() {
       try {
         ws = new WebSocket(WS_URL);
 
+        ws.onopen = () => {
+          ws?.send(JSON.stringify({ subscribe: ["queue"] }));
+        };
+
         ws.onmessage = (evt) => {
           try {
             const msg = JSON.parse(evt.data);
             if (msg.type === "queue_batch") {
+              const data = msg.data ?? {};
               const event: BatchEvent = {
                 timestamp: new Date(),
-                docs: msg.docs ?? 0,
-                graphOps: msg.graph_ops ?? 0,
-                messages: Array.isArray(msg.messages) ? msg.messages : [],
+                docs: data.docs ?? 0,
+                graphOps: data.graph_ops ?? 0,
+                messages: Array.isArray(data.messages) ? data.messages : [],
               };
               setBatchEvents((prev) => {
                 const next = [event, ...prev];
@@ -36,6 +36,8 @@ export interface AnomalyRule {
   detection: Detection;
   filters?: Filters;
   notifications: NotificationChannel[];
+  /** Above this many matches a run sends one summary notification. */
+  max_notify_entities?: number;
 }
 
 export interface RuleMetadata {
@@ -119,6 +121,8 @@ export interface RunResult {
   matches_found: number;
   evaluation_ms: number;
   message: string;
+  /** True when matches exceeded max_notify_entities and only a summary was sent. */
+  volume_capped?: boolean;
 }
 
 /** Result of POST /anomaly-rules/{id}/test-notify */
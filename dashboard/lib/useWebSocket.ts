@@ -90,6 +90,7 @@ export function useWebSocket(
       ws.onopen = () => {
         setStatus("connected");
         retryRef.current = 0;
+        ws.send(JSON.stringify({ subscribe: ["loading", "insights"] }));
       };
 
       ws.onmessage = (event) => {